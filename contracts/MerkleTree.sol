@@ -147,6 +147,14 @@ abstract contract MerkleTree is Initializable {
         return $.nextFreeLeafId - $.firstLeafId - 1;
     }
 
+    /*
+     * Check whether `merkleRoot` has ever been a root of the tree, i.e. whether proofs against it
+     * are accepted.
+     */
+    function merkleRootExists(uint256 merkleRoot) public view returns (bool) {
+        return _merkleRootExists(merkleRoot);
+    }
+
     function _merkleRootExists(
         uint256 merkleRoot
     ) internal view returns (bool) {
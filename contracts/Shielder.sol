@@ -4,11 +4,16 @@ pragma solidity 0.8.26;
 import { Halo2Verifier as DepositVerifier } from "./DepositVerifier.sol";
 import { Halo2Verifier as NewAccountVerifier } from "./NewAccountVerifier.sol";
 import { Halo2Verifier as WithdrawVerifier } from "./WithdrawVerifier.sol";
+import { Halo2Verifier as BatchWithdrawVerifier } from "./BatchWithdrawVerifier.sol";
+import { Halo2Verifier as TransferVerifier } from "./TransferVerifier.sol";
+import { Halo2Verifier as ClaimVerifier } from "./ClaimVerifier.sol";
+import { Halo2Verifier as MigrationVerifier } from "./MigrationVerifier.sol";
 import { Initializable } from "@openzeppelin/contracts-upgradeable/proxy/utils/Initializable.sol";
 import { MerkleTree } from "./MerkleTree.sol";
 import { Nullifiers } from "./Nullifiers.sol";
 import { AnonymityRevoker } from "./AnonymityRevoker.sol";
 import { ProtocolFee } from "./ProtocolFee.sol";
+import { TokenList } from "./TokenList.sol";
 import { Ownable2StepUpgradeable } from "@openzeppelin/contracts-upgradeable/access/Ownable2StepUpgradeable.sol";
 import { PausableUpgradeable } from "@openzeppelin/contracts-upgradeable/utils/PausableUpgradeable.sol";
 import { UUPSUpgradeable } from "@openzeppelin/contracts-upgradeable/proxy/utils/UUPSUpgradeable.sol";
@@ -28,7 +33,8 @@ contract Shielder is
     MerkleTree,
     Nullifiers,
     AnonymityRevoker,
-    ProtocolFee
+    ProtocolFee,
+    TokenList
 {
     // -- Constants --
 
@@ -36,7 +42,7 @@ contract Shielder is
     ///  - `v1` is the version of the note schema,
     ///  - `v1.v2` is the version of the circuits used,
     ///  - `v1.v2.v3` is the version of the contract itself.
-    bytes3 public constant CONTRACT_VERSION = 0x000101;
+    bytes3 public constant CONTRACT_VERSION = 0x000601;
 
     /// This amount of gas should be sufficient for ether transfers
     /// and simple fallback function execution, yet still protecting against reentrancy attack.
@@ -52,6 +58,13 @@ contract Shielder is
     /// so we control the sum of balances instead.
     uint256 public constant MAX_CONTRACT_BALANCE = MAX_TRANSACTION_AMOUNT;
 
+    /// Number of withdrawals proven together in `withdrawBatch`. Must match the batch size of the
+    /// batched withdraw circuit.
+    uint256 public constant WITHDRAW_BATCH_SIZE = 2;
+
+    /// Number of public inputs of a single withdrawal.
+    uint256 private constant WITHDRAW_PUBLIC_INPUTS = 9;
+
     /// The modulus of the field used in the circuits.
     uint256 private constant FIELD_MODULUS =
         21888242871839275222246405745257275088548364400416034343698204186575808495617;
@@ -59,6 +72,22 @@ contract Shielder is
     /// A special value of `tokenAddress` in circuits used to represent the native token.
     address private constant NATIVE_TOKEN_NOTE_ADDRESS = address(0);
 
+    // -- Types --
+
+    /// A single withdrawal in `withdrawBatch`. The token and the relayer are common for the batch.
+    struct BatchedWithdrawal {
+        uint256 amount;
+        address withdrawalAddress;
+        uint256 merkleRoot;
+        uint256 oldNullifierHash;
+        uint256 newNote;
+        uint256 relayerFee;
+        uint256 macSalt;
+        uint256 macCommitment;
+        uint256 associationSetRoot;
+        bytes memo;
+    }
+
     // -- Events --
     event NewAccount(
         bytes3 contractVersion,
@@ -94,10 +123,46 @@ contract Shielder is
         uint256 fee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         uint256 pocketMoney,
         uint256 protocolFee,
         bytes memo
     );
+    event Transfer(
+        bytes3 contractVersion,
+        address tokenAddress,
+        uint256 newNote,
+        uint256 newNoteIndex,
+        uint256 receipt,
+        uint256 receiptIndex,
+        uint256[4] encryptedReceiptNonce,
+        uint256 encryptedValue,
+        uint256[4] revokerEncryption,
+        uint256 macSalt,
+        uint256 macCommitment,
+        bytes memo
+    );
+    event Claim(
+        bytes3 contractVersion,
+        address tokenAddress,
+        uint256 newNote,
+        uint256 newNoteIndex,
+        uint256 receiptNullifier,
+        uint256 encryptedValue,
+        uint256 macSalt,
+        uint256 macCommitment,
+        bytes memo
+    );
+    event Migrate(
+        bytes3 contractVersion,
+        address tokenAddress,
+        uint8 oldNoteVersion,
+        uint256 newNote,
+        uint256 newNoteIndex,
+        uint256 macSalt,
+        uint256 macCommitment,
+        bytes memo
+    );
 
     // -- Errors --
 
@@ -108,6 +173,10 @@ contract Shielder is
     error NativeTransferFailed();
     error WithdrawVerificationFailed();
     error NewAccountVerificationFailed();
+    error TransferVerificationFailed();
+    error ClaimVerificationFailed();
+    error MigrationVerificationFailed();
+    error UnsupportedNoteVersion();
     error ZeroAmount();
     error AmountTooHigh();
     error ContractBalanceLimitReached();
@@ -133,7 +202,9 @@ contract Shielder is
         bool _isArbitrumChain,
         uint256 _protocolDepositFeeBps,
         uint256 _protocolWithdrawFeeBps,
-        address _protocolFeeReceiver
+        address _protocolFeeReceiver,
+        address[] memory _tokenList,
+        uint256 _tokenListRoot
     ) public initializer {
         __Ownable_init(initialOwner);
         __Pausable_init();
@@ -148,6 +219,7 @@ contract Shielder is
             _protocolWithdrawFeeBps,
             _protocolFeeReceiver
         );
+        __TokenList_init(_tokenList, _tokenListRoot);
         _pause();
     }
 
@@ -196,6 +268,16 @@ contract Shielder is
         );
     }
 
+    /*
+     * Allows the owner to change the set of tokens that can be shielded.
+     */
+    function setTokenList(
+        address[] calldata newTokenList,
+        uint256 newTokenListRoot
+    ) external onlyOwner fieldElement(newTokenListRoot) {
+        _setTokenList(newTokenList, newTokenListRoot);
+    }
+
     /*
      * Creates a fresh note, with an optional native token deposit.
      *
@@ -343,7 +425,7 @@ contract Shielder is
         protocolFee = _computeProtocolDepositFee(amount);
 
         // @dev must follow the same order as in the circuit
-        uint256[] memory publicInputs = new uint256[](13);
+        uint256[] memory publicInputs = new uint256[](14);
         publicInputs[0] = newNote;
         publicInputs[1] = prenullifier;
         publicInputs[2] = amount - protocolFee;
@@ -369,6 +451,8 @@ contract Shielder is
         publicInputs[11] = macSalt;
         publicInputs[12] = macCommitment;
 
+        publicInputs[13] = tokenListRoot();
+
         bool success = NewAccountVerifier.verifyProof(proof, publicInputs);
 
         if (!success) revert NewAccountVerificationFailed();
@@ -541,6 +625,7 @@ contract Shielder is
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         bytes calldata memo
     ) external whenNotPaused {
         (uint256 newNoteIndex, uint256 protocolFee) = _withdraw(
@@ -556,6 +641,7 @@ contract Shielder is
             relayerFee,
             macSalt,
             macCommitment,
+            associationSetRoot,
             0,
             memo
         );
@@ -575,6 +661,7 @@ contract Shielder is
             relayerFee,
             macSalt,
             macCommitment,
+            associationSetRoot,
             0,
             protocolFee,
             memo
@@ -594,6 +681,7 @@ contract Shielder is
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         bytes calldata memo
     ) external payable whenNotPaused {
         uint256 pocketMoney = msg.value;
@@ -611,6 +699,7 @@ contract Shielder is
             relayerFee,
             macSalt,
             macCommitment,
+            associationSetRoot,
             pocketMoney,
             memo
         );
@@ -638,6 +727,7 @@ contract Shielder is
             relayerFee,
             macSalt,
             macCommitment,
+            associationSetRoot,
             pocketMoney,
             protocolFee,
             memo
@@ -657,6 +747,7 @@ contract Shielder is
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         uint256 pocketMoney,
         bytes calldata memo
     )
@@ -664,6 +755,7 @@ contract Shielder is
         restrictContractVersion(expectedContractVersion)
         fieldElement(oldNullifierHash)
         fieldElement(newNote)
+        fieldElement(associationSetRoot)
         returns (uint256 newNoteIndex, uint256 protocolFee)
     {
         require(amount != 0, ZeroAmount());
@@ -676,36 +768,423 @@ contract Shielder is
         require(nullifiers(oldNullifierHash) == 0, DuplicatedNullifier());
 
         // @dev needs to match the order in the circuit
-        uint256[] memory publicInputs = new uint256[](8);
+        uint256[] memory publicInputs = new uint256[](9);
         publicInputs[0] = merkleRoot;
         publicInputs[1] = oldNullifierHash;
         publicInputs[2] = newNote;
         publicInputs[3] = amount;
         publicInputs[4] = addressToUInt256(tokenAddress);
 
-        uint256 chainId = block.chainid;
+        publicInputs[5] = _withdrawCommitment(
+            withdrawalAddress,
+            relayerAddress,
+            relayerFee,
+            pocketMoney,
+            protocolFee,
+            memo
+        );
+        publicInputs[6] = macSalt;
+        publicInputs[7] = macCommitment;
+        // @dev zero means that no association set is claimed
+        publicInputs[8] = associationSetRoot;
+
+        bool success = WithdrawVerifier.verifyProof(proof, publicInputs);
+
+        if (!success) revert WithdrawVerificationFailed();
+
+        newNoteIndex = _addNote(newNote);
+        _registerNullifier(oldNullifierHash);
+    }
 
+    /*
+     * Withdraw `WITHDRAW_BATCH_SIZE` independent withdrawals of the same token, proven together.
+     *
+     * Every withdrawal is handled as in `withdrawNative` / `withdrawERC20` (without pocket money)
+     * and emits its own `Withdraw` event.
+     */
+    function withdrawBatch(
+        bytes3 expectedContractVersion,
+        address tokenAddress,
+        address relayerAddress,
+        BatchedWithdrawal[WITHDRAW_BATCH_SIZE] calldata withdrawals,
+        bytes calldata proof
+    ) external whenNotPaused restrictContractVersion(expectedContractVersion) {
+        // @dev needs to match the order in the circuit
+        uint256[] memory publicInputs = new uint256[](
+            WITHDRAW_BATCH_SIZE * WITHDRAW_PUBLIC_INPUTS
+        );
+        uint256[WITHDRAW_BATCH_SIZE] memory protocolFees;
+        for (uint256 i = 0; i < WITHDRAW_BATCH_SIZE; i++) {
+            protocolFees[i] = _fillBatchedWithdrawalInputs(
+                publicInputs,
+                i * WITHDRAW_PUBLIC_INPUTS,
+                tokenAddress,
+                relayerAddress,
+                withdrawals[i]
+            );
+        }
+
+        bool success = BatchWithdrawVerifier.verifyProof(proof, publicInputs);
+
+        if (!success) revert WithdrawVerificationFailed();
+
+        for (uint256 i = 0; i < WITHDRAW_BATCH_SIZE; i++) {
+            BatchedWithdrawal calldata withdrawal = withdrawals[i];
+            // @dev the same nullifier might be used twice within the batch
+            require(
+                nullifiers(withdrawal.oldNullifierHash) == 0,
+                DuplicatedNullifier()
+            );
+            uint256 newNoteIndex = _addNote(withdrawal.newNote);
+            _registerNullifier(withdrawal.oldNullifierHash);
+
+            _payOutBatchedWithdrawal(
+                tokenAddress,
+                relayerAddress,
+                withdrawal,
+                protocolFees[i]
+            );
+
+            emit Withdraw(
+                CONTRACT_VERSION,
+                tokenAddress,
+                withdrawal.amount,
+                withdrawal.withdrawalAddress,
+                withdrawal.newNote,
+                newNoteIndex,
+                relayerAddress,
+                withdrawal.relayerFee,
+                withdrawal.macSalt,
+                withdrawal.macCommitment,
+                withdrawal.associationSetRoot,
+                0,
+                protocolFees[i],
+                withdrawal.memo
+            );
+        }
+    }
+
+    /// Check a single withdrawal of a batch and write its public inputs to `publicInputs`, starting
+    /// at `offset`. Returns the protocol fee of the withdrawal.
+    function _fillBatchedWithdrawalInputs(
+        uint256[] memory publicInputs,
+        uint256 offset,
+        address tokenAddress,
+        address relayerAddress,
+        BatchedWithdrawal calldata withdrawal
+    )
+        private
+        view
+        fieldElement(withdrawal.oldNullifierHash)
+        fieldElement(withdrawal.newNote)
+        fieldElement(withdrawal.associationSetRoot)
+        returns (uint256 protocolFee)
+    {
+        require(withdrawal.amount != 0, ZeroAmount());
+        require(withdrawal.amount <= MAX_TRANSACTION_AMOUNT, AmountTooHigh());
+
+        protocolFee = _computeProtocolWithdrawFee(withdrawal.amount);
+
+        require(
+            withdrawal.amount - protocolFee > withdrawal.relayerFee,
+            FeeHigherThanAmount()
+        );
+        require(
+            _merkleRootExists(withdrawal.merkleRoot),
+            MerkleRootDoesNotExist()
+        );
+        require(
+            nullifiers(withdrawal.oldNullifierHash) == 0,
+            DuplicatedNullifier()
+        );
+
+        publicInputs[offset] = withdrawal.merkleRoot;
+        publicInputs[offset + 1] = withdrawal.oldNullifierHash;
+        publicInputs[offset + 2] = withdrawal.newNote;
+        publicInputs[offset + 3] = withdrawal.amount;
+        publicInputs[offset + 4] = addressToUInt256(tokenAddress);
+        publicInputs[offset + 5] = _withdrawCommitment(
+            withdrawal.withdrawalAddress,
+            relayerAddress,
+            withdrawal.relayerFee,
+            0,
+            protocolFee,
+            withdrawal.memo
+        );
+        publicInputs[offset + 6] = withdrawal.macSalt;
+        publicInputs[offset + 7] = withdrawal.macCommitment;
+        // @dev zero means that no association set is claimed
+        publicInputs[offset + 8] = withdrawal.associationSetRoot;
+    }
+
+    function _payOutBatchedWithdrawal(
+        address tokenAddress,
+        address relayerAddress,
+        BatchedWithdrawal calldata withdrawal,
+        uint256 protocolFee
+    ) private {
+        uint256 netAmount = withdrawal.amount - protocolFee - withdrawal.relayerFee;
+        if (tokenAddress == NATIVE_TOKEN_NOTE_ADDRESS) {
+            _transferNative(withdrawal.withdrawalAddress, netAmount);
+            _transferNative(protocolFeeReceiver(), protocolFee);
+            _transferNative(relayerAddress, withdrawal.relayerFee);
+        } else {
+            IERC20 token = IERC20(tokenAddress);
+            _transferERC20(token, withdrawal.withdrawalAddress, netAmount);
+            _transferERC20(token, protocolFeeReceiver(), protocolFee);
+            _transferERC20(token, relayerAddress, withdrawal.relayerFee);
+        }
+    }
+
+    /// Commitment to the withdrawal parameters that are not constrained by the circuit.
+    function _withdrawCommitment(
+        address withdrawalAddress,
+        address relayerAddress,
+        uint256 relayerFee,
+        uint256 pocketMoney,
+        uint256 protocolFee,
+        bytes calldata memo
+    ) private view returns (uint256) {
         bytes memory commitment = abi.encodePacked(
             CONTRACT_VERSION,
             addressToUInt256(withdrawalAddress),
             addressToUInt256(relayerAddress),
             relayerFee,
-            chainId,
+            block.chainid,
             pocketMoney,
             protocolFee,
             memo
         );
         // @dev shifting right by 4 bits so the commitment is smaller from r
+        return uint256(keccak256(commitment)) >> 4;
+    }
+
+    /*
+     * Transfer shielded funds to another shielded account.
+     *
+     * The sender's note is replaced with `newNote` and the transferred amount goes to `receipt`,
+     * which only the recipient can claim (see `claim`). `encryptedReceiptNonce` and
+     * `revokerEncryption` are the ElGamal ciphertexts of the receipt nonce for the recipient and
+     * for the anonymity revoker, as affine coordinates `[c1.x, c1.y, c2.x, c2.y]`.
+     * `encryptedValue` is the transferred amount masked with a key derived from the nonce. No
+     * funds leave the contract.
+     */
+    function transfer(
+        bytes3 expectedContractVersion,
+        address tokenAddress,
+        uint256 merkleRoot,
+        uint256 oldNullifierHash,
+        uint256 newNote,
+        uint256 receipt,
+        uint256[4] calldata encryptedReceiptNonce,
+        uint256 encryptedValue,
+        uint256[4] calldata revokerEncryption,
+        bytes calldata proof,
+        uint256 macSalt,
+        uint256 macCommitment,
+        bytes calldata memo
+    )
+        external
+        whenNotPaused
+        restrictContractVersion(expectedContractVersion)
+        fieldElement(oldNullifierHash)
+        fieldElement(newNote)
+        fieldElement(receipt)
+    {
+        require(_merkleRootExists(merkleRoot), MerkleRootDoesNotExist());
+        require(nullifiers(oldNullifierHash) == 0, DuplicatedNullifier());
+
+        (uint256 arX, uint256 arY) = anonymityRevokerPubkey();
+
+        // @dev needs to match the order in the circuit
+        uint256[] memory publicInputs = new uint256[](19);
+        publicInputs[0] = merkleRoot;
+        publicInputs[1] = oldNullifierHash;
+        publicInputs[2] = newNote;
+        publicInputs[3] = receipt;
+        publicInputs[4] = addressToUInt256(tokenAddress);
+
+        bytes memory commitment = abi.encodePacked(
+            CONTRACT_VERSION,
+            block.chainid,
+            memo
+        );
+        // @dev shifting right by 4 bits so the commitment is smaller from r
+        publicInputs[5] = uint256(keccak256(commitment)) >> 4;
+        publicInputs[6] = encryptedReceiptNonce[0];
+        publicInputs[7] = encryptedReceiptNonce[1];
+        publicInputs[8] = encryptedReceiptNonce[2];
+        publicInputs[9] = encryptedReceiptNonce[3];
+        publicInputs[10] = encryptedValue;
+        publicInputs[11] = arX;
+        publicInputs[12] = arY;
+        publicInputs[13] = revokerEncryption[0];
+        publicInputs[14] = revokerEncryption[1];
+        publicInputs[15] = revokerEncryption[2];
+        publicInputs[16] = revokerEncryption[3];
+        publicInputs[17] = macSalt;
+        publicInputs[18] = macCommitment;
+
+        bool success = TransferVerifier.verifyProof(proof, publicInputs);
+
+        if (!success) revert TransferVerificationFailed();
+
+        uint256 newNoteIndex = _addNote(newNote);
+        uint256 receiptIndex = _addNote(receipt);
+        _registerNullifier(oldNullifierHash);
+
+        emit Transfer(
+            CONTRACT_VERSION,
+            tokenAddress,
+            newNote,
+            newNoteIndex,
+            receipt,
+            receiptIndex,
+            encryptedReceiptNonce,
+            encryptedValue,
+            revokerEncryption,
+            macSalt,
+            macCommitment,
+            memo
+        );
+    }
+
+    /*
+     * Claim a receipt of a transfer into the recipient's note.
+     *
+     * The proof shows that the receipt is in the tree and bound to the recipient's receiving tag.
+     * `receiptNullifier` prevents claiming the same receipt twice. `encryptedValue` is the claimed
+     * amount masked with a key known only to the recipient. No funds leave the contract.
+     */
+    function claim(
+        bytes3 expectedContractVersion,
+        address tokenAddress,
+        uint256 merkleRoot,
+        uint256 oldNullifierHash,
+        uint256 newNote,
+        uint256 receiptNullifier,
+        uint256 encryptedValue,
+        bytes calldata proof,
+        uint256 macSalt,
+        uint256 macCommitment,
+        bytes calldata memo
+    )
+        external
+        whenNotPaused
+        restrictContractVersion(expectedContractVersion)
+        fieldElement(oldNullifierHash)
+        fieldElement(newNote)
+        fieldElement(receiptNullifier)
+    {
+        require(_merkleRootExists(merkleRoot), MerkleRootDoesNotExist());
+        require(nullifiers(oldNullifierHash) == 0, DuplicatedNullifier());
+        require(nullifiers(receiptNullifier) == 0, DuplicatedNullifier());
+
+        // @dev needs to match the order in the circuit
+        uint256[] memory publicInputs = new uint256[](9);
+        publicInputs[0] = merkleRoot;
+        publicInputs[1] = oldNullifierHash;
+        publicInputs[2] = newNote;
+        publicInputs[3] = receiptNullifier;
+        publicInputs[4] = addressToUInt256(tokenAddress);
+
+        bytes memory commitment = abi.encodePacked(
+            CONTRACT_VERSION,
+            block.chainid,
+            memo
+        );
+        // @dev shifting right by 4 bits so the commitment is smaller from r
+        publicInputs[5] = uint256(keccak256(commitment)) >> 4;
+        publicInputs[6] = encryptedValue;
+        publicInputs[7] = macSalt;
+        publicInputs[8] = macCommitment;
+
+        bool success = ClaimVerifier.verifyProof(proof, publicInputs);
+
+        if (!success) revert ClaimVerificationFailed();
+
+        uint256 newNoteIndex = _addNote(newNote);
+        _registerNullifier(oldNullifierHash);
+        _registerNullifier(receiptNullifier);
+
+        emit Claim(
+            CONTRACT_VERSION,
+            tokenAddress,
+            newNote,
+            newNoteIndex,
+            receiptNullifier,
+            encryptedValue,
+            macSalt,
+            macCommitment,
+            memo
+        );
+    }
+
+    /*
+     * Migrate a note of version `oldNoteVersion` to the current note version.
+     *
+     * The new note has the same ID, balance and token as the old one. The old nullifier is
+     * registered, so the old note cannot be spent again. No funds are moved.
+     */
+    function migrate(
+        bytes3 expectedContractVersion,
+        address tokenAddress,
+        uint8 oldNoteVersion,
+        uint256 merkleRoot,
+        uint256 oldNullifierHash,
+        uint256 newNote,
+        bytes calldata proof,
+        uint256 macSalt,
+        uint256 macCommitment,
+        bytes calldata memo
+    )
+        external
+        whenNotPaused
+        restrictContractVersion(expectedContractVersion)
+        fieldElement(oldNullifierHash)
+        fieldElement(newNote)
+    {
+        // @dev a note of the current version has nothing to migrate, and notes of a newer version
+        // @dev could exist only after a downgrade
+        require(oldNoteVersion < _noteVersion(), UnsupportedNoteVersion());
+        require(_merkleRootExists(merkleRoot), MerkleRootDoesNotExist());
+        require(nullifiers(oldNullifierHash) == 0, DuplicatedNullifier());
+
+        // @dev needs to match the order in the circuit
+        uint256[] memory publicInputs = new uint256[](8);
+        publicInputs[0] = merkleRoot;
+        publicInputs[1] = oldNullifierHash;
+        publicInputs[2] = newNote;
+        publicInputs[3] = addressToUInt256(tokenAddress);
+        publicInputs[4] = oldNoteVersion;
+
+        bytes memory commitment = abi.encodePacked(
+            CONTRACT_VERSION,
+            block.chainid,
+            memo
+        );
+        // @dev shifting right by 4 bits so the commitment is smaller from r
         publicInputs[5] = uint256(keccak256(commitment)) >> 4;
         publicInputs[6] = macSalt;
         publicInputs[7] = macCommitment;
 
-        bool success = WithdrawVerifier.verifyProof(proof, publicInputs);
+        bool success = MigrationVerifier.verifyProof(proof, publicInputs);
 
-        if (!success) revert WithdrawVerificationFailed();
+        if (!success) revert MigrationVerificationFailed();
 
-        newNoteIndex = _addNote(newNote);
+        uint256 newNoteIndex = _addNote(newNote);
         _registerNullifier(oldNullifierHash);
+
+        emit Migrate(
+            CONTRACT_VERSION,
+            tokenAddress,
+            oldNoteVersion,
+            newNote,
+            newNoteIndex,
+            macSalt,
+            macCommitment,
+            memo
+        );
     }
 
     function _transferNative(address to, uint256 amount) private {
@@ -735,6 +1214,11 @@ contract Shielder is
         }
     }
 
+    /// Version of the note schema, i.e. the first byte of `CONTRACT_VERSION`.
+    function _noteVersion() internal pure virtual returns (uint8) {
+        return uint8(CONTRACT_VERSION[0]);
+    }
+
     function addressToUInt256(address addr) public pure returns (uint256) {
         return uint256(uint160(addr));
     }
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity 0.8.26;
+
+import { Initializable } from "@openzeppelin/contracts-upgradeable/proxy/utils/Initializable.sol";
+
+abstract contract TokenList is Initializable {
+    // keccak256(abi.encode(uint256(keccak256("zkos.storage.TokenList")) - 1)) & ~bytes32(uint256(0xff))
+    bytes32 private constant TOKEN_LIST_LOCATION =
+        0xc61234ddf70726f3926fa5321981a41638b45dd9806cf5e5b3ad94aec3c7af00;
+
+    /// @custom:storage-location erc7201:zkos.storage.TokenList
+    struct TokenListStorage {
+        // ERC20 tokens that can be shielded. The native token is always allowed.
+        address[] tokens;
+        // Root of the Merkle tree built off-chain from `tokens` (see `shielder_circuits::TokenList`).
+        // IMPORTANT: it is not validated against `tokens`; clients rebuild the tree and compare.
+        uint256 tokenListRoot;
+    }
+
+    /*
+     * Initialize the token list.
+     */
+    // solhint-disable func-name-mixedcase
+    function __TokenList_init(
+        address[] memory tokens,
+        uint256 _tokenListRoot
+    ) internal onlyInitializing {
+        _setTokenList(tokens, _tokenListRoot);
+    }
+
+    function _setTokenList(
+        address[] memory tokens,
+        uint256 _tokenListRoot
+    ) internal {
+        TokenListStorage storage $ = _getTokenListStorage();
+        $.tokens = tokens;
+        $.tokenListRoot = _tokenListRoot;
+    }
+
+    function _getTokenListStorage()
+        private
+        pure
+        returns (TokenListStorage storage $)
+    {
+        assembly {
+            $.slot := TOKEN_LIST_LOCATION
+        }
+    }
+
+    function tokenList() public view returns (address[] memory) {
+        TokenListStorage storage $ = _getTokenListStorage();
+        return $.tokens;
+    }
+
+    function tokenListRoot() public view returns (uint256) {
+        TokenListStorage storage $ = _getTokenListStorage();
+        return $.tokenListRoot;
+    }
+}
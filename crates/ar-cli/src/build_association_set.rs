@@ -0,0 +1,57 @@
+use std::{collections::HashSet, path::Path};
+
+use log::{info, warn};
+use rusqlite::Connection;
+use shielder_contract::AssociationSet;
+use type_conversions::field_to_u256;
+
+use crate::{
+    common::blob_to_field,
+    db::{self, Event},
+    error::Error,
+};
+
+/// Build an association set of all indexed accounts except the ones that made any of the
+/// `exclude` txs and write it to `output`
+///
+/// If the viewing key of an excluded tx is known, every tx made with the same key is excluded as
+/// well. Otherwise only the account created in that tx (if any) is excluded.
+pub async fn run(
+    connection: Connection,
+    output: &Path,
+    exclude: &[[u8; 32]],
+    to_block: Option<u64>,
+) -> Result<(), Error> {
+    let mut excluded_txs: HashSet<Vec<u8>> = exclude.iter().map(|tx| tx.to_vec()).collect();
+
+    for tx_hash in exclude {
+        let Event { viewing_key, .. } = db::query_event(&connection, tx_hash)?;
+        match viewing_key {
+            Some(key) => excluded_txs.extend(
+                db::query_events(&connection, Some(key))?
+                    .into_iter()
+                    .map(|event| event.tx_hash),
+            ),
+            None => warn!(
+                "No viewing key matching tx 0x{} could be found, only the account created in it is excluded.",
+                hex::encode(tx_hash)
+            ),
+        }
+    }
+
+    let prenullifiers = db::query_accounts(&connection, to_block)?
+        .into_iter()
+        .filter(|account| !excluded_txs.contains(&account.tx_hash))
+        .map(|account| blob_to_field(&account.prenullifier).map(field_to_u256))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let set = AssociationSet::new(prenullifiers)?;
+    set.save(output)?;
+
+    info!(
+        "Association set with {} accounts and root {} written to {output:?}",
+        set.len(),
+        set.root()
+    );
+    Ok(())
+}
@@ -80,6 +80,25 @@ pub enum Command {
         #[arg(long, value_parser = ValueParser::new(parse_32byte_array))]
         tx_hash: [u8; 32],
     },
+
+    /// Build an association set of all indexed accounts except the ones behind the excluded txs
+    BuildAssociationSet {
+        #[clap(flatten)]
+        db: Db,
+
+        #[arg(long, default_value = "./association_set.json")]
+        output: PathBuf,
+
+        /// Tx hash of an account to be left out of the set (can be repeated).
+        ///
+        /// All txs with the same viewing key are left out as well.
+        #[arg(long, value_parser = ValueParser::new(parse_32byte_array))]
+        exclude: Vec<[u8; 32]>,
+
+        /// Only include accounts created up to this block (inclusive).
+        #[arg(long)]
+        to_block: Option<u64>,
+    },
 }
 
 #[derive(Debug, Args)]
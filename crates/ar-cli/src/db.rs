@@ -102,6 +102,67 @@ pub fn query_events(
     results.into_iter().collect()
 }
 
+/// Account created in a `NewAccount` event
+#[derive(Debug)]
+pub struct Account {
+    pub tx_hash: Vec<u8>,
+    pub log_index: u64,
+    pub block_number: u64,
+    pub prenullifier: Vec<u8>,
+}
+
+pub fn create_accounts_table(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS accounts (
+            tx_hash BLOB NOT NULL,
+            log_index INTEGER NOT NULL,
+            block_number INTEGER NOT NULL,
+            prenullifier BLOB NOT NULL,
+            PRIMARY KEY (tx_hash, log_index)
+        )",
+        (),
+    )?;
+    Ok(())
+}
+
+pub fn upsert_account(connection: &Connection, account: Account) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "REPLACE INTO accounts (tx_hash, log_index, block_number, prenullifier) VALUES (?1, ?2, ?3, ?4)",
+        (&account.tx_hash, &account.log_index, &account.block_number, &account.prenullifier),
+    )?;
+
+    Ok(())
+}
+
+/// Query accounts created up to the optional `to_block` (inclusive), in the order of creation
+pub fn query_accounts(
+    connection: &Connection,
+    to_block: Option<u64>,
+) -> Result<Vec<Account>, rusqlite::Error> {
+    let statement = match to_block {
+        Some(_) => "SELECT tx_hash, log_index, block_number, prenullifier FROM accounts WHERE block_number <= ?1 ORDER BY block_number, log_index",
+        None => "SELECT tx_hash, log_index, block_number, prenullifier FROM accounts ORDER BY block_number, log_index",
+    };
+
+    let mut query = connection.prepare(statement)?;
+
+    let f = |row: &Row| -> Result<Account, rusqlite::Error> {
+        Ok(Account {
+            tx_hash: row.get(0)?,
+            log_index: row.get(1)?,
+            block_number: row.get(2)?,
+            prenullifier: row.get(3)?,
+        })
+    };
+
+    let results = match to_block {
+        Some(block) => query.query_map([block], f)?,
+        None => query.query_map([], f)?,
+    };
+
+    results.into_iter().collect()
+}
+
 pub fn create_checkpoint_table(
     connection: &Connection,
     table: &str,
@@ -12,12 +12,14 @@ use rusqlite::Connection;
 use shielder_circuits::Fr;
 use shielder_contract::{
     providers::create_simple_provider,
-    ShielderContract::{Deposit, NewAccount, ShielderContractEvents, Withdraw},
+    ShielderContract::{
+        Claim, Deposit, Migrate, NewAccount, ShielderContractEvents, Transfer, Withdraw,
+    },
 };
 use type_conversions::u256_to_field;
 
 use crate::{
-    db::{self, Event},
+    db::{self, Account, Event},
     error::Error,
 };
 
@@ -36,6 +38,7 @@ pub async fn run(
     let base_filter = Filter::new().address(*shielder_address);
 
     db::create_events_table(&connection)?;
+    db::create_accounts_table(&connection)?;
     db::create_checkpoint_table(&connection, CHECKPOINT_TABLE_NAME)?;
 
     let last_seen_block = db::query_checkpoint(&connection, CHECKPOINT_TABLE_NAME)?;
@@ -70,12 +73,17 @@ fn process_logs(logs: Vec<Log>, connection: &Connection) -> Result<(), Error> {
 
         match log.topic0() {
             Some(&NewAccount::SIGNATURE_HASH) => {
+                let event = NewAccount::decode_log_data(log.data(), true)?;
+                persist_account(
+                    connection,
+                    &event,
+                    &tx_hash,
+                    log.log_index.ok_or(Error::MissingData)?,
+                    block_number,
+                )?;
                 persist_event(
                     connection,
-                    ShielderContractEvents::NewAccount(NewAccount::decode_log_data(
-                        log.data(),
-                        true,
-                    )?),
+                    ShielderContractEvents::NewAccount(event),
                     &tx_hash,
                     block_number,
                 )?;
@@ -96,6 +104,30 @@ fn process_logs(logs: Vec<Log>, connection: &Connection) -> Result<(), Error> {
                     block_number,
                 )?;
             }
+            Some(&Transfer::SIGNATURE_HASH) => {
+                persist_event(
+                    connection,
+                    ShielderContractEvents::Transfer(Transfer::decode_log_data(log.data(), true)?),
+                    &tx_hash,
+                    block_number,
+                )?;
+            }
+            Some(&Claim::SIGNATURE_HASH) => {
+                persist_event(
+                    connection,
+                    ShielderContractEvents::Claim(Claim::decode_log_data(log.data(), true)?),
+                    &tx_hash,
+                    block_number,
+                )?;
+            }
+            Some(&Migrate::SIGNATURE_HASH) => {
+                persist_event(
+                    connection,
+                    ShielderContractEvents::Migrate(Migrate::decode_log_data(log.data(), true)?),
+                    &tx_hash,
+                    block_number,
+                )?;
+            }
             _ => debug!("Skipping log with an unknown topic {:?}", log.topic0()),
         };
     }
@@ -124,6 +156,21 @@ fn persist_event(
             macSalt,
             macCommitment,
             ..
+        })
+        | ShielderContractEvents::Transfer(Transfer {
+            macSalt,
+            macCommitment,
+            ..
+        })
+        | ShielderContractEvents::Claim(Claim {
+            macSalt,
+            macCommitment,
+            ..
+        })
+        | ShielderContractEvents::Migrate(Migrate {
+            macSalt,
+            macCommitment,
+            ..
         }) => (macSalt, macCommitment),
     };
 
@@ -141,3 +188,23 @@ fn persist_event(
     info!("Persisting event {event:?}");
     db::upsert_event(connection, event)
 }
+
+fn persist_account(
+    connection: &Connection,
+    event: &NewAccount,
+    tx_hash: &[u8; 32],
+    log_index: u64,
+    block_number: u64,
+) -> Result<(), rusqlite::Error> {
+    let prenullifier = u256_to_field::<Fr>(event.prenullifier).to_bytes();
+
+    let account = Account {
+        tx_hash: tx_hash.to_vec(),
+        log_index,
+        block_number,
+        prenullifier: prenullifier.to_vec(),
+    };
+
+    info!("Persisting account {account:?}");
+    db::upsert_account(connection, account)
+}
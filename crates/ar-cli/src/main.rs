@@ -6,6 +6,7 @@ use cli::{ChainConfig, Cli};
 use error::Error;
 use log::info;
 
+mod build_association_set;
 mod cli;
 mod collect_viewing_keys;
 mod common;
@@ -113,6 +114,16 @@ async fn main() -> Result<(), Error> {
             let connection = db::init(&db.path)?;
             reveal::run(connection, tx_hash).await?
         }
+
+        cli::Command::BuildAssociationSet {
+            db,
+            output,
+            exclude,
+            to_block,
+        } => {
+            let connection = db::init(&db.path)?;
+            build_association_set::run(connection, output, exclude, *to_block).await?
+        }
     }
 
     Ok(())
@@ -1,4 +1,18 @@
 //! Small library for encrypting and decrypting content with a password.
+//!
+//! Encrypted content is stored in a self-describing envelope:
+//!
+//! | field         | size | description                                     |
+//! |---------------|------|-------------------------------------------------|
+//! | magic         | 4    | `MAGIC`                                         |
+//! | version       | 1    | envelope format version (`FORMAT_VERSION`)      |
+//! | argon2 params | 20   | variant, version, memory, time and lanes (LE)   |
+//! | salt          | 32   | random, per-file salt for the key derivation    |
+//! | nonce         | 24   | random, per-file XChaCha20-Poly1305 nonce       |
+//! | ciphertext    | ...  | encrypted content, header is authenticated data |
+//!
+//! Content encrypted with the legacy scheme (fixed salt and nonce, no header) can still be
+//! decrypted.
 
 #![deny(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -7,29 +21,224 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 
-use anyhow::{anyhow, Result};
-use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305};
+use anyhow::{anyhow, bail, Result};
+use argon2::{Config, Variant, Version};
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    KeyInit, XChaCha20Poly1305,
+};
+use rand_core::CryptoRngCore;
 
-const SALT: [u8; 32] = [41u8; 32];
-const NONCE: [u8; 24] = [41u8; 24];
+/// Magic bytes starting every envelope.
+pub const MAGIC: [u8; 4] = *b"SHCE";
+/// Current envelope format version.
+pub const FORMAT_VERSION: u8 = 1;
 
-fn scheme_from_password(password: &[u8]) -> Result<XChaCha20Poly1305> {
-    let key = argon2::hash_raw(password, &SALT, &Default::default())
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 24;
+const KEY_LENGTH: u32 = 32;
+const PARAMS_LENGTH: usize = 5 * 4;
+const HEADER_LENGTH: usize = MAGIC.len() + 1 + PARAMS_LENGTH + SALT_LENGTH + NONCE_LENGTH;
+
+/// Upper bounds on the key derivation parameters accepted from an envelope header. The header is
+/// read before anything is authenticated, so without them a crafted file could make decryption
+/// allocate or compute arbitrarily much.
+const MAX_MEM_COST: u32 = 256 * 1024;
+const MAX_TIME_COST: u32 = 16;
+const MAX_LANES: u32 = 16;
+
+const LEGACY_SALT: [u8; 32] = [41u8; 32];
+const LEGACY_NONCE: [u8; 24] = [41u8; 24];
+
+/// Key derivation parameters stored in the envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct KdfParams {
+    variant: Variant,
+    version: Version,
+    mem_cost: u32,
+    time_cost: u32,
+    lanes: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        let config = Config::default();
+        Self {
+            variant: config.variant,
+            version: config.version,
+            mem_cost: config.mem_cost,
+            time_cost: config.time_cost,
+            lanes: config.lanes,
+        }
+    }
+}
+
+impl KdfParams {
+    fn to_bytes(self) -> [u8; PARAMS_LENGTH] {
+        let mut bytes = [0u8; PARAMS_LENGTH];
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip([
+            self.variant.as_u32(),
+            self.version.as_u32(),
+            self.mem_cost,
+            self.time_cost,
+            self.lanes,
+        ]) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut values = bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().expect("chunk has 4 bytes")));
+        let mut next = || {
+            values
+                .next()
+                .ok_or(anyhow!("Truncated key derivation parameters"))
+        };
+
+        let params = Self {
+            variant: Variant::from_u32(next()?).map_err(|e| anyhow!("Invalid variant: {e}"))?,
+            version: Version::from_u32(next()?).map_err(|e| anyhow!("Invalid version: {e}"))?,
+            mem_cost: next()?,
+            time_cost: next()?,
+            lanes: next()?,
+        };
+        params.check_bounds()?;
+        Ok(params)
+    }
+
+    fn check_bounds(&self) -> Result<()> {
+        if self.mem_cost > MAX_MEM_COST {
+            bail!(
+                "Memory cost {} exceeds the maximum of {MAX_MEM_COST}",
+                self.mem_cost
+            );
+        }
+        if self.time_cost > MAX_TIME_COST {
+            bail!(
+                "Time cost {} exceeds the maximum of {MAX_TIME_COST}",
+                self.time_cost
+            );
+        }
+        if self.lanes > MAX_LANES {
+            bail!("Lanes {} exceed the maximum of {MAX_LANES}", self.lanes);
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> Config<'static> {
+        Config {
+            variant: self.variant,
+            version: self.version,
+            mem_cost: self.mem_cost,
+            time_cost: self.time_cost,
+            lanes: self.lanes,
+            hash_length: KEY_LENGTH,
+            ..Config::default()
+        }
+    }
+}
+
+fn scheme_from_password(
+    password: &[u8],
+    salt: &[u8],
+    config: &Config,
+) -> Result<XChaCha20Poly1305> {
+    let key = argon2::hash_raw(password, salt, config)
         .map_err(|e| anyhow!("Failed to derive key from password: {e}"))?;
     Ok(XChaCha20Poly1305::new(key.as_slice().into()))
 }
 
-/// Encrypt `content` with `password`.
+/// Encrypt `content` with `password`, using a fresh random salt and nonce.
+#[cfg(feature = "std")]
 pub fn encrypt(content: &[u8], password: &[u8]) -> Result<Vec<u8>> {
-    scheme_from_password(password)?
-        .encrypt(NONCE.as_slice().into(), content)
-        .map_err(|e| anyhow!("Failed to encrypt data: {e}"))
+    encrypt_with_rng(content, password, &mut rand_core::OsRng)
+}
+
+/// Encrypt `content` with `password`. The salt and the nonce are drawn from `rng`.
+pub fn encrypt_with_rng(
+    content: &[u8],
+    password: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<Vec<u8>> {
+    let params = KdfParams::default();
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let mut envelope = Vec::with_capacity(HEADER_LENGTH + content.len());
+    envelope.extend_from_slice(&MAGIC);
+    envelope.push(FORMAT_VERSION);
+    envelope.extend_from_slice(&params.to_bytes());
+    envelope.extend_from_slice(&salt);
+    envelope.extend_from_slice(&nonce);
+
+    let ciphertext = scheme_from_password(password, &salt, &params.config())?
+        .encrypt(
+            nonce.as_slice().into(),
+            Payload {
+                msg: content,
+                aad: &envelope,
+            },
+        )
+        .map_err(|e| anyhow!("Failed to encrypt data: {e}"))?;
+
+    envelope.extend_from_slice(&ciphertext);
+    Ok(envelope)
 }
 
-/// Decrypt `content` with `password`.
+/// Decrypt `content` with `password`. Both the envelope format and the legacy format are
+/// supported.
 pub fn decrypt(content: &[u8], password: &[u8]) -> Result<Vec<u8>> {
-    scheme_from_password(password)?
-        .decrypt(NONCE.as_slice().into(), content)
+    if !content.starts_with(&MAGIC) {
+        return decrypt_legacy(content, password);
+    }
+    // A legacy ciphertext might start with magic bytes by accident.
+    decrypt_envelope(content, password)
+        .or_else(|e| decrypt_legacy(content, password).map_err(|_| e))
+}
+
+/// Returns `true` if `content` was encrypted with the legacy scheme (fixed salt and nonce) and
+/// should be re-encrypted with `encrypt`.
+pub fn is_legacy_format(content: &[u8]) -> bool {
+    !content.starts_with(&MAGIC)
+}
+
+fn decrypt_envelope(content: &[u8], password: &[u8]) -> Result<Vec<u8>> {
+    if content.len() < HEADER_LENGTH {
+        bail!("Encrypted content is too short");
+    }
+    let (header, ciphertext) = content.split_at(HEADER_LENGTH);
+
+    let version = header[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        bail!("Unsupported encryption format version: {version}");
+    }
+
+    let params_start = MAGIC.len() + 1;
+    let salt_start = params_start + PARAMS_LENGTH;
+    let nonce_start = salt_start + SALT_LENGTH;
+    let params = KdfParams::from_bytes(&header[params_start..salt_start])?;
+    let salt = &header[salt_start..nonce_start];
+    let nonce = &header[nonce_start..];
+
+    scheme_from_password(password, salt, &params.config())?
+        .decrypt(
+            nonce.into(),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|e| anyhow!("Failed to decrypt data - probably the password is incorrect: {e}"))
+}
+
+fn decrypt_legacy(content: &[u8], password: &[u8]) -> Result<Vec<u8>> {
+    scheme_from_password(password, &LEGACY_SALT, &Default::default())?
+        .decrypt(LEGACY_NONCE.as_slice().into(), content)
         .map_err(|e| anyhow!("Failed to decrypt data - probably the password is incorrect: {e}"))
 }
 
@@ -40,3 +249,75 @@ pub fn decrypt_to_string(content: &[u8], password: &[u8]) -> Result<String> {
     String::from_utf8(decrypted)
         .map_err(|e| anyhow!("Failed to decrypt data - probably the password is incorrect: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305};
+
+    use super::*;
+
+    const CONTENT: &[u8] = b"shielded state";
+    const PASSWORD: &[u8] = b"password";
+
+    fn legacy_encrypt(content: &[u8], password: &[u8]) -> Vec<u8> {
+        let key = argon2::hash_raw(password, &LEGACY_SALT, &Default::default()).unwrap();
+        XChaCha20Poly1305::new(key.as_slice().into())
+            .encrypt(LEGACY_NONCE.as_slice().into(), content)
+            .unwrap()
+    }
+
+    #[test]
+    fn roundtrip() {
+        let encrypted = encrypt(CONTENT, PASSWORD).unwrap();
+        assert!(encrypted.starts_with(&MAGIC));
+        assert_eq!(decrypt(&encrypted, PASSWORD).unwrap(), CONTENT);
+        assert!(!is_legacy_format(&encrypted));
+    }
+
+    #[test]
+    fn same_password_gives_different_ciphertexts() {
+        assert_ne!(
+            encrypt(CONTENT, PASSWORD).unwrap(),
+            encrypt(CONTENT, PASSWORD).unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_password_fails() {
+        let encrypted = encrypt(CONTENT, PASSWORD).unwrap();
+        assert!(decrypt(&encrypted, b"wrong").is_err());
+    }
+
+    #[test]
+    fn tampered_header_fails() {
+        let mut encrypted = encrypt(CONTENT, PASSWORD).unwrap();
+        encrypted[HEADER_LENGTH - 1] ^= 1;
+        assert!(decrypt(&encrypted, PASSWORD).is_err());
+    }
+
+    #[test]
+    fn oversized_kdf_params_are_rejected() {
+        let params_start = MAGIC.len() + 1;
+        for (offset, value) in [
+            (8, MAX_MEM_COST + 1),
+            (12, MAX_TIME_COST + 1),
+            (16, MAX_LANES + 1),
+            (8, u32::MAX),
+        ] {
+            let mut encrypted = encrypt(CONTENT, PASSWORD).unwrap();
+            let start = params_start + offset;
+            encrypted[start..start + 4].copy_from_slice(&value.to_le_bytes());
+
+            let error = decrypt_envelope(&encrypted, PASSWORD).unwrap_err();
+            assert!(error.to_string().contains("exceed"), "{error}");
+            assert!(decrypt(&encrypted, PASSWORD).is_err());
+        }
+    }
+
+    #[test]
+    fn legacy_content_can_be_decrypted() {
+        let encrypted = legacy_encrypt(CONTENT, PASSWORD);
+        assert_eq!(decrypt(&encrypted, PASSWORD).unwrap(), CONTENT);
+        assert!(is_legacy_format(&encrypted));
+    }
+}
@@ -7,10 +7,13 @@ use shielder_account::{
 };
 use shielder_contract::{
     call_type::{Call, DryRun, EstimateGas},
+    token_list::get_token_list_path,
     ConnectionPolicy, NoProvider, ShielderUser,
 };
 use shielder_setup::{
-    protocol_fee::compute_protocol_fee_from_gross, shielder_circuits::GrumpkinPointAffine,
+    consts::{ARITY, TOKEN_TREE_HEIGHT},
+    protocol_fee::compute_protocol_fee_from_gross,
+    shielder_circuits::GrumpkinPointAffine,
 };
 
 use crate::shielder::{get_mac_salt, pk::NEW_ACCOUNT_PROVING_EQUIPMENT};
@@ -34,6 +37,7 @@ pub async fn estimate_new_account_gas(
     );
 
     let anonymity_revoker_public_key = user.anonymity_revoker_pubkey::<DryRun>().await?;
+    let token_list_path = get_token_list_path(token.address(), &user).await?;
     let protocol_fee_bps = user.protocol_deposit_fee_bps::<DryRun>().await?;
 
     let protocol_fee = compute_protocol_fee_from_gross(amount, protocol_fee_bps);
@@ -45,7 +49,7 @@ pub async fn estimate_new_account_gas(
         anonymity_revoker_public_key,
         user.address(),
         protocol_fee,
-        Bytes::from(vec![]),
+        token_list_path,
     )?;
     let estimated_gas = match token {
         Token::Native => {
@@ -68,7 +72,7 @@ fn prepare_call(
     anonymity_revoker_public_key: GrumpkinPointAffine<U256>,
     caller_address: Address,
     protocol_fee: U256,
-    memo: Bytes,
+    token_list_path: [[U256; ARITY]; TOKEN_TREE_HEIGHT],
 ) -> Result<NewAccountCall> {
     let (params, pk) = NEW_ACCOUNT_PROVING_EQUIPMENT.clone();
     // let (params, pk) = get_proving_equipment(CircuitType::NewAccount)?;
@@ -78,7 +82,8 @@ fn prepare_call(
         mac_salt: get_mac_salt(),
         caller_address,
         protocol_fee,
-        memo,
+        memo: Bytes::from(vec![]),
+        token_list_path,
     };
 
     Ok(shielder_account.prepare_call::<NewAccountCallType>(&params, &pk, token, amount, &extra))
@@ -92,6 +97,7 @@ pub async fn create_new_account(
     protocol_fee: U256,
 ) -> Result<TxHash> {
     let anonymity_revoker_public_key = user.anonymity_revoker_pubkey::<DryRun>().await?;
+    let token_list_path = get_token_list_path(token.address(), user).await?;
 
     let call = prepare_call(
         shielder_account,
@@ -100,7 +106,7 @@ pub async fn create_new_account(
         anonymity_revoker_public_key,
         user.address(),
         protocol_fee,
-        Bytes::from(vec![]),
+        token_list_path,
     )?;
 
     let (tx_hash, _) = match token {
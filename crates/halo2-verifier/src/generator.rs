@@ -1,4 +1,10 @@
-use std::{fs::File, io::Write, path::PathBuf, str};
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+    str,
+};
 
 use halo2_proofs::{
     halo2curves::bn256::{Bn256, Fr},
@@ -7,14 +13,26 @@ use halo2_proofs::{
 use halo2_solidity_verifier::{BatchOpenScheme::Bdfg21, SolidityGenerator};
 use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
 use shielder_circuits::{
+    batch_withdraw::BatchWithdrawProverKnowledge,
     circuits::{generate_keys_with_min_k, Params},
+    claim::ClaimProverKnowledge,
     deposit::DepositProverKnowledge,
+    migration::MigrationProverKnowledge,
     new_account::NewAccountProverKnowledge,
+    transfer::TransferProverKnowledge,
     withdraw::WithdrawProverKnowledge,
     EnumCount, ProverKnowledge, MAX_K,
 };
+use shielder_setup::{
+    version::contract_version,
+    vk_fingerprint::{
+        expected_vk_fingerprint, vk_fingerprint, DeployedCircuit, ShielderCircuit, VkFingerprint,
+        VK_FINGERPRINTS,
+    },
+};
 
 const CONTRACTS_DIR: &str = "./contracts";
+const VK_FINGERPRINTS_FILE: &str = "./crates/shielder-setup/vk_fingerprints.rs";
 
 pub fn main() {
     let full_parameters = read_setup_parameters(
@@ -23,27 +41,90 @@ pub fn main() {
     )
     .expect("failed to read parameters from the ptau file");
 
-    handle_relation::<NewAccountProverKnowledge<Fr>>(full_parameters.clone(), "NewAccount");
-    handle_relation::<DepositProverKnowledge<Fr>>(full_parameters.clone(), "Deposit");
-    handle_relation::<WithdrawProverKnowledge<Fr>>(full_parameters, "Withdraw");
+    let fingerprints = [
+        handle_relation::<NewAccountProverKnowledge<Fr>>(full_parameters.clone(), "NewAccount"),
+        handle_relation::<DepositProverKnowledge<Fr>>(full_parameters.clone(), "Deposit"),
+        handle_relation::<WithdrawProverKnowledge<Fr>>(full_parameters.clone(), "Withdraw"),
+        handle_relation::<TransferProverKnowledge<Fr>>(full_parameters.clone(), "Transfer"),
+        handle_relation::<ClaimProverKnowledge<Fr>>(full_parameters.clone(), "Claim"),
+        handle_relation::<MigrationProverKnowledge<Fr>>(full_parameters.clone(), "Migration"),
+        handle_relation::<BatchWithdrawProverKnowledge<Fr>>(full_parameters, "BatchWithdraw"),
+    ];
+    record_vk_fingerprints(&fingerprints);
 }
 
-/// Generate verifier contract for the given circuit type.
-fn handle_relation<PK: ProverKnowledge>(full_params: Params, relation: &str) {
+/// Generate verifier contract for the given circuit type. Returns the fingerprint of the embedded
+/// verification key.
+fn handle_relation<PK: ProverKnowledge>(
+    full_params: Params,
+    relation: &str,
+) -> (ShielderCircuit, VkFingerprint)
+where
+    PK::Circuit: DeployedCircuit,
+{
     println!("Generating {relation} relation contracts...");
-    let verifier_solidity = generate_solidity_verification_bundle::<PK>(full_params);
+    let (verifier_solidity, fingerprint) = generate_solidity_verification_bundle::<PK>(full_params);
     save_contract_source(&format!("{relation}Verifier.sol"), &verifier_solidity);
+    (PK::Circuit::CIRCUIT, fingerprint)
 }
 
 /// Given trusted setup, generate Solidity code for the verifier with embedded verification key.
+/// Returns the code together with the fingerprint of the key.
 fn generate_solidity_verification_bundle<PK: ProverKnowledge>(
     full_parameters: ParamsKZG<Bn256>,
-) -> String {
+) -> (String, VkFingerprint) {
     let (parameters, _, _, vk) = generate_keys_with_min_k(PK::Circuit::default(), full_parameters)
         .expect("Failed to generate keys");
-    SolidityGenerator::new(&parameters, &vk, Bdfg21, PK::PublicInput::COUNT)
+    let solidity = SolidityGenerator::new(&parameters, &vk, Bdfg21, PK::PublicInput::COUNT)
         .render()
-        .expect("Failed to generate separate contracts")
+        .expect("Failed to generate separate contracts");
+    (solidity, vk_fingerprint(&vk))
+}
+
+/// Record `fingerprints` for the current contract version in `VK_FINGERPRINTS_FILE`. If the version
+/// is already recorded, the keys must not have changed - the circuits of a released version are
+/// fixed, so a change requires a new contract version.
+fn record_vk_fingerprints(fingerprints: &[(ShielderCircuit, VkFingerprint)]) {
+    let version = contract_version();
+    if VK_FINGERPRINTS
+        .iter()
+        .any(|(recorded, _)| recorded.is_compatible_with(&version))
+    {
+        for &(circuit, fingerprint) in fingerprints {
+            assert_eq!(
+                expected_vk_fingerprint(version, circuit),
+                Some(fingerprint),
+                "Verifying key of the {} circuit differs from the one recorded for contract \
+                 version {version}. Bump the contract version.",
+                circuit.name()
+            );
+        }
+        println!("Verifying key fingerprints of contract version {version} are up to date");
+        return;
+    }
+
+    let mut entry = format!(
+        "    (\n        ContractVersion {{\n            note_version: {},\n            \
+         circuit_version: {},\n            patch_version: {},\n        }},\n        &[\n",
+        version.note_version, version.circuit_version, version.patch_version
+    );
+    for (circuit, fingerprint) in fingerprints {
+        writeln!(
+            entry,
+            "            (\n                ShielderCircuit::{circuit:?},\n                \
+             alloy_primitives::b256!(\"{fingerprint:x}\"),\n            ),"
+        )
+        .unwrap();
+    }
+    entry.push_str("        ],\n    ),\n");
+
+    let mut table = fs::read_to_string(VK_FINGERPRINTS_FILE).expect("Can read fingerprints");
+    let end = table
+        .rfind(']')
+        .expect("Fingerprints should be a slice literal");
+    table.insert_str(end, &entry);
+    fs::write(VK_FINGERPRINTS_FILE, table).expect("Can write fingerprints");
+    println!("Recorded verifying key fingerprints of contract version {version}");
 }
 
 /// Writes solidity source code to the file under `CONTRACTS_DIR` directory.
@@ -62,16 +143,25 @@ mod test {
     use evm_utils::{compilation::source_to_bytecode, EvmRunner, EvmRunnerError, SuccessResult};
     use halo2_proofs::halo2curves::bn256::Fr;
     use halo2_solidity_verifier::verifier_contract;
+    use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
     use shielder_circuits::{
+        batch_withdraw::BatchWithdrawProverKnowledge,
         circuits::{generate_proof, generate_setup_params},
+        claim::ClaimProverKnowledge,
         consts::MAX_K,
         deposit::DepositProverKnowledge,
         generate_keys_with_min_k,
+        migration::MigrationProverKnowledge,
         new_account::NewAccountProverKnowledge,
+        transfer::TransferProverKnowledge,
         withdraw::WithdrawProverKnowledge,
         ProverKnowledge,
     };
-    use shielder_setup::parameter_generation::rng;
+    use shielder_setup::{
+        parameter_generation::rng,
+        version::contract_version,
+        vk_fingerprint::{expected_vk_fingerprint, DeployedCircuit},
+    };
 
     use crate::generate_solidity_verification_bundle;
 
@@ -79,6 +169,10 @@ mod test {
     pub const NEW_ACCOUNT_VERIFICATION_GAS_COST: u64 = 706212; //1.1 * 642011;
     pub const DEPOSIT_VERIFICATION_GAS_COST: u64 = 914940; //1.1 * 831764;
     pub const WITHDRAW_VERIFICATION_GAS_COST: u64 = 1017855; //1.1 * 925323;
+    pub const BATCH_WITHDRAW_VERIFICATION_GAS_COST: u64 = 1100000;
+    pub const TRANSFER_VERIFICATION_GAS_COST: u64 = 1100000;
+    pub const CLAIM_VERIFICATION_GAS_COST: u64 = 1100000;
+    pub const MIGRATION_VERIFICATION_GAS_COST: u64 = 914940;
 
     fn deploy_source_code(source: &str, contract_name: &str, evm: &mut EvmRunner) -> Address {
         let bytecode = source_to_bytecode(source, contract_name, true);
@@ -120,7 +214,7 @@ mod test {
         let prover_knowledge = PK::random_correct_example(&mut rng);
         let public_input = prover_knowledge.serialize_public_input();
 
-        let verifier_solidity =
+        let (verifier_solidity, _) =
             generate_solidity_verification_bundle::<PK>(full_parameters.clone());
 
         let (parameters, _, pk, _) =
@@ -133,6 +227,47 @@ mod test {
         assert!(result.unwrap() <= cost_upper_bound);
     }
 
+    // Check that the verifying key fingerprints recorded for the current contract version are the
+    // ones of keys generated from the trusted setup, as `main` does.
+    fn check_recorded_vk_fingerprint<PK: ProverKnowledge>()
+    where
+        PK::Circuit: DeployedCircuit,
+    {
+        let full_parameters = read_setup_parameters(
+            get_ptau_file_path(MAX_K, Format::PerpetualPowersOfTau),
+            Format::PerpetualPowersOfTau,
+        )
+        .expect("failed to read parameters from the ptau file");
+        let (_, fingerprint) = generate_solidity_verification_bundle::<PK>(full_parameters);
+
+        let circuit = PK::Circuit::CIRCUIT;
+        let version = contract_version();
+        let expected = expected_vk_fingerprint(version, circuit).unwrap_or_else(|| {
+            panic!(
+                "No fingerprint of the {} circuit is recorded for contract version {version}. \
+                 Run `make generate-verifier-contracts`.",
+                circuit.name()
+            )
+        });
+        assert_eq!(
+            fingerprint,
+            expected,
+            "Recorded fingerprint of the {} circuit is stale",
+            circuit.name()
+        );
+    }
+
+    #[test]
+    fn recorded_vk_fingerprints_match_generated_keys() {
+        check_recorded_vk_fingerprint::<NewAccountProverKnowledge<Fr>>();
+        check_recorded_vk_fingerprint::<DepositProverKnowledge<Fr>>();
+        check_recorded_vk_fingerprint::<WithdrawProverKnowledge<Fr>>();
+        check_recorded_vk_fingerprint::<TransferProverKnowledge<Fr>>();
+        check_recorded_vk_fingerprint::<ClaimProverKnowledge<Fr>>();
+        check_recorded_vk_fingerprint::<MigrationProverKnowledge<Fr>>();
+        check_recorded_vk_fingerprint::<BatchWithdrawProverKnowledge<Fr>>();
+    }
+
     #[test]
     fn prove_and_verify_new_account() {
         prove_and_verify::<NewAccountProverKnowledge<Fr>>(NEW_ACCOUNT_VERIFICATION_GAS_COST);
@@ -147,4 +282,24 @@ mod test {
     fn prove_and_verify_withdraw() {
         prove_and_verify::<WithdrawProverKnowledge<Fr>>(WITHDRAW_VERIFICATION_GAS_COST);
     }
+
+    #[test]
+    fn prove_and_verify_batch_withdraw() {
+        prove_and_verify::<BatchWithdrawProverKnowledge<Fr>>(BATCH_WITHDRAW_VERIFICATION_GAS_COST);
+    }
+
+    #[test]
+    fn prove_and_verify_transfer() {
+        prove_and_verify::<TransferProverKnowledge<Fr>>(TRANSFER_VERIFICATION_GAS_COST);
+    }
+
+    #[test]
+    fn prove_and_verify_claim() {
+        prove_and_verify::<ClaimProverKnowledge<Fr>>(CLAIM_VERIFICATION_GAS_COST);
+    }
+
+    #[test]
+    fn prove_and_verify_migration() {
+        prove_and_verify::<MigrationProverKnowledge<Fr>>(MIGRATION_VERIFICATION_GAS_COST);
+    }
 }
@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity 0.8.26;
+
+import { Shielder } from "Shielder.sol";
+
+/// @title ShielderNoteVersionHarness
+/// @notice Shielder with a newer note schema version, so that the notes it has already created
+/// @notice (with the current version) can be migrated in tests.
+/// @custom:oz-upgrades-unsafe-allow external-library-linking
+contract ShielderNoteVersionHarness is Shielder {
+    function _noteVersion() internal pure override returns (uint8) {
+        return uint8(CONTRACT_VERSION[0]) + 1;
+    }
+}
@@ -2,15 +2,18 @@ use std::{env, fs::File, io::Write};
 
 use alloy_primitives::U256;
 use integration_tests::{
+    batch_withdraw_proving_params,
     calls::{
         deposit::{invoke_call as deposit_call, prepare_call as deposit_calldata},
         new_account::{invoke_call as new_account_call, prepare_call as new_account_calldata},
         withdraw::{invoke_call as withdraw_call, prepare_args, prepare_call as withdraw_calldata},
     },
+    claim_proving_params,
     deploy::{
         deployment, Deployment, MEMO_BYTES, PROTOCOL_FEES, ZERO_MEMO_BYTES, ZERO_PROTOCOL_FEES,
     },
-    deposit_proving_params, new_account_proving_params, withdraw_proving_params, TestToken,
+    deposit_proving_params, migration_proving_params, new_account_proving_params,
+    transfer_proving_params, withdraw_proving_params, TestToken,
 };
 use shielder_account::{
     call_data::{DepositCall, NewAccountCall, WithdrawCall},
@@ -33,6 +36,10 @@ fn main() {
         &new_account_proving_params(),
         &deposit_proving_params(),
         &withdraw_proving_params(),
+        &batch_withdraw_proving_params(),
+        &transfer_proving_params(),
+        &claim_proving_params(),
+        &migration_proving_params(),
     );
 
     let mut shielder_account =
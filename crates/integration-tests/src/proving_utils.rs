@@ -3,10 +3,14 @@ use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
 use rand::{RngCore, SeedableRng};
 use rstest::fixture;
 use shielder_circuits::{
+    batch_withdraw::BatchWithdrawCircuit,
     circuits::{Params, ProvingKey, VerifyingKey},
+    claim::ClaimCircuit,
     deposit::DepositCircuit,
     generate_keys_with_min_k, generate_proof,
+    migration::MigrationCircuit,
     new_account::NewAccountCircuit,
+    transfer::TransferCircuit,
     verify,
     withdraw::WithdrawCircuit,
     Fr, ProverKnowledge, MAX_K,
@@ -72,3 +76,31 @@ pub fn withdraw_proving_params() -> ProvingParams {
     println!("Preparing Withdraw proving keys");
     prepare_proving_keys::<WithdrawCircuit>()
 }
+
+#[fixture]
+#[once]
+pub fn batch_withdraw_proving_params() -> ProvingParams {
+    println!("Preparing BatchWithdraw proving keys");
+    prepare_proving_keys::<BatchWithdrawCircuit>()
+}
+
+#[fixture]
+#[once]
+pub fn transfer_proving_params() -> ProvingParams {
+    println!("Preparing Transfer proving keys");
+    prepare_proving_keys::<TransferCircuit>()
+}
+
+#[fixture]
+#[once]
+pub fn claim_proving_params() -> ProvingParams {
+    println!("Preparing Claim proving keys");
+    prepare_proving_keys::<ClaimCircuit>()
+}
+
+#[fixture]
+#[once]
+pub fn migration_proving_params() -> ProvingParams {
+    println!("Preparing Migration proving keys");
+    prepare_proving_keys::<MigrationCircuit>()
+}
@@ -12,6 +12,10 @@ pub enum ShielderCallErrors {
     NativeTransferFailed(ShielderContract::NativeTransferFailed),
     WithdrawVerificationFailed(ShielderContract::WithdrawVerificationFailed),
     NewAccountVerificationFailed(ShielderContract::NewAccountVerificationFailed),
+    TransferVerificationFailed(ShielderContract::TransferVerificationFailed),
+    ClaimVerificationFailed(ShielderContract::ClaimVerificationFailed),
+    MigrationVerificationFailed(ShielderContract::MigrationVerificationFailed),
+    UnsupportedNoteVersion(ShielderContract::UnsupportedNoteVersion),
     ZeroAmount(ShielderContract::ZeroAmount),
     AmountTooHigh(ShielderContract::AmountTooHigh),
     ContractBalanceLimitReached(ShielderContract::ContractBalanceLimitReached),
@@ -20,6 +24,10 @@ pub enum ShielderCallErrors {
 
     InvalidGrumpkinPoint(ShielderContract::InvalidGrumpkinPoint),
     OwnableUnauthorizedAccount(ShielderContract::OwnableUnauthorizedAccount),
+    DepositFeeTooHigh(ShielderContract::DepositFeeTooHigh),
+    WithdrawFeeTooHigh(ShielderContract::WithdrawFeeTooHigh),
+    EnforcedPause(ShielderContract::EnforcedPause),
+    ExpectedPause(ShielderContract::ExpectedPause),
 
     DestinationTriggeredRevert(),
 }
@@ -48,6 +56,18 @@ impl From<ShielderContractErrors> for ShielderCallErrors {
             ShielderContractErrors::NewAccountVerificationFailed(e) => {
                 ShielderCallErrors::NewAccountVerificationFailed(e)
             }
+            ShielderContractErrors::TransferVerificationFailed(e) => {
+                ShielderCallErrors::TransferVerificationFailed(e)
+            }
+            ShielderContractErrors::ClaimVerificationFailed(e) => {
+                ShielderCallErrors::ClaimVerificationFailed(e)
+            }
+            ShielderContractErrors::MigrationVerificationFailed(e) => {
+                ShielderCallErrors::MigrationVerificationFailed(e)
+            }
+            ShielderContractErrors::UnsupportedNoteVersion(e) => {
+                ShielderCallErrors::UnsupportedNoteVersion(e)
+            }
             ShielderContractErrors::ZeroAmount(e) => ShielderCallErrors::ZeroAmount(e),
             ShielderContractErrors::AmountTooHigh(e) => ShielderCallErrors::AmountTooHigh(e),
             ShielderContractErrors::ContractBalanceLimitReached(e) => {
@@ -63,6 +83,14 @@ impl From<ShielderContractErrors> for ShielderCallErrors {
             ShielderContractErrors::OwnableUnauthorizedAccount(e) => {
                 ShielderCallErrors::OwnableUnauthorizedAccount(e)
             }
+            ShielderContractErrors::DepositFeeTooHigh(e) => {
+                ShielderCallErrors::DepositFeeTooHigh(e)
+            }
+            ShielderContractErrors::WithdrawFeeTooHigh(e) => {
+                ShielderCallErrors::WithdrawFeeTooHigh(e)
+            }
+            ShielderContractErrors::EnforcedPause(e) => ShielderCallErrors::EnforcedPause(e),
+            ShielderContractErrors::ExpectedPause(e) => ShielderCallErrors::ExpectedPause(e),
         }
     }
 }
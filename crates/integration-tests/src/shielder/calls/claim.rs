@@ -0,0 +1,249 @@
+use alloy_primitives::{Bytes, TxHash, U256};
+use shielder_account::{
+    call_data::{ClaimCall, ClaimCallType, ClaimExtra},
+    ReceivedTransfer, ShielderAccount,
+};
+use shielder_contract::ShielderContract::claimCall;
+use shielder_setup::version::contract_version;
+
+use crate::{
+    shielder::{deploy::Deployment, invoke_shielder_call, merkle::get_merkle_path, CallResult},
+    TestToken,
+};
+
+pub fn prepare_call(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    token: TestToken,
+    receipt: ReceivedTransfer,
+    memo: Bytes,
+) -> (ClaimCall, U256) {
+    let note_index = shielder_account
+        .current_leaf_index()
+        .expect("No leaf index");
+
+    let (params, pk) = deployment.claim_proving_params.clone();
+    let merkle_path = get_merkle_path(
+        deployment.contract_suite.shielder,
+        note_index,
+        &mut deployment.evm,
+    );
+    let receipt_path = get_merkle_path(
+        deployment.contract_suite.shielder,
+        receipt.receipt_index,
+        &mut deployment.evm,
+    );
+
+    let calldata = shielder_account.prepare_call::<ClaimCallType>(
+        &params,
+        &pk,
+        token.token(deployment),
+        receipt.value,
+        &ClaimExtra {
+            merkle_path,
+            receipt,
+            receipt_path,
+            contract_version: contract_version(),
+            chain_id: U256::from(1),
+            mac_salt: U256::ZERO,
+            memo,
+        },
+    );
+    (calldata, note_index)
+}
+
+pub fn invoke_call(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    calldata: &ClaimCall,
+) -> CallResult {
+    let call_result = invoke_shielder_call(deployment, &claimCall::from(calldata.clone()), None);
+
+    match call_result {
+        Ok((events, success_result)) => {
+            assert!(events.len() == 1);
+            let event = events[0].clone();
+            shielder_account.register_action((TxHash::default(), event.clone()));
+            Ok((events, success_result))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use alloy_primitives::U256;
+    use rstest::rstest;
+    use shielder_account::{ReceivedTransfer, ShielderAccount};
+    use shielder_contract::ShielderContract::{Claim, ShielderContractEvents};
+    use shielder_setup::version::contract_version;
+
+    use crate::{
+        call_errors::ShielderCallErrors,
+        calls::{
+            claim::{invoke_call, prepare_call},
+            new_account, transfer, withdraw,
+        },
+        deploy::{MEMO_BYTES, ZERO_MEMO_BYTES},
+        shielder::{
+            actor_balance_decreased_by,
+            deploy::{deployment, Deployment},
+            recipient_balance_increased_by,
+        },
+        TestToken,
+    };
+
+    /// Create a sender with 100 and a recipient with 10 shielded tokens, and transfer 30 from the
+    /// former to the latter.
+    fn transfer_to_recipient(
+        deployment: &mut Deployment,
+        token: TestToken,
+    ) -> (ShielderAccount, ReceivedTransfer) {
+        let (private_key, public_key) = transfer::recipient_keys();
+        let mut sender = new_account::create_account_and_call(
+            deployment,
+            token,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+        let recipient = new_account::create_account_and_call(
+            deployment,
+            token,
+            U256::from(2),
+            U256::from(10),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, _) = transfer::prepare_call(
+            deployment,
+            &mut sender,
+            token,
+            U256::from(30),
+            recipient.receiving_tag(),
+            public_key,
+            ZERO_MEMO_BYTES,
+        );
+        let events = transfer::invoke_call(deployment, &mut sender, &calldata)
+            .unwrap()
+            .0;
+        let ShielderContractEvents::Transfer(event) = &events[0] else {
+            panic!("Expected a Transfer event");
+        };
+        let received = recipient.received_transfer(private_key, event).unwrap();
+        (recipient, received)
+    }
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn succeeds(mut deployment: Deployment, #[case] token: TestToken) {
+        let (mut recipient, received) = transfer_to_recipient(&mut deployment, token);
+
+        let (calldata, note_index) =
+            prepare_call(&mut deployment, &mut recipient, token, received, MEMO_BYTES);
+        let events = invoke_call(&mut deployment, &mut recipient, &calldata)
+            .unwrap()
+            .0;
+
+        assert_eq!(
+            events,
+            vec![ShielderContractEvents::Claim(Claim {
+                contractVersion: contract_version().to_bytes(),
+                tokenAddress: token.address(&deployment),
+                newNote: calldata.new_note,
+                newNoteIndex: note_index + U256::from(3),
+                receiptNullifier: recipient.receipt_nullifier(received.receipt),
+                encryptedValue: calldata.encrypted_value,
+                macSalt: U256::ZERO,
+                macCommitment: calldata.mac_commitment,
+                memo: MEMO_BYTES,
+            })]
+        );
+        assert_eq!(recipient.shielded_amount, U256::from(40));
+        assert_eq!(recipient.note(recipient.token), Some(calldata.new_note));
+        assert!(actor_balance_decreased_by(
+            &deployment,
+            token,
+            U256::from(110)
+        ));
+    }
+
+    #[rstest]
+    fn recipient_can_spend_claimed_funds(mut deployment: Deployment) {
+        let (mut recipient, received) = transfer_to_recipient(&mut deployment, TestToken::Native);
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut recipient,
+            TestToken::Native,
+            received,
+            ZERO_MEMO_BYTES,
+        );
+        assert!(invoke_call(&mut deployment, &mut recipient, &calldata).is_ok());
+
+        let (withdraw_calldata, _) = withdraw::prepare_call(
+            &mut deployment,
+            &mut recipient,
+            withdraw::prepare_args(
+                TestToken::Native,
+                U256::from(35),
+                U256::from(1),
+                U256::ZERO,
+                ZERO_MEMO_BYTES,
+            ),
+        );
+        assert!(withdraw::invoke_call(&mut deployment, &mut recipient, &withdraw_calldata).is_ok());
+
+        assert_eq!(recipient.shielded_amount, U256::from(5));
+        assert!(recipient_balance_increased_by(
+            &deployment,
+            TestToken::Native,
+            U256::from(34)
+        ));
+    }
+
+    #[rstest]
+    fn cannot_claim_same_receipt_twice(mut deployment: Deployment) {
+        let (mut recipient, received) = transfer_to_recipient(&mut deployment, TestToken::Native);
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut recipient,
+            TestToken::Native,
+            received,
+            ZERO_MEMO_BYTES,
+        );
+        assert!(invoke_call(&mut deployment, &mut recipient, &calldata).is_ok());
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut recipient,
+            TestToken::Native,
+            received,
+            ZERO_MEMO_BYTES,
+        );
+        let result = invoke_call(&mut deployment, &mut recipient, &calldata);
+
+        assert_matches!(result, Err(ShielderCallErrors::DuplicatedNullifier(_)));
+    }
+
+    #[rstest]
+    fn fails_if_proof_incorrect(mut deployment: Deployment) {
+        let (mut recipient, received) = transfer_to_recipient(&mut deployment, TestToken::Native);
+        let (mut calldata, _) = prepare_call(
+            &mut deployment,
+            &mut recipient,
+            TestToken::Native,
+            received,
+            ZERO_MEMO_BYTES,
+        );
+        calldata.encrypted_value = calldata.encrypted_value.wrapping_add(U256::from(1));
+
+        let result = invoke_call(&mut deployment, &mut recipient, &calldata);
+
+        assert_matches!(result, Err(ShielderCallErrors::ClaimVerificationFailed(_)));
+    }
+}
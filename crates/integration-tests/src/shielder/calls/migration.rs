@@ -0,0 +1,282 @@
+use alloy_primitives::{Bytes, TxHash, U256};
+use shielder_account::{
+    call_data::{MigrateCall, MigrationCallType, MigrationExtra},
+    ShielderAccount,
+};
+use shielder_contract::ShielderContract::migrateCall;
+use shielder_setup::version::contract_version;
+
+use crate::{
+    shielder::{deploy::Deployment, invoke_shielder_call, merkle::get_merkle_path, CallResult},
+    TestToken,
+};
+
+pub fn prepare_call(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    token: TestToken,
+    memo: Bytes,
+) -> (MigrateCall, U256) {
+    let note_index = shielder_account
+        .current_leaf_index()
+        .expect("No leaf index");
+
+    let (params, pk) = deployment.migration_proving_params.clone();
+    let merkle_path = get_merkle_path(
+        deployment.contract_suite.shielder,
+        note_index,
+        &mut deployment.evm,
+    );
+
+    let calldata = shielder_account.prepare_call::<MigrationCallType>(
+        &params,
+        &pk,
+        token.token(deployment),
+        U256::ZERO,
+        &MigrationExtra {
+            merkle_path,
+            contract_version: contract_version(),
+            chain_id: U256::from(1),
+            mac_salt: U256::ZERO,
+            memo,
+        },
+    );
+    (calldata, note_index)
+}
+
+pub fn invoke_call(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    calldata: &MigrateCall,
+) -> CallResult {
+    let call_result = invoke_shielder_call(deployment, &migrateCall::from(calldata.clone()), None);
+
+    match call_result {
+        Ok((events, success_result)) => {
+            assert!(events.len() == 1);
+            let event = events[0].clone();
+            shielder_account.register_action((TxHash::default(), event.clone()));
+            Ok((events, success_result))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use alloy_primitives::U256;
+    use rstest::rstest;
+    use shielder_contract::ShielderContract::{Migrate, ShielderContractEvents};
+    use shielder_setup::version::contract_version;
+
+    use crate::{
+        call_errors::ShielderCallErrors,
+        calls::{
+            migration::{invoke_call, prepare_call},
+            new_account, withdraw,
+        },
+        deploy::{MEMO_BYTES, ZERO_MEMO_BYTES},
+        shielder::{
+            actor_balance_decreased_by,
+            deploy::{deployment, note_version_harness_deployment, Deployment},
+            destination_balances_unchanged, recipient_balance_increased_by,
+        },
+        TestToken,
+    };
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn succeeds(
+        #[from(note_version_harness_deployment)] mut deployment: Deployment,
+        #[case] token: TestToken,
+    ) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(1),
+            U256::from(100),
+            MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, note_index) =
+            prepare_call(&mut deployment, &mut shielder_account, token, MEMO_BYTES);
+        let events = invoke_call(&mut deployment, &mut shielder_account, &calldata)
+            .unwrap()
+            .0;
+
+        assert_eq!(
+            events,
+            vec![ShielderContractEvents::Migrate(Migrate {
+                contractVersion: contract_version().to_bytes(),
+                tokenAddress: token.address(&deployment),
+                oldNoteVersion: contract_version().note_version,
+                newNote: calldata.new_note,
+                newNoteIndex: note_index + U256::from(1),
+                macSalt: U256::ZERO,
+                macCommitment: calldata.mac_commitment,
+                memo: MEMO_BYTES,
+            })]
+        );
+        assert_eq!(shielder_account.shielded_amount, U256::from(100));
+        assert_eq!(
+            shielder_account.note(shielder_account.token),
+            Some(calldata.new_note)
+        );
+        assert!(actor_balance_decreased_by(
+            &deployment,
+            token,
+            U256::from(100)
+        ));
+        assert!(destination_balances_unchanged(&deployment, token))
+    }
+
+    #[rstest]
+    fn migrated_note_can_be_spent(
+        #[from(note_version_harness_deployment)] mut deployment: Deployment,
+    ) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            TestToken::Native,
+            ZERO_MEMO_BYTES,
+        );
+        assert!(invoke_call(&mut deployment, &mut shielder_account, &calldata).is_ok());
+
+        let (withdraw_calldata, _) = withdraw::prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            withdraw::prepare_args(
+                TestToken::Native,
+                U256::from(10),
+                U256::from(1),
+                U256::ZERO,
+                ZERO_MEMO_BYTES,
+            ),
+        );
+        assert!(
+            withdraw::invoke_call(&mut deployment, &mut shielder_account, &withdraw_calldata)
+                .is_ok()
+        );
+        assert!(recipient_balance_increased_by(
+            &deployment,
+            TestToken::Native,
+            U256::from(9)
+        ));
+    }
+
+    #[rstest]
+    fn fails_if_proof_incorrect(
+        #[from(note_version_harness_deployment)] mut deployment: Deployment,
+    ) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (mut calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            TestToken::Native,
+            ZERO_MEMO_BYTES,
+        );
+        calldata.new_note = calldata.new_note.wrapping_add(U256::from(1));
+
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+
+        assert_matches!(
+            result,
+            Err(ShielderCallErrors::MigrationVerificationFailed(_))
+        );
+    }
+
+    #[rstest]
+    fn cannot_migrate_same_note_twice(
+        #[from(note_version_harness_deployment)] mut deployment: Deployment,
+    ) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            TestToken::Native,
+            ZERO_MEMO_BYTES,
+        );
+        assert!(invoke_call(&mut deployment, &mut shielder_account, &calldata).is_ok());
+
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+
+        assert_matches!(result, Err(ShielderCallErrors::DuplicatedNullifier(_)));
+    }
+
+    #[rstest]
+    fn fails_if_note_already_has_current_version(mut deployment: Deployment) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            TestToken::Native,
+            ZERO_MEMO_BYTES,
+        );
+        assert_eq!(calldata.old_note_version, contract_version().note_version);
+
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+
+        assert_matches!(result, Err(ShielderCallErrors::UnsupportedNoteVersion(_)));
+    }
+
+    #[rstest]
+    fn fails_if_old_note_version_is_newer_than_current(mut deployment: Deployment) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (mut calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            TestToken::Native,
+            ZERO_MEMO_BYTES,
+        );
+        calldata.old_note_version = contract_version().note_version + 1;
+
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+
+        assert_matches!(result, Err(ShielderCallErrors::UnsupportedNoteVersion(_)));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod claim;
 pub mod deposit;
+pub mod migration;
 pub mod new_account;
+pub mod transfer;
 pub mod withdraw;
+pub mod withdraw_batch;
@@ -5,7 +5,10 @@ use shielder_account::{
     call_data::{NewAccountCall, NewAccountCallExtra, NewAccountCallType},
     ShielderAccount, Token,
 };
-use shielder_contract::ShielderContract::{newAccountERC20Call, newAccountNativeCall};
+use shielder_contract::{
+    token_list::token_list_path,
+    ShielderContract::{newAccountERC20Call, newAccountNativeCall},
+};
 use shielder_setup::protocol_fee::compute_protocol_fee_from_gross;
 
 use crate::{
@@ -28,6 +31,8 @@ pub fn prepare_call(
     let protocol_fee_bps =
         get_protocol_deposit_fee_bps(deployment.contract_suite.shielder, &mut deployment.evm);
     let protocol_fee = compute_protocol_fee_from_gross(amount, protocol_fee_bps);
+    let token_list_path =
+        token_list_path(&deployment.token_list, token.address(deployment)).unwrap();
 
     shielder_account.prepare_call::<NewAccountCallType>(
         &params,
@@ -41,6 +46,7 @@ pub fn prepare_call(
             caller_address: Address::from_str(ACTOR_ADDRESS).unwrap(),
             protocol_fee,
             memo,
+            token_list_path,
         },
     )
 }
@@ -130,7 +136,7 @@ mod tests {
         deploy::{deployment, MEMO_BYTES, PROTOCOL_FEES, ZERO_MEMO_BYTES, ZERO_PROTOCOL_FEES},
         protocol_fee_receiver_balance_increased_by,
         protocol_fees::ProtocolFeesBps,
-        shielder::{actor_balance_decreased_by, Deployment},
+        shielder::{actor_balance_decreased_by, set_token_list, Deployment},
     };
 
     const GAS_CONSUMPTION_NATIVE: u64 = 1989104;
@@ -408,4 +414,28 @@ mod tests {
         );
         assert!(actor_balance_decreased_by(&deployment, token, U256::ZERO))
     }
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn fails_if_token_list_changed(mut deployment: Deployment, #[case] token: TestToken) {
+        let mut shielder_account = ShielderAccount::default();
+        let amount = U256::from(10);
+        let calldata = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            token,
+            amount,
+            ZERO_MEMO_BYTES,
+        );
+        set_token_list(&mut deployment, vec![]);
+
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+
+        assert_matches!(
+            result,
+            Err(ShielderCallErrors::NewAccountVerificationFailed(_))
+        );
+        assert!(actor_balance_decreased_by(&deployment, token, U256::ZERO))
+    }
 }
@@ -0,0 +1,318 @@
+use alloy_primitives::{Bytes, TxHash, U256};
+use shielder_account::{
+    call_data::{TransferCall, TransferCallType, TransferExtra},
+    ShielderAccount,
+};
+use shielder_circuits::{generate_keys, grumpkin, Fr, GrumpkinPointAffine};
+use shielder_contract::ShielderContract::transferCall;
+use shielder_setup::version::contract_version;
+use type_conversions::field_to_u256;
+
+use crate::{
+    shielder::{
+        deploy::{Deployment, ANONYMITY_REVOKER_PKEY},
+        invoke_shielder_call,
+        merkle::get_merkle_path,
+        CallResult,
+    },
+    TestToken,
+};
+
+/// Generate a random key pair of a transfer recipient.
+pub fn recipient_keys() -> (grumpkin::Fr, GrumpkinPointAffine<U256>) {
+    let (private_key, public_key) = generate_keys(&mut rand::thread_rng());
+    let public_key: GrumpkinPointAffine<Fr> = public_key.into();
+    (
+        private_key,
+        GrumpkinPointAffine::new(field_to_u256(public_key.x), field_to_u256(public_key.y)),
+    )
+}
+
+pub fn prepare_call(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    token: TestToken,
+    amount: U256,
+    recipient_tag: U256,
+    recipient_public_key: GrumpkinPointAffine<U256>,
+    memo: Bytes,
+) -> (TransferCall, U256) {
+    let note_index = shielder_account
+        .current_leaf_index()
+        .expect("No leaf index");
+
+    let (params, pk) = deployment.transfer_proving_params.clone();
+    let merkle_path = get_merkle_path(
+        deployment.contract_suite.shielder,
+        note_index,
+        &mut deployment.evm,
+    );
+
+    let calldata = shielder_account.prepare_call::<TransferCallType>(
+        &params,
+        &pk,
+        token.token(deployment),
+        amount,
+        &TransferExtra {
+            merkle_path,
+            recipient_tag,
+            recipient_public_key,
+            encryption_salt: U256::from(7),
+            anonymity_revoker_public_key: ANONYMITY_REVOKER_PKEY,
+            revoker_encryption_salt: U256::from(11),
+            contract_version: contract_version(),
+            chain_id: U256::from(1),
+            mac_salt: U256::ZERO,
+            memo,
+        },
+    );
+    (calldata, note_index)
+}
+
+pub fn invoke_call(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    calldata: &TransferCall,
+) -> CallResult {
+    let call_result = invoke_shielder_call(deployment, &transferCall::from(calldata.clone()), None);
+
+    match call_result {
+        Ok((events, success_result)) => {
+            assert!(events.len() == 1);
+            let event = events[0].clone();
+            shielder_account.register_action((TxHash::default(), event.clone()));
+            Ok((events, success_result))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use alloy_primitives::U256;
+    use rstest::rstest;
+    use shielder_account::{ShielderAccount, Token};
+    use shielder_contract::ShielderContract::{ShielderContractEvents, Transfer};
+    use shielder_setup::version::contract_version;
+
+    use crate::{
+        call_errors::ShielderCallErrors,
+        calls::{
+            new_account,
+            transfer::{invoke_call, prepare_call, recipient_keys},
+        },
+        deploy::{MEMO_BYTES, ZERO_MEMO_BYTES},
+        shielder::{
+            actor_balance_decreased_by,
+            deploy::{deployment, Deployment},
+            destination_balances_unchanged,
+        },
+        TestToken,
+    };
+
+    fn transfer_event(event: &ShielderContractEvents) -> &Transfer {
+        let ShielderContractEvents::Transfer(event) = event else {
+            panic!("Expected a Transfer event");
+        };
+        event
+    }
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn succeeds(mut deployment: Deployment, #[case] token: TestToken) {
+        let (private_key, public_key) = recipient_keys();
+        let recipient = ShielderAccount::new(U256::from(2), token.token(&deployment));
+        let mut sender = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(1),
+            U256::from(100),
+            MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, note_index) = prepare_call(
+            &mut deployment,
+            &mut sender,
+            token,
+            U256::from(30),
+            recipient.receiving_tag(),
+            public_key,
+            MEMO_BYTES,
+        );
+        let events = invoke_call(&mut deployment, &mut sender, &calldata)
+            .unwrap()
+            .0;
+
+        assert_eq!(
+            events,
+            vec![ShielderContractEvents::Transfer(Transfer {
+                contractVersion: contract_version().to_bytes(),
+                tokenAddress: token.address(&deployment),
+                newNote: calldata.new_note,
+                newNoteIndex: note_index + U256::from(1),
+                receipt: calldata.receipt,
+                receiptIndex: note_index + U256::from(2),
+                encryptedReceiptNonce: [
+                    calldata.encrypted_receipt_nonce_c1.x,
+                    calldata.encrypted_receipt_nonce_c1.y,
+                    calldata.encrypted_receipt_nonce_c2.x,
+                    calldata.encrypted_receipt_nonce_c2.y,
+                ],
+                encryptedValue: calldata.encrypted_value,
+                revokerEncryption: [
+                    calldata.revoker_encryption_c1.x,
+                    calldata.revoker_encryption_c1.y,
+                    calldata.revoker_encryption_c2.x,
+                    calldata.revoker_encryption_c2.y,
+                ],
+                macSalt: U256::ZERO,
+                macCommitment: calldata.mac_commitment,
+                memo: MEMO_BYTES,
+            })]
+        );
+        assert_eq!(sender.shielded_amount, U256::from(70));
+        assert_eq!(sender.note(sender.token), Some(calldata.new_note));
+
+        let received = recipient
+            .received_transfer(private_key, transfer_event(&events[0]))
+            .unwrap();
+        assert_eq!(received.value, U256::from(30));
+        assert_eq!(received.receipt, calldata.receipt);
+        assert_eq!(received.receipt_index, note_index + U256::from(2));
+
+        assert!(actor_balance_decreased_by(
+            &deployment,
+            token,
+            U256::from(100)
+        ));
+        assert!(destination_balances_unchanged(&deployment, token))
+    }
+
+    #[rstest]
+    fn only_recipient_can_receive_transfer(mut deployment: Deployment) {
+        let (private_key, public_key) = recipient_keys();
+        let (other_private_key, _) = recipient_keys();
+        let recipient = ShielderAccount::new(U256::from(2), Token::Native);
+        let other_account = ShielderAccount::new(U256::from(3), Token::Native);
+        let mut sender = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut sender,
+            TestToken::Native,
+            U256::from(30),
+            recipient.receiving_tag(),
+            public_key,
+            ZERO_MEMO_BYTES,
+        );
+        let events = invoke_call(&mut deployment, &mut sender, &calldata)
+            .unwrap()
+            .0;
+        let event = transfer_event(&events[0]);
+
+        assert!(recipient.received_transfer(private_key, event).is_some());
+        assert_eq!(recipient.received_transfer(other_private_key, event), None);
+        assert_eq!(other_account.received_transfer(private_key, event), None);
+    }
+
+    #[rstest]
+    fn fails_if_proof_incorrect(mut deployment: Deployment) {
+        let (_, public_key) = recipient_keys();
+        let mut sender = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (mut calldata, _) = prepare_call(
+            &mut deployment,
+            &mut sender,
+            TestToken::Native,
+            U256::from(30),
+            U256::from(2),
+            public_key,
+            ZERO_MEMO_BYTES,
+        );
+        calldata.encrypted_value = calldata.encrypted_value.wrapping_add(U256::from(1));
+
+        let result = invoke_call(&mut deployment, &mut sender, &calldata);
+
+        assert_matches!(
+            result,
+            Err(ShielderCallErrors::TransferVerificationFailed(_))
+        );
+    }
+
+    #[rstest]
+    fn fails_if_revoker_encryption_incorrect(mut deployment: Deployment) {
+        let (_, public_key) = recipient_keys();
+        let mut sender = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (mut calldata, _) = prepare_call(
+            &mut deployment,
+            &mut sender,
+            TestToken::Native,
+            U256::from(30),
+            U256::from(2),
+            public_key,
+            ZERO_MEMO_BYTES,
+        );
+        calldata.revoker_encryption_c2 = calldata.encrypted_receipt_nonce_c2;
+
+        let result = invoke_call(&mut deployment, &mut sender, &calldata);
+
+        assert_matches!(
+            result,
+            Err(ShielderCallErrors::TransferVerificationFailed(_))
+        );
+    }
+
+    #[rstest]
+    fn cannot_use_same_note_twice(mut deployment: Deployment) {
+        let (_, public_key) = recipient_keys();
+        let mut sender = new_account::create_account_and_call(
+            &mut deployment,
+            TestToken::Native,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut sender,
+            TestToken::Native,
+            U256::from(30),
+            U256::from(2),
+            public_key,
+            ZERO_MEMO_BYTES,
+        );
+        assert!(invoke_call(&mut deployment, &mut sender, &calldata).is_ok());
+
+        let result = invoke_call(&mut deployment, &mut sender, &calldata);
+
+        assert_matches!(result, Err(ShielderCallErrors::DuplicatedNullifier(_)));
+    }
+}
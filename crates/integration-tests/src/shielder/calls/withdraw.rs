@@ -2,7 +2,10 @@ use std::str::FromStr;
 
 use alloy_primitives::{Address, Bytes, TxHash, U256};
 use shielder_account::{
-    call_data::{WithdrawCall, WithdrawCallType, WithdrawExtra},
+    call_data::{
+        AssociationSetPath, CallType, WithdrawCall, WithdrawCallType, WithdrawExtra,
+        WithdrawWitness,
+    },
     ShielderAccount, Token,
 };
 use shielder_contract::ShielderContract::{withdrawERC20Call, withdrawNativeCall};
@@ -27,6 +30,7 @@ pub struct PrepareCallArgs {
     relayer_fee: U256,
     pocket_money: U256,
     memo: Bytes,
+    association_set: Option<AssociationSetPath>,
 }
 
 pub fn prepare_args(
@@ -44,6 +48,7 @@ pub fn prepare_args(
         relayer_fee,
         pocket_money,
         memo,
+        association_set: None,
     }
 }
 
@@ -52,12 +57,41 @@ pub fn prepare_call(
     shielder_account: &mut ShielderAccount,
     args: PrepareCallArgs,
 ) -> (WithdrawCall, U256) {
+    let (params, pk) = deployment.withdraw_proving_params.clone();
+    let (token, amount, extra, note_index) = prepare_extra(deployment, shielder_account, args);
+
+    let calldata =
+        shielder_account.prepare_call::<WithdrawCallType>(&params, &pk, token, amount, &extra);
+
+    (calldata, note_index)
+}
+
+/// Like `prepare_call`, but instead of proving the withdrawal, return its witness (to be proven
+/// in a batch). The proof in the returned call is empty.
+pub fn prepare_call_with_witness(
+    deployment: &mut Deployment,
+    shielder_account: &mut ShielderAccount,
+    args: PrepareCallArgs,
+) -> (WithdrawCall, WithdrawWitness) {
+    let (token, amount, extra, _) = prepare_extra(deployment, shielder_account, args);
+
+    let knowledge =
+        WithdrawCallType::prepare_prover_knowledge(shielder_account, token, amount, &extra);
+    let calldata = WithdrawCallType::prepare_call_data(&knowledge, vec![], &extra);
+
+    (calldata, WithdrawWitness::from(&knowledge))
+}
+
+fn prepare_extra(
+    deployment: &mut Deployment,
+    shielder_account: &ShielderAccount,
+    args: PrepareCallArgs,
+) -> (Token, U256, WithdrawExtra, U256) {
     let amount = U256::from(args.amount);
     let note_index = shielder_account
         .current_leaf_index()
         .expect("No leaf index");
 
-    let (params, pk) = deployment.withdraw_proving_params.clone();
     let merkle_path = get_merkle_path(
         deployment.contract_suite.shielder,
         note_index,
@@ -68,26 +102,21 @@ pub fn prepare_call(
         get_protocol_withdraw_fee_bps(deployment.contract_suite.shielder, &mut deployment.evm);
     let protocol_fee = compute_protocol_fee_from_gross(amount, protocol_fee_bps);
 
-    let calldata = shielder_account.prepare_call::<WithdrawCallType>(
-        &params,
-        &pk,
-        args.token.token(deployment),
-        amount,
-        &WithdrawExtra {
-            merkle_path,
-            to: args.withdraw_address,
-            relayer_address: args.relayer_address,
-            relayer_fee: args.relayer_fee,
-            contract_version: contract_version(),
-            chain_id: U256::from(1),
-            mac_salt: U256::ZERO,
-            pocket_money: args.pocket_money,
-            protocol_fee,
-            memo: args.memo,
-        },
-    );
+    let extra = WithdrawExtra {
+        merkle_path,
+        to: args.withdraw_address,
+        relayer_address: args.relayer_address,
+        relayer_fee: args.relayer_fee,
+        contract_version: contract_version(),
+        chain_id: U256::from(1),
+        mac_salt: U256::ZERO,
+        pocket_money: args.pocket_money,
+        protocol_fee,
+        memo: args.memo,
+        association_set: args.association_set,
+    };
 
-    (calldata, note_index)
+    (args.token.token(deployment), amount, extra, note_index)
 }
 
 pub fn invoke_call(
@@ -126,10 +155,14 @@ mod tests {
     use evm_utils::SuccessResult;
     use halo2_proofs::halo2curves::ff::PrimeField;
     use rstest::rstest;
-    use shielder_account::{call_data::WithdrawCall, ShielderAccount};
+    use shielder_account::{
+        call_data::{AssociationSetPath, WithdrawCall},
+        ShielderAccount,
+    };
     use shielder_circuits::Fr;
-    use shielder_contract::ShielderContract::{
-        ShielderContractEvents, Withdraw, WrongContractVersion,
+    use shielder_contract::{
+        AssociationSet,
+        ShielderContract::{ShielderContractEvents, Withdraw, WrongContractVersion},
     };
     use shielder_setup::{
         protocol_fee::compute_protocol_fee_from_gross, version::contract_version,
@@ -267,6 +300,7 @@ mod tests {
                 fee: relayer_fee,
                 macSalt: U256::ZERO,
                 macCommitment: withdraw_calldata.mac_commitment,
+                associationSetRoot: U256::ZERO,
                 pocketMoney: pocket_money,
                 protocolFee: withdraw_protocol_fee,
                 memo,
@@ -394,6 +428,7 @@ mod tests {
                 fee: relayer_fee,
                 macSalt: U256::ZERO,
                 macCommitment: withdraw_calldata.mac_commitment,
+                associationSetRoot: U256::ZERO,
                 pocketMoney: pocket_money,
                 protocolFee: withdraw_protocol_fee,
                 memo,
@@ -439,6 +474,108 @@ mod tests {
         ));
     }
 
+    fn association_set_with(shielder_account: &ShielderAccount) -> AssociationSetPath {
+        let set = AssociationSet::new(
+            [1, 2, 3]
+                .map(U256::from)
+                .into_iter()
+                .chain([shielder_account.association_set_leaf()]),
+        )
+        .unwrap();
+        let (root, path) = set
+            .merkle_path(shielder_account.association_set_leaf())
+            .unwrap();
+        AssociationSetPath { root, path }
+    }
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn succeeds_with_association_set(mut deployment: Deployment, #[case] token: TestToken) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(1),
+            U256::from(20),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+        let association_set = association_set_with(&shielder_account);
+        let association_set_root = association_set.root;
+
+        let (calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            PrepareCallArgs {
+                association_set: Some(association_set),
+                ..prepare_args(
+                    token,
+                    U256::from(5),
+                    U256::from(1),
+                    U256::ZERO,
+                    ZERO_MEMO_BYTES,
+                )
+            },
+        );
+        assert_eq!(calldata.association_set_root, association_set_root);
+
+        let events = invoke_call(&mut deployment, &mut shielder_account, &calldata)
+            .unwrap()
+            .0;
+
+        assert_matches!(
+            &events[..],
+            [ShielderContractEvents::Withdraw(Withdraw { associationSetRoot: root, .. })]
+                if *root == association_set_root
+        );
+        assert!(recipient_balance_increased_by(
+            &deployment,
+            token,
+            U256::from(4)
+        ));
+    }
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn fails_if_association_set_root_incorrect(
+        mut deployment: Deployment,
+        #[case] token: TestToken,
+    ) {
+        let mut shielder_account = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(1),
+            U256::from(20),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let (mut calldata, _) = prepare_call(
+            &mut deployment,
+            &mut shielder_account,
+            PrepareCallArgs {
+                association_set: Some(association_set_with(&shielder_account)),
+                ..prepare_args(
+                    token,
+                    U256::from(5),
+                    U256::from(1),
+                    U256::ZERO,
+                    ZERO_MEMO_BYTES,
+                )
+            },
+        );
+        calldata.association_set_root = calldata.association_set_root.wrapping_add(U256::from(1));
+
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+
+        assert_matches!(
+            result,
+            Err(ShielderCallErrors::WithdrawVerificationFailed(_))
+        );
+        assert!(destination_balances_unchanged(&deployment, token))
+    }
+
     #[rstest]
     #[case::native(TestToken::Native)]
     #[case::erc20(TestToken::ERC20)]
@@ -648,6 +785,7 @@ mod tests {
             proof: Bytes::from(vec![]),
             mac_salt: U256::ZERO,
             mac_commitment: U256::ZERO,
+            association_set_root: U256::ZERO,
             pocket_money: U256::ZERO,
             memo: ZERO_MEMO_BYTES,
         };
@@ -728,6 +866,7 @@ mod tests {
             proof: Bytes::from(vec![]),
             mac_salt: U256::ZERO,
             mac_commitment: U256::ZERO,
+            association_set_root: U256::ZERO,
             pocket_money: U256::ZERO,
             memo: ZERO_MEMO_BYTES,
         };
@@ -827,6 +966,11 @@ mod tests {
         assert_matches!(result, Err(ShielderCallErrors::NotAFieldElement(_)));
         mem::swap(&mut calldata.new_note, &mut swap_value);
 
+        mem::swap(&mut calldata.association_set_root, &mut swap_value);
+        let result = invoke_call(&mut deployment, &mut shielder_account, &calldata);
+        assert_matches!(result, Err(ShielderCallErrors::NotAFieldElement(_)));
+        mem::swap(&mut calldata.association_set_root, &mut swap_value);
+
         assert!(actor_balance_decreased_by(
             &deployment,
             token,
@@ -0,0 +1,185 @@
+use alloy_primitives::TxHash;
+use shielder_account::{
+    call_data::{prove_withdraw_batch, BatchWithdrawCall, WithdrawCall, WithdrawWitness},
+    ShielderAccount,
+};
+use shielder_circuits::consts::WITHDRAW_BATCH_SIZE;
+use shielder_contract::ShielderContract::withdrawBatchCall;
+
+use crate::shielder::{deploy::Deployment, invoke_shielder_call, CallResult};
+
+pub fn prepare_call(
+    deployment: &Deployment,
+    withdrawals: [(WithdrawCall, WithdrawWitness); WITHDRAW_BATCH_SIZE],
+) -> BatchWithdrawCall {
+    let (params, pk) = &deployment.batch_withdraw_proving_params;
+    prove_withdraw_batch(params, pk, withdrawals).expect("Withdrawals should be batchable")
+}
+
+/// Invoke `calldata`. The `i`-th event is registered in the `i`-th account of `shielder_accounts`.
+pub fn invoke_call(
+    deployment: &mut Deployment,
+    shielder_accounts: [&mut ShielderAccount; WITHDRAW_BATCH_SIZE],
+    calldata: &BatchWithdrawCall,
+) -> CallResult {
+    let calldata: withdrawBatchCall = calldata.clone().into();
+    let (events, success_result) = invoke_shielder_call(deployment, &calldata, None)?;
+
+    assert_eq!(events.len(), WITHDRAW_BATCH_SIZE);
+    for (account, event) in shielder_accounts.into_iter().zip(events.iter()) {
+        account.register_action((TxHash::default(), event.clone()));
+    }
+    Ok((events, success_result))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{assert_matches::assert_matches, str::FromStr};
+
+    use alloy_primitives::{Address, U256};
+    use rstest::rstest;
+    use shielder_contract::ShielderContract::{ShielderContractEvents, Withdraw};
+    use shielder_setup::version::contract_version;
+
+    use crate::{
+        call_errors::ShielderCallErrors,
+        calls::{
+            new_account,
+            withdraw::{prepare_args, prepare_call_with_witness},
+            withdraw_batch::{invoke_call, prepare_call},
+        },
+        deploy::{deployment, Deployment, RECIPIENT_ADDRESS, RELAYER_ADDRESS, ZERO_MEMO_BYTES},
+        shielder::{recipient_balance_increased_by, relayer_balance_increased_by},
+        TestToken,
+    };
+
+    #[rstest]
+    #[case::native(TestToken::Native)]
+    #[case::erc20(TestToken::ERC20)]
+    fn succeeds(mut deployment: Deployment, #[case] token: TestToken) {
+        let mut first = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+        let mut second = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(2),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+
+        let relayer_fee = U256::from(1);
+        let first_withdrawal = prepare_call_with_witness(
+            &mut deployment,
+            &mut first,
+            prepare_args(
+                token,
+                U256::from(10),
+                relayer_fee,
+                U256::ZERO,
+                ZERO_MEMO_BYTES,
+            ),
+        );
+        let second_withdrawal = prepare_call_with_witness(
+            &mut deployment,
+            &mut second,
+            prepare_args(
+                token,
+                U256::from(20),
+                relayer_fee,
+                U256::ZERO,
+                ZERO_MEMO_BYTES,
+            ),
+        );
+
+        let calldata = prepare_call(&deployment, [first_withdrawal, second_withdrawal]);
+        let events = invoke_call(&mut deployment, [&mut first, &mut second], &calldata)
+            .unwrap()
+            .0;
+
+        let expected_events = calldata
+            .withdrawals
+            .iter()
+            .enumerate()
+            .map(|(i, withdrawal)| {
+                ShielderContractEvents::Withdraw(Withdraw {
+                    contractVersion: contract_version().to_bytes(),
+                    tokenAddress: token.address(&deployment),
+                    amount: withdrawal.amount,
+                    withdrawalAddress: Address::from_str(RECIPIENT_ADDRESS).unwrap(),
+                    newNote: withdrawal.new_note,
+                    relayerAddress: Address::from_str(RELAYER_ADDRESS).unwrap(),
+                    // Notes 0 and 1 were created by the new account calls.
+                    newNoteIndex: U256::from(2 + i),
+                    fee: relayer_fee,
+                    macSalt: U256::ZERO,
+                    macCommitment: withdrawal.mac_commitment,
+                    associationSetRoot: U256::ZERO,
+                    pocketMoney: U256::ZERO,
+                    protocolFee: U256::ZERO,
+                    memo: ZERO_MEMO_BYTES,
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, expected_events);
+
+        assert!(recipient_balance_increased_by(
+            &deployment,
+            token,
+            U256::from(28)
+        ));
+        assert!(relayer_balance_increased_by(
+            &deployment,
+            token,
+            U256::from(2)
+        ));
+        assert_eq!(first.shielded_amount, U256::from(90));
+        assert_eq!(second.shielded_amount, U256::from(80));
+    }
+
+    #[rstest]
+    fn fails_if_nullifier_is_repeated_within_batch(mut deployment: Deployment) {
+        let token = TestToken::Native;
+        let mut account = new_account::create_account_and_call(
+            &mut deployment,
+            token,
+            U256::from(1),
+            U256::from(100),
+            ZERO_MEMO_BYTES,
+        )
+        .unwrap();
+        let mut account_copy = account.clone();
+
+        let withdrawal = prepare_call_with_witness(
+            &mut deployment,
+            &mut account,
+            prepare_args(
+                token,
+                U256::from(10),
+                U256::from(1),
+                U256::ZERO,
+                ZERO_MEMO_BYTES,
+            ),
+        );
+
+        let calldata = prepare_call(&deployment, [withdrawal.clone(), withdrawal]);
+        let result = invoke_call(
+            &mut deployment,
+            [&mut account, &mut account_copy],
+            &calldata,
+        );
+
+        assert_matches!(result, Err(ShielderCallErrors::DuplicatedNullifier(_)));
+        assert!(recipient_balance_increased_by(
+            &deployment,
+            token,
+            U256::ZERO
+        ));
+    }
+}
@@ -8,15 +8,18 @@ use evm_utils::{
     EvmRunner,
 };
 use rstest::fixture;
-use shielder_circuits::GrumpkinPointAffine;
-use shielder_contract::ShielderContract::initializeCall;
+use shielder_circuits::{GrumpkinPointAffine, TokenList};
+use shielder_contract::{token_list::build_token_list, ShielderContract::initializeCall};
+use type_conversions::field_to_u256;
 
 use crate::{
     deploy_contract,
     erc20::TestERC20,
     protocol_fees::ProtocolFeesBps,
     proving_utils::{
-        deposit_proving_params, new_account_proving_params, withdraw_proving_params, ProvingParams,
+        batch_withdraw_proving_params, claim_proving_params, deposit_proving_params,
+        migration_proving_params, new_account_proving_params, transfer_proving_params,
+        withdraw_proving_params, ProvingParams,
     },
     read_contract,
     shielder::{
@@ -123,14 +126,23 @@ const POSEIDON2_LIB_PLACEHOLDER: &str = "__$fa7e1b6d9a16949b5fb8159594c1e0b34c$_
 const NEW_ACCOUNT_VERIFIER_LIB_PLACEHOLDER: &str = "__$96275be2429eed9b26a54836ed89b224a2$__";
 const DEPOSIT_VERIFIER_LIB_PLACEHOLDER: &str = "__$d586e7da5a0e0b714a5d44ed4e0f6a624d$__";
 const WITHDRAW_VERIFIER_LIB_PLACEHOLDER: &str = "__$06bb88608c3ade14b496e12c6067f182f6$__";
+const BATCH_WITHDRAW_VERIFIER_LIB_PLACEHOLDER: &str = "__$6b4f27e9fcceb36aa379e841087c44b11d$__";
+const TRANSFER_VERIFIER_LIB_PLACEHOLDER: &str = "__$57687768f83138849521851d346fa026e2$__";
+const CLAIM_VERIFIER_LIB_PLACEHOLDER: &str = "__$589da79f3b949a19635f9f72cd4a90dca2$__";
+const MIGRATION_VERIFIER_LIB_PLACEHOLDER: &str = "__$f20fabe7acdf6827c7e9902db65e38e019$__";
 
 pub struct Deployment {
     pub evm: EvmRunner,
     pub test_erc20: TestERC20,
     pub contract_suite: ShielderContractSuite,
+    pub token_list: TokenList,
     pub new_account_proving_params: ProvingParams,
     pub deposit_proving_params: ProvingParams,
     pub withdraw_proving_params: ProvingParams,
+    pub batch_withdraw_proving_params: ProvingParams,
+    pub transfer_proving_params: ProvingParams,
+    pub claim_proving_params: ProvingParams,
+    pub migration_proving_params: ProvingParams,
 }
 
 impl Deployment {
@@ -154,6 +166,10 @@ pub fn deployment(
     new_account_proving_params: &ProvingParams,
     deposit_proving_params: &ProvingParams,
     withdraw_proving_params: &ProvingParams,
+    batch_withdraw_proving_params: &ProvingParams,
+    transfer_proving_params: &ProvingParams,
+    claim_proving_params: &ProvingParams,
+    migration_proving_params: &ProvingParams,
 ) -> Deployment {
     let mut evm = EvmRunner::aleph_evm();
 
@@ -212,6 +228,7 @@ pub fn deployment(
         Some(reverting_bytecode),
     );
 
+    let token_list = build_token_list(&[test_erc20.contract_address]).unwrap();
     let shielder_address = deploy_shielder_contract(
         &mut evm,
         owner,
@@ -219,6 +236,7 @@ pub fn deployment(
         U256::ZERO,
         U256::ZERO,
         Address::from_str(PROTOCOL_FEE_RECEIVER_ADDRESS).unwrap(),
+        vec![test_erc20.contract_address],
     );
     unpause_shielder(shielder_address, &mut evm);
 
@@ -228,20 +246,54 @@ pub fn deployment(
         contract_suite: ShielderContractSuite {
             shielder: shielder_address,
         },
+        token_list,
         new_account_proving_params: new_account_proving_params.clone(),
         deposit_proving_params: deposit_proving_params.clone(),
         withdraw_proving_params: withdraw_proving_params.clone(),
+        batch_withdraw_proving_params: batch_withdraw_proving_params.clone(),
+        transfer_proving_params: transfer_proving_params.clone(),
+        claim_proving_params: claim_proving_params.clone(),
+        migration_proving_params: migration_proving_params.clone(),
     }
 }
 
-/// Deploys the Shielder implementation contract.
+/// Deploy whole Shielder suite and upgrade the Shielder contract to `ShielderNoteVersionHarness`
+/// (see `contracts/ShielderNoteVersionHarness.sol` of this crate), whose note version is newer than
+/// the current one. Notes created by the suite can then be migrated.
+#[fixture]
+pub fn note_version_harness_deployment(mut deployment: Deployment) -> Deployment {
+    let implementation_address = deploy_shielder_implementation(
+        &mut deployment.evm,
+        include_str!("../../contracts/ShielderNoteVersionHarness.sol"),
+        "ShielderNoteVersionHarness",
+    );
+    deployment
+        .evm
+        .call(
+            deployment.contract_suite.shielder,
+            erc1967proxy::upgradeToAndCallCall {
+                newImplementation: implementation_address,
+                data: Bytes::new(),
+            }
+            .abi_encode(),
+            Some(Address::from_str(DEPLOYER_ADDRESS).unwrap()),
+            None,
+        )
+        .expect("Failed to upgrade Shielder contract");
+    deployment
+}
+
+/// Deploys the Shielder implementation contract (`contract_name` from `solidity_code`).
 ///
 /// This requires more steps than deploying a regular contract because Solc leaves placeholders
 /// in the bytecode, which has to be replaced with a deployed Poseidon2 contract address.
-fn deploy_shielder_implementation(evm: &mut EvmRunner) -> Address {
+fn deploy_shielder_implementation(
+    evm: &mut EvmRunner,
+    solidity_code: &str,
+    contract_name: &str,
+) -> Address {
     // 1. Compile the Shielder implementation contract. It will contain placeholders.
-    let solidity_code = read_contract("Shielder.sol");
-    let implementation_bytecode = source_to_bytecode(solidity_code, "Shielder", false);
+    let implementation_bytecode = source_to_bytecode(solidity_code, contract_name, false);
 
     // 2. Compile and deploy auxiliary contracts.
     let poseidon2_address =
@@ -280,6 +332,38 @@ fn deploy_shielder_implementation(evm: &mut EvmRunner) -> Address {
                 .to_string()
                 .strip_prefix("0x")
                 .unwrap(),
+        )
+        .replace(
+            BATCH_WITHDRAW_VERIFIER_LIB_PLACEHOLDER,
+            verifiers
+                .batch_withdraw_verifier
+                .to_string()
+                .strip_prefix("0x")
+                .unwrap(),
+        )
+        .replace(
+            TRANSFER_VERIFIER_LIB_PLACEHOLDER,
+            verifiers
+                .transfer_verifier
+                .to_string()
+                .strip_prefix("0x")
+                .unwrap(),
+        )
+        .replace(
+            CLAIM_VERIFIER_LIB_PLACEHOLDER,
+            verifiers
+                .claim_verifier
+                .to_string()
+                .strip_prefix("0x")
+                .unwrap(),
+        )
+        .replace(
+            MIGRATION_VERIFIER_LIB_PLACEHOLDER,
+            verifiers
+                .migration_verifier
+                .to_string()
+                .strip_prefix("0x")
+                .unwrap(),
         );
     let ready_bytecode = hex::decode(with_linked_libs).unwrap();
 
@@ -296,8 +380,11 @@ fn deploy_shielder_contract(
     protocol_deposit_fee_bps: U256,
     protocol_withdraw_fee_bps: U256,
     protocol_fee_receiver: Address,
+    token_list: Vec<Address>,
 ) -> Address {
-    let implementation_address = deploy_shielder_implementation(evm);
+    let implementation_address =
+        deploy_shielder_implementation(evm, &read_contract("Shielder.sol"), "Shielder");
+    let token_list_root = field_to_u256(build_token_list(&token_list).unwrap().root());
     let initialization_data = initializeCall {
         initialOwner: owner,
         _anonymityRevokerPublicKeyX: ar_key.x,
@@ -306,6 +393,8 @@ fn deploy_shielder_contract(
         _protocolDepositFeeBps: protocol_deposit_fee_bps,
         _protocolWithdrawFeeBps: protocol_withdraw_fee_bps,
         _protocolFeeReceiver: protocol_fee_receiver,
+        _tokenList: token_list,
+        _tokenListRoot: token_list_root,
     }
     .abi_encode();
 
@@ -367,6 +456,7 @@ mod tests {
             U256::ZERO,
             U256::ZERO,
             Address::from_str(PROTOCOL_FEE_RECEIVER_ADDRESS).unwrap(),
+            vec![],
         );
     }
 
@@ -397,6 +487,7 @@ mod tests {
             U256::ZERO,
             U256::ZERO,
             Address::from_str(PROTOCOL_FEE_RECEIVER_ADDRESS).unwrap(),
+            vec![],
         );
     }
 
@@ -432,6 +523,7 @@ mod tests {
             U256::ZERO,
             U256::ZERO,
             Address::from_str(PROTOCOL_FEE_RECEIVER_ADDRESS).unwrap(),
+            vec![],
         );
     }
 }
@@ -5,4 +5,7 @@ pub const ERC_1967_PROXY_BYTECODE: &str =
 
 sol! {
     constructor(address implementation, bytes memory _data) payable;
+
+    /// UUPS upgrade, implemented by the Shielder contract behind the proxy.
+    function upgradeToAndCall(address newImplementation, bytes memory data) external payable;
 }
@@ -12,10 +12,14 @@ use deploy::{
 };
 use evm_utils::{EvmRunner, EvmRunnerError, SuccessResult};
 use shielder_account::Token;
-use shielder_contract::ShielderContract::{
-    setProtocolDepositFeeBpsCall, setProtocolWithdrawFeeBpsCall, unpauseCall,
-    ShielderContractEvents,
+use shielder_contract::{
+    token_list::build_token_list,
+    ShielderContract::{
+        setProtocolDepositFeeBpsCall, setProtocolWithdrawFeeBpsCall, setTokenListCall, unpauseCall,
+        ShielderContractEvents,
+    },
 };
+use type_conversions::field_to_u256;
 
 pub mod address_conversion;
 pub mod ar_pubkey;
@@ -58,6 +62,25 @@ fn set_protocol_withdraw_fee(shielder: Address, protocol_fee: U256, evm: &mut Ev
     .expect("Call failed");
 }
 
+/// Replace the list of allowed tokens (both in the contract and in `deployment`).
+pub fn set_token_list(deployment: &mut Deployment, tokens: Vec<Address>) {
+    let token_list = build_token_list(&tokens).unwrap();
+    deployment
+        .evm
+        .call(
+            deployment.contract_suite.shielder,
+            setTokenListCall {
+                newTokenList: tokens,
+                newTokenListRoot: field_to_u256(token_list.root()),
+            }
+            .abi_encode(),
+            Some(Address::from_str(DEPLOYER_ADDRESS).unwrap()),
+            None,
+        )
+        .expect("Call failed");
+    deployment.token_list = token_list;
+}
+
 type CallResult = Result<(Vec<ShielderContractEvents>, SuccessResult), ShielderCallErrors>;
 
 // Calls Shielder. If successful, returns *just the events emitted by the Shielder contract*,
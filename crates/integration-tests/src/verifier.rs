@@ -10,6 +10,10 @@ pub struct VerificationContracts {
     pub new_account_verifier: Address,
     pub deposit_verifier: Address,
     pub withdraw_verifier: Address,
+    pub batch_withdraw_verifier: Address,
+    pub transfer_verifier: Address,
+    pub claim_verifier: Address,
+    pub migration_verifier: Address,
 }
 
 pub fn deploy_verifiers(evm: &mut EvmRunner) -> VerificationContracts {
@@ -17,11 +21,20 @@ pub fn deploy_verifiers(evm: &mut EvmRunner) -> VerificationContracts {
         deploy_contract("NewAccountVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
     let deposit_verifier = deploy_contract("DepositVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
     let withdraw_verifier = deploy_contract("WithdrawVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
+    let batch_withdraw_verifier =
+        deploy_contract("BatchWithdrawVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
+    let transfer_verifier = deploy_contract("TransferVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
+    let claim_verifier = deploy_contract("ClaimVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
+    let migration_verifier = deploy_contract("MigrationVerifier.sol", VERIFIER_CONTRACT_NAME, evm);
 
     VerificationContracts {
         new_account_verifier,
         deposit_verifier,
         withdraw_verifier,
+        batch_withdraw_verifier,
+        transfer_verifier,
+        claim_verifier,
+        migration_verifier,
     }
 }
 
@@ -32,8 +45,9 @@ mod tests {
     use evm_utils::EvmRunner;
     use halo2_solidity_verifier::verifier_contract;
     use shielder_circuits::{
-        deposit::DepositProverKnowledge, new_account::NewAccountProverKnowledge,
-        withdraw::WithdrawProverKnowledge, Fr,
+        claim::ClaimProverKnowledge, deposit::DepositProverKnowledge,
+        migration::MigrationProverKnowledge, new_account::NewAccountProverKnowledge,
+        transfer::TransferProverKnowledge, withdraw::WithdrawProverKnowledge, Fr,
     };
 
     use super::deploy_verifiers;
@@ -100,6 +114,48 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn transfer_contract_verification_works() {
+        let mut evm = EvmRunner::aleph_evm();
+        let verification_contracts = deploy_verifiers(&mut evm);
+
+        let (proof, pub_input) = proving_utils::prepare_proof::<TransferProverKnowledge<Fr>>();
+        assert!(verify_with_contract(
+            proof,
+            pub_input,
+            verification_contracts.transfer_verifier,
+            &mut evm,
+        ));
+    }
+
+    #[test]
+    fn claim_contract_verification_works() {
+        let mut evm = EvmRunner::aleph_evm();
+        let verification_contracts = deploy_verifiers(&mut evm);
+
+        let (proof, pub_input) = proving_utils::prepare_proof::<ClaimProverKnowledge<Fr>>();
+        assert!(verify_with_contract(
+            proof,
+            pub_input,
+            verification_contracts.claim_verifier,
+            &mut evm,
+        ));
+    }
+
+    #[test]
+    fn migration_contract_verification_works() {
+        let mut evm = EvmRunner::aleph_evm();
+        let verification_contracts = deploy_verifiers(&mut evm);
+
+        let (proof, pub_input) = proving_utils::prepare_proof::<MigrationProverKnowledge<Fr>>();
+        assert!(verify_with_contract(
+            proof,
+            pub_input,
+            verification_contracts.migration_verifier,
+            &mut evm,
+        ));
+    }
+
     // Should trigger an early return in `Halo2Verifier`.
     #[test]
     fn fails_on_empty_proof() {
@@ -0,0 +1,103 @@
+"""Generates a synthetic Nitro attestation document used by the unit tests.
+
+The document mimics the structure of the documents produced by the NSM driver: a COSE_Sign1
+structure signed (ES384) by a leaf certificate, chained to a self-signed test root through an
+intermediate certificate. Run from this directory: `python3 generate.py`.
+"""
+
+import datetime
+import hashlib
+import struct
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.hazmat.primitives.asymmetric.utils import decode_dss_signature
+from cryptography.x509.oid import NameOID
+
+NOT_BEFORE = datetime.datetime(2025, 1, 1, tzinfo=datetime.timezone.utc)
+NOT_AFTER = datetime.datetime(2035, 1, 1, tzinfo=datetime.timezone.utc)
+TIMESTAMP_MS = 1_760_000_000_000
+
+
+def cbor_head(major, value):
+    if value < 24:
+        return bytes([major << 5 | value])
+    if value < 2**8:
+        return bytes([major << 5 | 24, value])
+    if value < 2**16:
+        return bytes([major << 5 | 25]) + struct.pack(">H", value)
+    if value < 2**32:
+        return bytes([major << 5 | 26]) + struct.pack(">I", value)
+    return bytes([major << 5 | 27]) + struct.pack(">Q", value)
+
+
+def cbor(value):
+    if value is None:
+        return b"\xf6"
+    if isinstance(value, int):
+        return cbor_head(0, value) if value >= 0 else cbor_head(1, -1 - value)
+    if isinstance(value, bytes):
+        return cbor_head(2, len(value)) + value
+    if isinstance(value, str):
+        encoded = value.encode()
+        return cbor_head(3, len(encoded)) + encoded
+    if isinstance(value, list):
+        return cbor_head(4, len(value)) + b"".join(cbor(v) for v in value)
+    if isinstance(value, dict):
+        return cbor_head(5, len(value)) + b"".join(cbor(k) + cbor(v) for k, v in value.items())
+    raise TypeError(value)
+
+
+def certificate(common_name, key, issuer_name, issuer_key, is_ca):
+    name = x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, common_name)])
+    builder = (
+        x509.CertificateBuilder()
+        .subject_name(name)
+        .issuer_name(issuer_name or name)
+        .public_key(key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(NOT_BEFORE)
+        .not_valid_after(NOT_AFTER)
+        .add_extension(x509.BasicConstraints(ca=is_ca, path_length=None), critical=True)
+    )
+    return builder.sign(issuer_key, hashes.SHA384())
+
+
+def der(cert):
+    return cert.public_bytes(serialization.Encoding.DER)
+
+
+root_key = ec.generate_private_key(ec.SECP384R1())
+root = certificate("test-nitro-root", root_key, None, root_key, True)
+intermediate_key = ec.generate_private_key(ec.SECP384R1())
+intermediate = certificate("test-nitro-intermediate", intermediate_key, root.subject, root_key, True)
+leaf_key = ec.generate_private_key(ec.SECP384R1())
+leaf = certificate("test-nitro-enclave", leaf_key, intermediate.subject, intermediate_key, False)
+
+public_key = bytes(range(65))
+payload = cbor(
+    {
+        "module_id": "i-0123456789abcdef0-enc0123456789abcdef",
+        "digest": "SHA384",
+        "timestamp": TIMESTAMP_MS,
+        "pcrs": {i: bytes([i + 1]) * 48 if i < 3 else bytes(48) for i in range(16)},
+        "certificate": der(leaf),
+        "cabundle": [der(root), der(intermediate)],
+        "public_key": public_key,
+        "user_data": None,
+        "nonce": None,
+    }
+)
+
+protected = cbor({1: -35})  # alg: ES384
+sig_structure = cbor(["Signature1", protected, b"", payload])
+r, s = decode_dss_signature(leaf_key.sign(sig_structure, ec.ECDSA(hashes.SHA384())))
+signature = r.to_bytes(48, "big") + s.to_bytes(48, "big")
+
+with open("attestation.cbor", "wb") as f:
+    f.write(cbor([protected, {}, payload, signature]))
+with open("public_key.bin", "wb") as f:
+    f.write(public_key)
+with open("root_fingerprint.txt", "w") as f:
+    f.write(hashlib.sha256(der(root)).hexdigest() + "\n")
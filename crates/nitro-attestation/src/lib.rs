@@ -0,0 +1,324 @@
+//! Verification of AWS Nitro Enclaves attestation documents.
+//!
+//! TEE servers return an attestation document together with their public key. Before encrypting
+//! anything to that key, a client should check that:
+//! - the document is signed by a certificate chaining up to the pinned AWS Nitro root,
+//! - the enclave image (PCR values) is one of the allowed ones,
+//! - the public key embedded in the document is the one being used.
+//!
+//! The crate is `no_std` (with `alloc`), so it can be used from the WASM and mobile bindings.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use coset::{iana, CborSerializable, CoseSign1, TaggedCborSerializable};
+use p384::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+use sha2::{Digest, Sha256};
+use x509_cert::{
+    der::{Decode, Encode},
+    Certificate,
+};
+
+/// SHA-256 fingerprint of the DER-encoded AWS Nitro Enclaves root certificate (G1), as published
+/// in the AWS documentation.
+pub const AWS_NITRO_ROOT_CERTIFICATE_FINGERPRINT: [u8; 32] = [
+    0x64, 0x1a, 0x03, 0x21, 0xa3, 0xe2, 0x44, 0xef, 0xe4, 0x56, 0x46, 0x31, 0x95, 0xd6, 0x06, 0x31,
+    0x7e, 0xd7, 0xcd, 0xcc, 0x3c, 0x17, 0x56, 0xe0, 0x98, 0x93, 0xf3, 0xc6, 0x8f, 0x79, 0xbb, 0x5b,
+];
+
+/// Length of a single PCR value (SHA-384 digest).
+pub const PCR_LENGTH: usize = 48;
+
+/// PCR values of an enclave image, keyed by the PCR index.
+pub type Pcrs = BTreeMap<usize, Vec<u8>>;
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum AttestationError {
+    #[error("Malformed COSE_Sign1 structure: {0}")]
+    MalformedCose(String),
+    #[error("Malformed attestation document: {0}")]
+    MalformedDocument(String),
+    #[error("Malformed certificate: {0}")]
+    MalformedCertificate(String),
+    #[error("Unsupported signing algorithm")]
+    UnsupportedAlgorithm,
+    #[error("Root certificate does not match the pinned fingerprint")]
+    UntrustedRoot,
+    #[error("Certificate `{0}` is not valid at the given time")]
+    CertificateExpired(String),
+    #[error("Certificate `{0}` is not signed by its issuer")]
+    InvalidCertificateSignature(String),
+    #[error("Invalid document signature")]
+    InvalidSignature,
+    #[error("PCR values do not match any allowed enclave image")]
+    PcrMismatch,
+    #[error("Public key does not match the attested one")]
+    PublicKeyMismatch,
+}
+
+/// What the client is willing to trust.
+#[derive(Clone, Debug)]
+pub struct Policy {
+    /// SHA-256 fingerprint of the DER-encoded root certificate.
+    pub root_certificate_fingerprint: [u8; 32],
+    /// Allowed enclave images. The document must match all the PCR values of at least one of
+    /// them. Indices not present in an entry are not checked.
+    pub allowed_pcrs: Vec<Pcrs>,
+}
+
+impl Policy {
+    /// Trust the AWS Nitro root and the given enclave images.
+    pub fn aws(allowed_pcrs: Vec<Pcrs>) -> Self {
+        Self {
+            root_certificate_fingerprint: AWS_NITRO_ROOT_CERTIFICATE_FINGERPRINT,
+            allowed_pcrs,
+        }
+    }
+}
+
+/// Payload of the attestation document, as produced by the Nitro Secure Module.
+#[derive(Clone, Debug, Deserialize)]
+pub struct AttestationDocument {
+    pub module_id: String,
+    pub digest: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub pcrs: BTreeMap<usize, ByteBuf>,
+    pub certificate: ByteBuf,
+    /// Certificate chain, starting with the root certificate.
+    pub cabundle: Vec<ByteBuf>,
+    pub public_key: Option<ByteBuf>,
+    pub user_data: Option<ByteBuf>,
+    pub nonce: Option<ByteBuf>,
+}
+
+/// Verify `document` against `policy` and check that it attests `public_key`. `now` (seconds since
+/// the Unix epoch) is used to check the validity of the certificates.
+///
+/// Returns the parsed document on success.
+pub fn verify(
+    document: &[u8],
+    public_key: &[u8],
+    policy: &Policy,
+    now: u64,
+) -> Result<AttestationDocument, AttestationError> {
+    let cose = CoseSign1::from_tagged_slice(document)
+        .or_else(|_| CoseSign1::from_slice(document))
+        .map_err(|e| AttestationError::MalformedCose(alloc::format!("{e:?}")))?;
+    if cose.protected.header.alg != Some(coset::Algorithm::Assigned(iana::Algorithm::ES384)) {
+        return Err(AttestationError::UnsupportedAlgorithm);
+    }
+
+    let payload = cose
+        .payload
+        .as_ref()
+        .ok_or_else(|| AttestationError::MalformedCose("missing payload".into()))?;
+    let attestation: AttestationDocument = ciborium::from_reader(payload.as_slice())
+        .map_err(|e| AttestationError::MalformedDocument(alloc::format!("{e}")))?;
+
+    let leaf = verify_certificate_chain(&attestation, policy, now)?;
+    let leaf_key = verifying_key(&leaf)?;
+    cose.verify_signature(b"", |signature, data| {
+        let signature =
+            Signature::from_slice(signature).map_err(|_| AttestationError::InvalidSignature)?;
+        leaf_key
+            .verify(data, &signature)
+            .map_err(|_| AttestationError::InvalidSignature)
+    })?;
+
+    verify_pcrs(&attestation, &policy.allowed_pcrs)?;
+
+    if attestation.public_key.as_deref().map(|key| key.as_slice()) != Some(public_key) {
+        return Err(AttestationError::PublicKeyMismatch);
+    }
+
+    Ok(attestation)
+}
+
+/// Check that `cabundle` starts at the pinned root and that every certificate, including the
+/// enclave's one, is signed by its predecessor. Returns the enclave's certificate.
+fn verify_certificate_chain(
+    attestation: &AttestationDocument,
+    policy: &Policy,
+    now: u64,
+) -> Result<Certificate, AttestationError> {
+    let root = attestation
+        .cabundle
+        .first()
+        .ok_or_else(|| AttestationError::MalformedDocument("empty cabundle".into()))?;
+    if Sha256::digest(root)[..] != policy.root_certificate_fingerprint {
+        return Err(AttestationError::UntrustedRoot);
+    }
+
+    let mut issuer = parse_certificate(root)?;
+    check_validity(&issuer, now)?;
+    for der in attestation.cabundle[1..]
+        .iter()
+        .chain([&attestation.certificate])
+    {
+        let certificate = parse_certificate(der)?;
+        check_validity(&certificate, now)?;
+        check_issued_by(&certificate, &issuer)?;
+        issuer = certificate;
+    }
+    Ok(issuer)
+}
+
+fn parse_certificate(der: &[u8]) -> Result<Certificate, AttestationError> {
+    Certificate::from_der(der)
+        .map_err(|e| AttestationError::MalformedCertificate(alloc::format!("{e}")))
+}
+
+fn subject(certificate: &Certificate) -> String {
+    alloc::format!("{}", certificate.tbs_certificate.subject)
+}
+
+fn check_validity(certificate: &Certificate, now: u64) -> Result<(), AttestationError> {
+    let validity = &certificate.tbs_certificate.validity;
+    let not_before = validity.not_before.to_unix_duration().as_secs();
+    let not_after = validity.not_after.to_unix_duration().as_secs();
+    if now < not_before || now > not_after {
+        return Err(AttestationError::CertificateExpired(subject(certificate)));
+    }
+    Ok(())
+}
+
+fn check_issued_by(
+    certificate: &Certificate,
+    issuer: &Certificate,
+) -> Result<(), AttestationError> {
+    let invalid = || AttestationError::InvalidCertificateSignature(subject(certificate));
+    if certificate.tbs_certificate.issuer != issuer.tbs_certificate.subject {
+        return Err(invalid());
+    }
+
+    let tbs = certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| AttestationError::MalformedCertificate(alloc::format!("{e}")))?;
+    let signature = certificate
+        .signature
+        .as_bytes()
+        .and_then(|bytes| Signature::from_der(bytes).ok())
+        .ok_or_else(invalid)?;
+    verifying_key(issuer)?
+        .verify(&tbs, &signature)
+        .map_err(|_| invalid())
+}
+
+fn verifying_key(certificate: &Certificate) -> Result<VerifyingKey, AttestationError> {
+    let public_key = &certificate
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key;
+    VerifyingKey::from_sec1_bytes(public_key.raw_bytes())
+        .map_err(|e| AttestationError::MalformedCertificate(alloc::format!("{e}")))
+}
+
+fn verify_pcrs(
+    attestation: &AttestationDocument,
+    allowed_pcrs: &[Pcrs],
+) -> Result<(), AttestationError> {
+    let matches = |expected: &Pcrs| {
+        expected.iter().all(|(index, value)| {
+            attestation
+                .pcrs
+                .get(index)
+                .is_some_and(|actual| actual.as_slice() == value.as_slice())
+        })
+    };
+    if allowed_pcrs.iter().any(matches) {
+        Ok(())
+    } else {
+        Err(AttestationError::PcrMismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+
+    const DOCUMENT: &[u8] = include_bytes!("../fixtures/attestation.cbor");
+    const PUBLIC_KEY: &[u8] = include_bytes!("../fixtures/public_key.bin");
+    const ROOT_FINGERPRINT: &str = include_str!("../fixtures/root_fingerprint.txt");
+    /// 2025-10-09, within the validity of the fixture certificates.
+    const NOW: u64 = 1_760_000_000;
+
+    fn image_pcrs() -> Pcrs {
+        (0..3).map(|i| (i, vec![i as u8 + 1; PCR_LENGTH])).collect()
+    }
+
+    fn policy(allowed_pcrs: Vec<Pcrs>) -> Policy {
+        Policy {
+            root_certificate_fingerprint: hex::decode(ROOT_FINGERPRINT.trim())
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            allowed_pcrs,
+        }
+    }
+
+    #[test]
+    fn accepts_valid_document() {
+        let document = verify(DOCUMENT, PUBLIC_KEY, &policy(vec![image_pcrs()]), NOW).unwrap();
+        assert_eq!(document.digest, "SHA384");
+        assert_eq!(document.cabundle.len(), 2);
+    }
+
+    #[test]
+    fn rejects_unpinned_root() {
+        let result = verify(DOCUMENT, PUBLIC_KEY, &Policy::aws(vec![image_pcrs()]), NOW);
+        assert_eq!(result.unwrap_err(), AttestationError::UntrustedRoot);
+    }
+
+    #[test]
+    fn rejects_unknown_image() {
+        let mut pcrs = image_pcrs();
+        pcrs.insert(2, vec![0xff; PCR_LENGTH]);
+        let result = verify(DOCUMENT, PUBLIC_KEY, &policy(vec![pcrs]), NOW);
+        assert_eq!(result.unwrap_err(), AttestationError::PcrMismatch);
+
+        let result = verify(DOCUMENT, PUBLIC_KEY, &policy(vec![]), NOW);
+        assert_eq!(result.unwrap_err(), AttestationError::PcrMismatch);
+    }
+
+    #[test]
+    fn rejects_other_public_key() {
+        let result = verify(DOCUMENT, &[0; 65], &policy(vec![image_pcrs()]), NOW);
+        assert_eq!(result.unwrap_err(), AttestationError::PublicKeyMismatch);
+    }
+
+    #[test]
+    fn rejects_expired_certificates() {
+        let in_2040 = 2_208_988_800;
+        let result = verify(DOCUMENT, PUBLIC_KEY, &policy(vec![image_pcrs()]), in_2040);
+        assert!(matches!(
+            result.unwrap_err(),
+            AttestationError::CertificateExpired(_)
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_signature() {
+        let mut document = DOCUMENT.to_vec();
+        *document.last_mut().unwrap() ^= 1;
+        let result = verify(&document, PUBLIC_KEY, &policy(vec![image_pcrs()]), NOW);
+        assert_eq!(result.unwrap_err(), AttestationError::InvalidSignature);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        let result = verify(&[1, 2, 3], PUBLIC_KEY, &policy(vec![image_pcrs()]), NOW);
+        assert!(matches!(
+            result.unwrap_err(),
+            AttestationError::MalformedCose(_)
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+//! Run and audit a powers-of-tau ceremony, see [`powers_of_tau::ceremony`].
+//!
+//! ```bash
+//! ptau-ceremony init --k 13 --transcript ceremony.bin
+//! ptau-ceremony contribute --transcript ceremony.bin   # once per participant
+//! ptau-ceremony verify --transcript ceremony.bin
+//! ptau-ceremony export --transcript ceremony.bin --format ptau --output ppot_0080_13.ptau
+//! ```
+//!
+//! An exported `.ptau` file named `ppot_0080_{k}.ptau` is picked up by `halo2-verifier` and the
+//! build scripts when `PTAU_RESOURCES_DIR` points to its directory.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use halo2curves::{
+    bn256::G1Affine,
+    group::{prime::PrimeCurveAffine, GroupEncoding},
+};
+use powers_of_tau::{
+    ceremony::{Accumulator, Ceremony},
+    Format,
+};
+use rand::rngs::OsRng;
+
+#[derive(Parser, Debug)]
+#[command(name = "ptau-ceremony", version, about)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Start a new ceremony transcript.
+    Init {
+        /// Log2 of the number of G1 powers used by halo2.
+        #[arg(long, conflicts_with = "from_ptau")]
+        k: Option<u32>,
+
+        /// Continue from an existing `.ptau` file instead of starting from `τ = 1`.
+        #[arg(long)]
+        from_ptau: Option<PathBuf>,
+
+        /// Where to write the transcript. Existing file will be overwritten!
+        #[arg(long)]
+        transcript: PathBuf,
+    },
+
+    /// Add a contribution with a fresh secret to the transcript.
+    Contribute {
+        #[arg(long)]
+        transcript: PathBuf,
+
+        /// Where to write the updated transcript. Defaults to overwriting `--transcript`.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Verify all the contributions and the final powers.
+    Verify {
+        #[arg(long)]
+        transcript: PathBuf,
+
+        /// The `.ptau` file that the ceremony started from. Without it, the transcript must start
+        /// from `τ = 1`.
+        #[arg(long)]
+        initial_ptau: Option<PathBuf>,
+    },
+
+    /// Verify the transcript and export the final powers.
+    Export {
+        #[arg(long)]
+        transcript: PathBuf,
+
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+
+        #[arg(long)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ExportFormat {
+    /// halo2 parameters, see `Format::Raw`.
+    Raw,
+    /// `.ptau` file, see `Format::PerpetualPowersOfTau`.
+    Ptau,
+}
+
+impl From<ExportFormat> for Format {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Raw => Format::Raw,
+            ExportFormat::Ptau => Format::PerpetualPowersOfTau,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Init {
+            k,
+            from_ptau,
+            transcript,
+        } => {
+            let accumulator = match (k, from_ptau) {
+                (_, Some(ptau)) => Accumulator::read_ptau(&ptau)?,
+                (Some(k), None) if k > 0 => Accumulator::new(k),
+                _ => bail!("Either a positive `--k` or `--from-ptau` must be given"),
+            };
+            Ceremony::new(accumulator).save(&transcript)?;
+            println!("Transcript written to {transcript:?}");
+        }
+        Command::Contribute { transcript, output } => {
+            let mut ceremony = Ceremony::load(&transcript)?;
+            let contribution = ceremony.contribute(OsRng);
+            ceremony.save(output.as_ref().unwrap_or(&transcript))?;
+
+            println!(
+                "Contribution #{} added. Publish the following values so that others can \
+                 identify it in the transcript:",
+                ceremony.contributions.len()
+            );
+            println!("  x·G1:  0x{}", hex(contribution.x_g1.to_bytes()));
+            println!("  x·G2:  0x{}", hex(contribution.x_g2.to_bytes()));
+            println!("  τ·G1:  0x{}", hex(contribution.tau_g1.to_bytes()));
+        }
+        Command::Verify {
+            transcript,
+            initial_ptau,
+        } => {
+            let ceremony = Ceremony::load(&transcript)?;
+            if let Some(initial_ptau) = initial_ptau {
+                if Accumulator::read_ptau(&initial_ptau)?.tau() != ceremony.initial_tau {
+                    bail!("The transcript does not start from {initial_ptau:?}");
+                }
+            } else if ceremony.initial_tau != G1Affine::generator() {
+                bail!(
+                    "The transcript does not start from τ = 1, pass the `.ptau` file it was \
+                     initialized with as `--initial-ptau`"
+                );
+            }
+            ceremony.verify(&mut OsRng)?;
+
+            println!(
+                "Transcript is valid: {} contribution(s), k = {}",
+                ceremony.contributions.len(),
+                ceremony.accumulator.k
+            );
+            for (index, contribution) in ceremony.contributions.iter().enumerate() {
+                println!(
+                    "  #{}: x·G1 = 0x{}",
+                    index + 1,
+                    hex(contribution.x_g1.to_bytes())
+                );
+            }
+        }
+        Command::Export {
+            transcript,
+            format,
+            output,
+        } => {
+            let ceremony = Ceremony::load(&transcript)?;
+            ceremony.verify(&mut OsRng)?;
+            if ceremony.accumulator.tau() == G1Affine::generator() {
+                bail!("Refusing to export powers of τ = 1, add a contribution first");
+            }
+            ceremony.accumulator.write(&output, format.into())?;
+            println!("Powers exported to {output:?}");
+        }
+    }
+    Ok(())
+}
+
+fn hex(bytes: impl AsRef<[u8]>) -> String {
+    bytes
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
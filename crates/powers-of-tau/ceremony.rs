@@ -0,0 +1,551 @@
+//! Multi-party powers-of-tau ceremony.
+//!
+//! The ceremony state is an [`Accumulator`]: the powers `[G1, τ·G1, ..., τ^(2n-2)·G1]` and
+//! `[G2, τ·G2, ..., τ^(n-1)·G2]` for `n = 2^k`, i.e. the same content as the `tauG1` and `tauG2`
+//! sections of a `.ptau` file. Every participant multiplies `τ` by a fresh secret `x` and publishes
+//! a [`Contribution`] with a proof of knowledge of `x`. As long as one participant destroyed their
+//! secret, nobody knows the final `τ`.
+//!
+//! [`Ceremony::verify`] checks the whole transcript with pairings:
+//!  - every contribution proves knowledge of `x` and moves `τ·G1` from the previous value to
+//!    `x·τ·G1`,
+//!  - the final accumulator consists of successive powers of the same `τ` in G1 and G2.
+//!
+//! The result can be exported with [`Accumulator::write`] to any [`Format`], so that it can be
+//! consumed by [`read`](crate::read) (and hence `halo2-verifier`) unchanged.
+
+use std::{
+    fs::File,
+    io,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use halo2_proofs::{
+    arithmetic::{best_multiexp, parallelize, CurveAffine},
+    halo2curves::serde::SerdeObject,
+    poly::commitment::ParamsProver,
+    SerdeFormat,
+};
+use halo2curves::{
+    bn256::{Bn256, Fq, Fr, G1Affine, G2Affine},
+    group::{
+        ff::{Field, FromUniformBytes, PrimeField},
+        prime::PrimeCurveAffine,
+        Curve, GroupEncoding,
+    },
+    pairing::Engine,
+};
+use num_bigint::BigUint;
+use rand::RngCore;
+use sha3::{Digest, Sha3_512};
+use shielder_circuits::circuits::Params as Srs;
+
+use crate::{montgomery_r, read_g1, read_g2, read_k, Format};
+
+/// Magic bytes of a ceremony transcript file.
+const TRANSCRIPT_MAGIC: &[u8; 8] = b"shptau\0\x01";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CeremonyError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid transcript: {0}")]
+    InvalidTranscript(String),
+    #[error("Accumulator has {0} G1 powers and {1} G2 powers, expected 2n-1 and n")]
+    InvalidSize(usize, usize),
+    #[error("Accumulator powers do not start with the generators")]
+    InvalidGenerators,
+    #[error("G1 powers are not successive powers of tau")]
+    InvalidG1Powers,
+    #[error("G2 powers are not successive powers of tau")]
+    InvalidG2Powers,
+    #[error("Contribution {0} has an invalid proof of knowledge")]
+    InvalidProofOfKnowledge(usize),
+    #[error("Contribution {0} is not an update of the previous state")]
+    InvalidUpdate(usize),
+    #[error("Accumulator does not match the last contribution")]
+    AccumulatorMismatch,
+}
+
+pub type CeremonyResult<T> = Result<T, CeremonyError>;
+
+/// Powers of tau: `2n - 1` powers in G1 and `n` powers in G2, where `n = 2^k`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Accumulator {
+    pub k: u32,
+    pub tau_g1: Vec<G1Affine>,
+    pub tau_g2: Vec<G2Affine>,
+}
+
+impl Accumulator {
+    /// Accumulator for `τ = 1`. This is not secure until at least one contribution is added.
+    pub fn new(k: u32) -> Self {
+        let n = 1 << k;
+        Self {
+            k,
+            tau_g1: vec![G1Affine::generator(); 2 * n - 1],
+            tau_g2: vec![G2Affine::generator(); n],
+        }
+    }
+
+    fn n(&self) -> usize {
+        1 << self.k
+    }
+
+    /// `τ·G1`.
+    pub fn tau(&self) -> G1Affine {
+        self.tau_g1[1]
+    }
+
+    /// Read the accumulator from a `.ptau` file (only `Format::PerpetualPowersOfTau` carries all
+    /// the powers).
+    pub fn read_ptau(ptau_file: &Path) -> io::Result<Self> {
+        let mut reader = File::open(ptau_file)?;
+        let k = read_k(&mut reader)?;
+        let n = 1 << k;
+        Ok(Self {
+            k,
+            tau_g1: read_g1::<Bn256>(&mut reader, 2 * n - 1)?,
+            tau_g2: read_g2::<Bn256>(&mut reader, n)?,
+        })
+    }
+
+    /// Halo2 parameters: the first `n` powers in G1 together with `G2` and `τ·G2`.
+    pub fn to_srs(&self) -> Srs {
+        let n = self.n();
+        Srs::new(self.k).from_parts(
+            self.k,
+            self.tau_g1[..n].to_vec(),
+            None,
+            self.tau_g2[0],
+            self.tau_g2[1],
+        )
+    }
+
+    /// Export the accumulator in the given format.
+    pub fn write(&self, path: &Path, format: Format) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            Format::Raw => self
+                .to_srs()
+                .write_custom(&mut writer, SerdeFormat::RawBytes)?,
+            Format::PerpetualPowersOfTau => self.write_ptau(&mut writer)?,
+        }
+        writer.flush()
+    }
+
+    /// Write the header, `tauG1` and `tauG2` sections of a `.ptau` file. The Groth16-specific
+    /// sections (`alphaTauG1`, `betaTauG1`, `betaG2`) and the snarkjs contribution log are not
+    /// written.
+    fn write_ptau<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let base_size = <Fq as PrimeField>::Repr::default().as_ref().len();
+        let modulus = BigUint::from_bytes_le((-Fq::ONE).to_repr().as_ref()) + 1u64;
+        let mut modulus_bytes = modulus.to_bytes_le();
+        modulus_bytes.resize(base_size, 0);
+
+        writer.write_all(b"ptau")?;
+        writer.write_u32::<LittleEndian>(1)?;
+        writer.write_u32::<LittleEndian>(3)?;
+
+        // Header section: `n8q`, `q`, `power`, `ceremonyPower`.
+        writer.write_u32::<LittleEndian>(1)?;
+        writer.write_u64::<LittleEndian>((4 + base_size + 4 + 4) as u64)?;
+        writer.write_u32::<LittleEndian>(base_size as u32)?;
+        writer.write_all(&modulus_bytes)?;
+        writer.write_u32::<LittleEndian>(self.k)?;
+        writer.write_u32::<LittleEndian>(self.k)?;
+
+        writer.write_u32::<LittleEndian>(2)?;
+        writer.write_u64::<LittleEndian>((self.tau_g1.len() * 2 * base_size) as u64)?;
+        for point in &self.tau_g1 {
+            let coordinates = point.coordinates().unwrap();
+            for coordinate in [coordinates.x(), coordinates.y()] {
+                writer.write_all(to_montgomery_repr(*coordinate).as_ref())?;
+            }
+        }
+
+        writer.write_u32::<LittleEndian>(3)?;
+        writer.write_u64::<LittleEndian>((self.tau_g2.len() * 4 * base_size) as u64)?;
+        for point in &self.tau_g2 {
+            let coordinates = point.coordinates().unwrap();
+            for coordinate in [coordinates.x(), coordinates.y()] {
+                let repr = coordinate.to_repr();
+                for half in repr.as_ref().chunks_exact(base_size) {
+                    let mut base_repr = <Fq as PrimeField>::Repr::default();
+                    base_repr.as_mut().copy_from_slice(half);
+                    let base = Option::from(Fq::from_repr(base_repr))
+                        .ok_or_else(|| io::Error::other("invalid G2 coordinate"))?;
+                    writer.write_all(to_montgomery_repr(base).as_ref())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the accumulator starts with the generators and consists of successive powers of
+    /// the same `τ`. All the consecutive pairs are checked at once, using a random linear
+    /// combination.
+    pub fn verify(&self, rng: &mut impl RngCore) -> CeremonyResult<()> {
+        let n = self.n();
+        if self.k == 0 || self.tau_g1.len() != 2 * n - 1 || self.tau_g2.len() != n {
+            return Err(CeremonyError::InvalidSize(
+                self.tau_g1.len(),
+                self.tau_g2.len(),
+            ));
+        }
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+        if self.tau_g1[0] != g1 || self.tau_g2[0] != g2 {
+            return Err(CeremonyError::InvalidGenerators);
+        }
+        if bool::from(self.tau().is_identity()) {
+            return Err(CeremonyError::InvalidG1Powers);
+        }
+
+        // e(τ^(i+1)·G1, G2) = e(τ^i·G1, τ·G2) for every i.
+        let (previous, next) = random_combinations(&self.tau_g1, rng);
+        if Bn256::pairing(&next, &g2) != Bn256::pairing(&previous, &self.tau_g2[1]) {
+            return Err(CeremonyError::InvalidG1Powers);
+        }
+
+        // e(G1, τ^(i+1)·G2) = e(τ·G1, τ^i·G2) for every i.
+        let (previous, next) = random_combinations(&self.tau_g2, rng);
+        if Bn256::pairing(&g1, &next) != Bn256::pairing(&self.tau(), &previous) {
+            return Err(CeremonyError::InvalidG2Powers);
+        }
+        Ok(())
+    }
+
+    /// Multiply `τ` by `x`.
+    fn update(&mut self, x: Fr) {
+        multiply_by_powers(&mut self.tau_g1, x);
+        multiply_by_powers(&mut self.tau_g2, x);
+    }
+}
+
+/// Public record of a single contribution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    /// `τ·G1` after the contribution.
+    pub tau_g1: G1Affine,
+    /// `x·G1`, where `x` is the secret of the contributor.
+    pub x_g1: G1Affine,
+    /// `x·G2`.
+    pub x_g2: G2Affine,
+    /// Schnorr proof of knowledge of `x`, bound to the state before the contribution.
+    pub pok: ProofOfKnowledge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofOfKnowledge {
+    pub commitment: G1Affine,
+    pub response: Fr,
+}
+
+impl ProofOfKnowledge {
+    fn prove(
+        x: Fr,
+        x_g1: G1Affine,
+        x_g2: G2Affine,
+        previous_tau: G1Affine,
+        rng: impl RngCore,
+    ) -> Self {
+        let nonce = Fr::random(rng);
+        let commitment = (G1Affine::generator() * nonce).to_affine();
+        let challenge = challenge(previous_tau, x_g1, x_g2, commitment);
+        Self {
+            commitment,
+            response: nonce + challenge * x,
+        }
+    }
+
+    fn verify(&self, x_g1: G1Affine, x_g2: G2Affine, previous_tau: G1Affine) -> bool {
+        let challenge = challenge(previous_tau, x_g1, x_g2, self.commitment);
+        G1Affine::generator() * self.response == x_g1 * challenge + self.commitment
+    }
+}
+
+/// Ceremony transcript: the current accumulator together with all the contributions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ceremony {
+    /// `τ·G1` of the accumulator that the ceremony started from.
+    pub initial_tau: G1Affine,
+    pub contributions: Vec<Contribution>,
+    pub accumulator: Accumulator,
+}
+
+impl Ceremony {
+    /// Start a ceremony from `accumulator`, e.g. [`Accumulator::new`] or a `.ptau` file from
+    /// another ceremony.
+    pub fn new(accumulator: Accumulator) -> Self {
+        Self {
+            initial_tau: accumulator.tau(),
+            contributions: Vec::new(),
+            accumulator,
+        }
+    }
+
+    /// Add a contribution with a fresh secret sampled from `rng`. The secret is dropped when this
+    /// function returns.
+    pub fn contribute(&mut self, mut rng: impl RngCore) -> Contribution {
+        let x = loop {
+            let x = Fr::random(&mut rng);
+            if !bool::from(x.is_zero()) {
+                break x;
+            }
+        };
+        let previous_tau = self.accumulator.tau();
+        let x_g1 = (G1Affine::generator() * x).to_affine();
+        let x_g2 = (G2Affine::generator() * x).to_affine();
+        let pok = ProofOfKnowledge::prove(x, x_g1, x_g2, previous_tau, &mut rng);
+
+        self.accumulator.update(x);
+        let contribution = Contribution {
+            tau_g1: self.accumulator.tau(),
+            x_g1,
+            x_g2,
+            pok,
+        };
+        self.contributions.push(contribution);
+        contribution
+    }
+
+    /// Verify the whole transcript. `initial_tau` should additionally be compared with the
+    /// accumulator that the ceremony started from.
+    pub fn verify(&self, rng: &mut impl RngCore) -> CeremonyResult<()> {
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+
+        let mut previous_tau = self.initial_tau;
+        for (index, contribution) in self.contributions.iter().enumerate() {
+            if bool::from(contribution.x_g1.is_identity())
+                || !contribution
+                    .pok
+                    .verify(contribution.x_g1, contribution.x_g2, previous_tau)
+            {
+                return Err(CeremonyError::InvalidProofOfKnowledge(index));
+            }
+            // The same `x` in G1 and G2, and `τ·G1` multiplied by `x`.
+            if Bn256::pairing(&contribution.x_g1, &g2) != Bn256::pairing(&g1, &contribution.x_g2)
+                || Bn256::pairing(&contribution.tau_g1, &g2)
+                    != Bn256::pairing(&previous_tau, &contribution.x_g2)
+            {
+                return Err(CeremonyError::InvalidUpdate(index));
+            }
+            previous_tau = contribution.tau_g1;
+        }
+
+        if self.accumulator.tau() != previous_tau {
+            return Err(CeremonyError::AccumulatorMismatch);
+        }
+        self.accumulator.verify(rng)
+    }
+
+    /// Persist the transcript to `path`.
+    pub fn save(&self, path: &Path) -> CeremonyResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(TRANSCRIPT_MAGIC)?;
+        writer.write_u32::<LittleEndian>(self.accumulator.k)?;
+        self.initial_tau.write_raw(&mut writer)?;
+
+        writer.write_u32::<LittleEndian>(self.contributions.len() as u32)?;
+        for contribution in &self.contributions {
+            contribution.tau_g1.write_raw(&mut writer)?;
+            contribution.x_g1.write_raw(&mut writer)?;
+            contribution.x_g2.write_raw(&mut writer)?;
+            contribution.pok.commitment.write_raw(&mut writer)?;
+            contribution.pok.response.write_raw(&mut writer)?;
+        }
+
+        for point in &self.accumulator.tau_g1 {
+            point.write_raw(&mut writer)?;
+        }
+        for point in &self.accumulator.tau_g2 {
+            point.write_raw(&mut writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a transcript saved with [`Ceremony::save`]. Points are checked to be on the curve, the
+    /// transcript itself is not verified.
+    pub fn load(path: &Path) -> CeremonyResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != TRANSCRIPT_MAGIC {
+            return Err(CeremonyError::InvalidTranscript(
+                "not a ceremony transcript".into(),
+            ));
+        }
+        let k = reader.read_u32::<LittleEndian>()?;
+        if k >= usize::BITS {
+            return Err(CeremonyError::InvalidTranscript(format!("invalid k: {k}")));
+        }
+        let n = 1usize << k;
+        let initial_tau = G1Affine::read_raw(&mut reader)?;
+
+        let contribution_count = reader.read_u32::<LittleEndian>()?;
+        let contributions = (0..contribution_count)
+            .map(|_| -> io::Result<Contribution> {
+                Ok(Contribution {
+                    tau_g1: G1Affine::read_raw(&mut reader)?,
+                    x_g1: G1Affine::read_raw(&mut reader)?,
+                    x_g2: G2Affine::read_raw(&mut reader)?,
+                    pok: ProofOfKnowledge {
+                        commitment: G1Affine::read_raw(&mut reader)?,
+                        response: Fr::read_raw(&mut reader)?,
+                    },
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        let tau_g1 = (0..2 * n - 1)
+            .map(|_| G1Affine::read_raw(&mut reader))
+            .collect::<io::Result<_>>()?;
+        let tau_g2 = (0..n)
+            .map(|_| G2Affine::read_raw(&mut reader))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self {
+            initial_tau,
+            contributions,
+            accumulator: Accumulator { k, tau_g1, tau_g2 },
+        })
+    }
+}
+
+/// Fiat-Shamir challenge for [`ProofOfKnowledge`].
+fn challenge(previous_tau: G1Affine, x_g1: G1Affine, x_g2: G2Affine, commitment: G1Affine) -> Fr {
+    let mut hasher = Sha3_512::new();
+    hasher.update(b"shielder-powers-of-tau-pok");
+    hasher.update(previous_tau.to_bytes());
+    hasher.update(x_g1.to_bytes());
+    hasher.update(x_g2.to_bytes());
+    hasher.update(commitment.to_bytes());
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    Fr::from_uniform_bytes(&bytes)
+}
+
+/// Multiply the `i`-th point by `x^i`.
+fn multiply_by_powers<C: CurveAffine>(points: &mut [C], x: C::Scalar) {
+    parallelize(points, |points, start| {
+        let mut power = x.pow_vartime([start as u64]);
+        for point in points.iter_mut() {
+            *point = (*point * power).to_affine();
+            power *= x;
+        }
+    });
+}
+
+/// Returns `(Σ r_i·P_i, Σ r_i·P_(i+1))` for random `r_i`, where `i` ranges over all but the last
+/// point.
+fn random_combinations<C: CurveAffine>(points: &[C], rng: &mut impl RngCore) -> (C, C) {
+    let coefficients = (0..points.len() - 1)
+        .map(|_| C::Scalar::random(&mut *rng))
+        .collect::<Vec<_>>();
+    (
+        best_multiexp(&coefficients, &points[..points.len() - 1]).to_affine(),
+        best_multiexp(&coefficients, &points[1..]).to_affine(),
+    )
+}
+
+/// Inverse of the conversion done when reading `.ptau` files: coordinates are stored in Montgomery
+/// form.
+fn to_montgomery_repr(value: Fq) -> <Fq as PrimeField>::Repr {
+    (value * montgomery_r::<Fq>()).to_repr()
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::poly::commitment::Params as _;
+    use rand::rngs::OsRng;
+
+    use crate::{
+        ceremony::{Accumulator, Ceremony, CeremonyError},
+        read, Format,
+    };
+
+    const K: u32 = 4;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}-{}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn contributions_verify() {
+        let mut ceremony = Ceremony::new(Accumulator::new(K));
+        ceremony.contribute(OsRng);
+        ceremony.contribute(OsRng);
+
+        assert!(ceremony.verify(&mut OsRng).is_ok());
+    }
+
+    #[test]
+    fn tampered_accumulator_is_rejected() {
+        let mut ceremony = Ceremony::new(Accumulator::new(K));
+        ceremony.contribute(OsRng);
+        ceremony.accumulator.tau_g1.swap(2, 3);
+
+        assert!(matches!(
+            ceremony.verify(&mut OsRng),
+            Err(CeremonyError::InvalidG1Powers)
+        ));
+    }
+
+    #[test]
+    fn contribution_without_knowledge_is_rejected() {
+        let mut ceremony = Ceremony::new(Accumulator::new(K));
+        ceremony.contribute(OsRng);
+        let mut other = ceremony.clone();
+        other.contribute(OsRng);
+
+        // Replay the first proof of knowledge for the second contribution.
+        other.contributions[1].pok = ceremony.contributions[0].pok;
+
+        assert!(matches!(
+            other.verify(&mut OsRng),
+            Err(CeremonyError::InvalidProofOfKnowledge(1))
+        ));
+    }
+
+    #[test]
+    fn transcript_survives_save_and_load() {
+        let mut ceremony = Ceremony::new(Accumulator::new(K));
+        ceremony.contribute(OsRng);
+        let path = temp_path("ceremony");
+
+        ceremony.save(&path).unwrap();
+        let loaded = Ceremony::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, ceremony);
+    }
+
+    #[test]
+    fn exports_are_readable() {
+        let mut ceremony = Ceremony::new(Accumulator::new(K));
+        ceremony.contribute(OsRng);
+        let accumulator = &ceremony.accumulator;
+
+        for format in [Format::Raw, Format::PerpetualPowersOfTau] {
+            let path = temp_path("ceremony-export");
+            accumulator.write(&path, format).unwrap();
+            let srs = read(path.clone(), format).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(srs.k(), K);
+            assert_eq!(srs.get_g(), &accumulator.tau_g1[..1 << K]);
+            assert_eq!(srs.s_g2(), accumulator.tau_g2[1]);
+        }
+
+        let path = temp_path("ceremony-ptau");
+        accumulator
+            .write(&path, Format::PerpetualPowersOfTau)
+            .unwrap();
+        let reimported = Accumulator::read_ptau(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&reimported, accumulator);
+    }
+}
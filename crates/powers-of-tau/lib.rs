@@ -1,4 +1,5 @@
-//! Utilities for reading in Perpetual Powers of Tau (.ptau) files.
+//! Utilities for reading in Perpetual Powers of Tau (.ptau) files and for running our own
+//! powers-of-tau ceremony (see [`ceremony`]).
 
 use std::{
     env,
@@ -22,6 +23,8 @@ use halo2curves::{
 use num_bigint::BigUint;
 use shielder_circuits::{circuits::Params as Srs, G1Affine};
 
+pub mod ceremony;
+
 pub const HEADER_SIZE_OFFSET: u64 = 16;
 pub const HEADER_OFFSET: u64 = HEADER_SIZE_OFFSET + 8;
 
@@ -0,0 +1,167 @@
+use alloy_primitives::{Bytes, U256};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use shielder_circuits::{
+    balance_attestation::{BalanceAttestationInstance, BalanceAttestationProverKnowledge},
+    circuits::{Params, ProvingKey, VerifyingKey},
+    consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+    generate_proof, verify, Fr, ProverKnowledge, PublicInputProvider,
+};
+use shielder_setup::version::contract_version;
+use type_conversions::{address_to_field, field_to_u256, u256_to_field};
+
+use crate::{ShielderAccount, Token};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BalanceAttestationError {
+    /// The account has no note yet.
+    NoNote,
+    /// The note has an older version and has to be migrated first.
+    OutdatedNoteVersion,
+    /// The shielded amount is lower than the threshold.
+    InsufficientBalance,
+    /// The proof does not match the public inputs.
+    InvalidProof,
+}
+
+/// Proof that a shielded account holds at least `threshold` of `token`, bound to a verifier-chosen
+/// `challenge` (see `shielder_circuits::balance_attestation`).
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct BalanceAttestation {
+    pub token: Token,
+    pub merkle_root: U256,
+    pub nullifier_hash: U256,
+    pub threshold: U256,
+    pub challenge: U256,
+    pub proof: Bytes,
+}
+
+impl PublicInputProvider<BalanceAttestationInstance> for BalanceAttestation {
+    fn compute_public_input(&self, instance_id: BalanceAttestationInstance) -> Fr {
+        use BalanceAttestationInstance::*;
+        match instance_id {
+            MerkleRoot => u256_to_field(self.merkle_root),
+            HashedNullifier => u256_to_field(self.nullifier_hash),
+            TokenAddress => address_to_field(self.token.address()),
+            Threshold => u256_to_field(self.threshold),
+            Challenge => u256_to_field(self.challenge),
+        }
+    }
+}
+
+impl BalanceAttestation {
+    /// Verify the proof against the public inputs. This does not check the on-chain state: the
+    /// verifier should additionally ensure that `merkle_root` is a recent root of the note tree and
+    /// that `nullifier_hash` is not registered in the contract (i.e. the note is still unspent).
+    pub fn verify(
+        &self,
+        params: &Params,
+        vk: &VerifyingKey,
+    ) -> Result<(), BalanceAttestationError> {
+        verify(params, vk, &self.proof, &self.serialize_public_input())
+            .map_err(|_| BalanceAttestationError::InvalidProof)
+    }
+}
+
+impl ShielderAccount {
+    /// Prove that the current note of the account holds at least `threshold` tokens. `merkle_path`
+    /// is the path of the current note (see `Self::current_leaf_index`).
+    pub fn attest_balance(
+        &self,
+        params: &Params,
+        pk: &ProvingKey,
+        merkle_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+        threshold: U256,
+        challenge: U256,
+    ) -> Result<BalanceAttestation, BalanceAttestationError> {
+        if self.nonce == 0 {
+            return Err(BalanceAttestationError::NoNote);
+        }
+        if self.note_version != contract_version().note_version {
+            return Err(BalanceAttestationError::OutdatedNoteVersion);
+        }
+        if self.shielded_amount < threshold {
+            return Err(BalanceAttestationError::InsufficientBalance);
+        }
+
+        let knowledge = BalanceAttestationProverKnowledge {
+            challenge: u256_to_field(challenge),
+            threshold: u256_to_field(threshold),
+            id: u256_to_field(self.id),
+            nullifier: u256_to_field(self.previous_nullifier()),
+            account_balance: u256_to_field(self.shielded_amount),
+            token_address: address_to_field(self.token.address()),
+            path: merkle_path.map(|level| level.map(u256_to_field)),
+        };
+        let proof = generate_proof(
+            params,
+            pk,
+            knowledge.create_circuit(),
+            &knowledge.serialize_public_input(),
+            &mut OsRng,
+        );
+
+        use BalanceAttestationInstance::*;
+        Ok(BalanceAttestation {
+            token: self.token,
+            merkle_root: field_to_u256(knowledge.compute_public_input(MerkleRoot)),
+            nullifier_hash: field_to_u256(knowledge.compute_public_input(HashedNullifier)),
+            threshold,
+            challenge,
+            proof: Bytes::from(proof),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{TxHash, U256};
+    use shielder_circuits::{
+        balance_attestation::BalanceAttestationCircuit, generate_keys_with_min_k,
+        generate_setup_params, merkle::generate_example_path_with_given_leaf, MAX_K,
+    };
+    use type_conversions::{field_to_u256, u256_to_field};
+
+    use crate::{
+        balance_attestation::BalanceAttestationError, ShielderAccount, ShielderAction, Token,
+    };
+
+    #[test]
+    fn attestation_verifies_only_for_sufficient_balance_and_same_challenge() {
+        let mut rng = rand::thread_rng();
+        let mut account = ShielderAccount::new(U256::from(1), Token::Native);
+        account.register_action(ShielderAction::new_account(
+            U256::from(100),
+            U256::ZERO,
+            TxHash::ZERO,
+            Token::Native,
+            U256::ZERO,
+        ));
+
+        let note = u256_to_field(account.note(Token::Native).unwrap());
+        let (_, path) = generate_example_path_with_given_leaf(note, &mut rng);
+        let path = path.map(|level| level.map(field_to_u256));
+
+        let (params, _, pk, vk) = generate_keys_with_min_k(
+            BalanceAttestationCircuit::default(),
+            generate_setup_params(MAX_K, &mut rng),
+        )
+        .unwrap();
+
+        assert_eq!(
+            account.attest_balance(&params, &pk, path, U256::from(101), U256::from(7)),
+            Err(BalanceAttestationError::InsufficientBalance)
+        );
+
+        let mut attestation = account
+            .attest_balance(&params, &pk, path, U256::from(100), U256::from(7))
+            .unwrap();
+        assert_eq!(attestation.verify(&params, &vk), Ok(()));
+
+        attestation.challenge = U256::from(8);
+        assert_eq!(
+            attestation.verify(&params, &vk),
+            Err(BalanceAttestationError::InvalidProof)
+        );
+    }
+}
@@ -1,26 +1,36 @@
 use alloy_primitives::{Address, Bytes, FixedBytes, U256};
-use rand::rngs::OsRng;
 use shielder_circuits::{
+    batch_withdraw::BatchWithdrawProverKnowledge,
     circuits::{Params, ProvingKey},
-    consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+    claim::ClaimProverKnowledge,
+    consts::{
+        merkle_constants::{ARITY, NOTE_TREE_HEIGHT, TOKEN_TREE_HEIGHT},
+        WITHDRAW_BATCH_SIZE,
+    },
     deposit::DepositProverKnowledge,
-    field_element_to_le_bits,
+    empty_association_path, field_element_to_le_bits,
+    migration::MigrationProverKnowledge,
     new_account::NewAccountProverKnowledge,
+    transfer::TransferProverKnowledge,
     withdraw::WithdrawProverKnowledge,
-    Field, Fr, GrumpkinPointAffine, ProverKnowledge, PublicInputProvider,
+    Field, Fr, GrumpkinPointAffine, PublicInputProvider,
 };
 use shielder_contract::{
-    DepositCommitment, NewAccountCommitment,
+    ClaimCommitment, DepositCommitment, MigrationCommitment, NewAccountCommitment,
     ShielderContract::{
-        depositERC20Call, depositNativeCall, newAccountERC20Call, newAccountNativeCall,
-        withdrawERC20Call, withdrawNativeCall,
+        claimCall, depositERC20Call, depositNativeCall, migrateCall, newAccountERC20Call,
+        newAccountNativeCall, transferCall, withdrawBatchCall, withdrawERC20Call,
+        withdrawNativeCall, BatchedWithdrawal,
     },
-    WithdrawCommitment,
+    TransferCommitment, WithdrawCommitment,
 };
 use shielder_setup::version::{contract_version, ContractVersion};
 use type_conversions::{address_to_field, field_to_address, field_to_u256, u256_to_field};
 
-use crate::{ShielderAccount, Token};
+use crate::{
+    proving::{generate_proof, Provable, ProvingBackend, ProvingError},
+    ReceivedTransfer, ShielderAccount, Token,
+};
 
 struct ActionSecrets {
     nullifier_old: U256,
@@ -37,7 +47,7 @@ pub trait CallType {
     type Extra;
     /// We suppose that every call has a corresponding circuit values struct used to generate a
     /// proof.
-    type ProverKnowledge: ProverKnowledge;
+    type ProverKnowledge: Provable;
     /// The type of the contract call data. Must be a type convertible to `SolCall`.
     type Calldata;
 
@@ -127,6 +137,8 @@ pub struct NewAccountCallExtra {
     pub caller_address: Address,
     pub protocol_fee: U256,
     pub memo: Bytes,
+    /// Path of the token in the tree of allowed tokens (see `shielder_contract::token_list`).
+    pub token_list_path: [[U256; ARITY]; TOKEN_TREE_HEIGHT],
 }
 
 pub enum NewAccountCallType {}
@@ -159,6 +171,7 @@ impl CallType for NewAccountCallType {
                 y: u256_to_field(extra.anonymity_revoker_public_key.y),
             },
             mac_salt: u256_to_field(extra.mac_salt),
+            token_list_path: map_path_to_field(extra.token_list_path),
         }
     }
 
@@ -326,6 +339,7 @@ pub struct WithdrawCall {
     pub relayer_fee: U256,
     pub mac_salt: U256,
     pub mac_commitment: U256,
+    pub association_set_root: U256,
     pub proof: Bytes,
     pub pocket_money: U256,
     pub memo: Bytes,
@@ -348,6 +362,7 @@ impl TryFrom<WithdrawCall> for withdrawNativeCall {
                 relayerFee: calldata.relayer_fee,
                 macSalt: calldata.mac_salt,
                 macCommitment: calldata.mac_commitment,
+                associationSetRoot: calldata.association_set_root,
                 memo: calldata.memo,
             }),
             Token::ERC20(_) => Err(CallTypeConversionError),
@@ -374,12 +389,21 @@ impl TryFrom<WithdrawCall> for withdrawERC20Call {
                 relayerFee: calldata.relayer_fee,
                 macSalt: calldata.mac_salt,
                 macCommitment: calldata.mac_commitment,
+                associationSetRoot: calldata.association_set_root,
                 memo: calldata.memo,
             }),
         }
     }
 }
 
+/// Path of the account prenullifier in an association set with `root` (see
+/// `shielder_contract::AssociationSet`).
+#[derive(Clone, Debug)]
+pub struct AssociationSetPath {
+    pub root: U256,
+    pub path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+}
+
 pub struct WithdrawExtra {
     pub merkle_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
     pub to: Address,
@@ -391,6 +415,8 @@ pub struct WithdrawExtra {
     pub pocket_money: U256,
     pub protocol_fee: U256,
     pub memo: Bytes,
+    /// `None` if no association set is claimed.
+    pub association_set: Option<AssociationSetPath>,
 }
 
 pub enum WithdrawCallType {}
@@ -423,8 +449,16 @@ impl CallType for WithdrawCallType {
         }
         .commitment_hash();
 
+        let id = u256_to_field(account.id);
+        let (association_set_root, association_path) = match &extra.association_set {
+            Some(AssociationSetPath { root, path }) => {
+                (u256_to_field(*root), map_path_to_field(*path))
+            }
+            None => (Fr::ZERO, empty_association_path(id)),
+        };
+
         WithdrawProverKnowledge {
-            id: u256_to_field(account.id),
+            id,
             nullifier_old: u256_to_field(nullifier_old),
             account_old_balance: u256_to_field(account.shielded_amount),
             token_address: address_to_field(token.address()),
@@ -433,6 +467,8 @@ impl CallType for WithdrawCallType {
             nullifier_new: u256_to_field(nullifier_new),
             commitment: u256_to_field(commitment),
             mac_salt: u256_to_field(extra.mac_salt),
+            association_set_root,
+            association_path,
         }
     }
 
@@ -443,7 +479,7 @@ impl CallType for WithdrawCallType {
     ) -> Self::Calldata {
         use shielder_circuits::circuits::withdraw::WithdrawInstance::*;
         WithdrawCall {
-            expected_contract_version: contract_version().to_bytes(),
+            expected_contract_version: extra.contract_version.to_bytes(),
             token: field_to_address(pk.token_address).into(),
             amount: field_to_u256(pk.compute_public_input(WithdrawalValue)),
             withdrawal_address: extra.to,
@@ -455,13 +491,544 @@ impl CallType for WithdrawCallType {
             relayer_fee: extra.relayer_fee,
             mac_salt: field_to_u256(pk.compute_public_input(MacSalt)),
             mac_commitment: field_to_u256(pk.compute_public_input(MacCommitment)),
+            association_set_root: field_to_u256(pk.compute_public_input(AssociationSetRoot)),
             pocket_money: extra.pocket_money,
             memo: extra.memo.clone(),
         }
     }
 }
 
+/// Secret inputs of a single withdrawal, needed to prove it in a batch (see
+/// `prove_withdraw_batch`). They contain the account id, so batches must be proven by the client
+/// owning all the batched accounts and never by a relayer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawWitness {
+    pub id: U256,
+    pub nullifier_old: U256,
+    pub account_old_balance: U256,
+    pub token_address: Address,
+    pub path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+    pub withdrawal_value: U256,
+    pub nullifier_new: U256,
+    pub commitment: U256,
+    pub mac_salt: U256,
+    pub association_set_root: U256,
+    pub association_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+}
+
+impl From<&WithdrawProverKnowledge<Fr>> for WithdrawWitness {
+    fn from(knowledge: &WithdrawProverKnowledge<Fr>) -> Self {
+        let map_path = |path: [[Fr; ARITY]; NOTE_TREE_HEIGHT]| path.map(|l| l.map(field_to_u256));
+        Self {
+            id: field_to_u256(knowledge.id),
+            nullifier_old: field_to_u256(knowledge.nullifier_old),
+            account_old_balance: field_to_u256(knowledge.account_old_balance),
+            token_address: field_to_address(knowledge.token_address),
+            path: map_path(knowledge.path),
+            withdrawal_value: field_to_u256(knowledge.withdrawal_value),
+            nullifier_new: field_to_u256(knowledge.nullifier_new),
+            commitment: field_to_u256(knowledge.commitment),
+            mac_salt: field_to_u256(knowledge.mac_salt),
+            association_set_root: field_to_u256(knowledge.association_set_root),
+            association_path: map_path(knowledge.association_path),
+        }
+    }
+}
+
+impl From<&WithdrawWitness> for WithdrawProverKnowledge<Fr> {
+    fn from(witness: &WithdrawWitness) -> Self {
+        Self {
+            id: u256_to_field(witness.id),
+            nullifier_old: u256_to_field(witness.nullifier_old),
+            account_old_balance: u256_to_field(witness.account_old_balance),
+            token_address: address_to_field(witness.token_address),
+            path: map_path_to_field(witness.path),
+            withdrawal_value: u256_to_field(witness.withdrawal_value),
+            nullifier_new: u256_to_field(witness.nullifier_new),
+            commitment: u256_to_field(witness.commitment),
+            mac_salt: u256_to_field(witness.mac_salt),
+            association_set_root: u256_to_field(witness.association_set_root),
+            association_path: map_path_to_field(witness.association_path),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BatchWithdrawError {
+    /// Withdrawals of a batch must use the same token.
+    TokenMismatch,
+    /// Withdrawals of a batch must use the same relayer.
+    RelayerMismatch,
+    /// Withdrawals of a batch must expect the same contract version.
+    ContractVersionMismatch,
+    /// Pocket money is not supported in batched withdrawals.
+    PocketMoney,
+    /// The witness of the withdrawal at `index` does not match its call.
+    WitnessMismatch { index: usize },
+}
+
+/// `WITHDRAW_BATCH_SIZE` withdrawals of the same token, relayed together with a single proof.
+#[derive(Clone, Debug)]
+pub struct BatchWithdrawCall {
+    pub expected_contract_version: FixedBytes<3>,
+    pub token: Token,
+    pub relayer_address: Address,
+    /// Individual withdrawals. Their `proof` and `pocket_money` fields are ignored.
+    pub withdrawals: [WithdrawCall; WITHDRAW_BATCH_SIZE],
+    pub proof: Bytes,
+}
+
+impl From<BatchWithdrawCall> for withdrawBatchCall {
+    fn from(calldata: BatchWithdrawCall) -> Self {
+        Self {
+            expectedContractVersion: calldata.expected_contract_version,
+            tokenAddress: calldata.token.address(),
+            relayerAddress: calldata.relayer_address,
+            withdrawals: calldata.withdrawals.map(|w| BatchedWithdrawal {
+                amount: w.amount,
+                withdrawalAddress: w.withdrawal_address,
+                merkleRoot: w.merkle_root,
+                oldNullifierHash: w.old_nullifier_hash,
+                newNote: w.new_note,
+                relayerFee: w.relayer_fee,
+                macSalt: w.mac_salt,
+                macCommitment: w.mac_commitment,
+                associationSetRoot: w.association_set_root,
+                memo: w.memo,
+            }),
+            proof: calldata.proof,
+        }
+    }
+}
+
+/// Prove `withdrawals` together. Every call must come with the witness of its own withdrawal.
+///
+/// The public inputs of the witnesses are checked against the calls, except for the commitment,
+/// which depends on the chain state (protocol fee) and is checked by the contract.
+pub fn prove_withdraw_batch(
+    params: &Params,
+    pk: &ProvingKey,
+    withdrawals: [(WithdrawCall, WithdrawWitness); WITHDRAW_BATCH_SIZE],
+) -> Result<BatchWithdrawCall, BatchWithdrawError> {
+    let first = &withdrawals[0].0;
+    for (index, (call, witness)) in withdrawals.iter().enumerate() {
+        if call.token != first.token {
+            return Err(BatchWithdrawError::TokenMismatch);
+        }
+        if call.relayer_address != first.relayer_address {
+            return Err(BatchWithdrawError::RelayerMismatch);
+        }
+        if call.expected_contract_version != first.expected_contract_version {
+            return Err(BatchWithdrawError::ContractVersionMismatch);
+        }
+        if call.pocket_money != U256::ZERO {
+            return Err(BatchWithdrawError::PocketMoney);
+        }
+        if !witness_matches_call(&witness.into(), call) {
+            return Err(BatchWithdrawError::WitnessMismatch { index });
+        }
+    }
+
+    let knowledge = BatchWithdrawProverKnowledge {
+        withdrawals: withdrawals.each_ref().map(|(_, witness)| witness.into()),
+    };
+    let proof = generate_proof(params, pk, &knowledge);
+
+    let (expected_contract_version, token, relayer_address) = (
+        first.expected_contract_version,
+        first.token,
+        first.relayer_address,
+    );
+    Ok(BatchWithdrawCall {
+        expected_contract_version,
+        token,
+        relayer_address,
+        withdrawals: withdrawals.map(|(call, _)| call),
+        proof: Bytes::from(proof),
+    })
+}
+
+fn witness_matches_call(knowledge: &WithdrawProverKnowledge<Fr>, call: &WithdrawCall) -> bool {
+    use shielder_circuits::circuits::withdraw::WithdrawInstance::{self, *};
+    let public_input =
+        |instance: WithdrawInstance| field_to_u256(knowledge.compute_public_input(instance));
+
+    public_input(MerkleRoot) == call.merkle_root
+        && public_input(HashedOldNullifier) == call.old_nullifier_hash
+        && public_input(HashedNewNote) == call.new_note
+        && public_input(WithdrawalValue) == call.amount
+        && knowledge.token_address == address_to_field(call.token.address())
+        && public_input(MacSalt) == call.mac_salt
+        && public_input(MacCommitment) == call.mac_commitment
+        && public_input(AssociationSetRoot) == call.association_set_root
+}
+
+#[derive(Clone, Debug)]
+pub struct TransferCall {
+    pub token: Token,
+    pub expected_contract_version: FixedBytes<3>,
+    pub merkle_root: U256,
+    pub old_nullifier_hash: U256,
+    pub new_note: U256,
+    pub receipt: U256,
+    pub encrypted_receipt_nonce_c1: GrumpkinPointAffine<U256>,
+    pub encrypted_receipt_nonce_c2: GrumpkinPointAffine<U256>,
+    pub encrypted_value: U256,
+    pub revoker_encryption_c1: GrumpkinPointAffine<U256>,
+    pub revoker_encryption_c2: GrumpkinPointAffine<U256>,
+    pub mac_salt: U256,
+    pub mac_commitment: U256,
+    pub proof: Bytes,
+    pub memo: Bytes,
+}
+
+impl From<TransferCall> for transferCall {
+    fn from(calldata: TransferCall) -> Self {
+        Self {
+            expectedContractVersion: calldata.expected_contract_version,
+            tokenAddress: calldata.token.address(),
+            merkleRoot: calldata.merkle_root,
+            oldNullifierHash: calldata.old_nullifier_hash,
+            newNote: calldata.new_note,
+            receipt: calldata.receipt,
+            encryptedReceiptNonce: [
+                calldata.encrypted_receipt_nonce_c1.x,
+                calldata.encrypted_receipt_nonce_c1.y,
+                calldata.encrypted_receipt_nonce_c2.x,
+                calldata.encrypted_receipt_nonce_c2.y,
+            ],
+            encryptedValue: calldata.encrypted_value,
+            revokerEncryption: [
+                calldata.revoker_encryption_c1.x,
+                calldata.revoker_encryption_c1.y,
+                calldata.revoker_encryption_c2.x,
+                calldata.revoker_encryption_c2.y,
+            ],
+            proof: calldata.proof,
+            macSalt: calldata.mac_salt,
+            macCommitment: calldata.mac_commitment,
+            memo: calldata.memo,
+        }
+    }
+}
+
+pub struct TransferExtra {
+    pub merkle_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+    /// Receiving tag of the recipient, to which the receipt is bound.
+    pub recipient_tag: U256,
+    /// Public key of the recipient, to which the receipt nonce is encrypted.
+    pub recipient_public_key: GrumpkinPointAffine<U256>,
+    pub encryption_salt: U256,
+    pub anonymity_revoker_public_key: GrumpkinPointAffine<U256>,
+    pub revoker_encryption_salt: U256,
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    pub mac_salt: U256,
+    pub memo: Bytes,
+}
+
+pub enum TransferCallType {}
+impl CallType for TransferCallType {
+    type Extra = TransferExtra;
+    type ProverKnowledge = TransferProverKnowledge<Fr>;
+    type Calldata = TransferCall;
+
+    fn prepare_prover_knowledge(
+        account: &ShielderAccount,
+        token: Token,
+        amount: U256,
+        extra: &Self::Extra,
+    ) -> Self::ProverKnowledge {
+        let ActionSecrets {
+            nullifier_old,
+            nullifier_new,
+            ..
+        } = account.get_secrets();
+
+        let commitment = TransferCommitment {
+            contract_version: extra.contract_version,
+            chain_id: extra.chain_id,
+            memo: extra.memo.clone(),
+        }
+        .commitment_hash();
+
+        TransferProverKnowledge {
+            transfer_value: u256_to_field(amount),
+            commitment: u256_to_field(commitment),
+            id: u256_to_field(account.id),
+            nullifier_old: u256_to_field(nullifier_old),
+            account_old_balance: u256_to_field(account.shielded_amount),
+            token_address: address_to_field(token.address()),
+            path: map_path_to_field(extra.merkle_path),
+            nullifier_new: u256_to_field(nullifier_new),
+            recipient_tag: u256_to_field(extra.recipient_tag),
+            receipt_nonce: u256_to_field(account.next_receipt_nonce()),
+            recipient_public_key: GrumpkinPointAffine {
+                x: u256_to_field(extra.recipient_public_key.x),
+                y: u256_to_field(extra.recipient_public_key.y),
+            },
+            encryption_salt: field_element_to_le_bits::<Fr>(u256_to_field(extra.encryption_salt)),
+            anonymity_revoker_public_key: GrumpkinPointAffine {
+                x: u256_to_field(extra.anonymity_revoker_public_key.x),
+                y: u256_to_field(extra.anonymity_revoker_public_key.y),
+            },
+            revoker_encryption_salt: field_element_to_le_bits::<Fr>(u256_to_field(
+                extra.revoker_encryption_salt,
+            )),
+            mac_salt: u256_to_field(extra.mac_salt),
+        }
+    }
+
+    fn prepare_call_data(
+        pk: &Self::ProverKnowledge,
+        proof: Vec<u8>,
+        extra: &Self::Extra,
+    ) -> Self::Calldata {
+        use shielder_circuits::circuits::transfer::TransferInstance::*;
+        let point = |x, y| {
+            GrumpkinPointAffine::<U256>::new(
+                field_to_u256(pk.compute_public_input(x)),
+                field_to_u256(pk.compute_public_input(y)),
+            )
+        };
+        TransferCall {
+            token: field_to_address(pk.token_address).into(),
+            expected_contract_version: extra.contract_version.to_bytes(),
+            merkle_root: field_to_u256(pk.compute_public_input(MerkleRoot)),
+            old_nullifier_hash: field_to_u256(pk.compute_public_input(HashedOldNullifier)),
+            new_note: field_to_u256(pk.compute_public_input(HashedNewNote)),
+            receipt: field_to_u256(pk.compute_public_input(Receipt)),
+            encrypted_receipt_nonce_c1: point(
+                EncryptedNonceCiphertext1X,
+                EncryptedNonceCiphertext1Y,
+            ),
+            encrypted_receipt_nonce_c2: point(
+                EncryptedNonceCiphertext2X,
+                EncryptedNonceCiphertext2Y,
+            ),
+            encrypted_value: field_to_u256(pk.compute_public_input(EncryptedValue)),
+            revoker_encryption_c1: point(RevokerCiphertext1X, RevokerCiphertext1Y),
+            revoker_encryption_c2: point(RevokerCiphertext2X, RevokerCiphertext2Y),
+            mac_salt: field_to_u256(pk.compute_public_input(MacSalt)),
+            mac_commitment: field_to_u256(pk.compute_public_input(MacCommitment)),
+            proof: Bytes::from(proof),
+            memo: extra.memo.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ClaimCall {
+    pub token: Token,
+    pub expected_contract_version: FixedBytes<3>,
+    pub merkle_root: U256,
+    pub old_nullifier_hash: U256,
+    pub new_note: U256,
+    pub receipt_nullifier: U256,
+    pub encrypted_value: U256,
+    pub mac_salt: U256,
+    pub mac_commitment: U256,
+    pub proof: Bytes,
+    pub memo: Bytes,
+}
+
+impl From<ClaimCall> for claimCall {
+    fn from(calldata: ClaimCall) -> Self {
+        Self {
+            expectedContractVersion: calldata.expected_contract_version,
+            tokenAddress: calldata.token.address(),
+            merkleRoot: calldata.merkle_root,
+            oldNullifierHash: calldata.old_nullifier_hash,
+            newNote: calldata.new_note,
+            receiptNullifier: calldata.receipt_nullifier,
+            encryptedValue: calldata.encrypted_value,
+            proof: calldata.proof,
+            macSalt: calldata.mac_salt,
+            macCommitment: calldata.mac_commitment,
+            memo: calldata.memo,
+        }
+    }
+}
+
+pub struct ClaimExtra {
+    /// Merkle path of the account note.
+    pub merkle_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+    /// The claimed receipt (see `ShielderAccount::received_transfer`).
+    pub receipt: ReceivedTransfer,
+    /// Merkle path of the receipt. Must lead to the same root as `merkle_path`.
+    pub receipt_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    pub mac_salt: U256,
+    pub memo: Bytes,
+}
+
+/// Claim of a received transfer into the account note. The `amount` passed to
+/// `ShielderAccount::prepare_call` is ignored, as the whole value of the receipt is claimed.
+pub enum ClaimCallType {}
+impl CallType for ClaimCallType {
+    type Extra = ClaimExtra;
+    type ProverKnowledge = ClaimProverKnowledge<Fr>;
+    type Calldata = ClaimCall;
+
+    fn prepare_prover_knowledge(
+        account: &ShielderAccount,
+        token: Token,
+        _amount: U256,
+        extra: &Self::Extra,
+    ) -> Self::ProverKnowledge {
+        let ActionSecrets {
+            nullifier_old,
+            nullifier_new,
+            ..
+        } = account.get_secrets();
+
+        let commitment = ClaimCommitment {
+            contract_version: extra.contract_version,
+            chain_id: extra.chain_id,
+            memo: extra.memo.clone(),
+        }
+        .commitment_hash();
+
+        ClaimProverKnowledge {
+            id: u256_to_field(account.id),
+            nullifier_old: u256_to_field(nullifier_old),
+            account_old_balance: u256_to_field(account.shielded_amount),
+            token_address: address_to_field(token.address()),
+            path: map_path_to_field(extra.merkle_path),
+            receipt_nonce: u256_to_field(extra.receipt.nonce),
+            receipt_value: u256_to_field(extra.receipt.value),
+            receipt_path: map_path_to_field(extra.receipt_path),
+            nullifier_new: u256_to_field(nullifier_new),
+            mac_salt: u256_to_field(extra.mac_salt),
+            commitment: u256_to_field(commitment),
+        }
+    }
+
+    fn prepare_call_data(
+        pk: &Self::ProverKnowledge,
+        proof: Vec<u8>,
+        extra: &Self::Extra,
+    ) -> Self::Calldata {
+        use shielder_circuits::circuits::claim::ClaimInstance::*;
+        ClaimCall {
+            token: field_to_address(pk.token_address).into(),
+            expected_contract_version: extra.contract_version.to_bytes(),
+            merkle_root: field_to_u256(pk.compute_public_input(MerkleRoot)),
+            old_nullifier_hash: field_to_u256(pk.compute_public_input(HashedOldNullifier)),
+            new_note: field_to_u256(pk.compute_public_input(HashedNewNote)),
+            receipt_nullifier: field_to_u256(pk.compute_public_input(ReceiptNullifier)),
+            encrypted_value: field_to_u256(pk.compute_public_input(EncryptedValue)),
+            mac_salt: field_to_u256(pk.compute_public_input(MacSalt)),
+            mac_commitment: field_to_u256(pk.compute_public_input(MacCommitment)),
+            proof: Bytes::from(proof),
+            memo: extra.memo.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MigrateCall {
+    pub token: Token,
+    pub expected_contract_version: FixedBytes<3>,
+    pub old_note_version: u8,
+    pub merkle_root: U256,
+    pub old_nullifier_hash: U256,
+    pub new_note: U256,
+    pub mac_salt: U256,
+    pub mac_commitment: U256,
+    pub proof: Bytes,
+    pub memo: Bytes,
+}
+
+impl From<MigrateCall> for migrateCall {
+    fn from(calldata: MigrateCall) -> Self {
+        Self {
+            expectedContractVersion: calldata.expected_contract_version,
+            tokenAddress: calldata.token.address(),
+            oldNoteVersion: calldata.old_note_version,
+            merkleRoot: calldata.merkle_root,
+            oldNullifierHash: calldata.old_nullifier_hash,
+            newNote: calldata.new_note,
+            proof: calldata.proof,
+            macSalt: calldata.mac_salt,
+            macCommitment: calldata.mac_commitment,
+            memo: calldata.memo,
+        }
+    }
+}
+
+pub struct MigrationExtra {
+    pub merkle_path: [[U256; ARITY]; NOTE_TREE_HEIGHT],
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    pub mac_salt: U256,
+    pub memo: Bytes,
+}
+
+/// Migration of the account note to the current note version. The `amount` passed to
+/// `ShielderAccount::prepare_call` is ignored, as the whole balance is carried forward.
+pub enum MigrationCallType {}
+impl CallType for MigrationCallType {
+    type Extra = MigrationExtra;
+    type ProverKnowledge = MigrationProverKnowledge<Fr>;
+    type Calldata = MigrateCall;
+
+    fn prepare_prover_knowledge(
+        account: &ShielderAccount,
+        token: Token,
+        _amount: U256,
+        extra: &Self::Extra,
+    ) -> Self::ProverKnowledge {
+        let ActionSecrets {
+            nullifier_old,
+            nullifier_new,
+            ..
+        } = account.get_secrets();
+
+        let commitment = MigrationCommitment {
+            contract_version: extra.contract_version,
+            chain_id: extra.chain_id,
+            memo: extra.memo.clone(),
+        }
+        .commitment_hash();
+
+        MigrationProverKnowledge {
+            commitment: u256_to_field(commitment),
+            old_note_version: Fr::from(account.note_version as u64),
+            id: u256_to_field(account.id),
+            nullifier_old: u256_to_field(nullifier_old),
+            account_balance: u256_to_field(account.shielded_amount),
+            token_address: address_to_field(token.address()),
+            path: map_path_to_field(extra.merkle_path),
+            nullifier_new: u256_to_field(nullifier_new),
+            mac_salt: u256_to_field(extra.mac_salt),
+        }
+    }
+
+    fn prepare_call_data(
+        pk: &Self::ProverKnowledge,
+        proof: Vec<u8>,
+        extra: &Self::Extra,
+    ) -> Self::Calldata {
+        use shielder_circuits::circuits::migration::MigrationInstance::*;
+        MigrateCall {
+            token: field_to_address(pk.token_address).into(),
+            expected_contract_version: extra.contract_version.to_bytes(),
+            old_note_version: field_to_u256(pk.compute_public_input(OldNoteVersion)).to::<u8>(),
+            merkle_root: field_to_u256(pk.compute_public_input(MerkleRoot)),
+            old_nullifier_hash: field_to_u256(pk.compute_public_input(HashedOldNullifier)),
+            new_note: field_to_u256(pk.compute_public_input(HashedNewNote)),
+            mac_salt: field_to_u256(pk.compute_public_input(MacSalt)),
+            mac_commitment: field_to_u256(pk.compute_public_input(MacCommitment)),
+            proof: Bytes::from(proof),
+            memo: extra.memo.clone(),
+        }
+    }
+}
+
 impl ShielderAccount {
+    /// Prepare the call data, proving locally with `params` and `pk`.
     pub fn prepare_call<CT: CallType>(
         &self,
         params: &Params,
@@ -475,6 +1042,20 @@ impl ShielderAccount {
         CT::prepare_call_data(&prover_knowledge, proof, extra)
     }
 
+    /// Prepare the call data, proving with `backend`. With [`crate::proving::LocalProver`], this is
+    /// equivalent to `prepare_call`.
+    pub async fn prepare_call_with<CT: CallType>(
+        &self,
+        backend: &impl ProvingBackend,
+        token: Token,
+        amount: U256,
+        extra: &CT::Extra,
+    ) -> Result<CT::Calldata, ProvingError> {
+        let prover_knowledge = CT::prepare_prover_knowledge(self, token, amount, extra);
+        let proof = backend.prove(&prover_knowledge).await?;
+        Ok(CT::prepare_call_data(&prover_knowledge, proof, extra))
+    }
+
     fn get_secrets(&self) -> ActionSecrets {
         let nullifier_old = self.previous_nullifier();
         let nullifier_new = self.next_nullifier();
@@ -486,22 +1067,11 @@ impl ShielderAccount {
     }
 }
 
-fn generate_proof(
-    params: &Params,
-    pk: &ProvingKey,
-    prover_knowledge: &impl ProverKnowledge,
-) -> Vec<u8> {
-    shielder_circuits::generate_proof(
-        params,
-        pk,
-        prover_knowledge.create_circuit(),
-        &prover_knowledge.serialize_public_input(),
-        &mut OsRng,
-    )
-}
-
-fn map_path_to_field(path: [[U256; ARITY]; NOTE_TREE_HEIGHT]) -> [[Fr; ARITY]; NOTE_TREE_HEIGHT] {
-    let mut result = [[Fr::ZERO; ARITY]; NOTE_TREE_HEIGHT];
+/// Convert a Merkle path, as returned by the Shielder contract, into field elements.
+pub fn map_path_to_field<const TREE_HEIGHT: usize>(
+    path: [[U256; ARITY]; TREE_HEIGHT],
+) -> [[Fr; ARITY]; TREE_HEIGHT] {
+    let mut result = [[Fr::ZERO; ARITY]; TREE_HEIGHT];
     for (i, row) in path.iter().enumerate() {
         for (j, element) in row.iter().enumerate() {
             result[i][j] = u256_to_field(*element);
@@ -0,0 +1,92 @@
+use alloy_primitives::U256;
+
+use crate::{
+    secrets::{derive_id, derive_token_key},
+    ShielderAccount, Token,
+};
+
+/// Deterministic derivation of many unlinkable shielded accounts from a single private key.
+///
+/// Account `index` for a given token on a given chain always gets the same ID, so a lost state can
+/// be recovered by deriving consecutive accounts and looking for their actions on chain.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Keyring {
+    private_key: U256,
+    chain_id: u64,
+}
+
+impl Keyring {
+    pub fn new(private_key: U256, chain_id: u64) -> Self {
+        Self {
+            private_key,
+            chain_id,
+        }
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// The seed of the ID of the account `index` for `token`.
+    pub fn id_seed(&self, token: Token, index: u32) -> U256 {
+        let token_key = derive_token_key(self.private_key, token.address());
+        derive_id(token_key, self.chain_id, index)
+    }
+
+    /// A fresh (without any history) account `index` for `token`.
+    pub fn account(&self, token: Token, index: u32) -> ShielderAccount {
+        ShielderAccount::new(self.id_seed(token, index), token)
+    }
+
+    /// Fresh accounts for `token`, in the order of their indices, starting from `from`.
+    pub fn accounts(
+        &self,
+        token: Token,
+        from: u32,
+    ) -> impl Iterator<Item = (u32, ShielderAccount)> + '_ {
+        (from..=u32::MAX).map(move |index| (index, self.account(token, index)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+
+    use crate::{keyring::Keyring, Token};
+
+    const TOKEN: Token = Token::ERC20(Address::repeat_byte(0x11));
+
+    #[test]
+    fn derivation_is_deterministic() {
+        let keyring = Keyring::new(U256::from(42), 1);
+        assert_eq!(
+            keyring.account(TOKEN, 3),
+            Keyring::new(U256::from(42), 1).account(TOKEN, 3)
+        );
+    }
+
+    #[test]
+    fn accounts_are_separated_by_index_token_chain_and_key() {
+        let keyring = Keyring::new(U256::from(42), 1);
+        let id = keyring.account(TOKEN, 0).id;
+
+        assert_ne!(id, keyring.account(TOKEN, 1).id);
+        assert_ne!(id, keyring.account(Token::Native, 0).id);
+        assert_ne!(id, Keyring::new(U256::from(42), 2).account(TOKEN, 0).id);
+        assert_ne!(id, Keyring::new(U256::from(43), 1).account(TOKEN, 0).id);
+    }
+
+    #[test]
+    fn accounts_iterator_follows_indices() {
+        let keyring = Keyring::new(U256::from(42), 1);
+        let accounts = keyring.accounts(TOKEN, 5).take(2).collect::<Vec<_>>();
+
+        assert_eq!(
+            accounts,
+            vec![
+                (5, keyring.account(TOKEN, 5)),
+                (6, keyring.account(TOKEN, 6))
+            ]
+        );
+    }
+}
@@ -4,13 +4,19 @@ use alloy_primitives::{Address, U256};
 use halo2curves::bn256::Fr;
 use serde::{Deserialize, Serialize};
 
+pub mod balance_attestation;
 #[cfg(feature = "contract")]
 pub mod call_data;
+pub mod keyring;
+pub mod proving;
 pub mod secrets;
 mod shielder_action;
 
 pub use shielder_action::{ShielderAction, ShielderTxData};
-use shielder_circuits::{generate_user_id, note_hash, Note};
+use shielder_circuits::{
+    association_set_leaf, derive_viewing_key, generate_receipt_nonce, generate_user_id, note_hash,
+    receipt_nullifier, receiving_tag, Note, NoteVersion,
+};
 use shielder_setup::{native_token::NATIVE_TOKEN_ADDRESS, version::contract_version};
 use type_conversions::{address_to_field, field_to_address, field_to_u256, u256_to_field};
 
@@ -31,6 +37,11 @@ pub struct ShielderAccount {
     pub shielded_amount: U256,
     /// The history of actions performed by the account.
     pub history: Vec<ShielderAction>,
+    /// The version of the current note of the account. Notes are always created with the note
+    /// version of the SDK, so this differs from it only if the account was created with an older
+    /// SDK. Such a note has to be migrated before the next action (see `Self::requires_migration`).
+    #[serde(default)]
+    pub note_version: u8,
 }
 
 impl Display for ShielderAccount {
@@ -40,6 +51,7 @@ impl Display for ShielderAccount {
             .field("nonce", &self.nonce)
             .field("token", &self.token)
             .field("shielded_amount", &self.shielded_amount)
+            .field("note_version", &self.note_version)
             .field("current_leaf_index", &self.current_leaf_index())
             .finish()
     }
@@ -77,18 +89,32 @@ impl ShielderAccount {
                     .checked_sub(data.amount)
                     .expect("shielded amount underflow");
             }
+            ShielderAction::Transfer {
+                encrypted_value, ..
+            } => {
+                self.shielded_amount = self
+                    .shielded_amount
+                    .checked_sub(self.transfer_amount(*encrypted_value))
+                    .expect("shielded amount underflow");
+            }
+            ShielderAction::Claim {
+                encrypted_value, ..
+            } => {
+                self.shielded_amount = self
+                    .shielded_amount
+                    .checked_add(self.claimed_amount(*encrypted_value))
+                    .expect("shielded amount overflow");
+            }
+            ShielderAction::Migrate { .. } => {}
         }
+        self.note_version = contract_version().note_version;
         self.nonce += 1;
         self.history.push(action);
     }
 
     /// Get the index of the last leaf in the Merkle tree containing the account's note.
     pub fn current_leaf_index(&self) -> Option<U256> {
-        self.history.last().map(|action| match action {
-            ShielderAction::NewAccount(data)
-            | ShielderAction::Deposit(data)
-            | ShielderAction::Withdraw { data, .. } => data.note_index,
-        })
+        self.history.last().map(ShielderAction::note_index)
     }
 
     /// Compute note representing current state. `None` if no operations have been performed.
@@ -96,14 +122,54 @@ impl ShielderAccount {
         if self.nonce == 0 {
             return None;
         }
-        let raw_note: Fr = note_hash(&Note {
-            version: contract_version().note_version(),
-            id: u256_to_field(self.id),
-            nullifier: u256_to_field(self.previous_nullifier()),
-            account_balance: u256_to_field(self.shielded_amount),
-            token_address: address_to_field(token.address()),
-        });
-        Some(field_to_u256(raw_note))
+        Some(compute_note(
+            NoteVersion::new(self.note_version),
+            self.id,
+            self.previous_nullifier(),
+            self.shielded_amount,
+            token,
+        ))
+    }
+
+    /// Compute the note that the account would have after performing `action` as its next action.
+    /// `None` if the action cannot be performed by the account (e.g. it concerns another token or
+    /// withdraws more than the shielded amount).
+    pub fn note_after(&self, action: &ShielderAction) -> Option<U256> {
+        if action.token() != self.token {
+            return None;
+        }
+        let shielded_amount = match action {
+            ShielderAction::Deposit(data) | ShielderAction::NewAccount(data) => self
+                .shielded_amount
+                .checked_add(data.amount.checked_sub(data.protocol_fee)?)?,
+            ShielderAction::Withdraw { data, .. } => {
+                self.shielded_amount.checked_sub(data.amount)?
+            }
+            ShielderAction::Transfer {
+                encrypted_value, ..
+            } => self
+                .shielded_amount
+                .checked_sub(self.transfer_amount(*encrypted_value))?,
+            ShielderAction::Claim {
+                encrypted_value, ..
+            } => self
+                .shielded_amount
+                .checked_add(self.claimed_amount(*encrypted_value))?,
+            ShielderAction::Migrate { .. } => self.shielded_amount,
+        };
+        Some(compute_note(
+            contract_version().note_version(),
+            self.id,
+            self.next_nullifier(),
+            shielded_amount,
+            self.token,
+        ))
+    }
+
+    /// Whether the current note of the account has a different version than the notes created by
+    /// the SDK. Such a note cannot be used in any action other than migration.
+    pub fn requires_migration(&self) -> bool {
+        self.nonce > 0 && self.note_version != contract_version().note_version
     }
 
     /// Get the prenullifier (the nullifier of the first action - new account).
@@ -111,6 +177,12 @@ impl ShielderAccount {
         self.id
     }
 
+    /// The leaf representing the account in association sets. This is the hashed prenullifier that
+    /// is published in the `NewAccount` event.
+    pub fn association_set_leaf(&self) -> U256 {
+        field_to_u256(association_set_leaf(u256_to_field(self.id)))
+    }
+
     /// Generate the nullifier for the next action to be done.
     pub fn next_nullifier(&self) -> U256 {
         secrets::nonced::derive_nullifier(self.id, self.nonce)
@@ -124,6 +196,110 @@ impl ShielderAccount {
             |nonce| secrets::nonced::derive_nullifier(self.id, nonce),
         )
     }
+
+    /// The receiving tag of the account. Transfers to the account create receipts bound to the
+    /// tag, which can be claimed only with the account ID.
+    pub fn receiving_tag(&self) -> U256 {
+        field_to_u256(receiving_tag(u256_to_field(self.id)))
+    }
+
+    /// Generate the nonce of the receipt, if the next action is a transfer. It is derived from the
+    /// account secrets, so that the transferred amount can be recovered from the chain.
+    pub fn next_receipt_nonce(&self) -> U256 {
+        let seed = secrets::nonced::derive_recipient_seed(self.id, self.nonce);
+        field_to_u256(generate_receipt_nonce(u256_to_field::<Fr>(seed).to_bytes()))
+    }
+
+    /// Unmask the amount of the transfer that is the next action of the account.
+    pub fn transfer_amount(&self, encrypted_value: U256) -> U256 {
+        let value_key = derive_viewing_key(u256_to_field(self.next_receipt_nonce()));
+        field_to_u256(u256_to_field::<Fr>(encrypted_value) - value_key)
+    }
+
+    /// Unmask the amount of the claim that is the next action of the account.
+    pub fn claimed_amount(&self, encrypted_value: U256) -> U256 {
+        let value_key = derive_viewing_key(u256_to_field(self.previous_nullifier()));
+        field_to_u256(u256_to_field::<Fr>(encrypted_value) - value_key)
+    }
+
+    /// The nullifier published when the account claims `receipt`.
+    pub fn receipt_nullifier(&self, receipt: U256) -> U256 {
+        field_to_u256(receipt_nullifier(
+            u256_to_field(self.id),
+            u256_to_field(receipt),
+        ))
+    }
+
+    /// Find out whether the receipt of `event` can be claimed by the account. The receipt nonce is
+    /// decrypted with `private_key` and the receipt is recomputed with the receiving tag of the
+    /// account. If the nonce was not encrypted to `private_key` or the receipt is not bound to the
+    /// account, `None` is returned.
+    #[cfg(feature = "contract")]
+    pub fn received_transfer(
+        &self,
+        private_key: shielder_circuits::grumpkin::Fr,
+        event: &shielder_contract::ShielderContract::Transfer,
+    ) -> Option<ReceivedTransfer> {
+        use shielder_circuits::{decrypt, receipt_hash, GrumpkinPointAffine, Receipt};
+
+        if Token::from(event.tokenAddress) != self.token {
+            return None;
+        }
+
+        let [c1x, c1y, c2x, c2y] = event.encryptedReceiptNonce.map(u256_to_field::<Fr>);
+        let message: GrumpkinPointAffine<Fr> = decrypt(
+            GrumpkinPointAffine::new(c1x, c1y).into(),
+            GrumpkinPointAffine::new(c2x, c2y).into(),
+            private_key,
+        )
+        .into();
+        let nonce = message.x;
+        let value = u256_to_field::<Fr>(event.encryptedValue) - derive_viewing_key(nonce);
+
+        let receipt = receipt_hash(&Receipt {
+            tag: u256_to_field(self.receiving_tag()),
+            nonce,
+            value,
+            token_address: address_to_field(self.token.address()),
+        });
+        if field_to_u256(receipt) != event.receipt {
+            return None;
+        }
+        Some(ReceivedTransfer {
+            receipt: event.receipt,
+            receipt_index: event.receiptIndex,
+            nonce: field_to_u256(nonce),
+            value: field_to_u256(value),
+        })
+    }
+}
+
+/// Receipt of a transfer that can be claimed by the account (see
+/// `ShielderAccount::received_transfer`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ReceivedTransfer {
+    pub receipt: U256,
+    /// Index of the receipt leaf in the note tree.
+    pub receipt_index: U256,
+    pub nonce: U256,
+    pub value: U256,
+}
+
+fn compute_note(
+    version: NoteVersion,
+    id: U256,
+    nullifier: U256,
+    shielded_amount: U256,
+    token: Token,
+) -> U256 {
+    let raw_note: Fr = note_hash(&Note {
+        version,
+        id: u256_to_field(id),
+        nullifier: u256_to_field(nullifier),
+        account_balance: u256_to_field(shielded_amount),
+        token_address: address_to_field(token.address()),
+    });
+    field_to_u256(raw_note)
 }
 
 #[derive(
@@ -153,3 +329,150 @@ impl From<Address> for Token {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, TxHash, U256};
+    use shielder_circuits::{derive_viewing_key, Fr};
+    use type_conversions::{field_to_u256, u256_to_field};
+
+    use crate::{ShielderAccount, ShielderAction, Token};
+
+    #[test]
+    fn note_after_matches_note_of_updated_account() {
+        let mut account = ShielderAccount::new(U256::from(1), Token::Native);
+        let actions = [
+            ShielderAction::new_account(
+                U256::from(100),
+                U256::ZERO,
+                TxHash::ZERO,
+                Token::Native,
+                U256::from(1),
+            ),
+            ShielderAction::deposit(
+                U256::from(50),
+                U256::from(1),
+                TxHash::ZERO,
+                Token::Native,
+                U256::ZERO,
+            ),
+            ShielderAction::withdraw(
+                U256::from(20),
+                U256::from(2),
+                TxHash::ZERO,
+                Address::ZERO,
+                Token::Native,
+                U256::ZERO,
+            ),
+        ];
+
+        for action in actions {
+            let expected = account.note_after(&action);
+            account.register_action(action);
+            assert_eq!(expected, account.note(Token::Native));
+        }
+    }
+
+    #[test]
+    fn note_after_rejects_foreign_or_impossible_actions() {
+        let account = ShielderAccount::new(U256::from(1), Token::Native);
+
+        let other_token = ShielderAction::deposit(
+            U256::from(50),
+            U256::ZERO,
+            TxHash::ZERO,
+            Token::ERC20(Address::repeat_byte(1)),
+            U256::ZERO,
+        );
+        let too_big_withdrawal = ShielderAction::withdraw(
+            U256::from(1),
+            U256::ZERO,
+            TxHash::ZERO,
+            Address::ZERO,
+            Token::Native,
+            U256::ZERO,
+        );
+
+        assert_eq!(account.note_after(&other_token), None);
+        assert_eq!(account.note_after(&too_big_withdrawal), None);
+    }
+
+    #[test]
+    fn transfer_decreases_shielded_amount_by_unmasked_value() {
+        let mut account = ShielderAccount::new(U256::from(1), Token::Native);
+        account.register_action(ShielderAction::new_account(
+            U256::from(100),
+            U256::ZERO,
+            TxHash::ZERO,
+            Token::Native,
+            U256::ZERO,
+        ));
+
+        let value_key = derive_viewing_key(u256_to_field(account.next_receipt_nonce()));
+        let transfer = ShielderAction::transfer(
+            field_to_u256(Fr::from(30) + value_key),
+            U256::from(1),
+            TxHash::ZERO,
+            Token::Native,
+        );
+
+        let expected = account.note_after(&transfer);
+        account.register_action(transfer);
+        assert_eq!(account.shielded_amount, U256::from(70));
+        assert_eq!(expected, account.note(Token::Native));
+    }
+
+    #[test]
+    fn claim_increases_shielded_amount_by_unmasked_value() {
+        let mut account = ShielderAccount::new(U256::from(1), Token::Native);
+        account.register_action(ShielderAction::new_account(
+            U256::from(100),
+            U256::ZERO,
+            TxHash::ZERO,
+            Token::Native,
+            U256::ZERO,
+        ));
+
+        let value_key = derive_viewing_key(u256_to_field(account.previous_nullifier()));
+        let claim = ShielderAction::claim(
+            field_to_u256(Fr::from(30) + value_key),
+            U256::from(2),
+            TxHash::ZERO,
+            Token::Native,
+        );
+
+        let expected = account.note_after(&claim);
+        account.register_action(claim);
+        assert_eq!(account.shielded_amount, U256::from(130));
+        assert_eq!(expected, account.note(Token::Native));
+    }
+
+    #[test]
+    fn migration_keeps_shielded_amount_and_updates_note_version() {
+        let mut account = ShielderAccount::new(U256::from(1), Token::Native);
+        account.register_action(ShielderAction::new_account(
+            U256::from(100),
+            U256::ZERO,
+            TxHash::ZERO,
+            Token::Native,
+            U256::ZERO,
+        ));
+        assert!(!account.requires_migration());
+
+        account.note_version += 1;
+        assert!(account.requires_migration());
+
+        let migration = ShielderAction::migrate(
+            account.note_version,
+            U256::from(1),
+            TxHash::ZERO,
+            Token::Native,
+        );
+        let expected = account.note_after(&migration);
+        account.register_action(migration);
+
+        assert!(!account.requires_migration());
+        assert_eq!(account.shielded_amount, U256::from(100));
+        assert_eq!(expected, account.note(Token::Native));
+    }
+}
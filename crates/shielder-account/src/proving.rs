@@ -0,0 +1,257 @@
+//! Proving backends used to prepare Shielder calls (see `ShielderAccount::prepare_call_with`).
+//!
+//! A proof can be generated locally, with in-memory parameters and proving key
+//! ([`LocalProver`]), by the remote TEE prover server ([`TeeProver`], behind the `tee-prover`
+//! feature), or faked in tests ([`MockProver`]). [`Prover`] selects one of them at runtime.
+
+use std::future::Future;
+
+use rand::rngs::OsRng;
+use shielder_circuits::{
+    balance_attestation::BalanceAttestationProverKnowledge,
+    batch_withdraw::BatchWithdrawProverKnowledge,
+    circuits::{Params, ProvingKey},
+    claim::ClaimProverKnowledge,
+    deposit::DepositProverKnowledge,
+    migration::MigrationProverKnowledge,
+    new_account::NewAccountProverKnowledge,
+    transfer::TransferProverKnowledge,
+    withdraw::WithdrawProverKnowledge,
+    Fr, ProverKnowledge,
+};
+#[cfg(feature = "tee-prover")]
+use shielder_prover_circuits::{
+    deposit::{DepositProveInputBytes, SerializableDepositCircuit},
+    new_account::{NewAccountProveInputsBytes, SerializableNewAccountCircuit},
+    withdraw::{SerializableWithdrawCircuit, WithdrawProveInputsBytes},
+};
+#[cfg(feature = "tee-prover")]
+use shielder_prover_client::TeeProverClient;
+#[cfg(feature = "tee-prover")]
+pub use shielder_prover_client::{Pcrs, Policy as AttestationPolicy, PCR_LENGTH};
+#[cfg(feature = "tee-prover")]
+pub use tee::TeeProver;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProvingError {
+    #[error("The {0} circuit is not supported by this proving backend")]
+    UnsupportedCircuit(&'static str),
+    #[error("TEE prover failed: {0}")]
+    Tee(String),
+}
+
+/// Prover knowledge of a circuit that can be proven by a [`ProvingBackend`].
+pub trait Provable: ProverKnowledge + Sync {
+    /// Human-readable name of the circuit, used in errors.
+    const CIRCUIT_NAME: &'static str;
+
+    /// Prove with the TEE prover server. Fails with [`ProvingError::UnsupportedCircuit`] if the
+    /// server cannot prove this circuit.
+    #[cfg(feature = "tee-prover")]
+    fn prove_with_tee(
+        &self,
+        _client: &TeeProverClient,
+    ) -> impl Future<Output = Result<Vec<u8>, ProvingError>> + Send {
+        std::future::ready(Err(ProvingError::UnsupportedCircuit(Self::CIRCUIT_NAME)))
+    }
+}
+
+/// A way of generating proofs for Shielder circuits.
+pub trait ProvingBackend {
+    /// Generate a proof for `knowledge`.
+    fn prove<PK: Provable>(
+        &self,
+        knowledge: &PK,
+    ) -> impl Future<Output = Result<Vec<u8>, ProvingError>> + Send;
+}
+
+/// Proves in-process, with the given parameters and proving key.
+#[derive(Copy, Clone, Debug)]
+pub struct LocalProver<'a> {
+    params: &'a Params,
+    pk: &'a ProvingKey,
+}
+
+impl<'a> LocalProver<'a> {
+    pub fn new(params: &'a Params, pk: &'a ProvingKey) -> Self {
+        Self { params, pk }
+    }
+}
+
+impl ProvingBackend for LocalProver<'_> {
+    async fn prove<PK: Provable>(&self, knowledge: &PK) -> Result<Vec<u8>, ProvingError> {
+        Ok(generate_proof(self.params, self.pk, knowledge))
+    }
+}
+
+/// Returns a fixed proof without proving anything. Only for tests.
+#[derive(Clone, Debug, Default)]
+pub struct MockProver {
+    proof: Vec<u8>,
+}
+
+impl MockProver {
+    pub fn new(proof: Vec<u8>) -> Self {
+        Self { proof }
+    }
+}
+
+impl ProvingBackend for MockProver {
+    async fn prove<PK: Provable>(&self, _knowledge: &PK) -> Result<Vec<u8>, ProvingError> {
+        Ok(self.proof.clone())
+    }
+}
+
+/// Proving backend chosen at runtime, e.g. from a configuration flag.
+#[derive(Clone, Debug)]
+pub enum Prover<'a> {
+    Local(LocalProver<'a>),
+    #[cfg(feature = "tee-prover")]
+    Tee(TeeProver),
+    Mock(MockProver),
+}
+
+impl ProvingBackend for Prover<'_> {
+    async fn prove<PK: Provable>(&self, knowledge: &PK) -> Result<Vec<u8>, ProvingError> {
+        match self {
+            Prover::Local(prover) => prover.prove(knowledge).await,
+            #[cfg(feature = "tee-prover")]
+            Prover::Tee(prover) => prover.prove(knowledge).await,
+            Prover::Mock(prover) => prover.prove(knowledge).await,
+        }
+    }
+}
+
+pub(crate) fn generate_proof(
+    params: &Params,
+    pk: &ProvingKey,
+    prover_knowledge: &impl ProverKnowledge,
+) -> Vec<u8> {
+    shielder_circuits::generate_proof(
+        params,
+        pk,
+        prover_knowledge.create_circuit(),
+        &prover_knowledge.serialize_public_input(),
+        &mut OsRng,
+    )
+}
+
+impl Provable for NewAccountProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "new account";
+
+    #[cfg(feature = "tee-prover")]
+    async fn prove_with_tee(&self, client: &TeeProverClient) -> Result<Vec<u8>, ProvingError> {
+        tee::prove::<SerializableNewAccountCircuit>(client, NewAccountProveInputsBytes::from(self))
+            .await
+    }
+}
+
+impl Provable for DepositProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "deposit";
+
+    #[cfg(feature = "tee-prover")]
+    async fn prove_with_tee(&self, client: &TeeProverClient) -> Result<Vec<u8>, ProvingError> {
+        tee::prove::<SerializableDepositCircuit>(client, DepositProveInputBytes::from(self)).await
+    }
+}
+
+impl Provable for WithdrawProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "withdraw";
+
+    #[cfg(feature = "tee-prover")]
+    async fn prove_with_tee(&self, client: &TeeProverClient) -> Result<Vec<u8>, ProvingError> {
+        tee::prove::<SerializableWithdrawCircuit>(client, WithdrawProveInputsBytes::from(self))
+            .await
+    }
+}
+
+impl Provable for TransferProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "transfer";
+}
+
+impl Provable for ClaimProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "claim";
+}
+
+impl Provable for MigrationProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "migration";
+}
+
+impl Provable for BatchWithdrawProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "batch withdraw";
+}
+
+impl Provable for BalanceAttestationProverKnowledge<Fr> {
+    const CIRCUIT_NAME: &'static str = "balance attestation";
+}
+
+#[cfg(feature = "tee-prover")]
+mod tee {
+    use shielder_prover_client::{Policy, TeeCircuit, TeeProverClient, TeeProverClientError};
+
+    use super::{Provable, ProvingBackend, ProvingError};
+
+    impl From<TeeProverClientError> for ProvingError {
+        fn from(e: TeeProverClientError) -> Self {
+            ProvingError::Tee(e.to_string())
+        }
+    }
+
+    /// Proves remotely, with the TEE prover server (`tee/crates/shielder-prover-server`).
+    ///
+    /// The witness is encrypted with the public key of the TEE, and the proof with a fresh
+    /// ephemeral key of the client, so neither is visible on the way. The enclave itself reads the
+    /// witness: it stays private only if the public key is attested to belong to a trusted enclave
+    /// image. Otherwise whoever runs the server can read it.
+    #[derive(Clone, Debug)]
+    pub struct TeeProver {
+        client: TeeProverClient,
+    }
+
+    impl TeeProver {
+        /// Prover trusting the TEE public key only if its attestation satisfies `policy`.
+        pub fn new(url: impl Into<String>, policy: Policy) -> Self {
+            Self::from_client(TeeProverClient::new(url, policy))
+        }
+
+        /// Prover trusting any public key returned by the server. Whoever runs the server can then
+        /// read the witnesses, so use it only with servers built without attestation.
+        pub fn new_insecure_unattested(url: impl Into<String>) -> Self {
+            Self::from_client(TeeProverClient::new_insecure_unattested(url))
+        }
+
+        /// Use a preconfigured client.
+        pub fn from_client(client: TeeProverClient) -> Self {
+            Self { client }
+        }
+    }
+
+    impl ProvingBackend for TeeProver {
+        async fn prove<PK: Provable>(&self, knowledge: &PK) -> Result<Vec<u8>, ProvingError> {
+            knowledge.prove_with_tee(&self.client).await
+        }
+    }
+
+    pub(super) async fn prove<C: TeeCircuit>(
+        client: &TeeProverClient,
+        inputs: C::Input,
+    ) -> Result<Vec<u8>, ProvingError> {
+        Ok(client.generate_proof::<C>(inputs).await?.proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use shielder_circuits::{transfer::TransferProverKnowledge, Fr, ProverKnowledge};
+
+    use super::{MockProver, Prover, ProvingBackend};
+
+    #[tokio::test]
+    async fn prover_dispatches_to_the_selected_backend() {
+        let prover = Prover::Mock(MockProver::new(vec![1, 2, 3]));
+        let knowledge = TransferProverKnowledge::<Fr>::random_correct_example(&mut OsRng);
+
+        assert_eq!(prover.prove(&knowledge).await.unwrap(), vec![1, 2, 3]);
+    }
+}
@@ -1,9 +1,11 @@
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use sha3::Digest;
 
 enum Label {
     Nullifier,
     Id,
+    Token,
+    Recipient,
 }
 
 impl Label {
@@ -11,6 +13,8 @@ impl Label {
         match self {
             Label::Nullifier => b"nullifier",
             Label::Id => b"id",
+            Label::Token => b"token",
+            Label::Recipient => b"recipient",
         }
     }
 }
@@ -43,6 +47,16 @@ pub mod nonced {
         hasher.update(nonce.to_be_bytes());
         finalize_hash(hasher)
     }
+
+    /// Returns a pseudorandom field element deterministically computed from `id` and `nonce`,
+    /// used as a starting point for the receipt nonce of a transfer.
+    pub fn derive_recipient_seed(id: U256, nonce: u32) -> U256 {
+        let mut hasher = sha3::Keccak256::new();
+        hasher.update(id.to_be_bytes_vec());
+        hasher.update(Label::Recipient.as_bytes());
+        hasher.update(nonce.to_be_bytes());
+        finalize_hash(hasher)
+    }
 }
 
 /// Private-key-dependent derivation of a per-chain & per-token private ID.
@@ -55,14 +69,28 @@ pub fn derive_id(private_key: U256, chain_id: u64, account_nonce: u32) -> U256 {
     finalize_hash(hasher)
 }
 
+/// Private-key-dependent derivation of a per-token private key, which can be passed to
+/// [`derive_id`] in place of the master private key to get accounts separated by token.
+pub fn derive_token_key(private_key: U256, token_address: Address) -> U256 {
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update(private_key.to_be_bytes_vec());
+    hasher.update(Label::Token.as_bytes());
+    hasher.update(token_address.as_slice());
+    finalize_hash(hasher)
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use alloy_primitives::U256;
+    use alloy_primitives::{Address, U256};
     use halo2curves::{bn256::Fr, ff::PrimeField};
 
-    use crate::secrets::{derive_id, nonced::derive_nullifier, FIELD_MODULUS};
+    use crate::secrets::{
+        derive_id, derive_token_key,
+        nonced::{derive_nullifier, derive_recipient_seed},
+        FIELD_MODULUS,
+    };
 
     #[test]
     pub fn modulus_constant_is_correct() {
@@ -88,6 +116,19 @@ mod tests {
         assert_eq!(expected_before_modulo.reduce_mod(FIELD_MODULUS), actual)
     }
 
+    #[test]
+    pub fn derive_recipient_seed_is_correct() {
+        // Calculated using online tools as the Keccak-256 of the concatenation of:
+        //   000000000000000000000000000000000000000000000000000000000000000f
+        //   726563697069656e74 ("recipient")
+        //   000000ff
+        let expected =
+            U256::from_str("0x0a476997de811c4b57565c6631d1ff5d22c763fecef48f40e4d5357794a57b56")
+                .unwrap();
+
+        assert_eq!(expected, derive_recipient_seed(U256::from(15), 0x000000ff));
+    }
+
     #[test]
     pub fn derive_id_is_correct() {
         // Calculated using online tools as the Keccak-256 of the concatenation of:
@@ -104,4 +145,20 @@ mod tests {
         assert_ne!(expected_before_modulo, actual);
         assert_eq!(expected_before_modulo.reduce_mod(FIELD_MODULUS), actual)
     }
+
+    #[test]
+    pub fn derive_token_key_is_correct() {
+        // Calculated using online tools as the Keccak-256 of the concatenation of:
+        //   0000000000000000000000000000000000000000000000000000000000000010
+        //   746f6b656e ("token")
+        //   1111111111111111111111111111111111111111
+        let expected_before_modulo =
+            U256::from_str("0xa8b8c96c8f1f9ec54cc24c22d2571cddf4d3ae7219a57372c8936bc0ee80fdc8")
+                .unwrap();
+
+        let actual = derive_token_key(U256::from(16), Address::repeat_byte(0x11));
+
+        assert_ne!(expected_before_modulo, actual);
+        assert_eq!(expected_before_modulo.reduce_mod(FIELD_MODULUS), actual)
+    }
 }
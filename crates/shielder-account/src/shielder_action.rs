@@ -1,7 +1,9 @@
 use alloy_primitives::{Address, TxHash, U256};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "contract")]
-use shielder_contract::ShielderContract::{Deposit, NewAccount, ShielderContractEvents, Withdraw};
+use shielder_contract::ShielderContract::{
+    Claim, Deposit, Migrate, NewAccount, ShielderContractEvents, Transfer, Withdraw,
+};
 
 use crate::Token;
 
@@ -9,7 +11,35 @@ use crate::Token;
 pub enum ShielderAction {
     NewAccount(ShielderTxData),
     Deposit(ShielderTxData),
-    Withdraw { to: Address, data: ShielderTxData },
+    Withdraw {
+        to: Address,
+        data: ShielderTxData,
+    },
+    /// Transfer to another shielded account. The amount is not public, only masked with the
+    /// viewing key of the receipt nonce (see `ShielderAccount::transfer_amount`).
+    Transfer {
+        encrypted_value: U256,
+        note_index: U256,
+        tx_hash: TxHash,
+        token: Token,
+    },
+    /// Claim of a receipt of a transfer from another shielded account into the account note. The
+    /// amount is masked with the viewing key of the previous nullifier (see
+    /// `ShielderAccount::claimed_amount`).
+    Claim {
+        encrypted_value: U256,
+        note_index: U256,
+        tx_hash: TxHash,
+        token: Token,
+    },
+    /// Migration of the account note from `old_note_version` to the current note version. The
+    /// shielded amount does not change.
+    Migrate {
+        old_note_version: u8,
+        note_index: U256,
+        tx_hash: TxHash,
+        token: Token,
+    },
 }
 
 #[cfg(feature = "contract")]
@@ -57,6 +87,24 @@ impl From<(TxHash, ShielderContractEvents)> for ShielderAction {
                 tokenAddress.into(),
                 protocolFee,
             ),
+            ShielderContractEvents::Transfer(Transfer {
+                encryptedValue,
+                newNoteIndex,
+                tokenAddress,
+                ..
+            }) => Self::transfer(encryptedValue, newNoteIndex, tx_hash, tokenAddress.into()),
+            ShielderContractEvents::Claim(Claim {
+                encryptedValue,
+                newNoteIndex,
+                tokenAddress,
+                ..
+            }) => Self::claim(encryptedValue, newNoteIndex, tx_hash, tokenAddress.into()),
+            ShielderContractEvents::Migrate(Migrate {
+                oldNoteVersion,
+                newNoteIndex,
+                tokenAddress,
+                ..
+            }) => Self::migrate(oldNoteVersion, newNoteIndex, tx_hash, tokenAddress.into()),
         }
     }
 }
@@ -114,11 +162,57 @@ impl ShielderAction {
         }
     }
 
+    pub fn transfer(
+        encrypted_value: U256,
+        note_index: U256,
+        tx_hash: TxHash,
+        token: Token,
+    ) -> Self {
+        Self::Transfer {
+            encrypted_value,
+            note_index,
+            tx_hash,
+            token,
+        }
+    }
+
+    pub fn claim(encrypted_value: U256, note_index: U256, tx_hash: TxHash, token: Token) -> Self {
+        Self::Claim {
+            encrypted_value,
+            note_index,
+            tx_hash,
+            token,
+        }
+    }
+
+    pub fn migrate(old_note_version: u8, note_index: U256, tx_hash: TxHash, token: Token) -> Self {
+        Self::Migrate {
+            old_note_version,
+            note_index,
+            tx_hash,
+            token,
+        }
+    }
+
     pub fn token(&self) -> Token {
         match self {
             Self::NewAccount(data) | Self::Deposit(data) | Self::Withdraw { data, .. } => {
                 data.token
             }
+            Self::Transfer { token, .. }
+            | Self::Claim { token, .. }
+            | Self::Migrate { token, .. } => *token,
+        }
+    }
+
+    pub fn note_index(&self) -> U256 {
+        match self {
+            Self::NewAccount(data) | Self::Deposit(data) | Self::Withdraw { data, .. } => {
+                data.note_index
+            }
+            Self::Transfer { note_index, .. }
+            | Self::Claim { note_index, .. }
+            | Self::Migrate { note_index, .. } => *note_index,
         }
     }
 }
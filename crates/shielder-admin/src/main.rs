@@ -0,0 +1,195 @@
+use std::{fs, path::PathBuf};
+
+use alloy_primitives::{Address, U256};
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use shielder_contract::{
+    AdminAction, ConnectionPolicy, NoProvider, ShielderAdmin, ShielderReader, ShielderState,
+};
+use shielder_setup::shielder_circuits::GrumpkinPointAffine;
+
+/// Perform owner-only operations on the Shielder contract.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Cli {
+    #[arg(long, env = "NODE_RPC_URL")]
+    rpc_url: String,
+
+    #[arg(long, env = "SHIELDER_ADDRESS")]
+    shielder_address: Address,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the current configuration and state of the contract.
+    State,
+
+    /// Send a transaction performing the action, signed with `--private-key`.
+    Execute {
+        /// Private key of the contract owner.
+        #[arg(long, env = "PRIVATE_KEY", value_parser = parse_signer)]
+        private_key: PrivateKeySigner,
+
+        #[clap(subcommand)]
+        action: Action,
+    },
+
+    /// Simulate the action, without sending any transaction.
+    DryRun {
+        /// Caller of the simulated transaction. By default, the contract owner.
+        #[arg(long)]
+        from: Option<Address>,
+
+        #[clap(subcommand)]
+        action: Action,
+    },
+
+    /// Simulate the action as the contract owner and print the unsigned transaction performing it,
+    /// as JSON, to be proposed to the owner multisig.
+    Export {
+        /// Write the transaction to this file instead of the standard output.
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        #[clap(subcommand)]
+        action: Action,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Action {
+    Pause,
+    Unpause,
+    SetProtocolDepositFeeBps { fee_bps: U256 },
+    SetProtocolWithdrawFeeBps { fee_bps: U256 },
+    SetProtocolFeeReceiver { receiver: Address },
+    SetAnonymityRevokerPubkey { x: U256, y: U256 },
+    SetTokenList { tokens: Vec<Address> },
+}
+
+impl TryFrom<Action> for AdminAction {
+    type Error = anyhow::Error;
+
+    fn try_from(action: Action) -> Result<Self> {
+        Ok(match action {
+            Action::Pause => AdminAction::Pause,
+            Action::Unpause => AdminAction::Unpause,
+            Action::SetProtocolDepositFeeBps { fee_bps } => {
+                AdminAction::SetProtocolDepositFeeBps(fee_bps)
+            }
+            Action::SetProtocolWithdrawFeeBps { fee_bps } => {
+                AdminAction::SetProtocolWithdrawFeeBps(fee_bps)
+            }
+            Action::SetProtocolFeeReceiver { receiver } => {
+                AdminAction::SetProtocolFeeReceiver(receiver)
+            }
+            Action::SetAnonymityRevokerPubkey { x, y } => {
+                AdminAction::SetAnonymityRevokerPubkey(GrumpkinPointAffine::new(x, y))
+            }
+            Action::SetTokenList { tokens } => AdminAction::set_token_list(tokens)?,
+        })
+    }
+}
+
+fn parse_signer(string: &str) -> Result<PrivateKeySigner> {
+    string
+        .parse()
+        .map_err(|e| anyhow!("Invalid private key: {e}"))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let reader = ShielderReader::from_rpc_url(cli.shielder_address, &cli.rpc_url).await?;
+    let before = reader.state().await?;
+    print_state("Current state", &before);
+
+    match cli.command {
+        Command::State => {}
+        Command::Execute {
+            private_key,
+            action,
+        } => {
+            let admin = ShielderAdmin::<NoProvider>::new(
+                cli.shielder_address,
+                ConnectionPolicy::OnDemand {
+                    rpc_url: cli.rpc_url,
+                    signer: private_key,
+                },
+            );
+            let (tx_hash, block_hash) = admin.execute(&AdminAction::try_from(action)?).await?;
+            println!("Executed in transaction {tx_hash} (block {block_hash})");
+            print_state("State after", &reader.state().await?);
+        }
+        Command::DryRun { from, action } => {
+            let action = AdminAction::try_from(action)?;
+            dry_run(&reader, from.unwrap_or(before.owner), &action).await?;
+            println!("Dry run succeeded");
+            print_state("State after (expected)", &action.apply(before));
+        }
+        Command::Export { output, action } => {
+            let action = AdminAction::try_from(action)?;
+            dry_run(&reader, before.owner, &action).await?;
+            print_state("State after (expected)", &action.apply(before));
+
+            let tx =
+                serde_json::to_string_pretty(&action.unsigned_transaction(cli.shielder_address))?;
+            match output {
+                Some(path) => {
+                    fs::write(&path, tx)?;
+                    println!("Transaction written to {}", path.display());
+                }
+                None => println!("{tx}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn dry_run(reader: &ShielderReader, caller: Address, action: &AdminAction) -> Result<()> {
+    let admin = ShielderAdmin::new(
+        reader.contract_address(),
+        ConnectionPolicy::Keep {
+            provider: reader.provider().clone(),
+            caller_address: caller,
+        },
+    );
+    admin
+        .dry_run(action)
+        .await
+        .map_err(|e| anyhow!("Dry run as {caller} failed: {e}"))
+}
+
+fn print_state(title: &str, state: &ShielderState) {
+    let (root, next_free_leaf_id, max_leaf_id, first_leaf_id) = state.merkle_tree;
+    println!("{title}:");
+    println!("  contract version:          {:?}", state.contract_version);
+    println!("  owner:                     {}", state.owner);
+    println!("  paused:                    {}", state.paused);
+    println!(
+        "  protocol deposit fee bps:  {}",
+        state.protocol_deposit_fee_bps
+    );
+    println!(
+        "  protocol withdraw fee bps: {}",
+        state.protocol_withdraw_fee_bps
+    );
+    println!(
+        "  protocol fee receiver:     {}",
+        state.protocol_fee_receiver
+    );
+    println!(
+        "  anonymity revoker pubkey:  ({}, {})",
+        state.anonymity_revoker_pubkey.x, state.anonymity_revoker_pubkey.y
+    );
+    println!("  token list root:           {}", state.token_list_root);
+    println!("  merkle root:               {root}");
+    println!(
+        "  notes:                     {} (leaves {first_leaf_id}..={max_leaf_id})",
+        next_free_leaf_id - first_leaf_id
+    );
+}
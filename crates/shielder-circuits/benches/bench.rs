@@ -3,8 +3,11 @@ use halo2_proofs::halo2curves::bn256::{Fr, G1};
 use rand_core::OsRng;
 use shielder_circuits::{
     circuits::{
+        balance_attestation::BalanceAttestationProverKnowledge,
+        batch_withdraw::BatchWithdrawProverKnowledge, claim::ClaimProverKnowledge,
         deposit::DepositProverKnowledge, merkle::MerkleProverKnowledge,
-        new_account::NewAccountProverKnowledge, withdraw::WithdrawProverKnowledge,
+        migration::MigrationProverKnowledge, new_account::NewAccountProverKnowledge,
+        transfer::TransferProverKnowledge, withdraw::WithdrawProverKnowledge,
     },
     consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT, WIDTH},
     generate_keys_with_min_k, generate_proof, generate_setup_params, verify, CircuitCost,
@@ -136,6 +139,57 @@ criterion_group! {
     targets = bench_withdraw
 }
 
+pub fn bench_batch_withdraw(c: &mut Criterion) {
+    bench_circuit::<BatchWithdrawProverKnowledge<Fr>>(c, "NoteBatchWithdrawCircuit")
+}
+
+criterion_group! {
+    name = batch_withdraw;
+    config = Criterion::default().sample_size(10);
+    targets = bench_batch_withdraw
+}
+
+pub fn bench_transfer(c: &mut Criterion) {
+    bench_circuit::<TransferProverKnowledge<Fr>>(c, "NoteTransferCircuit")
+}
+
+criterion_group! {
+    name = transfer;
+    config = Criterion::default().sample_size(10);
+    targets = bench_transfer
+}
+
+pub fn bench_claim(c: &mut Criterion) {
+    bench_circuit::<ClaimProverKnowledge<Fr>>(c, "NoteClaimCircuit")
+}
+
+criterion_group! {
+    name = claim;
+    config = Criterion::default().sample_size(10);
+    targets = bench_claim
+}
+
+pub fn bench_migration(c: &mut Criterion) {
+    bench_circuit::<MigrationProverKnowledge<Fr>>(c, "NoteMigrationCircuit")
+}
+
+criterion_group! {
+    name = migration;
+    config = Criterion::default().sample_size(10);
+    targets = bench_migration
+}
+
+pub fn bench_balance_attestation(c: &mut Criterion) {
+    bench_circuit::<BalanceAttestationProverKnowledge<Fr>>(c, "BalanceAttestationCircuit")
+}
+
+criterion_group! {
+    name = balance_attestation;
+    config = Criterion::default().sample_size(10);
+    targets = bench_balance_attestation
+}
+
 criterion_main! {
-    merkle, deposit, new_account, withdraw
+    merkle, deposit, new_account, withdraw, batch_withdraw, transfer, claim, migration,
+    balance_attestation
 }
@@ -5,8 +5,11 @@ use rand::rngs::StdRng;
 use rand_core::{OsRng, SeedableRng};
 use shielder_circuits::{
     circuits::{
+        balance_attestation::BalanceAttestationProverKnowledge,
+        batch_withdraw::BatchWithdrawProverKnowledge, claim::ClaimProverKnowledge,
         deposit::DepositProverKnowledge, merkle::MerkleProverKnowledge,
-        new_account::NewAccountProverKnowledge, withdraw::WithdrawProverKnowledge, Params,
+        migration::MigrationProverKnowledge, new_account::NewAccountProverKnowledge,
+        transfer::TransferProverKnowledge, withdraw::WithdrawProverKnowledge, Params,
     },
     consts::merkle_constants::NOTE_TREE_HEIGHT,
     generate_keys_with_min_k, generate_proof, generate_setup_params, Fr, ProverKnowledge, G1,
@@ -55,5 +58,10 @@ fn main() {
     measure_circuit::<NewAccountProverKnowledge<Fr>>("New account");
     measure_circuit::<DepositProverKnowledge<Fr>>("Deposit");
     measure_circuit::<WithdrawProverKnowledge<Fr>>("Withdraw");
+    measure_circuit::<BatchWithdrawProverKnowledge<Fr>>("Batch withdraw");
+    measure_circuit::<TransferProverKnowledge<Fr>>("Transfer");
+    measure_circuit::<ClaimProverKnowledge<Fr>>("Claim");
+    measure_circuit::<MigrationProverKnowledge<Fr>>("Migration");
+    measure_circuit::<BalanceAttestationProverKnowledge<Fr>>("Balance attestation");
     measure_circuit::<MerkleProverKnowledge<NOTE_TREE_HEIGHT, Fr>>("Merkle");
 }
@@ -0,0 +1,116 @@
+use halo2_proofs::plonk::Error;
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::{
+    consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+    gates::{
+        membership::{MembershipGate, MembershipGateInput},
+        Gate,
+    },
+    instance_wrapper::InstanceWrapper,
+    merkle::{MerkleChip, MerkleInstance, MerkleProverKnowledge},
+    poseidon::circuit::{hash, PoseidonChip},
+    synthesizer::Synthesizer,
+    AssignedCell, Field, Fr,
+};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
+pub enum AssociationSetInstance {
+    AssociationSetRoot,
+}
+
+impl TryFrom<AssociationSetInstance> for MerkleInstance {
+    type Error = ();
+
+    fn try_from(value: AssociationSetInstance) -> Result<Self, Self::Error> {
+        match value {
+            AssociationSetInstance::AssociationSetRoot => Ok(MerkleInstance::MerkleRoot),
+        }
+    }
+}
+
+/// Chip that proves that an account belongs to an association set.
+///
+/// An association set is a Merkle tree of height `NOTE_TREE_HEIGHT` whose leaves are prenullifiers
+/// (`hash(id)`, published when the account is created). Its root is published as
+/// `AssociationSetRoot`. The zero root means that no association set is claimed: in that case the
+/// path is not checked against the root (any path for the prenullifier is accepted, see
+/// `off_circuit::empty_association_path`).
+#[derive(Clone, Debug)]
+pub struct AssociationSetChip {
+    public_inputs: InstanceWrapper<AssociationSetInstance>,
+    merkle: MerkleChip,
+}
+
+impl AssociationSetChip {
+    pub fn new(
+        public_inputs: InstanceWrapper<AssociationSetInstance>,
+        membership_gate: MembershipGate<ARITY>,
+        poseidon: PoseidonChip,
+    ) -> Self {
+        Self {
+            merkle: MerkleChip {
+                public_inputs: public_inputs.narrow(),
+                membership_gate,
+                poseidon,
+            },
+            public_inputs,
+        }
+    }
+
+    /// Constrain the prenullifier of `id` to be a leaf of the tree with `root` (unless `root` is
+    /// zero) and publish `root` as `AssociationSetRoot`.
+    pub fn check_membership(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        id: &AssignedCell,
+        root: &AssignedCell,
+        path: &[[AssignedCell; ARITY]; NOTE_TREE_HEIGHT],
+    ) -> Result<(), Error> {
+        let prenullifier = hash(synthesizer, self.merkle.poseidon.clone(), [id.clone()])?;
+        let computed_root = self
+            .merkle
+            .compute_root(synthesizer, &MerkleProverKnowledge::new(prenullifier, path))?;
+
+        // `root` is either zero or the root computed from the path.
+        let zero = synthesizer.assign_constant("zero", Fr::ZERO)?;
+        let mut haystack = [(); ARITY].map(|_| zero.clone());
+        haystack[0] = computed_root;
+        self.merkle.membership_gate.apply_in_new_region(
+            synthesizer,
+            MembershipGateInput {
+                needle: root.clone(),
+                haystack,
+            },
+        )?;
+
+        self.public_inputs.constrain_cells(
+            synthesizer,
+            [(root.clone(), AssociationSetInstance::AssociationSetRoot)],
+        )
+    }
+}
+
+pub mod off_circuit {
+    use crate::{
+        consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+        poseidon::off_circuit::hash,
+        Field, Fr,
+    };
+
+    /// Leaf of the association set that represents the account `id`.
+    pub fn association_set_leaf(id: Fr) -> Fr {
+        hash(&[id])
+    }
+
+    /// Path used when no association set is claimed (i.e. when the root is zero). It is the path of
+    /// the prenullifier of `id` in a tree that contains only this leaf.
+    pub fn empty_association_path(id: Fr) -> [[Fr; ARITY]; NOTE_TREE_HEIGHT] {
+        let mut path = [[Fr::ZERO; ARITY]; NOTE_TREE_HEIGHT];
+        path[0][0] = association_set_leaf(id);
+        for i in 1..NOTE_TREE_HEIGHT {
+            path[i][0] = hash(&path[i - 1]);
+        }
+        path
+    }
+}
@@ -1,10 +1,13 @@
+pub mod association_set;
 pub mod el_gamal;
 pub mod mac;
 pub mod note;
 pub mod points_add;
 pub mod range_check;
+pub mod receipt;
 pub mod scalar_multiply;
 pub mod sum;
 pub mod to_affine;
 pub mod to_projective;
+pub mod token_list;
 pub mod viewing_key;
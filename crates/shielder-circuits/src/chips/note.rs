@@ -111,7 +111,18 @@ impl NoteChip {
         note: &Note<AssignedCell>,
     ) -> Result<AssignedCell, Error> {
         let note_version = self.assign_note_version(note, synthesizer)?;
+        self.versioned_note_hash(synthesizer, note_version, note)
+    }
 
+    /// Like `note_hash`, but with the note version taken from the `note_version` cell rather than
+    /// fixed at compile time (`note.version` is ignored). Used for notes of a version that is only
+    /// known at proving time, e.g. in note migration.
+    pub fn versioned_note_hash(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        note_version: AssignedCell,
+        note: &Note<AssignedCell>,
+    ) -> Result<AssignedCell, Error> {
         let h_balance = self.balance_hash(synthesizer, note)?;
 
         self.public_inputs.constrain_cells(
@@ -0,0 +1,106 @@
+//! Receipts of private transfers.
+//!
+//! A transfer cannot create a note for the recipient: whoever knows the ID and the nullifier of a
+//! note can spend it, and the sender would know both. Instead, the transfer adds to the note tree
+//! a receipt, which binds the transferred value to the receiving tag of the recipient:
+//! `hash(RECEIPT_SALT, tag, nonce, value, token)`, where `tag = hash(viewing_key(id))`. The tag
+//! is public (it is a part of the receiving address), but only the owner of `id` can prove that
+//! the tag is theirs and claim the receipt into their own note. A claimed receipt is nullified
+//! with `hash(RECEIPT_SALT, id, receipt)`, which cannot be computed without `id`.
+
+use halo2_proofs::plonk::Error;
+
+use crate::{
+    consts::RECEIPT_SALT,
+    poseidon::circuit::{hash, PoseidonChip},
+    synthesizer::Synthesizer,
+    AssignedCell,
+};
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Receipt<T> {
+    /// Receiving tag of the recipient.
+    pub tag: T,
+    /// Random nonce chosen by the sender, making receipts of equal transfers distinct.
+    pub nonce: T,
+    pub value: T,
+    pub token_address: T,
+}
+
+pub mod off_circuit {
+    use crate::{
+        chips::{receipt::Receipt, viewing_key::off_circuit::derive_viewing_key},
+        consts::RECEIPT_SALT,
+        poseidon::off_circuit::hash,
+        Fr,
+    };
+
+    /// The receiving tag of the account with `id`.
+    pub fn receiving_tag(id: Fr) -> Fr {
+        hash(&[derive_viewing_key(id)])
+    }
+
+    pub fn receipt_hash(receipt: &Receipt<Fr>) -> Fr {
+        hash(&[
+            *RECEIPT_SALT,
+            receipt.tag,
+            receipt.nonce,
+            receipt.value,
+            receipt.token_address,
+        ])
+    }
+
+    /// The nullifier of `receipt` claimed by the account with `id`.
+    pub fn receipt_nullifier(id: Fr, receipt: Fr) -> Fr {
+        hash(&[*RECEIPT_SALT, id, receipt])
+    }
+}
+
+/// Chip that computes receipts, receiving tags and receipt nullifiers (see the module docs).
+#[derive(Clone, Debug)]
+pub struct ReceiptChip {
+    poseidon: PoseidonChip,
+}
+
+impl ReceiptChip {
+    pub fn new(poseidon: PoseidonChip) -> Self {
+        Self { poseidon }
+    }
+
+    pub fn receiving_tag(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        viewing_key: AssignedCell,
+    ) -> Result<AssignedCell, Error> {
+        hash(synthesizer, self.poseidon.clone(), [viewing_key])
+    }
+
+    pub fn receipt_hash(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        receipt: &Receipt<AssignedCell>,
+    ) -> Result<AssignedCell, Error> {
+        let salt = synthesizer.assign_constant("Receipt salt", *RECEIPT_SALT)?;
+        hash(
+            synthesizer,
+            self.poseidon.clone(),
+            [
+                salt,
+                receipt.tag.clone(),
+                receipt.nonce.clone(),
+                receipt.value.clone(),
+                receipt.token_address.clone(),
+            ],
+        )
+    }
+
+    pub fn receipt_nullifier(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        id: AssignedCell,
+        receipt: AssignedCell,
+    ) -> Result<AssignedCell, Error> {
+        let salt = synthesizer.assign_constant("Receipt salt", *RECEIPT_SALT)?;
+        hash(synthesizer, self.poseidon.clone(), [salt, id, receipt])
+    }
+}
@@ -0,0 +1,186 @@
+use halo2_proofs::plonk::Error;
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::{
+    consts::merkle_constants::{ARITY, TOKEN_TREE_HEIGHT},
+    gates::membership::MembershipGate,
+    instance_wrapper::InstanceWrapper,
+    merkle::{MerkleChip, MerkleInstance, MerkleProverKnowledge},
+    poseidon::circuit::PoseidonChip,
+    synthesizer::Synthesizer,
+    AssignedCell,
+};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
+pub enum TokenListInstance {
+    TokenListRoot,
+}
+
+impl TryFrom<TokenListInstance> for MerkleInstance {
+    type Error = ();
+
+    fn try_from(value: TokenListInstance) -> Result<Self, Self::Error> {
+        match value {
+            TokenListInstance::TokenListRoot => Ok(MerkleInstance::MerkleRoot),
+        }
+    }
+}
+
+/// Chip that proves that a token address belongs to the list of allowed tokens.
+///
+/// The list is committed to as a Merkle tree of height `TOKEN_TREE_HEIGHT` (see
+/// `off_circuit::TokenList`), whose root is published as `TokenListRoot`.
+#[derive(Clone, Debug)]
+pub struct TokenListChip {
+    merkle: MerkleChip,
+}
+
+impl TokenListChip {
+    pub fn new(
+        public_inputs: InstanceWrapper<TokenListInstance>,
+        membership_gate: MembershipGate<ARITY>,
+        poseidon: PoseidonChip,
+    ) -> Self {
+        Self {
+            merkle: MerkleChip {
+                public_inputs: public_inputs.narrow(),
+                membership_gate,
+                poseidon,
+            },
+        }
+    }
+
+    /// Constrain `token_address` to be a leaf of the token tree with `path`.
+    pub fn check_membership(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        token_address: &AssignedCell,
+        path: &[[AssignedCell; ARITY]; TOKEN_TREE_HEIGHT],
+    ) -> Result<(), Error> {
+        self.merkle.synthesize(
+            synthesizer,
+            &MerkleProverKnowledge::new(token_address, path),
+        )
+    }
+}
+
+pub mod off_circuit {
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        consts::merkle_constants::{ARITY, TOKEN_TREE_HEIGHT},
+        poseidon::off_circuit::hash,
+        Field, Fr,
+    };
+
+    /// The number of leaves in the token tree.
+    pub const TOKEN_LIST_CAPACITY: usize = ARITY.pow(TOKEN_TREE_HEIGHT as u32);
+
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    pub enum TokenListError {
+        /// More than `TOKEN_LIST_CAPACITY` tokens were given.
+        TooManyTokens,
+    }
+
+    /// Merkle tree of the allowed token addresses.
+    ///
+    /// Leaves are token addresses (as field elements), in the given order, followed by zeros. Since
+    /// the native token is represented by the zero address, it is a member of every tree that is not
+    /// completely filled.
+    #[derive(Clone, Debug)]
+    pub struct TokenList {
+        /// `levels[0]` are the leaves, `levels[TOKEN_TREE_HEIGHT - 1]` are the children of the root.
+        levels: Vec<Vec<Fr>>,
+        root: Fr,
+    }
+
+    impl TokenList {
+        pub fn new(tokens: &[Fr]) -> Result<Self, TokenListError> {
+            if tokens.len() > TOKEN_LIST_CAPACITY {
+                return Err(TokenListError::TooManyTokens);
+            }
+
+            let mut leaves = vec![Fr::ZERO; TOKEN_LIST_CAPACITY];
+            leaves[..tokens.len()].copy_from_slice(tokens);
+
+            let mut levels = vec![leaves];
+            while levels.last().unwrap().len() > ARITY {
+                let parents = levels
+                    .last()
+                    .unwrap()
+                    .chunks_exact(ARITY)
+                    .map(hash_children)
+                    .collect();
+                levels.push(parents);
+            }
+            let root = hash_children(levels.last().unwrap());
+
+            Ok(Self { levels, root })
+        }
+
+        pub fn root(&self) -> Fr {
+            self.root
+        }
+
+        /// Merkle path of `token`, or `None` if `token` is not in the list.
+        pub fn path(&self, token: Fr) -> Option<[[Fr; ARITY]; TOKEN_TREE_HEIGHT]> {
+            let mut index = self.levels[0].iter().position(|leaf| *leaf == token)?;
+
+            let mut path = [[Fr::ZERO; ARITY]; TOKEN_TREE_HEIGHT];
+            for (level, nodes) in path.iter_mut().zip(&self.levels) {
+                let first = index - index % ARITY;
+                level.copy_from_slice(&nodes[first..first + ARITY]);
+                index /= ARITY;
+            }
+            Some(path)
+        }
+    }
+
+    fn hash_children(children: &[Fr]) -> Fr {
+        hash::<ARITY>(
+            children
+                .try_into()
+                .expect("node has exactly ARITY children"),
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::vec;
+
+        use super::{TokenList, TokenListError, TOKEN_LIST_CAPACITY};
+        use crate::{
+            consts::merkle_constants::TOKEN_TREE_HEIGHT, poseidon::off_circuit::hash, Field, Fr,
+        };
+
+        #[test]
+        fn path_leads_to_root() {
+            let tokens = [1u64, 2, 3, 4, 5, 6, 7, 8].map(Fr::from);
+            let list = TokenList::new(&tokens).unwrap();
+
+            for token in tokens.into_iter().chain([Fr::ZERO]) {
+                let path = list.path(token).unwrap();
+                assert!(path[0].contains(&token));
+                for levels in path.windows(2) {
+                    assert!(levels[1].contains(&hash(&levels[0])));
+                }
+                assert_eq!(hash(&path[TOKEN_TREE_HEIGHT - 1]), list.root());
+            }
+        }
+
+        #[test]
+        fn unlisted_token_has_no_path() {
+            let list = TokenList::new(&[Fr::ONE]).unwrap();
+            assert!(list.path(Fr::from(2)).is_none());
+        }
+
+        #[test]
+        fn rejects_too_many_tokens() {
+            let tokens = vec![Fr::ONE; TOKEN_LIST_CAPACITY + 1];
+            assert_eq!(
+                TokenList::new(&tokens).unwrap_err(),
+                TokenListError::TooManyTokens
+            );
+        }
+    }
+}
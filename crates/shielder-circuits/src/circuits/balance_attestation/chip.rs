@@ -0,0 +1,100 @@
+use halo2_proofs::plonk::Error;
+
+use crate::{
+    balance_attestation::BalanceAttestationInstance::{self, *},
+    chips::{
+        note::{Note, NoteChip},
+        range_check::RangeCheckChip,
+    },
+    circuits::{
+        balance_attestation::knowledge::BalanceAttestationProverKnowledge,
+        merkle::{MerkleChip, MerkleProverKnowledge},
+    },
+    consts::RANGE_PROOF_NUM_WORDS,
+    instance_wrapper::InstanceWrapper,
+    poseidon::circuit::{hash, PoseidonChip},
+    synthesizer::Synthesizer,
+    version::NOTE_VERSION,
+    AssignedCell,
+};
+
+#[derive(Clone, Debug)]
+pub struct BalanceAttestationChip {
+    pub public_inputs: InstanceWrapper<BalanceAttestationInstance>,
+    pub poseidon: PoseidonChip,
+    pub merkle: MerkleChip,
+    pub range_check: RangeCheckChip,
+    pub note: NoteChip,
+}
+
+impl BalanceAttestationChip {
+    pub fn check_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &BalanceAttestationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let note = self.note.note_hash(
+            synthesizer,
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier.clone(),
+                account_balance: knowledge.account_balance.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.merkle.synthesize(
+            synthesizer,
+            &MerkleProverKnowledge::new(note, &knowledge.path),
+        )
+    }
+
+    pub fn check_nullifier(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &BalanceAttestationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let hashed_nullifier = hash(
+            synthesizer,
+            self.poseidon.clone(),
+            [knowledge.nullifier.clone()],
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(hashed_nullifier, HashedNullifier)])
+    }
+
+    /// Check that `threshold <= account_balance`.
+    pub fn check_balance(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &BalanceAttestationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.public_inputs
+            .constrain_cells(synthesizer, [(knowledge.threshold.clone(), Threshold)])?;
+
+        // The threshold is chosen by the verifier, so it has to be range checked here. Otherwise,
+        // a threshold close to the field modulus would wrap the difference below into the range.
+        self.range_check
+            .constrain_value::<RANGE_PROOF_NUM_WORDS>(synthesizer, knowledge.threshold.clone())?;
+
+        let surplus = self.note.decrease_balance(
+            synthesizer,
+            knowledge.account_balance.clone(),
+            knowledge.threshold.clone(),
+        )?;
+
+        self.range_check
+            .constrain_value::<RANGE_PROOF_NUM_WORDS>(synthesizer, surplus)
+    }
+
+    pub fn check_challenge(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &BalanceAttestationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.public_inputs
+            .constrain_cells(synthesizer, [(knowledge.challenge.clone(), Challenge)])
+    }
+}
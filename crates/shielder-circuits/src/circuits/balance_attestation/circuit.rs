@@ -0,0 +1,190 @@
+use halo2_proofs::{
+    circuit::{floor_planner::V1, Layouter},
+    plonk::{Advice, Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    balance_attestation::{BalanceAttestationInstance, BalanceAttestationProverKnowledge},
+    circuits::balance_attestation::chip::BalanceAttestationChip,
+    column_pool::{ColumnPool, PreSynthesisPhase},
+    config_builder::ConfigsBuilder,
+    embed::Embed,
+    instance_wrapper::InstanceWrapper,
+    synthesizer::create_synthesizer,
+    Fr, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct BalanceAttestationCircuit(pub BalanceAttestationProverKnowledge<Value>);
+
+impl Circuit<Fr> for BalanceAttestationCircuit {
+    type Config = (
+        BalanceAttestationChip,
+        ColumnPool<Advice, PreSynthesisPhase>,
+    );
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let public_inputs = InstanceWrapper::<BalanceAttestationInstance>::new(meta);
+
+        let configs_builder = ConfigsBuilder::new(meta)
+            .with_merkle(public_inputs.narrow())
+            .with_range_check()
+            .with_note(public_inputs.narrow());
+
+        (
+            BalanceAttestationChip {
+                public_inputs,
+                poseidon: configs_builder.poseidon_chip(),
+                merkle: configs_builder.merkle_chip(),
+                range_check: configs_builder.range_check_chip(),
+                note: configs_builder.note_chip(),
+            },
+            configs_builder.finish(),
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (main_chip, column_pool): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let pool = column_pool.start_synthesis();
+        let mut synthesizer = create_synthesizer(&mut layouter, &pool);
+        let knowledge = self
+            .0
+            .embed(&mut synthesizer, "BalanceAttestationProverKnowledge")?;
+
+        main_chip.check_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_nullifier(&mut synthesizer, &knowledge)?;
+        main_chip.check_balance(&mut synthesizer, &knowledge)?;
+        main_chip.check_challenge(&mut synthesizer, &knowledge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand_core::OsRng;
+
+    use crate::{
+        balance_attestation::BalanceAttestationInstance::*,
+        circuits::{
+            balance_attestation::knowledge::BalanceAttestationProverKnowledge,
+            test_utils::{
+                expect_prover_success_and_run_verification, run_full_pipeline,
+                PublicInputProviderExt,
+            },
+        },
+        generate_keys_with_min_k, generate_proof, generate_setup_params,
+        merkle::generate_example_path_with_given_leaf,
+        note_hash,
+        version::NOTE_VERSION,
+        Field, Note, ProverKnowledge, PublicInputProvider, MAX_K,
+    };
+
+    fn expect_proof_generation_failure(pk: BalanceAttestationProverKnowledge<Fr>) {
+        let params = generate_setup_params(MAX_K, &mut OsRng);
+        let circuit = pk.create_circuit();
+        let (params, _, key, _) = generate_keys_with_min_k(circuit.clone(), params).unwrap();
+        generate_proof(
+            &params,
+            &key,
+            circuit,
+            &pk.serialize_public_input(),
+            &mut OsRng,
+        );
+    }
+
+    #[test]
+    fn passes_if_inputs_correct() {
+        run_full_pipeline::<BalanceAttestationProverKnowledge<Fr>>();
+    }
+
+    #[test]
+    fn passes_if_threshold_equals_balance() {
+        let mut pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+        pk.threshold = pk.account_balance;
+
+        assert!(expect_prover_success_and_run_verification(
+            pk.create_circuit(),
+            &pk.serialize_public_input()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn fails_if_hashed_nullifier_is_incorrect() {
+        let pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(HashedNullifier, |hash| hash + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_threshold_is_incorrect() {
+        let pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(Threshold, |threshold| threshold + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_challenge_is_incorrect() {
+        let pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(Challenge, |challenge| challenge + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_merkle_proof_uses_wrong_note() {
+        let mut pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+
+        let (merkle_root, path) =
+            generate_example_path_with_given_leaf(Fr::random(&mut OsRng), &mut OsRng);
+        pk.path = path;
+        let pub_input = pk.with_substitution(MerkleRoot, |_| merkle_root);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn fails_if_threshold_exceeds_balance() {
+        let mut pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+        pk.threshold = pk.account_balance + Fr::ONE;
+
+        expect_proof_generation_failure(pk);
+    }
+
+    #[test]
+    #[should_panic]
+    fn fails_if_threshold_wraps_around_the_field() {
+        let mut pk = BalanceAttestationProverKnowledge::random_correct_example(&mut OsRng);
+        pk.account_balance = Fr::ZERO;
+        pk.threshold = -Fr::ONE;
+        let h_note = note_hash(&Note {
+            version: NOTE_VERSION,
+            id: pk.id,
+            nullifier: pk.nullifier,
+            account_balance: pk.account_balance,
+            token_address: pk.token_address,
+        });
+        (_, pk.path) = generate_example_path_with_given_leaf(h_note, &mut OsRng);
+
+        expect_proof_generation_failure(pk);
+    }
+}
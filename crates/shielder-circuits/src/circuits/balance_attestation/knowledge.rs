@@ -0,0 +1,96 @@
+use halo2_proofs::halo2curves::ff::PrimeField;
+use macros::embeddable;
+use rand_core::RngCore;
+
+use crate::{
+    balance_attestation::{circuit::BalanceAttestationCircuit, BalanceAttestationInstance},
+    consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+    curve_arithmetic,
+    embed::Embed,
+    merkle::generate_example_path_with_given_leaf,
+    note_hash,
+    poseidon::off_circuit::hash,
+    version::NOTE_VERSION,
+    Field, Fr, Note, ProverKnowledge, PublicInputProvider, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+#[embeddable(
+    receiver = "BalanceAttestationProverKnowledge<Value>",
+    embedded = "BalanceAttestationProverKnowledge<crate::AssignedCell>"
+)]
+pub struct BalanceAttestationProverKnowledge<T> {
+    // Verifier-chosen parameters
+    pub challenge: T,
+    pub threshold: T,
+
+    // Attested note
+    pub id: T,
+    pub nullifier: T,
+    pub account_balance: T,
+    pub token_address: T,
+
+    // Merkle proof
+    pub path: [[T; ARITY]; NOTE_TREE_HEIGHT],
+}
+
+impl ProverKnowledge for BalanceAttestationProverKnowledge<Fr> {
+    type Circuit = BalanceAttestationCircuit;
+    type PublicInput = BalanceAttestationInstance;
+
+    /// All initial values are random. `threshold` is not greater than `account_balance`.
+    fn random_correct_example(rng: &mut impl RngCore) -> Self {
+        let id = curve_arithmetic::generate_user_id(Fr::random(&mut *rng).to_bytes());
+        let nullifier = Fr::random(&mut *rng);
+
+        let balance = rng.next_u64();
+        let account_balance = Fr::from_u128(balance as u128);
+        let threshold = Fr::from_u128((rng.next_u64() % (balance / 2 + 1)) as u128);
+        let token_address = Fr::random(&mut *rng);
+        let h_note = note_hash(&Note {
+            version: NOTE_VERSION,
+            id,
+            nullifier,
+            account_balance,
+            token_address,
+        });
+
+        let (_, path) = generate_example_path_with_given_leaf(h_note, &mut *rng);
+
+        Self {
+            challenge: Fr::random(rng),
+            threshold,
+            id,
+            nullifier,
+            account_balance,
+            token_address,
+            path,
+        }
+    }
+
+    fn create_circuit(&self) -> Self::Circuit {
+        BalanceAttestationCircuit(BalanceAttestationProverKnowledge {
+            challenge: Value::known(self.challenge),
+            threshold: Value::known(self.threshold),
+
+            id: Value::known(self.id),
+            nullifier: Value::known(self.nullifier),
+            account_balance: Value::known(self.account_balance),
+            token_address: Value::known(self.token_address),
+
+            path: self.path.map(|level| level.map(Value::known)),
+        })
+    }
+}
+
+impl PublicInputProvider<BalanceAttestationInstance> for BalanceAttestationProverKnowledge<Fr> {
+    fn compute_public_input(&self, instance_id: BalanceAttestationInstance) -> Fr {
+        match instance_id {
+            BalanceAttestationInstance::MerkleRoot => hash(&self.path[NOTE_TREE_HEIGHT - 1]),
+            BalanceAttestationInstance::HashedNullifier => hash(&[self.nullifier]),
+            BalanceAttestationInstance::TokenAddress => self.token_address,
+            BalanceAttestationInstance::Threshold => self.threshold,
+            BalanceAttestationInstance::Challenge => self.challenge,
+        }
+    }
+}
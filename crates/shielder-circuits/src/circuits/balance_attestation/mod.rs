@@ -0,0 +1,77 @@
+//! Off-chain attestation that a shielded account holds at least `Threshold` of a token.
+//!
+//! The owner proves knowledge of a note in the tree with root `MerkleRoot` whose balance is not
+//! smaller than `Threshold`, without revealing the balance itself. The current nullifier is hashed
+//! and revealed, so that the verifier can check that the note has not been spent yet (i.e. its
+//! nullifier hash is not registered in the contract). The proof is bound to a verifier-chosen
+//! `Challenge`, so it cannot be replayed to another counterparty or in another session.
+//!
+//! Note that the revealed nullifier hash lets the verifier recognize the transaction that spends
+//! the note later on.
+
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::{chips::note::NoteInstance, merkle::MerkleInstance};
+
+mod chip;
+mod circuit;
+mod knowledge;
+
+pub use circuit::BalanceAttestationCircuit;
+pub use knowledge::BalanceAttestationProverKnowledge;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
+pub enum BalanceAttestationInstance {
+    MerkleRoot,
+    HashedNullifier,
+    TokenAddress,
+    Threshold,
+    Challenge,
+}
+
+impl TryFrom<BalanceAttestationInstance> for MerkleInstance {
+    type Error = ();
+
+    fn try_from(value: BalanceAttestationInstance) -> Result<Self, Self::Error> {
+        match value {
+            BalanceAttestationInstance::MerkleRoot => Ok(Self::MerkleRoot),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<BalanceAttestationInstance> for NoteInstance {
+    type Error = ();
+
+    fn try_from(value: BalanceAttestationInstance) -> Result<Self, Self::Error> {
+        match value {
+            BalanceAttestationInstance::TokenAddress => Ok(NoteInstance::TokenAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{vec, vec::Vec};
+
+    use strum::IntoEnumIterator;
+
+    use super::{BalanceAttestationInstance, BalanceAttestationInstance::*};
+
+    #[test]
+    fn instance_order() {
+        // This is the order used in other parts of the codebase (e.g., in bindings).
+        let expected_order = vec![
+            MerkleRoot,
+            HashedNullifier,
+            TokenAddress,
+            Threshold,
+            Challenge,
+        ];
+        assert_eq!(
+            expected_order,
+            BalanceAttestationInstance::iter().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use core::array;
+
+use halo2_proofs::{
+    circuit::{floor_planner::V1, Layouter},
+    plonk::{Advice, Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    batch_withdraw::{BatchWithdrawInstance, BatchWithdrawProverKnowledge},
+    column_pool::{ColumnPool, PreSynthesisPhase},
+    config_builder::ConfigsBuilder,
+    consts::WITHDRAW_BATCH_SIZE,
+    embed::Embed,
+    instance_wrapper::InstanceWrapper,
+    synthesizer::create_synthesizer,
+    withdraw::{WithdrawChip, WithdrawInstance},
+    Fr, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct BatchWithdrawCircuit(pub BatchWithdrawProverKnowledge<Value>);
+
+impl Circuit<Fr> for BatchWithdrawCircuit {
+    type Config = (
+        [WithdrawChip; WITHDRAW_BATCH_SIZE],
+        ColumnPool<Advice, PreSynthesisPhase>,
+    );
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let public_inputs = InstanceWrapper::<BatchWithdrawInstance>::new(meta);
+        let withdrawal_inputs = |index: usize| {
+            public_inputs
+                .narrow_with::<WithdrawInstance>(|id| (id.index == index).then_some(id.instance))
+        };
+
+        // Gates are shared by all the withdrawals, only the public input offsets differ.
+        let first_inputs = withdrawal_inputs(0);
+        let configs_builder = ConfigsBuilder::new(meta)
+            .with_merkle(first_inputs.narrow())
+            .with_range_check()
+            .with_note(first_inputs.narrow())
+            .with_association_set(first_inputs.narrow());
+
+        let chip = WithdrawChip {
+            public_inputs: first_inputs,
+            poseidon: configs_builder.poseidon_chip(),
+            merkle: configs_builder.merkle_chip(),
+            range_check: configs_builder.range_check_chip(),
+            sum_chip: configs_builder.sum_chip(),
+            note: configs_builder.note_chip(),
+            association_set: configs_builder.association_set_chip(),
+        };
+
+        (
+            array::from_fn(|index| chip.with_public_inputs(withdrawal_inputs(index))),
+            configs_builder.finish(),
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (chips, column_pool): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let pool = column_pool.start_synthesis();
+        let mut synthesizer = create_synthesizer(&mut layouter, &pool);
+        let withdrawals = self
+            .0
+            .withdrawals
+            .embed(&mut synthesizer, "BatchWithdrawProverKnowledge")?;
+
+        for (chip, knowledge) in chips.iter().zip(withdrawals.iter()) {
+            chip.check_withdrawal(&mut synthesizer, knowledge)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use crate::{
+        batch_withdraw::{BatchWithdrawInstance, BatchWithdrawProverKnowledge},
+        circuits::test_utils::{
+            expect_prover_success_and_run_verification, run_full_pipeline, PublicInputProviderExt,
+        },
+        consts::WITHDRAW_BATCH_SIZE,
+        withdraw::WithdrawInstance::{self, *},
+        Field, Fr, ProverKnowledge, PublicInputProvider,
+    };
+
+    #[test]
+    fn passes_if_inputs_correct() {
+        run_full_pipeline::<BatchWithdrawProverKnowledge<Fr>>();
+    }
+
+    #[test]
+    fn public_input_is_concatenation_of_withdraw_public_inputs() {
+        let mut rng = SmallRng::from_seed([42; 32]);
+        let pk = BatchWithdrawProverKnowledge::random_correct_example(&mut rng);
+
+        let expected = pk
+            .withdrawals
+            .iter()
+            .flat_map(|withdrawal| withdrawal.serialize_public_input())
+            .collect::<Vec<_>>();
+
+        assert_eq!(pk.serialize_public_input(), expected);
+    }
+
+    fn assert_fails_with_incorrect(index: usize, instance: WithdrawInstance) {
+        let mut rng = SmallRng::from_seed([42; 32]);
+        let pk = BatchWithdrawProverKnowledge::random_correct_example(&mut rng);
+        let pub_input =
+            pk.with_substitution(BatchWithdrawInstance { index, instance }, |v| v + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_first_withdrawal_value_is_incorrect() {
+        assert_fails_with_incorrect(0, WithdrawalValue);
+    }
+
+    #[test]
+    fn fails_if_last_merkle_root_is_incorrect() {
+        assert_fails_with_incorrect(WITHDRAW_BATCH_SIZE - 1, MerkleRoot);
+    }
+
+    #[test]
+    fn fails_if_withdrawals_are_swapped() {
+        let mut rng = SmallRng::from_seed([42; 32]);
+        let pk = BatchWithdrawProverKnowledge::random_correct_example(&mut rng);
+        let mut swapped = pk.clone();
+        swapped.withdrawals.swap(0, 1);
+
+        assert!(expect_prover_success_and_run_verification(
+            pk.create_circuit(),
+            &swapped.serialize_public_input()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use core::array;
+
+use rand_core::RngCore;
+
+use crate::{
+    batch_withdraw::{circuit::BatchWithdrawCircuit, BatchWithdrawInstance},
+    consts::WITHDRAW_BATCH_SIZE,
+    withdraw::WithdrawProverKnowledge,
+    Fr, ProverKnowledge, PublicInputProvider,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct BatchWithdrawProverKnowledge<T> {
+    pub withdrawals: [WithdrawProverKnowledge<T>; WITHDRAW_BATCH_SIZE],
+}
+
+impl ProverKnowledge for BatchWithdrawProverKnowledge<Fr> {
+    type Circuit = BatchWithdrawCircuit;
+    type PublicInput = BatchWithdrawInstance;
+
+    /// Every withdrawal is an independent `WithdrawProverKnowledge::random_correct_example`.
+    fn random_correct_example(rng: &mut impl RngCore) -> Self {
+        Self {
+            withdrawals: array::from_fn(|_| {
+                WithdrawProverKnowledge::random_correct_example(&mut *rng)
+            }),
+        }
+    }
+
+    fn create_circuit(&self) -> Self::Circuit {
+        BatchWithdrawCircuit(BatchWithdrawProverKnowledge {
+            withdrawals: self
+                .withdrawals
+                .each_ref()
+                .map(|withdrawal| withdrawal.create_circuit().0),
+        })
+    }
+}
+
+impl PublicInputProvider<BatchWithdrawInstance> for BatchWithdrawProverKnowledge<Fr> {
+    fn compute_public_input(&self, instance_id: BatchWithdrawInstance) -> Fr {
+        self.withdrawals[instance_id.index].compute_public_input(instance_id.instance)
+    }
+}
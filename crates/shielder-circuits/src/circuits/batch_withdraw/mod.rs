@@ -0,0 +1,74 @@
+//! Proof of `WITHDRAW_BATCH_SIZE` independent withdrawals.
+//!
+//! Every withdrawal in the batch is constrained exactly as in the withdraw circuit. The public
+//! input is the concatenation of the withdraw public inputs of all the withdrawals, in the batch
+//! order.
+
+use core::{iter::Map, ops::Range};
+
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::{consts::WITHDRAW_BATCH_SIZE, withdraw::WithdrawInstance};
+
+mod circuit;
+mod knowledge;
+
+pub use circuit::BatchWithdrawCircuit;
+pub use knowledge::BatchWithdrawProverKnowledge;
+
+/// Public input `instance` of the `index`-th withdrawal in the batch.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct BatchWithdrawInstance {
+    pub index: usize,
+    pub instance: WithdrawInstance,
+}
+
+impl BatchWithdrawInstance {
+    fn from_offset(offset: usize) -> Self {
+        Self {
+            index: offset / WithdrawInstance::COUNT,
+            instance: WithdrawInstance::iter()
+                .nth(offset % WithdrawInstance::COUNT)
+                .expect("offset is within the withdraw instance"),
+        }
+    }
+}
+
+impl EnumCount for BatchWithdrawInstance {
+    const COUNT: usize = WITHDRAW_BATCH_SIZE * WithdrawInstance::COUNT;
+}
+
+impl IntoEnumIterator for BatchWithdrawInstance {
+    type Iterator = Map<Range<usize>, fn(usize) -> Self>;
+
+    fn iter() -> Self::Iterator {
+        (0..Self::COUNT).map(Self::from_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use strum::{EnumCount, IntoEnumIterator};
+
+    use super::BatchWithdrawInstance;
+    use crate::{consts::WITHDRAW_BATCH_SIZE, withdraw::WithdrawInstance};
+
+    #[test]
+    fn instance_order() {
+        // Withdrawals follow each other, each with the withdraw instance order.
+        let expected_order = (0..WITHDRAW_BATCH_SIZE)
+            .flat_map(|index| {
+                WithdrawInstance::iter()
+                    .map(move |instance| BatchWithdrawInstance { index, instance })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected_order.len(), BatchWithdrawInstance::COUNT);
+        assert_eq!(
+            expected_order,
+            BatchWithdrawInstance::iter().collect::<Vec<_>>()
+        );
+    }
+}
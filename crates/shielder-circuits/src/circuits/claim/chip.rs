@@ -0,0 +1,190 @@
+use halo2_proofs::plonk::Error;
+
+use crate::{
+    chips::{
+        mac::{MacChip, MacInput},
+        note::{Note, NoteChip},
+        range_check::RangeCheckChip,
+        receipt::{Receipt, ReceiptChip},
+        sum::SumChip,
+        viewing_key::ViewingKeyChip,
+    },
+    circuits::{
+        claim::knowledge::ClaimProverKnowledge,
+        merkle::{MerkleChip, MerkleProverKnowledge},
+    },
+    claim::ClaimInstance::{self, *},
+    consts::RANGE_PROOF_NUM_WORDS,
+    instance_wrapper::InstanceWrapper,
+    poseidon::circuit::{hash, PoseidonChip},
+    synthesizer::Synthesizer,
+    version::NOTE_VERSION,
+    AssignedCell,
+};
+
+#[derive(Clone, Debug)]
+pub struct ClaimChip {
+    pub public_inputs: InstanceWrapper<ClaimInstance>,
+    pub poseidon: PoseidonChip,
+    pub merkle: MerkleChip,
+    pub range_check: RangeCheckChip,
+    pub sum_chip: SumChip,
+    pub note: NoteChip,
+}
+
+impl ClaimChip {
+    pub fn check_old_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let old_note = self.note.note_hash(
+            synthesizer,
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier_old.clone(),
+                account_balance: knowledge.account_old_balance.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.merkle.synthesize(
+            synthesizer,
+            &MerkleProverKnowledge::new(old_note, &knowledge.path),
+        )
+    }
+
+    pub fn check_old_nullifier(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let hashed_old_nullifier = hash(
+            synthesizer,
+            self.poseidon.clone(),
+            [knowledge.nullifier_old.clone()],
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(hashed_old_nullifier, HashedOldNullifier)])
+    }
+
+    /// Check that the receipt is bound to the receiving tag of the claimer, that it is in the note
+    /// tree, and publish its nullifier.
+    pub fn check_receipt(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let receipt_chip = ReceiptChip::new(self.poseidon.clone());
+
+        let viewing_key = ViewingKeyChip::new(self.poseidon.clone())
+            .derive_viewing_key(synthesizer, knowledge.id.clone())?;
+        let tag = receipt_chip.receiving_tag(synthesizer, viewing_key)?;
+
+        let receipt = receipt_chip.receipt_hash(
+            synthesizer,
+            &Receipt {
+                tag,
+                nonce: knowledge.receipt_nonce.clone(),
+                value: knowledge.receipt_value.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.merkle.synthesize(
+            synthesizer,
+            &MerkleProverKnowledge::new(receipt.clone(), &knowledge.receipt_path),
+        )?;
+
+        let nullifier =
+            receipt_chip.receipt_nullifier(synthesizer, knowledge.id.clone(), receipt)?;
+        self.public_inputs
+            .constrain_cells(synthesizer, [(nullifier, ReceiptNullifier)])
+    }
+
+    pub fn check_new_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let new_balance = self.note.increase_balance(
+            synthesizer,
+            knowledge.account_old_balance.clone(),
+            knowledge.receipt_value.clone(),
+        )?;
+
+        // The value is not checked by the contract, so the new balance has to be range checked
+        // here.
+        self.range_check
+            .constrain_value::<RANGE_PROOF_NUM_WORDS>(synthesizer, new_balance.clone())?;
+
+        let new_note = self.note.note_hash(
+            synthesizer,
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier_new.clone(),
+                account_balance: new_balance,
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(new_note, HashedNewNote)])
+    }
+
+    /// Mask the claimed value with the viewing key of the old nullifier, so that the claimer can
+    /// recover it from the chain.
+    pub fn check_encrypted_value(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let value_key = ViewingKeyChip::new(self.poseidon.clone())
+            .derive_viewing_key(synthesizer, knowledge.nullifier_old.clone())?;
+
+        let encrypted_value = synthesizer.assign_value(
+            "encrypted_value",
+            knowledge.receipt_value.value() + value_key.value(),
+        )?;
+        self.sum_chip.constrain_sum(
+            synthesizer,
+            knowledge.receipt_value.clone(),
+            value_key,
+            encrypted_value.clone(),
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(encrypted_value, EncryptedValue)])
+    }
+
+    pub fn check_commitment(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.public_inputs
+            .constrain_cells(synthesizer, [(knowledge.commitment.clone(), Commitment)])
+    }
+
+    pub fn check_mac(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &ClaimProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let viewing_key = ViewingKeyChip::new(self.poseidon.clone())
+            .derive_viewing_key(synthesizer, knowledge.id.clone())?;
+
+        MacChip::new(self.poseidon.clone(), self.public_inputs.narrow()).mac(
+            synthesizer,
+            &MacInput {
+                key: viewing_key,
+                salt: knowledge.mac_salt.clone(),
+            },
+        )?;
+
+        Ok(())
+    }
+}
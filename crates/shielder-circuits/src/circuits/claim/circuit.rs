@@ -0,0 +1,175 @@
+use halo2_proofs::{
+    circuit::{floor_planner::V1, Layouter},
+    plonk::{Advice, Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    circuits::claim::chip::ClaimChip,
+    claim::{ClaimInstance, ClaimProverKnowledge},
+    column_pool::{ColumnPool, PreSynthesisPhase},
+    config_builder::ConfigsBuilder,
+    embed::Embed,
+    instance_wrapper::InstanceWrapper,
+    synthesizer::create_synthesizer,
+    Fr, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct ClaimCircuit(pub ClaimProverKnowledge<Value>);
+
+impl Circuit<Fr> for ClaimCircuit {
+    type Config = (ClaimChip, ColumnPool<Advice, PreSynthesisPhase>);
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let public_inputs = InstanceWrapper::<ClaimInstance>::new(meta);
+
+        let configs_builder = ConfigsBuilder::new(meta)
+            .with_merkle(public_inputs.narrow())
+            .with_range_check()
+            .with_note(public_inputs.narrow());
+
+        (
+            ClaimChip {
+                public_inputs,
+                poseidon: configs_builder.poseidon_chip(),
+                merkle: configs_builder.merkle_chip(),
+                range_check: configs_builder.range_check_chip(),
+                sum_chip: configs_builder.sum_chip(),
+                note: configs_builder.note_chip(),
+            },
+            configs_builder.finish(),
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (main_chip, column_pool): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let pool = column_pool.start_synthesis();
+        let mut synthesizer = create_synthesizer(&mut layouter, &pool);
+        let knowledge = self.0.embed(&mut synthesizer, "ClaimProverKnowledge")?;
+
+        main_chip.check_old_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_old_nullifier(&mut synthesizer, &knowledge)?;
+        main_chip.check_receipt(&mut synthesizer, &knowledge)?;
+        main_chip.check_new_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_encrypted_value(&mut synthesizer, &knowledge)?;
+        main_chip.check_commitment(&mut synthesizer, &knowledge)?;
+        main_chip.check_mac(&mut synthesizer, &knowledge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand_core::OsRng;
+
+    use crate::{
+        circuits::{
+            claim::knowledge::ClaimProverKnowledge,
+            test_utils::{
+                expect_prover_success_and_run_verification, run_full_pipeline,
+                PublicInputProviderExt,
+            },
+        },
+        claim::ClaimInstance::*,
+        consts::MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK,
+        derive_viewing_key, generate_keys_with_min_k, generate_proof, generate_setup_params,
+        receipt_hash, receiving_tag, Field, ProverKnowledge, PublicInputProvider, Receipt, MAX_K,
+    };
+
+    #[test]
+    fn passes_if_inputs_correct() {
+        run_full_pipeline::<ClaimProverKnowledge<Fr>>();
+    }
+
+    #[test]
+    fn claimer_can_recover_the_value() {
+        let pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        let value = pk.compute_public_input(EncryptedValue) - derive_viewing_key(pk.nullifier_old);
+
+        assert_eq!(value, pk.receipt_value);
+    }
+
+    #[test]
+    fn fails_if_receipt_belongs_to_someone_else() {
+        let mut pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        let foreign_receipt = receipt_hash(&Receipt {
+            tag: receiving_tag(pk.id + Fr::ONE),
+            nonce: pk.receipt_nonce,
+            value: pk.receipt_value,
+            token_address: pk.token_address,
+        });
+        pk.rebuild_paths(foreign_receipt);
+        let pub_input = pk.serialize_public_input();
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_receipt_nullifier_is_incorrect() {
+        let pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(ReceiptNullifier, |n| n + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_h_note_new_is_not_the_hash_of_appropriate_witnesses() {
+        let pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(HashedNewNote, |hash| hash + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_encrypted_value_is_incorrect() {
+        let pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(EncryptedValue, |v| v + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_mac_commitment_is_incorrect() {
+        let pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(MacCommitment, |c| c + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn fails_if_new_balance_exceeds_the_range() {
+        let mut pk = ClaimProverKnowledge::random_correct_example(&mut OsRng);
+        pk.account_old_balance = Fr::from_u128(MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK);
+        pk.rebuild_paths(pk.compute_receipt());
+
+        let params = generate_setup_params(MAX_K, &mut OsRng);
+        let circuit = pk.create_circuit();
+        let (params, _, key, _) = generate_keys_with_min_k(circuit.clone(), params).unwrap();
+        generate_proof(
+            &params,
+            &key,
+            circuit,
+            &pk.serialize_public_input(),
+            &mut OsRng,
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use macros::embeddable;
+use rand_core::RngCore;
+
+use crate::{
+    chips::{
+        receipt::{
+            off_circuit::{receipt_hash, receipt_nullifier, receiving_tag},
+            Receipt,
+        },
+        viewing_key,
+    },
+    claim::{circuit::ClaimCircuit, ClaimInstance},
+    consts::{
+        merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+        MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK,
+    },
+    curve_arithmetic,
+    embed::Embed,
+    note_hash,
+    poseidon::off_circuit::hash,
+    version::NOTE_VERSION,
+    Field, Fr, Note, ProverKnowledge, PublicInputProvider, Value,
+};
+
+/// Stores values needed to compute example inputs for `ClaimCircuit`. Provides a function
+/// to create such inputs.
+#[derive(Clone, Debug, Default)]
+#[embeddable(
+    receiver = "ClaimProverKnowledge<Value>",
+    embedded = "ClaimProverKnowledge<crate::AssignedCell>"
+)]
+pub struct ClaimProverKnowledge<T> {
+    // Old note
+    pub id: T,
+    pub nullifier_old: T,
+    pub account_old_balance: T,
+    pub token_address: T,
+
+    // Merkle proof of the old note
+    pub path: [[T; ARITY]; NOTE_TREE_HEIGHT],
+
+    // Receipt
+    pub receipt_nonce: T,
+    pub receipt_value: T,
+
+    // Merkle proof of the receipt
+    pub receipt_path: [[T; ARITY]; NOTE_TREE_HEIGHT],
+
+    // New note
+    pub nullifier_new: T,
+
+    // Salt for MAC.
+    pub mac_salt: T,
+
+    pub commitment: T,
+}
+
+impl ClaimProverKnowledge<Fr> {
+    pub(super) fn compute_receipt(&self) -> Fr {
+        receipt_hash(&Receipt {
+            tag: receiving_tag(self.id),
+            nonce: self.receipt_nonce,
+            value: self.receipt_value,
+            token_address: self.token_address,
+        })
+    }
+
+    /// Put the old note and `receipt` next to each other at the bottom of `path` and recompute the
+    /// rest of it. Both Merkle proofs use the resulting path.
+    pub(super) fn rebuild_paths(&mut self, receipt: Fr) {
+        self.path[0][0] = note_hash(&Note {
+            version: NOTE_VERSION,
+            id: self.id,
+            nullifier: self.nullifier_old,
+            account_balance: self.account_old_balance,
+            token_address: self.token_address,
+        });
+        self.path[0][1] = receipt;
+        for i in 1..NOTE_TREE_HEIGHT {
+            self.path[i][0] = hash(&self.path[i - 1]);
+        }
+        self.receipt_path = self.path;
+    }
+}
+
+impl ProverKnowledge for ClaimProverKnowledge<Fr> {
+    type Circuit = ClaimCircuit;
+    type PublicInput = ClaimInstance;
+
+    /// Creates a random example with correct inputs. All values are random except for the balance
+    /// and the receipt value. The old note and the receipt are siblings in the note tree.
+    ///
+    /// `account_old_balance` and `receipt_value` sum up to the largest possible value that passes
+    /// the range check.
+    fn random_correct_example(rng: &mut impl RngCore) -> Self {
+        let id = curve_arithmetic::generate_user_id(Fr::random(&mut *rng).to_bytes());
+        let nullifier_old = Fr::random(&mut *rng);
+        let account_old_balance = Fr::from_u128(MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK - 1);
+        let token_address = Fr::ZERO;
+        let mut knowledge = Self {
+            id,
+            nullifier_old,
+            account_old_balance,
+            token_address,
+            receipt_nonce: Fr::random(&mut *rng),
+            receipt_value: Fr::ONE,
+            nullifier_new: Fr::random(&mut *rng),
+            mac_salt: Fr::random(&mut *rng),
+            commitment: Fr::random(&mut *rng),
+            ..Default::default()
+        };
+
+        knowledge.path = [(); NOTE_TREE_HEIGHT].map(|_| [(); ARITY].map(|_| Fr::random(&mut *rng)));
+        knowledge.rebuild_paths(knowledge.compute_receipt());
+
+        knowledge
+    }
+
+    fn create_circuit(&self) -> Self::Circuit {
+        ClaimCircuit(ClaimProverKnowledge {
+            id: Value::known(self.id),
+            nullifier_old: Value::known(self.nullifier_old),
+            account_old_balance: Value::known(self.account_old_balance),
+            token_address: Value::known(self.token_address),
+            path: self.path.map(|level| level.map(Value::known)),
+            receipt_nonce: Value::known(self.receipt_nonce),
+            receipt_value: Value::known(self.receipt_value),
+            receipt_path: self.receipt_path.map(|level| level.map(Value::known)),
+            nullifier_new: Value::known(self.nullifier_new),
+            mac_salt: Value::known(self.mac_salt),
+            commitment: Value::known(self.commitment),
+        })
+    }
+}
+
+impl PublicInputProvider<ClaimInstance> for ClaimProverKnowledge<Fr> {
+    fn compute_public_input(&self, instance_id: ClaimInstance) -> Fr {
+        let viewing_key = viewing_key::off_circuit::derive_viewing_key(self.id);
+
+        match instance_id {
+            ClaimInstance::MerkleRoot => hash(&self.path[NOTE_TREE_HEIGHT - 1]),
+            ClaimInstance::HashedOldNullifier => hash(&[self.nullifier_old]),
+            ClaimInstance::HashedNewNote => note_hash(&Note {
+                version: NOTE_VERSION,
+                id: self.id,
+                nullifier: self.nullifier_new,
+                account_balance: self.account_old_balance + self.receipt_value,
+                token_address: self.token_address,
+            }),
+            ClaimInstance::ReceiptNullifier => receipt_nullifier(self.id, self.compute_receipt()),
+            ClaimInstance::TokenAddress => self.token_address,
+            ClaimInstance::Commitment => self.commitment,
+            ClaimInstance::EncryptedValue => {
+                self.receipt_value
+                    + viewing_key::off_circuit::derive_viewing_key(self.nullifier_old)
+            }
+            ClaimInstance::MacSalt => self.mac_salt,
+            ClaimInstance::MacCommitment => hash(&[self.mac_salt, viewing_key]),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Claim of a receipt of a private transfer (see `chips::receipt` and the transfer circuit).
+//!
+//! The recipient spends their note and the receipt, and creates a new note with the balance
+//! increased by the value of the receipt. Both the note and the receipt must be in the note tree
+//! with the same Merkle root. The circuit checks that the receipt is bound to the receiving tag of
+//! the recipient's ID, so only the recipient can claim it. The receipt nullifier is published, so
+//! that every receipt can be claimed only once.
+//!
+//! The claimed value is masked with a key derived from the old nullifier, so that the recipient
+//! can recover the amount from the chain.
+
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::{
+    chips::{mac::MacInstance, note::NoteInstance},
+    merkle::MerkleInstance,
+};
+
+mod chip;
+mod circuit;
+mod knowledge;
+
+pub use circuit::ClaimCircuit;
+pub use knowledge::ClaimProverKnowledge;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
+pub enum ClaimInstance {
+    MerkleRoot,
+    HashedOldNullifier,
+    HashedNewNote,
+    ReceiptNullifier,
+    TokenAddress,
+    Commitment,
+    EncryptedValue,
+    MacSalt,
+    MacCommitment,
+}
+
+impl TryFrom<ClaimInstance> for MerkleInstance {
+    type Error = ();
+
+    fn try_from(value: ClaimInstance) -> Result<Self, Self::Error> {
+        match value {
+            ClaimInstance::MerkleRoot => Ok(Self::MerkleRoot),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<ClaimInstance> for NoteInstance {
+    type Error = ();
+
+    fn try_from(value: ClaimInstance) -> Result<Self, Self::Error> {
+        match value {
+            ClaimInstance::TokenAddress => Ok(NoteInstance::TokenAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<ClaimInstance> for MacInstance {
+    type Error = ();
+
+    fn try_from(value: ClaimInstance) -> Result<Self, Self::Error> {
+        match value {
+            ClaimInstance::MacSalt => Ok(Self::MacSalt),
+            ClaimInstance::MacCommitment => Ok(Self::MacCommitment),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{vec, vec::Vec};
+
+    use strum::IntoEnumIterator;
+
+    use super::{ClaimInstance, ClaimInstance::*};
+
+    #[test]
+    fn instance_order() {
+        // This is the order used in other parts of the codebase (e.g., in contracts).
+        let expected_order = vec![
+            MerkleRoot,
+            HashedOldNullifier,
+            HashedNewNote,
+            ReceiptNullifier,
+            TokenAddress,
+            Commitment,
+            EncryptedValue,
+            MacSalt,
+            MacCommitment,
+        ];
+        assert_eq!(expected_order, ClaimInstance::iter().collect::<Vec<_>>());
+    }
+}
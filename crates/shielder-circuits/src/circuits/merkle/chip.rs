@@ -28,6 +28,19 @@ impl MerkleChip {
         synthesizer: &mut impl Synthesizer,
         knowledge: &MerkleProverKnowledge<TREE_HEIGHT, AssignedCell>,
     ) -> Result<(), Error> {
+        let root = self.compute_root(synthesizer, knowledge)?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(root, MerkleRoot)])
+    }
+
+    /// Check that `knowledge.path` is a valid path for `knowledge.leaf` and return the root it
+    /// leads to. Unlike `synthesize`, the root is not constrained to the public input.
+    pub fn compute_root<const TREE_HEIGHT: usize>(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &MerkleProverKnowledge<TREE_HEIGHT, AssignedCell>,
+    ) -> Result<AssignedCell, Error> {
         let mut current_root = knowledge.leaf.clone();
 
         for level in knowledge.path.clone().into_iter() {
@@ -44,7 +57,6 @@ impl MerkleChip {
             current_root = hash(synthesizer, self.poseidon.clone(), level)?;
         }
 
-        self.public_inputs
-            .constrain_cells(synthesizer, [(current_root, MerkleRoot)])
+        Ok(current_root)
     }
 }
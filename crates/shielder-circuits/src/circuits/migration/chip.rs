@@ -0,0 +1,121 @@
+use halo2_proofs::plonk::Error;
+
+use crate::{
+    chips::{
+        mac::{MacChip, MacInput},
+        note::{Note, NoteChip},
+        viewing_key::ViewingKeyChip,
+    },
+    circuits::{
+        merkle::{MerkleChip, MerkleProverKnowledge},
+        migration::knowledge::MigrationProverKnowledge,
+    },
+    instance_wrapper::InstanceWrapper,
+    migration::MigrationInstance::{self, *},
+    poseidon::circuit::{hash, PoseidonChip},
+    synthesizer::Synthesizer,
+    version::NOTE_VERSION,
+    AssignedCell,
+};
+
+#[derive(Clone, Debug)]
+pub struct MigrationChip {
+    pub public_inputs: InstanceWrapper<MigrationInstance>,
+    pub poseidon: PoseidonChip,
+    pub merkle: MerkleChip,
+    pub note: NoteChip,
+}
+
+impl MigrationChip {
+    pub fn check_old_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &MigrationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.public_inputs.constrain_cells(
+            synthesizer,
+            [(knowledge.old_note_version.clone(), OldNoteVersion)],
+        )?;
+
+        let old_note = self.note.versioned_note_hash(
+            synthesizer,
+            knowledge.old_note_version.clone(),
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier_old.clone(),
+                account_balance: knowledge.account_balance.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.merkle.synthesize(
+            synthesizer,
+            &MerkleProverKnowledge::new(old_note, &knowledge.path),
+        )
+    }
+
+    pub fn check_old_nullifier(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &MigrationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let hashed_old_nullifier = hash(
+            synthesizer,
+            self.poseidon.clone(),
+            [knowledge.nullifier_old.clone()],
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(hashed_old_nullifier, HashedOldNullifier)])
+    }
+
+    /// The new note has the current version, but the same ID, balance and token as the old one.
+    pub fn check_new_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &MigrationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let new_note = self.note.note_hash(
+            synthesizer,
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier_new.clone(),
+                account_balance: knowledge.account_balance.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(new_note, HashedNewNote)])
+    }
+
+    pub fn check_commitment(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &MigrationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.public_inputs
+            .constrain_cells(synthesizer, [(knowledge.commitment.clone(), Commitment)])
+    }
+
+    pub fn check_mac(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &MigrationProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let viewing_key = ViewingKeyChip::new(self.poseidon.clone())
+            .derive_viewing_key(synthesizer, knowledge.id.clone())?;
+
+        MacChip::new(self.poseidon.clone(), self.public_inputs.narrow()).mac(
+            synthesizer,
+            &MacInput {
+                key: viewing_key,
+                salt: knowledge.mac_salt.clone(),
+            },
+        )?;
+
+        Ok(())
+    }
+}
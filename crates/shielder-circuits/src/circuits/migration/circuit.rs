@@ -0,0 +1,175 @@
+use halo2_proofs::{
+    circuit::{floor_planner::V1, Layouter},
+    plonk::{Advice, Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    circuits::migration::chip::MigrationChip,
+    column_pool::{ColumnPool, PreSynthesisPhase},
+    config_builder::ConfigsBuilder,
+    embed::Embed,
+    instance_wrapper::InstanceWrapper,
+    migration::{MigrationInstance, MigrationProverKnowledge},
+    synthesizer::create_synthesizer,
+    Fr, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct MigrationCircuit(pub MigrationProverKnowledge<Value>);
+
+impl Circuit<Fr> for MigrationCircuit {
+    type Config = (MigrationChip, ColumnPool<Advice, PreSynthesisPhase>);
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let public_inputs = InstanceWrapper::<MigrationInstance>::new(meta);
+
+        let configs_builder = ConfigsBuilder::new(meta)
+            .with_merkle(public_inputs.narrow())
+            .with_note(public_inputs.narrow());
+
+        (
+            MigrationChip {
+                public_inputs,
+                poseidon: configs_builder.poseidon_chip(),
+                merkle: configs_builder.merkle_chip(),
+                note: configs_builder.note_chip(),
+            },
+            configs_builder.finish(),
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (main_chip, column_pool): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let pool = column_pool.start_synthesis();
+        let mut synthesizer = create_synthesizer(&mut layouter, &pool);
+        let knowledge = self.0.embed(&mut synthesizer, "MigrationProverKnowledge")?;
+
+        main_chip.check_old_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_old_nullifier(&mut synthesizer, &knowledge)?;
+        main_chip.check_new_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_commitment(&mut synthesizer, &knowledge)?;
+        main_chip.check_mac(&mut synthesizer, &knowledge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand_core::OsRng;
+
+    use crate::{
+        circuits::{
+            migration::knowledge::MigrationProverKnowledge,
+            test_utils::{
+                expect_prover_success_and_run_verification, run_full_pipeline,
+                PublicInputProviderExt,
+            },
+        },
+        merkle::generate_example_path_with_given_leaf,
+        migration::MigrationInstance::*,
+        note_hash,
+        version::NOTE_VERSION,
+        Field, Note, ProverKnowledge, PublicInputProvider,
+    };
+
+    #[test]
+    fn passes_if_inputs_correct() {
+        run_full_pipeline::<MigrationProverKnowledge<Fr>>();
+    }
+
+    #[test]
+    fn new_note_has_current_version_and_same_balance() {
+        let pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+
+        assert_eq!(
+            note_hash(&Note {
+                version: NOTE_VERSION,
+                id: pk.id,
+                nullifier: pk.nullifier_new,
+                account_balance: pk.account_balance,
+                token_address: pk.token_address,
+            }),
+            pk.compute_public_input(HashedNewNote)
+        );
+    }
+
+    #[test]
+    fn passes_if_old_note_has_current_version() {
+        let mut pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+        pk.old_note_version = NOTE_VERSION.as_field();
+        let h_note_old = note_hash(&Note {
+            version: NOTE_VERSION,
+            id: pk.id,
+            nullifier: pk.nullifier_old,
+            account_balance: pk.account_balance,
+            token_address: pk.token_address,
+        });
+        (_, pk.path) = generate_example_path_with_given_leaf(h_note_old, &mut OsRng);
+
+        assert!(expect_prover_success_and_run_verification(
+            pk.create_circuit(),
+            &pk.serialize_public_input()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn fails_if_old_note_version_is_incorrect() {
+        let pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(OldNoteVersion, |v| v + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_old_note_was_created_with_other_version() {
+        let mut pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+        pk.old_note_version += Fr::ONE;
+
+        assert!(expect_prover_success_and_run_verification(
+            pk.create_circuit(),
+            &pk.serialize_public_input()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fails_if_h_note_new_is_not_the_hash_of_appropriate_witnesses() {
+        let pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(HashedNewNote, |hash| hash + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_old_nullifier_hash_is_incorrect() {
+        let pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(HashedOldNullifier, |hash| hash + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_mac_commitment_is_incorrect() {
+        let pk = MigrationProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(MacCommitment, |c| c + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use halo2_proofs::halo2curves::ff::PrimeField;
+use macros::embeddable;
+use rand_core::RngCore;
+
+use crate::{
+    chips::viewing_key,
+    consts::merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+    curve_arithmetic,
+    embed::Embed,
+    merkle::generate_example_path_with_given_leaf,
+    migration::{circuit::MigrationCircuit, MigrationInstance},
+    note_hash,
+    poseidon::off_circuit::hash,
+    version::NOTE_VERSION,
+    Field, Fr, Note, NoteVersion, ProverKnowledge, PublicInputProvider, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+#[embeddable(
+    receiver = "MigrationProverKnowledge<Value>",
+    embedded = "MigrationProverKnowledge<crate::AssignedCell>"
+)]
+pub struct MigrationProverKnowledge<T> {
+    // Additional public parameters that need to be included in proof
+    pub commitment: T,
+
+    // Old note
+    pub old_note_version: T,
+    pub id: T,
+    pub nullifier_old: T,
+    pub account_balance: T,
+    pub token_address: T,
+
+    // Merkle proof
+    pub path: [[T; ARITY]; NOTE_TREE_HEIGHT],
+
+    // New note
+    pub nullifier_new: T,
+
+    // Salt for MAC.
+    pub mac_salt: T,
+}
+
+impl ProverKnowledge for MigrationProverKnowledge<Fr> {
+    type Circuit = MigrationCircuit;
+    type PublicInput = MigrationInstance;
+
+    /// All initial values are random, including the version of the old note.
+    fn random_correct_example(rng: &mut impl RngCore) -> Self {
+        let id = curve_arithmetic::generate_user_id(Fr::random(&mut *rng).to_bytes());
+        let nullifier_old = Fr::random(&mut *rng);
+        let old_note_version = NoteVersion::new(rng.next_u32() as u8);
+
+        let account_balance = Fr::from_u128(rng.next_u64() as u128);
+        let token_address = Fr::random(&mut *rng);
+        let h_note_old = note_hash(&Note {
+            version: old_note_version,
+            id,
+            nullifier: nullifier_old,
+            account_balance,
+            token_address,
+        });
+
+        let (_, path) = generate_example_path_with_given_leaf(h_note_old, &mut *rng);
+
+        Self {
+            commitment: Fr::random(&mut *rng),
+            old_note_version: old_note_version.as_field(),
+            id,
+            nullifier_old,
+            account_balance,
+            token_address,
+            path,
+            nullifier_new: Fr::random(&mut *rng),
+            mac_salt: Fr::random(rng),
+        }
+    }
+
+    fn create_circuit(&self) -> Self::Circuit {
+        MigrationCircuit(MigrationProverKnowledge {
+            commitment: Value::known(self.commitment),
+
+            old_note_version: Value::known(self.old_note_version),
+            id: Value::known(self.id),
+            nullifier_old: Value::known(self.nullifier_old),
+            account_balance: Value::known(self.account_balance),
+            token_address: Value::known(self.token_address),
+
+            path: self.path.map(|level| level.map(Value::known)),
+
+            nullifier_new: Value::known(self.nullifier_new),
+
+            mac_salt: Value::known(self.mac_salt),
+        })
+    }
+}
+
+impl PublicInputProvider<MigrationInstance> for MigrationProverKnowledge<Fr> {
+    fn compute_public_input(&self, instance_id: MigrationInstance) -> Fr {
+        let viewing_key = viewing_key::off_circuit::derive_viewing_key(self.id);
+
+        match instance_id {
+            MigrationInstance::MerkleRoot => hash(&self.path[NOTE_TREE_HEIGHT - 1]),
+            MigrationInstance::HashedOldNullifier => hash(&[self.nullifier_old]),
+            MigrationInstance::HashedNewNote => note_hash(&Note {
+                version: NOTE_VERSION,
+                id: self.id,
+                nullifier: self.nullifier_new,
+                account_balance: self.account_balance,
+                token_address: self.token_address,
+            }),
+            MigrationInstance::TokenAddress => self.token_address,
+            MigrationInstance::OldNoteVersion => self.old_note_version,
+            MigrationInstance::Commitment => self.commitment,
+            MigrationInstance::MacSalt => self.mac_salt,
+            MigrationInstance::MacCommitment => hash(&[self.mac_salt, viewing_key]),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Migration of a note to the current note version.
+//!
+//! The owner proves knowledge of a note of version `OldNoteVersion` in the tree and creates a note
+//! of version `NOTE_VERSION` with the same ID, balance and token. The old nullifier is revealed
+//! (hashed), just like in deposits and withdrawals, so that the old note cannot be spent again.
+
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::{
+    chips::{mac::MacInstance, note::NoteInstance},
+    merkle::MerkleInstance,
+};
+
+mod chip;
+mod circuit;
+mod knowledge;
+
+pub use circuit::MigrationCircuit;
+pub use knowledge::MigrationProverKnowledge;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
+pub enum MigrationInstance {
+    MerkleRoot,
+    HashedOldNullifier,
+    HashedNewNote,
+    TokenAddress,
+    OldNoteVersion,
+    Commitment,
+    MacSalt,
+    MacCommitment,
+}
+
+impl TryFrom<MigrationInstance> for MerkleInstance {
+    type Error = ();
+
+    fn try_from(value: MigrationInstance) -> Result<Self, Self::Error> {
+        match value {
+            MigrationInstance::MerkleRoot => Ok(Self::MerkleRoot),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<MigrationInstance> for NoteInstance {
+    type Error = ();
+
+    fn try_from(value: MigrationInstance) -> Result<Self, Self::Error> {
+        match value {
+            MigrationInstance::TokenAddress => Ok(NoteInstance::TokenAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<MigrationInstance> for MacInstance {
+    type Error = ();
+
+    fn try_from(value: MigrationInstance) -> Result<Self, Self::Error> {
+        match value {
+            MigrationInstance::MacSalt => Ok(Self::MacSalt),
+            MigrationInstance::MacCommitment => Ok(Self::MacCommitment),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{vec, vec::Vec};
+
+    use strum::IntoEnumIterator;
+
+    use super::{MigrationInstance, MigrationInstance::*};
+
+    #[test]
+    fn instance_order() {
+        // This is the order used in other parts of the codebase (e.g., in contracts).
+        let expected_order = vec![
+            MerkleRoot,
+            HashedOldNullifier,
+            HashedNewNote,
+            TokenAddress,
+            OldNoteVersion,
+            Commitment,
+            MacSalt,
+            MacCommitment,
+        ];
+        assert_eq!(
+            expected_order,
+            MigrationInstance::iter().collect::<Vec<_>>()
+        );
+    }
+}
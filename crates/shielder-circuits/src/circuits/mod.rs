@@ -19,9 +19,14 @@ use transcript::Keccak256Transcript;
 
 use crate::consts::MAX_K;
 
+pub mod balance_attestation;
+pub mod batch_withdraw;
+pub mod claim;
 pub mod deposit;
 pub mod merkle;
+pub mod migration;
 pub mod new_account;
+pub mod transfer;
 pub mod withdraw;
 
 pub mod marshall;
@@ -55,7 +60,7 @@ pub fn generate_keys_with_min_k(
     let circuit = circuit.without_witnesses();
     let mut last_err = None;
 
-    for k in 6..MAX_K {
+    for k in 6..=MAX_K {
         let mut params = params.clone();
         params.downsize(k);
         match keygen_vk_custom(&params, &circuit, COMPRESS_SELECTORS) {
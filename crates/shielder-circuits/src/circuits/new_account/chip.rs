@@ -7,6 +7,7 @@ use crate::{
         note::{Note, NoteChip},
         to_affine::ToAffineChip,
         to_projective::ToProjectiveChip,
+        token_list::TokenListChip,
         viewing_key::ViewingKeyChip,
     },
     circuits::new_account::knowledge::NewAccountProverKnowledge,
@@ -30,6 +31,7 @@ pub struct NewAccountChip {
     pub el_gamal_encryption: ElGamalEncryptionChip,
     pub to_projective: ToProjectiveChip,
     pub to_affine: ToAffineChip,
+    pub token_list: TokenListChip,
 }
 
 impl NewAccountChip {
@@ -159,4 +161,16 @@ impl NewAccountChip {
         self.public_inputs
             .constrain_cells(synthesizer, [(knowledge.commitment.clone(), Commitment)])
     }
+
+    pub fn check_token_allowed(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &NewAccountProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.token_list.check_membership(
+            synthesizer,
+            &knowledge.token_address,
+            &knowledge.token_list_path,
+        )
+    }
 }
@@ -33,7 +33,8 @@ impl Circuit<Fr> for NewAccountCircuit {
             .with_is_point_on_curve_affine()
             .with_to_projective_chip()
             .with_to_affine_chip()
-            .with_el_gamal_encryption_chip();
+            .with_el_gamal_encryption_chip()
+            .with_token_list(public_inputs.narrow());
 
         (
             NewAccountChip {
@@ -44,6 +45,7 @@ impl Circuit<Fr> for NewAccountCircuit {
                 el_gamal_encryption: configs_builder.el_gamal_encryption_chip(),
                 to_projective: configs_builder.to_projective_chip(),
                 to_affine: configs_builder.to_affine_chip(),
+                token_list: configs_builder.token_list_chip(),
             },
             configs_builder.finish(),
         )
@@ -66,7 +68,8 @@ impl Circuit<Fr> for NewAccountCircuit {
         main_chip.constrain_prenullifier(&mut synthesizer, &knowledge)?;
         main_chip.constrain_encrypting_viewing_key(&mut synthesizer, &knowledge)?;
         main_chip.check_mac(&mut synthesizer, &knowledge)?;
-        main_chip.check_commitment(&mut synthesizer, &knowledge)
+        main_chip.check_commitment(&mut synthesizer, &knowledge)?;
+        main_chip.check_token_allowed(&mut synthesizer, &knowledge)
     }
 }
 
@@ -77,7 +80,9 @@ mod tests {
     use rand_core::{OsRng, SeedableRng};
 
     use crate::{
+        chips::token_list::off_circuit::TokenList,
         circuits::{
+            merkle::generate_example_path_with_given_leaf,
             new_account::knowledge::NewAccountProverKnowledge,
             test_utils::{
                 expect_prover_success_and_run_verification, run_full_pipeline,
@@ -100,6 +105,7 @@ mod tests {
         let mut rng = SmallRng::from_seed([42; 32]);
         let mut pk = NewAccountProverKnowledge::random_correct_example(&mut rng);
         pk.token_address = Fr::from(123);
+        (_, pk.token_list_path) = generate_example_path_with_given_leaf(pk.token_address, &mut rng);
         let pub_input = pk.serialize_public_input();
 
         assert!(
@@ -182,5 +188,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn passes_with_token_from_token_list() {
+        let mut pk = NewAccountProverKnowledge::random_correct_example(&mut OsRng);
+        let token_list = TokenList::new(&[Fr::from(1), Fr::from(123), Fr::from(7)]).unwrap();
+        pk.token_address = Fr::from(123);
+        pk.token_list_path = token_list.path(pk.token_address).unwrap();
+        let pub_input = pk.serialize_public_input();
+
+        assert_eq!(pk.compute_public_input(TokenListRoot), token_list.root());
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_ok()
+        );
+    }
+
+    #[test]
+    fn fails_if_token_is_not_in_token_list() {
+        let mut pk = NewAccountProverKnowledge::random_correct_example(&mut OsRng);
+        pk.token_address = Fr::from(123);
+        let pub_input = pk.serialize_public_input();
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_token_list_root_is_incorrect() {
+        let pk = NewAccountProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(TokenListRoot, |r| r + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
     // TODO: Add more tests, as the above tests do not cover all the logic that should be covered.
 }
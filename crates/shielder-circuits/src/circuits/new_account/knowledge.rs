@@ -7,10 +7,14 @@ use crate::{
         el_gamal::{self},
         viewing_key,
     },
-    consts::FIELD_BITS,
+    consts::{
+        merkle_constants::{ARITY, TOKEN_TREE_HEIGHT},
+        FIELD_BITS,
+    },
     curve_arithmetic::{self, GrumpkinPointAffine},
     embed::Embed,
     field_element_to_le_bits, le_bits_to_field_element,
+    merkle::generate_example_path_with_given_leaf,
     new_account::{circuit::NewAccountCircuit, NewAccountInstance},
     note_hash,
     poseidon::off_circuit::hash,
@@ -32,6 +36,8 @@ pub struct NewAccountProverKnowledge<T> {
     pub encryption_salt: [T; FIELD_BITS],
     pub anonymity_revoker_public_key: GrumpkinPointAffine<T>,
     pub mac_salt: T,
+    /// Path of `token_address` in the tree of allowed tokens.
+    pub token_list_path: [[T; ARITY]; TOKEN_TREE_HEIGHT],
 }
 
 impl<T: Default + Copy> Default for NewAccountProverKnowledge<T> {
@@ -45,6 +51,7 @@ impl<T: Default + Copy> Default for NewAccountProverKnowledge<T> {
             encryption_salt: [T::default(); FIELD_BITS],
             anonymity_revoker_public_key: GrumpkinPointAffine::default(),
             mac_salt: T::default(),
+            token_list_path: [[T::default(); ARITY]; TOKEN_TREE_HEIGHT],
         }
     }
 }
@@ -54,15 +61,20 @@ impl ProverKnowledge for NewAccountProverKnowledge<Fr> {
     type PublicInput = NewAccountInstance;
 
     fn random_correct_example(rng: &mut impl RngCore) -> Self {
+        let token_address = Fr::ZERO;
+        let (_, token_list_path) =
+            generate_example_path_with_given_leaf::<TOKEN_TREE_HEIGHT>(token_address, &mut *rng);
+
         Self {
             id: curve_arithmetic::generate_user_id(Fr::random(&mut *rng).to_bytes()),
             nullifier: Fr::random(&mut *rng),
             initial_deposit: Fr::ONE,
             commitment: Fr::random(&mut *rng),
-            token_address: Fr::ZERO,
+            token_address,
             encryption_salt: field_element_to_le_bits(grumpkin::Fr::ONE),
             anonymity_revoker_public_key: GrumpkinPointAffine::random(rng),
             mac_salt: Fr::random(rng),
+            token_list_path,
         }
     }
 
@@ -79,6 +91,7 @@ impl ProverKnowledge for NewAccountProverKnowledge<Fr> {
                 Value::known(self.anonymity_revoker_public_key.y),
             ),
             mac_salt: Value::known(self.mac_salt),
+            token_list_path: self.token_list_path.map(|level| level.map(Value::known)),
         })
     }
 }
@@ -121,6 +134,7 @@ impl PublicInputProvider<NewAccountInstance> for NewAccountProverKnowledge<Fr> {
             NewAccountInstance::EncryptedKeyCiphertext2Y => ciphertext2.y,
             NewAccountInstance::MacSalt => self.mac_salt,
             NewAccountInstance::MacCommitment => hash(&[self.mac_salt, viewing_key]),
+            NewAccountInstance::TokenListRoot => hash(&self.token_list_path[TOKEN_TREE_HEIGHT - 1]),
         }
     }
 }
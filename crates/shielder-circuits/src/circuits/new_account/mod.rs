@@ -7,7 +7,7 @@ mod knowledge;
 pub use circuit::NewAccountCircuit;
 pub use knowledge::NewAccountProverKnowledge;
 
-use crate::chips::{mac::MacInstance, note::NoteInstance};
+use crate::chips::{mac::MacInstance, note::NoteInstance, token_list::TokenListInstance};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
 pub enum NewAccountInstance {
@@ -24,6 +24,7 @@ pub enum NewAccountInstance {
     EncryptedKeyCiphertext2Y,
     MacSalt,
     MacCommitment,
+    TokenListRoot,
 }
 
 impl TryFrom<NewAccountInstance> for NoteInstance {
@@ -49,6 +50,17 @@ impl TryFrom<NewAccountInstance> for MacInstance {
     }
 }
 
+impl TryFrom<NewAccountInstance> for TokenListInstance {
+    type Error = ();
+
+    fn try_from(value: NewAccountInstance) -> Result<Self, Self::Error> {
+        match value {
+            NewAccountInstance::TokenListRoot => Ok(Self::TokenListRoot),
+            _ => Err(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{vec, vec::Vec};
@@ -74,6 +86,7 @@ mod tests {
             EncryptedKeyCiphertext2Y,
             MacSalt,
             MacCommitment,
+            TokenListRoot,
         ];
         assert_eq!(
             expected_order,
@@ -0,0 +1,277 @@
+use halo2_proofs::{arithmetic::Field, halo2curves::bn256::Fr, plonk::Error};
+
+use crate::{
+    chips::{
+        el_gamal::{ElGamalEncryptionChip, ElGamalEncryptionChipOutput, ElGamalEncryptionInput},
+        mac::{MacChip, MacInput},
+        note::{Note, NoteChip},
+        range_check::RangeCheckChip,
+        receipt::{Receipt, ReceiptChip},
+        sum::SumChip,
+        to_affine::ToAffineChip,
+        to_projective::ToProjectiveChip,
+        viewing_key::ViewingKeyChip,
+    },
+    circuits::{
+        merkle::{MerkleChip, MerkleProverKnowledge},
+        transfer::knowledge::TransferProverKnowledge,
+    },
+    consts::{FIELD_BITS, RANGE_PROOF_NUM_WORDS},
+    curve_arithmetic::{self, GrumpkinPointAffine},
+    embed::Embed,
+    gates::{is_point_on_curve_affine::IsPointOnCurveAffineGate, Gate},
+    instance_wrapper::InstanceWrapper,
+    poseidon::circuit::{hash, PoseidonChip},
+    synthesizer::Synthesizer,
+    transfer::TransferInstance::{self, *},
+    version::NOTE_VERSION,
+    AssignedCell, GrumpkinPoint,
+};
+
+#[derive(Clone, Debug)]
+pub struct TransferChip {
+    pub public_inputs: InstanceWrapper<TransferInstance>,
+    pub poseidon: PoseidonChip,
+    pub merkle: MerkleChip,
+    pub range_check: RangeCheckChip,
+    pub sum_chip: SumChip,
+    pub note: NoteChip,
+    pub is_point_on_curve: IsPointOnCurveAffineGate,
+    pub el_gamal_encryption: ElGamalEncryptionChip,
+    pub to_projective: ToProjectiveChip,
+    pub to_affine: ToAffineChip,
+}
+
+impl TransferChip {
+    pub fn check_old_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let old_note = self.note.note_hash(
+            synthesizer,
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier_old.clone(),
+                account_balance: knowledge.account_old_balance.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.merkle.synthesize(
+            synthesizer,
+            &MerkleProverKnowledge::new(old_note, &knowledge.path),
+        )
+    }
+
+    pub fn check_old_nullifier(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let hashed_old_nullifier = hash(
+            synthesizer,
+            self.poseidon.clone(),
+            [knowledge.nullifier_old.clone()],
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(hashed_old_nullifier, HashedOldNullifier)])
+    }
+
+    pub fn check_new_note(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        // Unlike in withdrawals, the value is not checked by the contract, so it has to be range
+        // checked here. Together with the check of the new balance, this ensures that the value
+        // does not exceed the old balance.
+        self.range_check.constrain_value::<RANGE_PROOF_NUM_WORDS>(
+            synthesizer,
+            knowledge.transfer_value.clone(),
+        )?;
+
+        let new_balance = self.note.decrease_balance(
+            synthesizer,
+            knowledge.account_old_balance.clone(),
+            knowledge.transfer_value.clone(),
+        )?;
+
+        self.range_check
+            .constrain_value::<RANGE_PROOF_NUM_WORDS>(synthesizer, new_balance.clone())?;
+
+        let new_note = self.note.note_hash(
+            synthesizer,
+            &Note {
+                version: NOTE_VERSION,
+                id: knowledge.id.clone(),
+                nullifier: knowledge.nullifier_new.clone(),
+                account_balance: new_balance,
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(new_note, HashedNewNote)])
+    }
+
+    pub fn check_receipt(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let receipt = ReceiptChip::new(self.poseidon.clone()).receipt_hash(
+            synthesizer,
+            &Receipt {
+                tag: knowledge.recipient_tag.clone(),
+                nonce: knowledge.receipt_nonce.clone(),
+                value: knowledge.transfer_value.clone(),
+                token_address: knowledge.token_address.clone(),
+            },
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(receipt, TransferInstance::Receipt)])
+    }
+
+    /// Encrypt the receipt nonce with the recipient public key and with the anonymity revoker
+    /// public key. The nonce is used as the x-coordinate of the encrypted point.
+    pub fn check_encrypted_receipt_nonce(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let nonce = knowledge.receipt_nonce.clone();
+
+        let y_squared_value =
+            curve_arithmetic::quadratic_residue_given_x_affine(nonce.value().copied());
+        let y_value =
+            y_squared_value.map(|v| v.sqrt().expect("element does not have a square root"));
+        let y = y_value.embed(synthesizer, "y")?;
+        self.is_point_on_curve.apply_in_new_region(
+            synthesizer,
+            GrumpkinPointAffine::new(nonce.clone(), y.clone()),
+        )?;
+
+        let z = synthesizer.assign_constant("ONE", Fr::ONE)?;
+        let message = GrumpkinPoint::new(nonce, y, z);
+
+        let [c1, c2] = self.encrypt(
+            synthesizer,
+            &message,
+            &knowledge.recipient_public_key,
+            &knowledge.encryption_salt,
+        )?;
+        let [revoker_c1, revoker_c2] = self.encrypt(
+            synthesizer,
+            &message,
+            &knowledge.anonymity_revoker_public_key,
+            &knowledge.revoker_encryption_salt,
+        )?;
+
+        self.public_inputs.constrain_cells(
+            synthesizer,
+            [
+                (c1.x, EncryptedNonceCiphertext1X),
+                (c1.y, EncryptedNonceCiphertext1Y),
+                (c2.x, EncryptedNonceCiphertext2X),
+                (c2.y, EncryptedNonceCiphertext2Y),
+                (
+                    knowledge.anonymity_revoker_public_key.x.clone(),
+                    AnonymityRevokerPublicKeyX,
+                ),
+                (
+                    knowledge.anonymity_revoker_public_key.y.clone(),
+                    AnonymityRevokerPublicKeyY,
+                ),
+                (revoker_c1.x, RevokerCiphertext1X),
+                (revoker_c1.y, RevokerCiphertext1Y),
+                (revoker_c2.x, RevokerCiphertext2X),
+                (revoker_c2.y, RevokerCiphertext2Y),
+            ],
+        )
+    }
+
+    /// ElGamal encryption of `message` with `public_key`, as affine ciphertexts.
+    fn encrypt(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        message: &GrumpkinPoint<AssignedCell>,
+        public_key: &GrumpkinPointAffine<AssignedCell>,
+        salt_le_bits: &[AssignedCell; FIELD_BITS],
+    ) -> Result<[GrumpkinPointAffine<AssignedCell>; 2], Error> {
+        let public_key = self.to_projective.to_projective(synthesizer, public_key)?;
+
+        let ElGamalEncryptionChipOutput {
+            ciphertext1,
+            ciphertext2,
+        } = self.el_gamal_encryption.encrypt(
+            synthesizer,
+            &ElGamalEncryptionInput {
+                message: message.clone(),
+                public_key,
+                salt_le_bits: salt_le_bits.clone(),
+            },
+        )?;
+
+        Ok([
+            self.to_affine.to_affine(synthesizer, &ciphertext1)?,
+            self.to_affine.to_affine(synthesizer, &ciphertext2)?,
+        ])
+    }
+
+    /// Mask the transfer value with the viewing key of the receipt nonce, known to the recipient
+    /// (and to the anonymity revoker) after decrypting the nonce.
+    pub fn check_encrypted_value(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let value_key = ViewingKeyChip::new(self.poseidon.clone())
+            .derive_viewing_key(synthesizer, knowledge.receipt_nonce.clone())?;
+
+        let encrypted_value = synthesizer.assign_value(
+            "encrypted_value",
+            knowledge.transfer_value.value() + value_key.value(),
+        )?;
+        self.sum_chip.constrain_sum(
+            synthesizer,
+            knowledge.transfer_value.clone(),
+            value_key,
+            encrypted_value.clone(),
+        )?;
+
+        self.public_inputs
+            .constrain_cells(synthesizer, [(encrypted_value, EncryptedValue)])
+    }
+
+    pub fn check_commitment(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.public_inputs
+            .constrain_cells(synthesizer, [(knowledge.commitment.clone(), Commitment)])
+    }
+
+    pub fn check_mac(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &TransferProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        let viewing_key = ViewingKeyChip::new(self.poseidon.clone())
+            .derive_viewing_key(synthesizer, knowledge.id.clone())?;
+
+        MacChip::new(self.poseidon.clone(), self.public_inputs.narrow()).mac(
+            synthesizer,
+            &MacInput {
+                key: viewing_key,
+                salt: knowledge.mac_salt.clone(),
+            },
+        )?;
+
+        Ok(())
+    }
+}
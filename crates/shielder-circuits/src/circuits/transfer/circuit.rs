@@ -0,0 +1,245 @@
+use halo2_proofs::{
+    circuit::{floor_planner::V1, Layouter},
+    plonk::{Advice, Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    circuits::transfer::chip::TransferChip,
+    column_pool::{ColumnPool, PreSynthesisPhase},
+    config_builder::ConfigsBuilder,
+    embed::Embed,
+    instance_wrapper::InstanceWrapper,
+    synthesizer::create_synthesizer,
+    transfer::{TransferInstance, TransferProverKnowledge},
+    Fr, Value,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct TransferCircuit(pub TransferProverKnowledge<Value>);
+
+impl Circuit<Fr> for TransferCircuit {
+    type Config = (TransferChip, ColumnPool<Advice, PreSynthesisPhase>);
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let public_inputs = InstanceWrapper::<TransferInstance>::new(meta);
+
+        let configs_builder = ConfigsBuilder::new(meta)
+            .with_merkle(public_inputs.narrow())
+            .with_range_check()
+            .with_note(public_inputs.narrow())
+            .with_is_point_on_curve_affine()
+            .with_to_projective_chip()
+            .with_to_affine_chip()
+            .with_el_gamal_encryption_chip();
+
+        (
+            TransferChip {
+                public_inputs,
+                poseidon: configs_builder.poseidon_chip(),
+                merkle: configs_builder.merkle_chip(),
+                range_check: configs_builder.range_check_chip(),
+                sum_chip: configs_builder.sum_chip(),
+                note: configs_builder.note_chip(),
+                is_point_on_curve: configs_builder.is_point_on_curve_affine_gate(),
+                el_gamal_encryption: configs_builder.el_gamal_encryption_chip(),
+                to_projective: configs_builder.to_projective_chip(),
+                to_affine: configs_builder.to_affine_chip(),
+            },
+            configs_builder.finish(),
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (main_chip, column_pool): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let pool = column_pool.start_synthesis();
+        let mut synthesizer = create_synthesizer(&mut layouter, &pool);
+        let knowledge = self.0.embed(&mut synthesizer, "TransferProverKnowledge")?;
+
+        main_chip.check_old_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_old_nullifier(&mut synthesizer, &knowledge)?;
+        main_chip.check_new_note(&mut synthesizer, &knowledge)?;
+        main_chip.check_receipt(&mut synthesizer, &knowledge)?;
+        main_chip.check_encrypted_receipt_nonce(&mut synthesizer, &knowledge)?;
+        main_chip.check_encrypted_value(&mut synthesizer, &knowledge)?;
+        main_chip.check_commitment(&mut synthesizer, &knowledge)?;
+        main_chip.check_mac(&mut synthesizer, &knowledge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::{bn256::Fr, grumpkin};
+    use rand_core::OsRng;
+
+    use crate::{
+        circuits::{
+            test_utils::{
+                expect_prover_success_and_run_verification, run_full_pipeline,
+                PublicInputProviderExt,
+            },
+            transfer::knowledge::TransferProverKnowledge,
+        },
+        curve_arithmetic::GrumpkinPointAffine,
+        decrypt, derive_viewing_key, generate_keys, generate_keys_with_min_k, generate_proof,
+        generate_setup_params, receipt_hash, receiving_tag,
+        transfer::TransferInstance::{self, *},
+        Field, GrumpkinPoint, ProverKnowledge, PublicInputProvider, Receipt, MAX_K,
+    };
+
+    #[test]
+    fn passes_if_inputs_correct() {
+        run_full_pipeline::<TransferProverKnowledge<Fr>>();
+    }
+
+    fn decrypt_nonce(
+        pk: &TransferProverKnowledge<Fr>,
+        private_key: grumpkin::Fr,
+        [c1x, c1y, c2x, c2y]: [TransferInstance; 4],
+    ) -> Fr {
+        let ciphertext = |x: TransferInstance, y: TransferInstance| -> GrumpkinPoint<Fr> {
+            GrumpkinPointAffine::new(pk.compute_public_input(x), pk.compute_public_input(y)).into()
+        };
+        let message: GrumpkinPointAffine<Fr> =
+            decrypt(ciphertext(c1x, c1y), ciphertext(c2x, c2y), private_key).into();
+        message.x
+    }
+
+    #[test]
+    fn recipient_can_decrypt_and_recompute_the_receipt() {
+        let (private_key, public_key) = generate_keys(&mut OsRng);
+        let mut pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        pk.recipient_public_key = public_key.into();
+        pk.recipient_tag = receiving_tag(Fr::from(7));
+        pk.transfer_value = Fr::from(42);
+
+        let nonce = decrypt_nonce(
+            &pk,
+            private_key,
+            [
+                EncryptedNonceCiphertext1X,
+                EncryptedNonceCiphertext1Y,
+                EncryptedNonceCiphertext2X,
+                EncryptedNonceCiphertext2Y,
+            ],
+        );
+        let value = pk.compute_public_input(EncryptedValue) - derive_viewing_key(nonce);
+
+        assert_eq!(nonce, pk.receipt_nonce);
+        assert_eq!(value, Fr::from(42));
+        assert_eq!(
+            receipt_hash(&Receipt {
+                tag: receiving_tag(Fr::from(7)),
+                nonce,
+                value,
+                token_address: pk.token_address,
+            }),
+            pk.compute_public_input(TransferInstance::Receipt)
+        );
+    }
+
+    #[test]
+    fn anonymity_revoker_can_decrypt_the_nonce() {
+        let (private_key, public_key) = generate_keys(&mut OsRng);
+        let mut pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        pk.anonymity_revoker_public_key = public_key.into();
+
+        let nonce = decrypt_nonce(
+            &pk,
+            private_key,
+            [
+                RevokerCiphertext1X,
+                RevokerCiphertext1Y,
+                RevokerCiphertext2X,
+                RevokerCiphertext2Y,
+            ],
+        );
+
+        assert_eq!(nonce, pk.receipt_nonce);
+    }
+
+    #[test]
+    fn fails_if_receipt_is_incorrect() {
+        let pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(TransferInstance::Receipt, |hash| hash + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_h_note_new_is_not_the_hash_of_appropriate_witnesses() {
+        let pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(HashedNewNote, |hash| hash + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_ciphertext_is_incorrect() {
+        let pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(EncryptedNonceCiphertext2X, |x| x + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_revoker_ciphertext_is_incorrect() {
+        let pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(RevokerCiphertext1Y, |y| y + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_encrypted_value_is_incorrect() {
+        let pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(EncryptedValue, |v| v + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_mac_commitment_is_incorrect() {
+        let pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(MacCommitment, |c| c + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn fails_if_transfer_value_exceeds_balance() {
+        let mut pk = TransferProverKnowledge::random_correct_example(&mut OsRng);
+        pk.transfer_value = pk.account_old_balance + Fr::ONE;
+
+        let params = generate_setup_params(MAX_K, &mut OsRng);
+        let circuit = pk.create_circuit();
+        let (params, _, key, _) = generate_keys_with_min_k(circuit.clone(), params).unwrap();
+        generate_proof(
+            &params,
+            &key,
+            circuit,
+            &pk.serialize_public_input(),
+            &mut OsRng,
+        );
+    }
+}
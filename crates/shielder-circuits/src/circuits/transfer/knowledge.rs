@@ -0,0 +1,230 @@
+use halo2_proofs::halo2curves::{ff::PrimeField, grumpkin};
+use macros::embeddable;
+use rand_core::RngCore;
+
+use crate::{
+    chips::{
+        el_gamal,
+        receipt::{off_circuit::receipt_hash, Receipt},
+        viewing_key,
+    },
+    consts::{
+        merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
+        FIELD_BITS, MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK,
+    },
+    curve_arithmetic::{self, GrumpkinPointAffine},
+    embed::Embed,
+    field_element_to_le_bits, le_bits_to_field_element,
+    merkle::generate_example_path_with_given_leaf,
+    note_hash,
+    poseidon::off_circuit::hash,
+    transfer::{circuit::TransferCircuit, TransferInstance},
+    version::NOTE_VERSION,
+    Field, Fr, Note, ProverKnowledge, PublicInputProvider, Value,
+};
+
+#[derive(Clone, Debug)]
+#[embeddable(
+    receiver = "TransferProverKnowledge<Value>",
+    embedded = "TransferProverKnowledge<crate::AssignedCell>"
+)]
+pub struct TransferProverKnowledge<T> {
+    pub transfer_value: T,
+
+    // Additional public parameters that need to be included in proof
+    pub commitment: T,
+
+    // Old note
+    pub id: T,
+    pub nullifier_old: T,
+    pub account_old_balance: T,
+    pub token_address: T,
+
+    // Merkle proof
+    pub path: [[T; ARITY]; NOTE_TREE_HEIGHT],
+
+    // New note
+    pub nullifier_new: T,
+
+    // Receipt
+    /// Receiving tag of the recipient (see `chips::receipt`).
+    pub recipient_tag: T,
+    /// Nonce of the receipt. Must be the x-coordinate of a point on the Grumpkin curve (see
+    /// `generate_receipt_nonce`).
+    pub receipt_nonce: T,
+    pub recipient_public_key: GrumpkinPointAffine<T>,
+    pub encryption_salt: [T; FIELD_BITS],
+
+    // Encryption of the receipt nonce for the anonymity revoker.
+    pub anonymity_revoker_public_key: GrumpkinPointAffine<T>,
+    pub revoker_encryption_salt: [T; FIELD_BITS],
+
+    // Salt for MAC.
+    pub mac_salt: T,
+}
+
+impl<T: Default + Copy> Default for TransferProverKnowledge<T> {
+    fn default() -> Self {
+        Self {
+            transfer_value: T::default(),
+            commitment: T::default(),
+            id: T::default(),
+            nullifier_old: T::default(),
+            account_old_balance: T::default(),
+            token_address: T::default(),
+            path: [[T::default(); ARITY]; NOTE_TREE_HEIGHT],
+            nullifier_new: T::default(),
+            recipient_tag: T::default(),
+            receipt_nonce: T::default(),
+            recipient_public_key: GrumpkinPointAffine::default(),
+            encryption_salt: [T::default(); FIELD_BITS],
+            anonymity_revoker_public_key: GrumpkinPointAffine::default(),
+            revoker_encryption_salt: [T::default(); FIELD_BITS],
+            mac_salt: T::default(),
+        }
+    }
+}
+
+impl ProverKnowledge for TransferProverKnowledge<Fr> {
+    type Circuit = TransferCircuit;
+    type PublicInput = TransferInstance;
+
+    /// All initial values are random, except for the account balance and the transfer value.
+    ///
+    /// `account_old_balance` has the largest possible value that passes the range check.
+    fn random_correct_example(rng: &mut impl RngCore) -> Self {
+        let id = curve_arithmetic::generate_user_id(Fr::random(&mut *rng).to_bytes());
+        let nullifier_old = Fr::random(&mut *rng);
+
+        let account_old_balance = Fr::from_u128(MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK);
+        let token_address = Fr::ZERO;
+        let h_note_old = note_hash(&Note {
+            version: NOTE_VERSION,
+            id,
+            nullifier: nullifier_old,
+            account_balance: account_old_balance,
+            token_address,
+        });
+
+        let (_, path) = generate_example_path_with_given_leaf(h_note_old, &mut *rng);
+
+        Self {
+            transfer_value: Fr::ONE,
+            commitment: Fr::random(&mut *rng),
+            id,
+            nullifier_old,
+            account_old_balance,
+            token_address,
+            path,
+            nullifier_new: Fr::random(&mut *rng),
+            recipient_tag: Fr::random(&mut *rng),
+            receipt_nonce: curve_arithmetic::generate_receipt_nonce(
+                Fr::random(&mut *rng).to_bytes(),
+            ),
+            recipient_public_key: GrumpkinPointAffine::random(&mut *rng),
+            encryption_salt: field_element_to_le_bits(grumpkin::Fr::random(&mut *rng)),
+            anonymity_revoker_public_key: GrumpkinPointAffine::random(&mut *rng),
+            revoker_encryption_salt: field_element_to_le_bits(grumpkin::Fr::random(&mut *rng)),
+            mac_salt: Fr::random(rng),
+        }
+    }
+
+    fn create_circuit(&self) -> Self::Circuit {
+        TransferCircuit(TransferProverKnowledge {
+            transfer_value: Value::known(self.transfer_value),
+            commitment: Value::known(self.commitment),
+
+            id: Value::known(self.id),
+            nullifier_old: Value::known(self.nullifier_old),
+            account_old_balance: Value::known(self.account_old_balance),
+            token_address: Value::known(self.token_address),
+
+            path: self.path.map(|level| level.map(Value::known)),
+
+            nullifier_new: Value::known(self.nullifier_new),
+
+            recipient_tag: Value::known(self.recipient_tag),
+            receipt_nonce: Value::known(self.receipt_nonce),
+            recipient_public_key: GrumpkinPointAffine::new(
+                Value::known(self.recipient_public_key.x),
+                Value::known(self.recipient_public_key.y),
+            ),
+            encryption_salt: self.encryption_salt.map(Value::known),
+
+            anonymity_revoker_public_key: GrumpkinPointAffine::new(
+                Value::known(self.anonymity_revoker_public_key.x),
+                Value::known(self.anonymity_revoker_public_key.y),
+            ),
+            revoker_encryption_salt: self.revoker_encryption_salt.map(Value::known),
+
+            mac_salt: Value::known(self.mac_salt),
+        })
+    }
+}
+
+impl TransferProverKnowledge<Fr> {
+    /// ElGamal encryption of the receipt nonce with `public_key`, as affine ciphertexts.
+    fn encrypted_nonce(
+        &self,
+        public_key: GrumpkinPointAffine<Fr>,
+        salt_le_bits: &[Fr; FIELD_BITS],
+    ) -> (GrumpkinPointAffine<Fr>, GrumpkinPointAffine<Fr>) {
+        let y = curve_arithmetic::quadratic_residue_given_x_affine(self.receipt_nonce)
+            .sqrt()
+            .expect("element has a square root");
+        let salt: grumpkin::Fr = le_bits_to_field_element(salt_le_bits);
+        let (c1, c2) = el_gamal::off_circuit::encrypt(
+            GrumpkinPointAffine::new(self.receipt_nonce, y).into(),
+            public_key.into(),
+            salt,
+        );
+        (c1.into(), c2.into())
+    }
+}
+
+impl PublicInputProvider<TransferInstance> for TransferProverKnowledge<Fr> {
+    fn compute_public_input(&self, instance_id: TransferInstance) -> Fr {
+        let viewing_key = viewing_key::off_circuit::derive_viewing_key(self.id);
+        let value_key = viewing_key::off_circuit::derive_viewing_key(self.receipt_nonce);
+
+        let (ciphertext1, ciphertext2) =
+            self.encrypted_nonce(self.recipient_public_key, &self.encryption_salt);
+        let (revoker_ciphertext1, revoker_ciphertext2) = self.encrypted_nonce(
+            self.anonymity_revoker_public_key,
+            &self.revoker_encryption_salt,
+        );
+
+        match instance_id {
+            TransferInstance::MerkleRoot => hash(&self.path[NOTE_TREE_HEIGHT - 1]),
+            TransferInstance::HashedOldNullifier => hash(&[self.nullifier_old]),
+            TransferInstance::HashedNewNote => note_hash(&Note {
+                version: NOTE_VERSION,
+                id: self.id,
+                nullifier: self.nullifier_new,
+                account_balance: self.account_old_balance - self.transfer_value,
+                token_address: self.token_address,
+            }),
+            TransferInstance::Receipt => receipt_hash(&Receipt {
+                tag: self.recipient_tag,
+                nonce: self.receipt_nonce,
+                value: self.transfer_value,
+                token_address: self.token_address,
+            }),
+            TransferInstance::TokenAddress => self.token_address,
+            TransferInstance::Commitment => self.commitment,
+            TransferInstance::EncryptedNonceCiphertext1X => ciphertext1.x,
+            TransferInstance::EncryptedNonceCiphertext1Y => ciphertext1.y,
+            TransferInstance::EncryptedNonceCiphertext2X => ciphertext2.x,
+            TransferInstance::EncryptedNonceCiphertext2Y => ciphertext2.y,
+            TransferInstance::EncryptedValue => self.transfer_value + value_key,
+            TransferInstance::AnonymityRevokerPublicKeyX => self.anonymity_revoker_public_key.x,
+            TransferInstance::AnonymityRevokerPublicKeyY => self.anonymity_revoker_public_key.y,
+            TransferInstance::RevokerCiphertext1X => revoker_ciphertext1.x,
+            TransferInstance::RevokerCiphertext1Y => revoker_ciphertext1.y,
+            TransferInstance::RevokerCiphertext2X => revoker_ciphertext2.x,
+            TransferInstance::RevokerCiphertext2Y => revoker_ciphertext2.y,
+            TransferInstance::MacSalt => self.mac_salt,
+            TransferInstance::MacCommitment => hash(&[self.mac_salt, viewing_key]),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Private transfer of funds between two shielded accounts.
+//!
+//! The sender spends their note and creates a note of their own, with the balance decreased by
+//! the transferred value. The value itself goes to a receipt for the recipient (see
+//! `chips::receipt`), bound to the receiving tag that the recipient published. The recipient
+//! moves the value into their own note with the claim circuit. The sender knows everything about
+//! the receipt except for the recipient's ID, which is necessary to claim it.
+//!
+//! The receipt nonce is ElGamal-encrypted with the recipient's public key and the value is masked
+//! with a key derived from the nonce, so that the recipient can find and claim the receipt. The
+//! nonce is also encrypted with the anonymity revoker's public key: together with the viewing
+//! keys of all accounts, it lets the anonymity revoker identify the recipient of the transfer.
+
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::{
+    chips::{mac::MacInstance, note::NoteInstance},
+    merkle::MerkleInstance,
+};
+
+mod chip;
+mod circuit;
+mod knowledge;
+
+pub use circuit::TransferCircuit;
+pub use knowledge::TransferProverKnowledge;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumCount)]
+pub enum TransferInstance {
+    MerkleRoot,
+    HashedOldNullifier,
+    HashedNewNote,
+    Receipt,
+    TokenAddress,
+    Commitment,
+    EncryptedNonceCiphertext1X,
+    EncryptedNonceCiphertext1Y,
+    EncryptedNonceCiphertext2X,
+    EncryptedNonceCiphertext2Y,
+    EncryptedValue,
+    AnonymityRevokerPublicKeyX,
+    AnonymityRevokerPublicKeyY,
+    RevokerCiphertext1X,
+    RevokerCiphertext1Y,
+    RevokerCiphertext2X,
+    RevokerCiphertext2Y,
+    MacSalt,
+    MacCommitment,
+}
+
+impl TryFrom<TransferInstance> for MerkleInstance {
+    type Error = ();
+
+    fn try_from(value: TransferInstance) -> Result<Self, Self::Error> {
+        match value {
+            TransferInstance::MerkleRoot => Ok(Self::MerkleRoot),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<TransferInstance> for NoteInstance {
+    type Error = ();
+
+    fn try_from(value: TransferInstance) -> Result<Self, Self::Error> {
+        match value {
+            TransferInstance::TokenAddress => Ok(NoteInstance::TokenAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<TransferInstance> for MacInstance {
+    type Error = ();
+
+    fn try_from(value: TransferInstance) -> Result<Self, Self::Error> {
+        match value {
+            TransferInstance::MacSalt => Ok(Self::MacSalt),
+            TransferInstance::MacCommitment => Ok(Self::MacCommitment),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{vec, vec::Vec};
+
+    use strum::IntoEnumIterator;
+
+    use super::{TransferInstance, TransferInstance::*};
+
+    #[test]
+    fn instance_order() {
+        // This is the order used in other parts of the codebase (e.g., in contracts).
+        let expected_order = vec![
+            MerkleRoot,
+            HashedOldNullifier,
+            HashedNewNote,
+            Receipt,
+            TokenAddress,
+            Commitment,
+            EncryptedNonceCiphertext1X,
+            EncryptedNonceCiphertext1Y,
+            EncryptedNonceCiphertext2X,
+            EncryptedNonceCiphertext2Y,
+            EncryptedValue,
+            AnonymityRevokerPublicKeyX,
+            AnonymityRevokerPublicKeyY,
+            RevokerCiphertext1X,
+            RevokerCiphertext1Y,
+            RevokerCiphertext2X,
+            RevokerCiphertext2Y,
+            MacSalt,
+            MacCommitment,
+        ];
+        assert_eq!(expected_order, TransferInstance::iter().collect::<Vec<_>>());
+    }
+}
@@ -2,6 +2,7 @@ use halo2_proofs::plonk::Error;
 
 use crate::{
     chips::{
+        association_set::AssociationSetChip,
         mac::{MacChip, MacInput},
         note::{Note, NoteChip},
         range_check::RangeCheckChip,
@@ -29,9 +30,46 @@ pub struct WithdrawChip {
     pub range_check: RangeCheckChip,
     pub sum_chip: SumChip,
     pub note: NoteChip,
+    pub association_set: AssociationSetChip,
 }
 
 impl WithdrawChip {
+    /// The same chip, but constraining the public inputs at `public_inputs` (e.g. a withdrawal in
+    /// a batch).
+    pub fn with_public_inputs(&self, public_inputs: InstanceWrapper<WithdrawInstance>) -> Self {
+        Self {
+            merkle: MerkleChip {
+                public_inputs: public_inputs.narrow(),
+                ..self.merkle.clone()
+            },
+            note: NoteChip {
+                public_inputs: public_inputs.narrow(),
+                ..self.note.clone()
+            },
+            association_set: AssociationSetChip::new(
+                public_inputs.narrow(),
+                self.merkle.membership_gate,
+                self.poseidon.clone(),
+            ),
+            public_inputs,
+            ..self.clone()
+        }
+    }
+
+    /// Apply all the withdrawal constraints.
+    pub fn check_withdrawal(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &WithdrawProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.check_old_note(synthesizer, knowledge)?;
+        self.check_old_nullifier(synthesizer, knowledge)?;
+        self.check_new_note(synthesizer, knowledge)?;
+        self.check_commitment(synthesizer, knowledge)?;
+        self.check_mac(synthesizer, knowledge)?;
+        self.check_association_set(synthesizer, knowledge)
+    }
+
     pub fn check_old_note(
         &self,
         synthesizer: &mut impl Synthesizer,
@@ -130,4 +168,17 @@ impl WithdrawChip {
 
         Ok(())
     }
+
+    pub fn check_association_set(
+        &self,
+        synthesizer: &mut impl Synthesizer,
+        knowledge: &WithdrawProverKnowledge<AssignedCell>,
+    ) -> Result<(), Error> {
+        self.association_set.check_membership(
+            synthesizer,
+            &knowledge.id,
+            &knowledge.association_set_root,
+            &knowledge.association_path,
+        )
+    }
 }
@@ -31,7 +31,8 @@ impl Circuit<Fr> for WithdrawCircuit {
         let configs_builder = ConfigsBuilder::new(meta)
             .with_merkle(public_inputs.narrow())
             .with_range_check()
-            .with_note(public_inputs.narrow());
+            .with_note(public_inputs.narrow())
+            .with_association_set(public_inputs.narrow());
 
         (
             WithdrawChip {
@@ -41,6 +42,7 @@ impl Circuit<Fr> for WithdrawCircuit {
                 range_check: configs_builder.range_check_chip(),
                 sum_chip: configs_builder.sum_chip(),
                 note: configs_builder.note_chip(),
+                association_set: configs_builder.association_set_chip(),
             },
             configs_builder.finish(),
         )
@@ -55,11 +57,7 @@ impl Circuit<Fr> for WithdrawCircuit {
         let mut synthesizer = create_synthesizer(&mut layouter, &pool);
         let knowledge = self.0.embed(&mut synthesizer, "WithdrawProverKnowledge")?;
 
-        main_chip.check_old_note(&mut synthesizer, &knowledge)?;
-        main_chip.check_old_nullifier(&mut synthesizer, &knowledge)?;
-        main_chip.check_new_note(&mut synthesizer, &knowledge)?;
-        main_chip.check_commitment(&mut synthesizer, &knowledge)?;
-        main_chip.check_mac(&mut synthesizer, &knowledge)
+        main_chip.check_withdrawal(&mut synthesizer, &knowledge)
     }
 }
 
@@ -81,7 +79,8 @@ mod tests {
             withdraw::knowledge::WithdrawProverKnowledge,
         },
         consts::merkle_constants::NOTE_TREE_HEIGHT,
-        generate_keys_with_min_k, generate_proof, generate_setup_params, note_hash,
+        empty_association_path, generate_keys_with_min_k, generate_proof, generate_setup_params,
+        note_hash,
         poseidon::off_circuit::hash,
         test_utils::expect_instance_permutation_failures,
         version::NOTE_VERSION,
@@ -150,6 +149,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn passes_if_no_association_set_is_claimed() {
+        let mut pk = WithdrawProverKnowledge::random_correct_example(&mut OsRng);
+        pk.association_set_root = Fr::ZERO;
+        pk.association_path = empty_association_path(pk.id);
+
+        assert!(expect_prover_success_and_run_verification(
+            pk.create_circuit(),
+            &pk.serialize_public_input()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn fails_if_association_set_root_is_incorrect() {
+        let pk = WithdrawProverKnowledge::random_correct_example(&mut OsRng);
+        let pub_input = pk.with_substitution(AssociationSetRoot, |root| root + Fr::ONE);
+
+        assert!(
+            expect_prover_success_and_run_verification(pk.create_circuit(), &pub_input).is_err()
+        );
+    }
+
+    #[test]
+    fn fails_if_association_path_uses_other_account() {
+        let mut pk = WithdrawProverKnowledge::random_correct_example(&mut OsRng);
+
+        let (association_set_root, association_path) =
+            generate_example_path_with_given_leaf(Fr::random(&mut OsRng), &mut OsRng);
+        pk.association_set_root = association_set_root;
+        pk.association_path = association_path;
+
+        assert!(expect_prover_success_and_run_verification(
+            pk.create_circuit(),
+            &pk.serialize_public_input()
+        )
+        .is_err());
+    }
+
     #[test]
     fn fails_if_incorrect_h_nullifier_is_published() {
         let pk = WithdrawProverKnowledge::random_correct_example(&mut OsRng);
@@ -235,6 +273,7 @@ mod tests {
                 TokenAddress => pk.token_address,
                 MacSalt => pk.mac_salt,
                 MacCommitment => hash(&[pk.mac_salt, off_circuit::derive_viewing_key(pk.id)]),
+                AssociationSetRoot => pk.association_set_root,
             };
 
             assert_eq!(
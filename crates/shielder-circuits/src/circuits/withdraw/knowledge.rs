@@ -3,7 +3,7 @@ use macros::embeddable;
 use rand_core::RngCore;
 
 use crate::{
-    chips::viewing_key,
+    chips::{association_set::off_circuit::association_set_leaf, viewing_key},
     consts::{
         merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
         MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK,
@@ -43,6 +43,10 @@ pub struct WithdrawProverKnowledge<T> {
 
     // Salt for MAC.
     pub mac_salt: T,
+
+    // Association set (zero root means that no association set is claimed)
+    pub association_set_root: T,
+    pub association_path: [[T; ARITY]; NOTE_TREE_HEIGHT],
 }
 
 impl ProverKnowledge for WithdrawProverKnowledge<Fr> {
@@ -73,6 +77,8 @@ impl ProverKnowledge for WithdrawProverKnowledge<Fr> {
         });
 
         let (_, path) = generate_example_path_with_given_leaf(h_note_old, &mut *rng);
+        let (association_set_root, association_path) =
+            generate_example_path_with_given_leaf(association_set_leaf(id), &mut *rng);
 
         Self {
             withdrawal_value: Fr::ONE,
@@ -84,6 +90,8 @@ impl ProverKnowledge for WithdrawProverKnowledge<Fr> {
             path,
             nullifier_new: Fr::random(&mut *rng),
             mac_salt: Fr::random(rng),
+            association_set_root,
+            association_path,
         }
     }
 
@@ -102,6 +110,9 @@ impl ProverKnowledge for WithdrawProverKnowledge<Fr> {
             withdrawal_value: Value::known(self.withdrawal_value),
             commitment: Value::known(self.commitment),
             mac_salt: Value::known(self.mac_salt),
+
+            association_set_root: Value::known(self.association_set_root),
+            association_path: self.association_path.map(|level| level.map(Value::known)),
         })
     }
 }
@@ -125,6 +136,7 @@ impl PublicInputProvider<WithdrawInstance> for WithdrawProverKnowledge<Fr> {
             WithdrawInstance::TokenAddress => self.token_address,
             WithdrawInstance::MacSalt => self.mac_salt,
             WithdrawInstance::MacCommitment => hash(&[self.mac_salt, viewing_key]),
+            WithdrawInstance::AssociationSetRoot => self.association_set_root,
         }
     }
 }
@@ -1,11 +1,15 @@
 use strum_macros::{EnumCount, EnumIter};
 
-use crate::{chips::note::NoteInstance, merkle::MerkleInstance};
+use crate::{
+    chips::{association_set::AssociationSetInstance, note::NoteInstance},
+    merkle::MerkleInstance,
+};
 
 mod chip;
 mod circuit;
 mod knowledge;
 
+pub(crate) use chip::WithdrawChip;
 pub use circuit::WithdrawCircuit;
 pub use knowledge::WithdrawProverKnowledge;
 
@@ -21,6 +25,7 @@ pub enum WithdrawInstance {
     Commitment,
     MacSalt,
     MacCommitment,
+    AssociationSetRoot,
 }
 
 impl TryFrom<WithdrawInstance> for MerkleInstance {
@@ -57,6 +62,17 @@ impl TryFrom<WithdrawInstance> for MacInstance {
     }
 }
 
+impl TryFrom<WithdrawInstance> for AssociationSetInstance {
+    type Error = ();
+
+    fn try_from(value: WithdrawInstance) -> Result<Self, Self::Error> {
+        match value {
+            WithdrawInstance::AssociationSetRoot => Ok(Self::AssociationSetRoot),
+            _ => Err(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{vec, vec::Vec};
@@ -77,6 +93,7 @@ mod tests {
             Commitment,
             MacSalt,
             MacCommitment,
+            AssociationSetRoot,
         ];
         assert_eq!(expected_order, WithdrawInstance::iter().collect::<Vec<_>>());
     }
@@ -2,6 +2,7 @@ use halo2_proofs::plonk::{Advice, ConstraintSystem, Fixed};
 
 use crate::{
     chips::{
+        association_set::{AssociationSetChip, AssociationSetInstance},
         el_gamal::ElGamalEncryptionChip,
         note::{NoteChip, NoteInstance},
         points_add::PointsAddChip,
@@ -10,9 +11,10 @@ use crate::{
         sum::SumChip,
         to_affine::ToAffineChip,
         to_projective::ToProjectiveChip,
+        token_list::{TokenListChip, TokenListInstance},
     },
     column_pool::{AccessColumn, ColumnPool, ConfigPhase, PreSynthesisPhase},
-    consts::merkle_constants::WIDTH,
+    consts::merkle_constants::{ARITY, WIDTH},
     gates::{
         is_point_on_curve_affine::IsPointOnCurveAffineGate, membership::MembershipGate,
         points_add::PointsAddGate, scalar_multiply::ScalarMultiplyGate, sum::SumGate,
@@ -29,7 +31,10 @@ pub struct ConfigsBuilder<'cs> {
     advice_pool: ColumnPool<Advice, ConfigPhase>,
     fixed_pool: ColumnPool<Fixed, ConfigPhase>,
 
+    membership: Option<MembershipGate<ARITY>>,
     merkle: Option<MerkleChip>,
+    token_list: Option<TokenListChip>,
+    association_set: Option<AssociationSetChip>,
     poseidon: Option<PoseidonChip>,
     range_check: Option<RangeCheckChip>,
     sum: Option<SumChip>,
@@ -57,7 +62,10 @@ impl<'cs> ConfigsBuilder<'cs> {
             advice_pool: ColumnPool::<Advice, _>::new(),
             fixed_pool: ColumnPool::<Fixed, _>::new(),
 
+            membership: None,
             merkle: None,
+            token_list: None,
+            association_set: None,
             poseidon: None,
             range_check: None,
             sum: None,
@@ -96,12 +104,26 @@ impl<'cs> ConfigsBuilder<'cs> {
         self.poseidon.clone().expect("Poseidon not configured")
     }
 
+    pub fn with_membership(mut self) -> Self {
+        check_if_cached!(self, membership);
+        self.membership = Some(MembershipGate::create_gate(
+            self.system,
+            &mut self.advice_pool,
+        ));
+        self
+    }
+
+    pub fn membership_gate(&self) -> MembershipGate<ARITY> {
+        self.membership.expect("Membership not configured")
+    }
+
     pub fn with_merkle(mut self, public_inputs: InstanceWrapper<MerkleInstance>) -> Self {
         check_if_cached!(self, merkle);
         self = self.with_poseidon();
+        self = self.with_membership();
 
         self.merkle = Some(MerkleChip {
-            membership_gate: MembershipGate::create_gate(self.system, &mut self.advice_pool),
+            membership_gate: self.membership_gate(),
             public_inputs,
             poseidon: self.poseidon_chip(),
         });
@@ -112,6 +134,45 @@ impl<'cs> ConfigsBuilder<'cs> {
         self.merkle.clone().expect("Merkle not configured")
     }
 
+    pub fn with_token_list(mut self, public_inputs: InstanceWrapper<TokenListInstance>) -> Self {
+        check_if_cached!(self, token_list);
+        self = self.with_poseidon();
+        self = self.with_membership();
+
+        self.token_list = Some(TokenListChip::new(
+            public_inputs,
+            self.membership_gate(),
+            self.poseidon_chip(),
+        ));
+        self
+    }
+
+    pub fn token_list_chip(&self) -> TokenListChip {
+        self.token_list.clone().expect("TokenList not configured")
+    }
+
+    pub fn with_association_set(
+        mut self,
+        public_inputs: InstanceWrapper<AssociationSetInstance>,
+    ) -> Self {
+        check_if_cached!(self, association_set);
+        self = self.with_poseidon();
+        self = self.with_membership();
+
+        self.association_set = Some(AssociationSetChip::new(
+            public_inputs,
+            self.membership_gate(),
+            self.poseidon_chip(),
+        ));
+        self
+    }
+
+    pub fn association_set_chip(&self) -> AssociationSetChip {
+        self.association_set
+            .clone()
+            .expect("AssociationSet not configured")
+    }
+
     pub fn with_range_check(mut self) -> Self {
         check_if_cached!(self, range_check);
         self = self.with_sum();
@@ -3,6 +3,10 @@ use lazy_static::lazy_static;
 
 pub const MAX_K: u32 = 13;
 
+/// Number of withdrawals proven together by the batched withdraw circuit. The whole batch must fit
+/// in `2^MAX_K` rows.
+pub const WITHDRAW_BATCH_SIZE: usize = 2;
+
 pub const MAX_NONCE_BIT_LENGTH: usize = 16;
 pub const MAX_TOKEN_ACCUMULATION_BIT_LENGTH: usize = 112;
 pub const MAX_ACCOUNT_BALANCE_PASSING_RANGE_CHECK: u128 =
@@ -48,4 +52,9 @@ lazy_static! {
     ///
     /// This is the ASCII encoding of "key for AR".
     pub static ref VIEWING_KEY_SALT: Fr = Fr::from_u128(0x6B657920666F72204152);
+
+    /// The salt separating receipts of private transfers (and their nullifiers) from other hashes.
+    ///
+    /// This is the ASCII encoding of "receipt".
+    pub static ref RECEIPT_SALT: Fr = Fr::from_u128(0x72656365697074);
 }
@@ -158,6 +158,26 @@ pub fn generate_user_id(start_from: [u8; 32]) -> Fr {
     }
 }
 
+/// Given a 32 byte array with a field element generates a random receipt `nonce` that is itself
+/// the x-coordinate of a point on the (affine) Grumpkin curve, so that it can be ElGamal-encrypted
+/// (this is how the transfer circuit passes the nonce to the recipient and to the anonymity
+/// revoker).
+///
+/// The procedure is deterministic given the byte array, which is treated as an x-coordinate to start the incremental search with.
+pub fn generate_receipt_nonce(start_from: [u8; 32]) -> Fr {
+    let mut nonce = Fr::from_bytes(&start_from).expect("not a 32 byte array");
+
+    loop {
+        let y_squared = nonce * nonce * nonce + G1::b();
+        match y_squared.sqrt().into_option() {
+            Some(_) => return nonce,
+            None => {
+                nonce += Fr::one();
+            }
+        }
+    }
+}
+
 /// Converts given field element to the individual LE bit representation
 ///
 /// panics if value is not `FIELD_BITS` bits
@@ -357,6 +377,22 @@ mod tests {
         assert!(curve_arithmetic::is_point_on_curve_affine(point));
     }
 
+    #[test]
+    fn receipt_nonce_generation() {
+        let bytes = [21u128.to_le_bytes(), 37u128.to_le_bytes()]
+            .concat()
+            .try_into()
+            .expect("not a 32 byte array");
+
+        let nonce = curve_arithmetic::generate_receipt_nonce(bytes);
+        let y = (nonce * nonce * nonce + G1::b())
+            .sqrt()
+            .expect("element is not a quadratic residue");
+        let point = GrumpkinPointAffine::new(nonce, y);
+
+        assert!(curve_arithmetic::is_point_on_curve_affine(point));
+    }
+
     #[test]
     fn le_bits_conversion_from_fr() {
         let rng = rng();
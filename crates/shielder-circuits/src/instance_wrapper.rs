@@ -63,9 +63,18 @@ impl<ParentId: IntoEnumIterator + Ord + Clone> InstanceWrapper<ParentId> {
     where
         ParentId: TryInto<ChildId>,
     {
+        self.narrow_with(|parent_instance| parent_instance.clone().try_into().ok())
+    }
+
+    /// Like `narrow`, but the child instances are selected by `select` instead of `TryInto`. Useful
+    /// when the parent instance contains several copies of the child instance.
+    pub fn narrow_with<ChildId: IntoEnumIterator + Ord>(
+        &self,
+        select: impl Fn(&ParentId) -> Option<ChildId>,
+    ) -> InstanceWrapper<ChildId> {
         let mut child_offsets = BTreeMap::new();
         for (parent_instance, &parent_offset) in self.offsets.iter() {
-            if let Ok(child_instance) = parent_instance.clone().try_into() {
+            if let Some(child_instance) = select(parent_instance) {
                 child_offsets.insert(child_instance, parent_offset);
             }
         }
@@ -21,8 +21,14 @@ mod version;
 use alloc::{fmt::Debug, vec::Vec};
 
 pub use chips::{
+    association_set::off_circuit::{association_set_leaf, empty_association_path},
     el_gamal::off_circuit::{decrypt, encrypt, generate_keys},
     note::{off_circuit::note_hash, Note},
+    receipt::{
+        off_circuit::{receipt_hash, receipt_nullifier, receiving_tag},
+        Receipt,
+    },
+    token_list::off_circuit::{TokenList, TokenListError, TOKEN_LIST_CAPACITY},
     viewing_key::off_circuit::derive_viewing_key,
 };
 pub use circuits::*;
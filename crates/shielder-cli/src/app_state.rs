@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{btree_map::Entry, BTreeMap, HashMap},
     str::FromStr,
 };
 
@@ -9,11 +9,13 @@ use alloy_signer_local::PrivateKeySigner;
 use alloy_transport::BoxTransport;
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
-use shielder_account::{ShielderAccount, Token};
+use shielder_account::{keyring::Keyring, ShielderAccount, Token};
 use shielder_circuits::poseidon::off_circuit::hash;
 use shielder_contract::{
-    providers::create_simple_provider, ConnectionPolicy, ShielderContractError, ShielderUser,
+    call_type::DryRun, providers::RpcEndpoints, ConnectionPolicy, ShielderContractError,
+    ShielderUser,
 };
+use shielder_setup::version::ContractVersion;
 use tracing::{debug, warn};
 use type_conversions::{address_to_field, field_to_u256, u256_to_field};
 
@@ -36,6 +38,10 @@ impl RelayerRpcUrl {
         format!("{}/relay", self.base_url)
     }
 
+    pub fn relay_status_url(&self, id: U256) -> String {
+        format!("{}/relay/{id}", self.base_url)
+    }
+
     pub fn fees_url(&self) -> String {
         format!("{}/quote_fees", self.base_url)
     }
@@ -62,18 +68,43 @@ pub struct ProtocolFees {
     pub withdraw_fee: Option<U256>,
 }
 
+/// Shielded accounts of a single token, by their keyring index.
+pub type TokenAccounts = BTreeMap<u32, ShielderAccount>;
+
+/// Index under which the account created before the keyring was introduced (see
+/// `AppState::legacy_id_seed`) is stored when the account 0 derived by the keyring is in use too.
+pub const LEGACY_ACCOUNT_INDEX: u32 = u32::MAX;
+
 /// Application info that is kept locally.
 ///
 /// WARNING: You SHOULD NOT use `Self::Default` in production, as this will set the seed to
 /// zero, which is insecure and might get in conflict with other accounts (similarly set up)
 #[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize, Serialize)]
 pub struct AppState {
-    pub accounts: HashMap<Address, ShielderAccount>,
+    #[serde(deserialize_with = "legacy::deserialize_accounts")]
+    pub accounts: HashMap<Address, TokenAccounts>,
+    /// Index of the account used for operations with a token (0 if not set).
+    #[serde(default)]
+    pub selected_accounts: HashMap<Address, u32>,
+    /// Cached chain ID of the node (needed for account derivation).
+    #[serde(default)]
+    pub chain_id: Option<u64>,
     pub node_rpc_url: String,
+    /// Nodes to fall back to when `node_rpc_url` is unavailable.
+    #[serde(default)]
+    pub fallback_node_rpc_urls: Vec<String>,
+    /// Number of nodes that must agree on a contract read (see `FailoverConfig::quorum`).
+    #[serde(default)]
+    pub rpc_quorum: Option<usize>,
     pub contract_address: Address,
+    #[serde(default)]
+    pub contract_deployment_block: u64,
     pub relayer_rpc_url: RelayerRpcUrl,
     pub signing_key: String,
     pub protocol_fees: ProtocolFees,
+    /// Version of the deployed contract, fetched once per session (see `Self::contract_version`).
+    #[serde(skip)]
+    onchain_contract_version: Option<ContractVersion>,
 }
 
 impl AppState {
@@ -90,16 +121,98 @@ impl AppState {
         }
     }
 
-    /// If the account for `token` does not exist, create a new one. For ZK ID use either the
-    /// provided `zkid_seed` or the default one derived from the signing key.
-    pub fn ensure_account_exist(&mut self, token: Token, zkid_seed: Option<U256>) {
-        let zkid_seed = zkid_seed.unwrap_or_else(|| self.default_zkid_seed(token));
-        if let Entry::Vacant(e) = self.accounts.entry(token.address()) {
-            e.insert(ShielderAccount::new(zkid_seed, token));
+    /// Index of the account currently used for operations with `token`.
+    pub fn selected_index(&self, token: Token) -> u32 {
+        self.selected_accounts
+            .get(&token.address())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The account currently used for operations with `token`.
+    ///
+    /// Panics if it does not exist (see `Self::ensure_account_exist`).
+    pub fn account(&self, token: Token) -> &ShielderAccount {
+        &self.accounts[&token.address()][&self.selected_index(token)]
+    }
+
+    /// Mutable version of `Self::account`.
+    pub fn account_mut(&mut self, token: Token) -> &mut ShielderAccount {
+        let index = self.selected_index(token);
+        self.accounts
+            .get_mut(&token.address())
+            .and_then(|accounts| accounts.get_mut(&index))
+            .expect("Account should exist")
+    }
+
+    /// If the selected account for `token` does not exist, create a new one. For ZK ID use either
+    /// the provided `zkid_seed` or the one derived from the signing key by the keyring.
+    pub async fn ensure_account_exist(
+        &mut self,
+        token: Token,
+        zkid_seed: Option<U256>,
+    ) -> anyhow::Result<()> {
+        let index = self.selected_index(token);
+        let exists = self
+            .accounts
+            .get(&token.address())
+            .is_some_and(|accounts| accounts.contains_key(&index));
+        if exists {
+            return Ok(());
         }
+        let account = match zkid_seed {
+            Some(zkid_seed) => ShielderAccount::new(zkid_seed, token),
+            None => self.keyring().await?.account(token, index),
+        };
+        self.insert_account(index, account);
+        Ok(())
     }
 
-    fn default_zkid_seed(&self, token: Token) -> U256 {
+    /// Store `account` under `index`, unless there is already an account with this index.
+    pub fn insert_account(&mut self, index: u32, account: ShielderAccount) {
+        if let Entry::Vacant(e) = self
+            .accounts
+            .entry(account.token.address())
+            .or_default()
+            .entry(index)
+        {
+            e.insert(account);
+        }
+    }
+
+    /// Keyring deriving shielded accounts from the signing key. The chain ID is fetched from the
+    /// node on the first use.
+    pub async fn keyring(&mut self) -> anyhow::Result<Keyring> {
+        let chain_id = match self.chain_id {
+            Some(chain_id) => chain_id,
+            None => {
+                let chain_id = self.create_simple_provider().await?.get_chain_id().await?;
+                self.chain_id = Some(chain_id);
+                chain_id
+            }
+        };
+        let seed =
+            U256::from_str(&self.signing_key).expect("Invalid key format - cannot cast to U256");
+        Ok(Keyring::new(seed, chain_id))
+    }
+
+    /// The version of the deployed contract. It is fetched from the node on the first use and
+    /// reused for the rest of the session.
+    pub async fn contract_version(&mut self) -> Result<ContractVersion, ShielderContractError> {
+        if let Some(version) = self.onchain_contract_version {
+            return Ok(version);
+        }
+        let version = self
+            .create_shielder_user()?
+            .contract_version::<DryRun>()
+            .await?;
+        self.onchain_contract_version = Some(version);
+        Ok(version)
+    }
+
+    /// ID seed of the account of `token` created before the keyring was introduced (there was a
+    /// single account per token then, derived directly from the signing key).
+    pub fn legacy_id_seed(&self, token: Token) -> U256 {
         let seed =
             U256::from_str(&self.signing_key).expect("Invalid key format - cannot cast to U256");
         field_to_u256(hash(&[
@@ -112,31 +225,116 @@ impl AppState {
         format!(
             "
 Node address:          {}
+Fallback nodes:        {:?}
+RPC quorum:            {:?}
 Contract address:      {}
+Deployment block:      {}
 Relayer url:           {}
 Depositor signing key: {}",
             self.node_rpc_url,
+            self.fallback_node_rpc_urls,
+            self.rpc_quorum,
             self.contract_address,
+            self.contract_deployment_block,
             self.relayer_rpc_url.relay_url(),
             self.signing_key
         )
     }
 
-    pub fn create_shielder_user(&self) -> ShielderUser {
+    pub fn create_shielder_user(&self) -> Result<ShielderUser, ShielderContractError> {
         let signer = PrivateKeySigner::from_str(&self.signing_key)
             .expect("Invalid key format - cannot cast to PrivateKeySigner");
-        ShielderUser::new(
+        Ok(ShielderUser::new(
             self.contract_address,
-            ConnectionPolicy::OnDemand {
-                rpc_url: self.node_rpc_url.clone(),
+            ConnectionPolicy::OnDemandFailover {
+                rpc_endpoints: self.node_rpc()?,
                 signer,
             },
-        )
+        ))
     }
 
     pub async fn create_simple_provider(
         &self,
     ) -> Result<impl Provider<BoxTransport, AnyNetwork>, ShielderContractError> {
-        create_simple_provider(&self.node_rpc_url).await
+        self.node_rpc()?.simple_provider().await
+    }
+
+    /// The main node followed by the fallback ones.
+    pub fn node_rpc(&self) -> Result<RpcEndpoints, ShielderContractError> {
+        RpcEndpoints::new(
+            self.node_rpc_url.clone(),
+            self.fallback_node_rpc_urls.clone(),
+            self.rpc_quorum,
+        )
+    }
+}
+
+mod legacy {
+    use std::collections::{BTreeMap, HashMap};
+
+    use alloy_primitives::Address;
+    use serde::{Deserialize, Deserializer};
+    use shielder_account::ShielderAccount;
+
+    use super::TokenAccounts;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Accounts {
+        Current(HashMap<Address, TokenAccounts>),
+        /// Before multiple accounts per token were supported, there was a single account per
+        /// token. It becomes the account with index 0.
+        SinglePerToken(HashMap<Address, ShielderAccount>),
+    }
+
+    pub fn deserialize_accounts<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Address, TokenAccounts>, D::Error> {
+        Ok(match Accounts::deserialize(deserializer)? {
+            Accounts::Current(accounts) => accounts,
+            Accounts::SinglePerToken(accounts) => accounts
+                .into_iter()
+                .map(|(token, account)| (token, BTreeMap::from([(0, account)])))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+    use serde_json::{json, Value};
+    use shielder_account::{ShielderAccount, Token};
+
+    use super::AppState;
+
+    #[test]
+    fn single_account_per_token_state_is_read_as_account_zero() {
+        let account = ShielderAccount::new(U256::from(1), Token::Native);
+        let mut state = serde_json::to_value(AppState::new("0x01")).unwrap();
+        state["accounts"] = json!({ Token::Native.address().to_string(): account });
+        if let Value::Object(fields) = &mut state {
+            fields.remove("selected_accounts");
+            fields.remove("chain_id");
+        }
+
+        let state: AppState = serde_json::from_value(state).unwrap();
+
+        assert_eq!(state.account(Token::Native), &account);
+    }
+
+    #[test]
+    fn accounts_survive_serialization() {
+        let mut state = AppState::new("0x01");
+        state.insert_account(0, ShielderAccount::new(U256::from(1), Token::Native));
+        state.insert_account(3, ShielderAccount::new(U256::from(2), Token::Native));
+        state.selected_accounts.insert(Token::Native.address(), 3);
+
+        let serialized = serde_json::to_string(&state).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<AppState>(&serialized).unwrap(),
+            state
+        );
     }
 }
@@ -1,10 +1,13 @@
 use std::path::PathBuf;
 
 use alloy_primitives::{Address, U256};
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use inquire::Password;
-use shielder_account::Token;
+use shielder_account::{
+    proving::{AttestationPolicy, Pcrs, TeeProver},
+    Token,
+};
 
 #[derive(Clone, Eq, PartialEq, Debug, Parser)]
 pub struct CliConfig {
@@ -12,6 +15,33 @@ pub struct CliConfig {
     #[clap(long, default_value = "~/.shielder-state", value_parser = parsing::parse_path)]
     pub state_file: PathBuf,
 
+    /// Path to the file containing the local copy of the note Merkle tree.
+    #[clap(long, default_value = "~/.shielder-note-tree", value_parser = parsing::parse_path)]
+    pub note_tree_file: PathBuf,
+
+    /// Use the asynchronous relay API: submit the withdrawal and poll the relayer for its status
+    /// instead of waiting on an open connection.
+    #[clap(long, default_value = "false")]
+    pub async_relay: bool,
+
+    /// Where to generate proofs for new account, deposit and withdraw calls.
+    #[clap(long, value_enum, default_value = "local")]
+    pub prover: ProverKind,
+
+    /// URL of the TEE prover server, used with `--prover tee`.
+    #[clap(long, required_if_eq("prover", "tee"))]
+    pub tee_prover_url: Option<String>,
+
+    /// Allowed PCR0 value (hex-encoded measurement of the enclave image) of the TEE prover, used
+    /// with `--prover tee`. Can be repeated to allow several images.
+    #[clap(long = "tee-allowed-pcr0", value_parser = parsing::parse_pcr)]
+    pub tee_allowed_pcr0: Vec<parsing::Pcr>,
+
+    /// Trust any public key returned by the TEE prover, without checking its attestation. Whoever
+    /// runs the server can then read the witnesses. Only for testing.
+    #[clap(long, default_value = "false", conflicts_with = "tee_allowed_pcr0")]
+    pub insecure_unattested_tee: bool,
+
     /// Logging configuration.
     #[clap(short = 'l', value_enum, default_value = "text")]
     pub logging_format: LoggingFormat,
@@ -42,6 +72,30 @@ impl CliConfig {
                 .prompt()?),
         }
     }
+
+    /// TEE prover for `--prover tee`. Its public key is trusted only if attested to belong to one
+    /// of the allowed enclave images, unless `--insecure-unattested-tee` is set.
+    pub fn tee_prover(&self) -> Result<TeeProver> {
+        let url = self
+            .tee_prover_url
+            .clone()
+            .ok_or_else(|| anyhow!("`--tee-prover-url` is required with `--prover tee`"))?;
+        if self.insecure_unattested_tee {
+            return Ok(TeeProver::new_insecure_unattested(url));
+        }
+        if self.tee_allowed_pcr0.is_empty() {
+            bail!(
+                "`--prover tee` requires `--tee-allowed-pcr0` \
+                 (or `--insecure-unattested-tee` for a server without attestation)"
+            );
+        }
+        let allowed_pcrs = self
+            .tee_allowed_pcr0
+            .iter()
+            .map(|parsing::Pcr(pcr0)| Pcrs::from([(0, pcr0.clone())]))
+            .collect();
+        Ok(TeeProver::new(url, AttestationPolicy::aws(allowed_pcrs)))
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Subcommand)]
@@ -88,11 +142,20 @@ pub enum StateWriteCommand {
     NodeUrl {
         /// RPC endpoint address of the node to connect to.
         node: String,
+        /// RPC endpoint addresses of the nodes to fall back to when `node` is unavailable.
+        #[clap(long, num_args = 1..)]
+        fallback: Vec<String>,
+        /// Number of nodes that must agree on the result of a contract read.
+        #[clap(long)]
+        quorum: Option<usize>,
     },
     /// Set address of the Shielder contract.
     ContractAddress {
         /// Address of the Shielder contract.
         address: Address,
+        /// Block in which the Shielder contract was deployed. Local note tree is synced from it.
+        #[clap(long, default_value = "0")]
+        deployment_block: u64,
     },
     /// Set relayer URL address.
     RelayerUrl {
@@ -100,12 +163,37 @@ pub enum StateWriteCommand {
         url: String,
     },
     /// Recover state from the blockchain.
+    ///
+    /// By default, accounts derived from the private key are recovered one by one, starting from
+    /// index 0, until the first account without any on-chain actions.
     RecoverState {
         /// Token to recover.
         #[clap(value_parser = parsing::parse_token)]
         token: Token,
-        /// Optional seed for the ZK ID. If not provided, will be derived from the private key.
+        /// Optional seed for the ZK ID. If provided, only the selected account is recovered (or the
+        /// one given by `--index`) using this seed.
         zkid_seed: Option<U256>,
+        /// Recover only the account with this index.
+        #[clap(long)]
+        index: Option<u32>,
+    },
+    /// Derive a new account for a token from the private key and select it for subsequent
+    /// operations.
+    CreateAccount {
+        /// Token of the account.
+        #[clap(value_parser = parsing::parse_token)]
+        token: Token,
+        /// Index of the account. If not provided, the lowest index without a local account is used.
+        #[clap(long)]
+        index: Option<u32>,
+    },
+    /// Select the account used for operations with a token.
+    SelectAccount {
+        /// Token of the account.
+        #[clap(value_parser = parsing::parse_token)]
+        token: Token,
+        /// Index of the account.
+        index: u32,
     },
 }
 
@@ -113,10 +201,22 @@ pub enum StateWriteCommand {
 pub enum StateReadCommand {
     /// Display account details.
     DisplayAccount,
+    /// List local accounts (of all tokens or of a single one) with their indices.
+    ListAccounts {
+        /// Token whose accounts should be listed.
+        #[clap(value_parser = parsing::parse_token)]
+        token: Option<Token>,
+    },
     /// Display full account history.
     History,
     /// Display application configuration.
     AppConfig,
+    /// Compute the root of the tree of allowed tokens, as expected by the `setTokenList` call of
+    /// the Shielder contract. The native token is always allowed and should not be listed.
+    TokenListRoot {
+        /// ERC20 tokens that can be shielded.
+        tokens: Vec<Address>,
+    },
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Subcommand)]
@@ -133,6 +233,15 @@ pub enum ContractInteractionCommand {
     Withdraw(WithdrawCmd),
     /// Unshield some ERC20 tokens.
     WithdrawERC20(WithdrawERC20Cmd),
+    /// Unshield tokens from several accounts at once, with a single proof. The transaction is sent
+    /// by the signer directly, not through the relayer.
+    WithdrawBatch(WithdrawBatchCmd),
+    /// Migrate the account note to the note version of the CLI. This is done automatically before
+    /// deposits and withdrawals, if needed.
+    Upgrade(UpgradeCmd),
+    /// Prove to a counterparty that the account holds at least some amount of a token, without
+    /// revealing the exact shielded amount. Nothing is sent to the contract.
+    AttestBalance(AttestBalanceCmd),
 }
 
 impl ContractInteractionCommand {
@@ -143,6 +252,9 @@ impl ContractInteractionCommand {
             NewAccountERC20(NewAccountERC20Cmd { token_address, .. })
             | DepositERC20(DepositERC20Cmd { token_address, .. })
             | WithdrawERC20(WithdrawERC20Cmd { token_address, .. }) => Token::ERC20(*token_address),
+            WithdrawBatch(WithdrawBatchCmd { token, .. })
+            | Upgrade(UpgradeCmd { token, .. })
+            | AttestBalance(AttestBalanceCmd { token, .. }) => *token,
         }
     }
 }
@@ -200,6 +312,10 @@ pub struct WithdrawCmd {
     /// Optional memo attached to the contract call.
     #[clap(long, value_parser = parsing::parse_memo, default_value = "")]
     pub memo: parsing::Memo,
+    /// Association set (as published by `ar-cli build-association-set`) that the account proves
+    /// membership in. If not provided, no association set is claimed.
+    #[clap(long, value_parser = parsing::parse_path)]
+    pub association_set: Option<PathBuf>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Args)]
@@ -215,6 +331,62 @@ pub struct WithdrawERC20Cmd {
     /// Optional memo attached to the contract call.
     #[clap(long, value_parser = parsing::parse_memo, default_value = "")]
     pub memo: parsing::Memo,
+    /// Association set (as published by `ar-cli build-association-set`) that the account proves
+    /// membership in. If not provided, no association set is claimed.
+    #[clap(long, value_parser = parsing::parse_path)]
+    pub association_set: Option<PathBuf>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Args)]
+pub struct WithdrawBatchCmd {
+    /// Token of the accounts.
+    #[clap(value_parser = parsing::parse_token)]
+    pub token: Token,
+    /// Address to which the tokens should be sent.
+    pub to: Address,
+    /// Index of an account to withdraw from (see `list-accounts`). Has to be given once for every
+    /// withdrawal of the batch.
+    #[clap(long = "account", required = true)]
+    pub accounts: Vec<u32>,
+    /// Amount of the token to be unshielded from the corresponding `--account`.
+    #[clap(long = "amount", required = true)]
+    pub amounts: Vec<u128>,
+    /// Optional memo attached to every withdrawal.
+    #[clap(long, value_parser = parsing::parse_memo, default_value = "")]
+    pub memo: parsing::Memo,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Args)]
+pub struct UpgradeCmd {
+    /// Token of the account to be upgraded.
+    #[clap(value_parser = parsing::parse_token)]
+    pub token: Token,
+    /// Optional memo attached to the contract call.
+    #[clap(long, value_parser = parsing::parse_memo, default_value = "")]
+    pub memo: parsing::Memo,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Args)]
+pub struct AttestBalanceCmd {
+    /// Token of the account.
+    #[clap(value_parser = parsing::parse_token)]
+    pub token: Token,
+    /// Minimal shielded amount to be attested.
+    pub threshold: u128,
+    /// Challenge chosen by the verifier, to which the attestation is bound.
+    pub challenge: U256,
+    /// File to which the attestation is written (as JSON). If not provided, it is printed.
+    #[clap(long, value_parser = parsing::parse_path)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, ValueEnum)]
+pub enum ProverKind {
+    /// Prove locally, with proving keys generated on first use.
+    #[default]
+    Local,
+    /// Prove with the TEE prover server (see `--tee-prover-url`).
+    Tee,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, ValueEnum)]
@@ -228,8 +400,8 @@ mod parsing {
     use std::{path::PathBuf, str::FromStr};
 
     use alloy_primitives::{hex::FromHex, Bytes};
-    use anyhow::{anyhow, Result};
-    use shielder_account::Token;
+    use anyhow::{anyhow, bail, Result};
+    use shielder_account::{proving::PCR_LENGTH, Token};
 
     /// Wrapper type for memo to work around clap's TypeId issues with Option<Vec<u8>>
     #[derive(Clone, Debug, PartialEq, Eq)]
@@ -241,6 +413,10 @@ mod parsing {
         }
     }
 
+    /// Wrapper type for a PCR value, for the same reason as `Memo`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Pcr(pub Vec<u8>);
+
     pub fn parse_path(path: &str) -> Result<PathBuf> {
         let expanded_path =
             shellexpand::full(path).map_err(|e| anyhow!("Failed to expand path: {e:?}"))?;
@@ -268,6 +444,14 @@ mod parsing {
                 .map(|bytes| Memo(bytes.to_vec()))
         }
     }
+
+    pub fn parse_pcr(pcr: &str) -> Result<Pcr> {
+        let pcr = Bytes::from_hex(pcr).map_err(|_| anyhow!("Invalid PCR, expected hex string"))?;
+        if pcr.len() != PCR_LENGTH {
+            bail!("Invalid PCR, expected {PCR_LENGTH} bytes");
+        }
+        Ok(Pcr(pcr.to_vec()))
+    }
 }
 
 #[cfg(test)]
@@ -1,26 +1,36 @@
-use std::{env, io};
+use std::{env, io, path::Path};
 
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use shielder_account::Token;
+use shielder_contract::{
+    providers::RpcEndpoints, token_list::build_token_list, ShielderContractError,
+};
+use shielder_relayer::RelayMode;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
+use type_conversions::field_to_u256;
 
 use crate::{
     app_state::{AppState, RelayerRpcUrl},
     config::{
-        CliConfig,
+        AttestBalanceCmd, CliConfig,
         Command::{ContractInteraction, StateRead, StateWrite},
         ContractInteractionCommand, DepositCmd, DepositERC20Cmd, LoggingFormat, NewAccountCmd,
-        NewAccountERC20Cmd, StateReadCommand, StateWriteCommand, WithdrawCmd, WithdrawERC20Cmd,
+        NewAccountERC20Cmd, ProverKind, StateReadCommand, StateWriteCommand, UpgradeCmd,
+        WithdrawBatchCmd, WithdrawCmd, WithdrawERC20Cmd,
     },
     recovery::recover_state,
-    shielder_ops::{deposit, new_account, withdraw},
+    shielder_ops::{
+        attest_balance, deposit, new_account, upgrade, upgrade_if_needed, withdraw, withdraw_batch,
+        ProvingMode,
+    },
     state_file::{create_and_save_new_state, get_app_state, save_app_state},
 };
 
 mod app_state;
 mod config;
+mod note_tree;
 mod recovery;
 mod shielder_ops;
 mod state_file;
@@ -54,13 +64,25 @@ async fn perform_state_write_action(
         StateWriteCommand::Initialize { .. } => {
             unreachable!("State initialization should have been handled in a different context")
         }
-        StateWriteCommand::NodeUrl { node } => {
-            info!("Setting node address to {node}");
+        StateWriteCommand::NodeUrl {
+            node,
+            fallback,
+            quorum,
+        } => {
+            info!("Setting node address to {node} (fallback: {fallback:?}, quorum: {quorum:?})");
+            RpcEndpoints::new(node.clone(), fallback.clone(), quorum)?;
             app_state.node_rpc_url = node;
+            app_state.fallback_node_rpc_urls = fallback;
+            app_state.rpc_quorum = quorum;
+            app_state.chain_id = None;
         }
-        StateWriteCommand::ContractAddress { address } => {
-            info!("Setting contract address to {address}");
+        StateWriteCommand::ContractAddress {
+            address,
+            deployment_block,
+        } => {
+            info!("Setting contract address to {address} (deployed in block {deployment_block})");
             app_state.contract_address = address;
+            app_state.contract_deployment_block = deployment_block;
         }
         StateWriteCommand::RelayerUrl { url } => {
             let relayer_rpc_url = RelayerRpcUrl::new(url.clone());
@@ -68,9 +90,32 @@ async fn perform_state_write_action(
             info!("Setting relayer url to {url}");
             app_state.relayer_rpc_url = relayer_rpc_url;
         }
-        // for now we support only native recovery
-        StateWriteCommand::RecoverState { token, zkid_seed } => {
-            recover_state(app_state, token, zkid_seed).await?;
+        StateWriteCommand::RecoverState {
+            token,
+            zkid_seed,
+            index,
+        } => {
+            recover_state(app_state, token, zkid_seed, index).await?;
+        }
+        StateWriteCommand::CreateAccount { token, index } => {
+            let index = index.unwrap_or_else(|| {
+                let taken = app_state.accounts.get(&token.address());
+                (0..=u32::MAX)
+                    .find(|index| !taken.is_some_and(|accounts| accounts.contains_key(index)))
+                    .expect("There are fewer accounts than indices")
+            });
+            let account = app_state.keyring().await?.account(token, index);
+            info!(
+                "Selecting account {index} (ID: {}) for {token:?}",
+                account.id
+            );
+            app_state.insert_account(index, account);
+            app_state.selected_accounts.insert(token.address(), index);
+        }
+        StateWriteCommand::SelectAccount { token, index } => {
+            info!("Selecting account {index} for {token:?}");
+            app_state.selected_accounts.insert(token.address(), index);
+            app_state.ensure_account_exist(token, None).await?;
         }
     };
     Ok(())
@@ -79,48 +124,161 @@ async fn perform_state_write_action(
 fn perform_state_read_action(app_state: &AppState, command: StateReadCommand) -> Result<()> {
     match command {
         StateReadCommand::DisplayAccount => {
-            for account in app_state.accounts.values() {
+            for account in app_state
+                .accounts
+                .values()
+                .flat_map(|accounts| accounts.values())
+            {
                 println!("{}", account)
             }
         }
+        StateReadCommand::ListAccounts { token } => {
+            for (token_address, accounts) in &app_state.accounts {
+                if token.is_some_and(|token| token.address() != *token_address) {
+                    continue;
+                }
+                let selected = app_state.selected_index(Token::from(*token_address));
+                for (index, account) in accounts {
+                    let marker = if *index == selected { "*" } else { " " };
+                    println!(
+                        "{marker} {token_address} #{index}: id {}, shielded amount {}, {} actions",
+                        account.id,
+                        account.shielded_amount,
+                        account.history.len()
+                    );
+                }
+            }
+        }
         StateReadCommand::History => {
-            for account in app_state.accounts.values() {
+            for account in app_state
+                .accounts
+                .values()
+                .flat_map(|accounts| accounts.values())
+            {
                 println!("{:#?}", account.history)
             }
         }
         StateReadCommand::AppConfig => {
             println!("{}", app_state.display_app_config())
         }
+        StateReadCommand::TokenListRoot { tokens } => {
+            println!("{}", field_to_u256(build_token_list(&tokens)?.root()))
+        }
     };
     Ok(())
 }
 
+/// Attach actionable advice to well-known contract reverts.
+fn explain_contract_error(err: anyhow::Error) -> anyhow::Error {
+    match err.downcast_ref::<ShielderContractError>() {
+        Some(e) if e.is_duplicated_nullifier() => err.context(
+            "The nullifier has already been used - local account state is outdated. \
+Run `recover-state` to re-sync it with the chain.",
+        ),
+        Some(e) if e.is_unknown_merkle_root() => err.context(
+            "The Merkle root is unknown to the contract (probably due to a chain reorganization). \
+Retry the command to refresh the Merkle path.",
+        ),
+        _ => err,
+    }
+}
+
 async fn perform_contract_action(
     app_state: &mut AppState,
     command: ContractInteractionCommand,
+    note_tree_file: &Path,
+    relay_mode: RelayMode,
+    proving_mode: &ProvingMode,
+) -> Result<()> {
+    _perform_contract_action(app_state, command, note_tree_file, relay_mode, proving_mode)
+        .await
+        .map_err(explain_contract_error)
+}
+
+async fn _perform_contract_action(
+    app_state: &mut AppState,
+    command: ContractInteractionCommand,
+    note_tree_file: &Path,
+    relay_mode: RelayMode,
+    proving_mode: &ProvingMode,
 ) -> Result<()> {
+    // Notes of an older version have to be migrated before they can be spent.
+    if matches!(
+        command,
+        ContractInteractionCommand::Deposit(_)
+            | ContractInteractionCommand::DepositERC20(_)
+            | ContractInteractionCommand::Withdraw(_)
+            | ContractInteractionCommand::WithdrawERC20(_)
+    ) {
+        upgrade_if_needed(app_state, command.token(), note_tree_file).await?;
+    }
+
     match command {
         ContractInteractionCommand::NewAccount(NewAccountCmd { amount, memo, .. }) => {
-            new_account(app_state, amount, Token::Native, memo.into()).await
+            new_account(app_state, amount, Token::Native, memo.into(), proving_mode).await
         }
         ContractInteractionCommand::NewAccountERC20(NewAccountERC20Cmd {
             amount,
             token_address,
             memo,
             ..
-        }) => new_account(app_state, amount, Token::ERC20(token_address), memo.into()).await,
+        }) => {
+            new_account(
+                app_state,
+                amount,
+                Token::ERC20(token_address),
+                memo.into(),
+                proving_mode,
+            )
+            .await
+        }
 
         ContractInteractionCommand::Deposit(DepositCmd { amount, memo }) => {
-            deposit(app_state, amount, Token::Native, memo.into()).await
+            deposit(
+                app_state,
+                amount,
+                Token::Native,
+                memo.into(),
+                note_tree_file,
+                proving_mode,
+            )
+            .await
         }
         ContractInteractionCommand::DepositERC20(DepositERC20Cmd {
             amount,
             token_address,
             memo,
-        }) => deposit(app_state, amount, Token::ERC20(token_address), memo.into()).await,
+        }) => {
+            deposit(
+                app_state,
+                amount,
+                Token::ERC20(token_address),
+                memo.into(),
+                note_tree_file,
+                proving_mode,
+            )
+            .await
+        }
 
-        ContractInteractionCommand::Withdraw(WithdrawCmd { amount, to, memo }) => {
-            withdraw(app_state, amount, to, Token::Native, 0, memo.into()).await
+        ContractInteractionCommand::Withdraw(WithdrawCmd {
+            amount,
+            to,
+            memo,
+            association_set,
+        }) => {
+            withdraw(
+                app_state,
+                amount,
+                to,
+                Token::Native,
+                0,
+                memo.into(),
+                association_set.as_deref(),
+                note_tree_file,
+                relay_mode,
+                proving_mode,
+            )
+            .await
         }
         ContractInteractionCommand::WithdrawERC20(WithdrawERC20Cmd {
             amount,
@@ -128,6 +286,7 @@ async fn perform_contract_action(
             token_address,
             pocket_money,
             memo,
+            association_set,
         }) => {
             withdraw(
                 app_state,
@@ -136,6 +295,50 @@ async fn perform_contract_action(
                 Token::ERC20(token_address),
                 pocket_money,
                 memo.into(),
+                association_set.as_deref(),
+                note_tree_file,
+                relay_mode,
+                proving_mode,
+            )
+            .await
+        }
+
+        ContractInteractionCommand::WithdrawBatch(WithdrawBatchCmd {
+            token,
+            to,
+            accounts,
+            amounts,
+            memo,
+        }) => {
+            withdraw_batch(
+                app_state,
+                token,
+                &accounts,
+                &amounts,
+                to,
+                memo.into(),
+                note_tree_file,
+            )
+            .await
+        }
+
+        ContractInteractionCommand::Upgrade(UpgradeCmd { token, memo }) => {
+            upgrade(app_state, token, memo.into(), note_tree_file).await
+        }
+
+        ContractInteractionCommand::AttestBalance(AttestBalanceCmd {
+            token,
+            threshold,
+            challenge,
+            output,
+        }) => {
+            attest_balance(
+                app_state,
+                token,
+                threshold,
+                challenge,
+                output.as_deref(),
+                note_tree_file,
             )
             .await
         }
@@ -155,7 +358,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let mut app_state = get_app_state(&cli_config.state_file, &password)?;
 
         if let Some(token) = cli_config.command.token() {
-            app_state.ensure_account_exist(token, cli_config.command.zkid_seed());
+            app_state
+                .ensure_account_exist(token, cli_config.command.zkid_seed())
+                .await?;
         }
 
         match cli_config.command {
@@ -165,7 +370,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             StateRead(cmd) => perform_state_read_action(&app_state, cmd)?,
             ContractInteraction(cmd) => {
-                perform_contract_action(&mut app_state, cmd).await?;
+                let relay_mode = match cli_config.async_relay {
+                    true => RelayMode::Async,
+                    false => RelayMode::Sync,
+                };
+                let proving_mode = match cli_config.prover {
+                    ProverKind::Local => ProvingMode::Local,
+                    ProverKind::Tee => ProvingMode::Tee(cli_config.tee_prover()?),
+                };
+                perform_contract_action(
+                    &mut app_state,
+                    cmd,
+                    &cli_config.note_tree_file,
+                    relay_mode,
+                    &proving_mode,
+                )
+                .await?;
                 save_app_state(&app_state, &cli_config.state_file, &password)?;
             }
         }
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use alloy_primitives::U256;
+use anyhow::{bail, Result};
+use shielder_contract::{note_tree::NoteTree, ShielderContractError};
+use shielder_setup::consts::{ARITY, TREE_HEIGHT};
+use tracing::debug;
+
+use crate::app_state::AppState;
+
+/// How many times we try to catch up with the contract, if new notes keep arriving while syncing.
+const SYNC_ATTEMPTS: usize = 3;
+
+/// Compute the Merkle path to `leaf_index` from the local copy of the note tree, without revealing
+/// the leaf to the node. The tree is first synced with the chain and its root is checked against
+/// the contract.
+pub async fn get_merkle_path(
+    app_state: &AppState,
+    note_tree_file: &Path,
+    leaf_index: U256,
+) -> Result<(U256, [[U256; ARITY]; TREE_HEIGHT])> {
+    let mut tree = NoteTree::load_or_new(
+        note_tree_file,
+        app_state.contract_address,
+        app_state.contract_deployment_block,
+    )?;
+    let provider = app_state.create_simple_provider().await?;
+    let shielder_user = app_state.create_shielder_user()?;
+
+    let mut attempt = 1;
+    loop {
+        tree.sync(&provider).await?;
+        debug!(
+            "Synced note tree up to block {} ({} notes)",
+            tree.next_block(),
+            tree.leaf_count()
+        );
+
+        match tree.check_root(&shielder_user).await {
+            Ok(()) => break,
+            Err(ShielderContractError::NoteTreeOutOfSync { .. }) if attempt < SYNC_ATTEMPTS => {
+                attempt += 1;
+            }
+            Err(err) => bail!("Local note tree is invalid: {err}"),
+        }
+    }
+
+    tree.save(note_tree_file)?;
+    Ok(tree.merkle_path(leaf_index)?)
+}
@@ -1,38 +1,280 @@
-use alloy_primitives::U256;
+use alloy_primitives::{TxHash, U256};
+use alloy_provider::{network::AnyNetwork, Provider};
+use alloy_transport::BoxTransport;
 use anyhow::Result;
-use shielder_account::Token;
+use shielder_account::{ShielderAccount, ShielderAction, Token};
 use shielder_circuits::poseidon::off_circuit::hash;
-use shielder_contract::{providers::create_simple_provider, recovery::get_shielder_action};
+use shielder_contract::{recovery::recover_shielder_action, ShielderUser};
+use tracing::{info, warn};
 use type_conversions::{field_to_u256, u256_to_field};
 
-use crate::app_state::AppState;
+use crate::app_state::{AppState, LEGACY_ACCOUNT_INDEX};
 
+/// Recover accounts of `token` from the chain.
+///
+/// If `zkid_seed` or `index` is given, only a single account is recovered (the selected one or
+/// the one with `index`, respectively). Otherwise, accounts derived from the signing key are
+/// recovered one by one, starting from index 0, until the first account without any on-chain
+/// action.
+///
+/// If the account 0 derived by the keyring has no actions, the account created before the keyring
+/// was introduced (see `AppState::legacy_id_seed`) is recovered as the account 0 instead. If both
+/// have actions, the legacy account is stored under `LEGACY_ACCOUNT_INDEX`.
 pub async fn recover_state(
     app_state: &mut AppState,
     token: Token,
     zkid_seed: Option<U256>,
+    index: Option<u32>,
 ) -> Result<()> {
-    let shielder_user = app_state.create_shielder_user();
-    app_state.ensure_account_exist(token, zkid_seed);
-    let AppState {
-        accounts,
-        node_rpc_url,
-        ..
-    } = app_state;
-    let provider = create_simple_provider(node_rpc_url).await?;
-
-    let account = accounts
-        .get_mut(&token.address())
-        .expect("We have just ensured the account exists");
-
-    loop {
+    let chain = Chain {
+        provider: app_state.create_simple_provider().await?,
+        shielder_user: app_state.create_shielder_user()?,
+    };
+    recover_accounts(app_state, &chain, token, zkid_seed, index).await
+}
+
+async fn recover_accounts(
+    app_state: &mut AppState,
+    actions: &impl ActionSource,
+    token: Token,
+    zkid_seed: Option<U256>,
+    index: Option<u32>,
+) -> Result<()> {
+    if let Some(zkid_seed) = zkid_seed {
+        let index = index.unwrap_or_else(|| app_state.selected_index(token));
+        let account = ShielderAccount::new(zkid_seed, token);
+        recover_and_store(app_state, actions, index, vec![account]).await?;
+        return Ok(());
+    }
+
+    let keyring = app_state.keyring().await?;
+    let candidates = |app_state: &AppState, index: u32| {
+        let mut candidates = vec![keyring.account(token, index)];
+        if index == 0 {
+            let legacy_seed = app_state.legacy_id_seed(token);
+            candidates.push(ShielderAccount::new(legacy_seed, token));
+        }
+        candidates
+    };
+
+    if let Some(index) = index {
+        let candidates = candidates(app_state, index);
+        recover_and_store(app_state, actions, index, candidates).await?;
+        return Ok(());
+    }
+
+    for index in 0..=u32::MAX {
+        let candidates = candidates(app_state, index);
+        if !recover_and_store(app_state, actions, index, candidates).await? {
+            info!("Account {index} has no on-chain actions, stopping the scan");
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Bring the local account with `index` up to date with the chain. If there is no such local
+/// account, the first of the fresh `candidates` that has any on-chain actions is recovered. The
+/// account is stored only if it has any actions. Returns whether it has.
+///
+/// Any other candidate with actions (i.e. the legacy account next to the account 0 derived by the
+/// keyring) is stored under `LEGACY_ACCOUNT_INDEX`, so that its funds are not lost.
+async fn recover_and_store(
+    app_state: &mut AppState,
+    actions: &impl ActionSource,
+    index: u32,
+    mut candidates: Vec<ShielderAccount>,
+) -> Result<bool> {
+    let token = candidates[0].token;
+    if let Some(local_account) = app_state
+        .accounts
+        .get(&token.address())
+        .and_then(|accounts| accounts.get(&index))
+    {
+        candidates[0] = local_account.clone();
+    }
+    let first_id = candidates[0].id;
+
+    let mut recovered = Vec::new();
+    for (position, mut account) in candidates.into_iter().enumerate() {
+        if position > 0 && account.id == first_id {
+            continue;
+        }
+        while let Some(action) = actions.next_action(&account).await? {
+            account.register_action(action);
+        }
+        if account.nonce != 0 {
+            recovered.push(account);
+        }
+    }
+
+    let mut recovered = recovered.into_iter();
+    let Some(account) = recovered.next() else {
+        return Ok(false);
+    };
+    info!(
+        "Recovered account {index} ({} actions)",
+        account.history.len()
+    );
+    let accounts = app_state.accounts.entry(token.address()).or_default();
+    accounts.insert(index, account);
+    if let Some(legacy_account) = recovered.next() {
+        warn!(
+            "Both the account {index} and the legacy account have on-chain actions, storing the \
+             legacy one as account {LEGACY_ACCOUNT_INDEX} ({} actions)",
+            legacy_account.history.len()
+        );
+        accounts.insert(LEGACY_ACCOUNT_INDEX, legacy_account);
+    }
+    Ok(true)
+}
+
+/// Source of the on-chain actions of shielded accounts.
+trait ActionSource {
+    /// The action that follows the current state of `account`, if it has already been made.
+    async fn next_action(&self, account: &ShielderAccount) -> Result<Option<ShielderAction>>;
+}
+
+struct Chain<P> {
+    provider: P,
+    shielder_user: ShielderUser,
+}
+
+impl<P: Provider<BoxTransport, AnyNetwork>> ActionSource for Chain<P> {
+    async fn next_action(&self, account: &ShielderAccount) -> Result<Option<ShielderAction>> {
         let expected_nullifier = account.previous_nullifier();
         let expected_nullifier_hash = field_to_u256(hash(&[u256_to_field(expected_nullifier)]));
 
-        match get_shielder_action(&provider, &shielder_user, expected_nullifier_hash).await? {
-            Some(action) => account.register_action(action),
-            None => break,
+        // Calls made through smart-contract wallets or other proxies are recognized by the note
+        // that the account should have produced.
+        let action = recover_shielder_action(
+            &self.provider,
+            &self.shielder_user,
+            expected_nullifier_hash,
+            |event| account.note_after(&ShielderAction::from((TxHash::ZERO, event.clone()))),
+        )
+        .await?;
+        Ok(action.map(ShielderAction::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy_primitives::{TxHash, U256};
+    use anyhow::Result;
+    use shielder_account::{ShielderAccount, ShielderAction, Token};
+
+    use super::{recover_accounts, ActionSource};
+    use crate::app_state::{AppState, LEGACY_ACCOUNT_INDEX};
+
+    /// On-chain actions, by the nullifier of the account state preceding them.
+    #[derive(Default)]
+    struct MockChain(HashMap<U256, ShielderAction>);
+
+    impl MockChain {
+        /// Make a few actions with `account`.
+        fn make_history(&mut self, mut account: ShielderAccount) -> ShielderAccount {
+            let token = account.token;
+            for action in [
+                ShielderAction::new_account(
+                    U256::from(10),
+                    U256::ZERO,
+                    TxHash::ZERO,
+                    token,
+                    U256::ZERO,
+                ),
+                ShielderAction::deposit(
+                    U256::from(5),
+                    U256::from(1),
+                    TxHash::ZERO,
+                    token,
+                    U256::ZERO,
+                ),
+            ] {
+                self.0.insert(account.previous_nullifier(), action.clone());
+                account.register_action(action);
+            }
+            account
         }
     }
-    Ok(())
+
+    impl ActionSource for MockChain {
+        async fn next_action(&self, account: &ShielderAccount) -> Result<Option<ShielderAction>> {
+            Ok(self.0.get(&account.previous_nullifier()).cloned())
+        }
+    }
+
+    fn app_state() -> AppState {
+        let mut app_state = AppState::new("0x01");
+        app_state.chain_id = Some(1);
+        app_state
+    }
+
+    #[tokio::test]
+    async fn legacy_account_is_recovered_as_account_zero() {
+        let mut app_state = app_state();
+        let token = Token::Native;
+        let keyring = app_state.keyring().await.unwrap();
+
+        let mut chain = MockChain::default();
+        let legacy_seed = app_state.legacy_id_seed(token);
+        let legacy = chain.make_history(ShielderAccount::new(legacy_seed, token));
+        let second = chain.make_history(keyring.account(token, 1));
+
+        recover_accounts(&mut app_state, &chain, token, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(app_state.accounts[&token.address()][&0], legacy);
+        assert_eq!(app_state.accounts[&token.address()][&1], second);
+        assert_eq!(app_state.accounts[&token.address()].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn keyring_account_zero_takes_precedence_over_legacy_one() {
+        let mut app_state = app_state();
+        let token = Token::Native;
+        let keyring = app_state.keyring().await.unwrap();
+
+        let mut chain = MockChain::default();
+        let legacy_seed = app_state.legacy_id_seed(token);
+        let legacy = chain.make_history(ShielderAccount::new(legacy_seed, token));
+        let first = chain.make_history(keyring.account(token, 0));
+
+        recover_accounts(&mut app_state, &chain, token, None, Some(0))
+            .await
+            .unwrap();
+
+        assert_eq!(app_state.account(token), &first);
+        assert_eq!(
+            app_state.accounts[&token.address()][&LEGACY_ACCOUNT_INDEX],
+            legacy
+        );
+        assert_eq!(app_state.accounts[&token.address()].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn legacy_account_is_recovered_next_to_local_account_zero() {
+        let mut app_state = app_state();
+        let token = Token::Native;
+        let keyring = app_state.keyring().await.unwrap();
+
+        let mut chain = MockChain::default();
+        let legacy_seed = app_state.legacy_id_seed(token);
+        let legacy = chain.make_history(ShielderAccount::new(legacy_seed, token));
+        let first = chain.make_history(keyring.account(token, 0));
+        app_state.insert_account(0, first.clone());
+
+        recover_accounts(&mut app_state, &chain, token, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(app_state.accounts[&token.address()][&0], first);
+        assert_eq!(
+            app_state.accounts[&token.address()][&LEGACY_ACCOUNT_INDEX],
+            legacy
+        );
+    }
 }
@@ -0,0 +1,56 @@
+use std::{fs, path::Path};
+
+use alloy_primitives::U256;
+use anyhow::{anyhow, bail, Result};
+use shielder_account::{balance_attestation::BalanceAttestationError, Token};
+use tracing::info;
+
+use crate::{
+    app_state::AppState,
+    note_tree::get_merkle_path,
+    shielder_ops::pk::{get_proving_equipment, CircuitType},
+};
+
+/// Prove that the selected `token` account holds at least `threshold` tokens, without revealing
+/// the exact amount. The attestation is bound to `challenge`, which should be chosen by the
+/// verifier. It is written as JSON to `output`, or printed if no file is given.
+pub async fn attest_balance(
+    app_state: &AppState,
+    token: Token,
+    threshold: u128,
+    challenge: U256,
+    output: Option<&Path>,
+    note_tree_file: &Path,
+) -> Result<()> {
+    let account = app_state.account(token);
+    let Some(leaf_index) = account.current_leaf_index() else {
+        bail!("The account has no shielded note yet");
+    };
+
+    let (merkle_root, merkle_path) = get_merkle_path(app_state, note_tree_file, leaf_index).await?;
+    let (params, pk) = get_proving_equipment(CircuitType::BalanceAttestation)?;
+
+    let attestation = account
+        .attest_balance(&params, &pk, merkle_path, U256::from(threshold), challenge)
+        .map_err(|err| match err {
+            BalanceAttestationError::OutdatedNoteVersion => {
+                anyhow!("The account note has an older version. Run `upgrade` first.")
+            }
+            BalanceAttestationError::InsufficientBalance => anyhow!(
+                "Shielded amount ({}) is lower than the threshold ({threshold})",
+                account.shielded_amount
+            ),
+            err => anyhow!("Cannot attest balance: {err:?}"),
+        })?;
+    info!("Attested balance of at least {threshold} against Merkle root {merkle_root}");
+
+    let attestation = serde_json::to_string_pretty(&attestation)?;
+    match output {
+        Some(path) => {
+            fs::write(path, attestation)?;
+            info!("Saved balance attestation to {path:?}");
+        }
+        None => println!("{attestation}"),
+    }
+    Ok(())
+}
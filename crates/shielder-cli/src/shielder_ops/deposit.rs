@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use alloy_primitives::{Address, Bytes, U256};
 use anyhow::Result;
 use shielder_account::{
@@ -7,7 +9,6 @@ use shielder_account::{
 use shielder_contract::{
     call_type::{Call, DryRun},
     events::get_event,
-    merkle_path::get_current_merkle_path,
     ShielderContract::Deposit,
 };
 use shielder_setup::{
@@ -18,9 +19,11 @@ use tracing::{debug, info};
 
 use crate::{
     app_state::AppState,
+    note_tree::get_merkle_path,
     shielder_ops::{
         get_mac_salt,
-        pk::{get_proving_equipment, CircuitType},
+        pk::CircuitType,
+        prover::{self, ProvingMode},
     },
 };
 
@@ -29,13 +32,17 @@ pub async fn deposit(
     amount: u128,
     token: Token,
     memo: Vec<u8>,
+    note_tree_file: &Path,
+    proving_mode: &ProvingMode,
 ) -> Result<()> {
     let memo = Bytes::from(memo);
-    let leaf_index = app_state.accounts[&token.address()]
+    let leaf_index = app_state
+        .account(token)
         .current_leaf_index()
         .expect("Deposit mustn't be the first action");
-    let shielder_user = app_state.create_shielder_user();
-    let (_merkle_root, merkle_path) = get_current_merkle_path(leaf_index, &shielder_user).await?;
+    let shielder_user = app_state.create_shielder_user()?;
+    let (_merkle_root, merkle_path) =
+        get_merkle_path(app_state, note_tree_file, leaf_index).await?;
 
     let protocol_fee_bps = if let Some(protocol_fee_bps) = app_state.protocol_fees.deposit_fee {
         protocol_fee_bps
@@ -56,7 +63,9 @@ pub async fn deposit(
         shielder_user.address(),
         protocol_fee,
         memo,
-    )?;
+        proving_mode,
+    )
+    .await?;
     let (tx_hash, block_hash) = match token {
         Token::Native => {
             shielder_user
@@ -79,9 +88,7 @@ pub async fn deposit(
     debug!("Deposit event: {deposit_event:?}");
 
     app_state
-        .accounts
-        .get_mut(&token.address())
-        .unwrap()
+        .account_mut(token)
         .register_action(ShielderAction::deposit(
             amount,
             deposit_event.newNoteIndex,
@@ -93,7 +100,8 @@ pub async fn deposit(
     Ok(())
 }
 
-fn prepare_call(
+#[allow(clippy::too_many_arguments)]
+async fn prepare_call(
     app_state: &AppState,
     amount: U256,
     token: Token,
@@ -101,8 +109,8 @@ fn prepare_call(
     caller_address: Address,
     protocol_fee: U256,
     memo: Bytes,
+    proving_mode: &ProvingMode,
 ) -> Result<DepositCall> {
-    let (params, pk) = get_proving_equipment(CircuitType::Deposit)?;
     let extra = DepositExtra {
         merkle_path,
         mac_salt: get_mac_salt(),
@@ -111,6 +119,13 @@ fn prepare_call(
         memo,
     };
 
-    Ok(app_state.accounts[&token.address()]
-        .prepare_call::<DepositCallType>(&params, &pk, token, amount, &extra))
+    prover::prepare_call::<DepositCallType>(
+        app_state.account(token),
+        proving_mode,
+        CircuitType::Deposit,
+        token,
+        amount,
+        &extra,
+    )
+    .await
 }
@@ -2,14 +2,22 @@ use alloy_primitives::{
     private::rand::{rngs::OsRng, Rng},
     U256,
 };
+pub use attest_balance::attest_balance;
 pub use deposit::deposit;
 pub use new_account::new_account;
+pub use prover::ProvingMode;
+pub use upgrade::{upgrade, upgrade_if_needed};
 pub use withdraw::withdraw;
+pub use withdraw_batch::withdraw_batch;
 
+mod attest_balance;
 mod deposit;
 mod new_account;
 mod pk;
+mod prover;
+mod upgrade;
 mod withdraw;
+mod withdraw_batch;
 
 fn get_mac_salt() -> U256 {
     let mut rng = OsRng;
@@ -4,10 +4,14 @@ use shielder_account::{
     call_data::{NewAccountCall, NewAccountCallExtra, NewAccountCallType},
     ShielderAction, Token,
 };
-use shielder_circuits::GrumpkinPointAffine;
+use shielder_circuits::{
+    consts::merkle_constants::{ARITY, TOKEN_TREE_HEIGHT},
+    GrumpkinPointAffine,
+};
 use shielder_contract::{
     call_type::{Call, DryRun},
     events::get_event,
+    token_list::get_token_list_path,
     ShielderContract::NewAccount,
 };
 use shielder_setup::protocol_fee::compute_protocol_fee_from_net;
@@ -17,7 +21,8 @@ use crate::{
     app_state::AppState,
     shielder_ops::{
         get_mac_salt,
-        pk::{get_proving_equipment, CircuitType},
+        pk::CircuitType,
+        prover::{self, ProvingMode},
     },
 };
 
@@ -26,10 +31,12 @@ pub async fn new_account(
     amount: u128,
     token: Token,
     memo: Vec<u8>,
+    proving_mode: &ProvingMode,
 ) -> Result<()> {
     let memo = Bytes::from(memo);
-    let user = app_state.create_shielder_user();
+    let user = app_state.create_shielder_user()?;
     let anonymity_revoker_public_key = user.anonymity_revoker_pubkey::<DryRun>().await?;
+    let token_list_path = get_token_list_path(token.address(), &user).await?;
 
     let protocol_fee_bps = if let Some(protocol_fee_bps) = app_state.protocol_fees.deposit_fee {
         protocol_fee_bps
@@ -50,7 +57,10 @@ pub async fn new_account(
         user.address(),
         protocol_fee,
         memo,
-    )?;
+        token_list_path,
+        proving_mode,
+    )
+    .await?;
 
     let (tx_hash, block_hash) = match token {
         Token::Native => {
@@ -74,9 +84,7 @@ pub async fn new_account(
     debug!("New account event: {new_account_event:?}");
 
     app_state
-        .accounts
-        .get_mut(&token.address())
-        .unwrap()
+        .account_mut(token)
         .register_action(ShielderAction::new_account(
             amount,
             new_account_event.newNoteIndex,
@@ -92,7 +100,8 @@ fn get_encryption_salt() -> U256 {
     get_mac_salt()
 }
 
-fn prepare_call(
+#[allow(clippy::too_many_arguments)]
+async fn prepare_call(
     app_state: &AppState,
     amount: U256,
     token: Token,
@@ -100,8 +109,9 @@ fn prepare_call(
     caller_address: Address,
     protocol_fee: U256,
     memo: Bytes,
+    token_list_path: [[U256; ARITY]; TOKEN_TREE_HEIGHT],
+    proving_mode: &ProvingMode,
 ) -> Result<NewAccountCall> {
-    let (params, pk) = get_proving_equipment(CircuitType::NewAccount)?;
     let extra = NewAccountCallExtra {
         anonymity_revoker_public_key,
         encryption_salt: get_encryption_salt(),
@@ -109,8 +119,16 @@ fn prepare_call(
         caller_address,
         protocol_fee,
         memo,
+        token_list_path,
     };
 
-    Ok(app_state.accounts[&token.address()]
-        .prepare_call::<NewAccountCallType>(&params, &pk, token, amount, &extra))
+    prover::prepare_call::<NewAccountCallType>(
+        app_state.account(token),
+        proving_mode,
+        CircuitType::NewAccount,
+        token,
+        amount,
+        &extra,
+    )
+    .await
 }
@@ -1,21 +1,28 @@
 use std::{fs, path::PathBuf, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
 use shielder_circuits::{
+    balance_attestation::BalanceAttestationCircuit,
+    batch_withdraw::BatchWithdrawCircuit,
     circuits::{Params, ProvingKey},
     deposit::DepositCircuit,
     generate_keys_with_min_k,
     marshall::{marshall_params, marshall_pk, unmarshall_params, unmarshall_pk},
+    migration::MigrationCircuit,
     new_account::NewAccountCircuit,
     withdraw::WithdrawCircuit,
     Params as _, MAX_K,
 };
-use tracing::debug;
+use shielder_setup::vk_fingerprint::{check_vk_fingerprint, ShielderCircuit};
+use tracing::{debug, warn};
 
 const NEW_ACCOUNT_PK_FILE: &str = "~/shielder-cli/new_account_pk";
 const DEPOSIT_PK_FILE: &str = "~/shielder-cli/deposit_pk";
 const WITHDRAW_PK_FILE: &str = "~/shielder-cli/withdraw_pk";
+const BATCH_WITHDRAW_PK_FILE: &str = "~/shielder-cli/batch_withdraw_pk";
+const MIGRATION_PK_FILE: &str = "~/shielder-cli/migration_pk";
+const BALANCE_ATTESTATION_PK_FILE: &str = "~/shielder-cli/balance_attestation_pk";
 const PROVING_PARAMS_FILE: &str = "~/shielder-cli/proving_params";
 
 #[derive(Copy, Clone, Debug)]
@@ -23,6 +30,9 @@ pub enum CircuitType {
     NewAccount,
     Deposit,
     Withdraw,
+    BatchWithdraw,
+    Migration,
+    BalanceAttestation,
 }
 
 impl CircuitType {
@@ -31,14 +41,44 @@ impl CircuitType {
             CircuitType::NewAccount => NEW_ACCOUNT_PK_FILE,
             CircuitType::Deposit => DEPOSIT_PK_FILE,
             CircuitType::Withdraw => WITHDRAW_PK_FILE,
+            CircuitType::BatchWithdraw => BATCH_WITHDRAW_PK_FILE,
+            CircuitType::Migration => MIGRATION_PK_FILE,
+            CircuitType::BalanceAttestation => BALANCE_ATTESTATION_PK_FILE,
         })
     }
 
+    pub fn circuit(self) -> ShielderCircuit {
+        match self {
+            CircuitType::NewAccount => ShielderCircuit::NewAccount,
+            CircuitType::Deposit => ShielderCircuit::Deposit,
+            CircuitType::Withdraw => ShielderCircuit::Withdraw,
+            CircuitType::BatchWithdraw => ShielderCircuit::BatchWithdraw,
+            CircuitType::Migration => ShielderCircuit::Migration,
+            CircuitType::BalanceAttestation => ShielderCircuit::BalanceAttestation,
+        }
+    }
+
+    /// Check that `pk` matches the verifying key expected by the current contract version. Keys
+    /// of circuits not verified by the contract are accepted as they are.
+    pub fn check_pk(self, pk: &ProvingKey) -> Result<()> {
+        if !self.circuit().is_verified_on_chain() {
+            return Ok(());
+        }
+        check_vk_fingerprint(self.circuit(), pk.get_vk())
+            .map(|fingerprint| {
+                debug!("Verifying key fingerprint of {self:?} circuit: {fingerprint}")
+            })
+            .map_err(|err| anyhow!("{err}"))
+    }
+
     pub fn unmarshall_pk(self, bytes: &[u8]) -> Result<(u32, ProvingKey)> {
         match self {
             CircuitType::NewAccount => unmarshall_pk::<NewAccountCircuit>(bytes),
             CircuitType::Deposit => unmarshall_pk::<DepositCircuit>(bytes),
             CircuitType::Withdraw => unmarshall_pk::<WithdrawCircuit>(bytes),
+            CircuitType::BatchWithdraw => unmarshall_pk::<BatchWithdrawCircuit>(bytes),
+            CircuitType::Migration => unmarshall_pk::<MigrationCircuit>(bytes),
+            CircuitType::BalanceAttestation => unmarshall_pk::<BalanceAttestationCircuit>(bytes),
         }
         .map_err(|_| anyhow::Error::msg("Failed to unmarshall proving key"))
     }
@@ -54,6 +94,15 @@ impl CircuitType {
             CircuitType::Withdraw => {
                 generate_keys_with_min_k(WithdrawCircuit::default(), full_params)?
             }
+            CircuitType::BatchWithdraw => {
+                generate_keys_with_min_k(BatchWithdrawCircuit::default(), full_params)?
+            }
+            CircuitType::Migration => {
+                generate_keys_with_min_k(MigrationCircuit::default(), full_params)?
+            }
+            CircuitType::BalanceAttestation => {
+                generate_keys_with_min_k(BalanceAttestationCircuit::default(), full_params)?
+            }
         };
         debug!("Generated keys for {self:?} circuit with k={k}");
         Ok((params, k, pk))
@@ -103,8 +152,18 @@ fn get_equipment(
     let file = circuit_type.filepath()?;
     debug!("Getting proving key from {file:?} for {circuit_type:?} circuit");
 
-    match fs::read(file.clone()).map(|bytes| circuit_type.unmarshall_pk(&bytes)) {
-        Ok(Ok((k, pk))) => {
+    let cached_pk = fs::read(file.clone())
+        .map_err(Into::into)
+        .and_then(|bytes| circuit_type.unmarshall_pk(&bytes))
+        .and_then(|(k, pk)| {
+            circuit_type.check_pk(&pk).inspect_err(|err| {
+                warn!("Ignoring proving key from {file:?}: {err}");
+            })?;
+            Ok((k, pk))
+        });
+
+    match cached_pk {
+        Ok((k, pk)) => {
             debug!("Found and decoded proving key from {file:?}");
             let old_k = full_params.k();
             full_params.downsize(k);
@@ -112,10 +171,11 @@ fn get_equipment(
             Ok((full_params, pk))
         }
         _ => {
-            debug!("Proving key not found or found corrupted, generating new one...");
+            debug!("Proving key not found, found corrupted or stale, generating new one...");
 
             let (params, k, pk) = circuit_type.generate_keys(full_params)?;
             debug!("Generated new proving key");
+            circuit_type.check_pk(&pk)?;
 
             save_content(file.clone(), &marshall_pk(k, &pk))?;
             debug!("Saved proving key to {file:?}");
@@ -0,0 +1,44 @@
+use alloy_primitives::U256;
+use anyhow::Result;
+use shielder_account::{
+    call_data::CallType,
+    proving::{LocalProver, TeeProver},
+    ShielderAccount, Token,
+};
+
+use crate::shielder_ops::pk::{get_proving_equipment, CircuitType};
+
+/// Where proofs for new account, deposit and withdraw calls are generated.
+#[derive(Clone, Debug)]
+pub enum ProvingMode {
+    /// Locally, with the proving keys cached on disk (see `pk`).
+    Local,
+    /// Remotely, by the TEE prover server. The prover is created once per session, so that the
+    /// TEE public key is fetched only once.
+    Tee(TeeProver),
+}
+
+/// Prepare the call data of `CT`, proving with the backend selected by `proving_mode`.
+pub async fn prepare_call<CT: CallType>(
+    account: &ShielderAccount,
+    proving_mode: &ProvingMode,
+    circuit: CircuitType,
+    token: Token,
+    amount: U256,
+    extra: &CT::Extra,
+) -> Result<CT::Calldata> {
+    let calldata = match proving_mode {
+        ProvingMode::Local => {
+            let (params, pk) = get_proving_equipment(circuit)?;
+            account
+                .prepare_call_with::<CT>(&LocalProver::new(&params, &pk), token, amount, extra)
+                .await?
+        }
+        ProvingMode::Tee(prover) => {
+            account
+                .prepare_call_with::<CT>(prover, token, amount, extra)
+                .await?
+        }
+    };
+    Ok(calldata)
+}
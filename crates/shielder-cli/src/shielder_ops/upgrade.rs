@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use alloy_primitives::{Bytes, U256};
+use alloy_provider::Provider;
+use anyhow::{bail, Result};
+use shielder_account::{
+    call_data::{MigrateCall, MigrationCallType, MigrationExtra},
+    ShielderAction, Token,
+};
+use shielder_contract::{call_type::Call, events::get_event, ShielderContract::Migrate};
+use shielder_setup::version::contract_version;
+use tracing::{debug, info, warn};
+
+use crate::{
+    app_state::AppState,
+    note_tree::get_merkle_path,
+    shielder_ops::{
+        get_mac_salt,
+        pk::{get_proving_equipment, CircuitType},
+    },
+};
+
+/// Migrate the note of the selected `token` account, if its version differs from the note version
+/// of the CLI. Fails if the contract runs a version incompatible with the CLI (different note or
+/// circuit version), as then neither migration nor any other action would be accepted.
+pub async fn upgrade_if_needed(
+    app_state: &mut AppState,
+    token: Token,
+    note_tree_file: &Path,
+) -> Result<()> {
+    let onchain_version = app_state.contract_version().await?;
+    if !onchain_version.is_compatible_with(&contract_version()) {
+        bail!(
+            "The contract runs version {onchain_version}, which is incompatible with the CLI \
+version {}. Update the CLI.",
+            contract_version()
+        );
+    }
+    if onchain_version != contract_version() {
+        warn!(
+            "The contract runs version {onchain_version}, the CLI was built for {}",
+            contract_version()
+        );
+    }
+
+    if app_state.account(token).requires_migration() {
+        info!(
+            "Account note has version {}, migrating it to version {}",
+            app_state.account(token).note_version,
+            contract_version().note_version
+        );
+        upgrade(app_state, token, Vec::new(), note_tree_file).await?;
+    }
+    Ok(())
+}
+
+pub async fn upgrade(
+    app_state: &mut AppState,
+    token: Token,
+    memo: Vec<u8>,
+    note_tree_file: &Path,
+) -> Result<()> {
+    let account = app_state.account(token);
+    if !account.requires_migration() {
+        info!(
+            "Account note already has version {}, nothing to migrate",
+            contract_version().note_version
+        );
+        return Ok(());
+    }
+    let old_note_version = account.note_version;
+    let leaf_index = account
+        .current_leaf_index()
+        .expect("Migration mustn't be the first action");
+
+    let onchain_version = app_state.contract_version().await?;
+    let shielder_user = app_state.create_shielder_user()?;
+    let (_merkle_root, merkle_path) =
+        get_merkle_path(app_state, note_tree_file, leaf_index).await?;
+    let chain_id = app_state
+        .create_simple_provider()
+        .await?
+        .get_chain_id()
+        .await?;
+
+    let call = prepare_call(
+        app_state,
+        token,
+        MigrationExtra {
+            merkle_path,
+            contract_version: onchain_version,
+            chain_id: U256::from(chain_id),
+            mac_salt: get_mac_salt(),
+            memo: Bytes::from(memo),
+        },
+    )?;
+    let (tx_hash, block_hash) = shielder_user.migrate::<Call>(call.into()).await?;
+
+    let migrate_event = get_event::<Migrate>(
+        &app_state.create_simple_provider().await?,
+        tx_hash,
+        block_hash,
+    )
+    .await?;
+    debug!("Migrate event: {migrate_event:?}");
+
+    app_state
+        .account_mut(token)
+        .register_action(ShielderAction::migrate(
+            old_note_version,
+            migrate_event.newNoteIndex,
+            tx_hash,
+            token,
+        ));
+    info!(
+        "Migrated account note from version {old_note_version} to {}",
+        contract_version().note_version
+    );
+    Ok(())
+}
+
+fn prepare_call(app_state: &AppState, token: Token, extra: MigrationExtra) -> Result<MigrateCall> {
+    let (params, pk) = get_proving_equipment(CircuitType::Migration)?;
+    Ok(app_state.account(token).prepare_call::<MigrationCallType>(
+        &params,
+        &pk,
+        token,
+        U256::ZERO,
+        &extra,
+    ))
+}
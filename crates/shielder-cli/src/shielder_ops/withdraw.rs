@@ -1,34 +1,42 @@
-use std::str::FromStr;
+use std::{path::Path, str::FromStr, time::Duration};
 
 use alloy_primitives::{Address, BlockHash, Bytes, TxHash, U256};
 use alloy_provider::{network::AnyNetwork, Provider};
 use alloy_transport::BoxTransport;
 use anyhow::{anyhow, bail, Result};
+use reqwest::{header::RETRY_AFTER, StatusCode};
 use serde::Serialize;
 use shielder_account::{
-    call_data::{WithdrawCallType, WithdrawExtra},
+    call_data::{AssociationSetPath, WithdrawCallType, WithdrawExtra},
     ShielderAction, Token,
 };
 use shielder_contract::{
-    call_type::DryRun, events::get_event, merkle_path::get_current_merkle_path,
-    ShielderContract::Withdraw,
+    call_type::DryRun, events::get_event, AssociationSet, ShielderContract::Withdraw,
 };
 use shielder_relayer::{
-    QuoteFeeQuery, QuoteFeeResponse, RelayCalldata, RelayQuery, RelayResponse,
-    SimpleServiceResponse,
+    QuoteFeeQuery, QuoteFeeResponse, RelayCalldata, RelayMode, RelayModeQuery, RelayQuery,
+    RelayResponse, RelayStatus, RelayStatusResponse, RelayTicket, SimpleServiceResponse,
 };
-use shielder_setup::{protocol_fee::compute_protocol_fee_from_net, version::contract_version};
+use shielder_setup::protocol_fee::compute_protocol_fee_from_net;
 use tokio::time::sleep;
 use tracing::{debug, info};
 
 use crate::{
     app_state::{AppState, RelayerRpcUrl},
+    note_tree::get_merkle_path,
     shielder_ops::{
         get_mac_salt,
-        pk::{get_proving_equipment, CircuitType},
+        pk::CircuitType,
+        prover::{self, ProvingMode},
     },
 };
 
+/// How many times a request rejected due to a full relayer queue is retried.
+const QUEUE_FULL_RETRIES: u32 = 3;
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const STATUS_POLL_ATTEMPTS: u32 = 120;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn withdraw(
     app_state: &mut AppState,
     amount: u128,
@@ -36,6 +44,10 @@ pub async fn withdraw(
     token: Token,
     pocket_money: u128,
     memo: Vec<u8>,
+    association_set: Option<&Path>,
+    note_tree_file: &Path,
+    relay_mode: RelayMode,
+    proving_mode: &ProvingMode,
 ) -> Result<()> {
     app_state.relayer_rpc_url.check_connection().await?;
 
@@ -43,52 +55,34 @@ pub async fn withdraw(
     let memo = Bytes::from(memo);
     let quoted_fee = get_relayer_total_fee(app_state, token, pocket_money).await?;
 
-    let protocol_fee_bps = if let Some(protocol_fee_bps) = app_state.protocol_fees.withdraw_fee {
-        protocol_fee_bps
-    } else {
-        let shielder_user = app_state.create_shielder_user();
-        let protocol_fee_bps = shielder_user.protocol_withdraw_fee_bps::<DryRun>().await?;
-        app_state.protocol_fees.withdraw_fee = Some(protocol_fee_bps);
-        protocol_fee_bps
-    };
+    let protocol_fee_bps = protocol_withdraw_fee_bps(app_state).await?;
 
     let mut amount = U256::from(amount) + quoted_fee.fee_details.total_cost_fee_token;
     let protocol_fee = compute_protocol_fee_from_net(U256::from(amount), protocol_fee_bps);
 
     amount += protocol_fee;
 
-    let shielded_amount = app_state.accounts[&token.address()].shielded_amount;
+    let shielded_amount = app_state.account(token).shielded_amount;
 
     if amount > shielded_amount {
         bail!("Not enough funds to withdraw");
     }
 
-    let relayer_response = reqwest::Client::new()
-        .post(app_state.relayer_rpc_url.relay_url())
-        .json(
-            &prepare_relayer_query(
-                app_state,
-                amount,
-                to,
-                token,
-                quoted_fee,
-                pocket_money,
-                protocol_fee,
-                memo,
-            )
-            .await?,
-        )
-        .send()
-        .await?;
-
-    debug!("Relayer response: {relayer_response:?}");
-    if !relayer_response.status().is_success() {
-        bail!(
-            "Relayer failed to process the request: {:?}",
-            relayer_response.status()
-        );
-    }
-    let tx_hash = relayer_response.json::<RelayResponse>().await?.tx_hash;
+    let relayer_query = prepare_relayer_query(
+        app_state,
+        amount,
+        to,
+        token,
+        quoted_fee,
+        pocket_money,
+        protocol_fee,
+        memo,
+        association_set,
+        note_tree_file,
+        proving_mode,
+    )
+    .await?;
+    let tx_hash = relay(&app_state.relayer_rpc_url, &relayer_query, relay_mode).await?;
 
     let provider = app_state.create_simple_provider().await?;
     let block_hash = get_block_hash(&provider, tx_hash).await?;
@@ -97,9 +91,7 @@ pub async fn withdraw(
     debug!("Withdraw event: {withdraw_event:?}");
 
     app_state
-        .accounts
-        .get_mut(&token.address())
-        .unwrap()
+        .account_mut(token)
         .register_action(ShielderAction::withdraw(
             amount,
             withdraw_event.newNoteIndex,
@@ -112,6 +104,91 @@ pub async fn withdraw(
     Ok(())
 }
 
+/// Protocol withdraw fee (in basis points), fetched from the contract on the first use.
+pub async fn protocol_withdraw_fee_bps(app_state: &mut AppState) -> Result<U256> {
+    if let Some(protocol_fee_bps) = app_state.protocol_fees.withdraw_fee {
+        return Ok(protocol_fee_bps);
+    }
+    let shielder_user = app_state.create_shielder_user()?;
+    let protocol_fee_bps = shielder_user.protocol_withdraw_fee_bps::<DryRun>().await?;
+    app_state.protocol_fees.withdraw_fee = Some(protocol_fee_bps);
+    Ok(protocol_fee_bps)
+}
+
+/// Send the query to the relayer and return the hash of the relay transaction. If the relayer
+/// queue is full, the request is retried after the time advised by the relayer.
+async fn relay(
+    relayer_rpc_url: &RelayerRpcUrl,
+    query: &RelayQuery,
+    mode: RelayMode,
+) -> Result<TxHash> {
+    let client = reqwest::Client::new();
+    let mut attempt = 0;
+    let relayer_response = loop {
+        let response = client
+            .post(relayer_rpc_url.relay_url())
+            .query(&RelayModeQuery { mode })
+            .json(query)
+            .send()
+            .await?;
+        debug!("Relayer response: {response:?}");
+
+        match retry_after(&response) {
+            Some(delay) if attempt < QUEUE_FULL_RETRIES => {
+                attempt += 1;
+                info!("Relayer is busy, retrying in {delay:?}");
+                sleep(delay).await;
+            }
+            _ => break response,
+        }
+    };
+
+    if !relayer_response.status().is_success() {
+        let status = relayer_response.status();
+        let message = relayer_response.text().await.unwrap_or_default();
+        bail!("Relayer failed to process the request: {status:?} {message}");
+    }
+
+    match mode {
+        RelayMode::Sync => Ok(relayer_response.json::<RelayResponse>().await?.tx_hash),
+        RelayMode::Async => {
+            let ticket = relayer_response.json::<RelayTicket>().await?;
+            info!("Relay request accepted, ticket: {}", ticket.id);
+            wait_for_relay(relayer_rpc_url, ticket).await
+        }
+    }
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    if response.status() != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    seconds.parse().ok().map(Duration::from_secs)
+}
+
+/// Poll the relayer until the transaction for `ticket` is submitted.
+async fn wait_for_relay(relayer_rpc_url: &RelayerRpcUrl, ticket: RelayTicket) -> Result<TxHash> {
+    for _ in 0..STATUS_POLL_ATTEMPTS {
+        let response = reqwest::get(relayer_rpc_url.relay_status_url(ticket.id)).await?;
+        if !response.status().is_success() {
+            bail!("Failed to get relay status: {:?}", response.status());
+        }
+        let status = response.json::<RelayStatusResponse>().await?;
+        debug!("Relay status: {status:?}");
+
+        match (status.status, status.tx_hash, status.error) {
+            (RelayStatus::Submitted | RelayStatus::Mined, Some(tx_hash), _) => return Ok(tx_hash),
+            (RelayStatus::Failed, _, Some(error)) => {
+                bail!("Relay failed ({:?}): {}", error.kind, error.message)
+            }
+            (RelayStatus::Failed, _, None) => bail!("Relay failed"),
+            _ => sleep(STATUS_POLL_INTERVAL).await,
+        }
+    }
+    bail!("Relay was not completed in time (ticket: {})", ticket.id)
+}
+
 async fn get_block_hash(
     provider: &impl Provider<BoxTransport, AnyNetwork>,
     tx_hash: TxHash,
@@ -173,7 +250,7 @@ async fn get_relayer_address(relayer_rpc_url: &RelayerRpcUrl) -> Result<Address>
 
 #[allow(clippy::too_many_arguments)]
 async fn prepare_relayer_query(
-    app_state: &AppState,
+    app_state: &mut AppState,
     amount: U256,
     to: Address,
     token: Token,
@@ -181,23 +258,30 @@ async fn prepare_relayer_query(
     pocket_money: U256,
     protocol_fee: U256,
     memo: Bytes,
+    association_set: Option<&Path>,
+    note_tree_file: &Path,
+    proving_mode: &ProvingMode,
 ) -> Result<impl Serialize> {
-    let (params, pk) = get_proving_equipment(CircuitType::Withdraw)?;
-    let leaf_index = app_state.accounts[&token.address()]
+    let leaf_index = app_state
+        .account(token)
         .current_leaf_index()
         .expect("Deposit mustn't be the first action");
-    let (merkle_root, merkle_path) =
-        get_current_merkle_path(leaf_index, &app_state.create_shielder_user()).await?;
+    let (merkle_root, merkle_path) = get_merkle_path(app_state, note_tree_file, leaf_index).await?;
+    let association_set = association_set
+        .map(|file| association_set_path(app_state, token, file))
+        .transpose()?;
 
     let chain_id = app_state
         .create_simple_provider()
         .await?
         .get_chain_id()
         .await?;
+    let contract_version = app_state.contract_version().await?;
 
-    let calldata = app_state.accounts[&token.address()].prepare_call::<WithdrawCallType>(
-        &params,
-        &pk,
+    let calldata = prover::prepare_call::<WithdrawCallType>(
+        app_state.account(token),
+        proving_mode,
+        CircuitType::Withdraw,
         token,
         amount,
         &WithdrawExtra {
@@ -205,18 +289,20 @@ async fn prepare_relayer_query(
             to,
             relayer_address: get_relayer_address(&app_state.relayer_rpc_url).await?,
             relayer_fee: quoted_fee.fee_details.total_cost_fee_token,
-            contract_version: contract_version(),
+            contract_version,
             chain_id: U256::from(chain_id),
             mac_salt: get_mac_salt(),
             pocket_money,
             protocol_fee,
             memo: memo.clone(),
+            association_set,
         },
-    );
+    )
+    .await?;
 
     Ok(RelayQuery {
         calldata: RelayCalldata {
-            expected_contract_version: contract_version().to_bytes(),
+            expected_contract_version: contract_version.to_bytes(),
             amount,
             withdraw_address: to,
             merkle_root,
@@ -227,9 +313,25 @@ async fn prepare_relayer_query(
             fee_amount: calldata.relayer_fee,
             mac_salt: calldata.mac_salt,
             mac_commitment: calldata.mac_commitment,
+            association_set_root: calldata.association_set_root,
             pocket_money,
             memo,
         },
         quote: quoted_fee.into(),
     })
 }
+
+fn association_set_path(
+    app_state: &AppState,
+    token: Token,
+    file: &Path,
+) -> Result<AssociationSetPath> {
+    let set = AssociationSet::load(file)?;
+    let leaf = app_state.account(token).association_set_leaf();
+    if !set.contains(leaf) {
+        bail!("The account is not a member of the association set {file:?}");
+    }
+    let (root, path) = set.merkle_path(leaf)?;
+    info!("Claiming membership in the association set with root {root}");
+    Ok(AssociationSetPath { root, path })
+}
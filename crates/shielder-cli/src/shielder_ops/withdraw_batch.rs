@@ -0,0 +1,145 @@
+use std::{collections::BTreeSet, path::Path};
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::Provider;
+use anyhow::{anyhow, bail, Result};
+use shielder_account::{
+    call_data::{
+        prove_withdraw_batch, CallType, WithdrawCall, WithdrawCallType, WithdrawExtra,
+        WithdrawWitness,
+    },
+    ShielderAction, Token,
+};
+use shielder_circuits::consts::WITHDRAW_BATCH_SIZE;
+use shielder_contract::{call_type::Call, events::get_events, ShielderContract::Withdraw};
+use shielder_setup::protocol_fee::compute_protocol_fee_from_net;
+use tracing::{debug, info};
+
+use crate::{
+    app_state::AppState,
+    note_tree::get_merkle_path,
+    shielder_ops::{
+        get_mac_salt,
+        pk::{get_proving_equipment, CircuitType},
+        withdraw::protocol_withdraw_fee_bps,
+    },
+};
+
+/// Withdraw `amounts` from the `token` accounts with `indices` (one withdrawal per account) to
+/// `to`, with a single proof for all of them.
+///
+/// Proving a batch needs the secrets of all the batched accounts, so the call is sent by the
+/// signer directly instead of through the relayer. The signer pays for gas and no relayer fee is
+/// charged.
+pub async fn withdraw_batch(
+    app_state: &mut AppState,
+    token: Token,
+    indices: &[u32],
+    amounts: &[u128],
+    to: Address,
+    memo: Vec<u8>,
+    note_tree_file: &Path,
+) -> Result<()> {
+    if indices.len() != WITHDRAW_BATCH_SIZE || amounts.len() != WITHDRAW_BATCH_SIZE {
+        bail!("A batch consists of exactly {WITHDRAW_BATCH_SIZE} accounts and amounts");
+    }
+    if indices.iter().collect::<BTreeSet<_>>().len() != indices.len() {
+        bail!("An account can withdraw only once in a batch");
+    }
+
+    let memo = Bytes::from(memo);
+    let protocol_fee_bps = protocol_withdraw_fee_bps(app_state).await?;
+    let contract_version = app_state.contract_version().await?;
+    let chain_id = app_state
+        .create_simple_provider()
+        .await?
+        .get_chain_id()
+        .await?;
+    let shielder_user = app_state.create_shielder_user()?;
+
+    let mut withdrawals = Vec::with_capacity(WITHDRAW_BATCH_SIZE);
+    for (&index, &amount) in indices.iter().zip(amounts) {
+        let account = app_state
+            .accounts
+            .get(&token.address())
+            .and_then(|accounts| accounts.get(&index))
+            .ok_or_else(|| anyhow!("There is no account {index} for {token:?}"))?
+            .clone();
+        if account.requires_migration() {
+            bail!("Account {index} has to be upgraded first (select it and run `upgrade`)");
+        }
+
+        let protocol_fee = compute_protocol_fee_from_net(U256::from(amount), protocol_fee_bps);
+        let amount = U256::from(amount) + protocol_fee;
+        if amount > account.shielded_amount {
+            bail!("Not enough funds to withdraw from account {index}");
+        }
+
+        let leaf_index = account
+            .current_leaf_index()
+            .expect("Withdrawal mustn't be the first action");
+        let (_merkle_root, merkle_path) =
+            get_merkle_path(app_state, note_tree_file, leaf_index).await?;
+
+        let extra = WithdrawExtra {
+            merkle_path,
+            to,
+            relayer_address: shielder_user.address(),
+            relayer_fee: U256::ZERO,
+            contract_version,
+            chain_id: U256::from(chain_id),
+            mac_salt: get_mac_salt(),
+            pocket_money: U256::ZERO,
+            protocol_fee,
+            memo: memo.clone(),
+            association_set: None,
+        };
+        let knowledge = WithdrawCallType::prepare_prover_knowledge(&account, token, amount, &extra);
+        let call = WithdrawCallType::prepare_call_data(&knowledge, vec![], &extra);
+        withdrawals.push((call, WithdrawWitness::from(&knowledge)));
+    }
+    let withdrawals: [(WithdrawCall, WithdrawWitness); WITHDRAW_BATCH_SIZE] = withdrawals
+        .try_into()
+        .expect("There is a withdrawal for every account");
+
+    let (params, pk) = get_proving_equipment(CircuitType::BatchWithdraw)?;
+    let call = prove_withdraw_batch(&params, &pk, withdrawals)
+        .map_err(|err| anyhow!("Withdrawals cannot be batched: {err:?}"))?;
+    let (tx_hash, block_hash) = shielder_user
+        .withdraw_batch::<Call>(call.clone().into())
+        .await?;
+
+    let withdraw_events = get_events::<Withdraw>(
+        &app_state.create_simple_provider().await?,
+        tx_hash,
+        block_hash,
+    )
+    .await?;
+    debug!("Withdraw events: {withdraw_events:?}");
+    if withdraw_events.len() != WITHDRAW_BATCH_SIZE {
+        bail!("Expected {WITHDRAW_BATCH_SIZE} withdraw events, found {withdraw_events:?}");
+    }
+
+    let accounts = app_state
+        .accounts
+        .get_mut(&token.address())
+        .expect("Accounts should exist");
+    for ((index, withdrawal), event) in indices.iter().zip(&call.withdrawals).zip(withdraw_events) {
+        accounts
+            .get_mut(index)
+            .expect("Account should exist")
+            .register_action(ShielderAction::withdraw(
+                withdrawal.amount,
+                event.newNoteIndex,
+                tx_hash,
+                to,
+                token,
+                event.protocolFee,
+            ));
+        info!(
+            "Withdrawn {} tokens from account {index}",
+            withdrawal.amount
+        );
+    }
+    Ok(())
+}
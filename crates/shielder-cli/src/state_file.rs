@@ -5,8 +5,8 @@ use std::{
 };
 
 use anyhow::{anyhow, bail, Result};
-use content_encryption::{decrypt_to_string, encrypt};
-use tracing::debug;
+use content_encryption::{decrypt_to_string, encrypt, is_legacy_format};
+use tracing::{debug, info};
 
 use crate::app_state::AppState;
 
@@ -25,7 +25,8 @@ pub fn get_app_state(path: &PathBuf, password: &str) -> Result<AppState> {
 
 /// Save `app_state` to `path`.
 ///
-/// `path` will be encrypted with `password`.
+/// `path` will be encrypted with `password`, always in the current envelope format (so state files
+/// in the legacy format are upgraded on the first save).
 pub fn save_app_state(app_state: &AppState, path: &PathBuf, password: &str) -> Result<()> {
     let serialized =
         serde_json::to_string_pretty(app_state).map_err(|e| anyhow!("Failed to serialize: {e}"))?;
@@ -36,6 +37,9 @@ pub fn save_app_state(app_state: &AppState, path: &PathBuf, password: &str) -> R
 /// Read `AppState` from `path` (decrypting the content with `password`).
 fn read_from(path: &Path, password: &str) -> Result<AppState> {
     let file_content = fs::read(path).map_err(|e| anyhow!("Failed to read file content: {e}"))?;
+    if is_legacy_format(&file_content) {
+        info!("State file {path:?} uses legacy encryption, it will be re-encrypted on next save");
+    }
     let decrypted_content = decrypt_to_string(&file_content, password.as_bytes())?;
     serde_json::from_str::<AppState>(&decrypted_content)
         .map_err(|e| anyhow!("Failed to deserialize application state: {e}"))
@@ -0,0 +1,302 @@
+use alloy_primitives::{Address, BlockHash, Bytes, TxHash, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::SolCall;
+use serde::Serialize;
+use shielder_setup::shielder_circuits::GrumpkinPointAffine;
+use type_conversions::field_to_u256;
+
+use crate::{
+    call_type::{Call, CallType, DryRun},
+    connection::{Connection, ConnectionPolicy, NoProvider},
+    token_list::build_token_list,
+    ContractResult,
+    ShielderContract::{
+        pauseCall, setAnonymityRevokerPubkeyCall, setProtocolDepositFeeBpsCall,
+        setProtocolFeeReceiverCall, setProtocolWithdrawFeeBpsCall, setTokenListCall, unpauseCall,
+    },
+    ShielderContractError, ShielderState,
+};
+
+/// Owner-only operation on the Shielder contract.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    Pause,
+    Unpause,
+    SetProtocolDepositFeeBps(U256),
+    SetProtocolWithdrawFeeBps(U256),
+    SetProtocolFeeReceiver(Address),
+    SetAnonymityRevokerPubkey(GrumpkinPointAffine<U256>),
+    /// `root` must be the root of the token tree built from `tokens`, see [`Self::check`].
+    SetTokenList {
+        tokens: Vec<Address>,
+        root: U256,
+    },
+}
+
+impl AdminAction {
+    /// Set the allowed ERC20 tokens to `tokens`, with the root of the token tree built from them.
+    pub fn set_token_list(tokens: Vec<Address>) -> ContractResult<Self> {
+        let root = field_to_u256(build_token_list(&tokens)?.root());
+        Ok(AdminAction::SetTokenList { tokens, root })
+    }
+
+    /// Check the action before it is sent. The contract doesn't recompute the token tree, so a
+    /// token list with a wrong root would be accepted and make every new account proof revert.
+    pub fn check(&self) -> ContractResult<()> {
+        if let AdminAction::SetTokenList { tokens, root } = self {
+            let local = field_to_u256(build_token_list(tokens)?.root());
+            if local != *root {
+                return Err(ShielderContractError::TokenListRootMismatch {
+                    local,
+                    contract: *root,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// ABI-encoded call of the Shielder contract performing the action.
+    pub fn calldata(&self) -> Bytes {
+        match self {
+            AdminAction::Pause => pauseCall::new(()).abi_encode(),
+            AdminAction::Unpause => unpauseCall::new(()).abi_encode(),
+            AdminAction::SetProtocolDepositFeeBps(fee_bps) => {
+                setProtocolDepositFeeBpsCall { _0: *fee_bps }.abi_encode()
+            }
+            AdminAction::SetProtocolWithdrawFeeBps(fee_bps) => {
+                setProtocolWithdrawFeeBpsCall { _0: *fee_bps }.abi_encode()
+            }
+            AdminAction::SetProtocolFeeReceiver(receiver) => setProtocolFeeReceiverCall {
+                newProtocolFeeReceiver: *receiver,
+            }
+            .abi_encode(),
+            AdminAction::SetAnonymityRevokerPubkey(pubkey) => setAnonymityRevokerPubkeyCall {
+                anonymityRevokerPubkeyX: pubkey.x,
+                anonymityRevokerPubkeyY: pubkey.y,
+            }
+            .abi_encode(),
+            AdminAction::SetTokenList { tokens, root } => setTokenListCall {
+                newTokenList: tokens.clone(),
+                newTokenListRoot: *root,
+            }
+            .abi_encode(),
+        }
+        .into()
+    }
+
+    /// Unsigned transaction performing the action, to be executed by the owner (e.g. proposed to a
+    /// multisig).
+    pub fn unsigned_transaction(&self, contract_address: Address) -> UnsignedTransaction {
+        UnsignedTransaction {
+            to: contract_address,
+            value: U256::ZERO,
+            data: self.calldata(),
+        }
+    }
+
+    /// The state of the contract after a successful execution of the action on `state`.
+    pub fn apply(&self, mut state: ShielderState) -> ShielderState {
+        match self {
+            AdminAction::Pause => state.paused = true,
+            AdminAction::Unpause => state.paused = false,
+            AdminAction::SetProtocolDepositFeeBps(fee_bps) => {
+                state.protocol_deposit_fee_bps = *fee_bps
+            }
+            AdminAction::SetProtocolWithdrawFeeBps(fee_bps) => {
+                state.protocol_withdraw_fee_bps = *fee_bps
+            }
+            AdminAction::SetProtocolFeeReceiver(receiver) => {
+                state.protocol_fee_receiver = *receiver
+            }
+            AdminAction::SetAnonymityRevokerPubkey(pubkey) => {
+                state.anonymity_revoker_pubkey = *pubkey
+            }
+            AdminAction::SetTokenList { root, .. } => state.token_list_root = *root,
+        }
+        state
+    }
+}
+
+/// Transaction in the format accepted by multisig wallets (e.g. Safe Transaction Builder).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnsignedTransaction {
+    pub to: Address,
+    #[serde(serialize_with = "serialize_decimal")]
+    pub value: U256,
+    pub data: Bytes,
+}
+
+fn serialize_decimal<S: serde::Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+/// Owner of the Shielder contract. Performs `AdminAction`s, either for real or as dry runs (e.g.
+/// to check that they won't revert before proposing them to a multisig).
+#[derive(Clone)]
+pub struct ShielderAdmin<Provider = NoProvider> {
+    connection: Connection<Provider>,
+}
+
+impl<P: Provider + Clone> ShielderAdmin<P> {
+    /// Create a new `ShielderAdmin` instance. For dry runs of a multisig owner, use
+    /// `ConnectionPolicy::Keep` with the multisig address as `caller_address`.
+    pub fn new(contract_address: Address, connection_policy: ConnectionPolicy<P>) -> Self {
+        let connection = Connection::new(contract_address, connection_policy);
+        Self { connection }
+    }
+
+    /// Get the address of the admin.
+    pub fn address(&self) -> Address {
+        self.connection.caller_address()
+    }
+
+    /// Get the address of the Shielder contract.
+    pub fn contract_address(&self) -> Address {
+        self.connection.contract_address()
+    }
+
+    /// Simulate `action` without sending a transaction. Fails with the decoded revert reason if
+    /// the action would revert (e.g. because the caller is not the owner), or if it doesn't pass
+    /// [`AdminAction::check`].
+    pub async fn dry_run(&self, action: &AdminAction) -> ContractResult<()> {
+        self.perform::<DryRun, ()>(action).await
+    }
+
+    /// Perform `action` and wait for the block inclusion.
+    pub async fn execute(&self, action: &AdminAction) -> ContractResult<(TxHash, BlockHash)> {
+        self.perform::<Call, (TxHash, BlockHash)>(action).await
+    }
+
+    async fn perform<C, R>(&self, action: &AdminAction) -> ContractResult<R>
+    where
+        C: CallType<pauseCall, Result = R>
+            + CallType<unpauseCall, Result = R>
+            + CallType<setProtocolDepositFeeBpsCall, Result = R>
+            + CallType<setProtocolWithdrawFeeBpsCall, Result = R>
+            + CallType<setProtocolFeeReceiverCall, Result = R>
+            + CallType<setAnonymityRevokerPubkeyCall, Result = R>
+            + CallType<setTokenListCall, Result = R>,
+    {
+        action.check()?;
+        match action {
+            AdminAction::Pause => self.connection.call::<C, _>(pauseCall::new(())).await,
+            AdminAction::Unpause => self.connection.call::<C, _>(unpauseCall::new(())).await,
+            AdminAction::SetProtocolDepositFeeBps(fee_bps) => {
+                self.connection
+                    .call::<C, _>(setProtocolDepositFeeBpsCall { _0: *fee_bps })
+                    .await
+            }
+            AdminAction::SetProtocolWithdrawFeeBps(fee_bps) => {
+                self.connection
+                    .call::<C, _>(setProtocolWithdrawFeeBpsCall { _0: *fee_bps })
+                    .await
+            }
+            AdminAction::SetProtocolFeeReceiver(receiver) => {
+                self.connection
+                    .call::<C, _>(setProtocolFeeReceiverCall {
+                        newProtocolFeeReceiver: *receiver,
+                    })
+                    .await
+            }
+            AdminAction::SetAnonymityRevokerPubkey(pubkey) => {
+                self.connection
+                    .call::<C, _>(setAnonymityRevokerPubkeyCall {
+                        anonymityRevokerPubkeyX: pubkey.x,
+                        anonymityRevokerPubkeyY: pubkey.y,
+                    })
+                    .await
+            }
+            AdminAction::SetTokenList { tokens, root } => {
+                self.connection
+                    .call::<C, _>(setTokenListCall {
+                        newTokenList: tokens.clone(),
+                        newTokenListRoot: *root,
+                    })
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use alloy_sol_types::SolCall;
+    use shielder_setup::{shielder_circuits::GrumpkinPointAffine, version::contract_version};
+
+    use super::AdminAction;
+    use crate::{
+        ShielderContract::setProtocolFeeReceiverCall, ShielderContractError, ShielderState,
+    };
+
+    fn state() -> ShielderState {
+        ShielderState {
+            contract_version: contract_version(),
+            owner: Address::random(),
+            paused: false,
+            merkle_tree: Default::default(),
+            anonymity_revoker_pubkey: GrumpkinPointAffine::new(U256::from(1), U256::from(2)),
+            protocol_deposit_fee_bps: U256::ZERO,
+            protocol_withdraw_fee_bps: U256::ZERO,
+            protocol_fee_receiver: Address::random(),
+            token_list_root: U256::ZERO,
+        }
+    }
+
+    #[test]
+    fn calldata_encodes_the_action() {
+        let receiver = Address::random();
+        let calldata = AdminAction::SetProtocolFeeReceiver(receiver).calldata();
+
+        let call = setProtocolFeeReceiverCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(call.newProtocolFeeReceiver, receiver);
+    }
+
+    #[test]
+    fn apply_changes_only_the_affected_field() {
+        let before = state();
+
+        let after = AdminAction::SetProtocolWithdrawFeeBps(U256::from(30)).apply(before.clone());
+        assert_eq!(after.protocol_withdraw_fee_bps, U256::from(30));
+        assert_eq!(
+            ShielderState {
+                protocol_withdraw_fee_bps: U256::ZERO,
+                ..after
+            },
+            before
+        );
+
+        assert!(AdminAction::Pause.apply(before).paused);
+    }
+
+    #[test]
+    fn token_list_root_must_match_the_tokens() {
+        let action = AdminAction::set_token_list(vec![Address::random()]).unwrap();
+        assert!(action.check().is_ok());
+
+        let AdminAction::SetTokenList { root, .. } = action else {
+            unreachable!("set_token_list builds SetTokenList")
+        };
+        assert_eq!(action.apply(state()).token_list_root, root);
+
+        let other_tokens = AdminAction::SetTokenList {
+            tokens: vec![Address::random()],
+            root,
+        };
+        assert!(matches!(
+            other_tokens.check(),
+            Err(ShielderContractError::TokenListRootMismatch { contract, .. }) if contract == root
+        ));
+    }
+
+    #[test]
+    fn unsigned_transaction_is_serialized_for_multisig() {
+        let contract = Address::random();
+        let tx = AdminAction::Unpause.unsigned_transaction(contract);
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json["to"], serde_json::json!(contract));
+        assert_eq!(json["value"], "0");
+        assert_eq!(json["data"], "0x3f4ba83a");
+    }
+}
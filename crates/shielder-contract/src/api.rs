@@ -9,9 +9,11 @@ use crate::{
     connection::{Connection, ConnectionPolicy, NoProvider},
     ContractResult,
     ShielderContract::{
-        anonymityRevokerPubkeyCall, depositERC20Call, depositNativeCall, getMerklePathCall,
-        newAccountERC20Call, newAccountNativeCall, nullifiersCall, protocolDepositFeeBpsCall,
-        protocolWithdrawFeeBpsCall, withdrawERC20Call, withdrawNativeCall,
+        anonymityRevokerPubkeyCall, claimCall, depositERC20Call, depositNativeCall,
+        getMerklePathCall, merkleTreeCall, migrateCall, newAccountERC20Call, newAccountNativeCall,
+        nullifiersCall, protocolDepositFeeBpsCall, protocolWithdrawFeeBpsCall, tokenListCall,
+        tokenListRootCall, transferCall, withdrawBatchCall, withdrawERC20Call, withdrawNativeCall,
+        CONTRACT_VERSIONCall,
     },
 };
 
@@ -37,6 +39,11 @@ impl<P: Provider + Clone> ShielderUser<P> {
         self.connection.caller_address()
     }
 
+    /// Get the address of the Shielder contract.
+    pub fn contract_address(&self) -> Address {
+        self.connection.contract_address()
+    }
+
     /// Create new account.
     pub async fn new_account_native<C: CallType<newAccountNativeCall>>(
         &self,
@@ -90,6 +97,47 @@ impl<P: Provider + Clone> ShielderUser<P> {
             .await
     }
 
+    /// Withdraw a batch of withdrawals of the same token, proven together.
+    pub async fn withdraw_batch<C: CallType<withdrawBatchCall>>(
+        &self,
+        call: withdrawBatchCall,
+    ) -> ContractResult<C::Result> {
+        self.connection.call::<C, _>(call).await
+    }
+
+    /// Transfer funds to another shielded account. No funds leave the contract.
+    pub async fn transfer<C: CallType<transferCall>>(
+        &self,
+        call: transferCall,
+    ) -> ContractResult<C::Result> {
+        self.connection.call::<C, _>(call).await
+    }
+
+    /// Claim a receipt of a transfer into the recipient's note. No funds leave the contract.
+    pub async fn claim<C: CallType<claimCall>>(
+        &self,
+        call: claimCall,
+    ) -> ContractResult<C::Result> {
+        self.connection.call::<C, _>(call).await
+    }
+
+    /// Migrate a note of an older note version to the current one. No funds are moved.
+    pub async fn migrate<C: CallType<migrateCall>>(
+        &self,
+        call: migrateCall,
+    ) -> ContractResult<C::Result> {
+        self.connection.call::<C, _>(call).await
+    }
+
+    /// Get the version of the deployed contract.
+    pub async fn contract_version<C: CallType<CONTRACT_VERSIONCall>>(
+        &self,
+    ) -> ContractResult<C::Result> {
+        self.connection
+            .call::<C, _>(CONTRACT_VERSIONCall::new(()))
+            .await
+    }
+
     /// Get the block number for the `nullifierHash`. `0` means that the nullifier hasn't been used
     /// yet.
     pub async fn nullifiers<C: CallType<nullifiersCall>>(
@@ -113,6 +161,11 @@ impl<P: Provider + Clone> ShielderUser<P> {
             .await
     }
 
+    /// Get the current state of the note tree: `(root, nextFreeLeafId, maxLeafId, firstLeafId)`.
+    pub async fn merkle_tree<C: CallType<merkleTreeCall>>(&self) -> ContractResult<C::Result> {
+        self.connection.call::<C, _>(merkleTreeCall::new(())).await
+    }
+
     pub async fn anonymity_revoker_pubkey<C: CallType<anonymityRevokerPubkeyCall>>(
         &self,
     ) -> ContractResult<C::Result> {
@@ -137,6 +190,18 @@ impl<P: Provider + Clone> ShielderUser<P> {
             .await
     }
 
+    pub async fn token_list<C: CallType<tokenListCall>>(&self) -> ContractResult<C::Result> {
+        self.connection.call::<C, _>(tokenListCall::new(())).await
+    }
+
+    pub async fn token_list_root<C: CallType<tokenListRootCall>>(
+        &self,
+    ) -> ContractResult<C::Result> {
+        self.connection
+            .call::<C, _>(tokenListRootCall::new(()))
+            .await
+    }
+
     #[cfg(feature = "erc20")]
     pub async fn approve_erc20<C: CallType<approveCall>>(
         &self,
@@ -0,0 +1,168 @@
+//! Association sets of Shielder accounts.
+//!
+//! An association set is a list of prenullifiers (`hash(id)`, published in `NewAccount` events)
+//! chosen by an association set provider, e.g. all accounts that were not flagged by the anonymity
+//! revoker. A withdrawal may prove that the withdrawing account belongs to such a set by publishing
+//! its root (`associationSetRoot`) without revealing which account it is.
+//!
+//! The set is committed to as a Merkle tree with the same shape as the note tree, so `NoteTree` is
+//! reused to compute roots and paths.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use alloy_primitives::U256;
+use serde::{Deserialize, Serialize};
+use shielder_setup::consts::{ARITY, TREE_HEIGHT};
+
+use crate::{note_tree::NoteTree, ContractResult, ShielderContractError};
+
+/// Merkle tree of prenullifiers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssociationSet {
+    prenullifiers: Vec<U256>,
+    indices: HashMap<U256, u64>,
+    tree: NoteTree,
+}
+
+/// Published form of an association set. The root is included so that users can check that they
+/// have downloaded the set they intend to claim.
+#[derive(Deserialize, Serialize)]
+struct AssociationSetFile {
+    root: U256,
+    prenullifiers: Vec<U256>,
+}
+
+impl AssociationSet {
+    /// Build the set from `prenullifiers`. Leaves are inserted in the given order, duplicates are
+    /// skipped.
+    pub fn new(prenullifiers: impl IntoIterator<Item = U256>) -> ContractResult<Self> {
+        let mut set = Self::default();
+        for prenullifier in prenullifiers {
+            if set.indices.contains_key(&prenullifier) {
+                continue;
+            }
+            let index = set.tree.leaf_count();
+            set.tree.insert(U256::from(index), prenullifier)?;
+            set.indices.insert(prenullifier, index);
+            set.prenullifiers.push(prenullifier);
+        }
+        Ok(set)
+    }
+
+    /// Read a set published with `save`. Fails if the published root doesn't match the
+    /// prenullifiers.
+    pub fn load(path: &Path) -> ContractResult<Self> {
+        let content = fs::read(path).map_err(|e| {
+            ShielderContractError::Other(format!(
+                "Failed to read association set from {path:?}: {e}"
+            ))
+        })?;
+        let file = serde_json::from_slice::<AssociationSetFile>(&content).map_err(|e| {
+            ShielderContractError::Other(format!("Failed to deserialize association set: {e}"))
+        })?;
+
+        let set = Self::new(file.prenullifiers)?;
+        if set.root() != file.root {
+            return Err(ShielderContractError::Other(format!(
+                "Association set root {} does not match the published root {}",
+                set.root(),
+                file.root
+            )));
+        }
+        Ok(set)
+    }
+
+    /// Persist the set to `path`.
+    pub fn save(&self, path: &Path) -> ContractResult<()> {
+        let serialized = serde_json::to_vec_pretty(&AssociationSetFile {
+            root: self.root(),
+            prenullifiers: self.prenullifiers.clone(),
+        })
+        .map_err(|e| {
+            ShielderContractError::Other(format!("Failed to serialize association set: {e}"))
+        })?;
+        fs::write(path, serialized).map_err(|e| {
+            ShielderContractError::Other(format!("Failed to save association set to {path:?}: {e}"))
+        })
+    }
+
+    /// Root of the set (`0` for an empty set, which is indistinguishable from claiming no set).
+    pub fn root(&self) -> U256 {
+        self.tree.root()
+    }
+
+    /// Number of accounts in the set.
+    pub fn len(&self) -> usize {
+        self.prenullifiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prenullifiers.is_empty()
+    }
+
+    pub fn contains(&self, prenullifier: U256) -> bool {
+        self.indices.contains_key(&prenullifier)
+    }
+
+    /// Merkle path of `prenullifier` together with the root of the set.
+    pub fn merkle_path(
+        &self,
+        prenullifier: U256,
+    ) -> ContractResult<(U256, [[U256; ARITY]; TREE_HEIGHT])> {
+        let index = self.indices.get(&prenullifier).ok_or_else(|| {
+            ShielderContractError::Other(format!(
+                "Prenullifier {prenullifier} is not in the association set"
+            ))
+        })?;
+        self.tree.merkle_path(U256::from(*index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+    use shielder_setup::shielder_circuits::{empty_association_path, Fr};
+    use type_conversions::field_to_u256;
+
+    use super::AssociationSet;
+
+    #[test]
+    fn duplicates_are_skipped() {
+        let set = AssociationSet::new([1, 2, 1, 3].map(U256::from)).unwrap();
+        let expected = AssociationSet::new([1, 2, 3].map(U256::from)).unwrap();
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.root(), expected.root());
+    }
+
+    #[test]
+    fn unknown_prenullifier_has_no_path() {
+        let set = AssociationSet::new([1, 2].map(U256::from)).unwrap();
+        assert!(set.merkle_path(U256::from(3)).is_err());
+    }
+
+    #[test]
+    fn singleton_path_matches_empty_association_path() {
+        let id = Fr::from(42);
+        let expected = empty_association_path(id).map(|level| level.map(field_to_u256));
+        let prenullifier = expected[0][0];
+
+        let set = AssociationSet::new([prenullifier]).unwrap();
+        let (_, path) = set.merkle_path(prenullifier).unwrap();
+
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn survives_save_and_load() {
+        let set = AssociationSet::new([5, 6, 7].map(U256::from)).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("association-set-{}.json", rand::random::<u64>()));
+
+        set.save(&path).unwrap();
+        let loaded = AssociationSet::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, set);
+    }
+}
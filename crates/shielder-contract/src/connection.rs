@@ -12,12 +12,13 @@ use crate::erc20::ERC20;
 #[cfg(not(feature = "erc20"))]
 use crate::ShielderContractError;
 use crate::{
-    call_type::CallType, providers::create_provider_with_signer, ContractResult, ShielderContract,
-    ShielderContractCall,
+    call_type::CallType,
+    providers::{create_provider_with_signer, RpcEndpoints},
+    ContractResult, ShielderContract, ShielderContractCall,
 };
 
 /// Placeholder for a provider in `ConnectionPolicy` / `Connection` and `ShielderUser` when only
-/// `ConnectionPolicy::OnDemand` variant is used.
+/// the `ConnectionPolicy::OnDemand` or `ConnectionPolicy::OnDemandFailover` variant is used.
 #[derive(Clone)]
 pub enum NoProvider {}
 impl Provider for NoProvider {
@@ -36,13 +37,19 @@ pub enum ConnectionPolicy<Provider = NoProvider> {
         rpc_url: String,
         signer: PrivateKeySigner,
     },
+    /// Like `OnDemand`, but over several RPC endpoints (see `FailoverTransport`).
+    OnDemandFailover {
+        rpc_endpoints: RpcEndpoints,
+        signer: PrivateKeySigner,
+    },
 }
 
 impl<P: Provider> ConnectionPolicy<P> {
     pub fn caller_address(&self) -> Address {
         match self {
             ConnectionPolicy::Keep { caller_address, .. } => *caller_address,
-            ConnectionPolicy::OnDemand { signer, .. } => signer.address(),
+            ConnectionPolicy::OnDemand { signer, .. }
+            | ConnectionPolicy::OnDemandFailover { signer, .. } => signer.address(),
         }
     }
 }
@@ -68,6 +75,10 @@ impl<P: Provider + Clone> Connection<P> {
         self.policy.caller_address()
     }
 
+    pub fn contract_address(&self) -> Address {
+        self.contract_address
+    }
+
     #[cfg(feature = "erc20")]
     pub async fn call_with_address<CT: CallType<Call>, Call: ShielderContractCall + Unpin>(
         &self,
@@ -126,6 +137,14 @@ impl<P: Provider + Clone> Connection<P> {
                 self.call_with_resolved_provider::<CT, _>(contract_address, call, value, provider)
                     .await
             }
+            ConnectionPolicy::OnDemandFailover {
+                rpc_endpoints,
+                signer,
+            } => {
+                let provider = rpc_endpoints.provider_with_signer(signer.clone()).await?;
+                self.call_with_resolved_provider::<CT, _>(contract_address, call, value, provider)
+                    .await
+            }
         }
     }
 
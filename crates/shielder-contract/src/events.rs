@@ -1,11 +1,17 @@
 use alloy_network::AnyNetwork;
 use alloy_primitives::{BlockHash, TxHash};
 use alloy_provider::Provider;
-use alloy_rpc_types::Filter;
+use alloy_rpc_types::{Filter, Log};
 use alloy_sol_types::SolEvent;
 use alloy_transport::BoxTransport;
 
-use crate::{ContractResult, ShielderContractError};
+use crate::{
+    ContractResult,
+    ShielderContract::{
+        Claim, Deposit, Migrate, NewAccount, ShielderContractEvents, Transfer, Withdraw,
+    },
+    ShielderContractError,
+};
 
 /// Look at the logs of `tx_hash` in `block_hash` and return the first event of type `Event`.
 pub async fn get_event<Event: SolEvent>(
@@ -13,8 +19,22 @@ pub async fn get_event<Event: SolEvent>(
     tx_hash: TxHash,
     block_hash: BlockHash,
 ) -> ContractResult<Event> {
+    get_events(provider, tx_hash, block_hash)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ShielderContractError::EventNotFound)
+}
+
+/// Look at the logs of `tx_hash` in `block_hash` and return all events of type `Event`, in the
+/// order of emission.
+pub async fn get_events<Event: SolEvent>(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    tx_hash: TxHash,
+    block_hash: BlockHash,
+) -> ContractResult<Vec<Event>> {
     let filter = Filter::new().at_block_hash(block_hash);
-    provider
+    Ok(provider
         .get_logs(&filter)
         .await
         .map_err(ShielderContractError::ProviderError)?
@@ -26,6 +46,34 @@ pub async fn get_event<Event: SolEvent>(
             let log_data = log.data().clone();
             Event::decode_log_data(&log_data, true).ok()
         })
-        .next()
-        .ok_or(ShielderContractError::EventNotFound)
+        .collect())
+}
+
+/// Decode a Shielder event from `log`. Returns `None` if the log is not a Shielder event.
+pub fn decode_shielder_event(log: &Log) -> ContractResult<Option<ShielderContractEvents>> {
+    let decoding_error =
+        |e: alloy_sol_types::Error| ShielderContractError::Other(format!("Invalid event: {e}"));
+
+    let event = match log.topic0() {
+        Some(&NewAccount::SIGNATURE_HASH) => ShielderContractEvents::NewAccount(
+            NewAccount::decode_log_data(log.data(), true).map_err(decoding_error)?,
+        ),
+        Some(&Deposit::SIGNATURE_HASH) => ShielderContractEvents::Deposit(
+            Deposit::decode_log_data(log.data(), true).map_err(decoding_error)?,
+        ),
+        Some(&Withdraw::SIGNATURE_HASH) => ShielderContractEvents::Withdraw(
+            Withdraw::decode_log_data(log.data(), true).map_err(decoding_error)?,
+        ),
+        Some(&Transfer::SIGNATURE_HASH) => ShielderContractEvents::Transfer(
+            Transfer::decode_log_data(log.data(), true).map_err(decoding_error)?,
+        ),
+        Some(&Claim::SIGNATURE_HASH) => ShielderContractEvents::Claim(
+            Claim::decode_log_data(log.data(), true).map_err(decoding_error)?,
+        ),
+        Some(&Migrate::SIGNATURE_HASH) => ShielderContractEvents::Migrate(
+            Migrate::decode_log_data(log.data(), true).map_err(decoding_error)?,
+        ),
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
 }
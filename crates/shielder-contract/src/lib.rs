@@ -1,25 +1,33 @@
+pub use admin::{AdminAction, ShielderAdmin};
 use alloy_contract::Error;
 pub use alloy_primitives;
 use alloy_primitives::{keccak256, Address, Bytes, TxHash, U256};
 use alloy_signer_local::LocalSignerError;
-use alloy_sol_types::SolValue;
+use alloy_sol_types::{SolInterface, SolValue};
 use alloy_transport::TransportError;
 pub use api::ShielderUser;
+pub use association_set::AssociationSet;
 pub use connection::{ConnectionPolicy, NoProvider};
+pub use reader::{ShielderReader, ShielderState};
 use shielder_setup::version::ContractVersion;
 use type_conversions::address_to_u256;
 pub use types::*;
 
+pub mod admin;
 mod api;
+pub mod association_set;
 pub mod call_type;
 mod connection;
 #[cfg(feature = "erc20")]
 pub mod erc20;
 pub mod events;
 pub mod merkle_path;
+pub mod note_tree;
 pub mod protocol_fee;
 pub mod providers;
+pub mod reader;
 pub mod recovery;
+pub mod token_list;
 mod types;
 
 /// Errors that can occur when interacting with the Shielder contract.
@@ -32,6 +40,10 @@ pub enum ShielderContractError {
     SignerConflict,
     #[error("Call failed: {0:?}")]
     CallError(Error),
+    #[error("Call reverted by the Shielder contract: {0:?}")]
+    Revert(ShielderContract::ShielderContractErrors),
+    #[error("Call reverted with unrecognized data: {0}")]
+    UnknownRevert(Bytes),
     #[error("Couldn't track the transaction")]
     WatchError,
     #[error("Event was not found for the provided transaction coordinates")]
@@ -43,16 +55,75 @@ pub enum ShielderContractError {
         version: ContractVersion,
         sdk_version: ContractVersion,
     },
+    #[error("Local note tree has {local} notes, but the contract has {contract}")]
+    NoteTreeOutOfSync { local: u64, contract: U256 },
+    #[error("Local note tree root {local} does not match the contract root {contract}")]
+    NoteTreeRootMismatch { local: U256, contract: U256 },
+    #[error("Token list root {local} computed from the contract token list does not match the contract root {contract}")]
+    TokenListRootMismatch { local: U256, contract: U256 },
+    #[error("Token {0} is not allowed by the Shielder contract")]
+    TokenNotAllowed(Address),
     #[error("Other error: {0}")]
     Other(String),
 }
 
+/// RPC error messages reported by nodes when the nonce of the signer has already been used.
+const NONCE_CONFLICT_MESSAGES: [&str; 3] = [
+    "nonce too low",
+    "transaction already imported",
+    "already known",
+];
+
+impl ShielderContractError {
+    /// Returns the decoded revert reason, if the call was reverted by the Shielder contract.
+    pub fn revert_reason(&self) -> Option<&ShielderContract::ShielderContractErrors> {
+        match self {
+            ShielderContractError::Revert(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// The nullifier has already been spent: the local account state is outdated and should be
+    /// re-synced with the chain.
+    pub fn is_duplicated_nullifier(&self) -> bool {
+        matches!(
+            self.revert_reason(),
+            Some(ShielderContract::ShielderContractErrors::DuplicatedNullifier(_))
+        )
+    }
+
+    /// The Merkle root used in the proof is unknown to the contract: the Merkle path should be
+    /// refreshed and the proof regenerated.
+    pub fn is_unknown_merkle_root(&self) -> bool {
+        matches!(
+            self.revert_reason(),
+            Some(ShielderContract::ShielderContractErrors::MerkleRootDoesNotExist(_))
+        )
+    }
+}
+
+/// Decode revert data returned by the Shielder contract.
+pub fn decode_revert_data(data: &[u8]) -> ShielderContractError {
+    match ShielderContract::ShielderContractErrors::abi_decode(data, true) {
+        Ok(reason) => ShielderContractError::Revert(reason),
+        Err(_) => ShielderContractError::UnknownRevert(Bytes::copy_from_slice(data)),
+    }
+}
+
 impl From<Error> for ShielderContractError {
     fn from(e: Error) -> Self {
-        let e_str = e.to_string();
-        if e_str.contains("nonce too low")
-            || e_str.contains("transaction already imported")
-            || e_str.contains("already known")
+        let Error::TransportError(transport_error) = &e else {
+            return ShielderContractError::CallError(e);
+        };
+        let Some(payload) = transport_error.as_error_resp() else {
+            return ShielderContractError::CallError(e);
+        };
+
+        if let Some(revert_data) = payload.as_revert_data() {
+            decode_revert_data(&revert_data)
+        } else if NONCE_CONFLICT_MESSAGES
+            .iter()
+            .any(|msg| payload.message.contains(msg))
         {
             ShielderContractError::SignerConflict
         } else {
@@ -141,16 +212,88 @@ impl DepositCommitment {
     }
 }
 
+pub struct TransferCommitment {
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    pub memo: Bytes,
+}
+
+impl TransferCommitment {
+    pub fn commitment_hash(&self) -> U256 {
+        // Same order as in contract
+        let hash: U256 = keccak256(
+            (
+                self.contract_version.to_bytes(),
+                self.chain_id,
+                self.memo.clone(),
+            )
+                .abi_encode_packed(),
+        )
+        .into();
+        // shifting right by 4 bits, same as in the contract
+        hash >> 4
+    }
+}
+
+pub struct ClaimCommitment {
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    pub memo: Bytes,
+}
+
+impl ClaimCommitment {
+    pub fn commitment_hash(&self) -> U256 {
+        // Same order as in contract
+        let hash: U256 = keccak256(
+            (
+                self.contract_version.to_bytes(),
+                self.chain_id,
+                self.memo.clone(),
+            )
+                .abi_encode_packed(),
+        )
+        .into();
+        // shifting right by 4 bits, same as in the contract
+        hash >> 4
+    }
+}
+
+pub struct MigrationCommitment {
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    pub memo: Bytes,
+}
+
+impl MigrationCommitment {
+    pub fn commitment_hash(&self) -> U256 {
+        // Same order as in contract
+        let hash: U256 = keccak256(
+            (
+                self.contract_version.to_bytes(),
+                self.chain_id,
+                self.memo.clone(),
+            )
+                .abi_encode_packed(),
+        )
+        .into();
+        // shifting right by 4 bits, same as in the contract
+        hash >> 4
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use alloy_primitives::{Address, Bytes, U256};
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_primitives::{hex, Address, Bytes, U256};
+    use alloy_sol_types::SolInterface;
+    use alloy_transport::RpcError;
     use halo2curves::ff::PrimeField;
     use rand::{thread_rng, Rng};
     use shielder_setup::version::ContractVersion;
 
-    use crate::WithdrawCommitment;
+    use crate::{ShielderContract::*, ShielderContractError, WithdrawCommitment};
 
     fn sample_commitment() -> WithdrawCommitment {
         let mut rng = thread_rng();
@@ -185,4 +328,59 @@ mod tests {
             );
         }
     }
+
+    fn rpc_error(message: &str, data: Option<&[u8]>) -> alloy_contract::Error {
+        let data = data
+            .map(|data| format!(r#","data":"{}""#, hex::encode_prefixed(data)))
+            .unwrap_or_default();
+        let payload: ErrorPayload =
+            serde_json::from_str(&format!(r#"{{"code":3,"message":"{message}"{data}}}"#)).unwrap();
+        alloy_contract::Error::TransportError(RpcError::ErrorResp(payload))
+    }
+
+    #[test]
+    fn shielder_revert_is_decoded() {
+        let data = ShielderContractErrors::DuplicatedNullifier(DuplicatedNullifier {}).abi_encode();
+        let error = ShielderContractError::from(rpc_error("execution reverted", Some(&data)));
+
+        assert!(error.is_duplicated_nullifier());
+        assert!(!error.is_unknown_merkle_root());
+    }
+
+    #[test]
+    fn revert_with_arguments_is_decoded() {
+        let reason = ShielderContractErrors::WrongContractVersion(WrongContractVersion {
+            actual: [0, 1, 2].into(),
+            expectedByCaller: [0, 1, 1].into(),
+        });
+        let error = ShielderContractError::from(rpc_error(
+            "execution reverted",
+            Some(&reason.abi_encode()),
+        ));
+
+        assert_eq!(error.revert_reason(), Some(&reason));
+    }
+
+    #[test]
+    fn foreign_revert_is_kept_raw() {
+        let data = [0xde, 0xad, 0xbe, 0xef];
+        let error = ShielderContractError::from(rpc_error("execution reverted", Some(&data)));
+
+        assert!(matches!(
+            error,
+            ShielderContractError::UnknownRevert(raw) if raw == Bytes::from(data)
+        ));
+    }
+
+    #[test]
+    fn nonce_conflict_is_detected() {
+        let error = ShielderContractError::from(rpc_error("nonce too low", None));
+        assert!(matches!(error, ShielderContractError::SignerConflict));
+    }
+
+    #[test]
+    fn other_rpc_errors_are_passed_through() {
+        let error = ShielderContractError::from(rpc_error("insufficient funds", None));
+        assert!(matches!(error, ShielderContractError::CallError(_)));
+    }
 }
@@ -0,0 +1,366 @@
+//! Local mirror of the Shielder note Merkle tree.
+//!
+//! Asking the node for `getMerklePath(leaf_index)` reveals to the RPC provider which note belongs
+//! to us. Instead, `NoteTree` is filled with the notes emitted in `NewAccount`, `Deposit`,
+//! `Withdraw`, `Transfer`, `Claim` and `Migrate` events and computes roots and paths locally,
+//! exactly as `MerkleTree.sol` does.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use alloy_network::AnyNetwork;
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::Filter;
+use alloy_transport::BoxTransport;
+use serde::{Deserialize, Serialize};
+use shielder_setup::{
+    consts::{ARITY, TREE_HEIGHT},
+    shielder_circuits::{poseidon::off_circuit::hash, Fr},
+};
+use type_conversions::{field_to_u256, u256_to_field};
+
+use crate::{
+    call_type::DryRun, events::decode_shielder_event, ContractResult, ShielderContractError,
+    ShielderUser,
+};
+
+/// Number of blocks queried for logs in a single `eth_getLogs` request.
+pub const LOGS_BATCH_SIZE: u64 = 10_000;
+
+/// Index of the root node. Nodes are numbered like in `MerkleTree.sol`: children of node `p` are
+/// `p * ARITY - (ARITY - 2) .. p * ARITY + 1`.
+const ROOT_ID: u64 = 1;
+
+/// Returns `(first_leaf_id, max_leaf_id)` for a tree of `TREE_HEIGHT`. Mirrors `treeBounds` from
+/// `MerkleTree.sol`.
+const fn tree_bounds() -> (u64, u64) {
+    let mut size = 1u64;
+    let mut power = 1u64;
+    let mut i = 0;
+    while i < TREE_HEIGHT {
+        power *= ARITY as u64;
+        size += power;
+        i += 1;
+    }
+    (size - power + 1, size)
+}
+
+const FIRST_LEAF_ID: u64 = tree_bounds().0;
+const MAX_LEAF_ID: u64 = tree_bounds().1;
+
+const fn parent(node_id: u64) -> u64 {
+    (node_id + ARITY as u64 - 2) / ARITY as u64
+}
+
+const fn first_child(node_id: u64) -> u64 {
+    node_id * ARITY as u64 - (ARITY as u64 - 2)
+}
+
+/// Incremental, sparse, `ARITY`-ary Poseidon Merkle tree of notes.
+///
+/// Only non-empty nodes are stored. Empty nodes are zero, as in the contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NoteTree {
+    /// The Shielder contract this tree mirrors.
+    contract_address: Address,
+    /// Non-empty nodes of the tree, indexed like in the contract.
+    nodes: BTreeMap<u64, U256>,
+    /// Number of leaves inserted so far.
+    leaf_count: u64,
+    /// First block that hasn't been scanned for events yet.
+    next_block: u64,
+}
+
+impl NoteTree {
+    /// Create an empty tree for the contract at `contract_address`. Event scanning will start at
+    /// `deployment_block`.
+    pub fn new(contract_address: Address, deployment_block: u64) -> Self {
+        Self {
+            contract_address,
+            next_block: deployment_block,
+            ..Default::default()
+        }
+    }
+
+    /// Read the tree from `path`. If the file doesn't exist or was created for a different
+    /// contract, a fresh tree is returned instead.
+    pub fn load_or_new(
+        path: &Path,
+        contract_address: Address,
+        deployment_block: u64,
+    ) -> ContractResult<Self> {
+        if !path.exists() {
+            return Ok(Self::new(contract_address, deployment_block));
+        }
+        let content = fs::read(path).map_err(|e| {
+            ShielderContractError::Other(format!("Failed to read note tree from {path:?}: {e}"))
+        })?;
+        let tree = serde_json::from_slice::<Self>(&content).map_err(|e| {
+            ShielderContractError::Other(format!("Failed to deserialize note tree: {e}"))
+        })?;
+
+        if tree.contract_address != contract_address {
+            tracing::warn!(
+                "Note tree at {path:?} belongs to a different contract, starting from scratch"
+            );
+            return Ok(Self::new(contract_address, deployment_block));
+        }
+        Ok(tree)
+    }
+
+    /// Persist the tree to `path`.
+    pub fn save(&self, path: &Path) -> ContractResult<()> {
+        let serialized = serde_json::to_vec(self).map_err(|e| {
+            ShielderContractError::Other(format!("Failed to serialize note tree: {e}"))
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                ShielderContractError::Other(format!("Failed to create {parent:?}: {e}"))
+            })?;
+        }
+        fs::write(path, serialized).map_err(|e| {
+            ShielderContractError::Other(format!("Failed to save note tree to {path:?}: {e}"))
+        })
+    }
+
+    /// Number of notes in the tree.
+    pub fn leaf_count(&self) -> u64 {
+        self.leaf_count
+    }
+
+    /// First block that hasn't been scanned for events yet.
+    pub fn next_block(&self) -> u64 {
+        self.next_block
+    }
+
+    /// Current root of the tree (`0` for an empty tree, like in the contract).
+    pub fn root(&self) -> U256 {
+        self.node(ROOT_ID)
+    }
+
+    /// Append `note` to the tree. `note_index` is the `newNoteIndex` reported by the contract and
+    /// must be the next free position. Re-inserting an already known note is a no-op.
+    pub fn insert(&mut self, note_index: U256, note: U256) -> ContractResult<()> {
+        let index = u64::try_from(note_index)
+            .map_err(|_| ShielderContractError::Other("Note index out of range".into()))?;
+
+        if index < self.leaf_count {
+            return match self.node(FIRST_LEAF_ID + index) == note {
+                true => Ok(()),
+                false => Err(ShielderContractError::Other(format!(
+                    "Conflicting note at index {index}"
+                ))),
+            };
+        }
+        if index > self.leaf_count {
+            return Err(ShielderContractError::Other(format!(
+                "Missing notes: expected index {}, got {index}",
+                self.leaf_count
+            )));
+        }
+
+        let mut node_id = FIRST_LEAF_ID + index;
+        if node_id > MAX_LEAF_ID {
+            return Err(ShielderContractError::Other("Note tree is full".into()));
+        }
+
+        self.set_node(node_id, note);
+        for _ in 0..TREE_HEIGHT {
+            node_id = parent(node_id);
+            let value = self.hash_children(node_id);
+            self.set_node(node_id, value);
+        }
+        self.leaf_count += 1;
+        Ok(())
+    }
+
+    /// Compute the Merkle path to the leaf at `leaf_index` together with the current root. The
+    /// result has the same shape as `merkle_path::get_current_merkle_path`.
+    pub fn merkle_path(
+        &self,
+        leaf_index: U256,
+    ) -> ContractResult<(U256, [[U256; ARITY]; TREE_HEIGHT])> {
+        let index = u64::try_from(leaf_index)
+            .ok()
+            .filter(|index| *index < self.leaf_count)
+            .ok_or(ShielderContractError::Other(format!(
+                "Leaf {leaf_index} is not present in the local note tree"
+            )))?;
+
+        let mut node_id = FIRST_LEAF_ID + index;
+        let mut path = [[U256::ZERO; ARITY]; TREE_HEIGHT];
+        for level in path.iter_mut() {
+            node_id = parent(node_id);
+            let first_child = first_child(node_id);
+            for (j, element) in level.iter_mut().enumerate() {
+                *element = self.node(first_child + j as u64);
+            }
+        }
+        Ok((self.root(), path))
+    }
+
+    /// Fetch all Shielder events from `next_block` up to the current chain head and insert the
+    /// notes they carry.
+    pub async fn sync(
+        &mut self,
+        provider: &impl Provider<BoxTransport, AnyNetwork>,
+    ) -> ContractResult<()> {
+        let current_height = provider
+            .get_block_number()
+            .await
+            .map_err(ShielderContractError::ProviderError)?;
+        let base_filter = Filter::new().address(self.contract_address);
+
+        while self.next_block <= current_height {
+            let last_batch_block = (self.next_block + LOGS_BATCH_SIZE - 1).min(current_height);
+            let filter = base_filter
+                .clone()
+                .from_block(self.next_block)
+                .to_block(last_batch_block);
+
+            let logs = provider
+                .get_logs(&filter)
+                .await
+                .map_err(ShielderContractError::ProviderError)?;
+            tracing::debug!(
+                "Found {} Shielder logs in blocks {} : {last_batch_block}",
+                logs.len(),
+                self.next_block
+            );
+
+            for log in logs {
+                if let Some(event) = decode_shielder_event(&log)? {
+                    for (index, note) in event.notes() {
+                        self.insert(index, note)?;
+                    }
+                }
+            }
+            self.next_block = last_batch_block + 1;
+        }
+        Ok(())
+    }
+
+    /// Compare the local root with the root stored in the contract. Both trees must contain the
+    /// same number of notes, so this should be called right after `sync`.
+    pub async fn check_root<P: Provider + Clone>(
+        &self,
+        shielder_user: &ShielderUser<P>,
+    ) -> ContractResult<()> {
+        let (contract_root, next_free_leaf_id, _, first_leaf_id) =
+            shielder_user.merkle_tree::<DryRun>().await?;
+        let contract_leaf_count = next_free_leaf_id - first_leaf_id;
+
+        if contract_leaf_count != U256::from(self.leaf_count) {
+            return Err(ShielderContractError::NoteTreeOutOfSync {
+                local: self.leaf_count,
+                contract: contract_leaf_count,
+            });
+        }
+        if contract_root != self.root() {
+            return Err(ShielderContractError::NoteTreeRootMismatch {
+                local: self.root(),
+                contract: contract_root,
+            });
+        }
+        Ok(())
+    }
+
+    fn node(&self, node_id: u64) -> U256 {
+        self.nodes.get(&node_id).copied().unwrap_or_default()
+    }
+
+    fn set_node(&mut self, node_id: u64, value: U256) {
+        self.nodes.insert(node_id, value);
+    }
+
+    fn hash_children(&self, node_id: u64) -> U256 {
+        let first_child = first_child(node_id);
+        let children: [Fr; ARITY] =
+            core::array::from_fn(|j| u256_to_field(self.node(first_child + j as u64)));
+        field_to_u256(hash(&children))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use shielder_setup::{
+        consts::{ARITY, TREE_HEIGHT},
+        shielder_circuits::{poseidon::off_circuit::hash, Fr},
+    };
+    use type_conversions::{field_to_u256, u256_to_field};
+
+    use super::{NoteTree, FIRST_LEAF_ID};
+
+    fn hash_level(level: &[U256; ARITY]) -> U256 {
+        let level: [Fr; ARITY] = core::array::from_fn(|j| u256_to_field(level[j]));
+        field_to_u256(hash(&level))
+    }
+
+    fn tree_with_notes(count: u64) -> NoteTree {
+        let mut tree = NoteTree::new(Address::ZERO, 0);
+        for i in 0..count {
+            tree.insert(U256::from(i), U256::from(1000 + i)).unwrap();
+        }
+        tree
+    }
+
+    #[test]
+    fn first_leaf_id_matches_contract_layout() {
+        // sum of 7^i for i in 0..=13, minus 7^13, plus 1
+        assert_eq!(FIRST_LEAF_ID, 16_148_168_402);
+    }
+
+    #[test]
+    fn empty_tree_has_zero_root() {
+        assert_eq!(NoteTree::default().root(), U256::ZERO);
+    }
+
+    #[test]
+    fn paths_are_consistent_with_root() {
+        let tree = tree_with_notes(20);
+
+        for leaf in 0..20u64 {
+            let (root, path) = tree.merkle_path(U256::from(leaf)).unwrap();
+            assert_eq!(root, tree.root());
+            assert_eq!(path[0][leaf as usize % ARITY], U256::from(1000 + leaf));
+            for level in 1..TREE_HEIGHT {
+                assert!(path[level].contains(&hash_level(&path[level - 1])));
+            }
+            assert_eq!(hash_level(&path[TREE_HEIGHT - 1]), root);
+        }
+    }
+
+    #[test]
+    fn reinserting_known_note_is_noop() {
+        let mut tree = tree_with_notes(3);
+        let root = tree.root();
+
+        tree.insert(U256::from(1), U256::from(1001)).unwrap();
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.leaf_count(), 3);
+    }
+
+    #[test]
+    fn rejects_gaps_and_conflicts() {
+        let mut tree = tree_with_notes(3);
+
+        assert!(tree.insert(U256::from(5), U256::from(1)).is_err());
+        assert!(tree.insert(U256::from(1), U256::from(1)).is_err());
+    }
+
+    #[test]
+    fn unknown_leaf_has_no_path() {
+        assert!(tree_with_notes(3).merkle_path(U256::from(3)).is_err());
+    }
+
+    #[test]
+    fn survives_serialization() {
+        let tree = tree_with_notes(10);
+        let serialized = serde_json::to_vec(&tree).unwrap();
+        assert_eq!(
+            tree,
+            serde_json::from_slice::<NoteTree>(&serialized).unwrap()
+        );
+    }
+}
@@ -0,0 +1,803 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use alloy_json_rpc::{
+    Id, Request, RequestPacket, ResponsePacket, ResponsePayload, SerializedRequest,
+};
+use alloy_primitives::{hex, U64};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::SolCall;
+use alloy_transport::{BoxTransport, TransportError, TransportErrorKind, TransportFut};
+use futures::future::join_all;
+use serde_json::Value;
+use tower::Service;
+
+use crate::{
+    reader::Multicall3::aggregate3Call,
+    ContractResult,
+    ShielderContract::{getMerklePathCall, merkleRootExistsCall, nullifiersCall},
+    ShielderContractError,
+};
+
+/// Configuration of `FailoverTransport`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailoverConfig {
+    /// How long an endpoint is skipped after a failed request.
+    pub ejection_period: Duration,
+    /// Number of endpoints that must return the same result of a quorum read. `None` disables
+    /// quorum reads: they are served by a single endpoint, like any other read.
+    pub quorum: Option<usize>,
+    /// Selectors of the `eth_call`s that are quorum reads, also when called through Multicall3
+    /// `aggregate3`. By default: Merkle paths, nullifiers and Merkle root existence.
+    pub quorum_selectors: Vec<[u8; 4]>,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            ejection_period: Duration::from_secs(30),
+            quorum: None,
+            quorum_selectors: vec![
+                getMerklePathCall::SELECTOR,
+                nullifiersCall::SELECTOR,
+                merkleRootExistsCall::SELECTOR,
+            ],
+        }
+    }
+}
+
+impl FailoverConfig {
+    /// Check that the quorum can be reached by `endpoints` endpoints.
+    pub fn check(&self, endpoints: usize) -> ContractResult<()> {
+        match self.quorum {
+            Some(quorum) if quorum == 0 || quorum > endpoints => {
+                Err(ShielderContractError::Other(format!(
+                    "RPC quorum must be between 1 and the number of RPC URLs ({endpoints}), got \
+                     {quorum}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    transport: BoxTransport,
+    ejected_until: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn is_healthy(&self) -> bool {
+        self.ejected_until
+            .lock()
+            .expect("lock should not be poisoned")
+            .is_none_or(|until| Instant::now() >= until)
+    }
+
+    fn eject(&self, period: Duration, error: &TransportError) {
+        tracing::warn!(url = %self.url, ?error, "RPC endpoint failed, ejecting it");
+        *self
+            .ejected_until
+            .lock()
+            .expect("lock should not be poisoned") = Some(Instant::now() + period);
+    }
+
+    fn restore(&self) {
+        *self
+            .ejected_until
+            .lock()
+            .expect("lock should not be poisoned") = None;
+    }
+
+    async fn send(&self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        self.transport.clone().call(request).await
+    }
+}
+
+/// How a request is dispatched to the endpoints.
+enum Dispatch {
+    /// Send to one endpoint, failing over to the next ones.
+    RoundRobin,
+    /// Send to the endpoints until enough of them agree on the result.
+    Quorum(usize),
+    /// Send to all endpoints.
+    Broadcast,
+}
+
+/// JSON-RPC transport over several RPC endpoints of the same chain.
+///
+/// - Reads are sent to the endpoints in turns. An endpoint that fails (i.e. doesn't return a
+///   JSON-RPC response at all) is skipped for `FailoverConfig::ejection_period`, and the request
+///   is retried with the next endpoint.
+/// - If `FailoverConfig::quorum` is set, quorum reads (see `FailoverConfig::quorum_selectors`) are
+///   sent to all healthy endpoints, and fail unless enough of them return the same result. Their
+///   `latest` block is first pinned to a block number that enough endpoints have reached, so that
+///   endpoints a block apart still agree. A JSON-RPC batch is a quorum read if any of its requests
+///   is.
+/// - Signed transactions (`eth_sendRawTransaction`) are broadcast to all endpoints.
+#[derive(Clone, Debug)]
+pub struct FailoverTransport {
+    endpoints: Arc<Vec<Endpoint>>,
+    config: Arc<FailoverConfig>,
+    next: Arc<AtomicUsize>,
+}
+
+impl FailoverTransport {
+    /// Connect to all of `rpc_urls`.
+    pub async fn connect(rpc_urls: &[String], config: FailoverConfig) -> ContractResult<Self> {
+        if rpc_urls.is_empty() {
+            return Err("At least one RPC URL is required".into());
+        }
+        config.check(rpc_urls.len())?;
+
+        let mut endpoints = Vec::with_capacity(rpc_urls.len());
+        for url in rpc_urls {
+            let provider = ProviderBuilder::new()
+                .on_builtin(url)
+                .await
+                .map_err(ShielderContractError::ProviderError)?;
+            endpoints.push(Endpoint {
+                url: url.clone(),
+                transport: provider.client().transport().clone(),
+                ejected_until: Mutex::new(None),
+            });
+        }
+
+        Ok(Self {
+            endpoints: Arc::new(endpoints),
+            config: Arc::new(config),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// URLs of the endpoints that are not ejected at the moment.
+    pub fn healthy_endpoints(&self) -> Vec<String> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_healthy())
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    async fn dispatch(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        match self.dispatch_kind(&request) {
+            Dispatch::RoundRobin => self.round_robin(request).await,
+            Dispatch::Quorum(quorum) => self.quorum(request, quorum).await,
+            Dispatch::Broadcast => self.broadcast(request).await,
+        }
+    }
+
+    fn dispatch_kind(&self, request: &RequestPacket) -> Dispatch {
+        let requests = match request {
+            RequestPacket::Single(request) => std::slice::from_ref(request),
+            RequestPacket::Batch(requests) => requests.as_slice(),
+        };
+        if requests
+            .iter()
+            .any(|request| request.method() == "eth_sendRawTransaction")
+        {
+            return Dispatch::Broadcast;
+        }
+        match self.config.quorum {
+            Some(quorum) if requests.iter().any(|request| self.is_quorum_read(request)) => {
+                Dispatch::Quorum(quorum)
+            }
+            _ => Dispatch::RoundRobin,
+        }
+    }
+
+    fn is_quorum_read(&self, request: &SerializedRequest) -> bool {
+        request.method() == "eth_call"
+            && call_selectors(request)
+                .iter()
+                .any(|selector| self.config.quorum_selectors.contains(selector))
+    }
+
+    /// Endpoints in the order in which they should be tried: healthy ones first, starting from the
+    /// next one in turn, then the ejected ones as a last resort.
+    fn rotation(&self) -> Vec<&Endpoint> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let (healthy, ejected): (Vec<_>, Vec<_>) = (0..self.endpoints.len())
+            .map(|i| &self.endpoints[(start + i) % self.endpoints.len()])
+            .partition(|endpoint| endpoint.is_healthy());
+        healthy.into_iter().chain(ejected).collect()
+    }
+
+    async fn round_robin(&self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let mut last_error = None;
+        for endpoint in self.rotation() {
+            match endpoint.send(request.clone()).await {
+                Ok(response) => {
+                    endpoint.restore();
+                    return Ok(response);
+                }
+                Err(error) => {
+                    endpoint.eject(self.config.ejection_period, &error);
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.expect("there is at least one endpoint"))
+    }
+
+    async fn quorum(
+        &self,
+        request: RequestPacket,
+        quorum: usize,
+    ) -> Result<ResponsePacket, TransportError> {
+        let mut endpoints = self.rotation();
+        let healthy = endpoints.iter().filter(|e| e.is_healthy()).count();
+        endpoints.truncate(healthy.max(quorum));
+
+        let request = self.pin_block(request, &endpoints, quorum).await?;
+        let responses = join_all(
+            endpoints
+                .iter()
+                .map(|endpoint| endpoint.send(request.clone())),
+        )
+        .await;
+
+        // Responses grouped by their result, with the number of endpoints that returned it.
+        let mut votes: Vec<(String, ResponsePacket, usize)> = vec![];
+        for (endpoint, response) in endpoints.iter().zip(responses) {
+            match response {
+                Ok(response) => {
+                    endpoint.restore();
+                    let key = response_key(&response);
+                    match votes.iter_mut().find(|(k, _, _)| *k == key) {
+                        Some((_, _, count)) => *count += 1,
+                        None => votes.push((key, response, 1)),
+                    }
+                }
+                Err(error) => endpoint.eject(self.config.ejection_period, &error),
+            }
+        }
+
+        match votes.into_iter().find(|(_, _, count)| *count >= quorum) {
+            Some((_, response, _)) => Ok(response),
+            None => Err(TransportErrorKind::custom_str(&format!(
+                "Fewer than {quorum} RPC endpoints agreed on the result"
+            ))),
+        }
+    }
+
+    /// Replace the `latest` block of the `eth_call`s in `request` with the highest block number
+    /// that at least `quorum` of `endpoints` have reached. Lagging endpoints then fail to answer
+    /// instead of outvoting the others with stale state.
+    async fn pin_block(
+        &self,
+        request: RequestPacket,
+        endpoints: &[&Endpoint],
+        quorum: usize,
+    ) -> Result<RequestPacket, TransportError> {
+        let block_number_request = RequestPacket::Single(
+            Request::new("eth_blockNumber", Id::Number(0), ())
+                .serialize()
+                .map_err(TransportError::ser_err)?,
+        );
+        let responses = join_all(
+            endpoints
+                .iter()
+                .map(|endpoint| endpoint.send(block_number_request.clone())),
+        )
+        .await;
+
+        let mut block_numbers = vec![];
+        for (endpoint, response) in endpoints.iter().zip(responses) {
+            match response {
+                Ok(response) => block_numbers.extend(block_number(&response)),
+                Err(error) => endpoint.eject(self.config.ejection_period, &error),
+            }
+        }
+        block_numbers.sort_unstable_by(|a, b| b.cmp(a));
+        let Some(block_number) = block_numbers.get(quorum.saturating_sub(1)) else {
+            return Err(TransportErrorKind::custom_str(&format!(
+                "Fewer than {quorum} RPC endpoints reported their block number"
+            )));
+        };
+
+        let block = format!("{block_number:#x}");
+        match request {
+            RequestPacket::Single(request) => {
+                Ok(RequestPacket::Single(pin_call_block(request, &block)?))
+            }
+            RequestPacket::Batch(requests) => Ok(RequestPacket::Batch(
+                requests
+                    .into_iter()
+                    .map(|request| pin_call_block(request, &block))
+                    .collect::<Result<_, _>>()?,
+            )),
+        }
+    }
+
+    async fn broadcast(&self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let endpoints = self.rotation();
+        let responses = join_all(
+            endpoints
+                .iter()
+                .map(|endpoint| endpoint.send(request.clone())),
+        )
+        .await;
+
+        // A successful response is preferred, as other endpoints may have already received the
+        // transaction from the network and report it as known.
+        let mut success = None;
+        let mut error_response = None;
+        let mut last_error = None;
+        for (endpoint, response) in endpoints.iter().zip(responses) {
+            match response {
+                Ok(response) => {
+                    endpoint.restore();
+                    match is_success(&response) {
+                        true => success.get_or_insert(response),
+                        false => error_response.get_or_insert(response),
+                    };
+                }
+                Err(error) => {
+                    endpoint.eject(self.config.ejection_period, &error);
+                    last_error = Some(error);
+                }
+            }
+        }
+        success
+            .or(error_response)
+            .ok_or_else(|| last_error.expect("there is at least one endpoint"))
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().dispatch(request))
+    }
+}
+
+/// Selectors of the functions called by an `eth_call` request: the called one and, for Multicall3
+/// `aggregate3`, the aggregated ones.
+fn call_selectors(request: &SerializedRequest) -> Vec<[u8; 4]> {
+    let Some(input) = call_input(request) else {
+        return vec![];
+    };
+    let Some(selector) = selector_of(&input) else {
+        return vec![];
+    };
+
+    let mut selectors = vec![selector];
+    if selector == aggregate3Call::SELECTOR {
+        if let Ok(aggregate) = aggregate3Call::abi_decode(&input, true) {
+            selectors.extend(
+                aggregate
+                    .calls
+                    .iter()
+                    .filter_map(|call| selector_of(&call.callData)),
+            );
+        }
+    }
+    selectors
+}
+
+/// Calldata of an `eth_call` request.
+fn call_input(request: &SerializedRequest) -> Option<Vec<u8>> {
+    let params: Vec<Value> = serde_json::from_str(request.params()?.get()).ok()?;
+    let call = params.first()?;
+    let input = call.get("input").or_else(|| call.get("data"))?.as_str()?;
+    hex::decode(input.strip_prefix("0x")?).ok()
+}
+
+fn selector_of(calldata: &[u8]) -> Option<[u8; 4]> {
+    calldata.get(..4)?.try_into().ok()
+}
+
+/// Pin an `eth_call` at the `latest` block (explicitly or by default) to `block`. Other requests
+/// are returned unchanged.
+fn pin_call_block(
+    request: SerializedRequest,
+    block: &str,
+) -> Result<SerializedRequest, TransportError> {
+    if request.method() != "eth_call" {
+        return Ok(request);
+    }
+    let Some(mut params) = request
+        .params()
+        .and_then(|params| serde_json::from_str::<Vec<Value>>(params.get()).ok())
+    else {
+        return Ok(request);
+    };
+
+    let at_latest = match params.as_slice() {
+        [_] => true,
+        [_, Value::String(tag), ..] => tag == "latest",
+        _ => false,
+    };
+    if !at_latest {
+        return Ok(request);
+    }
+    params.truncate(1);
+    params.push(block.into());
+
+    Request::new(request.method().to_string(), request.id().clone(), params)
+        .serialize()
+        .map_err(TransportError::ser_err)
+}
+
+/// Block number returned by `eth_blockNumber`.
+fn block_number(response: &ResponsePacket) -> Option<u64> {
+    let ResponsePacket::Single(response) = response else {
+        return None;
+    };
+    let ResponsePayload::Success(result) = &response.payload else {
+        return None;
+    };
+    serde_json::from_str::<U64>(result.get())
+        .ok()
+        .map(|block_number| block_number.to())
+}
+
+fn response_key(response: &ResponsePacket) -> String {
+    match response {
+        ResponsePacket::Single(response) => payload_key(&response.payload),
+        ResponsePacket::Batch(responses) => {
+            // Endpoints may answer the requests of a batch in any order.
+            let mut keys = responses
+                .iter()
+                .map(|response| format!("{:?}:{}", response.id, payload_key(&response.payload)))
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys.join(",")
+        }
+    }
+}
+
+fn payload_key(payload: &ResponsePayload) -> String {
+    match payload {
+        ResponsePayload::Success(result) => format!("result:{}", result.get()),
+        ResponsePayload::Failure(error) => format!("error:{}:{}", error.code, error.message),
+    }
+}
+
+fn is_success(response: &ResponsePacket) -> bool {
+    match response {
+        ResponsePacket::Single(response) => {
+            matches!(response.payload, ResponsePayload::Success(_))
+        }
+        ResponsePacket::Batch(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    };
+
+    use alloy_primitives::{hex, Address, Bytes, U256};
+    use alloy_provider::{Provider, ProviderBuilder};
+    use alloy_rpc_client::RpcClient;
+    use alloy_sol_types::SolCall;
+    use alloy_transport::{Transport, TransportResult};
+    use axum::{
+        extract::State,
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        routing::post,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+
+    use super::{FailoverConfig, FailoverTransport};
+    use crate::{
+        providers::{create_failover_provider, RpcEndpoints},
+        reader::Multicall3::{aggregate3Call, Call3Result},
+        ShielderContract::nullifiersCall,
+        ShielderReader,
+    };
+
+    const TX_HASH: &str = "0x0101010101010101010101010101010101010101010101010101010101010101";
+
+    /// JSON-RPC node at block `block_number`, answering `eth_blockNumber`, `eth_getCode`,
+    /// `eth_call` (always with `call_result`, also for every call aggregated by Multicall3) and
+    /// `eth_sendRawTransaction`.
+    struct MockNode {
+        call_result: U256,
+        block_number: u64,
+        down: AtomicBool,
+        methods: Mutex<Vec<String>>,
+        /// Block parameter of every `eth_call`.
+        call_blocks: Mutex<Vec<Value>>,
+    }
+
+    impl MockNode {
+        fn requests(&self, method: &str) -> usize {
+            let methods = self.methods.lock().unwrap();
+            methods.iter().filter(|m| *m == method).count()
+        }
+
+        fn answer(&self, request: &Value) -> Value {
+            let method = request["method"].as_str().unwrap().to_string();
+            self.methods.lock().unwrap().push(method.clone());
+
+            let result = match method.as_str() {
+                "eth_blockNumber" => json!(format!("{:#x}", self.block_number)),
+                "eth_getCode" => json!("0x01"),
+                "eth_call" => {
+                    let params = &request["params"];
+                    self.call_blocks.lock().unwrap().push(params[1].clone());
+                    json!(hex::encode_prefixed(self.call(&params[0])))
+                }
+                "eth_sendRawTransaction" => json!(TX_HASH),
+                _ => panic!("Unexpected method {method}"),
+            };
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        }
+
+        fn call(&self, call: &Value) -> Vec<u8> {
+            let input = call.get("input").or_else(|| call.get("data")).unwrap();
+            let input = hex::decode(input.as_str().unwrap()).unwrap();
+            let result = self.call_result.to_be_bytes::<32>().to_vec();
+            match aggregate3Call::abi_decode(&input, true) {
+                Ok(aggregate) => {
+                    let results = aggregate
+                        .calls
+                        .iter()
+                        .map(|_| Call3Result {
+                            success: true,
+                            returnData: result.clone().into(),
+                        })
+                        .collect::<Vec<_>>();
+                    aggregate3Call::abi_encode_returns(&(results,))
+                }
+                Err(_) => result,
+            }
+        }
+    }
+
+    async fn handle(State(node): State<Arc<MockNode>>, Json(request): Json<Value>) -> Response {
+        let response = match &request {
+            Value::Array(requests) => requests.iter().map(|r| node.answer(r)).collect(),
+            request => node.answer(request),
+        };
+        if node.down.load(Ordering::Relaxed) {
+            return StatusCode::BAD_GATEWAY.into_response();
+        }
+        Json(response).into_response()
+    }
+
+    async fn start_mock_node(
+        call_result: u64,
+        block_number: u64,
+        down: bool,
+    ) -> (Arc<MockNode>, String) {
+        let node = Arc::new(MockNode {
+            call_result: U256::from(call_result),
+            block_number,
+            down: AtomicBool::new(down),
+            methods: Mutex::new(vec![]),
+            call_blocks: Mutex::new(vec![]),
+        });
+        let app = Router::new()
+            .route("/", post(handle))
+            .with_state(node.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (node, url)
+    }
+
+    /// Nodes answering every `eth_call` with the respective `call_results`, all at block 1.
+    async fn start_mock_nodes(call_results: &[u64]) -> (Vec<Arc<MockNode>>, Vec<String>) {
+        let mut nodes = vec![];
+        let mut urls = vec![];
+        for &call_result in call_results {
+            let (node, url) = start_mock_node(call_result, 1, false).await;
+            nodes.push(node);
+            urls.push(url);
+        }
+        (nodes, urls)
+    }
+
+    fn quorum_config(quorum: usize) -> FailoverConfig {
+        FailoverConfig {
+            quorum: Some(quorum),
+            ..Default::default()
+        }
+    }
+
+    /// Read two nullifiers in a single JSON-RPC batch.
+    async fn read_nullifiers_in_batch(
+        urls: &[String],
+        quorum: usize,
+    ) -> TransportResult<Vec<Bytes>> {
+        let transport = FailoverTransport::connect(urls, quorum_config(quorum))
+            .await
+            .unwrap();
+        let client = RpcClient::new(transport.boxed(), false);
+        let shielder = Address::random();
+
+        let mut batch = client.new_batch();
+        let mut waiters = vec![];
+        for nullifier_hash in [1, 2] {
+            let call = nullifiersCall {
+                nullifierHash: U256::from(nullifier_hash),
+            };
+            let call = json!({"to": shielder, "input": hex::encode_prefixed(call.abi_encode())});
+            waiters.push(batch.add_call::<_, Bytes>("eth_call", &(call, "latest"))?);
+        }
+        batch.send().await?;
+
+        let mut results = vec![];
+        for waiter in waiters {
+            results.push(waiter.await?);
+        }
+        Ok(results)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reads_are_spread_over_endpoints() {
+        let (first, first_url) = start_mock_node(0, 1, false).await;
+        let (second, second_url) = start_mock_node(0, 1, false).await;
+        let provider =
+            create_failover_provider(&[first_url, second_url], FailoverConfig::default())
+                .await
+                .unwrap();
+
+        for _ in 0..4 {
+            assert_eq!(provider.get_block_number().await.unwrap(), 1);
+        }
+        assert_eq!(first.requests("eth_blockNumber"), 2);
+        assert_eq!(second.requests("eth_blockNumber"), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failing_endpoint_is_ejected() {
+        let (failing, failing_url) = start_mock_node(0, 1, true).await;
+        let (healthy, healthy_url) = start_mock_node(0, 1, false).await;
+        let transport = FailoverTransport::connect(
+            &[failing_url, healthy_url.clone()],
+            FailoverConfig::default(),
+        )
+        .await
+        .unwrap();
+        let provider =
+            ProviderBuilder::new().on_client(RpcClient::new(transport.clone().boxed(), false));
+
+        for _ in 0..3 {
+            assert_eq!(provider.get_block_number().await.unwrap(), 1);
+        }
+        assert_eq!(failing.requests("eth_blockNumber"), 1);
+        assert_eq!(healthy.requests("eth_blockNumber"), 3);
+        assert_eq!(transport.healthy_endpoints(), vec![healthy_url]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn quorum_reads_need_agreeing_endpoints() {
+        let (_, urls) = start_mock_nodes(&[0, 0, 5]).await;
+        let shielder = Address::random();
+
+        let provider = create_failover_provider(&urls, quorum_config(2))
+            .await
+            .unwrap();
+        let reader = ShielderReader::new(shielder, provider);
+        assert_eq!(reader.nullifiers(U256::from(1)).await.unwrap(), U256::ZERO);
+
+        let provider = create_failover_provider(&urls, quorum_config(3))
+            .await
+            .unwrap();
+        let reader = ShielderReader::new(shielder, provider);
+        assert!(reader.nullifiers(U256::from(1)).await.is_err());
+        // Other reads don't need a quorum.
+        assert!(reader.token_list_root().await.is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreachable_quorum_is_rejected() {
+        let (_, urls) = start_mock_nodes(&[0, 0]).await;
+
+        for quorum in [0, 3] {
+            assert!(FailoverTransport::connect(&urls, quorum_config(quorum))
+                .await
+                .is_err());
+        }
+        assert!(FailoverTransport::connect(&urls, quorum_config(2))
+            .await
+            .is_ok());
+
+        let [node, fallback] = [urls[0].clone(), urls[1].clone()];
+        assert!(RpcEndpoints::new(node.clone(), vec![fallback.clone()], Some(3)).is_err());
+        assert!(RpcEndpoints::new(node.clone(), vec![], Some(0)).is_err());
+        assert!(RpcEndpoints::new(node, vec![fallback], Some(2)).is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn quorum_covers_multicall_reads() {
+        let (nodes, urls) = start_mock_nodes(&[0, 0, 5]).await;
+        let shielder = Address::random();
+        let nullifier_hashes = [U256::from(1), U256::from(2)];
+
+        let provider = create_failover_provider(&urls, quorum_config(2))
+            .await
+            .unwrap();
+        let reader = ShielderReader::new(shielder, provider);
+        assert_eq!(
+            reader.nullifiers_batch(&nullifier_hashes).await.unwrap(),
+            vec![U256::ZERO; 2]
+        );
+        // Both nullifiers were read with a single `aggregate3` call, sent to every endpoint.
+        for node in &nodes {
+            assert_eq!(node.requests("eth_call"), 1);
+        }
+
+        let provider = create_failover_provider(&urls, quorum_config(3))
+            .await
+            .unwrap();
+        let reader = ShielderReader::new(shielder, provider);
+        assert!(reader.nullifiers_batch(&nullifier_hashes).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn quorum_covers_batch_requests() {
+        let (_, urls) = start_mock_nodes(&[0, 0, 5]).await;
+
+        let results = read_nullifiers_in_batch(&urls, 2).await.unwrap();
+        assert_eq!(results, vec![Bytes::from([0; 32]); 2]);
+
+        assert!(read_nullifiers_in_batch(&urls, 3).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn quorum_reads_are_pinned_to_a_common_block() {
+        let mut nodes = vec![];
+        let mut urls = vec![];
+        for block_number in [10, 12, 11] {
+            let (node, url) = start_mock_node(0, block_number, false).await;
+            nodes.push(node);
+            urls.push(url);
+        }
+
+        let provider = create_failover_provider(&urls, quorum_config(2))
+            .await
+            .unwrap();
+        let reader = ShielderReader::new(Address::random(), provider);
+        reader.nullifiers(U256::from(1)).await.unwrap();
+
+        // The highest block reached by two endpoints.
+        for node in nodes {
+            assert_eq!(*node.call_blocks.lock().unwrap(), vec![json!("0xb")]);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn transactions_are_broadcast() {
+        let (first, first_url) = start_mock_node(0, 1, false).await;
+        let (second, second_url) = start_mock_node(0, 1, true).await;
+        let (third, third_url) = start_mock_node(0, 1, false).await;
+        let provider = create_failover_provider(
+            &[first_url, second_url, third_url],
+            FailoverConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        let pending = provider.send_raw_transaction(&[0xc0]).await.unwrap();
+        assert_eq!(pending.tx_hash().to_string(), TX_HASH);
+        for node in [first, second, third] {
+            assert_eq!(node.requests("eth_sendRawTransaction"), 1);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use alloy_network::{AnyNetwork, Ethereum, EthereumWallet, Network};
+use alloy_provider::{
+    fillers::{
+        BlobGasFiller, CachedNonceManager, ChainIdFiller, FillerControlFlow, GasFiller,
+        NonceFiller, TxFiller, WalletFiller,
+    },
+    Provider, ProviderBuilder, RootProvider, SendableTx,
+};
+use alloy_rpc_client::RpcClient;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_transport::{BoxTransport, Transport, TransportResult};
+pub use failover::{FailoverConfig, FailoverTransport};
+
+use crate::{ContractResult, ShielderContractError};
+
+mod failover;
+
+/// Creates a provider for the given RPC URL.
+///
+/// This is a simple provider, without any fillers or
+/// signer configuration (apart from some devnet-specific defaults). It is suitable for doing
+/// read-only operations.
+pub async fn create_simple_provider(
+    rpc_url: &str,
+) -> ContractResult<impl Provider<BoxTransport, AnyNetwork>> {
+    ProviderBuilder::new()
+        .network::<AnyNetwork>()
+        .on_builtin(rpc_url)
+        .await
+        .map_err(ShielderContractError::ProviderError)
+}
+
+/// Creates a provider for the given RPC URL, with the given signer. This provider is suitable for
+/// doing write operations, as it will sign transactions with the given signer.
+///
+/// Note: The signer will fetch the nonce before every transaction. Use this if the same signer
+/// might be used from multiple places (although bare in mind that nonce conflicts might still
+/// occur).
+pub async fn create_provider_with_signer(
+    rpc_url: &str,
+    signer: PrivateKeySigner,
+) -> ContractResult<impl Provider + Clone> {
+    ProviderBuilder::new()
+        .with_recommended_fillers()
+        .filler(WalletFiller::new(EthereumWallet::from(signer)))
+        .on_builtin(rpc_url)
+        .await
+        .map_err(ShielderContractError::ProviderError)
+}
+
+/// Creates a provider for the given RPC URL, with the given signer. This provider is suitable for
+/// doing write operations, as it will sign transactions with the given signer.
+///
+/// Note: The signer will locally track the nonce and cache it. Use this if the signer is only used
+/// from one place.
+pub async fn create_provider_with_nonce_caching_signer(
+    rpc_url: &str,
+    signer: PrivateKeySigner,
+) -> ContractResult<impl Provider + Clone> {
+    ProviderBuilder::new()
+        // The four fillers below are the recommended fillers are the same as
+        // `with_recommended_fillers` except the `NonceManager` parameter for `NonceFiller`.
+        .filler(GasFiller)
+        .filler(BlobGasFiller)
+        .filler(NonceFiller::<CachedNonceManager>::default())
+        .filler(ChainIdFiller::default())
+        .filler(WalletFiller::new(EthereumWallet::from(signer)))
+        .filler(LoggingFiller::default())
+        .on_builtin(rpc_url)
+        .await
+        .map_err(ShielderContractError::ProviderError)
+        .map(Arc::new)
+}
+
+/// Creates a provider over several RPC URLs of the same chain, which fails over between them (see
+/// `FailoverTransport`). It is suitable for doing read-only operations.
+pub async fn create_failover_provider(
+    rpc_urls: &[String],
+    config: FailoverConfig,
+) -> ContractResult<RootProvider<BoxTransport>> {
+    let transport = FailoverTransport::connect(rpc_urls, config).await?;
+    Ok(ProviderBuilder::new().on_client(RpcClient::new(transport.boxed(), false)))
+}
+
+/// Creates a provider over several RPC URLs of the same chain, with the given signer. Transactions
+/// signed with it are broadcast to all the endpoints (see `FailoverTransport`).
+///
+/// Note: The signer will fetch the nonce before every transaction, as in
+/// `create_provider_with_signer`.
+pub async fn create_failover_provider_with_signer(
+    rpc_urls: &[String],
+    config: FailoverConfig,
+    signer: PrivateKeySigner,
+) -> ContractResult<impl Provider + Clone> {
+    let transport = FailoverTransport::connect(rpc_urls, config).await?;
+    Ok(ProviderBuilder::new()
+        .with_recommended_fillers()
+        .filler(WalletFiller::new(EthereumWallet::from(signer)))
+        .on_client(RpcClient::new(transport.boxed(), false)))
+}
+
+/// Creates a provider over several RPC URLs of the same chain, with the given signer, as in
+/// `create_failover_provider_with_signer`.
+///
+/// Note: The signer will locally track the nonce and cache it, as in
+/// `create_provider_with_nonce_caching_signer`.
+pub async fn create_failover_provider_with_nonce_caching_signer(
+    rpc_urls: &[String],
+    config: FailoverConfig,
+    signer: PrivateKeySigner,
+) -> ContractResult<impl Provider + Clone> {
+    let transport = FailoverTransport::connect(rpc_urls, config).await?;
+    Ok(Arc::new(
+        ProviderBuilder::new()
+            .filler(GasFiller)
+            .filler(BlobGasFiller)
+            .filler(NonceFiller::<CachedNonceManager>::default())
+            .filler(ChainIdFiller::default())
+            .filler(WalletFiller::new(EthereumWallet::from(signer)))
+            .filler(LoggingFiller::default())
+            .on_client(RpcClient::new(transport.boxed(), false)),
+    ))
+}
+
+/// RPC endpoints of a single chain, together with the configuration of the `FailoverTransport`
+/// over them. Components taking several node URLs pass it around instead of a single URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcEndpoints {
+    pub urls: Vec<String>,
+    pub failover: FailoverConfig,
+}
+
+impl RpcEndpoints {
+    /// `node_rpc_url` followed by `fallback_urls`. Fails if `quorum` can't be reached by them.
+    pub fn new(
+        node_rpc_url: String,
+        fallback_urls: Vec<String>,
+        quorum: Option<usize>,
+    ) -> ContractResult<Self> {
+        let urls = [vec![node_rpc_url], fallback_urls].concat();
+        let failover = FailoverConfig {
+            quorum,
+            ..Default::default()
+        };
+        failover.check(urls.len())?;
+        Ok(Self { urls, failover })
+    }
+
+    /// Read-only provider, like `create_simple_provider`.
+    pub async fn simple_provider(
+        &self,
+    ) -> ContractResult<impl Provider<BoxTransport, AnyNetwork> + Clone> {
+        let transport = FailoverTransport::connect(&self.urls, self.failover.clone()).await?;
+        Ok(ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .on_client(RpcClient::new(transport.boxed(), false)))
+    }
+
+    /// Read-only provider, see `create_failover_provider`.
+    pub async fn provider(&self) -> ContractResult<RootProvider<BoxTransport>> {
+        create_failover_provider(&self.urls, self.failover.clone()).await
+    }
+
+    /// See `create_failover_provider_with_signer`.
+    pub async fn provider_with_signer(
+        &self,
+        signer: PrivateKeySigner,
+    ) -> ContractResult<impl Provider + Clone> {
+        create_failover_provider_with_signer(&self.urls, self.failover.clone(), signer).await
+    }
+
+    /// See `create_failover_provider_with_nonce_caching_signer`.
+    pub async fn provider_with_nonce_caching_signer(
+        &self,
+        signer: PrivateKeySigner,
+    ) -> ContractResult<impl Provider + Clone> {
+        create_failover_provider_with_nonce_caching_signer(
+            &self.urls,
+            self.failover.clone(),
+            signer,
+        )
+        .await
+    }
+}
+
+/// A noop filler that reports transaction details once it is prepared, just before sending. For
+/// debugging purposes.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LoggingFiller {}
+
+impl TxFiller for LoggingFiller {
+    type Fillable = ();
+
+    fn status(&self, _tx: &<Ethereum as Network>::TransactionRequest) -> FillerControlFlow {
+        FillerControlFlow::Finished
+    }
+
+    fn fill_sync(&self, tx: &mut SendableTx<Ethereum>) {
+        match tx {
+            SendableTx::Builder(tx) => {
+                tracing::info!(sender = ?tx.from, to = ?tx.to, nonce = tx.nonce, "Sending a transaction");
+            }
+            SendableTx::Envelope(_) => {} // We don't use envelopes in this crate.
+        }
+    }
+
+    async fn prepare<P: Provider<T, Ethereum>, T: Transport + Clone>(
+        &self,
+        _provider: &P,
+        _tx: &<Ethereum as Network>::TransactionRequest,
+    ) -> TransportResult<Self::Fillable> {
+        Ok(())
+    }
+
+    async fn fill(
+        &self,
+        _fillable: Self::Fillable,
+        tx: SendableTx<Ethereum>,
+    ) -> TransportResult<SendableTx<Ethereum>> {
+        Ok(tx)
+    }
+}
@@ -0,0 +1,440 @@
+use std::{
+    marker::PhantomData,
+    sync::{Arc, OnceLock},
+};
+
+use alloy_contract::CallDecoder;
+use alloy_primitives::{address, Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder, RootProvider};
+use alloy_sol_types::{sol, SolCall};
+use alloy_transport::BoxTransport;
+use shielder_setup::{
+    consts::{ARITY, TREE_HEIGHT},
+    shielder_circuits::GrumpkinPointAffine,
+    version::ContractVersion,
+};
+
+use crate::{
+    call_type::{CallType, DryRun},
+    merkle_path::reorganize_merkle_path,
+    providers::RpcEndpoints,
+    ContractResult,
+    ShielderContract::{
+        self, anonymityRevokerPubkeyCall, getMerklePathCall, merkleRootExistsCall, merkleTreeCall,
+        nullifiersCall, ownerCall, pausedCall, protocolDepositFeeBpsCall, protocolFeeReceiverCall,
+        protocolWithdrawFeeBpsCall, tokenListCall, tokenListRootCall, CONTRACT_VERSIONCall,
+    },
+    ShielderContractCall, ShielderContractError,
+};
+
+/// Address of the Multicall3 contract, deployed at the same address on most EVM chains.
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// First contract version exposing `merkleRootExists`.
+const MERKLE_ROOT_EXISTS_VERSION: ContractVersion = ContractVersion {
+    note_version: 0,
+    circuit_version: 6,
+    patch_version: 1,
+};
+
+sol! {
+    #[sol(rpc)]
+    contract Multicall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+    }
+}
+
+/// Snapshot of the configuration and state of the Shielder contract, read at a single block when
+/// Multicall3 is available.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShielderState {
+    pub contract_version: ContractVersion,
+    pub owner: Address,
+    pub paused: bool,
+    /// `(root, nextFreeLeafId, maxLeafId, firstLeafId)`
+    pub merkle_tree: (U256, U256, U256, U256),
+    pub anonymity_revoker_pubkey: GrumpkinPointAffine<U256>,
+    pub protocol_deposit_fee_bps: U256,
+    pub protocol_withdraw_fee_bps: U256,
+    pub protocol_fee_receiver: Address,
+    pub token_list_root: U256,
+}
+
+/// Read-only client of the Shielder contract. Unlike `ShielderUser`, it doesn't need a signer.
+///
+/// Reads of many values (`read_many`, `state`, `nullifiers_batch`, `merkle_roots_exist`) are
+/// batched into a single `eth_call` through Multicall3 if it is deployed on the chain, and fall
+/// back to one `eth_call` per value otherwise.
+#[derive(Clone)]
+pub struct ShielderReader<Provider = RootProvider<BoxTransport>> {
+    contract_address: Address,
+    provider: Provider,
+    multicall_address: Option<Address>,
+    /// Whether there is code at `multicall_address`. Checked once, on the first batched read.
+    multicall_deployed: Arc<OnceLock<bool>>,
+    /// Whether the contract exposes `merkleRootExists`. Checked once, on the first root lookup.
+    merkle_root_exists_exposed: Arc<OnceLock<bool>>,
+}
+
+impl ShielderReader {
+    /// Create a reader connected to `rpc_url`.
+    pub async fn from_rpc_url(contract_address: Address, rpc_url: &str) -> ContractResult<Self> {
+        let provider = ProviderBuilder::new()
+            .on_builtin(rpc_url)
+            .await
+            .map_err(ShielderContractError::ProviderError)?;
+        Ok(Self::new(contract_address, provider))
+    }
+
+    /// Create a reader over several RPC endpoints (see `FailoverTransport`).
+    pub async fn from_rpc_endpoints(
+        contract_address: Address,
+        rpc_endpoints: &RpcEndpoints,
+    ) -> ContractResult<Self> {
+        Ok(Self::new(contract_address, rpc_endpoints.provider().await?))
+    }
+}
+
+// `Clone` is required for the same reasons as in `Connection`.
+impl<P: Provider + Clone> ShielderReader<P> {
+    /// Create a reader using `provider`.
+    pub fn new(contract_address: Address, provider: P) -> Self {
+        Self {
+            contract_address,
+            provider,
+            multicall_address: Some(MULTICALL3_ADDRESS),
+            multicall_deployed: Arc::new(OnceLock::new()),
+            merkle_root_exists_exposed: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Use Multicall3 deployed at `multicall_address` instead of the canonical address.
+    pub fn with_multicall(mut self, multicall_address: Address) -> Self {
+        self.multicall_address = Some(multicall_address);
+        self.multicall_deployed = Arc::new(OnceLock::new());
+        self
+    }
+
+    /// Never batch reads, always send one `eth_call` per value.
+    pub fn without_multicall(mut self) -> Self {
+        self.multicall_address = None;
+        self
+    }
+
+    /// Get the address of the Shielder contract.
+    pub fn contract_address(&self) -> Address {
+        self.contract_address
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Call a view function of the Shielder contract.
+    pub async fn read<C: ShielderContractCall + Unpin>(
+        &self,
+        call: &C,
+    ) -> ContractResult<C::UnwrappedResult>
+    where
+        PhantomData<C>: CallDecoder + Unpin,
+    {
+        let contract = ShielderContract::new(self.contract_address, self.provider.clone());
+        DryRun::action(contract.call_builder(call)).await
+    }
+
+    /// Call a view function of the Shielder contract several times, in one `eth_call` if
+    /// Multicall3 is available.
+    pub async fn read_many<C: ShielderContractCall + Unpin>(
+        &self,
+        calls: &[C],
+    ) -> ContractResult<Vec<C::UnwrappedResult>>
+    where
+        PhantomData<C>: CallDecoder + Unpin,
+    {
+        let calldata: Vec<Bytes> = calls.iter().map(|call| call.abi_encode().into()).collect();
+        if let Some(results) = self.aggregate(calldata).await? {
+            return results.iter().map(|data| decode::<C>(data)).collect();
+        }
+
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            results.push(self.read(call).await?);
+        }
+        Ok(results)
+    }
+
+    /// Read the whole configuration and state of the contract, see [`ShielderState`].
+    pub async fn state(&self) -> ContractResult<ShielderState> {
+        let calldata: Vec<Bytes> = vec![
+            CONTRACT_VERSIONCall::new(()).abi_encode().into(),
+            ownerCall::new(()).abi_encode().into(),
+            pausedCall::new(()).abi_encode().into(),
+            merkleTreeCall::new(()).abi_encode().into(),
+            anonymityRevokerPubkeyCall::new(()).abi_encode().into(),
+            protocolDepositFeeBpsCall::new(()).abi_encode().into(),
+            protocolWithdrawFeeBpsCall::new(()).abi_encode().into(),
+            protocolFeeReceiverCall::new(()).abi_encode().into(),
+            tokenListRootCall::new(()).abi_encode().into(),
+        ];
+
+        if let Some(results) = self.aggregate(calldata).await? {
+            return Ok(ShielderState {
+                contract_version: decode::<CONTRACT_VERSIONCall>(&results[0])?,
+                owner: decode::<ownerCall>(&results[1])?,
+                paused: decode::<pausedCall>(&results[2])?,
+                merkle_tree: decode::<merkleTreeCall>(&results[3])?,
+                anonymity_revoker_pubkey: decode::<anonymityRevokerPubkeyCall>(&results[4])?,
+                protocol_deposit_fee_bps: decode::<protocolDepositFeeBpsCall>(&results[5])?,
+                protocol_withdraw_fee_bps: decode::<protocolWithdrawFeeBpsCall>(&results[6])?,
+                protocol_fee_receiver: decode::<protocolFeeReceiverCall>(&results[7])?,
+                token_list_root: decode::<tokenListRootCall>(&results[8])?,
+            });
+        }
+
+        Ok(ShielderState {
+            contract_version: self.contract_version().await?,
+            owner: self.owner().await?,
+            paused: self.paused().await?,
+            merkle_tree: self.merkle_tree().await?,
+            anonymity_revoker_pubkey: self.anonymity_revoker_pubkey().await?,
+            protocol_deposit_fee_bps: self.protocol_deposit_fee_bps().await?,
+            protocol_withdraw_fee_bps: self.protocol_withdraw_fee_bps().await?,
+            protocol_fee_receiver: self.protocol_fee_receiver().await?,
+            token_list_root: self.token_list_root().await?,
+        })
+    }
+
+    /// Get the version of the deployed contract.
+    pub async fn contract_version(&self) -> ContractResult<ContractVersion> {
+        self.read(&CONTRACT_VERSIONCall::new(())).await
+    }
+
+    pub async fn owner(&self) -> ContractResult<Address> {
+        self.read(&ownerCall::new(())).await
+    }
+
+    pub async fn paused(&self) -> ContractResult<bool> {
+        self.read(&pausedCall::new(())).await
+    }
+
+    /// Get the block number for the `nullifierHash`. `0` means that the nullifier hasn't been used
+    /// yet.
+    pub async fn nullifiers(&self, nullifier_hash: U256) -> ContractResult<U256> {
+        self.read(&nullifiersCall {
+            nullifierHash: nullifier_hash,
+        })
+        .await
+    }
+
+    /// `nullifiers` for every hash in `nullifier_hashes`.
+    pub async fn nullifiers_batch(&self, nullifier_hashes: &[U256]) -> ContractResult<Vec<U256>> {
+        let calls = nullifier_hashes
+            .iter()
+            .map(|&nullifier_hash| nullifiersCall {
+                nullifierHash: nullifier_hash,
+            })
+            .collect::<Vec<_>>();
+        self.read_many(&calls).await
+    }
+
+    /// Get the flat Merkle path for a given ID (see `merkle_path::reorganize_merkle_path`).
+    pub async fn get_merkle_path(&self, id: U256) -> ContractResult<Vec<U256>> {
+        self.read(&getMerklePathCall::new((id,))).await
+    }
+
+    /// Get the current Merkle root and path to the leaf at `leaf_index`.
+    pub async fn current_merkle_path(
+        &self,
+        leaf_index: U256,
+    ) -> ContractResult<(U256, [[U256; ARITY]; TREE_HEIGHT])> {
+        reorganize_merkle_path(self.get_merkle_path(leaf_index).await?)
+    }
+
+    /// Get the current state of the note tree: `(root, nextFreeLeafId, maxLeafId, firstLeafId)`.
+    pub async fn merkle_tree(&self) -> ContractResult<(U256, U256, U256, U256)> {
+        self.read(&merkleTreeCall::new(())).await
+    }
+
+    /// Whether proofs against `merkle_root` are accepted by the contract.
+    ///
+    /// Contracts older than 0.6.1 don't expose `merkleRootExists`. For them only the current root
+    /// (from `merkleTree`) is recognized, so older roots still accepted by the contract are
+    /// reported as missing.
+    pub async fn merkle_root_exists(&self, merkle_root: U256) -> ContractResult<bool> {
+        if !self.merkle_root_exists_exposed().await? {
+            return Ok(merkle_root == self.merkle_tree().await?.0);
+        }
+        self.read(&merkleRootExistsCall {
+            merkleRoot: merkle_root,
+        })
+        .await
+    }
+
+    /// `merkle_root_exists` for every root in `merkle_roots`.
+    pub async fn merkle_roots_exist(&self, merkle_roots: &[U256]) -> ContractResult<Vec<bool>> {
+        if !self.merkle_root_exists_exposed().await? {
+            let current_root = self.merkle_tree().await?.0;
+            return Ok(merkle_roots
+                .iter()
+                .map(|&root| root == current_root)
+                .collect());
+        }
+        let calls = merkle_roots
+            .iter()
+            .map(|&merkle_root| merkleRootExistsCall {
+                merkleRoot: merkle_root,
+            })
+            .collect::<Vec<_>>();
+        self.read_many(&calls).await
+    }
+
+    pub async fn anonymity_revoker_pubkey(&self) -> ContractResult<GrumpkinPointAffine<U256>> {
+        self.read(&anonymityRevokerPubkeyCall::new(())).await
+    }
+
+    pub async fn protocol_deposit_fee_bps(&self) -> ContractResult<U256> {
+        self.read(&protocolDepositFeeBpsCall::new(())).await
+    }
+
+    pub async fn protocol_withdraw_fee_bps(&self) -> ContractResult<U256> {
+        self.read(&protocolWithdrawFeeBpsCall::new(())).await
+    }
+
+    pub async fn protocol_fee_receiver(&self) -> ContractResult<Address> {
+        self.read(&protocolFeeReceiverCall::new(())).await
+    }
+
+    pub async fn token_list(&self) -> ContractResult<Vec<Address>> {
+        self.read(&tokenListCall::new(())).await
+    }
+
+    pub async fn token_list_root(&self) -> ContractResult<U256> {
+        self.read(&tokenListRootCall::new(())).await
+    }
+
+    /// Execute `calls` to the Shielder contract in a single `eth_call` through Multicall3 and
+    /// return their raw results. `None` if Multicall3 is not available.
+    async fn aggregate(&self, calls: Vec<Bytes>) -> ContractResult<Option<Vec<Bytes>>> {
+        let Some(multicall_address) = self.multicall_address else {
+            return Ok(None);
+        };
+        if !self.multicall_deployed(multicall_address).await? {
+            return Ok(None);
+        }
+
+        let calls = calls
+            .into_iter()
+            .map(|call_data| Multicall3::Call3 {
+                target: self.contract_address,
+                allowFailure: false,
+                callData: call_data,
+            })
+            .collect();
+        let results = Multicall3::new(multicall_address, self.provider.clone())
+            .aggregate3(calls)
+            .call()
+            .await?
+            .returnData;
+        Ok(Some(
+            results
+                .into_iter()
+                .map(|result| result.returnData)
+                .collect(),
+        ))
+    }
+
+    async fn multicall_deployed(&self, multicall_address: Address) -> ContractResult<bool> {
+        if let Some(deployed) = self.multicall_deployed.get() {
+            return Ok(*deployed);
+        }
+
+        let code = self
+            .provider
+            .get_code_at(multicall_address)
+            .await
+            .map_err(ShielderContractError::ProviderError)?;
+        if code.is_empty() {
+            tracing::debug!(%multicall_address, "Multicall3 is not deployed, reads won't be batched");
+        }
+        Ok(*self.multicall_deployed.get_or_init(|| !code.is_empty()))
+    }
+
+    async fn merkle_root_exists_exposed(&self) -> ContractResult<bool> {
+        if let Some(exposed) = self.merkle_root_exists_exposed.get() {
+            return Ok(*exposed);
+        }
+
+        let contract_version = self.contract_version().await?;
+        if !exposes_merkle_root_exists(contract_version) {
+            tracing::debug!(
+                %contract_version,
+                "`merkleRootExists` is not available, only the current root will be recognized"
+            );
+        }
+        Ok(*self
+            .merkle_root_exists_exposed
+            .get_or_init(|| exposes_merkle_root_exists(contract_version)))
+    }
+}
+
+fn exposes_merkle_root_exists(contract_version: ContractVersion) -> bool {
+    contract_version.to_bytes() >= MERKLE_ROOT_EXISTS_VERSION.to_bytes()
+}
+
+/// Decode the raw result of `C`, as returned by Multicall3.
+fn decode<C: ShielderContractCall>(data: &[u8]) -> ContractResult<C::UnwrappedResult> {
+    let output = C::abi_decode_returns(data, true).map_err(|e| {
+        ShielderContractError::Other(format!("Couldn't decode result of {}: {e}", C::SIGNATURE))
+    })?;
+    Ok(C::unwrap_result(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use alloy_sol_types::SolCall;
+    use shielder_setup::version::ContractVersion;
+
+    use super::{decode, exposes_merkle_root_exists};
+    use crate::ShielderContract::{nullifiersCall, protocolFeeReceiverCall};
+
+    #[test]
+    fn multicall_results_are_decoded() {
+        let block = U256::from(42);
+        let data = nullifiersCall::abi_encode_returns(&(block,));
+        assert_eq!(decode::<nullifiersCall>(&data).unwrap(), block);
+
+        let receiver = Address::random();
+        let data = protocolFeeReceiverCall::abi_encode_returns(&(receiver,));
+        assert_eq!(decode::<protocolFeeReceiverCall>(&data).unwrap(), receiver);
+    }
+
+    #[test]
+    fn malformed_result_is_rejected() {
+        assert!(decode::<nullifiersCall>(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn merkle_root_exists_is_used_only_from_the_version_exposing_it() {
+        let version = |note_version, circuit_version, patch_version| ContractVersion {
+            note_version,
+            circuit_version,
+            patch_version,
+        };
+        assert!(!exposes_merkle_root_exists(version(0, 5, 0)));
+        assert!(!exposes_merkle_root_exists(version(0, 6, 0)));
+        assert!(exposes_merkle_root_exists(version(0, 6, 1)));
+        assert!(exposes_merkle_root_exists(version(0, 7, 0)));
+        assert!(exposes_merkle_root_exists(version(1, 0, 0)));
+    }
+}
@@ -1,22 +1,28 @@
 use alloy_network::{primitives::BlockTransactionsKind, AnyNetwork, TransactionResponse};
-use alloy_primitives::{BlockHash, BlockNumber, Bytes, TxHash, U256};
+use alloy_primitives::{Address, BlockHash, BlockNumber, Bytes, TxHash, U256};
 use alloy_provider::Provider;
-use alloy_rpc_types::TransactionTrait;
+use alloy_rpc_types::{Filter, TransactionTrait};
 use alloy_sol_types::SolCall;
 use alloy_transport::BoxTransport;
 
 use crate::{
     call_type::DryRun,
-    events::get_event,
+    events::{decode_shielder_event, get_event},
     ContractResult,
     ShielderContract::{
-        depositERC20Call, depositNativeCall, newAccountERC20Call, newAccountNativeCall,
-        withdrawERC20Call, withdrawNativeCall, Deposit, NewAccount, ShielderContractEvents,
-        Withdraw,
+        claimCall, depositERC20Call, depositNativeCall, migrateCall, newAccountERC20Call,
+        newAccountNativeCall, transferCall, withdrawERC20Call, withdrawNativeCall, Claim, Deposit,
+        Migrate, NewAccount, ShielderContractEvents, Transfer, Withdraw,
     },
     ShielderContractError, ShielderUser,
 };
 
+/// Find the Shielder action that spent `nullifier`, by decoding the top-level input of the
+/// transactions in the block where the nullifier was spent.
+///
+/// This is fast, but works only for transactions that call the Shielder contract directly. Use
+/// `recover_shielder_action` to also cover calls made through smart-contract wallets, bundlers,
+/// multicalls or other proxies.
 pub async fn get_shielder_action(
     provider: &impl Provider<BoxTransport, AnyNetwork>,
     shielder_user: &ShielderUser,
@@ -28,14 +34,72 @@ pub async fn get_shielder_action(
         return Ok(None);
     };
 
-    // 2. Fetch the block from the provider
+    // 2. Look for a direct Shielder call in the block
+    find_action_in_block_transactions(provider, block_number, nullifier).await
+}
+
+/// Find the Shielder action that spent `nullifier`.
+///
+/// First, the top-level input of the transactions in the block is decoded (as in
+/// `get_shielder_action`). If the Shielder call was not the top-level call of its transaction,
+/// the Shielder events emitted in the block are checked instead: `expected_note` should return
+/// the note that the account would end up with after the given event (or `None` if the event
+/// cannot belong to the account), and the event carrying exactly this note is returned.
+///
+/// Returns an error if the nullifier has been spent, but no matching action could be found.
+pub async fn recover_shielder_action(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    shielder_user: &ShielderUser,
+    nullifier: U256,
+    expected_note: impl Fn(&ShielderContractEvents) -> Option<U256>,
+) -> ContractResult<Option<(TxHash, ShielderContractEvents)>> {
+    // 1. Find the block number where the nullifier was spent, if any
+    let Some(block_number) = get_block_of_nullifier_spending(shielder_user, nullifier).await?
+    else {
+        return Ok(None);
+    };
+
+    // 2. Fast path: look for a direct Shielder call in the block
+    if let Some(action) =
+        find_action_in_block_transactions(provider, block_number, nullifier).await?
+    {
+        return Ok(Some(action));
+    }
+
+    // 3. Slow path: look for an event with the note that the account should have produced
+    tracing::debug!(
+        "No direct Shielder call found in block {block_number}, falling back to event logs"
+    );
+    match find_action_in_block_logs(
+        provider,
+        shielder_user.contract_address(),
+        block_number,
+        expected_note,
+    )
+    .await?
+    {
+        Some(action) => Ok(Some(action)),
+        None => Err(ShielderContractError::Other(format!(
+            "Nullifier {nullifier} was spent in block {block_number}, but no matching action was found"
+        ))),
+    }
+}
+
+/// Iterate over the transactions in the block and find the one that directly calls the Shielder
+/// contract with `nullifier`.
+async fn find_action_in_block_transactions(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    block_number: BlockNumber,
+    nullifier: U256,
+) -> ContractResult<Option<(TxHash, ShielderContractEvents)>> {
+    // 1. Fetch the block from the provider
     let block = provider
         .get_block_by_number(block_number.into(), BlockTransactionsKind::Full)
         .await
         .map_err(ShielderContractError::ProviderError)?
         .ok_or(ShielderContractError::Other("Block not found".into()))?;
 
-    // 3. Iterate over the transactions in the block and find the one that matches the nullifier
+    // 2. Iterate over the transactions in the block and find the one that matches the nullifier
     let txs = block
         .transactions
         .as_transactions()
@@ -58,6 +122,37 @@ pub async fn get_shielder_action(
     Ok(None)
 }
 
+/// Go through the Shielder events emitted in the block and return the one whose new note is equal
+/// to `expected_note(event)`. This doesn't depend on how the Shielder contract was called.
+pub async fn find_action_in_block_logs(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    contract_address: Address,
+    block_number: BlockNumber,
+    expected_note: impl Fn(&ShielderContractEvents) -> Option<U256>,
+) -> ContractResult<Option<(TxHash, ShielderContractEvents)>> {
+    let filter = Filter::new()
+        .address(contract_address)
+        .from_block(block_number)
+        .to_block(block_number);
+    let logs = provider
+        .get_logs(&filter)
+        .await
+        .map_err(ShielderContractError::ProviderError)?;
+
+    for log in logs {
+        let Some(event) = decode_shielder_event(&log)? else {
+            continue;
+        };
+        if expected_note(&event) == Some(event.note()) {
+            let tx_hash = log.transaction_hash.ok_or(ShielderContractError::Other(
+                "Transaction hash not found".into(),
+            ))?;
+            return Ok(Some((tx_hash, event)));
+        }
+    }
+    Ok(None)
+}
+
 /// Get the block number where the nullifier was spent, if any.
 pub async fn get_block_of_nullifier_spending(
     shielder_user: &ShielderUser,
@@ -141,5 +236,29 @@ pub async fn check_if_tx_is_shielder_action(
         )));
     }
 
+    if let Ok(call) = transferCall::abi_decode(tx_data, true) {
+        let event = get_event::<Transfer>(provider, tx_hash, block_hash).await?;
+        return Ok(Some((
+            ShielderContractEvents::Transfer(event),
+            call.oldNullifierHash,
+        )));
+    }
+
+    if let Ok(call) = claimCall::abi_decode(tx_data, true) {
+        let event = get_event::<Claim>(provider, tx_hash, block_hash).await?;
+        return Ok(Some((
+            ShielderContractEvents::Claim(event),
+            call.oldNullifierHash,
+        )));
+    }
+
+    if let Ok(call) = migrateCall::abi_decode(tx_data, true) {
+        let event = get_event::<Migrate>(provider, tx_hash, block_hash).await?;
+        return Ok(Some((
+            ShielderContractEvents::Migrate(event),
+            call.oldNullifierHash,
+        )));
+    }
+
     Ok(None)
 }
@@ -0,0 +1,53 @@
+use alloy_primitives::{Address, U256};
+use shielder_setup::{
+    consts::{ARITY, TOKEN_TREE_HEIGHT},
+    shielder_circuits::TokenList,
+};
+use type_conversions::{address_to_field, field_to_u256};
+
+use crate::{call_type::DryRun, ContractResult, ShielderContractError, ShielderUser};
+
+/// Query the contract for the list of allowed tokens and build the token tree from it. Fail if
+/// the root of the tree differs from the one committed to in the contract.
+pub async fn get_token_list(shielder_user: &ShielderUser) -> ContractResult<TokenList> {
+    let tokens = shielder_user.token_list::<DryRun>().await?;
+    let contract_root = shielder_user.token_list_root::<DryRun>().await?;
+
+    let token_list = build_token_list(&tokens)?;
+    let local_root = field_to_u256(token_list.root());
+    if local_root != contract_root {
+        return Err(ShielderContractError::TokenListRootMismatch {
+            local: local_root,
+            contract: contract_root,
+        });
+    }
+    Ok(token_list)
+}
+
+/// Query the contract for the current Merkle path of `token` in the tree of allowed tokens.
+pub async fn get_token_list_path(
+    token: Address,
+    shielder_user: &ShielderUser,
+) -> ContractResult<[[U256; ARITY]; TOKEN_TREE_HEIGHT]> {
+    token_list_path(&get_token_list(shielder_user).await?, token)
+}
+
+/// Build the token tree from a list of ERC20 token addresses.
+pub fn build_token_list(tokens: &[Address]) -> ContractResult<TokenList> {
+    let leaves = tokens
+        .iter()
+        .map(|token| address_to_field(*token))
+        .collect::<Vec<_>>();
+    TokenList::new(&leaves).map_err(|e| ShielderContractError::Other(format!("{e:?}")))
+}
+
+/// Merkle path of `token` in `token_list`.
+pub fn token_list_path(
+    token_list: &TokenList,
+    token: Address,
+) -> ContractResult<[[U256; ARITY]; TOKEN_TREE_HEIGHT]> {
+    token_list
+        .path(address_to_field(token))
+        .map(|path| path.map(|level| level.map(field_to_u256)))
+        .ok_or(ShielderContractError::TokenNotAllowed(token))
+}
@@ -4,7 +4,7 @@ use core::marker::PhantomData;
 use std::fmt::Debug;
 
 use alloy_contract::CallDecoder;
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use alloy_sol_types::{sol, SolCall};
 use shielder_setup::{
     shielder_circuits::GrumpkinPointAffine,
@@ -18,6 +18,19 @@ sol! {
     #[sol(rpc, all_derives = true)]
     #[derive(Debug, PartialEq, Eq)]
     contract ShielderContract {
+        struct BatchedWithdrawal {
+            uint256 amount;
+            address withdrawalAddress;
+            uint256 merkleRoot;
+            uint256 oldNullifierHash;
+            uint256 newNote;
+            uint256 relayerFee;
+            uint256 macSalt;
+            uint256 macCommitment;
+            uint256 associationSetRoot;
+            bytes memo;
+        }
+
         event NewAccount(
             bytes3 contractVersion,
             uint256 prenullifier,
@@ -52,10 +65,46 @@ sol! {
             uint256 fee,
             uint256 macSalt,
             uint256 macCommitment,
+            uint256 associationSetRoot,
             uint256 pocketMoney,
             uint256 protocolFee,
             bytes memo
         );
+        event Transfer(
+            bytes3 contractVersion,
+            address tokenAddress,
+            uint256 newNote,
+            uint256 newNoteIndex,
+            uint256 receipt,
+            uint256 receiptIndex,
+            uint256[4] encryptedReceiptNonce,
+            uint256 encryptedValue,
+            uint256[4] revokerEncryption,
+            uint256 macSalt,
+            uint256 macCommitment,
+            bytes memo
+        );
+        event Claim(
+            bytes3 contractVersion,
+            address tokenAddress,
+            uint256 newNote,
+            uint256 newNoteIndex,
+            uint256 receiptNullifier,
+            uint256 encryptedValue,
+            uint256 macSalt,
+            uint256 macCommitment,
+            bytes memo
+        );
+        event Migrate(
+            bytes3 contractVersion,
+            address tokenAddress,
+            uint8 oldNoteVersion,
+            uint256 newNote,
+            uint256 newNoteIndex,
+            uint256 macSalt,
+            uint256 macCommitment,
+            bytes memo
+        );
 
         error DepositVerificationFailed();
         error DuplicatedNullifier();
@@ -64,6 +113,10 @@ sol! {
         error NativeTransferFailed();
         error WithdrawVerificationFailed();
         error NewAccountVerificationFailed();
+        error TransferVerificationFailed();
+        error ClaimVerificationFailed();
+        error MigrationVerificationFailed();
+        error UnsupportedNoteVersion();
         error ZeroAmount();
         error AmountTooHigh();
         error ContractBalanceLimitReached();
@@ -72,6 +125,10 @@ sol! {
 
         error InvalidGrumpkinPoint();
         error OwnableUnauthorizedAccount(address account);
+        error DepositFeeTooHigh();
+        error WithdrawFeeTooHigh();
+        error EnforcedPause();
+        error ExpectedPause();
 
         function initialize(
             address initialOwner,
@@ -81,15 +138,21 @@ sol! {
             uint256 _protocolDepositFeeBps,
             uint256 _protocolWithdrawFeeBps,
             address _protocolFeeReceiver,
+            address[] memory _tokenList,
+            uint256 _tokenListRoot,
         ) public;
 
+        function CONTRACT_VERSION() public view returns (bytes3);
         function nullifiers(uint256 nullifierHash) public view returns (uint256);
 
+        function owner() public view returns (address);
+        function paused() public view returns (bool);
         function pause() external;
         function unpause() external;
 
         function setProtocolDepositFeeBps(uint256) external;
         function setProtocolWithdrawFeeBps(uint256) external;
+        function setProtocolFeeReceiver(address newProtocolFeeReceiver) external;
 
         function newAccountNative(
             bytes3 expectedContractVersion,
@@ -153,6 +216,7 @@ sol! {
             uint256 relayerFee,
             uint256 macSalt,
             uint256 macCommitment,
+            uint256 associationSetRoot,
             bytes calldata memo
         ) external whenNotPaused;
         function withdrawERC20(
@@ -168,12 +232,62 @@ sol! {
             uint256 relayerFee,
             uint256 macSalt,
             uint256 macCommitment,
+            uint256 associationSetRoot,
+            bytes calldata memo
+        ) external whenNotPaused;
+        function withdrawBatch(
+            bytes3 expectedContractVersion,
+            address tokenAddress,
+            address relayerAddress,
+            BatchedWithdrawal[2] calldata withdrawals,
+            bytes calldata proof
+        ) external whenNotPaused;
+        function transfer(
+            bytes3 expectedContractVersion,
+            address tokenAddress,
+            uint256 merkleRoot,
+            uint256 oldNullifierHash,
+            uint256 newNote,
+            uint256 receipt,
+            uint256[4] calldata encryptedReceiptNonce,
+            uint256 encryptedValue,
+            uint256[4] calldata revokerEncryption,
+            bytes calldata proof,
+            uint256 macSalt,
+            uint256 macCommitment,
+            bytes calldata memo
+        ) external whenNotPaused;
+        function claim(
+            bytes3 expectedContractVersion,
+            address tokenAddress,
+            uint256 merkleRoot,
+            uint256 oldNullifierHash,
+            uint256 newNote,
+            uint256 receiptNullifier,
+            uint256 encryptedValue,
+            bytes calldata proof,
+            uint256 macSalt,
+            uint256 macCommitment,
+            bytes calldata memo
+        ) external whenNotPaused;
+        function migrate(
+            bytes3 expectedContractVersion,
+            address tokenAddress,
+            uint8 oldNoteVersion,
+            uint256 merkleRoot,
+            uint256 oldNullifierHash,
+            uint256 newNote,
+            bytes calldata proof,
+            uint256 macSalt,
+            uint256 macCommitment,
             bytes calldata memo
         ) external whenNotPaused;
 
         function getMerklePath(
             uint256 id
         ) external view returns (uint256[] memory);
+        function merkleTree() public view returns (uint256, uint256, uint256, uint256);
+        function merkleRootExists(uint256 merkleRoot) public view returns (bool);
 
         function anonymityRevokerPubkey() public view returns (uint256, uint256);
         function setAnonymityRevokerPubkey(
@@ -183,6 +297,11 @@ sol! {
 
         function protocolDepositFeeBps() public view returns (uint256);
         function protocolWithdrawFeeBps() public view returns (uint256);
+        function protocolFeeReceiver() public view returns (address);
+
+        function tokenList() public view returns (address[] memory);
+        function tokenListRoot() public view returns (uint256);
+        function setTokenList(address[] calldata newTokenList, uint256 newTokenListRoot) external;
     }
 }
 
@@ -191,7 +310,36 @@ impl ShielderContractEvents {
         match self {
             Self::NewAccount(NewAccount { newNote: note, .. })
             | Self::Deposit(Deposit { newNote: note, .. })
-            | Self::Withdraw(Withdraw { newNote: note, .. }) => *note,
+            | Self::Withdraw(Withdraw { newNote: note, .. })
+            | Self::Transfer(Transfer { newNote: note, .. })
+            | Self::Claim(Claim { newNote: note, .. })
+            | Self::Migrate(Migrate { newNote: note, .. }) => *note,
+        }
+    }
+
+    pub fn note_index(&self) -> U256 {
+        match self {
+            Self::NewAccount(NewAccount { newNoteIndex, .. })
+            | Self::Deposit(Deposit { newNoteIndex, .. })
+            | Self::Withdraw(Withdraw { newNoteIndex, .. })
+            | Self::Transfer(Transfer { newNoteIndex, .. })
+            | Self::Claim(Claim { newNoteIndex, .. })
+            | Self::Migrate(Migrate { newNoteIndex, .. }) => *newNoteIndex,
+        }
+    }
+
+    /// All notes added to the tree by the event, as `(index, note)` pairs, in insertion order.
+    /// Unlike `note`, this includes the receipt of a transfer.
+    pub fn notes(&self) -> Vec<(U256, U256)> {
+        match self {
+            Self::Transfer(Transfer {
+                newNote,
+                newNoteIndex,
+                receipt,
+                receiptIndex,
+                ..
+            }) => vec![(*newNoteIndex, *newNote), (*receiptIndex, *receipt)],
+            _ => vec![(self.note_index(), self.note())],
         }
     }
 
@@ -205,6 +353,15 @@ impl ShielderContractEvents {
             })
             | Self::Withdraw(Withdraw {
                 contractVersion, ..
+            })
+            | Self::Transfer(Transfer {
+                contractVersion, ..
+            })
+            | Self::Claim(Claim {
+                contractVersion, ..
+            })
+            | Self::Migrate(Migrate {
+                contractVersion, ..
             }) => contractVersion,
         };
 
@@ -232,6 +389,9 @@ impl Clone for ShielderContractEvents {
             Self::NewAccount(event) => Self::NewAccount(event.clone()),
             Self::Deposit(event) => Self::Deposit(event.clone()),
             Self::Withdraw(event) => Self::Withdraw(event.clone()),
+            Self::Transfer(event) => Self::Transfer(event.clone()),
+            Self::Claim(event) => Self::Claim(event.clone()),
+            Self::Migrate(event) => Self::Migrate(event.clone()),
         }
     }
 }
@@ -259,6 +419,9 @@ impl_unit_call!(unpauseCall);
 
 impl_unit_call!(setProtocolDepositFeeBpsCall);
 impl_unit_call!(setProtocolWithdrawFeeBpsCall);
+impl_unit_call!(setProtocolFeeReceiverCall);
+impl_unit_call!(setAnonymityRevokerPubkeyCall);
+impl_unit_call!(setTokenListCall);
 
 impl_unit_call!(newAccountNativeCall);
 impl_unit_call!(depositNativeCall);
@@ -266,6 +429,17 @@ impl_unit_call!(withdrawNativeCall);
 impl_unit_call!(newAccountERC20Call);
 impl_unit_call!(depositERC20Call);
 impl_unit_call!(withdrawERC20Call);
+impl_unit_call!(withdrawBatchCall);
+impl_unit_call!(transferCall);
+impl_unit_call!(claimCall);
+impl_unit_call!(migrateCall);
+
+impl ShielderContractCall for CONTRACT_VERSIONCall {
+    type UnwrappedResult = ContractVersion;
+    fn unwrap_result(version: CONTRACT_VERSIONReturn) -> Self::UnwrappedResult {
+        ContractVersion::from_bytes(version._0)
+    }
+}
 
 impl ShielderContractCall for getMerklePathCall {
     type UnwrappedResult = Vec<U256>;
@@ -274,6 +448,21 @@ impl ShielderContractCall for getMerklePathCall {
     }
 }
 
+impl ShielderContractCall for merkleTreeCall {
+    /// `(root, nextFreeLeafId, maxLeafId, firstLeafId)`
+    type UnwrappedResult = (U256, U256, U256, U256);
+    fn unwrap_result(tree: merkleTreeReturn) -> Self::UnwrappedResult {
+        (tree._0, tree._1, tree._2, tree._3)
+    }
+}
+
+impl ShielderContractCall for merkleRootExistsCall {
+    type UnwrappedResult = bool;
+    fn unwrap_result(exists: merkleRootExistsReturn) -> Self::UnwrappedResult {
+        exists._0
+    }
+}
+
 impl ShielderContractCall for nullifiersCall {
     type UnwrappedResult = U256;
     fn unwrap_result(nullifier: nullifiersReturn) -> Self::UnwrappedResult {
@@ -304,3 +493,38 @@ impl ShielderContractCall for protocolWithdrawFeeBpsCall {
         fee._0
     }
 }
+
+impl ShielderContractCall for protocolFeeReceiverCall {
+    type UnwrappedResult = Address;
+    fn unwrap_result(receiver: protocolFeeReceiverReturn) -> Self::UnwrappedResult {
+        receiver._0
+    }
+}
+
+impl ShielderContractCall for ownerCall {
+    type UnwrappedResult = Address;
+    fn unwrap_result(owner: ownerReturn) -> Self::UnwrappedResult {
+        owner._0
+    }
+}
+
+impl ShielderContractCall for pausedCall {
+    type UnwrappedResult = bool;
+    fn unwrap_result(paused: pausedReturn) -> Self::UnwrappedResult {
+        paused._0
+    }
+}
+
+impl ShielderContractCall for tokenListCall {
+    type UnwrappedResult = Vec<Address>;
+    fn unwrap_result(tokens: tokenListReturn) -> Self::UnwrappedResult {
+        tokens._0
+    }
+}
+
+impl ShielderContractCall for tokenListRootCall {
+    type UnwrappedResult = U256;
+    fn unwrap_result(root: tokenListRootReturn) -> Self::UnwrappedResult {
+        root._0
+    }
+}
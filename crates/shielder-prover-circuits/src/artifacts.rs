@@ -0,0 +1,30 @@
+//! Helpers for build scripts that generate proving keys and parameters, later embedded into the
+//! TEE binaries.
+//!
+//! To speedup the build process, the artifacts are cached after the first build. When working
+//! locally, the `artifacts/` directory should be cleaned after the circuits are changed.
+
+use shielder_circuits::{
+    circuits::Params,
+    generate_keys_with_min_k,
+    marshall::{marshall_params, marshall_pk},
+    Fr,
+};
+
+/// Generate the artifacts for the circuit, i.e. hardcoded keys and parameters. Saves results to
+/// `artifacts/{circuit_name}/params.bin` and `artifacts/{circuit_name}/pk.bin`.
+pub fn generate<C: shielder_circuits::Circuit<Fr> + Default>(
+    circuit_name: &str,
+    full_params: &Params,
+) {
+    std::fs::create_dir_all(format!("artifacts/{circuit_name}"))
+        .expect("Failed to create directory");
+    let (params, k, pk, _) = generate_keys_with_min_k(C::default(), full_params.clone())
+        .expect("keys should not fail to generate");
+    let params_bytes = marshall_params(&params).expect("Failed to marshall params");
+    std::fs::write(format!("artifacts/{circuit_name}/params.bin"), params_bytes)
+        .expect("Failed to write params.bin");
+    let key_bytes = marshall_pk(k, &pk);
+    std::fs::write(format!("artifacts/{circuit_name}/pk.bin"), key_bytes)
+        .expect("Failed to write pk.bin");
+}
@@ -1,13 +1,12 @@
-use std::vec::Vec;
-
 use serde::{Deserialize, Serialize};
 use shielder_circuits::{
     deposit::{DepositInstance, DepositProverKnowledge},
     Fr, PublicInputProvider,
 };
+use shielder_setup::vk_fingerprint::VkFingerprint;
 use type_conversions::field_to_bytes;
 
-use crate::circuits::{vec_to_f, vec_to_path, SerializableCircuit};
+use crate::{path_to_vec, vec_to_f, vec_to_path, SerializableCircuit};
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct DepositPubInputsBytes {
@@ -50,11 +49,14 @@ impl From<DepositProverKnowledge<Fr>> for DepositPubInputsBytes {
 pub struct DepositCircuit(super::DepositCircuit);
 
 impl DepositCircuit {
-    pub fn new() -> Self {
-        DepositCircuit(super::DepositCircuit::new_pronto(
-            include_bytes!("../../artifacts/deposit/params.bin"),
-            include_bytes!("../../artifacts/deposit/pk.bin"),
-        ))
+    /// Create the circuit from embedded `params.bin` and `pk.bin` artifacts.
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        DepositCircuit(super::DepositCircuit::new_pronto(params_buf, pk_buf))
+    }
+
+    /// Fingerprint of the verifying key, see [`shielder_setup::vk_fingerprint`].
+    pub fn vk_fingerprint(&self) -> VkFingerprint {
+        self.0.vk_fingerprint()
     }
 }
 
@@ -71,6 +73,22 @@ pub struct DepositProveInputBytes {
     mac_salt: Vec<u8>,
 }
 
+impl From<&DepositProverKnowledge<Fr>> for DepositProveInputBytes {
+    fn from(knowledge: &DepositProverKnowledge<Fr>) -> Self {
+        DepositProveInputBytes {
+            id: field_to_bytes(knowledge.id),
+            nullifier_old: field_to_bytes(knowledge.nullifier_old),
+            account_balance_old: field_to_bytes(knowledge.account_old_balance),
+            token_address: field_to_bytes(knowledge.token_address),
+            path: path_to_vec(&knowledge.path),
+            value: field_to_bytes(knowledge.deposit_value),
+            commitment: field_to_bytes(knowledge.commitment),
+            nullifier_new: field_to_bytes(knowledge.nullifier_new),
+            mac_salt: field_to_bytes(knowledge.mac_salt),
+        }
+    }
+}
+
 impl SerializableCircuit for DepositCircuit {
     type Input = DepositProveInputBytes;
     type Output = DepositPubInputsBytes;
@@ -0,0 +1,133 @@
+//! Proving code shared by the TEE servers (`shielder-prover-tee` and `shielder-scheduler-tee`).
+//!
+//! The servers embed proving keys and parameters generated at build time (see [`artifacts`]) and
+//! use [`Circuit::new_pronto`] to load them. Loading fails if the embedded verifying key doesn't
+//! match the one expected by the contract (see [`shielder_setup::vk_fingerprint`]).
+
+use std::marker::PhantomData;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use shielder_circuits::{
+    circuits::{Params, ProvingKey},
+    consts::merkle_constants::ARITY,
+    deposit::DepositProverKnowledge,
+    generate_proof,
+    marshall::{unmarshall_params, unmarshall_pk},
+    new_account::NewAccountProverKnowledge,
+    withdraw::WithdrawProverKnowledge,
+    Fr, ProverKnowledge,
+};
+use shielder_setup::vk_fingerprint::{check_vk_fingerprint, DeployedCircuit, VkFingerprint};
+use type_conversions::{bytes_to_field, field_to_bytes};
+
+pub mod artifacts;
+pub mod deposit;
+pub mod new_account;
+pub mod withdraw;
+
+#[derive(Clone, Debug)]
+pub struct Circuit<PK: ProverKnowledge> {
+    params: Params,
+    pk: ProvingKey,
+    vk_fingerprint: VkFingerprint,
+    _phantom: PhantomData<PK>,
+}
+
+impl<PK: ProverKnowledge> Circuit<PK> {
+    /// Create a new circuit with hardcoded keys, which is faster than generating new keys.
+    ///
+    /// Panics if the verifying key doesn't match the current contract version.
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self
+    where
+        PK::Circuit: DeployedCircuit,
+    {
+        let params = unmarshall_params(params_buf).expect("Failed to unmarshall params");
+        let (_, pk) =
+            unmarshall_pk::<PK::Circuit>(pk_buf).expect("Failed to unmarshall proving key");
+        let vk_fingerprint = check_vk_fingerprint(PK::Circuit::CIRCUIT, pk.get_vk())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        Circuit {
+            params,
+            pk,
+            vk_fingerprint,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Fingerprint of the verifying key, see [`shielder_setup::vk_fingerprint`].
+    pub fn vk_fingerprint(&self) -> VkFingerprint {
+        self.vk_fingerprint
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    pub fn pk(&self) -> &ProvingKey {
+        &self.pk
+    }
+
+    pub fn prove(&self, values: &PK, rng: &mut impl RngCore) -> Vec<u8> {
+        generate_proof(
+            &self.params,
+            &self.pk,
+            values.create_circuit(),
+            &values.serialize_public_input(),
+            rng,
+        )
+    }
+}
+
+pub type DepositCircuit = Circuit<DepositProverKnowledge<Fr>>;
+pub type NewAccountCircuit = Circuit<NewAccountProverKnowledge<Fr>>;
+pub type WithdrawCircuit = Circuit<WithdrawProverKnowledge<Fr>>;
+
+pub fn vec_to_f(v: Vec<u8>) -> Fr {
+    bytes_to_field(v).expect("failed to convert to F")
+}
+
+pub fn vec_to_path<const TREE_HEIGHT: usize>(v: Vec<u8>) -> [[Fr; ARITY]; TREE_HEIGHT] {
+    assert_eq!(
+        TREE_HEIGHT * ARITY * Fr::size(),
+        v.len(),
+        "Vector length must be divisible by TREE_HEIGHT * ARITY * F::size()"
+    );
+
+    let mut result = [[Fr::default(); ARITY]; TREE_HEIGHT];
+    let mut iter = v.chunks_exact(Fr::size());
+
+    for row in result.iter_mut().take(TREE_HEIGHT) {
+        for elem in row.iter_mut().take(ARITY) {
+            if let Some(chunk) = iter.next() {
+                *elem = Fr::from_bytes(
+                    chunk
+                        .try_into()
+                        .unwrap_or_else(|_| panic!("should be {} bytes long", Fr::size())),
+                )
+                .expect("failed to convert to F");
+            }
+        }
+    }
+
+    result
+}
+
+/// Inverse of [`vec_to_path`].
+pub fn path_to_vec<const TREE_HEIGHT: usize>(path: &[[Fr; ARITY]; TREE_HEIGHT]) -> Vec<u8> {
+    path.iter()
+        .flatten()
+        .flat_map(|elem| field_to_bytes(*elem))
+        .collect()
+}
+
+/// A circuit that can be proven from serialized (byte-encoded) inputs.
+pub trait SerializableCircuit {
+    type Input: Serialize + for<'de> Deserialize<'de> + Clone;
+    type Output: Serialize + for<'de> Deserialize<'de>;
+
+    fn prove(&self, input: Self::Input) -> Vec<u8>;
+
+    fn pub_inputs(input: Self::Input) -> Self::Output;
+}
@@ -1,14 +1,13 @@
-use std::vec::Vec;
-
 use serde::{Deserialize, Serialize};
 use shielder_circuits::{
-    field_element_to_le_bits,
+    field_element_to_le_bits, le_bits_to_field_element,
     new_account::{NewAccountInstance, NewAccountProverKnowledge},
     Fr, GrumpkinPointAffine, PublicInputProvider,
 };
+use shielder_setup::vk_fingerprint::VkFingerprint;
 use type_conversions::field_to_bytes;
 
-use crate::circuits::{vec_to_f, SerializableCircuit};
+use crate::{path_to_vec, vec_to_f, vec_to_path, SerializableCircuit};
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct NewAccountPubInputsBytes {
@@ -25,6 +24,7 @@ pub struct NewAccountPubInputsBytes {
     pub sym_key_encryption_2_y: Vec<u8>,
     pub mac_salt: Vec<u8>,
     pub mac_commitment: Vec<u8>,
+    pub token_list_root: Vec<u8>,
 }
 
 impl From<NewAccountProverKnowledge<Fr>> for NewAccountPubInputsBytes {
@@ -67,6 +67,9 @@ impl From<NewAccountProverKnowledge<Fr>> for NewAccountPubInputsBytes {
             mac_commitment: field_to_bytes(
                 knowledge.compute_public_input(NewAccountInstance::MacCommitment),
             ),
+            token_list_root: field_to_bytes(
+                knowledge.compute_public_input(NewAccountInstance::TokenListRoot),
+            ),
         }
     }
 }
@@ -75,11 +78,14 @@ impl From<NewAccountProverKnowledge<Fr>> for NewAccountPubInputsBytes {
 pub struct NewAccountCircuit(super::NewAccountCircuit);
 
 impl NewAccountCircuit {
-    pub fn new() -> Self {
-        NewAccountCircuit(super::NewAccountCircuit::new_pronto(
-            include_bytes!("../../artifacts/new_account/params.bin"),
-            include_bytes!("../../artifacts/new_account/pk.bin"),
-        ))
+    /// Create the circuit from embedded `params.bin` and `pk.bin` artifacts.
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        NewAccountCircuit(super::NewAccountCircuit::new_pronto(params_buf, pk_buf))
+    }
+
+    /// Fingerprint of the verifying key, see [`shielder_setup::vk_fingerprint`].
+    pub fn vk_fingerprint(&self) -> VkFingerprint {
+        self.0.vk_fingerprint()
     }
 }
 
@@ -94,6 +100,30 @@ pub struct NewAccountProveInputsBytes {
     mac_salt: Vec<u8>,
     anonymity_revoker_public_key_x: Vec<u8>,
     anonymity_revoker_public_key_y: Vec<u8>,
+    token_list_path: Vec<u8>,
+}
+
+impl From<&NewAccountProverKnowledge<Fr>> for NewAccountProveInputsBytes {
+    fn from(knowledge: &NewAccountProverKnowledge<Fr>) -> Self {
+        NewAccountProveInputsBytes {
+            id: field_to_bytes(knowledge.id),
+            nullifier: field_to_bytes(knowledge.nullifier),
+            initial_deposit: field_to_bytes(knowledge.initial_deposit),
+            commitment: field_to_bytes(knowledge.commitment),
+            token_address: field_to_bytes(knowledge.token_address),
+            encryption_salt: field_to_bytes(le_bits_to_field_element::<Fr>(
+                &knowledge.encryption_salt,
+            )),
+            mac_salt: field_to_bytes(knowledge.mac_salt),
+            anonymity_revoker_public_key_x: field_to_bytes(
+                knowledge.anonymity_revoker_public_key.x,
+            ),
+            anonymity_revoker_public_key_y: field_to_bytes(
+                knowledge.anonymity_revoker_public_key.y,
+            ),
+            token_list_path: path_to_vec(&knowledge.token_list_path),
+        }
+    }
 }
 
 impl SerializableCircuit for NewAccountCircuit {
@@ -116,6 +146,7 @@ impl SerializableCircuit for NewAccountCircuit {
                     x: vec_to_f(new_account_bytes.anonymity_revoker_public_key_x),
                     y: vec_to_f(new_account_bytes.anonymity_revoker_public_key_y),
                 },
+                token_list_path: vec_to_path(new_account_bytes.token_list_path),
             },
             &mut rand::thread_rng(),
         )
@@ -138,6 +169,7 @@ impl SerializableCircuit for NewAccountCircuit {
                 x: vec_to_f(new_account_prove_inputs_bytes.anonymity_revoker_public_key_x),
                 y: vec_to_f(new_account_prove_inputs_bytes.anonymity_revoker_public_key_y),
             },
+            token_list_path: vec_to_path(new_account_prove_inputs_bytes.token_list_path),
         };
 
         knowledge.into()
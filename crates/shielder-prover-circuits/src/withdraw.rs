@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use shielder_circuits::{
+    empty_association_path,
+    withdraw::{WithdrawInstance, WithdrawProverKnowledge},
+    Field, Fr, PublicInputProvider,
+};
+use shielder_setup::vk_fingerprint::VkFingerprint;
+use type_conversions::field_to_bytes;
+
+use crate::{path_to_vec, vec_to_f, vec_to_path, SerializableCircuit};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WithdrawPubInputsBytes {
+    pub merkle_root: Vec<u8>,
+    pub h_nullifier_old: Vec<u8>,
+    pub h_note_new: Vec<u8>,
+    pub withdrawal_value: Vec<u8>,
+    pub token_address: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub mac_salt: Vec<u8>,
+    pub mac_commitment: Vec<u8>,
+    pub association_set_root: Vec<u8>,
+}
+
+impl From<WithdrawProverKnowledge<Fr>> for WithdrawPubInputsBytes {
+    fn from(knowledge: WithdrawProverKnowledge<Fr>) -> Self {
+        WithdrawPubInputsBytes {
+            merkle_root: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::MerkleRoot),
+            ),
+            h_nullifier_old: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::HashedOldNullifier),
+            ),
+            h_note_new: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::HashedNewNote),
+            ),
+            withdrawal_value: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::WithdrawalValue),
+            ),
+            token_address: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::TokenAddress),
+            ),
+            commitment: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::Commitment),
+            ),
+            mac_salt: field_to_bytes(knowledge.compute_public_input(WithdrawInstance::MacSalt)),
+            mac_commitment: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::MacCommitment),
+            ),
+            association_set_root: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::AssociationSetRoot),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithdrawCircuit(super::WithdrawCircuit);
+
+impl WithdrawCircuit {
+    /// Create the circuit from embedded `params.bin` and `pk.bin` artifacts.
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        WithdrawCircuit(super::WithdrawCircuit::new_pronto(params_buf, pk_buf))
+    }
+
+    /// Fingerprint of the verifying key, see [`shielder_setup::vk_fingerprint`].
+    pub fn vk_fingerprint(&self) -> VkFingerprint {
+        self.0.vk_fingerprint()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WithdrawProveInputsBytes {
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance_old: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    value: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    mac_salt: Vec<u8>,
+    /// Empty if no association set is claimed.
+    #[serde(default)]
+    association_set_root: Vec<u8>,
+    /// Empty if no association set is claimed.
+    #[serde(default)]
+    association_path: Vec<u8>,
+}
+
+impl From<WithdrawProveInputsBytes> for WithdrawProverKnowledge<Fr> {
+    fn from(inputs: WithdrawProveInputsBytes) -> Self {
+        let id = vec_to_f(inputs.id);
+        let (association_set_root, association_path) = match inputs.association_set_root.is_empty()
+        {
+            true => (Fr::ZERO, empty_association_path(id)),
+            false => (
+                vec_to_f(inputs.association_set_root),
+                vec_to_path(inputs.association_path),
+            ),
+        };
+
+        WithdrawProverKnowledge {
+            id,
+            nullifier_old: vec_to_f(inputs.nullifier_old),
+            account_old_balance: vec_to_f(inputs.account_balance_old),
+            token_address: vec_to_f(inputs.token_address),
+            path: vec_to_path(inputs.path),
+            withdrawal_value: vec_to_f(inputs.value),
+            nullifier_new: vec_to_f(inputs.nullifier_new),
+            commitment: vec_to_f(inputs.commitment),
+            mac_salt: vec_to_f(inputs.mac_salt),
+            association_set_root,
+            association_path,
+        }
+    }
+}
+
+impl From<&WithdrawProverKnowledge<Fr>> for WithdrawProveInputsBytes {
+    fn from(knowledge: &WithdrawProverKnowledge<Fr>) -> Self {
+        let (association_set_root, association_path) =
+            match knowledge.association_set_root == Fr::ZERO {
+                true => (vec![], vec![]),
+                false => (
+                    field_to_bytes(knowledge.association_set_root),
+                    path_to_vec(&knowledge.association_path),
+                ),
+            };
+
+        WithdrawProveInputsBytes {
+            id: field_to_bytes(knowledge.id),
+            nullifier_old: field_to_bytes(knowledge.nullifier_old),
+            account_balance_old: field_to_bytes(knowledge.account_old_balance),
+            token_address: field_to_bytes(knowledge.token_address),
+            path: path_to_vec(&knowledge.path),
+            value: field_to_bytes(knowledge.withdrawal_value),
+            nullifier_new: field_to_bytes(knowledge.nullifier_new),
+            commitment: field_to_bytes(knowledge.commitment),
+            mac_salt: field_to_bytes(knowledge.mac_salt),
+            association_set_root,
+            association_path,
+        }
+    }
+}
+
+impl SerializableCircuit for WithdrawCircuit {
+    type Input = WithdrawProveInputsBytes;
+    type Output = WithdrawPubInputsBytes;
+
+    fn prove(&self, withdraw_prove_inputs_bytes: WithdrawProveInputsBytes) -> Vec<u8> {
+        self.0
+            .prove(&withdraw_prove_inputs_bytes.into(), &mut rand::thread_rng())
+    }
+
+    fn pub_inputs(withdraw_prove_inputs_bytes: WithdrawProveInputsBytes) -> WithdrawPubInputsBytes {
+        WithdrawProverKnowledge::from(withdraw_prove_inputs_bytes).into()
+    }
+}
+
+pub type SerializableWithdrawCircuit = WithdrawCircuit;
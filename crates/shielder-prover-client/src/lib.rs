@@ -0,0 +1,398 @@
+//! Client of the TEE prover server (`tee/crates/shielder-prover-server`).
+//!
+//! The server exposes two endpoints:
+//! - `GET /public_key` returns the public key of the TEE with its attestation document,
+//! - `POST /proof` takes `{"payload": <base64>}`, where the payload is a JSON-encoded
+//!   [`RequestGenerateProofPayload`] encrypted to the TEE public key, and returns the proof and
+//!   the public inputs encrypted to the user public key sent in the request.
+//!
+//! Requests are padded to [`REQUEST_PAYLOAD_PADDING`] bytes and responses to
+//! [`RESPONSE_PAYLOAD_PADDING`] bytes before encryption, so that they don't reveal which circuit is
+//! being proven. A fresh (ephemeral) user key is generated for every proof.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ecies_encryption_lib::{
+    decrypt_padded, encrypt_padded, generate_keypair, utils::from_hex, PubKey,
+};
+use nitro_attestation::AttestationError;
+pub use nitro_attestation::{Pcrs, Policy, PCR_LENGTH};
+use serde::{Deserialize, Serialize};
+use shielder_prover_circuits::{
+    deposit::SerializableDepositCircuit, new_account::SerializableNewAccountCircuit,
+    withdraw::SerializableWithdrawCircuit, SerializableCircuit,
+};
+use shielder_prover_common::base64_serialization;
+pub use shielder_prover_common::protocol::{
+    CircuitType, RequestGenerateProofPayload, Response, ResponseGenerateProofPayload,
+    REQUEST_PAYLOAD_PADDING, RESPONSE_PAYLOAD_PADDING,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TeeProverClientError {
+    #[error("Request to the TEE prover failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Unexpected response from the TEE prover: {0}")]
+    UnexpectedResponse(String),
+    #[error("Invalid TEE public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("TEE public key is not attested: {0}")]
+    Attestation(#[from] AttestationError),
+    #[error("Encryption failed: {0}")]
+    Encryption(String),
+    #[error("Public inputs returned by the TEE prover don't match the request")]
+    PubInputsMismatch,
+}
+
+/// A circuit that the TEE prover server can prove.
+pub trait TeeCircuit: SerializableCircuit {
+    const CIRCUIT_TYPE: CircuitType;
+}
+
+impl TeeCircuit for SerializableNewAccountCircuit {
+    const CIRCUIT_TYPE: CircuitType = CircuitType::NewAccount;
+}
+
+impl TeeCircuit for SerializableDepositCircuit {
+    const CIRCUIT_TYPE: CircuitType = CircuitType::Deposit;
+}
+
+impl TeeCircuit for SerializableWithdrawCircuit {
+    const CIRCUIT_TYPE: CircuitType = CircuitType::Withdraw;
+}
+
+/// Public key of the TEE, as returned by `GET /public_key`.
+#[derive(Clone, Debug)]
+pub struct TeePublicKey {
+    pub public_key: Vec<u8>,
+    /// Empty if the server runs without attestation.
+    pub attestation_document: Vec<u8>,
+}
+
+/// Proof generated by the TEE, with the public inputs it computed.
+#[derive(Clone, Debug)]
+pub struct TeeProof<PubInputs> {
+    pub proof: Vec<u8>,
+    pub pub_inputs: PubInputs,
+}
+
+/// Body of `POST /proof`.
+#[derive(Serialize, Deserialize)]
+struct GenerateProofPayload {
+    #[serde(with = "base64_serialization")]
+    payload: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TeeProverClient {
+    url: String,
+    http: reqwest::Client,
+    attestation_policy: Option<Policy>,
+    public_key: Arc<Mutex<Option<TeePublicKey>>>,
+}
+
+impl TeeProverClient {
+    /// Client accepting the public key only if its attestation document satisfies `policy`.
+    pub fn new(url: impl Into<String>, policy: Policy) -> Self {
+        Self::with_policy(url.into(), Some(policy))
+    }
+
+    /// Client trusting any public key returned by the server, so whoever runs it can read the
+    /// circuit inputs. Only for servers built without attestation, e.g. in local tests.
+    pub fn new_insecure_unattested(url: impl Into<String>) -> Self {
+        Self::with_policy(url.into(), None)
+    }
+
+    fn with_policy(url: String, attestation_policy: Option<Policy>) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            attestation_policy,
+            public_key: Default::default(),
+        }
+    }
+
+    /// Public key of the TEE. It is fetched (and its attestation verified) on first use and
+    /// cached, clones of the client share the cache.
+    pub async fn public_key(&self) -> Result<TeePublicKey, TeeProverClientError> {
+        let cached = self.cached_public_key().clone();
+        if let Some(public_key) = cached {
+            return Ok(public_key);
+        }
+        let public_key = self.fetch_public_key().await?;
+        *self.cached_public_key() = Some(public_key.clone());
+        Ok(public_key)
+    }
+
+    /// Forget the cached public key, so that it is fetched again before the next proof. A TEE
+    /// generates a new key whenever it restarts.
+    pub fn forget_public_key(&self) {
+        *self.cached_public_key() = None;
+    }
+
+    /// Prove `inputs` with the TEE and check that the public inputs it returns are the ones
+    /// computed locally from `inputs`.
+    pub async fn generate_proof<C: TeeCircuit>(
+        &self,
+        inputs: C::Input,
+    ) -> Result<TeeProof<C::Output>, TeeProverClientError> {
+        let expected_pub_inputs = serde_json::to_value(C::pub_inputs(inputs.clone()))
+            .expect("public inputs should serialize");
+        let circuit_inputs = serde_json::to_vec(&inputs).expect("circuit inputs should serialize");
+
+        let response = self
+            .generate_proof_raw(C::CIRCUIT_TYPE, circuit_inputs)
+            .await?;
+
+        let pub_inputs: C::Output = serde_json::from_slice(&response.pub_inputs)
+            .map_err(|e| TeeProverClientError::UnexpectedResponse(e.to_string()))?;
+        if serde_json::to_value(&pub_inputs).expect("public inputs should serialize")
+            != expected_pub_inputs
+        {
+            return Err(TeeProverClientError::PubInputsMismatch);
+        }
+        Ok(TeeProof {
+            proof: response.proof,
+            pub_inputs,
+        })
+    }
+
+    /// Prove JSON-encoded `circuit_inputs` of `circuit_type`. The public inputs are not checked,
+    /// see `generate_proof`.
+    pub async fn generate_proof_raw(
+        &self,
+        circuit_type: CircuitType,
+        circuit_inputs: Vec<u8>,
+    ) -> Result<ResponseGenerateProofPayload, TeeProverClientError> {
+        let result = self.request_proof(circuit_type, circuit_inputs).await;
+        if result.is_err() {
+            // The TEE might have been restarted with a new key.
+            self.forget_public_key();
+        }
+        result
+    }
+
+    async fn request_proof(
+        &self,
+        circuit_type: CircuitType,
+        circuit_inputs: Vec<u8>,
+    ) -> Result<ResponseGenerateProofPayload, TeeProverClientError> {
+        let tee_public_key = PubKey::from_bytes(&self.public_key().await?.public_key)
+            .map_err(|e| TeeProverClientError::InvalidPublicKey(e.to_string()))?;
+        let (user_private_key, user_public_key) = generate_keypair();
+
+        let request = serde_json::to_vec(&RequestGenerateProofPayload {
+            circuit_type,
+            user_public_key: user_public_key.to_bytes(),
+            circuit_inputs,
+        })
+        .expect("request should serialize");
+        let payload = encrypt_padded(&request, &tee_public_key, REQUEST_PAYLOAD_PADDING)
+            .map_err(|e| TeeProverClientError::Encryption(e.to_string()))?;
+
+        let response = self
+            .http
+            .post(format!("{}/proof", self.url))
+            .json(&GenerateProofPayload { payload })
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Response>()
+            .await?;
+        let Response::EncryptedProof { payload } = response else {
+            return Err(TeeProverClientError::UnexpectedResponse(format!(
+                "{response:?}"
+            )));
+        };
+
+        let response = decrypt_padded(&payload, &user_private_key, RESPONSE_PAYLOAD_PADDING)
+            .map_err(|e| TeeProverClientError::Encryption(e.to_string()))?;
+        serde_json::from_slice(&response)
+            .map_err(|e| TeeProverClientError::UnexpectedResponse(e.to_string()))
+    }
+
+    async fn fetch_public_key(&self) -> Result<TeePublicKey, TeeProverClientError> {
+        let response = self
+            .http
+            .get(format!("{}/public_key", self.url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Response>()
+            .await?;
+        let Response::TeePublicKey {
+            public_key,
+            attestation_document,
+        } = response
+        else {
+            return Err(TeeProverClientError::UnexpectedResponse(format!(
+                "{response:?}"
+            )));
+        };
+
+        let public_key = from_hex(&public_key)
+            .map_err(|e| TeeProverClientError::InvalidPublicKey(e.to_string()))?;
+        if let Some(policy) = &self.attestation_policy {
+            nitro_attestation::verify(&attestation_document, &public_key, policy, now())?;
+        }
+        Ok(TeePublicKey {
+            public_key,
+            attestation_document,
+        })
+    }
+
+    fn cached_public_key(&self) -> std::sync::MutexGuard<'_, Option<TeePublicKey>> {
+        self.public_key
+            .lock()
+            .expect("public key cache should not be poisoned")
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after the Unix epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use axum::{
+        extract::State,
+        routing::{get, post},
+        Json, Router,
+    };
+    use ecies_encryption_lib::{
+        decrypt_padded, encrypt_padded, generate_keypair, utils::to_hex, PrivKey, PubKey,
+    };
+    use rand::rngs::OsRng;
+    use shielder_circuits::{new_account::NewAccountProverKnowledge, Fr, ProverKnowledge};
+    use shielder_prover_circuits::{
+        new_account::{NewAccountProveInputsBytes, SerializableNewAccountCircuit},
+        SerializableCircuit,
+    };
+
+    use super::*;
+
+    const PROOF: [u8; 3] = [1, 2, 3];
+
+    /// TEE prover that returns a fixed proof and the public inputs of the request.
+    struct MockTee {
+        private_key: Vec<u8>,
+        public_key: Vec<u8>,
+        public_key_requests: AtomicUsize,
+        tamper_pub_inputs: bool,
+    }
+
+    async fn public_key(State(tee): State<Arc<MockTee>>) -> Json<Response> {
+        tee.public_key_requests.fetch_add(1, Ordering::SeqCst);
+        Json(Response::TeePublicKey {
+            public_key: to_hex(&tee.public_key),
+            attestation_document: vec![],
+        })
+    }
+
+    async fn proof(
+        State(tee): State<Arc<MockTee>>,
+        Json(request): Json<GenerateProofPayload>,
+    ) -> Json<Response> {
+        let private_key = PrivKey::from_bytes(&tee.private_key).unwrap();
+        let request = decrypt_padded(&request.payload, &private_key, REQUEST_PAYLOAD_PADDING)
+            .expect("request should be padded and encrypted to the TEE key");
+        let request: RequestGenerateProofPayload = serde_json::from_slice(&request).unwrap();
+        assert!(matches!(request.circuit_type, CircuitType::NewAccount));
+
+        let inputs: NewAccountProveInputsBytes =
+            serde_json::from_slice(&request.circuit_inputs).unwrap();
+        let mut pub_inputs = SerializableNewAccountCircuit::pub_inputs(inputs);
+        if tee.tamper_pub_inputs {
+            pub_inputs.hashed_note = vec![0; 32];
+        }
+
+        let response = serde_json::to_vec(&ResponseGenerateProofPayload {
+            proof: PROOF.to_vec(),
+            pub_inputs: serde_json::to_vec(&pub_inputs).unwrap(),
+        })
+        .unwrap();
+        let user_public_key = PubKey::from_bytes(&request.user_public_key).unwrap();
+        Json(Response::EncryptedProof {
+            payload: encrypt_padded(&response, &user_public_key, RESPONSE_PAYLOAD_PADDING).unwrap(),
+        })
+    }
+
+    async fn start_mock_tee(tamper_pub_inputs: bool) -> (SocketAddr, Arc<MockTee>) {
+        let (private_key, public_key) = generate_keypair();
+        let tee = Arc::new(MockTee {
+            private_key: private_key.to_bytes(),
+            public_key: public_key.to_bytes(),
+            public_key_requests: AtomicUsize::new(0),
+            tamper_pub_inputs,
+        });
+        let app = Router::new()
+            .route("/public_key", get(public_key))
+            .route("/proof", post(proof))
+            .with_state(tee.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (address, tee)
+    }
+
+    fn inputs() -> NewAccountProveInputsBytes {
+        let knowledge = NewAccountProverKnowledge::<Fr>::random_correct_example(&mut OsRng);
+        NewAccountProveInputsBytes::from(&knowledge)
+    }
+
+    #[tokio::test]
+    async fn proves_with_cached_public_key() {
+        let (address, tee) = start_mock_tee(false).await;
+        let client = TeeProverClient::new_insecure_unattested(format!("http://{address}/"));
+
+        for _ in 0..2 {
+            let proof = client
+                .generate_proof::<SerializableNewAccountCircuit>(inputs())
+                .await
+                .unwrap();
+            assert_eq!(proof.proof, PROOF);
+        }
+        assert_eq!(tee.public_key_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_mismatched_pub_inputs() {
+        let (address, _) = start_mock_tee(true).await;
+        let client = TeeProverClient::new_insecure_unattested(format!("http://{address}"));
+
+        let result = client
+            .generate_proof::<SerializableNewAccountCircuit>(inputs())
+            .await;
+        assert!(matches!(
+            result,
+            Err(TeeProverClientError::PubInputsMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_unattested_public_key() {
+        let (address, tee) = start_mock_tee(false).await;
+        let client = TeeProverClient::new(format!("http://{address}"), Policy::aws(vec![]));
+
+        let result = client
+            .generate_proof::<SerializableNewAccountCircuit>(inputs())
+            .await;
+        assert!(matches!(result, Err(TeeProverClientError::Attestation(_))));
+        assert_eq!(tee.public_key_requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -90,6 +90,15 @@ fi
 if [[ -n "${MAX_POCKET_MONEY:-}" ]]; then
   ARGS+=(-e MAX_POCKET_MONEY="${MAX_POCKET_MONEY}")
 fi
+if [[ -n "${RELAY_JOURNAL_PATH:-}" ]]; then
+  ARGS+=(-e RELAY_JOURNAL_PATH="${RELAY_JOURNAL_PATH}")
+fi
+if [[ -n "${FALLBACK_NODE_RPC_URLS:-}" ]]; then
+  ARGS+=(-e FALLBACK_NODE_RPC_URLS="${FALLBACK_NODE_RPC_URLS}")
+fi
+if [[ -n "${RPC_QUORUM:-}" ]]; then
+  ARGS+=(-e RPC_QUORUM="${RPC_QUORUM}")
+fi
 
 DETACHED_FLAG=""
 if [[ "${DETACHED:-}" == "true" ]]; then
@@ -71,6 +71,26 @@ pub struct CLIConfig {
     )]
     pub node_rpc_url: Option<String>,
 
+    #[clap(
+        long,
+        help = "URLs of fallback Ethereum RPC nodes.",
+        long_help = format!("URLs of fallback Ethereum RPC nodes, used when the main node is \
+            unavailable. If not provided, the comma-separated value from the environment variable \
+            `{FALLBACK_NODE_RPC_URLS_ENV}` will be used. If that is not set, no fallback is used."),
+        num_args = 1..
+    )]
+    pub fallback_node_rpc_urls: Option<Vec<String>>,
+
+    #[clap(
+        long,
+        help = "Number of RPC nodes that must agree on a read.",
+        long_help = format!("Number of RPC nodes that must agree on the result of a contract read \
+            before it is trusted. If not provided, the value from the environment variable \
+            `{RPC_QUORUM_ENV}` will be used. If that is not set, reads are served by a single \
+            node.")
+    )]
+    pub rpc_quorum: Option<usize>,
+
     #[clap(
         long,
         help = "Address of the Shielder contract.",
@@ -212,6 +232,15 @@ pub struct CLIConfig {
         value_parser = parsing::parse_u256
     )]
     pub max_pocket_money: Option<U256>,
+
+    #[clap(
+        long,
+        help = "Path to the SQLite journal of relay requests.",
+        long_help = format!("Path to the SQLite journal of relay requests. If not provided, the \
+            value from the environment variable `{RELAY_JOURNAL_PATH_ENV}` will be used. If that is \
+            not set, the default value is `{DEFAULT_RELAY_JOURNAL_PATH}`.")
+    )]
+    pub relay_journal_path: Option<String>,
 }
 
 pub(super) mod parsing {
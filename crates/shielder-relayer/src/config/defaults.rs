@@ -19,3 +19,4 @@ pub const DEFAULT_PRICE_FEED_REFRESH_INTERVAL: Duration = Duration::from_secs(60
 pub const DEFAULT_SERVICE_FEE_PERCENT: u32 = 15;
 pub const DEFAULT_QUOTE_VALIDITY: Duration = Duration::from_secs(15);
 pub const DEFAULT_MAX_POCKET_MONEY: &str = "100_000_000_000_000_000"; // 0.1 TZERO
+pub const DEFAULT_RELAY_JOURNAL_PATH: &str = "relay-journal.sqlite";
@@ -9,7 +9,11 @@ use clap::Parser;
 use cli::CLIConfig;
 use defaults::*;
 pub use enums::{DryRunning, LoggingFormat, NoncePolicy};
-use shielder_contract::alloy_primitives::{Address, U256};
+use shielder_contract::{
+    alloy_primitives::{Address, U256},
+    providers::RpcEndpoints,
+    ContractResult,
+};
 use shielder_relayer::*;
 
 use crate::config::cli::parsing::{parse_seconds, parse_u256};
@@ -40,10 +44,23 @@ impl NetworkConfig {
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct ChainConfig {
     pub node_rpc_url: String,
+    pub fallback_node_rpc_urls: Vec<String>,
+    pub rpc_quorum: Option<usize>,
     pub shielder_contract_address: Address,
     pub relay_gas: u64,
 }
 
+impl ChainConfig {
+    /// The main node followed by the fallback ones.
+    pub fn node_rpc(&self) -> ContractResult<RpcEndpoints> {
+        RpcEndpoints::new(
+            self.node_rpc_url.clone(),
+            self.fallback_node_rpc_urls.clone(),
+            self.rpc_quorum,
+        )
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct OperationalConfig {
     pub balance_monitor_interval: Duration,
@@ -58,6 +75,7 @@ pub struct OperationalConfig {
     pub service_fee_percent: u32,
     pub quote_validity: Duration,
     pub max_pocket_money: U256,
+    pub relay_journal_path: String,
 }
 
 #[derive(Clone, Eq, PartialEq)]
@@ -112,6 +130,8 @@ fn resolve_config_from_cli_config(
         metrics_port,
         balance_monitor_interval,
         node_rpc_url,
+        fallback_node_rpc_urls,
+        rpc_quorum,
         shielder_contract_address,
         fee_destination_key,
         signing_keys,
@@ -126,6 +146,7 @@ fn resolve_config_from_cli_config(
         service_fee_percent,
         quote_validity,
         max_pocket_money,
+        relay_journal_path,
     }: CLIConfig,
 ) -> ServerConfig {
     let to_address = |s: &str| Address::from_str(s).expect("Invalid address");
@@ -153,8 +174,21 @@ fn resolve_config_from_cli_config(
         ),
     };
 
+    let fallback_node_rpc_urls = fallback_node_rpc_urls.unwrap_or_else(|| {
+        std::env::var(FALLBACK_NODE_RPC_URLS_ENV)
+            .map(|urls| urls.split(',').map(|s| s.to_string()).collect())
+            .unwrap_or_default()
+    });
+    let rpc_quorum = rpc_quorum.or_else(|| {
+        std::env::var(RPC_QUORUM_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+    });
+
     let chain_config = ChainConfig {
         node_rpc_url: resolve_value(node_rpc_url, NODE_RPC_URL_ENV, None),
+        fallback_node_rpc_urls,
+        rpc_quorum,
         shielder_contract_address: to_address(&resolve_value(
             shielder_contract_address,
             SHIELDER_CONTRACT_ADDRESS_ENV,
@@ -225,6 +259,11 @@ fn resolve_config_from_cli_config(
             parse_u256,
             Some(parse_u256(DEFAULT_MAX_POCKET_MONEY).unwrap()),
         ),
+        relay_journal_path: resolve_value(
+            relay_journal_path,
+            RELAY_JOURNAL_PATH_ENV,
+            Some(DEFAULT_RELAY_JOURNAL_PATH.to_string()),
+        ),
     };
 
     ServerConfig {
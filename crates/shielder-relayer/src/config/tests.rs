@@ -20,6 +20,11 @@ fn config_resolution() {
     let metrics_port = 5678;
     let balance_monitor_interval = Duration::from_secs(60);
     let node_rpc_url = "http://localhost:8545".to_string();
+    let fallback_node_rpc_urls = vec![
+        "http://localhost:8546".to_string(),
+        "http://localhost:8547".to_string(),
+    ];
+    let rpc_quorum = Some(2);
     let shielder_contract_address = address!("0000000000000000000000000000000000000000");
     let fee_destination_key = "key0".to_string();
     let key1 = "key1".to_string();
@@ -48,6 +53,7 @@ fn config_resolution() {
     let service_fee_percent = DEFAULT_SERVICE_FEE_PERCENT;
     let quote_validity = Duration::from_secs(11);
     let max_pocket_money = U256::from(12);
+    let relay_journal_path = "/tmp/journal.sqlite".to_string();
 
     let expected_config = ServerConfig {
         logging_format, // from CLI
@@ -58,6 +64,8 @@ fn config_resolution() {
         },
         chain: ChainConfig {
             node_rpc_url: node_rpc_url.clone(), // from CLI
+            fallback_node_rpc_urls,             // from env
+            rpc_quorum,                         // from CLI
             shielder_contract_address,          // from CLI
             relay_gas,                          // from env
         },
@@ -74,6 +82,7 @@ fn config_resolution() {
             service_fee_percent,         // default
             quote_validity,              // from env
             max_pocket_money,            // from CLI
+            relay_journal_path,          // from env
         },
         keys: KeyConfig {
             fee_destination_key: fee_destination_key.clone(), // from env
@@ -89,6 +98,8 @@ fn config_resolution() {
         metrics_port: Some(metrics_port),
         balance_monitor_interval: None,
         node_rpc_url: Some(node_rpc_url),
+        fallback_node_rpc_urls: None,
+        rpc_quorum,
         shielder_contract_address: Some(shielder_contract_address.to_string()),
         fee_destination_key: None,
         signing_keys: None,
@@ -103,6 +114,7 @@ fn config_resolution() {
         service_fee_percent: None,
         quote_validity: None,
         max_pocket_money: Some(max_pocket_money),
+        relay_journal_path: None,
     };
 
     // ---- Environment variables. -----------------------------------------------------------
@@ -115,8 +127,13 @@ fn config_resolution() {
         std::env::set_var(FEE_DESTINATION_KEY_ENV, fee_destination_key);
         std::env::set_var(RELAYER_SIGNING_KEYS_ENV, format!("{key1},{key2}"));
         std::env::set_var(RELAY_GAS_ENV, relay_gas.to_string());
+        std::env::set_var(
+            FALLBACK_NODE_RPC_URLS_ENV,
+            "http://localhost:8546,http://localhost:8547",
+        );
         std::env::set_var(TOKEN_CONFIG_ENV, "[]");
         std::env::set_var(QUOTE_VALIDITY_ENV, "11");
+        std::env::set_var(RELAY_JOURNAL_PATH_ENV, "/tmp/journal.sqlite");
         std::env::set_var(
             TOKEN_CONFIG_ENV,
             "[
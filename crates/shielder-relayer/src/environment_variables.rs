@@ -7,6 +7,8 @@ pub const RPC_HEALTH_CACHE_VALIDITY_ENV: &str = "RPC_HEALTH_CACHE_VALIDITY";
 pub const FEE_DESTINATION_KEY_ENV: &str = "FEE_DESTINATION_KEY";
 pub const RELAYER_SIGNING_KEYS_ENV: &str = "RELAYER_SIGNING_KEYS";
 pub const NODE_RPC_URL_ENV: &str = "NODE_RPC_URL";
+pub const FALLBACK_NODE_RPC_URLS_ENV: &str = "FALLBACK_NODE_RPC_URLS";
+pub const RPC_QUORUM_ENV: &str = "RPC_QUORUM";
 pub const SHIELDER_CONTRACT_ADDRESS_ENV: &str = "SHIELDER_CONTRACT_ADDRESS";
 pub const NONCE_POLICY_ENV: &str = "NONCE_POLICY";
 pub const DRY_RUNNING_ENV: &str = "DRY_RUNNING";
@@ -19,3 +21,4 @@ pub const TOKEN_CONFIG_ENV: &str = "TOKEN_CONFIG";
 pub const SERVICE_FEE_PERCENT_ENV: &str = "SERVICE_FEE_PERCENT";
 pub const QUOTE_VALIDITY_ENV: &str = "QUOTE_VALIDITY";
 pub const MAX_POCKET_MONEY_ENV: &str = "MAX_POCKET_MONEY";
+pub const RELAY_JOURNAL_PATH_ENV: &str = "RELAY_JOURNAL_PATH";
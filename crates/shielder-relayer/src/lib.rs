@@ -1,7 +1,9 @@
+use std::str::FromStr;
+
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use shielder_contract::alloy_primitives::{Address, Bytes, FixedBytes, TxHash, U256};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 mod environment_variables;
 pub use environment_variables::*;
@@ -25,6 +27,150 @@ pub struct RelayResponse {
     pub tx_hash: TxHash,
 }
 
+/// How the `/relay` endpoint should respond.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RelayMode {
+    /// Wait until the transaction is submitted and return its hash (`RelayResponse`).
+    #[default]
+    Sync,
+    /// Return `202 Accepted` with a `RelayTicket` right away. The progress can be tracked with
+    /// `GET /relay/{id}`.
+    Async,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RelayModeQuery {
+    #[serde(default)]
+    #[param(inline)]
+    pub mode: RelayMode,
+}
+
+/// Handle to an asynchronously processed relay request.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct RelayTicket {
+    /// Ticket identifier (the nullifier hash of the withdrawal).
+    #[schema(value_type = String)]
+    pub id: U256,
+}
+
+/// Lifecycle of a relay request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelayStatus {
+    /// Accepted and waiting for a worker.
+    Queued,
+    /// Being dry-run by a worker.
+    DryRun,
+    /// Being sent to the network by a worker. The transaction hash is not known yet.
+    Submitting,
+    /// Transaction has been sent to the network.
+    Submitted,
+    /// Transaction has been successfully included in a block.
+    Mined,
+    /// Relaying failed. The request can be retried.
+    Failed,
+}
+
+impl RelayStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelayStatus::Queued => "queued",
+            RelayStatus::DryRun => "dry-run",
+            RelayStatus::Submitting => "submitting",
+            RelayStatus::Submitted => "submitted",
+            RelayStatus::Mined => "mined",
+            RelayStatus::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for RelayStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(RelayStatus::Queued),
+            "dry-run" => Ok(RelayStatus::DryRun),
+            "submitting" => Ok(RelayStatus::Submitting),
+            "submitted" => Ok(RelayStatus::Submitted),
+            "mined" => Ok(RelayStatus::Mined),
+            "failed" => Ok(RelayStatus::Failed),
+            other => Err(format!("Unknown relay status: {other}")),
+        }
+    }
+}
+
+/// Classification of relay failures.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelayErrorKind {
+    /// The Shielder contract rejected the withdrawal (e.g. invalid proof or spent nullifier).
+    /// Retrying the same request won't help.
+    ContractRevert,
+    /// The relayer signer was used concurrently. The request can be retried.
+    SignerConflict,
+    /// Dry run failed for a reason other than a contract revert.
+    DryRunFailed,
+    /// Sending the transaction failed for a reason other than a contract revert.
+    RelayFailed,
+    /// The transaction was included in a block, but reverted.
+    TransactionReverted,
+    /// The relay queue was full. The request can be retried.
+    QueueFull,
+    /// Relayer-side failure (e.g. restart during processing). The request can be retried.
+    Internal,
+}
+
+impl RelayErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelayErrorKind::ContractRevert => "contract-revert",
+            RelayErrorKind::SignerConflict => "signer-conflict",
+            RelayErrorKind::DryRunFailed => "dry-run-failed",
+            RelayErrorKind::RelayFailed => "relay-failed",
+            RelayErrorKind::TransactionReverted => "transaction-reverted",
+            RelayErrorKind::QueueFull => "queue-full",
+            RelayErrorKind::Internal => "internal",
+        }
+    }
+}
+
+impl FromStr for RelayErrorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "contract-revert" => Ok(RelayErrorKind::ContractRevert),
+            "signer-conflict" => Ok(RelayErrorKind::SignerConflict),
+            "dry-run-failed" => Ok(RelayErrorKind::DryRunFailed),
+            "relay-failed" => Ok(RelayErrorKind::RelayFailed),
+            "transaction-reverted" => Ok(RelayErrorKind::TransactionReverted),
+            "queue-full" => Ok(RelayErrorKind::QueueFull),
+            "internal" => Ok(RelayErrorKind::Internal),
+            other => Err(format!("Unknown relay error kind: {other}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub struct RelayError {
+    pub kind: RelayErrorKind,
+    pub message: String,
+}
+
+/// Current state of a relay request.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct RelayStatusResponse {
+    #[schema(value_type = String)]
+    pub id: U256,
+    pub status: RelayStatus,
+    #[schema(value_type = Option<String>)]
+    pub tx_hash: Option<TxHash>,
+    pub error: Option<RelayError>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
 pub struct RelayCalldata {
     #[schema(value_type = Object)]
@@ -49,6 +195,10 @@ pub struct RelayCalldata {
     pub mac_salt: U256,
     #[schema(value_type = String)]
     pub mac_commitment: U256,
+    /// Root of the association set claimed in the proof. Zero (the default) means no claim.
+    #[serde(default)]
+    #[schema(value_type = String)]
+    pub association_set_root: U256,
     #[schema(value_type = String)]
     pub pocket_money: U256,
     #[schema(value_type = Object)]
@@ -7,7 +7,7 @@ use axum::{middleware, routing::get, Router};
 use price_feed::{start_price_feed, Prices};
 use shielder_contract::{
     alloy_primitives::{Address, U256},
-    providers::{create_provider_with_nonce_caching_signer, create_provider_with_signer},
+    providers::RpcEndpoints,
     ConnectionPolicy, ShielderUser,
 };
 use shielder_relayer::TokenInfo;
@@ -31,7 +31,7 @@ use crate::{
     },
     quote_cache::{garbage_collector_worker, QuoteCache},
     recharge::{start_recharging_worker, try_recharging_relayer},
-    relay::Taskmaster,
+    relay::{RelayJournal, Taskmaster},
 };
 
 mod config;
@@ -47,9 +47,10 @@ mod relay;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub node_rpc_url: String,
+    pub node_rpc: RpcEndpoints,
     pub relay_gas: u64,
     pub taskmaster: Taskmaster,
+    pub journal: RelayJournal,
     pub signer_info: SignerInfo,
     pub rpc_monitor: RpcMonitor,
     pub prices: Prices,
@@ -81,7 +82,7 @@ async fn main() -> Result<()> {
     info!("Server configuration:\n{server_config:#?}",);
     let rpc_monitor = RpcMonitor::new(
         server_config.operations.rpc_health_cache_validity,
-        server_config.chain.node_rpc_url.clone(),
+        server_config.chain.node_rpc()?,
     )
     .await;
     let signer_info = get_signer_info(&server_config.keys)?;
@@ -93,7 +94,7 @@ async fn main() -> Result<()> {
 
     tokio::try_join!(
         balance_monitor(
-            &server_config.chain.node_rpc_url,
+            &server_config.chain.node_rpc()?,
             server_config.operations.balance_monitor_interval,
             signer_info.balances.clone(),
         ),
@@ -163,8 +164,10 @@ async fn start_main_server(
 ) -> Result<()> {
     let fee_destination = signer_info.fee_destination_key.clone();
 
+    let node_rpc = config.chain.node_rpc()?;
+
     ensure_signers_have_funds(
-        &config.chain.node_rpc_url,
+        &node_rpc,
         fee_destination.clone(),
         &signer_info,
         &config.operations,
@@ -172,18 +175,34 @@ async fn start_main_server(
     .await?;
 
     let report_for_recharge = start_recharging_worker(
-        config.chain.node_rpc_url.clone(),
+        node_rpc.clone(),
         fee_destination,
         &signer_info.signer_addresses,
         config.operations.recharge_threshold,
         config.operations.recharge_amount,
     );
 
+    let journal = RelayJournal::open(&config.operations.relay_journal_path)?;
+    info!(
+        "Relay journal opened at {}",
+        config.operations.relay_journal_path
+    );
+    let settling_user: ShielderUser = ShielderUser::new(
+        config.chain.shielder_contract_address,
+        ConnectionPolicy::OnDemandFailover {
+            rpc_endpoints: node_rpc.clone(),
+            signer: signer_info.fee_destination_key.clone(),
+        },
+    );
+    journal
+        .settle_interrupted_submissions(&node_rpc, &settling_user)
+        .await?;
+
     let quote_cache = QuoteCache::new(config.operations.quote_validity);
     tokio::spawn(garbage_collector_worker(quote_cache.clone()));
 
     let state = AppState {
-        node_rpc_url: config.chain.node_rpc_url.clone(),
+        node_rpc: node_rpc.clone(),
         relay_gas: config.chain.relay_gas,
         signer_info: signer_info.clone(),
         rpc_monitor,
@@ -196,7 +215,10 @@ async fn start_main_server(
             .await?,
             config.operations.dry_running,
             report_for_recharge,
+            journal.clone(),
+            node_rpc,
         ),
+        journal,
         token_config: config.operations.token_config.clone(),
         prices,
         quote_cache,
@@ -211,6 +233,7 @@ async fn start_main_server(
         .routes(routes!(info_endpoints::max_pocket_money))
         .routes(routes!(quote::quote_fees))
         .routes(routes!(relay::relay))
+        .routes(routes!(relay::relay_status))
         .with_state(state.clone())
         .route_layer(middleware::from_fn(metrics::request_metrics))
         .split_for_parts();
@@ -227,13 +250,13 @@ async fn start_main_server(
 }
 
 async fn ensure_signers_have_funds(
-    node_rpc_urk: &str,
+    node_rpc: &RpcEndpoints,
     cornucopia: PrivateKeySigner,
     signers: &SignerInfo,
     operational_config: &OperationalConfig,
 ) -> Result<()> {
     let cornucopia_address = cornucopia.address();
-    let provider = create_provider_with_signer(node_rpc_urk, cornucopia).await?;
+    let provider = node_rpc.provider_with_signer(cornucopia).await?;
     for relayer in &signers.signer_addresses {
         let relayer_balance = try_recharging_relayer(
             &provider,
@@ -289,17 +312,17 @@ async fn build_shielder_users(
     config: &ChainConfig,
     nonce_policy: NoncePolicy,
 ) -> Result<Vec<ShielderUser<impl Provider + Clone>>> {
+    let node_rpc = config.node_rpc()?;
     let mut shielder_users = vec![];
     for signer in signers {
         let policy = match nonce_policy {
             NoncePolicy::Caching => ConnectionPolicy::Keep {
                 caller_address: signer.address(),
-                provider: create_provider_with_nonce_caching_signer(&config.node_rpc_url, signer)
-                    .await?,
+                provider: node_rpc.provider_with_nonce_caching_signer(signer).await?,
             },
-            NoncePolicy::Stateless => ConnectionPolicy::OnDemand {
+            NoncePolicy::Stateless => ConnectionPolicy::OnDemandFailover {
+                rpc_endpoints: node_rpc.clone(),
                 signer,
-                rpc_url: config.node_rpc_url.clone(),
             },
         };
         shielder_users.push(ShielderUser::new(config.shielder_contract_address, policy));
@@ -2,7 +2,7 @@ use alloy_provider::Provider;
 use anyhow::Result;
 use shielder_contract::{
     alloy_primitives::{Address, U256},
-    providers::create_simple_provider,
+    providers::RpcEndpoints,
 };
 use tokio::time::{interval, Duration};
 use tracing::error;
@@ -11,11 +11,11 @@ use crate::monitor::Balances;
 
 /// Periodically check the balance of the relayer's signer addresses.
 pub async fn balance_monitor(
-    node_rpc_url: &str,
+    node_rpc: &RpcEndpoints,
     interval_duration: Duration,
     balances: Balances,
 ) -> Result<()> {
-    let provider = create_simple_provider(node_rpc_url).await?;
+    let provider = node_rpc.simple_provider().await?;
     let mut interval = interval(interval_duration);
 
     loop {
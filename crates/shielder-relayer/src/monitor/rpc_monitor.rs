@@ -6,7 +6,7 @@ use std::{
 
 use alloy_provider::Provider;
 use parking_lot::Mutex;
-use shielder_contract::providers::create_simple_provider;
+use shielder_contract::providers::RpcEndpoints;
 
 #[derive(Clone)]
 pub struct RpcMonitor {
@@ -14,11 +14,11 @@ pub struct RpcMonitor {
 }
 
 impl RpcMonitor {
-    pub async fn new(cache_validity: Duration, rpc_url: String) -> Self {
-        let is_healthy = healthy(&rpc_url).await;
+    pub async fn new(cache_validity: Duration, node_rpc: RpcEndpoints) -> Self {
+        let is_healthy = healthy(&node_rpc).await;
         Self {
             inner: Arc::new(Mutex::new(InnerRpcMonitor {
-                rpc_url,
+                node_rpc,
                 is_healthy,
                 last_check_started: Instant::now(),
                 cache_validity,
@@ -44,8 +44,8 @@ impl RpcMonitor {
     }
 
     async fn update_health(&self) {
-        let url = self.inner.lock().rpc_url.clone();
-        let is_healthy = healthy(&url).await;
+        let node_rpc = self.inner.lock().node_rpc.clone();
+        let is_healthy = healthy(&node_rpc).await;
         self.inner.lock().is_healthy = is_healthy;
     }
 
@@ -55,15 +55,15 @@ impl RpcMonitor {
 }
 
 struct InnerRpcMonitor {
-    rpc_url: String,
+    node_rpc: RpcEndpoints,
     is_healthy: Result<(), String>,
     last_check_started: Instant,
     cache_validity: Duration,
 }
 
 /// Check if the RPC node is reachable.
-async fn healthy_no_timeout(node_rpc_url: &str) -> Result<(), String> {
-    match create_simple_provider(node_rpc_url).await {
+async fn healthy_no_timeout(node_rpc: &RpcEndpoints) -> Result<(), String> {
+    match node_rpc.simple_provider().await {
         Ok(provider) => match provider.get_chain_id().await {
             Ok(_) => Ok(()),
             Err(err) => cannot_reach_rpc_node(err),
@@ -72,9 +72,9 @@ async fn healthy_no_timeout(node_rpc_url: &str) -> Result<(), String> {
     }
 }
 
-async fn healthy(node_rpc_url: &str) -> Result<(), String> {
+async fn healthy(node_rpc: &RpcEndpoints) -> Result<(), String> {
     let timeout_duration = Duration::from_secs(10);
-    match tokio::time::timeout(timeout_duration, healthy_no_timeout(node_rpc_url)).await {
+    match tokio::time::timeout(timeout_duration, healthy_no_timeout(node_rpc)).await {
         Ok(result) => result,
         Err(_) => cannot_reach_rpc_node("timeout while checking RPC node health"),
     }
@@ -1,7 +1,7 @@
 use alloy_provider::Provider;
 use axum::{extract::State, response::IntoResponse, Json};
 use shielder_account::Token;
-use shielder_contract::{alloy_primitives::U256, providers::create_simple_provider};
+use shielder_contract::alloy_primitives::U256;
 use shielder_relayer::{
     compute_fee,
     server::{server_error, success_response},
@@ -92,7 +92,9 @@ async fn _quote_fees(
 }
 
 async fn get_gas_price(app_state: &AppState) -> Result<u128, String> {
-    let provider = create_simple_provider(&app_state.node_rpc_url)
+    let provider = app_state
+        .node_rpc
+        .simple_provider()
         .await
         .map_err(|err| format!("Failed to create provider: {err}"))?;
 
@@ -4,13 +4,13 @@ use alloy_signer_local::PrivateKeySigner;
 use anyhow::{bail, Result};
 use shielder_contract::{
     alloy_primitives::{Address, U256},
-    providers::create_provider_with_signer,
+    providers::RpcEndpoints,
 };
 use tokio::sync::mpsc::{self, Receiver as MPSCReceiver, Sender as MPSCSender};
 use tracing::{error, info};
 
 pub fn start_recharging_worker(
-    node_rpc_url: String,
+    node_rpc: RpcEndpoints,
     cornucopia: PrivateKeySigner,
     relay_workers: &[Address],
     recharge_threshold: U256,
@@ -18,7 +18,7 @@ pub fn start_recharging_worker(
 ) -> MPSCSender<Address> {
     let (relay_report_sender, relay_report_receiver) = mpsc::channel(relay_workers.len());
     tokio::spawn(recharging_worker(
-        node_rpc_url,
+        node_rpc,
         cornucopia,
         relay_report_receiver,
         recharge_threshold,
@@ -29,14 +29,14 @@ pub fn start_recharging_worker(
 }
 
 async fn recharging_worker(
-    node_rpc_url: String,
+    node_rpc: RpcEndpoints,
     cornucopia: PrivateKeySigner,
     mut relay_reports: MPSCReceiver<Address>,
     recharge_threshold: U256,
     recharge_amount: U256,
 ) -> Result<()> {
     let cornucopia_address = cornucopia.address();
-    let provider = create_provider_with_signer(&node_rpc_url, cornucopia).await?;
+    let provider = node_rpc.provider_with_signer(cornucopia).await?;
     while let Some(relayer) = relay_reports.recv().await {
         if let Err(err) = try_recharging_relayer(
             &provider,
@@ -0,0 +1,508 @@
+//! Persistent journal of relay requests, keyed by the nullifier hash of the withdrawal.
+//!
+//! A nullifier hash can be spent only once, so it is a natural idempotency key: if a client
+//! repeats a `/relay` call (e.g. after a timeout), we can return the result of the previous
+//! attempt instead of sending a transaction that would revert with `DuplicatedNullifier`.
+
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use alloy_network::ReceiptResponse;
+use alloy_provider::Provider;
+use parking_lot::Mutex;
+use rusqlite::{Connection, OptionalExtension, ToSql};
+use shielder_contract::{
+    alloy_primitives::{Address, TxHash, U256},
+    providers::RpcEndpoints,
+    recovery::get_shielder_action,
+    ShielderUser,
+};
+use shielder_relayer::{RelayError, RelayErrorKind, RelayStatus};
+use tokio::time::sleep;
+use tracing::{error, warn};
+
+/// How often we check whether a submitted transaction has been included in a block.
+const INCLUSION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// After this many checks, we stop waiting for the inclusion (the entry stays `Submitted`).
+const INCLUSION_CHECK_ATTEMPTS: u32 = 180;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub nullifier_hash: U256,
+    pub status: RelayStatus,
+    pub tx_hash: Option<TxHash>,
+    pub relayer_address: Option<Address>,
+    pub error: Option<RelayError>,
+}
+
+/// Outcome of trying to register a new relay request in the journal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Admission {
+    /// The request is new (or the previous attempt failed) and should be relayed.
+    Admitted,
+    /// The same nullifier is being processed right now.
+    InProgress,
+    /// The same nullifier has already been relayed in the given transaction.
+    AlreadyRelayed(TxHash),
+}
+
+/// Thread-safe handle to the SQLite relay journal.
+#[derive(Clone)]
+pub struct RelayJournal {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl RelayJournal {
+    /// Open (or create) the journal at `path`. `:memory:` creates an ephemeral journal.
+    ///
+    /// Requests that were queued or dry-run when the relayer was stopped are marked as failed - no
+    /// transaction was sent for them, so they can be safely retried. Requests that were being
+    /// submitted are left for `settle_interrupted_submissions`.
+    pub fn open(path: &str) -> Result<Self, rusqlite::Error> {
+        let connection = Connection::open(path)?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS relay_requests (
+                nullifier_hash TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                tx_hash TEXT,
+                relayer_address TEXT,
+                error_kind TEXT,
+                error TEXT,
+                updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+            )",
+            (),
+        )?;
+
+        let interrupted = connection.execute(
+            "UPDATE relay_requests SET status = ?1, error_kind = ?2, error = ?3,
+             updated_at = unixepoch() WHERE status IN (?4, ?5)",
+            (
+                RelayStatus::Failed.as_str(),
+                RelayErrorKind::Internal.as_str(),
+                "Interrupted by relayer restart",
+                RelayStatus::Queued.as_str(),
+                RelayStatus::DryRun.as_str(),
+            ),
+        )?;
+        if interrupted > 0 {
+            warn!("Marked {interrupted} interrupted relay requests as failed");
+        }
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Register a new relay request for `nullifier_hash`, unless the same nullifier is already
+    /// being processed or has already been relayed.
+    pub async fn admit(&self, nullifier_hash: U256) -> Result<Admission, rusqlite::Error> {
+        self.with_connection(move |connection| {
+            if let Some(entry) = query_entry(connection, nullifier_hash)? {
+                match (entry.status, entry.tx_hash) {
+                    (RelayStatus::Submitted | RelayStatus::Mined, Some(tx_hash)) => {
+                        return Ok(Admission::AlreadyRelayed(tx_hash))
+                    }
+                    (RelayStatus::Queued | RelayStatus::DryRun | RelayStatus::Submitting, _) => {
+                        return Ok(Admission::InProgress)
+                    }
+                    _ => {}
+                }
+            }
+
+            connection.execute(
+                "REPLACE INTO relay_requests (nullifier_hash, status) VALUES (?1, ?2)",
+                (nullifier_hash.to_string(), RelayStatus::Queued.as_str()),
+            )?;
+            Ok(Admission::Admitted)
+        })
+        .await
+    }
+
+    /// Transaction in which `nullifier_hash` has already been relayed, if any.
+    pub async fn relayed_in(
+        &self,
+        nullifier_hash: U256,
+    ) -> Result<Option<TxHash>, rusqlite::Error> {
+        Ok(self
+            .entry(nullifier_hash)
+            .await?
+            .and_then(|entry| match entry.status {
+                RelayStatus::Submitted | RelayStatus::Mined => entry.tx_hash,
+                _ => None,
+            }))
+    }
+
+    pub async fn entry(
+        &self,
+        nullifier_hash: U256,
+    ) -> Result<Option<JournalEntry>, rusqlite::Error> {
+        self.with_connection(move |connection| query_entry(connection, nullifier_hash))
+            .await
+    }
+
+    pub async fn record_dry_run(&self, nullifier_hash: U256, relayer_address: Address) {
+        self.update(
+            nullifier_hash,
+            "status = ?2, relayer_address = ?3",
+            vec![
+                RelayStatus::DryRun.as_str().to_string(),
+                relayer_address.to_string(),
+            ],
+        )
+        .await;
+    }
+
+    pub async fn record_submitting(&self, nullifier_hash: U256, relayer_address: Address) {
+        self.update(
+            nullifier_hash,
+            "status = ?2, relayer_address = ?3",
+            vec![
+                RelayStatus::Submitting.as_str().to_string(),
+                relayer_address.to_string(),
+            ],
+        )
+        .await;
+    }
+
+    pub async fn record_submitted(
+        &self,
+        nullifier_hash: U256,
+        relayer_address: Address,
+        tx: TxHash,
+    ) {
+        self.update(
+            nullifier_hash,
+            "status = ?2, relayer_address = ?3, tx_hash = ?4",
+            vec![
+                RelayStatus::Submitted.as_str().to_string(),
+                relayer_address.to_string(),
+                tx.to_string(),
+            ],
+        )
+        .await;
+    }
+
+    pub async fn record_mined(&self, nullifier_hash: U256) {
+        self.update(
+            nullifier_hash,
+            "status = ?2",
+            vec![RelayStatus::Mined.as_str().to_string()],
+        )
+        .await;
+    }
+
+    pub async fn record_failure(&self, nullifier_hash: U256, kind: RelayErrorKind, error: &str) {
+        self.update(
+            nullifier_hash,
+            "status = ?2, error_kind = ?3, error = ?4",
+            vec![
+                RelayStatus::Failed.as_str().to_string(),
+                kind.as_str().to_string(),
+                error.to_string(),
+            ],
+        )
+        .await;
+    }
+
+    /// Settle the requests that were being submitted when the relayer was stopped. Their
+    /// transaction might have been sent, so instead of failing them blindly, we look their
+    /// nullifier up on-chain: if it has been spent, the request is marked as mined in the spending
+    /// transaction, otherwise as failed. Requests that couldn't be checked stay `Submitting` and are
+    /// not admitted again.
+    pub async fn settle_interrupted_submissions(
+        &self,
+        node_rpc: &RpcEndpoints,
+        shielder_user: &ShielderUser,
+    ) -> anyhow::Result<()> {
+        let interrupted = self
+            .with_connection(|connection| {
+                let mut statement = connection
+                    .prepare("SELECT nullifier_hash FROM relay_requests WHERE status = ?1")?;
+                let nullifier_hashes = statement
+                    .query_map([RelayStatus::Submitting.as_str()], |row| {
+                        row.get::<_, String>(0)
+                    })?
+                    .collect::<Result<Vec<_>, _>>();
+                nullifier_hashes
+            })
+            .await?;
+        if interrupted.is_empty() {
+            return Ok(());
+        }
+
+        let provider = node_rpc.simple_provider().await?;
+        for nullifier_hash in interrupted {
+            let nullifier_hash = U256::from_str(&nullifier_hash)?;
+            match get_shielder_action(&provider, shielder_user, nullifier_hash).await {
+                Ok(Some((tx_hash, _))) => {
+                    self.update(
+                        nullifier_hash,
+                        "status = ?2, tx_hash = ?3",
+                        vec![RelayStatus::Mined.as_str().to_string(), tx_hash.to_string()],
+                    )
+                    .await
+                }
+                Ok(None) => {
+                    self.record_failure(
+                        nullifier_hash,
+                        RelayErrorKind::Internal,
+                        "Interrupted by relayer restart before the transaction was included",
+                    )
+                    .await
+                }
+                Err(err) => {
+                    error!("Couldn't settle interrupted relay of {nullifier_hash}: {err}")
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait (in the background) until the transaction is included in a block and update its
+    /// status accordingly.
+    pub fn watch_inclusion(&self, node_rpc: RpcEndpoints, nullifier_hash: U256, tx_hash: TxHash) {
+        let journal = self.clone();
+        tokio::spawn(async move {
+            let provider = match node_rpc.simple_provider().await {
+                Ok(provider) => provider,
+                Err(err) => {
+                    error!("Couldn't create provider to watch {tx_hash}: {err}");
+                    return;
+                }
+            };
+
+            for _ in 0..INCLUSION_CHECK_ATTEMPTS {
+                if let Ok(Some(receipt)) = provider.get_transaction_receipt(tx_hash).await {
+                    match receipt.status() {
+                        true => journal.record_mined(nullifier_hash).await,
+                        false => {
+                            journal
+                                .record_failure(
+                                    nullifier_hash,
+                                    RelayErrorKind::TransactionReverted,
+                                    "Transaction reverted",
+                                )
+                                .await
+                        }
+                    }
+                    return;
+                }
+                sleep(INCLUSION_CHECK_INTERVAL).await;
+            }
+            warn!("Transaction {tx_hash} was not included in time, leaving it as submitted");
+        });
+    }
+
+    /// Apply `assignments` (SQL `SET` clause, where `?1` is the nullifier hash) to the entry.
+    /// Journal failures are logged, but never interrupt relaying.
+    async fn update(&self, nullifier_hash: U256, assignments: &'static str, values: Vec<String>) {
+        let result = self
+            .with_connection(move |connection| {
+                let query = format!(
+                    "UPDATE relay_requests SET {assignments}, updated_at = unixepoch() \
+                     WHERE nullifier_hash = ?1"
+                );
+                let nullifier_hash = nullifier_hash.to_string();
+                let params: Vec<&dyn ToSql> = std::iter::once(&nullifier_hash as &dyn ToSql)
+                    .chain(values.iter().map(|value| value as &dyn ToSql))
+                    .collect();
+                connection.execute(&query, params.as_slice())
+            })
+            .await;
+
+        match result {
+            Ok(1) => {}
+            Ok(_) => warn!("No journal entry for nullifier hash {nullifier_hash}"),
+            Err(err) => error!("Failed to update relay journal: {err}"),
+        }
+    }
+
+    /// Run `f` on the connection in the blocking thread pool. SQLite calls (and waiting for the
+    /// connection lock) block the thread, so they must not run on the async runtime directly.
+    async fn with_connection<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<R, rusqlite::Error> + Send + 'static,
+    ) -> Result<R, rusqlite::Error> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || f(&connection.lock()))
+            .await
+            .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+    }
+}
+
+fn query_entry(
+    connection: &Connection,
+    nullifier_hash: U256,
+) -> Result<Option<JournalEntry>, rusqlite::Error> {
+    connection
+        .query_row(
+            "SELECT status, tx_hash, relayer_address, error_kind, error FROM relay_requests \
+             WHERE nullifier_hash = ?1",
+            [nullifier_hash.to_string()],
+            |row| {
+                let status: String = row.get(0)?;
+                let tx_hash: Option<String> = row.get(1)?;
+                let relayer_address: Option<String> = row.get(2)?;
+                let error_kind: Option<String> = row.get(3)?;
+                let error: Option<String> = row.get(4)?;
+
+                let error = match (error_kind, error) {
+                    (Some(kind), Some(message)) => Some(RelayError {
+                        kind: kind.parse().map_err(|e| conversion_error(3, e))?,
+                        message,
+                    }),
+                    _ => None,
+                };
+                Ok(JournalEntry {
+                    nullifier_hash,
+                    status: status.parse().map_err(|e| conversion_error(0, e))?,
+                    tx_hash: tx_hash.and_then(|h| h.parse().ok()),
+                    relayer_address: relayer_address.and_then(|a| a.parse().ok()),
+                    error,
+                })
+            },
+        )
+        .optional()
+}
+
+fn conversion_error(column: usize, error: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, error.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal() -> RelayJournal {
+        RelayJournal::open(":memory:").unwrap()
+    }
+
+    #[tokio::test]
+    async fn new_nullifier_is_admitted() {
+        let journal = journal();
+        assert_eq!(
+            journal.admit(U256::from(1)).await.unwrap(),
+            Admission::Admitted
+        );
+
+        let entry = journal.entry(U256::from(1)).await.unwrap().unwrap();
+        assert_eq!(entry.status, RelayStatus::Queued);
+    }
+
+    #[tokio::test]
+    async fn nullifier_in_progress_is_not_admitted_again() {
+        let journal = journal();
+        journal.admit(U256::from(1)).await.unwrap();
+        assert_eq!(
+            journal.admit(U256::from(1)).await.unwrap(),
+            Admission::InProgress
+        );
+
+        journal.record_dry_run(U256::from(1), Address::ZERO).await;
+        assert_eq!(
+            journal.admit(U256::from(1)).await.unwrap(),
+            Admission::InProgress
+        );
+    }
+
+    #[tokio::test]
+    async fn relayed_nullifier_returns_previous_transaction() {
+        let journal = journal();
+        let tx_hash = TxHash::repeat_byte(7);
+        let relayer = Address::repeat_byte(1);
+
+        journal.admit(U256::from(1)).await.unwrap();
+        journal
+            .record_submitted(U256::from(1), relayer, tx_hash)
+            .await;
+        assert_eq!(
+            journal.admit(U256::from(1)).await.unwrap(),
+            Admission::AlreadyRelayed(tx_hash)
+        );
+        assert_eq!(
+            journal.relayed_in(U256::from(1)).await.unwrap(),
+            Some(tx_hash)
+        );
+
+        journal.record_mined(U256::from(1)).await;
+        assert_eq!(
+            journal.entry(U256::from(1)).await.unwrap().unwrap(),
+            JournalEntry {
+                nullifier_hash: U256::from(1),
+                status: RelayStatus::Mined,
+                tx_hash: Some(tx_hash),
+                relayer_address: Some(relayer),
+                error: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_nullifier_can_be_retried() {
+        let journal = journal();
+        journal.admit(U256::from(1)).await.unwrap();
+        journal
+            .record_failure(
+                U256::from(1),
+                RelayErrorKind::DryRunFailed,
+                "Dry run failed",
+            )
+            .await;
+
+        let entry = journal.entry(U256::from(1)).await.unwrap().unwrap();
+        assert_eq!(entry.status, RelayStatus::Failed);
+        assert_eq!(
+            entry.error,
+            Some(RelayError {
+                kind: RelayErrorKind::DryRunFailed,
+                message: "Dry run failed".to_string(),
+            })
+        );
+
+        assert_eq!(
+            journal.admit(U256::from(1)).await.unwrap(),
+            Admission::Admitted
+        );
+        assert_eq!(
+            journal.entry(U256::from(1)).await.unwrap().unwrap().error,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn interrupted_requests_are_failed_on_reopen() {
+        let path =
+            std::env::temp_dir().join(format!("relay-journal-{}.sqlite", rand::random::<u64>()));
+        let path = path.to_str().unwrap();
+
+        let journal = RelayJournal::open(path).unwrap();
+        journal.admit(U256::from(1)).await.unwrap();
+        journal.admit(U256::from(2)).await.unwrap();
+        journal
+            .record_submitted(U256::from(2), Address::ZERO, TxHash::ZERO)
+            .await;
+        journal.admit(U256::from(3)).await.unwrap();
+        journal
+            .record_submitting(U256::from(3), Address::ZERO)
+            .await;
+        drop(journal);
+
+        let journal = RelayJournal::open(path).unwrap();
+        let interrupted = journal.entry(U256::from(1)).await.unwrap().unwrap();
+        assert_eq!(interrupted.status, RelayStatus::Failed);
+        assert_eq!(interrupted.error.unwrap().kind, RelayErrorKind::Internal);
+        assert_eq!(
+            journal.entry(U256::from(2)).await.unwrap().unwrap().status,
+            RelayStatus::Submitted
+        );
+        // The transaction might have been sent, so the request must not be retried blindly.
+        assert_eq!(
+            journal.entry(U256::from(3)).await.unwrap().unwrap().status,
+            RelayStatus::Submitting
+        );
+        assert_eq!(
+            journal.admit(U256::from(3)).await.unwrap(),
+            Admission::InProgress
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
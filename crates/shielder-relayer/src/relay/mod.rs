@@ -1,52 +1,77 @@
+use std::time::Duration;
+
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     response::{IntoResponse, Response},
     Json,
 };
 use shielder_account::{call_data::WithdrawCall, Token};
-use shielder_contract::alloy_primitives::{Address, U256};
+use shielder_contract::{
+    alloy_primitives::{Address, TxHash, U256},
+    ShielderContractError,
+};
 use shielder_relayer::{
     compute_fee,
-    server::{bad_request, server_error, success_response},
-    RelayCalldata, RelayQuery, RelayResponse, SimpleServiceResponse,
+    server::{
+        accepted_response, bad_request, not_found, server_error, success_response,
+        temporary_failure, temporary_failure_with_retry_after,
+    },
+    RelayCalldata, RelayErrorKind, RelayMode, RelayModeQuery, RelayQuery, RelayResponse,
+    RelayStatusResponse, RelayTicket, SimpleServiceResponse,
 };
 use shielder_setup::version::{contract_version, ContractVersion};
+use tokio::sync::oneshot::Receiver as OneshotReceiver;
 use tracing::{debug, error};
 
-pub use crate::relay::taskmaster::Taskmaster;
+pub use crate::relay::{journal::RelayJournal, taskmaster::Taskmaster};
 use crate::{
     metrics::WITHDRAW_FAILURE,
     quote_cache::CachedQuote,
-    relay::{request_trace::RequestTrace, taskmaster::TaskResult},
+    relay::{
+        journal::Admission,
+        request_trace::RequestTrace,
+        taskmaster::{RegistrationError, TaskResult},
+    },
     AppState,
 };
 
+mod journal;
 mod monitoring;
 mod request_trace;
 mod taskmaster;
 
 const TASK_QUEUE_SIZE: usize = 1024;
 const OPTIMISTIC_DRY_RUN_THRESHOLD: u32 = 32;
+/// How long clients should wait before retrying when the relay queue is full.
+const QUEUE_FULL_RETRY_AFTER: Duration = Duration::from_secs(5);
 
 /// The relay endpoint is used to relay a withdrawal request to the shielder contract.
+///
+/// In the `sync` mode (default), the response is sent once the transaction is submitted. In the
+/// `async` mode, a ticket is returned right away and the progress can be tracked with
+/// `GET /relay/{id}`.
 #[utoipa::path(
     post,
     path = "/relay",
+    params(RelayModeQuery),
     request_body(content = RelayQuery, description = "The relay request"),
     responses(
-        (status = 200, description = "Quotation successful", body = RelayResponse),
-        (status = BAD_REQUEST, description = "Failed to relay withdrawal. Ensure your query, including proof, is correct.", body = SimpleServiceResponse),
-        (status = SERVICE_UNAVAILABLE, description = "Failed to obtain current chain and price info. Try again later.", body = SimpleServiceResponse),
+        (status = 200, description = "Withdrawal relayed (sync mode)", body = RelayResponse),
+        (status = ACCEPTED, description = "Withdrawal accepted for relaying (async mode)", body = RelayTicket),
+        (status = BAD_REQUEST, description = "Failed to relay withdrawal. Ensure your query, including proof, is correct. If the contract reverted, the reason is included in the message.", body = SimpleServiceResponse),
+        (status = SERVICE_UNAVAILABLE, description = "Failed to obtain current chain and price info, the relay queue is full or the relayer signer is busy. Try again later (see `Retry-After`).", body = SimpleServiceResponse),
         (status = INTERNAL_SERVER_ERROR, description = "Server encountered unexpected error. Try again later.", body = SimpleServiceResponse),
     )
 )]
 pub async fn relay(
     State(app_state): State<AppState>,
+    Query(RelayModeQuery { mode }): Query<RelayModeQuery>,
     Json(query): Json<RelayQuery>,
 ) -> impl IntoResponse {
-    debug!("Relay request received: {query:?}");
-    match _relay(app_state, query).await {
-        Ok(response) => success_response(response),
+    debug!("Relay request received ({mode:?} mode): {query:?}");
+    match _relay(app_state, query, mode).await {
+        Ok(RelayOutcome::Relayed(response)) => success_response(response),
+        Ok(RelayOutcome::Accepted(ticket)) => accepted_response(ticket),
         Err(err) => {
             error!("Relay request failed: {err:?}");
             err
@@ -54,8 +79,67 @@ pub async fn relay(
     }
 }
 
-async fn _relay(app_state: AppState, query: RelayQuery) -> Result<RelayResponse, Response> {
+/// Get the current state of a relay request.
+#[utoipa::path(
+    get,
+    path = "/relay/{id}",
+    params(("id" = String, Path, description = "Relay ticket identifier (nullifier hash)")),
+    responses(
+        (status = 200, description = "Current state of the relay request", body = RelayStatusResponse),
+        (status = BAD_REQUEST, description = "Invalid ticket identifier", body = SimpleServiceResponse),
+        (status = NOT_FOUND, description = "Unknown ticket", body = SimpleServiceResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Server encountered unexpected error. Try again later.", body = SimpleServiceResponse),
+    )
+)]
+pub async fn relay_status(
+    State(app_state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let Ok(nullifier_hash) = id.parse::<U256>() else {
+        return bad_request("Invalid ticket identifier");
+    };
+    match app_state.journal.entry(nullifier_hash).await {
+        Ok(Some(entry)) => success_response(RelayStatusResponse {
+            id: entry.nullifier_hash,
+            status: entry.status,
+            tx_hash: entry.tx_hash,
+            error: entry.error,
+        }),
+        Ok(None) => not_found("Unknown ticket"),
+        Err(err) => journal_error(err),
+    }
+}
+
+enum RelayOutcome {
+    Relayed(RelayResponse),
+    Accepted(RelayTicket),
+}
+
+impl RelayOutcome {
+    /// Outcome for a request that has already been relayed in `tx_hash`.
+    fn already_relayed(mode: RelayMode, nullifier_hash: U256, tx_hash: TxHash) -> Self {
+        match mode {
+            RelayMode::Sync => RelayOutcome::Relayed(RelayResponse { tx_hash }),
+            RelayMode::Async => RelayOutcome::Accepted(RelayTicket { id: nullifier_hash }),
+        }
+    }
+}
+
+async fn _relay(
+    app_state: AppState,
+    query: RelayQuery,
+    mode: RelayMode,
+) -> Result<RelayOutcome, Response> {
     let mut request_trace = RequestTrace::new(&query);
+    let nullifier_hash = query.calldata.nullifier_hash;
+
+    // Repeated requests are answered before validation - the original quote might have already
+    // expired.
+    if let Some(outcome) =
+        check_already_relayed(&app_state, nullifier_hash, mode, &mut request_trace).await?
+    {
+        return Ok(outcome);
+    }
 
     check_expected_version(&query.calldata, &mut request_trace)?;
     check_pocket_money(&app_state, &query, &mut request_trace)?;
@@ -71,17 +155,62 @@ async fn _relay(app_state: AppState, query: RelayQuery) -> Result<RelayResponse,
     )
     .map_err(server_error)?;
 
+    if let Some(outcome) =
+        admit_to_journal(&app_state, nullifier_hash, mode, &mut request_trace).await?
+    {
+        return Ok(outcome);
+    }
+
     let withdraw_call = create_call(
         query.calldata,
         app_state.signer_info.fee_destination_address,
         fee_details.total_cost_fee_token,
     );
-    let rx = app_state
+    // Registration never waits for the queue: the entry is already journaled as queued, so it
+    // must be marked as failed right away, or every retry would be rejected as in progress.
+    let rx = match app_state
         .taskmaster
         .register_new_task(withdraw_call, request_trace)
-        .await
-        .map_err(|err| server_error(&format!("Failed to register new task: {err:?}")))?;
+    {
+        Ok(rx) => rx,
+        Err(err) => {
+            let (kind, response) = match err {
+                RegistrationError::QueueFull => (
+                    RelayErrorKind::QueueFull,
+                    temporary_failure_with_retry_after(
+                        "Relay queue is full. Retry later.",
+                        QUEUE_FULL_RETRY_AFTER,
+                    ),
+                ),
+                RegistrationError::Closed => (
+                    RelayErrorKind::Internal,
+                    server_error(&format!("Failed to register new task: {err}")),
+                ),
+            };
+            app_state
+                .journal
+                .record_failure(nullifier_hash, kind, &err.to_string())
+                .await;
+            return Err(response);
+        }
+    };
 
+    match mode {
+        RelayMode::Sync => await_task_result(app_state.journal, nullifier_hash, rx)
+            .await
+            .map(RelayOutcome::Relayed),
+        RelayMode::Async => {
+            tokio::spawn(await_task_result(app_state.journal, nullifier_hash, rx));
+            Ok(RelayOutcome::Accepted(RelayTicket { id: nullifier_hash }))
+        }
+    }
+}
+
+async fn await_task_result(
+    journal: RelayJournal,
+    nullifier_hash: U256,
+    rx: OneshotReceiver<(RequestTrace, TaskResult)>,
+) -> Result<RelayResponse, Response> {
     match rx.await {
         Ok((mut request_trace, task_result)) => match task_result {
             TaskResult::Ok(tx_hash) => {
@@ -89,22 +218,107 @@ async fn _relay(app_state: AppState, query: RelayQuery) -> Result<RelayResponse,
                 Ok(RelayResponse { tx_hash })
             }
             TaskResult::DryRunFailed(err) => {
+                let response = failure_response("Dry run", &err);
                 request_trace.record_dry_run_failure(err);
-                Err(bad_request("Dry run failed"))
+                Err(response)
             }
             TaskResult::RelayFailed(err) => {
+                let response = failure_response("Relay", &err);
                 request_trace.record_failure(err);
-                Err(bad_request("Relay failed"))
+                Err(response)
             }
         },
         Err(err) => {
             error!("[UNEXPECTED] Relay task master failed: {err}");
             metrics::counter!(WITHDRAW_FAILURE).increment(1);
+            journal
+                .record_failure(
+                    nullifier_hash,
+                    RelayErrorKind::Internal,
+                    "Relay task failed",
+                )
+                .await;
             Err(server_error("Relay task failed"))
         }
     }
 }
 
+async fn check_already_relayed(
+    app_state: &AppState,
+    nullifier_hash: U256,
+    mode: RelayMode,
+    request_trace: &mut RequestTrace,
+) -> Result<Option<RelayOutcome>, Response> {
+    match app_state.journal.relayed_in(nullifier_hash).await {
+        Ok(Some(tx_hash)) => {
+            request_trace.record_already_relayed(tx_hash);
+            Ok(Some(RelayOutcome::already_relayed(
+                mode,
+                nullifier_hash,
+                tx_hash,
+            )))
+        }
+        Ok(None) => Ok(None),
+        Err(err) => Err(journal_error(err)),
+    }
+}
+
+fn journal_error(err: rusqlite::Error) -> Response {
+    error!("Failed to access relay journal: {err}");
+    server_error("Failed to access relay journal")
+}
+
+/// Register the request in the relay journal. If the same nullifier has already been relayed,
+/// the previous result is returned instead, so that the client can safely repeat the request.
+async fn admit_to_journal(
+    app_state: &AppState,
+    nullifier_hash: U256,
+    mode: RelayMode,
+    request_trace: &mut RequestTrace,
+) -> Result<Option<RelayOutcome>, Response> {
+    match app_state.journal.admit(nullifier_hash).await {
+        Ok(Admission::Admitted) => Ok(None),
+        Ok(Admission::AlreadyRelayed(tx_hash)) => {
+            request_trace.record_already_relayed(tx_hash);
+            Ok(Some(RelayOutcome::already_relayed(
+                mode,
+                nullifier_hash,
+                tx_hash,
+            )))
+        }
+        Ok(Admission::InProgress) => {
+            request_trace.record_in_progress();
+            match mode {
+                // In the async mode, it is enough to point the client to the existing ticket.
+                RelayMode::Async => Ok(Some(RelayOutcome::Accepted(RelayTicket {
+                    id: nullifier_hash,
+                }))),
+                RelayMode::Sync => Err(temporary_failure(
+                    "Request with the same nullifier hash is being processed. Retry later.",
+                )),
+            }
+        }
+        Err(err) => Err(journal_error(err)),
+    }
+}
+
+/// Translate a failed contract interaction into a response. Contract reverts are reported back
+/// to the client (e.g. `DuplicatedNullifier` means that the client should re-sync its account),
+/// while signer conflicts are relayer-side and the request can be simply retried.
+fn failure_response(stage: &str, err: &ShielderContractError) -> Response {
+    match err {
+        ShielderContractError::SignerConflict => temporary_failure(&format!(
+            "{stage} failed due to a signer conflict. Retry later."
+        )),
+        _ => match err.revert_reason() {
+            Some(reason) => bad_request(&format!(
+                "{stage} failed: contract reverted with {reason:?}"
+            )),
+            None => bad_request(&format!("{stage} failed")),
+        },
+    }
+}
+
 fn create_call(c: RelayCalldata, relayer_address: Address, relayer_fee: U256) -> WithdrawCall {
     WithdrawCall {
         expected_contract_version: c.expected_contract_version,
@@ -118,6 +332,7 @@ fn create_call(c: RelayCalldata, relayer_address: Address, relayer_fee: U256) ->
         proof: c.proof,
         mac_salt: c.mac_salt,
         mac_commitment: c.mac_commitment,
+        association_set_root: c.association_set_root,
         token: c.fee_token,
         pocket_money: c.pocket_money,
         memo: c.memo,
@@ -103,6 +103,23 @@ impl RequestTrace {
         self.finish("❌ QUOTE VALIDITY FAILURE");
     }
 
+    pub fn record_queue_full(&mut self) {
+        metrics::counter!(WITHDRAW_FAILURE).increment(1);
+        error!("Relay queue is full");
+        self.finish("❌ QUEUE FULL");
+    }
+
+    pub fn record_already_relayed(&mut self, tx_hash: TxHash) {
+        info!("Nullifier has already been relayed in {tx_hash}");
+        self.tx_hash = Some(tx_hash);
+        self.finish("🔁 ALREADY RELAYED");
+    }
+
+    pub fn record_in_progress(&mut self) {
+        info!("Request with the same nullifier is being processed");
+        self.finish("🔁 IN PROGRESS");
+    }
+
     pub fn record_failure(&mut self, err: ShielderContractError) {
         metrics::counter!(WITHDRAW_FAILURE).increment(1);
         error!("Relay failed: {err}");
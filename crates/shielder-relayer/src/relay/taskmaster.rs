@@ -1,12 +1,13 @@
 use alloy_provider::Provider;
-use anyhow::Result;
-use async_channel::{Receiver as MPMCReceiver, Sender as MPMCSender};
+use async_channel::{Receiver as MPMCReceiver, Sender as MPMCSender, TrySendError};
 use shielder_account::{call_data::WithdrawCall, Token};
 use shielder_contract::{
-    alloy_primitives::{Address, TxHash},
+    alloy_primitives::{Address, TxHash, U256},
     call_type::{DryRun, Submit},
+    providers::RpcEndpoints,
     ShielderContractError, ShielderUser,
 };
+use shielder_relayer::RelayErrorKind;
 use tokio::sync::{
     mpsc::Sender as MPSCSender,
     oneshot,
@@ -17,12 +18,21 @@ use tracing::{error, info};
 use crate::{
     config::DryRunning,
     relay::{
+        journal::RelayJournal,
         monitoring::{DryRunSwitch, ObligatoryDryRun, OptionalDryRun, RelayingMonitoring},
         request_trace::RequestTrace,
         TASK_QUEUE_SIZE,
     },
 };
 
+#[derive(Debug, thiserror::Error)]
+pub enum RegistrationError {
+    #[error("Relay queue is full")]
+    QueueFull,
+    #[error("Relay queue is closed")]
+    Closed,
+}
+
 pub enum TaskResult {
     DryRunFailed(ShielderContractError),
     RelayFailed(ShielderContractError),
@@ -45,6 +55,8 @@ impl Taskmaster {
         shielder_users: Vec<ShielderUser<impl Provider + Clone + 'static>>,
         dry_running: DryRunning,
         recharge_reporter: MPSCSender<Address>,
+        journal: RelayJournal,
+        node_rpc: RpcEndpoints,
     ) -> Self {
         let (task_sender, task_receiver) = async_channel::bounded(TASK_QUEUE_SIZE);
 
@@ -56,6 +68,8 @@ impl Taskmaster {
                     task_receiver,
                     ObligatoryDryRun {},
                     recharge_reporter,
+                    journal,
+                    node_rpc,
                 );
             }
             DryRunning::Optimistic => {
@@ -65,6 +79,8 @@ impl Taskmaster {
                     task_receiver,
                     OptionalDryRun::new(),
                     recharge_reporter,
+                    journal,
+                    node_rpc,
                 );
             }
         }
@@ -77,6 +93,8 @@ impl Taskmaster {
         task_receiver: MPMCReceiver<Task>,
         dry_run_manager: impl RelayingMonitoring + DryRunSwitch + 'static,
         recharge_reporter: MPSCSender<Address>,
+        journal: RelayJournal,
+        node_rpc: RpcEndpoints,
     ) {
         for shielder_user in shielder_users {
             tokio::spawn(relay_worker(
@@ -84,15 +102,18 @@ impl Taskmaster {
                 shielder_user,
                 dry_run_manager.clone(),
                 recharge_reporter.clone(),
+                journal.clone(),
+                node_rpc.clone(),
             ));
         }
     }
 
-    pub async fn register_new_task(
+    /// Enqueue a new relay task. Fails immediately (instead of waiting) if the queue is full.
+    pub fn register_new_task(
         &self,
         payload: WithdrawCall,
         mut request_trace: RequestTrace,
-    ) -> Result<OneshotReceiver<(RequestTrace, TaskResult)>> {
+    ) -> Result<OneshotReceiver<(RequestTrace, TaskResult)>, RegistrationError> {
         let (report_sender, report_receiver) = oneshot::channel();
 
         request_trace.record("queued for relay");
@@ -101,12 +122,14 @@ impl Taskmaster {
             payload,
             request_trace,
         };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| anyhow::anyhow!("Failed to send task to relay"))?;
-
-        Ok(report_receiver)
+        match self.task_sender.try_send(task) {
+            Ok(()) => Ok(report_receiver),
+            Err(TrySendError::Full(mut task)) => {
+                task.request_trace.record_queue_full();
+                Err(RegistrationError::QueueFull)
+            }
+            Err(TrySendError::Closed(_)) => Err(RegistrationError::Closed),
+        }
     }
 }
 
@@ -115,14 +138,18 @@ async fn relay_worker(
     shielder_user: ShielderUser<impl Provider + Clone>,
     mut dry_run_manager: impl RelayingMonitoring + DryRunSwitch,
     recharge_reporter: MPSCSender<Address>,
+    journal: RelayJournal,
+    node_rpc: RpcEndpoints,
 ) {
     let worker_address = shielder_user.address();
     while let Ok(task) = requests.recv().await {
         let mut request_trace = task.request_trace;
         request_trace.record("received by worker");
         request_trace.set_relayer_address(worker_address);
+        let nullifier_hash: U256 = task.payload.old_nullifier_hash;
 
         if dry_run_manager.should_dry_run_now() {
+            journal.record_dry_run(nullifier_hash, worker_address).await;
             let dry_run_result = match task.payload.token {
                 Token::Native => {
                     shielder_user
@@ -141,6 +168,13 @@ async fn relay_worker(
             request_trace.record("dry run completed");
 
             if let Err(err) = dry_run_result {
+                journal
+                    .record_failure(
+                        nullifier_hash,
+                        classify_failure(&err, RelayErrorKind::DryRunFailed),
+                        &format!("Dry run failed: {err}"),
+                    )
+                    .await;
                 let _ = task
                     .report
                     .send((request_trace, TaskResult::DryRunFailed(err)));
@@ -148,6 +182,9 @@ async fn relay_worker(
             }
         }
 
+        journal
+            .record_submitting(nullifier_hash, worker_address)
+            .await;
         let submit_result = match task.payload.token {
             Token::Native => {
                 shielder_user
@@ -167,10 +204,21 @@ async fn relay_worker(
 
         match submit_result {
             Ok(tx_hash) => {
+                journal
+                    .record_submitted(nullifier_hash, worker_address, tx_hash)
+                    .await;
+                journal.watch_inclusion(node_rpc.clone(), nullifier_hash, tx_hash);
                 let _ = task.report.send((request_trace, TaskResult::Ok(tx_hash)));
                 dry_run_manager.notice_relay_success();
             }
             Err(err) => {
+                journal
+                    .record_failure(
+                        nullifier_hash,
+                        classify_failure(&err, RelayErrorKind::RelayFailed),
+                        &format!("Relay failed: {err}"),
+                    )
+                    .await;
                 let _ = task
                     .report
                     .send((request_trace, TaskResult::RelayFailed(err)));
@@ -188,3 +236,15 @@ async fn relay_worker(
 
     error!("Relay worker thread stopped working - channel closed. Corresponding address: {worker_address}");
 }
+
+/// Classify a failed contract interaction. `fallback` is used for failures that are neither
+/// contract reverts nor signer conflicts.
+fn classify_failure(err: &ShielderContractError, fallback: RelayErrorKind) -> RelayErrorKind {
+    match err {
+        ShielderContractError::Revert(_) | ShielderContractError::UnknownRevert(_) => {
+            RelayErrorKind::ContractRevert
+        }
+        ShielderContractError::SignerConflict => RelayErrorKind::SignerConflict,
+        _ => fallback,
+    }
+}
@@ -1,5 +1,7 @@
+use std::time::Duration;
+
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -15,6 +17,10 @@ pub fn success_response<R: Serialize>(response: R) -> Response {
     (StatusCode::OK, Json(response)).into_response()
 }
 
+pub fn accepted_response<R: Serialize>(response: R) -> Response {
+    (StatusCode::ACCEPTED, Json(response)).into_response()
+}
+
 pub fn server_error(msg: &str) -> Response {
     let code = StatusCode::INTERNAL_SERVER_ERROR;
     (code, jsonize_str(msg)).into_response()
@@ -29,6 +35,16 @@ pub fn temporary_failure(msg: &str) -> Response {
     (code, jsonize_str(msg)).into_response()
 }
 
+pub fn temporary_failure_with_retry_after(msg: &str, retry_after: Duration) -> Response {
+    let code = StatusCode::SERVICE_UNAVAILABLE;
+    let retry_after = [(RETRY_AFTER, retry_after.as_secs().to_string())];
+    (code, retry_after, jsonize_str(msg)).into_response()
+}
+
+pub fn not_found(msg: &str) -> Response {
+    (StatusCode::NOT_FOUND, jsonize_str(msg)).into_response()
+}
+
 fn jsonize_str(msg: &str) -> Json<SimpleServiceResponse> {
     Json(SimpleServiceResponse {
         message: msg.into(),
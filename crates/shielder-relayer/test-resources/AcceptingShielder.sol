@@ -15,6 +15,7 @@ contract AcceptingShielder {
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         bytes calldata memo
     ) external {}
 
@@ -31,6 +32,7 @@ contract AcceptingShielder {
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         bytes calldata memo
     ) external payable {}
 }
@@ -15,6 +15,7 @@ contract RevertingShielder {
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         bytes calldata memo
     ) external {
         revert();
@@ -33,6 +34,7 @@ contract RevertingShielder {
         uint256 relayerFee,
         uint256 macSalt,
         uint256 macCommitment,
+        uint256 associationSetRoot,
         bytes calldata memo
     ) external payable {
         revert();
@@ -1,8 +1,10 @@
 use std::time::Duration;
 
+use alloy_primitives::U256;
 use parameterized::parameterized;
 use reqwest::StatusCode;
 use shielder_account::Token;
+use shielder_relayer::RelayResponse;
 use tokio::time::sleep;
 
 use crate::utils::{
@@ -56,6 +58,27 @@ async fn when_contract_reverts_server_screams_failure(token: Token) {
     .await;
 }
 
+#[tokio::test]
+async fn repeated_relay_returns_previous_transaction() {
+    let context = TestContext::default().await;
+    let nullifier_hash = U256::from(41);
+
+    let quote = context.quote(Token::Native).await;
+    let first = context
+        .relay_with_nullifier(quote.clone(), Token::Native, nullifier_hash)
+        .await;
+    ctx_assert!(first.status().is_success(), context);
+    let first = first.json::<RelayResponse>().await.unwrap();
+
+    let second = context
+        .relay_with_nullifier(quote, Token::Native, nullifier_hash)
+        .await;
+    ctx_assert!(second.status().is_success(), context);
+    let second = second.json::<RelayResponse>().await.unwrap();
+
+    ctx_assert_eq!(first.tx_hash, second.tx_hash, context);
+}
+
 #[tokio::test]
 async fn metrics_register_withdrawals() {
     let context = TestContext::default().await;
@@ -111,6 +111,16 @@ impl TestContext {
     }
 
     pub async fn relay(&self, quote: RelayQuote, fee_token: Token) -> Response {
+        self.relay_with_nullifier(quote, fee_token, U256::from(rand::random::<u64>()))
+            .await
+    }
+
+    pub async fn relay_with_nullifier(
+        &self,
+        quote: RelayQuote,
+        fee_token: Token,
+        nullifier_hash: U256,
+    ) -> Response {
         reqwest::Client::new()
             .post(format!("{BASE_URL}:{}/relay", self.relayer_port))
             .json(&RelayQuery {
@@ -119,13 +129,14 @@ impl TestContext {
                     amount: U256::from(1),
                     withdraw_address: Address::from_str(FEE_DESTINATION).unwrap(),
                     merkle_root: U256::ZERO,
-                    nullifier_hash: U256::ZERO,
+                    nullifier_hash,
                     new_note: U256::ZERO,
                     proof: Bytes::new(),
                     fee_token,
                     fee_amount: U256::from_str("100_000_000_000_000_000").unwrap(),
                     mac_salt: U256::ZERO,
                     mac_commitment: U256::ZERO,
+                    association_set_root: U256::ZERO,
                     pocket_money: U256::ZERO,
                     memo: Bytes::from(vec![]),
                 },
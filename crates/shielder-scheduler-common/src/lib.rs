@@ -1,4 +1,4 @@
 pub mod base64_serialization;
 pub mod metrics;
 pub mod protocol;
-pub mod vsock;
+pub use tee_transport as transport;
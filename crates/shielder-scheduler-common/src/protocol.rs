@@ -1,15 +1,20 @@
-use alloy_primitives::{Address, Bytes, FixedBytes, U256};
+use std::collections::BTreeMap;
+
+use alloy_primitives::{Address, Bytes, FixedBytes, B256, U256};
 use serde::{Deserialize, Serialize};
 pub use shielder_relayer::RelayCalldata;
 use shielder_setup::consts::{ARITY, TREE_HEIGHT};
+use tee_transport::{Client, Server};
 
-use crate::{
-    base64_serialization,
-    vsock::{VsockClient, VsockServer},
-};
+use crate::base64_serialization;
 
 pub const VSOCK_PORT: u16 = 5000;
 
+/// Padding constant for encrypting [`Payload`].
+/// The payload is padded to this size before encryption, so that its length does not leak the
+/// length of the memo.
+pub const PAYLOAD_PADDING: usize = 4000;
+
 /// Payload for the `PrepareRelayCalldata` request.
 /// The payload is encrypted using the TEE Public Key.
 /// The TEE Public Key can be retrieved using the `TeePublicKey` request.
@@ -46,6 +51,10 @@ pub enum Request {
     /// Retrieves TEE Public Key, ie key which is used by the user to encrypt inputs to a circuit
     TeePublicKey,
 
+    /// Retrieves the contract version and the verifying key fingerprints of the circuits embedded
+    /// in the TEE server.
+    Info,
+
     /// Request to prepare calldata for a relay transaction.
     PrepareRelayCalldata {
         /// Encrypted payload, see [`Payload`].
@@ -73,10 +82,17 @@ pub enum Response {
         attestation_document: Vec<u8>,
     },
 
+    /// Response to [`Request::Info`].
+    Info {
+        contract_version: FixedBytes<3>,
+        /// Fingerprints (see `shielder_setup::vk_fingerprint`) by circuit name.
+        vk_fingerprints: BTreeMap<String, B256>,
+    },
+
     /// Response to [`Request::PrepareRelayCalldata`].
     /// Contains calldata that can be used to relay the transaction to the chain.
     PrepareRelayCalldata { calldata: RelayCalldata },
 }
 
-pub type TEEServer = VsockServer<Request, Response>;
-pub type TEEClient = VsockClient<Request, Response>;
+pub type TEEServer = Server<Request, Response>;
+pub type TEEClient = Client<Request, Response>;
@@ -1,4 +1,6 @@
 use clap::Parser;
+use shielder_contract::{providers::RpcEndpoints, ContractResult};
+use shielder_scheduler_common::transport::Endpoint;
 
 #[derive(Parser, Debug, Clone)]
 pub struct CommandLineArgs {
@@ -39,6 +41,11 @@ pub struct CommandLineArgs {
     #[clap(long, default_value_t = vsock::VMADDR_CID_HOST, env = "TEE_CID")]
     pub tee_cid: u32,
 
+    /// Endpoint of the TEE server, overriding `tee_cid` and `tee_port`, e.g. `tcp://127.0.0.1:5000`
+    /// or `unix:///tmp/tee.sock` when the TEE binary runs locally instead of in an enclave
+    #[clap(long, env = "TEE_ENDPOINT")]
+    pub tee_endpoint: Option<Endpoint>,
+
     /// How many tasks can be processed in parallel by the TEE task pool
     /// Do not raise it above 128 as this is the limit of vsock connections, at least
     /// for the rust lib used by this server
@@ -92,6 +99,12 @@ pub struct CommandLineArgs {
     /// RPC URL of the Ethereum node to connect to
     #[clap(long, env = "NODE_RPC_URL")]
     pub node_rpc_url: String,
+    /// RPC URLs of the Ethereum nodes to fall back to when `node_rpc_url` is unavailable
+    #[clap(long, env = "FALLBACK_NODE_RPC_URLS", value_delimiter = ',')]
+    pub fallback_node_rpc_urls: Vec<String>,
+    /// Number of nodes that must agree on the result of a contract read
+    #[clap(long, env = "RPC_QUORUM")]
+    pub rpc_quorum: Option<usize>,
     /// Address of the Shielder contract
     #[clap(long, env = "SHIELDER_ADDRESS")]
     pub shielder_address: String,
@@ -99,3 +112,22 @@ pub struct CommandLineArgs {
     #[clap(long, env = "RELAYER_RPC_URL")]
     pub relayer_rpc_url: String,
 }
+
+impl CommandLineArgs {
+    /// Endpoint on which the TEE server is reachable.
+    pub fn tee_address(&self) -> Endpoint {
+        self.tee_endpoint.clone().unwrap_or(Endpoint::Vsock {
+            cid: self.tee_cid,
+            port: self.tee_port as u32,
+        })
+    }
+
+    /// The main node followed by the fallback ones.
+    pub fn node_rpc(&self) -> ContractResult<RpcEndpoints> {
+        RpcEndpoints::new(
+            self.node_rpc_url.clone(),
+            self.fallback_node_rpc_urls.clone(),
+            self.rpc_quorum,
+        )
+    }
+}
@@ -1,9 +1,10 @@
+use alloy_primitives::U256;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response as AxumResponse},
 };
 use shielder_contract::ShielderContractError;
-use shielder_scheduler_common::vsock::VsockError;
+use shielder_scheduler_common::transport::TransportError;
 use tokio::task::JoinError;
 use tracing::error;
 
@@ -19,7 +20,7 @@ pub enum SchedulerServerError {
     JoinHandleError(#[from] JoinError),
 
     #[error("Proving Server error: {0}")]
-    ProvingServerError(#[from] VsockError),
+    ProvingServerError(#[from] TransportError),
 
     #[error("Failed to initialize metrics: {0}")]
     MetricsError(#[from] metrics_exporter_prometheus::BuildError),
@@ -35,6 +36,39 @@ pub enum SchedulerServerError {
 
     #[error("Contract error: {0}")]
     ContractError(#[from] ShielderContractError),
+
+    #[error("Relayer connection error: {0}")]
+    RelayerConnectionError(#[from] reqwest::Error),
+
+    #[error("Relayer rejected the request ({status}): {message}")]
+    RelayerRejected { status: u16, message: String },
+
+    #[error("Relayer fee {quoted} exceeds the maximum of {max}")]
+    RelayerFeeTooHigh { quoted: U256, max: U256 },
+
+    #[error("Unexpected TEE response: {0}")]
+    UnexpectedTeeResponse(String),
+}
+
+impl SchedulerServerError {
+    /// Whether processing the request again might succeed. A spent nullifier means that the
+    /// scheduled withdrawal has been superseded by another action of the account. Values that
+    /// failed to parse and client errors of the relayer (e.g. its own duplicated nullifier or
+    /// insufficient fee rejections) won't change either, except for throttling.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SchedulerServerError::ContractError(err) => !err.is_duplicated_nullifier(),
+            SchedulerServerError::ValueParseError(_) => false,
+            SchedulerServerError::RelayerRejected { status, .. } => {
+                let status =
+                    StatusCode::from_u16(*status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                !status.is_client_error()
+                    || status == StatusCode::TOO_MANY_REQUESTS
+                    || status == StatusCode::REQUEST_TIMEOUT
+            }
+            _ => true,
+        }
+    }
 }
 
 impl IntoResponse for SchedulerServerError {
@@ -51,3 +85,29 @@ impl IntoResponse for SchedulerServerError {
         (status, error_message).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SchedulerServerError;
+
+    fn relayer_rejected(status: u16) -> SchedulerServerError {
+        SchedulerServerError::RelayerRejected {
+            status,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn deterministic_failures_are_not_retried() {
+        assert!(!relayer_rejected(400).is_retryable());
+        assert!(!relayer_rejected(409).is_retryable());
+        assert!(!SchedulerServerError::ValueParseError(String::new()).is_retryable());
+    }
+
+    #[test]
+    fn transient_failures_are_retried() {
+        assert!(relayer_rejected(429).is_retryable());
+        assert!(relayer_rejected(500).is_retryable());
+        assert!(relayer_rejected(503).is_retryable());
+    }
+}
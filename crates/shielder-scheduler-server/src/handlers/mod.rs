@@ -3,19 +3,23 @@ use std::sync::Arc;
 use axum::Json;
 use shielder_scheduler_common::{
     protocol::{Request, Response, TEEClient},
-    vsock::VsockError,
+    transport::TransportError,
 };
 use tracing::{info_span, Instrument as _};
 
 use crate::AppState;
 
 pub mod health;
+pub mod info;
 pub mod schedule_withdraw;
 pub mod tee_public_key;
 
 /// Sends a request to the TEE server and returns the response.
-async fn tee_request(state: Arc<AppState>, request: Request) -> Result<Json<Response>, VsockError> {
-    let mut tee_client = TEEClient::new(state.options.tee_cid, state.options.tee_port as u32)
+pub(crate) async fn tee_request(
+    state: Arc<AppState>,
+    request: Request,
+) -> Result<Json<Response>, TransportError> {
+    let mut tee_client = TEEClient::connect(&state.options.tee_address())
         .instrument(info_span!("Building_VSOCK_connection"))
         .await?;
 
@@ -4,6 +4,7 @@ use alloy_primitives::U256;
 use axum::{extract::State, response::IntoResponse, Json};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shielder_account::Token;
 use shielder_scheduler_common::base64_serialization;
 use tracing::{error, info, instrument};
 
@@ -23,6 +24,12 @@ pub struct ScheduleWithdrawRequest {
     last_note_index: U256,
     /// Maximum fee that the relayer can charge for this transaction.
     max_relayer_fee: U256,
+    /// Token in which the relayer fee is paid. Needed to get a fee quote from the relayer.
+    #[serde(default)]
+    fee_token: Token,
+    /// Pocket money requested from the relayer. Needed to get a fee quote from the relayer.
+    #[serde(default)]
+    pocket_money: U256,
     /// Timestamp after which the relay is allowed (Unix timestamp in seconds).
     relay_after: i64,
 }
@@ -91,6 +98,8 @@ pub async fn schedule_withdraw(
         &schedule_withdraw_request.payload,
         schedule_withdraw_request.last_note_index,
         schedule_withdraw_request.max_relayer_fee,
+        schedule_withdraw_request.fee_token,
+        schedule_withdraw_request.pocket_money,
         relay_after,
     )
     .await
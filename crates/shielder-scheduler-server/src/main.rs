@@ -58,6 +58,9 @@ async fn main() -> Result<(), Error> {
         .upkeep_timeout(Duration::from_secs(options.metrics_upkeep_timeout_secs))
         .install()?;
 
+    // Check the RPC configuration before anything is scheduled
+    options.node_rpc()?;
+
     // Connect to the database
     let db_pool = db::connect_to_db(&options).await?;
 
@@ -96,6 +99,7 @@ async fn main() -> Result<(), Error> {
             "/public_key",
             get(server_handlers::tee_public_key::tee_public_key),
         )
+        .route("/info", get(server_handlers::info::info))
         .route(
             "/schedule_withdraw",
             post(server_handlers::schedule_withdraw::schedule_withdraw),
@@ -1,9 +1,12 @@
 use std::{sync::Arc, time::Duration};
 
-use alloy_primitives::U256;
-use alloy_signer_local::PrivateKeySigner;
+use alloy_primitives::{Address, TxHash, U256};
+use axum::Json;
 use chrono::Utc;
-use shielder_contract::{merkle_path::get_current_merkle_path, ConnectionPolicy, ShielderUser};
+use shielder_account::Token;
+use shielder_contract::ShielderReader;
+use shielder_relayer::{QuoteFeeQuery, QuoteFeeResponse, RelayQuery, RelayResponse};
+use shielder_scheduler_common::protocol::{RelayCalldata, Request, Response};
 use shielder_setup::{
     consts::ARITY, shielder_circuits::consts::merkle_constants::NOTE_TREE_HEIGHT,
 };
@@ -12,10 +15,11 @@ use tracing::{error, info, instrument, warn};
 
 use crate::{
     db::{
-        get_pending_requests, update_request_status, update_retry_attempt, RequestStatus,
-        ScheduledRequest,
+        complete_request, get_pending_requests, update_request_status, update_retry_attempt,
+        RequestStatus, ScheduledRequest,
     },
     error::SchedulerServerError,
+    handlers::tee_request,
     AppState,
 };
 
@@ -25,16 +29,21 @@ type Result<T> = std::result::Result<T, SchedulerServerError>;
 #[derive(Debug)]
 pub struct SchedulerProcessor {
     app_state: Arc<AppState>,
+    http_client: reqwest::Client,
 }
 
 #[derive(Debug)]
 struct ProcessingResult {
     request_id: i64,
+    tx_hash: TxHash,
 }
 
 impl SchedulerProcessor {
     pub fn new(app_state: Arc<AppState>) -> Self {
-        Self { app_state }
+        Self {
+            app_state,
+            http_client: reqwest::Client::new(),
+        }
     }
 
     /// Start the background processing loop
@@ -57,21 +66,22 @@ impl SchedulerProcessor {
         &self,
         leaf_index: U256,
     ) -> Result<(U256, [[U256; ARITY]; NOTE_TREE_HEIGHT])> {
-        let shielder_user = self.shielder_user_read_only();
-        Ok(get_current_merkle_path(leaf_index, &shielder_user).await?)
+        Ok(self
+            .shielder_reader()
+            .await?
+            .current_merkle_path(leaf_index)
+            .await?)
     }
 
-    fn shielder_user_read_only(&self) -> ShielderUser {
-        ShielderUser::new(
+    async fn shielder_reader(&self) -> Result<ShielderReader> {
+        Ok(ShielderReader::from_rpc_endpoints(
             self.app_state.options.shielder_address.parse().expect(
                 "Failed to parse shielder_address as a valid Ethereum address. \
 Please check the SHIELDER_ADDRESS environment variable or --shielder-address argument.",
             ),
-            ConnectionPolicy::OnDemand {
-                rpc_url: self.app_state.options.node_rpc_url.clone(),
-                signer: PrivateKeySigner::random(),
-            },
+            &self.app_state.options.node_rpc()?,
         )
+        .await?)
     }
 
     async fn process_pending_requests(&self) -> Result<()> {
@@ -105,13 +115,8 @@ Please check the SHIELDER_ADDRESS environment variable or --shielder-address arg
         match self.process_request_logic(request).await {
             Ok(result) => {
                 info!("Successfully processed request ID: {}", result.request_id);
-                update_request_status(
-                    &self.app_state.db_pool,
-                    result.request_id,
-                    RequestStatus::Completed,
-                    None,
-                )
-                .await?;
+                complete_request(&self.app_state.db_pool, result.request_id, result.tx_hash)
+                    .await?;
             }
             Err(e) => {
                 warn!(
@@ -119,7 +124,9 @@ Please check the SHIELDER_ADDRESS environment variable or --shielder-address arg
                     request_id, e
                 );
 
-                if request_retry_count < self.app_state.options.scheduler_max_retry_count as i32 {
+                if e.is_retryable()
+                    && request_retry_count < self.app_state.options.scheduler_max_retry_count as i32
+                {
                     let new_relay_after = Utc::now()
                         + Duration::from_secs(self.app_state.options.scheduler_retry_delay_secs);
 
@@ -132,8 +139,8 @@ Please check the SHIELDER_ADDRESS environment variable or --shielder-address arg
                     .await?;
                 } else {
                     warn!(
-                        "Request ID {} has reached maximum retry count, marking as Failed",
-                        request_id
+                        "Request ID {} cannot be retried (retry count: {}), marking as Failed",
+                        request_id, request_retry_count
                     );
                     update_request_status(
                         &self.app_state.db_pool,
@@ -151,30 +158,133 @@ Please check the SHIELDER_ADDRESS environment variable or --shielder-address arg
 
     /// The actual processing logic for a scheduled request
     async fn process_request_logic(&self, request: ScheduledRequest) -> Result<ProcessingResult> {
-        // Parse the U256 values
+        // Parse the stored values
         let last_note_index = request.last_note_index_as_u256().map_err(|e| {
             SchedulerServerError::ValueParseError(format!("Failed to parse last_note_index: {}", e))
         })?;
         let max_relayer_fee = request.max_relayer_fee_as_u256().map_err(|e| {
             SchedulerServerError::ValueParseError(format!("Failed to parse max_relayer_fee: {}", e))
         })?;
+        let fee_token = request.fee_token_as_token().map_err(|e| {
+            SchedulerServerError::ValueParseError(format!("Failed to parse fee_token: {}", e))
+        })?;
+        let pocket_money = request.pocket_money_as_u256().map_err(|e| {
+            SchedulerServerError::ValueParseError(format!("Failed to parse pocket_money: {}", e))
+        })?;
 
-        let (_, _merkle_path) = self.current_merkle_path(last_note_index).await?;
-
-        // TODO:
-        // 1. Get fee quote from the relayer
-        // 2. Prepare the relay calldata using the TEE
-        // 3. Submit to a relayer or relay directly to the blockchain
-
-        // For now, we'll simulate the processing
         info!(
             "Processing withdrawal request - last_note_index: {}, max_relayer_fee: {}, relay_after: {}",
             last_note_index, max_relayer_fee, request.relay_after
         );
 
-        // For now, just return success
+        // 1. Get fee quote from the relayer and make sure the user agreed to pay that much
+        let quote = self.quote_fees(fee_token, pocket_money).await?;
+        let relayer_fee = quote.fee_details.total_cost_fee_token;
+        if relayer_fee > max_relayer_fee {
+            return Err(SchedulerServerError::RelayerFeeTooHigh {
+                quoted: relayer_fee,
+                max: max_relayer_fee,
+            });
+        }
+        let relayer_address = self.relayer_fee_address().await?;
+
+        // 2. Prepare the relay calldata using the TEE
+        let (_, merkle_path) = self.current_merkle_path(last_note_index).await?;
+        let calldata = self
+            .prepare_relay_calldata(Request::PrepareRelayCalldata {
+                payload: request.payload,
+                relayer_fee,
+                relayer_address,
+                merkle_path: Box::new(merkle_path),
+            })
+            .await?;
+
+        // 3. Submit to the relayer
+        let tx_hash = self
+            .relay(RelayQuery {
+                calldata,
+                quote: quote.into(),
+            })
+            .await?;
+
         Ok(ProcessingResult {
             request_id: request.id,
+            tx_hash,
+        })
+    }
+
+    async fn quote_fees(&self, fee_token: Token, pocket_money: U256) -> Result<QuoteFeeResponse> {
+        let response = self
+            .http_client
+            .post(format!(
+                "{}/quote_fees",
+                self.app_state.options.relayer_rpc_url
+            ))
+            .json(&QuoteFeeQuery {
+                fee_token,
+                pocket_money,
+            })
+            .send()
+            .await?;
+        Ok(ensure_success(response).await?.json().await?)
+    }
+
+    async fn relayer_fee_address(&self) -> Result<Address> {
+        let response = self
+            .http_client
+            .get(format!(
+                "{}/fee_address",
+                self.app_state.options.relayer_rpc_url
+            ))
+            .send()
+            .await?;
+        let address = ensure_success(response).await?.json::<String>().await?;
+        address.parse().map_err(|e| {
+            SchedulerServerError::ValueParseError(format!("Failed to parse relayer address: {}", e))
         })
     }
+
+    async fn prepare_relay_calldata(&self, request: Request) -> Result<RelayCalldata> {
+        let app_state = self.app_state.clone();
+        let Json(response) = self
+            .app_state
+            .tee_task_pool
+            .spawn(async move { tee_request(app_state, request).await })
+            .await?
+            .await??
+            .map_err(SchedulerServerError::ProvingServerError)?;
+
+        match response {
+            Response::PrepareRelayCalldata { calldata } => Ok(calldata),
+            other => Err(SchedulerServerError::UnexpectedTeeResponse(format!(
+                "{other:?}"
+            ))),
+        }
+    }
+
+    async fn relay(&self, query: RelayQuery) -> Result<TxHash> {
+        let response = self
+            .http_client
+            .post(format!("{}/relay", self.app_state.options.relayer_rpc_url))
+            .json(&query)
+            .send()
+            .await?;
+        let relay_response = ensure_success(response)
+            .await?
+            .json::<RelayResponse>()
+            .await?;
+        info!("Relayed transaction: {}", relay_response.tx_hash);
+        Ok(relay_response.tx_hash)
+    }
+}
+
+async fn ensure_success(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    Err(SchedulerServerError::RelayerRejected {
+        status: status.as_u16(),
+        message: response.text().await.unwrap_or_default(),
+    })
 }
@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use alloy_primitives::{Address, U256};
 #[cfg(not(feature = "without_attestation"))]
@@ -7,25 +11,60 @@ use aws_nitro_enclaves_nsm_api::{
     api::Response as NsmResponse,
     driver::{nsm_exit, nsm_init, nsm_process_request},
 };
+use ecies_encryption_lib::{decrypt_padded, generate_keypair, utils::to_hex, PrivKey};
 use log::{debug, info};
+#[cfg(not(feature = "mock_prover"))]
+use shielder_account::proving::LocalProver;
+#[cfg(feature = "mock_prover")]
+use shielder_account::proving::MockProver;
+use shielder_account::{call_data::map_path_to_field, proving::ProvingBackend, Token};
+use shielder_circuits::{
+    empty_association_path,
+    withdraw::{WithdrawInstance, WithdrawProverKnowledge},
+    Field, Fr, PublicInputProvider,
+};
+use shielder_contract::WithdrawCommitment;
+use shielder_prover_circuits::WithdrawCircuit;
 use shielder_scheduler_common::{
-    protocol::{Payload, RelayCalldata, Request, Response, TEEServer},
-    vsock::VsockError,
+    protocol::{Payload, RelayCalldata, Request, Response, TEEServer, PAYLOAD_PADDING},
+    transport::{Connection, Endpoint, Listener, TransportError},
 };
-use shielder_setup::consts::{ARITY, TREE_HEIGHT};
-use tokio_vsock::{VsockAddr, VsockListener, VsockStream, VMADDR_CID_ANY};
+use shielder_setup::{
+    consts::{ARITY, TREE_HEIGHT},
+    version::{contract_version, ContractVersion},
+    vk_fingerprint::ShielderCircuit,
+};
+use tokio::runtime::Handle;
+use type_conversions::{address_to_field, field_to_u256, u256_to_field};
 
 pub struct Server {
+    private_key: Vec<u8>,
+    public_key: Vec<u8>,
+    withdraw_circuit: WithdrawCircuit,
+
     #[cfg(not(feature = "without_attestation"))]
     nsm_fd: i32,
 
-    listener: VsockListener,
+    listener: Listener,
 }
 
 impl Server {
-    pub fn new(port: u16) -> Result<Arc<Self>, VsockError> {
-        let address = VsockAddr::new(VMADDR_CID_ANY, port as u32);
-        let listener = VsockListener::bind(address)?;
+    pub async fn new(endpoint: &Endpoint) -> Result<Arc<Self>, TransportError> {
+        let listener = Listener::bind(endpoint).await?;
+        info!("Generating server's asymmetric keys...");
+
+        let (private_key, public_key) = generate_keypair();
+        info!("Server's public key: {}", to_hex(&public_key.to_bytes()));
+
+        info!("Loading withdraw proving key...");
+        let withdraw_circuit = WithdrawCircuit::new_pronto(
+            include_bytes!("../artifacts/withdraw/params.bin"),
+            include_bytes!("../artifacts/withdraw/pk.bin"),
+        );
+        info!(
+            "Withdraw verifying key fingerprint: {}",
+            withdraw_circuit.vk_fingerprint()
+        );
 
         #[cfg(not(feature = "without_attestation"))]
         let nsm_fd = Self::init_nsm_driver()?;
@@ -33,40 +72,46 @@ impl Server {
         #[cfg(feature = "without_attestation")]
         info!("Running server without attestation (TEST BUILD).");
 
+        #[cfg(feature = "mock_prover")]
+        info!("Running server with a mock prover (TEST BUILD).");
+
         Ok(Arc::new(Self {
             listener,
+            private_key: private_key.to_bytes(),
+            public_key: public_key.to_bytes(),
+            withdraw_circuit,
 
             #[cfg(not(feature = "without_attestation"))]
             nsm_fd,
         }))
     }
 
-    pub fn local_addr(&self) -> Result<VsockAddr, VsockError> {
-        Ok(self.listener.local_addr()?)
+    pub fn local_endpoint(&self) -> Result<Endpoint, TransportError> {
+        self.listener.local_endpoint()
     }
 
-    pub fn listener(&self) -> &VsockListener {
+    pub fn listener(&self) -> &Listener {
         &self.listener
     }
 
     pub fn public_key(&self) -> Vec<u8> {
-        // TODO: Implement public key retrieval logic
-        vec![0; 32] // Placeholder for the public key
+        self.public_key.clone()
     }
 
-    pub async fn handle_client(self: Arc<Self>, stream: VsockStream) {
-        let result = self.do_handle_client(stream).await;
+    pub async fn handle_client(self: Arc<Self>, connection: Connection) {
+        let result = self.do_handle_client(connection).await;
         debug!("Client disconnected: {result:?}");
     }
 
-    async fn do_handle_client(&self, stream: VsockStream) -> Result<(), VsockError> {
-        let mut server: TEEServer = stream.into();
+    async fn do_handle_client(&self, connection: Connection) -> Result<(), TransportError> {
+        let mut server: TEEServer = connection.into();
 
         loop {
             server
                 .handle_request(|request| match request {
                     Request::Ping => Ok(Response::Pong),
                     Request::TeePublicKey => self.public_key_response(),
+                    Request::Info => Ok(self.info_response()),
                     Request::PrepareRelayCalldata {
                         payload,
                         relayer_address,
@@ -83,9 +128,9 @@ impl Server {
         }
     }
 
-    fn public_key_response(&self) -> Result<Response, VsockError> {
+    fn public_key_response(&self) -> Result<Response, TransportError> {
         let public_key = self.public_key();
-        let public_key_hex = hex::encode(&public_key);
+        let public_key_hex = to_hex(&public_key);
 
         #[cfg(not(feature = "without_attestation"))]
         let attestation_document = self.request_attestation_from_nsm_driver(public_key)?;
@@ -99,48 +144,139 @@ impl Server {
         })
     }
 
+    fn info_response(&self) -> Response {
+        Response::Info {
+            contract_version: contract_version().to_bytes(),
+            vk_fingerprints: BTreeMap::from([(
+                ShielderCircuit::Withdraw.name().to_string(),
+                self.withdraw_circuit.vk_fingerprint(),
+            )]),
+        }
+    }
+
     fn prepare_relay_calldata_response(
         &self,
         payload: Vec<u8>,
-        _relayer_address: Address,
-        _relayer_fee: U256,
-        _merkle_path: Box<[[U256; ARITY]; TREE_HEIGHT]>,
-    ) -> Result<Response, VsockError> {
+        relayer_address: Address,
+        relayer_fee: U256,
+        merkle_path: Box<[[U256; ARITY]; TREE_HEIGHT]>,
+    ) -> Result<Response, TransportError> {
         let decrypted_payload = self.decrypt_payload(&payload)?;
+        let payload: Payload = serde_json::from_slice(&decrypted_payload)?;
 
-        let _deserialized_payload: Result<Payload, _> = serde_json::from_slice(&decrypted_payload);
+        if relayer_fee > payload.max_relayer_fee {
+            return Err(TransportError::Protocol(format!(
+                "Relayer fee {relayer_fee} exceeds the maximum of {}",
+                payload.max_relayer_fee
+            )));
+        }
+        if payload.relay_after > U256::from(Self::now()?) {
+            return Err(TransportError::Protocol(String::from(
+                "Withdrawal cannot be relayed yet.",
+            )));
+        }
 
-        // TODO: Implement proof generation logic here
+        let knowledge =
+            Self::withdraw_prover_knowledge(&payload, relayer_address, relayer_fee, *merkle_path);
+        // prove() might panic, which won't be caught here, however default behaviour of this server is to ignore panic
+        // see https://docs.rs/tokio/latest/tokio/runtime/enum.UnhandledPanic.html#variant.Ignore
+        let proof = self.prove(&knowledge)?;
 
         Ok(Response::PrepareRelayCalldata {
             calldata: RelayCalldata {
-                expected_contract_version: [0, 0, 0].into(),
-                amount: U256::from(0),
-                withdraw_address: Address::random(),
-                merkle_root: U256::from(0),
-                nullifier_hash: U256::from(0),
-                new_note: U256::from(0),
-                proof: Vec::new().into(),
-                fee_token: Default::default(),
-                fee_amount: U256::from(0),
-                mac_salt: U256::from(0),
-                mac_commitment: U256::from(0),
-                pocket_money: U256::from(0),
-                memo: Vec::new().into(),
+                expected_contract_version: payload.contract_version,
+                amount: payload.withdrawal_value,
+                withdraw_address: payload.withdraw_address,
+                merkle_root: field_to_u256(
+                    knowledge.compute_public_input(WithdrawInstance::MerkleRoot),
+                ),
+                nullifier_hash: field_to_u256(
+                    knowledge.compute_public_input(WithdrawInstance::HashedOldNullifier),
+                ),
+                new_note: field_to_u256(
+                    knowledge.compute_public_input(WithdrawInstance::HashedNewNote),
+                ),
+                proof: proof.into(),
+                fee_token: Token::from(payload.token_address),
+                fee_amount: relayer_fee,
+                mac_salt: payload.mac_salt,
+                mac_commitment: field_to_u256(
+                    knowledge.compute_public_input(WithdrawInstance::MacCommitment),
+                ),
+                association_set_root: field_to_u256(
+                    knowledge.compute_public_input(WithdrawInstance::AssociationSetRoot),
+                ),
+                pocket_money: payload.pocket_money,
+                memo: payload.memo,
             },
-        }) // Placeholder response
+        })
+    }
+
+    fn prove(&self, knowledge: &WithdrawProverKnowledge<Fr>) -> Result<Vec<u8>, TransportError> {
+        #[cfg(not(feature = "mock_prover"))]
+        let prover = LocalProver::new(self.withdraw_circuit.params(), self.withdraw_circuit.pk());
+        #[cfg(feature = "mock_prover")]
+        let prover = MockProver::default();
+
+        // Requests are handled synchronously (see `do_handle_client`), so wait for the proof on
+        // the current worker thread.
+        tokio::task::block_in_place(|| Handle::current().block_on(prover.prove(knowledge)))
+            .map_err(|e| TransportError::Protocol(e.to_string()))
+    }
+
+    fn withdraw_prover_knowledge(
+        payload: &Payload,
+        relayer_address: Address,
+        relayer_fee: U256,
+        merkle_path: [[U256; ARITY]; TREE_HEIGHT],
+    ) -> WithdrawProverKnowledge<Fr> {
+        let commitment = WithdrawCommitment {
+            contract_version: ContractVersion::from_bytes(payload.contract_version),
+            withdraw_address: payload.withdraw_address,
+            relayer_address,
+            relayer_fee,
+            chain_id: payload.chain_id,
+            pocket_money: payload.pocket_money,
+            protocol_fee: payload.protocol_fee,
+            memo: payload.memo.clone(),
+        }
+        .commitment_hash();
+
+        let id = u256_to_field(payload.account_id);
+
+        // Scheduled withdrawals do not claim any association set.
+        WithdrawProverKnowledge {
+            id,
+            nullifier_old: u256_to_field(payload.nullifier_old),
+            account_old_balance: u256_to_field(payload.account_old_balance),
+            token_address: address_to_field(payload.token_address),
+            path: map_path_to_field(merkle_path),
+            withdrawal_value: u256_to_field(payload.withdrawal_value),
+            nullifier_new: u256_to_field(payload.nullifier_new),
+            commitment: u256_to_field(commitment),
+            mac_salt: u256_to_field(payload.mac_salt),
+            association_set_root: Fr::ZERO,
+            association_path: empty_association_path(id),
+        }
+    }
+
+    fn decrypt_payload(&self, payload: &[u8]) -> Result<Vec<u8>, TransportError> {
+        let private_key = PrivKey::from_bytes(self.private_key.as_slice())?;
+        Ok(decrypt_padded(payload, &private_key, PAYLOAD_PADDING)?)
     }
 
-    fn decrypt_payload(&self, _payload: &[u8]) -> Result<Vec<u8>, VsockError> {
-        // TODO: Implement decryption logic here
-        Ok(_payload.to_vec()) // Placeholder for decrypted payload
+    fn now() -> Result<u64, TransportError> {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .map_err(|e| TransportError::Protocol(e.to_string()))
     }
 
     #[cfg(not(feature = "without_attestation"))]
     fn request_attestation_from_nsm_driver(
         &self,
         tee_public_key: Vec<u8>,
-    ) -> Result<Vec<u8>, VsockError> {
+    ) -> Result<Vec<u8>, TransportError> {
         match nsm_process_request(
             self.nsm_fd,
             NsmRequest::Attestation {
@@ -150,19 +286,19 @@ impl Server {
             },
         ) {
             NsmResponse::Attestation { document } => Ok(document),
-            _ => Err(VsockError::Protocol(String::from(
+            _ => Err(TransportError::Protocol(String::from(
                 "NSM driver failed to compute attestation.",
             ))),
         }
     }
 
     #[cfg(not(feature = "without_attestation"))]
-    fn init_nsm_driver() -> Result<i32, VsockError> {
+    fn init_nsm_driver() -> Result<i32, TransportError> {
         info!("Opening file descriptor to /dev/nsm driver.");
         let nsm_fd = nsm_init();
 
         if nsm_fd < 0 {
-            return Err(VsockError::Protocol(String::from(
+            return Err(TransportError::Protocol(String::from(
                 "Failed to initialize NSM driver.",
             )));
         }
@@ -3,6 +3,8 @@ pub use shielder_circuits;
 pub mod consts {
     pub const ARITY: usize = shielder_circuits::consts::merkle_constants::ARITY;
     pub const TREE_HEIGHT: usize = shielder_circuits::consts::merkle_constants::NOTE_TREE_HEIGHT;
+    pub const TOKEN_TREE_HEIGHT: usize =
+        shielder_circuits::consts::merkle_constants::TOKEN_TREE_HEIGHT;
 }
 
 pub mod native_token {
@@ -41,6 +43,8 @@ pub mod parameter_generation {
 }
 
 pub mod version {
+    use core::fmt::{self, Display, Formatter};
+
     use alloy_primitives::FixedBytes;
     use shielder_circuits::NoteVersion;
 
@@ -69,16 +73,315 @@ pub mod version {
         pub fn note_version(&self) -> NoteVersion {
             NoteVersion::new(self.note_version)
         }
+
+        /// Whether clients of `other` can interact with a contract of this version. Patch versions
+        /// change neither the note format nor the circuits, so only they may differ.
+        pub fn is_compatible_with(&self, other: &ContractVersion) -> bool {
+            self.note_version == other.note_version && self.circuit_version == other.circuit_version
+        }
     }
 
-    /// The contract version. Currently set to 0.1.1
+    impl Display for ContractVersion {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "{}.{}.{}",
+                self.note_version, self.circuit_version, self.patch_version
+            )
+        }
+    }
+
+    /// The contract version. Currently set to 0.6.1
     pub const fn contract_version() -> ContractVersion {
         ContractVersion {
             note_version: 0,
-            circuit_version: 1,
+            circuit_version: 6,
             patch_version: 1,
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::ContractVersion;
+
+        #[test]
+        fn only_patch_versions_may_differ_between_compatible_versions() {
+            let version = ContractVersion {
+                note_version: 1,
+                circuit_version: 2,
+                patch_version: 3,
+            };
+
+            assert!(version.is_compatible_with(&ContractVersion {
+                patch_version: 4,
+                ..version
+            }));
+            assert!(!version.is_compatible_with(&ContractVersion {
+                circuit_version: 3,
+                ..version
+            }));
+            assert!(!version.is_compatible_with(&ContractVersion {
+                note_version: 2,
+                ..version
+            }));
+        }
+    }
+}
+
+/// Fingerprints of the verifying keys expected by the contract.
+///
+/// A proving key is only useful if its verifying key matches the one baked into the verifier
+/// contract of the current [`contract_version`](version::contract_version). Keys loaded from disk
+/// or embedded into a binary may be stale (e.g. cached before the circuits changed), in which case
+/// every proof reverts on-chain. Loaders should call [`check_vk_fingerprint`] to fail early
+/// instead.
+///
+/// The expected fingerprints are committed in `vk_fingerprints.rs`, one entry per contract
+/// version. An entry is recorded by `make generate-verifier-contracts`, from the same verifying
+/// keys as the verifier contracts, and never changes afterwards.
+///
+/// [`check_vk_fingerprint`]: vk_fingerprint::check_vk_fingerprint
+pub mod vk_fingerprint {
+    use core::fmt::{self, Display, Formatter};
+
+    use alloy_primitives::{keccak256, B256};
+    use shielder_circuits::{
+        batch_withdraw::BatchWithdrawCircuit, circuits::VerifyingKey, claim::ClaimCircuit,
+        deposit::DepositCircuit, migration::MigrationCircuit, new_account::NewAccountCircuit,
+        transfer::TransferCircuit, withdraw::WithdrawCircuit, SERDE_FORMAT,
+    };
+
+    use crate::version::{contract_version, ContractVersion};
+
+    /// Keccak256 hash of the serialized verifying key.
+    pub type VkFingerprint = B256;
+
+    /// Circuits of the Shielder protocol.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum ShielderCircuit {
+        NewAccount,
+        Deposit,
+        Withdraw,
+        Transfer,
+        Claim,
+        Migration,
+        BalanceAttestation,
+        BatchWithdraw,
+    }
+
+    impl ShielderCircuit {
+        pub const ALL: [Self; 8] = [
+            Self::NewAccount,
+            Self::Deposit,
+            Self::Withdraw,
+            Self::Transfer,
+            Self::Claim,
+            Self::Migration,
+            Self::BalanceAttestation,
+            Self::BatchWithdraw,
+        ];
+
+        /// Circuits whose proofs are verified by the contract. Balance attestations are verified
+        /// off-chain, so there is no deployed verifying key to compare theirs with.
+        pub const VERIFIED_ON_CHAIN: [Self; 7] = [
+            Self::NewAccount,
+            Self::Deposit,
+            Self::Withdraw,
+            Self::Transfer,
+            Self::Claim,
+            Self::Migration,
+            Self::BatchWithdraw,
+        ];
+
+        /// Snake-case name, as used for artifact directories.
+        pub fn name(self) -> &'static str {
+            match self {
+                Self::NewAccount => "new_account",
+                Self::Deposit => "deposit",
+                Self::Withdraw => "withdraw",
+                Self::Transfer => "transfer",
+                Self::Claim => "claim",
+                Self::Migration => "migration",
+                Self::BalanceAttestation => "balance_attestation",
+                Self::BatchWithdraw => "batch_withdraw",
+            }
+        }
+
+        pub fn is_verified_on_chain(self) -> bool {
+            Self::VERIFIED_ON_CHAIN.contains(&self)
+        }
+    }
+
+    /// Associates a circuit type verified by the contract with its [`ShielderCircuit`].
+    pub trait DeployedCircuit {
+        const CIRCUIT: ShielderCircuit;
+    }
+
+    macro_rules! impl_deployed_circuit {
+        ($circuit_type:ty, $circuit:ident) => {
+            impl DeployedCircuit for $circuit_type {
+                const CIRCUIT: ShielderCircuit = ShielderCircuit::$circuit;
+            }
+        };
+    }
+
+    impl_deployed_circuit!(NewAccountCircuit, NewAccount);
+    impl_deployed_circuit!(DepositCircuit, Deposit);
+    impl_deployed_circuit!(WithdrawCircuit, Withdraw);
+    impl_deployed_circuit!(TransferCircuit, Transfer);
+    impl_deployed_circuit!(ClaimCircuit, Claim);
+    impl_deployed_circuit!(MigrationCircuit, Migration);
+    impl_deployed_circuit!(BatchWithdrawCircuit, BatchWithdraw);
+
+    /// Fingerprints of the verifying keys of all the [`ShielderCircuit::VERIFIED_ON_CHAIN`]
+    /// circuits, by the contract version they were recorded for.
+    pub const VK_FINGERPRINTS: &[(ContractVersion, &[(ShielderCircuit, VkFingerprint)])] =
+        include!("vk_fingerprints.rs");
+
+    /// Compute the fingerprint of `vk`.
+    pub fn vk_fingerprint(vk: &VerifyingKey) -> VkFingerprint {
+        keccak256(vk.to_bytes(SERDE_FORMAT))
+    }
+
+    /// Fingerprint of the verifying key of `circuit` expected by a contract of `version`. Patch
+    /// versions don't change the circuits, so the entry of any compatible version is used.
+    pub fn expected_vk_fingerprint(
+        version: ContractVersion,
+        circuit: ShielderCircuit,
+    ) -> Option<VkFingerprint> {
+        VK_FINGERPRINTS
+            .iter()
+            .find(|(recorded, _)| recorded.is_compatible_with(&version))?
+            .1
+            .iter()
+            .find(|(recorded, _)| *recorded == circuit)
+            .map(|(_, fingerprint)| *fingerprint)
+    }
+
+    /// A loaded verifying key can't be confirmed to be the one expected by the contract.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum VkFingerprintError {
+        /// No fingerprint of `circuit` is recorded for `contract_version`.
+        Unknown {
+            circuit: ShielderCircuit,
+            contract_version: ContractVersion,
+        },
+        /// The key doesn't match the recorded fingerprint.
+        Mismatch {
+            circuit: ShielderCircuit,
+            contract_version: ContractVersion,
+            expected: VkFingerprint,
+            actual: VkFingerprint,
+        },
+    }
+
+    impl Display for VkFingerprintError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Unknown {
+                    circuit,
+                    contract_version,
+                } => write!(
+                    f,
+                    "No verifying key fingerprint of the {} circuit is recorded for contract \
+                     version {contract_version}.",
+                    circuit.name()
+                ),
+                Self::Mismatch {
+                    circuit,
+                    contract_version,
+                    expected,
+                    actual,
+                } => write!(
+                    f,
+                    "Verifying key of the {} circuit has fingerprint {actual}, but contract \
+                     version {contract_version} expects {expected}. The proving key is stale or \
+                     was generated for another version.",
+                    circuit.name()
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for VkFingerprintError {}
+
+    /// Check that `vk` is the verifying key of `circuit` expected by the current contract version.
+    /// Returns the fingerprint of `vk`.
+    pub fn check_vk_fingerprint(
+        circuit: ShielderCircuit,
+        vk: &VerifyingKey,
+    ) -> Result<VkFingerprint, VkFingerprintError> {
+        let contract_version = contract_version();
+        let expected = expected_vk_fingerprint(contract_version, circuit).ok_or(
+            VkFingerprintError::Unknown {
+                circuit,
+                contract_version,
+            },
+        )?;
+        let actual = vk_fingerprint(vk);
+        if actual != expected {
+            return Err(VkFingerprintError::Mismatch {
+                circuit,
+                contract_version,
+                expected,
+                actual,
+            });
+        }
+        Ok(actual)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use shielder_circuits::{
+            deposit::DepositCircuit, generate_keys_with_min_k, generate_setup_params, MAX_K,
+        };
+
+        use crate::vk_fingerprint::{
+            check_vk_fingerprint, ShielderCircuit, VkFingerprintError, VK_FINGERPRINTS,
+        };
+
+        #[test]
+        fn every_version_records_exactly_the_circuits_verified_on_chain() {
+            for (version, fingerprints) in VK_FINGERPRINTS {
+                let circuits = fingerprints
+                    .iter()
+                    .map(|(circuit, _)| *circuit)
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    circuits,
+                    ShielderCircuit::VERIFIED_ON_CHAIN,
+                    "version {version}"
+                );
+            }
+        }
+
+        #[test]
+        fn every_version_is_recorded_once() {
+            for (index, (version, _)) in VK_FINGERPRINTS.iter().enumerate() {
+                assert!(VK_FINGERPRINTS[index + 1..]
+                    .iter()
+                    .all(|(other, _)| !other.is_compatible_with(version)));
+            }
+        }
+
+        #[test]
+        fn key_from_another_setup_is_rejected() {
+            let (_, _, _, vk) = generate_keys_with_min_k(
+                DepositCircuit::default(),
+                generate_setup_params(MAX_K, &mut rand::thread_rng()),
+            )
+            .unwrap();
+
+            let err = check_vk_fingerprint(ShielderCircuit::Deposit, &vk).unwrap_err();
+            assert!(matches!(
+                err,
+                VkFingerprintError::Mismatch {
+                    circuit: ShielderCircuit::Deposit,
+                    ..
+                }
+            ));
+        }
+    }
 }
 
 pub mod protocol_fee {
@@ -0,0 +1,4 @@
+// Generated by `make generate-verifier-contracts` (see `crates/halo2-verifier/src/generator.rs`).
+// Entries are only ever appended: the fingerprints of a released contract version never change.
+&[
+]
@@ -0,0 +1,543 @@
+//! Reconstruction of the public inputs of Shielder calls, in the same way as `Shielder.sol` does
+//! before calling the verifier contracts.
+
+use std::str::FromStr;
+
+use alloy_primitives::{Address, Bytes, FixedBytes, U256};
+use alloy_sol_types::SolInterface;
+use anyhow::{anyhow, Result};
+use shielder_circuits::{
+    balance_attestation::BalanceAttestationInstance, claim::ClaimInstance,
+    consts::WITHDRAW_BATCH_SIZE, deposit::DepositInstance, migration::MigrationInstance,
+    new_account::NewAccountInstance, transfer::TransferInstance, withdraw::WithdrawInstance, Fr,
+    IntoEnumIterator, PrimeField,
+};
+use shielder_contract::{
+    ClaimCommitment, DepositCommitment, MigrationCommitment, NewAccountCommitment,
+    ShielderContract::{BatchedWithdrawal, ShielderContractCalls},
+    TransferCommitment, WithdrawCommitment,
+};
+use shielder_setup::{
+    protocol_fee::compute_protocol_fee_from_gross, version::ContractVersion,
+    vk_fingerprint::ShielderCircuit,
+};
+use type_conversions::address_to_u256;
+
+/// Token address of native notes (`NATIVE_TOKEN_NOTE_ADDRESS`).
+const NATIVE_TOKEN: Address = Address::ZERO;
+
+/// Everything besides the calldata that the contract uses to build the public inputs.
+#[derive(Clone, Debug)]
+pub struct ChainContext {
+    pub contract_version: ContractVersion,
+    pub chain_id: U256,
+    /// `msg.sender` of the call.
+    pub sender: Address,
+    /// `msg.value` of the call.
+    pub value: U256,
+    pub protocol_deposit_fee_bps: U256,
+    pub protocol_withdraw_fee_bps: U256,
+    pub anonymity_revoker_pubkey: (U256, U256),
+    pub token_list_root: U256,
+}
+
+/// A Shielder call carrying a proof, together with the public inputs that the contract passes to
+/// the verifier.
+#[derive(Clone, Debug)]
+pub struct ProvenCall {
+    pub circuit: ShielderCircuit,
+    pub expected_contract_version: FixedBytes<3>,
+    pub proof: Bytes,
+    pub public_inputs: Vec<U256>,
+    /// Indices of the public inputs computed by the contract as commitments to the parameters that
+    /// are not constrained by the circuit.
+    pub commitment_indices: Vec<usize>,
+    /// Merkle roots that must be known to the contract.
+    pub merkle_roots: Vec<U256>,
+    /// Nullifiers that must not be spent yet.
+    pub nullifiers: Vec<U256>,
+}
+
+/// Decode the input of a transaction sent to the Shielder contract.
+pub fn decode_call(input: &[u8]) -> Result<ShielderContractCalls> {
+    ShielderContractCalls::abi_decode(input, true)
+        .map_err(|e| anyhow!("Input is not a Shielder call: {e}"))
+}
+
+/// Names of the public inputs of `circuit`, in the order expected by the verifier.
+pub fn public_input_names(circuit: ShielderCircuit) -> Vec<String> {
+    fn names<I: IntoEnumIterator + std::fmt::Debug>() -> Vec<String> {
+        I::iter().map(|instance| format!("{instance:?}")).collect()
+    }
+
+    match circuit {
+        ShielderCircuit::NewAccount => names::<NewAccountInstance>(),
+        ShielderCircuit::Deposit => names::<DepositInstance>(),
+        ShielderCircuit::Withdraw => names::<WithdrawInstance>(),
+        ShielderCircuit::Transfer => names::<TransferInstance>(),
+        ShielderCircuit::Claim => names::<ClaimInstance>(),
+        ShielderCircuit::Migration => names::<MigrationInstance>(),
+        ShielderCircuit::BalanceAttestation => names::<BalanceAttestationInstance>(),
+        ShielderCircuit::BatchWithdraw => (0..WITHDRAW_BATCH_SIZE)
+            .flat_map(|index| {
+                names::<WithdrawInstance>()
+                    .into_iter()
+                    .map(move |name| format!("{name}[{index}]"))
+            })
+            .collect(),
+    }
+}
+
+/// Parse a circuit name as returned by `ShielderCircuit::name`.
+pub fn parse_circuit(name: &str) -> Result<ShielderCircuit> {
+    ShielderCircuit::ALL
+        .into_iter()
+        .find(|circuit| circuit.name() == name)
+        .ok_or_else(|| {
+            let known = ShielderCircuit::ALL.map(ShielderCircuit::name).join(", ");
+            anyhow!("Unknown circuit `{name}`, expected one of: {known}")
+        })
+}
+
+/// Whether `value` is a canonical element of the scalar field (the `fieldElement` modifier).
+pub fn is_field_element(value: U256) -> bool {
+    value < field_modulus()
+}
+
+fn field_modulus() -> U256 {
+    U256::from_str(Fr::MODULUS).expect("modulus should be a valid hex number")
+}
+
+/// Reconstruct the public inputs of `call`. Returns `None` for calls that don't carry a proof.
+pub fn proven_call(call: &ShielderContractCalls, ctx: &ChainContext) -> Option<ProvenCall> {
+    use ShielderContractCalls::*;
+
+    let proven = match call {
+        newAccountNative(c) => new_account(
+            ctx,
+            c.expectedContractVersion,
+            NATIVE_TOKEN,
+            ctx.value,
+            [
+                c.newNote,
+                c.prenullifier,
+                c.symKeyEncryptionC1X,
+                c.symKeyEncryptionC1Y,
+                c.symKeyEncryptionC2X,
+                c.symKeyEncryptionC2Y,
+                c.macSalt,
+                c.macCommitment,
+            ],
+            &c.proof,
+        ),
+        newAccountERC20(c) => new_account(
+            ctx,
+            c.expectedContractVersion,
+            c.tokenAddress,
+            c.amount,
+            [
+                c.newNote,
+                c.prenullifier,
+                c.symKeyEncryptionC1X,
+                c.symKeyEncryptionC1Y,
+                c.symKeyEncryptionC2X,
+                c.symKeyEncryptionC2Y,
+                c.macSalt,
+                c.macCommitment,
+            ],
+            &c.proof,
+        ),
+        depositNative(c) => deposit(
+            ctx,
+            c.expectedContractVersion,
+            NATIVE_TOKEN,
+            ctx.value,
+            [
+                c.merkleRoot,
+                c.oldNullifierHash,
+                c.newNote,
+                c.macSalt,
+                c.macCommitment,
+            ],
+            &c.proof,
+        ),
+        depositERC20(c) => deposit(
+            ctx,
+            c.expectedContractVersion,
+            c.tokenAddress,
+            c.amount,
+            [
+                c.merkleRoot,
+                c.oldNullifierHash,
+                c.newNote,
+                c.macSalt,
+                c.macCommitment,
+            ],
+            &c.proof,
+        ),
+        withdrawNative(c) => {
+            let withdrawal = BatchedWithdrawal {
+                amount: c.amount,
+                withdrawalAddress: c.withdrawalAddress,
+                merkleRoot: c.merkleRoot,
+                oldNullifierHash: c.oldNullifierHash,
+                newNote: c.newNote,
+                relayerFee: c.relayerFee,
+                macSalt: c.macSalt,
+                macCommitment: c.macCommitment,
+                associationSetRoot: c.associationSetRoot,
+                memo: c.memo.clone(),
+            };
+            let mut proven = ProvenCall::new(
+                ShielderCircuit::Withdraw,
+                c.expectedContractVersion,
+                &c.proof,
+            );
+            push_withdrawal(
+                &mut proven,
+                ctx,
+                NATIVE_TOKEN,
+                c.relayerAddress,
+                U256::ZERO,
+                &withdrawal,
+            );
+            proven
+        }
+        withdrawERC20(c) => {
+            let withdrawal = BatchedWithdrawal {
+                amount: c.amount,
+                withdrawalAddress: c.withdrawalAddress,
+                merkleRoot: c.merkleRoot,
+                oldNullifierHash: c.oldNullifierHash,
+                newNote: c.newNote,
+                relayerFee: c.relayerFee,
+                macSalt: c.macSalt,
+                macCommitment: c.macCommitment,
+                associationSetRoot: c.associationSetRoot,
+                memo: c.memo.clone(),
+            };
+            let mut proven = ProvenCall::new(
+                ShielderCircuit::Withdraw,
+                c.expectedContractVersion,
+                &c.proof,
+            );
+            push_withdrawal(
+                &mut proven,
+                ctx,
+                c.tokenAddress,
+                c.relayerAddress,
+                ctx.value,
+                &withdrawal,
+            );
+            proven
+        }
+        withdrawBatch(c) => {
+            let mut proven = ProvenCall::new(
+                ShielderCircuit::BatchWithdraw,
+                c.expectedContractVersion,
+                &c.proof,
+            );
+            for withdrawal in &c.withdrawals {
+                push_withdrawal(
+                    &mut proven,
+                    ctx,
+                    c.tokenAddress,
+                    c.relayerAddress,
+                    U256::ZERO,
+                    withdrawal,
+                );
+            }
+            proven
+        }
+        transfer(c) => {
+            let mut proven = ProvenCall::new(
+                ShielderCircuit::Transfer,
+                c.expectedContractVersion,
+                &c.proof,
+            );
+            let commitment = TransferCommitment {
+                contract_version: ctx.contract_version,
+                chain_id: ctx.chain_id,
+                memo: c.memo.clone(),
+            }
+            .commitment_hash();
+            proven.public_inputs = vec![
+                c.merkleRoot,
+                c.oldNullifierHash,
+                c.newNote,
+                c.receipt,
+                address_to_u256(c.tokenAddress),
+                commitment,
+            ];
+            proven.public_inputs.extend(c.encryptedReceiptNonce);
+            proven.public_inputs.extend([
+                c.encryptedValue,
+                ctx.anonymity_revoker_pubkey.0,
+                ctx.anonymity_revoker_pubkey.1,
+            ]);
+            proven.public_inputs.extend(c.revokerEncryption);
+            proven.public_inputs.extend([c.macSalt, c.macCommitment]);
+            proven.commitment_indices = vec![5];
+            proven.merkle_roots = vec![c.merkleRoot];
+            proven.nullifiers = vec![c.oldNullifierHash];
+            proven
+        }
+        claim(c) => {
+            let mut proven =
+                ProvenCall::new(ShielderCircuit::Claim, c.expectedContractVersion, &c.proof);
+            let commitment = ClaimCommitment {
+                contract_version: ctx.contract_version,
+                chain_id: ctx.chain_id,
+                memo: c.memo.clone(),
+            }
+            .commitment_hash();
+            proven.public_inputs = vec![
+                c.merkleRoot,
+                c.oldNullifierHash,
+                c.newNote,
+                c.receiptNullifier,
+                address_to_u256(c.tokenAddress),
+                commitment,
+                c.encryptedValue,
+                c.macSalt,
+                c.macCommitment,
+            ];
+            proven.commitment_indices = vec![5];
+            proven.merkle_roots = vec![c.merkleRoot];
+            proven.nullifiers = vec![c.oldNullifierHash, c.receiptNullifier];
+            proven
+        }
+        migrate(c) => {
+            let mut proven = ProvenCall::new(
+                ShielderCircuit::Migration,
+                c.expectedContractVersion,
+                &c.proof,
+            );
+            let commitment = MigrationCommitment {
+                contract_version: ctx.contract_version,
+                chain_id: ctx.chain_id,
+                memo: c.memo.clone(),
+            }
+            .commitment_hash();
+            proven.public_inputs = vec![
+                c.merkleRoot,
+                c.oldNullifierHash,
+                c.newNote,
+                address_to_u256(c.tokenAddress),
+                U256::from(c.oldNoteVersion),
+                commitment,
+                c.macSalt,
+                c.macCommitment,
+            ];
+            proven.commitment_indices = vec![5];
+            proven.merkle_roots = vec![c.merkleRoot];
+            proven.nullifiers = vec![c.oldNullifierHash];
+            proven
+        }
+        _ => return None,
+    };
+    Some(proven)
+}
+
+impl ProvenCall {
+    fn new(
+        circuit: ShielderCircuit,
+        expected_contract_version: FixedBytes<3>,
+        proof: &Bytes,
+    ) -> Self {
+        Self {
+            circuit,
+            expected_contract_version,
+            proof: proof.clone(),
+            public_inputs: vec![],
+            commitment_indices: vec![],
+            merkle_roots: vec![],
+            nullifiers: vec![],
+        }
+    }
+}
+
+/// `[newNote, prenullifier, C1X, C1Y, C2X, C2Y, macSalt, macCommitment]`
+fn new_account(
+    ctx: &ChainContext,
+    expected_contract_version: FixedBytes<3>,
+    token: Address,
+    amount: U256,
+    [new_note, prenullifier, c1x, c1y, c2x, c2y, mac_salt, mac_commitment]: [U256; 8],
+    proof: &Bytes,
+) -> ProvenCall {
+    let protocol_fee = compute_protocol_fee_from_gross(amount, ctx.protocol_deposit_fee_bps);
+    let commitment = NewAccountCommitment {
+        caller_address: ctx.sender,
+        protocol_fee,
+    }
+    .commitment_hash();
+
+    let mut proven = ProvenCall::new(
+        ShielderCircuit::NewAccount,
+        expected_contract_version,
+        proof,
+    );
+    proven.public_inputs = vec![
+        new_note,
+        prenullifier,
+        amount.saturating_sub(protocol_fee),
+        commitment,
+        address_to_u256(token),
+        ctx.anonymity_revoker_pubkey.0,
+        ctx.anonymity_revoker_pubkey.1,
+        c1x,
+        c1y,
+        c2x,
+        c2y,
+        mac_salt,
+        mac_commitment,
+        ctx.token_list_root,
+    ];
+    proven.commitment_indices = vec![3];
+    // The contract refuses to register the same prenullifier twice.
+    proven.nullifiers = vec![prenullifier];
+    proven
+}
+
+/// `[merkleRoot, oldNullifierHash, newNote, macSalt, macCommitment]`
+fn deposit(
+    ctx: &ChainContext,
+    expected_contract_version: FixedBytes<3>,
+    token: Address,
+    amount: U256,
+    [merkle_root, old_nullifier_hash, new_note, mac_salt, mac_commitment]: [U256; 5],
+    proof: &Bytes,
+) -> ProvenCall {
+    let protocol_fee = compute_protocol_fee_from_gross(amount, ctx.protocol_deposit_fee_bps);
+    let commitment = DepositCommitment {
+        caller_address: ctx.sender,
+        protocol_fee,
+    }
+    .commitment_hash();
+
+    let mut proven = ProvenCall::new(ShielderCircuit::Deposit, expected_contract_version, proof);
+    proven.public_inputs = vec![
+        merkle_root,
+        old_nullifier_hash,
+        new_note,
+        amount.saturating_sub(protocol_fee),
+        commitment,
+        address_to_u256(token),
+        mac_salt,
+        mac_commitment,
+    ];
+    proven.commitment_indices = vec![4];
+    proven.merkle_roots = vec![merkle_root];
+    proven.nullifiers = vec![old_nullifier_hash];
+    proven
+}
+
+fn push_withdrawal(
+    proven: &mut ProvenCall,
+    ctx: &ChainContext,
+    token: Address,
+    relayer_address: Address,
+    pocket_money: U256,
+    withdrawal: &BatchedWithdrawal,
+) {
+    let protocol_fee =
+        compute_protocol_fee_from_gross(withdrawal.amount, ctx.protocol_withdraw_fee_bps);
+    let commitment = WithdrawCommitment {
+        contract_version: ctx.contract_version,
+        withdraw_address: withdrawal.withdrawalAddress,
+        relayer_address,
+        relayer_fee: withdrawal.relayerFee,
+        chain_id: ctx.chain_id,
+        pocket_money,
+        protocol_fee,
+        memo: withdrawal.memo.clone(),
+    }
+    .commitment_hash();
+
+    let offset = proven.public_inputs.len();
+    proven.public_inputs.extend([
+        withdrawal.merkleRoot,
+        withdrawal.oldNullifierHash,
+        withdrawal.newNote,
+        withdrawal.amount,
+        address_to_u256(token),
+        commitment,
+        withdrawal.macSalt,
+        withdrawal.macCommitment,
+        withdrawal.associationSetRoot,
+    ]);
+    proven.commitment_indices.push(offset + 5);
+    proven.merkle_roots.push(withdrawal.merkleRoot);
+    proven.nullifiers.push(withdrawal.oldNullifierHash);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, Bytes, FixedBytes, U256};
+    use alloy_sol_types::SolCall;
+    use shielder_contract::ShielderContract::{depositNativeCall, withdrawBatchCall};
+    use shielder_setup::{version::contract_version, vk_fingerprint::ShielderCircuit};
+
+    use super::{decode_call, proven_call, public_input_names, ChainContext};
+
+    fn context() -> ChainContext {
+        ChainContext {
+            contract_version: contract_version(),
+            chain_id: U256::from(1),
+            sender: Address::repeat_byte(1),
+            value: U256::from(1000),
+            protocol_deposit_fee_bps: U256::from(100),
+            protocol_withdraw_fee_bps: U256::from(100),
+            anonymity_revoker_pubkey: (U256::from(2), U256::from(3)),
+            token_list_root: U256::from(4),
+        }
+    }
+
+    #[test]
+    fn native_deposit_inputs_follow_the_contract() {
+        let input = depositNativeCall {
+            expectedContractVersion: contract_version().to_bytes(),
+            oldNullifierHash: U256::from(11),
+            newNote: U256::from(12),
+            merkleRoot: U256::from(13),
+            macSalt: U256::from(14),
+            macCommitment: U256::from(15),
+            proof: Bytes::from_static(b"proof"),
+            memo: Bytes::new(),
+        }
+        .abi_encode();
+
+        let proven = proven_call(&decode_call(&input).unwrap(), &context()).unwrap();
+
+        assert_eq!(proven.circuit, ShielderCircuit::Deposit);
+        assert_eq!(
+            proven.public_inputs.len(),
+            public_input_names(ShielderCircuit::Deposit).len()
+        );
+        // 1% of `msg.value` goes to the protocol.
+        assert_eq!(proven.public_inputs[3], U256::from(990));
+        assert_eq!(proven.merkle_roots, vec![U256::from(13)]);
+        assert_eq!(proven.nullifiers, vec![U256::from(11)]);
+    }
+
+    #[test]
+    fn batch_withdrawal_inputs_are_concatenated() {
+        let input = withdrawBatchCall {
+            expectedContractVersion: FixedBytes::ZERO,
+            tokenAddress: Address::ZERO,
+            relayerAddress: Address::repeat_byte(2),
+            withdrawals: Default::default(),
+            proof: Bytes::new(),
+        }
+        .abi_encode();
+
+        let proven = proven_call(&decode_call(&input).unwrap(), &context()).unwrap();
+
+        assert_eq!(
+            proven.public_inputs.len(),
+            public_input_names(ShielderCircuit::BatchWithdraw).len()
+        );
+        assert_eq!(proven.commitment_indices, vec![5, 14]);
+    }
+}
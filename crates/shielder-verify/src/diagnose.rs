@@ -0,0 +1,298 @@
+//! Diagnosis of a (reverted) transaction sent to the Shielder contract.
+//!
+//! The contract state is read at the block preceding the transaction, the public inputs are
+//! rebuilt from it exactly as the contract does, and every check that could have reverted the call
+//! is repeated separately: contract version, field elements, Merkle roots, nullifiers and finally
+//! the proof itself. If the proof only verifies with other protocol fees, the commitment (and not
+//! the proof) is to blame.
+
+use alloy_network::{AnyNetwork, Network, TransactionBuilder};
+use alloy_primitives::{Address, TxHash, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, TransactionTrait};
+use alloy_transport::BoxTransport;
+use anyhow::{anyhow, Result};
+use shielder_contract::{
+    decode_revert_data, providers::create_simple_provider, ShielderContract, ShielderContractError,
+};
+use shielder_setup::version::ContractVersion;
+
+use crate::{
+    calls::{decode_call, is_field_element, proven_call, public_input_names, ChainContext},
+    verify_natively,
+};
+
+pub async fn diagnose(rpc_url: &str, tx_hash: TxHash) -> Result<()> {
+    let provider = create_simple_provider(rpc_url).await?;
+
+    let tx = provider
+        .get_transaction_by_hash(tx_hash)
+        .await?
+        .ok_or_else(|| anyhow!("Transaction {tx_hash} not found"))?;
+    let contract_address = tx
+        .to()
+        .ok_or_else(|| anyhow!("Transaction {tx_hash} is a contract creation"))?;
+    let call = decode_call(tx.input())?;
+    println!("Decoded call:\n{call:#?}\n");
+
+    // The state that the transaction was executed against.
+    let state_block = match tx.block_number {
+        Some(block) => BlockId::number(block.saturating_sub(1)),
+        None => BlockId::latest(),
+    };
+    let chain_id = U256::from(provider.get_chain_id().await?);
+    let ctx = read_context(
+        &provider,
+        contract_address,
+        state_block,
+        chain_id,
+        tx.from,
+        tx.value(),
+    )
+    .await?;
+
+    let Some(proven) = proven_call(&call, &ctx) else {
+        println!("The call doesn't carry a proof, only simulating it.");
+        return simulate(&provider, contract_address, &ctx, tx.input(), state_block).await;
+    };
+    let contract = ShielderContract::new(contract_address, &provider);
+
+    // 1. Contract version.
+    if proven.expected_contract_version == ctx.contract_version.to_bytes() {
+        report(true, format!("contract version {}", ctx.contract_version));
+    } else {
+        report(
+            false,
+            format!(
+                "contract version: call expects {}, contract is {}",
+                ContractVersion::from_bytes(proven.expected_contract_version),
+                ctx.contract_version
+            ),
+        );
+    }
+
+    // 2. Field elements (commitments are always smaller than the modulus).
+    let names = public_input_names(proven.circuit);
+    let out_of_field = proven
+        .public_inputs
+        .iter()
+        .enumerate()
+        .filter(|(index, value)| {
+            !proven.commitment_indices.contains(index) && !is_field_element(**value)
+        })
+        .map(|(index, _)| names[index].as_str())
+        .collect::<Vec<_>>();
+    report(
+        out_of_field.is_empty(),
+        if out_of_field.is_empty() {
+            "all public inputs are field elements".to_string()
+        } else {
+            format!("not field elements: {}", out_of_field.join(", "))
+        },
+    );
+
+    // 3. Merkle roots. The contract keeps a history of roots, but exposes only the current one.
+    if !proven.merkle_roots.is_empty() {
+        let current_root = contract
+            .merkleTree()
+            .block(state_block)
+            .call()
+            .await
+            .map_err(ShielderContractError::from)?
+            ._0;
+        for root in &proven.merkle_roots {
+            if *root == current_root {
+                report(true, format!("merkle root {root} is the current root"));
+            } else {
+                println!(
+                    "[ ?? ] merkle root {root} is not the current root ({current_root}), it may \
+                     be a historical one or unknown to the contract"
+                );
+            }
+        }
+    }
+
+    // 4. Nullifiers.
+    for nullifier in &proven.nullifiers {
+        let spent_at = contract
+            .nullifiers(*nullifier)
+            .block(state_block)
+            .call()
+            .await
+            .map_err(ShielderContractError::from)?
+            ._0;
+        report(
+            spent_at.is_zero(),
+            if spent_at.is_zero() {
+                format!("nullifier {nullifier} is not spent")
+            } else {
+                format!("nullifier {nullifier} was already spent (block {spent_at})")
+            },
+        );
+    }
+
+    // 5. Proof against the public inputs built by the contract.
+    println!("Verifying the {} proof natively...", proven.circuit.name());
+    let fingerprint = verify_natively(proven.circuit, &proven.proof, &proven.public_inputs)?;
+    match fingerprint {
+        Ok(Some(fingerprint)) => report(true, format!("proof (verifying key {fingerprint})")),
+        Ok(None) => report(true, "proof".to_string()),
+        Err(reason) => {
+            report(false, format!("proof: {reason}"));
+            diagnose_commitment(&provider, contract_address, &call, &ctx).await?;
+        }
+    }
+
+    simulate(&provider, contract_address, &ctx, tx.input(), state_block).await
+}
+
+/// The proof didn't verify. Check whether it was generated for a commitment to different protocol
+/// fees: the fee at the latest block (fee changed in-between) or no fee at all (outdated client).
+async fn diagnose_commitment(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    contract_address: Address,
+    call: &ShielderContract::ShielderContractCalls,
+    ctx: &ChainContext,
+) -> Result<()> {
+    let latest = read_context(
+        provider,
+        contract_address,
+        BlockId::latest(),
+        ctx.chain_id,
+        ctx.sender,
+        ctx.value,
+    )
+    .await?;
+    let alternatives = [
+        (
+            "protocol fees at the latest block",
+            ChainContext {
+                protocol_deposit_fee_bps: latest.protocol_deposit_fee_bps,
+                protocol_withdraw_fee_bps: latest.protocol_withdraw_fee_bps,
+                ..ctx.clone()
+            },
+        ),
+        (
+            "zero protocol fees",
+            ChainContext {
+                protocol_deposit_fee_bps: U256::ZERO,
+                protocol_withdraw_fee_bps: U256::ZERO,
+                ..ctx.clone()
+            },
+        ),
+    ];
+
+    for (description, alternative) in alternatives {
+        if alternative.protocol_deposit_fee_bps == ctx.protocol_deposit_fee_bps
+            && alternative.protocol_withdraw_fee_bps == ctx.protocol_withdraw_fee_bps
+        {
+            continue;
+        }
+        let proven = proven_call(call, &alternative).expect("the call carries a proof");
+        if verify_natively(proven.circuit, &proven.proof, &proven.public_inputs)?.is_ok() {
+            println!(
+                "         the proof verifies with {description}: the commitment doesn't match \
+                 the contract state, regenerate the proof"
+            );
+            return Ok(());
+        }
+    }
+    println!(
+        "         the proof doesn't verify with any known commitment: it is invalid, was \
+         generated for other public inputs or with stale keys"
+    );
+    Ok(())
+}
+
+async fn read_context(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    contract_address: Address,
+    block: BlockId,
+    chain_id: U256,
+    sender: Address,
+    value: U256,
+) -> Result<ChainContext> {
+    let contract = ShielderContract::new(contract_address, provider);
+
+    let contract_version = contract
+        .CONTRACT_VERSION()
+        .block(block)
+        .call()
+        .await
+        .map_err(ShielderContractError::from)?
+        ._0;
+    let protocol_deposit_fee_bps = contract
+        .protocolDepositFeeBps()
+        .block(block)
+        .call()
+        .await
+        .map_err(ShielderContractError::from)?
+        ._0;
+    let protocol_withdraw_fee_bps = contract
+        .protocolWithdrawFeeBps()
+        .block(block)
+        .call()
+        .await
+        .map_err(ShielderContractError::from)?
+        ._0;
+    let anonymity_revoker_pubkey = contract
+        .anonymityRevokerPubkey()
+        .block(block)
+        .call()
+        .await
+        .map_err(ShielderContractError::from)?;
+    let token_list_root = contract
+        .tokenListRoot()
+        .block(block)
+        .call()
+        .await
+        .map_err(ShielderContractError::from)?
+        ._0;
+
+    Ok(ChainContext {
+        contract_version: ContractVersion::from_bytes(contract_version),
+        chain_id,
+        sender,
+        value,
+        protocol_deposit_fee_bps,
+        protocol_withdraw_fee_bps,
+        anonymity_revoker_pubkey: (anonymity_revoker_pubkey._0, anonymity_revoker_pubkey._1),
+        token_list_root,
+    })
+}
+
+/// Replay the call with `eth_call` and print the decoded revert reason.
+async fn simulate(
+    provider: &impl Provider<BoxTransport, AnyNetwork>,
+    contract_address: Address,
+    ctx: &ChainContext,
+    input: &[u8],
+    block: BlockId,
+) -> Result<()> {
+    let request = <AnyNetwork as Network>::TransactionRequest::default()
+        .with_from(ctx.sender)
+        .with_to(contract_address)
+        .with_value(ctx.value)
+        .with_input(input.to_vec());
+
+    match provider.call(&request).block(block).await {
+        Ok(_) => println!("Simulation: the call succeeds against the state before the transaction"),
+        Err(e) => match e
+            .as_error_resp()
+            .and_then(|payload| payload.as_revert_data())
+        {
+            Some(revert_data) => match decode_revert_data(&revert_data) {
+                ShielderContractError::Revert(reason) => {
+                    println!("Simulation: reverted with {reason:?}")
+                }
+                other => println!("Simulation: {other}"),
+            },
+            None => println!("Simulation: call failed: {e}"),
+        },
+    }
+    Ok(())
+}
+
+fn report(ok: bool, message: String) {
+    println!("[{}] {message}", if ok { " ok " } else { "FAIL" });
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use anyhow::{anyhow, Context, Result};
+use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
+use shielder_circuits::{
+    balance_attestation::BalanceAttestationCircuit,
+    batch_withdraw::BatchWithdrawCircuit,
+    circuits::{Params, VerifyingKey},
+    claim::ClaimCircuit,
+    deposit::DepositCircuit,
+    generate_keys_with_min_k,
+    migration::MigrationCircuit,
+    new_account::NewAccountCircuit,
+    transfer::TransferCircuit,
+    withdraw::WithdrawCircuit,
+    Circuit, Fr, MAX_K,
+};
+use shielder_setup::vk_fingerprint::{check_vk_fingerprint, ShielderCircuit, VkFingerprint};
+
+/// Verification material of a single circuit.
+pub struct VerificationKeys {
+    pub params: Params,
+    pub vk: VerifyingKey,
+    /// Present for the circuits verified by the contract.
+    pub fingerprint: Option<VkFingerprint>,
+}
+
+/// Verification material of `circuit`. Keys are generated on first use and cached, as a diagnosis
+/// may verify the same proof against several candidate public inputs.
+pub fn verification_keys(circuit: ShielderCircuit) -> Result<Arc<VerificationKeys>> {
+    static CACHE: OnceLock<Mutex<HashMap<ShielderCircuit, Arc<VerificationKeys>>>> =
+        OnceLock::new();
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .expect("keys cache should not be poisoned");
+
+    if let Some(keys) = cache.get(&circuit) {
+        return Ok(keys.clone());
+    }
+    let keys = Arc::new(generate(circuit)?);
+    cache.insert(circuit, keys.clone());
+    Ok(keys)
+}
+
+/// Generate the verifying key of `circuit` from the committed powers-of-tau, in the same way as
+/// the verifier contracts are generated. For circuits verified by the contract, fails if the key
+/// doesn't match the fingerprint expected by the current contract version.
+fn generate(circuit: ShielderCircuit) -> Result<VerificationKeys> {
+    let full_params = read_setup_parameters(
+        get_ptau_file_path(MAX_K, Format::PerpetualPowersOfTau),
+        Format::PerpetualPowersOfTau,
+    )
+    .map_err(|e| anyhow!("Failed to read parameters from the ptau file: {e}"))?;
+
+    let (params, vk) = match circuit {
+        ShielderCircuit::NewAccount => keys::<NewAccountCircuit>(full_params),
+        ShielderCircuit::Deposit => keys::<DepositCircuit>(full_params),
+        ShielderCircuit::Withdraw => keys::<WithdrawCircuit>(full_params),
+        ShielderCircuit::Transfer => keys::<TransferCircuit>(full_params),
+        ShielderCircuit::Claim => keys::<ClaimCircuit>(full_params),
+        ShielderCircuit::Migration => keys::<MigrationCircuit>(full_params),
+        ShielderCircuit::BalanceAttestation => keys::<BalanceAttestationCircuit>(full_params),
+        ShielderCircuit::BatchWithdraw => keys::<BatchWithdrawCircuit>(full_params),
+    }
+    .with_context(|| format!("Failed to generate keys for the {} circuit", circuit.name()))?;
+    let fingerprint = circuit
+        .is_verified_on_chain()
+        .then(|| check_vk_fingerprint(circuit, &vk))
+        .transpose()?;
+
+    Ok(VerificationKeys {
+        params,
+        vk,
+        fingerprint,
+    })
+}
+
+fn keys<C: Circuit<Fr> + Default>(full_params: Params) -> Result<(Params, VerifyingKey)> {
+    let (params, _, _, vk) =
+        generate_keys_with_min_k(C::default(), full_params).map_err(|e| anyhow!("{e:?}"))?;
+    Ok((params, vk))
+}
@@ -0,0 +1,120 @@
+use alloy_primitives::{hex, TxHash, U256};
+use alloy_sol_types::SolCall;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use halo2_solidity_verifier::verifier_contract::verifyProofCall;
+use shielder_circuits::Fr;
+use shielder_setup::vk_fingerprint::{ShielderCircuit, VkFingerprint};
+use type_conversions::u256_to_field;
+
+use crate::{
+    calls::{decode_call, is_field_element, parse_circuit, public_input_names},
+    keys::verification_keys,
+};
+
+mod calls;
+mod diagnose;
+mod keys;
+
+/// Verify Shielder proofs off-chain and find out why Shielder calls revert.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Verify a proof given as calldata of the verifier contract (`verifyProof(bytes,uint256[])`).
+    VerifyProof {
+        /// One of: new_account, deposit, withdraw, transfer, migration, balance_attestation,
+        /// batch_withdraw.
+        #[arg(long, value_parser = parse_circuit)]
+        circuit: ShielderCircuit,
+
+        /// Hex-encoded calldata, as produced by `verifier_contract::encode_calldata`.
+        #[arg(long)]
+        calldata: String,
+    },
+
+    /// Decode the input of a transaction sent to the Shielder contract.
+    Decode {
+        /// Hex-encoded transaction input.
+        #[arg(long)]
+        input: String,
+    },
+
+    /// Check a Shielder transaction against the contract state it was executed on, and report
+    /// whether it reverted because of the proof, a Merkle root, a nullifier or a commitment.
+    Diagnose {
+        #[arg(long)]
+        rpc_url: String,
+
+        #[arg(long)]
+        tx: TxHash,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::VerifyProof { circuit, calldata } => {
+            let call = verifyProofCall::abi_decode(&hex::decode(calldata)?, true)
+                .map_err(|e| anyhow!("Calldata is not a `verifyProof` call: {e}"))?;
+
+            let names = public_input_names(circuit);
+            if call.instances.len() != names.len() {
+                return Err(anyhow!(
+                    "The {} circuit has {} public inputs, got {}",
+                    circuit.name(),
+                    names.len(),
+                    call.instances.len()
+                ));
+            }
+            for (name, value) in names.iter().zip(&call.instances) {
+                let warning = if is_field_element(*value) {
+                    ""
+                } else {
+                    "  (not a field element!)"
+                };
+                println!("  {name}: {value}{warning}");
+            }
+
+            match verify_natively(circuit, &call.proof, &call.instances)? {
+                Ok(Some(fingerprint)) => {
+                    println!("Proof is valid (verifying key {fingerprint})")
+                }
+                Ok(None) => println!("Proof is valid"),
+                Err(reason) => return Err(anyhow!("Proof is invalid: {reason}")),
+            }
+        }
+        Command::Decode { input } => {
+            let call = decode_call(&hex::decode(input)?)?;
+            println!("{call:#?}");
+        }
+        Command::Diagnose { rpc_url, tx } => diagnose::diagnose(&rpc_url, tx).await?,
+    }
+    Ok(())
+}
+
+/// Verify `proof` with the verifying key of `circuit`. The outer error means that the verification
+/// couldn't be run at all, the inner one that the proof is invalid for `public_inputs`. Returns the
+/// fingerprint of the verifying key used, if `circuit` is verified by the contract.
+pub fn verify_natively(
+    circuit: ShielderCircuit,
+    proof: &[u8],
+    public_inputs: &[U256],
+) -> Result<Result<Option<VkFingerprint>, String>> {
+    let keys = verification_keys(circuit)?;
+    let instance = public_inputs
+        .iter()
+        .map(u256_to_field::<Fr>)
+        .collect::<Vec<_>>();
+
+    Ok(
+        shielder_circuits::verify(&keys.params, &keys.vk, proof, &instance)
+            .map(|()| keys.fingerprint)
+            .map_err(|e| format!("{e:?}")),
+    )
+}
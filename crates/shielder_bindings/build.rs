@@ -5,11 +5,15 @@
 //! When working locally, the `artifacts/` directory should be cleaned after the circuits are changed.
 use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
 use shielder_circuits::{
+    balance_attestation::BalanceAttestationCircuit,
     circuits::Params,
+    claim::ClaimCircuit,
     deposit::DepositCircuit,
     generate_keys_with_min_k,
     marshall::{marshall_params, marshall_pk},
+    migration::MigrationCircuit,
     new_account::NewAccountCircuit,
+    transfer::TransferCircuit,
     withdraw::WithdrawCircuit,
     Circuit, Fr, MAX_K,
 };
@@ -47,6 +51,26 @@ fn generate_withdraw(full_params: &Params) {
     gen_params_pk::<WithdrawCircuit>("withdraw", full_params);
 }
 
+/// This function is used to generate the artifacts for the TransferCircuit
+fn generate_transfer(full_params: &Params) {
+    gen_params_pk::<TransferCircuit>("transfer", full_params);
+}
+
+/// This function is used to generate the artifacts for the ClaimCircuit
+fn generate_claim(full_params: &Params) {
+    gen_params_pk::<ClaimCircuit>("claim", full_params);
+}
+
+/// This function is used to generate the artifacts for the MigrationCircuit
+fn generate_migration(full_params: &Params) {
+    gen_params_pk::<MigrationCircuit>("migration", full_params);
+}
+
+/// This function is used to generate the artifacts for the BalanceAttestationCircuit
+fn generate_balance_attestation(full_params: &Params) {
+    gen_params_pk::<BalanceAttestationCircuit>("balance_attestation", full_params);
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=../shielder-circuits");
     let full_params = read_setup_parameters(
@@ -58,4 +82,8 @@ fn main() {
     gen_deposit(&full_params);
     generate_new_account(&full_params);
     generate_withdraw(&full_params);
+    generate_transfer(&full_params);
+    generate_claim(&full_params);
+    generate_migration(&full_params);
+    generate_balance_attestation(&full_params);
 }
@@ -0,0 +1,79 @@
+use alloc::{format, string::String, vec::Vec};
+
+use nitro_attestation::{verify, Pcrs, Policy, AWS_NITRO_ROOT_CERTIFICATE_FINGERPRINT, PCR_LENGTH};
+#[cfg(feature = "build-wasm")]
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+/// Number of PCRs (PCR0, PCR1, PCR2) identifying an enclave image.
+const IMAGE_PCR_COUNT: usize = 3;
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Error))]
+#[derive(Debug, thiserror::Error)]
+pub enum AttestationError {
+    #[error("Attestation verification failed: {message}")]
+    VerificationFailed { message: String },
+}
+
+#[cfg(feature = "build-wasm")]
+impl From<AttestationError> for JsValue {
+    fn from(error: AttestationError) -> Self {
+        JsValue::from_str(&format!("{}", error))
+    }
+}
+
+/// SHA-256 fingerprint of the AWS Nitro Enclaves root certificate.
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn aws_nitro_root_certificate_fingerprint() -> Vec<u8> {
+    AWS_NITRO_ROOT_CERTIFICATE_FINGERPRINT.to_vec()
+}
+
+/// Verifies the attestation document returned by a TEE server together with its public key.
+///
+/// `allowed_images` is a concatenation of allowed enclave images, each encoded as
+/// `PCR0 || PCR1 || PCR2` (48 bytes each). `root_certificate_fingerprint` is the SHA-256
+/// fingerprint of the trusted root certificate (see `aws_nitro_root_certificate_fingerprint`) and
+/// `now` is the current time in seconds since the Unix epoch.
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn verify_attestation_document(
+    document: Vec<u8>,
+    public_key: Vec<u8>,
+    allowed_images: Vec<u8>,
+    root_certificate_fingerprint: Vec<u8>,
+    now: u64,
+) -> Result<(), AttestationError> {
+    let root_certificate_fingerprint = root_certificate_fingerprint.try_into().map_err(|_| {
+        AttestationError::VerificationFailed {
+            message: "Root certificate fingerprint must be 32 bytes long".into(),
+        }
+    })?;
+    if allowed_images.len() % (IMAGE_PCR_COUNT * PCR_LENGTH) != 0 {
+        return Err(AttestationError::VerificationFailed {
+            message: format!(
+                "Allowed images length must be divisible by {}",
+                IMAGE_PCR_COUNT * PCR_LENGTH
+            ),
+        });
+    }
+    let allowed_pcrs = allowed_images
+        .chunks_exact(IMAGE_PCR_COUNT * PCR_LENGTH)
+        .map(|image| {
+            image
+                .chunks_exact(PCR_LENGTH)
+                .map(<[u8]>::to_vec)
+                .enumerate()
+                .collect::<Pcrs>()
+        })
+        .collect();
+
+    let policy = Policy {
+        root_certificate_fingerprint,
+        allowed_pcrs,
+    };
+    verify(&document, &public_key, &policy, now)
+        .map(|_| ())
+        .map_err(|e| AttestationError::VerificationFailed {
+            message: format!("{e}"),
+        })
+}
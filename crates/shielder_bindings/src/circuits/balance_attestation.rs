@@ -0,0 +1,165 @@
+use alloc::vec::Vec;
+
+use shielder_circuits::{
+    balance_attestation::{BalanceAttestationInstance, BalanceAttestationProverKnowledge},
+    Fr, PublicInputProvider,
+};
+use type_conversions::field_to_bytes;
+#[cfg(feature = "build-wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::error::VerificationError;
+use crate::utils::{vec_to_f, vec_to_path};
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Record))]
+// `getter_with_clone` is required for `Vec<u8>` struct fields
+#[cfg_attr(feature = "build-wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default)]
+pub struct BalanceAttestationPubInputsBytes {
+    pub merkle_root: Vec<u8>,
+    pub h_nullifier: Vec<u8>,
+    pub token_address: Vec<u8>,
+    pub threshold: Vec<u8>,
+    pub challenge: Vec<u8>,
+}
+
+impl From<BalanceAttestationProverKnowledge<Fr>> for BalanceAttestationPubInputsBytes {
+    fn from(knowledge: BalanceAttestationProverKnowledge<Fr>) -> Self {
+        let input = |instance| field_to_bytes(knowledge.compute_public_input(instance));
+        BalanceAttestationPubInputsBytes {
+            merkle_root: input(BalanceAttestationInstance::MerkleRoot),
+            h_nullifier: input(BalanceAttestationInstance::HashedNullifier),
+            token_address: input(BalanceAttestationInstance::TokenAddress),
+            threshold: input(BalanceAttestationInstance::Threshold),
+            challenge: input(BalanceAttestationInstance::Challenge),
+        }
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Object))]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct BalanceAttestationCircuit(super::BalanceAttestationCircuit);
+
+#[cfg(feature = "build-wasm")]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl BalanceAttestationCircuit {
+    #[cfg_attr(feature = "build-wasm", wasm_bindgen(constructor))]
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        BalanceAttestationCircuit(super::BalanceAttestationCircuit::new_pronto(
+            params_buf, pk_buf,
+        ))
+    }
+}
+
+#[cfg(not(feature = "build-wasm"))]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+impl BalanceAttestationCircuit {
+    #[cfg_attr(feature = "build-uniffi", uniffi::constructor)]
+    pub fn new_pronto() -> Self {
+        BalanceAttestationCircuit(super::BalanceAttestationCircuit::new_pronto(
+            include_bytes!("../../artifacts/balance_attestation/params.bin"),
+            include_bytes!("../../artifacts/balance_attestation/pk.bin"),
+        ))
+    }
+}
+
+fn knowledge(
+    id: Vec<u8>,
+    nullifier: Vec<u8>,
+    account_balance: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    threshold: Vec<u8>,
+    challenge: Vec<u8>,
+) -> BalanceAttestationProverKnowledge<Fr> {
+    BalanceAttestationProverKnowledge {
+        challenge: vec_to_f(challenge),
+        threshold: vec_to_f(threshold),
+        id: vec_to_f(id),
+        nullifier: vec_to_f(nullifier),
+        account_balance: vec_to_f(account_balance),
+        token_address: vec_to_f(token_address),
+        path: vec_to_path(path),
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl BalanceAttestationCircuit {
+    /// Prove that the note `(id, nullifier, account_balance, token_address)` at `path` holds at
+    /// least `threshold` tokens. `challenge` is chosen by the verifier.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove(
+        &self,
+        id: Vec<u8>,
+        nullifier: Vec<u8>,
+        account_balance: Vec<u8>,
+        token_address: Vec<u8>,
+        path: Vec<u8>,
+        threshold: Vec<u8>,
+        challenge: Vec<u8>,
+    ) -> Vec<u8> {
+        self.0.prove(
+            &knowledge(
+                id,
+                nullifier,
+                account_balance,
+                token_address,
+                path,
+                threshold,
+                challenge,
+            ),
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Verify a balance attestation. Apart from this, the verifier should check that
+    /// `merkle_root` is a recent root of the note tree and that `h_nullifier` is not registered
+    /// in the contract (i.e. the attested note has not been spent).
+    pub fn verify(
+        &self,
+        merkle_root: Vec<u8>,
+        h_nullifier: Vec<u8>,
+        token_address: Vec<u8>,
+        threshold: Vec<u8>,
+        challenge: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> Result<(), VerificationError> {
+        let public_input = |input: BalanceAttestationInstance| {
+            let value = match input {
+                BalanceAttestationInstance::MerkleRoot => &merkle_root,
+                BalanceAttestationInstance::HashedNullifier => &h_nullifier,
+                BalanceAttestationInstance::TokenAddress => &token_address,
+                BalanceAttestationInstance::Threshold => &threshold,
+                BalanceAttestationInstance::Challenge => &challenge,
+            };
+            vec_to_f(value.clone())
+        };
+
+        self.0.verify(&public_input, proof).map_err(Into::into)
+    }
+}
+
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn balance_attestation_pub_inputs(
+    id: Vec<u8>,
+    nullifier: Vec<u8>,
+    account_balance: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    threshold: Vec<u8>,
+    challenge: Vec<u8>,
+) -> BalanceAttestationPubInputsBytes {
+    knowledge(
+        id,
+        nullifier,
+        account_balance,
+        token_address,
+        path,
+        threshold,
+        challenge,
+    )
+    .into()
+}
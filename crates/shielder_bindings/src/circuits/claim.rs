@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+
+use shielder_circuits::{
+    claim::{ClaimInstance, ClaimProverKnowledge},
+    Fr, PublicInputProvider,
+};
+use type_conversions::field_to_bytes;
+#[cfg(feature = "build-wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::error::VerificationError;
+use crate::utils::{vec_to_f, vec_to_path};
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Record))]
+// `getter_with_clone` is required for `Vec<u8>` struct fields
+#[cfg_attr(feature = "build-wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default)]
+pub struct ClaimPubInputsBytes {
+    pub merkle_root: Vec<u8>,
+    pub h_nullifier_old: Vec<u8>,
+    pub h_note_new: Vec<u8>,
+    pub receipt_nullifier: Vec<u8>,
+    pub token_address: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub encrypted_value: Vec<u8>,
+    pub mac_salt: Vec<u8>,
+    pub mac_commitment: Vec<u8>,
+}
+
+impl From<ClaimProverKnowledge<Fr>> for ClaimPubInputsBytes {
+    fn from(knowledge: ClaimProverKnowledge<Fr>) -> Self {
+        let input = |instance| field_to_bytes(knowledge.compute_public_input(instance));
+        ClaimPubInputsBytes {
+            merkle_root: input(ClaimInstance::MerkleRoot),
+            h_nullifier_old: input(ClaimInstance::HashedOldNullifier),
+            h_note_new: input(ClaimInstance::HashedNewNote),
+            receipt_nullifier: input(ClaimInstance::ReceiptNullifier),
+            token_address: input(ClaimInstance::TokenAddress),
+            commitment: input(ClaimInstance::Commitment),
+            encrypted_value: input(ClaimInstance::EncryptedValue),
+            mac_salt: input(ClaimInstance::MacSalt),
+            mac_commitment: input(ClaimInstance::MacCommitment),
+        }
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Object))]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct ClaimCircuit(super::ClaimCircuit);
+
+#[cfg(feature = "build-wasm")]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl ClaimCircuit {
+    #[cfg_attr(feature = "build-wasm", wasm_bindgen(constructor))]
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        ClaimCircuit(super::ClaimCircuit::new_pronto(params_buf, pk_buf))
+    }
+}
+
+#[cfg(not(feature = "build-wasm"))]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+impl ClaimCircuit {
+    #[cfg_attr(feature = "build-uniffi", uniffi::constructor)]
+    pub fn new_pronto() -> Self {
+        ClaimCircuit(super::ClaimCircuit::new_pronto(
+            include_bytes!("../../artifacts/claim/params.bin"),
+            include_bytes!("../../artifacts/claim/pk.bin"),
+        ))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn knowledge(
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance_old: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    receipt_nonce: Vec<u8>,
+    receipt_value: Vec<u8>,
+    receipt_path: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    mac_salt: Vec<u8>,
+) -> ClaimProverKnowledge<Fr> {
+    ClaimProverKnowledge {
+        id: vec_to_f(id),
+        nullifier_old: vec_to_f(nullifier_old),
+        account_old_balance: vec_to_f(account_balance_old),
+        token_address: vec_to_f(token_address),
+        path: vec_to_path(path),
+        receipt_nonce: vec_to_f(receipt_nonce),
+        receipt_value: vec_to_f(receipt_value),
+        receipt_path: vec_to_path(receipt_path),
+        nullifier_new: vec_to_f(nullifier_new),
+        mac_salt: vec_to_f(mac_salt),
+        commitment: vec_to_f(commitment),
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl ClaimCircuit {
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove(
+        &self,
+        id: Vec<u8>,
+        nullifier_old: Vec<u8>,
+        account_balance_old: Vec<u8>,
+        token_address: Vec<u8>,
+        path: Vec<u8>,
+        receipt_nonce: Vec<u8>,
+        receipt_value: Vec<u8>,
+        receipt_path: Vec<u8>,
+        nullifier_new: Vec<u8>,
+        commitment: Vec<u8>,
+        mac_salt: Vec<u8>,
+    ) -> Vec<u8> {
+        self.0.prove(
+            &knowledge(
+                id,
+                nullifier_old,
+                account_balance_old,
+                token_address,
+                path,
+                receipt_nonce,
+                receipt_value,
+                receipt_path,
+                nullifier_new,
+                commitment,
+                mac_salt,
+            ),
+            &mut rand::thread_rng(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        &self,
+        merkle_root: Vec<u8>,
+        h_nullifier_old: Vec<u8>,
+        h_note_new: Vec<u8>,
+        receipt_nullifier: Vec<u8>,
+        token_address: Vec<u8>,
+        commitment: Vec<u8>,
+        encrypted_value: Vec<u8>,
+        mac_salt: Vec<u8>,
+        mac_commitment: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> Result<(), VerificationError> {
+        let public_input = |input: ClaimInstance| {
+            let value = match input {
+                ClaimInstance::MerkleRoot => &merkle_root,
+                ClaimInstance::HashedOldNullifier => &h_nullifier_old,
+                ClaimInstance::HashedNewNote => &h_note_new,
+                ClaimInstance::ReceiptNullifier => &receipt_nullifier,
+                ClaimInstance::TokenAddress => &token_address,
+                ClaimInstance::Commitment => &commitment,
+                ClaimInstance::EncryptedValue => &encrypted_value,
+                ClaimInstance::MacSalt => &mac_salt,
+                ClaimInstance::MacCommitment => &mac_commitment,
+            };
+            vec_to_f(value.clone())
+        };
+
+        self.0.verify(&public_input, proof).map_err(Into::into)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn claim_pub_inputs(
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance_old: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    receipt_nonce: Vec<u8>,
+    receipt_value: Vec<u8>,
+    receipt_path: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    mac_salt: Vec<u8>,
+) -> ClaimPubInputsBytes {
+    knowledge(
+        id,
+        nullifier_old,
+        account_balance_old,
+        token_address,
+        path,
+        receipt_nonce,
+        receipt_value,
+        receipt_path,
+        nullifier_new,
+        commitment,
+        mac_salt,
+    )
+    .into()
+}
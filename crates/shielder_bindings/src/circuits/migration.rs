@@ -0,0 +1,185 @@
+use alloc::vec::Vec;
+
+use shielder_circuits::{
+    migration::{MigrationInstance, MigrationProverKnowledge},
+    Fr, PublicInputProvider,
+};
+use type_conversions::field_to_bytes;
+#[cfg(feature = "build-wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::error::VerificationError;
+use crate::utils::{vec_to_f, vec_to_path};
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Record))]
+// `getter_with_clone` is required for `Vec<u8>` struct fields
+#[cfg_attr(feature = "build-wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default)]
+pub struct MigrationPubInputsBytes {
+    pub merkle_root: Vec<u8>,
+    pub h_nullifier_old: Vec<u8>,
+    pub h_note_new: Vec<u8>,
+    pub token_address: Vec<u8>,
+    pub old_note_version: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub mac_salt: Vec<u8>,
+    pub mac_commitment: Vec<u8>,
+}
+
+impl From<MigrationProverKnowledge<Fr>> for MigrationPubInputsBytes {
+    fn from(knowledge: MigrationProverKnowledge<Fr>) -> Self {
+        let input = |instance| field_to_bytes(knowledge.compute_public_input(instance));
+        MigrationPubInputsBytes {
+            merkle_root: input(MigrationInstance::MerkleRoot),
+            h_nullifier_old: input(MigrationInstance::HashedOldNullifier),
+            h_note_new: input(MigrationInstance::HashedNewNote),
+            token_address: input(MigrationInstance::TokenAddress),
+            old_note_version: input(MigrationInstance::OldNoteVersion),
+            commitment: input(MigrationInstance::Commitment),
+            mac_salt: input(MigrationInstance::MacSalt),
+            mac_commitment: input(MigrationInstance::MacCommitment),
+        }
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Object))]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct MigrationCircuit(super::MigrationCircuit);
+
+#[cfg(feature = "build-wasm")]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl MigrationCircuit {
+    #[cfg_attr(feature = "build-wasm", wasm_bindgen(constructor))]
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        MigrationCircuit(super::MigrationCircuit::new_pronto(params_buf, pk_buf))
+    }
+}
+
+#[cfg(not(feature = "build-wasm"))]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+impl MigrationCircuit {
+    #[cfg_attr(feature = "build-uniffi", uniffi::constructor)]
+    pub fn new_pronto() -> Self {
+        MigrationCircuit(super::MigrationCircuit::new_pronto(
+            include_bytes!("../../artifacts/migration/params.bin"),
+            include_bytes!("../../artifacts/migration/pk.bin"),
+        ))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn knowledge(
+    old_note_version: u8,
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    mac_salt: Vec<u8>,
+) -> MigrationProverKnowledge<Fr> {
+    MigrationProverKnowledge {
+        commitment: vec_to_f(commitment),
+        old_note_version: Fr::from(old_note_version as u64),
+        id: vec_to_f(id),
+        nullifier_old: vec_to_f(nullifier_old),
+        account_balance: vec_to_f(account_balance),
+        token_address: vec_to_f(token_address),
+        path: vec_to_path(path),
+        nullifier_new: vec_to_f(nullifier_new),
+        mac_salt: vec_to_f(mac_salt),
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl MigrationCircuit {
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove(
+        &self,
+        old_note_version: u8,
+        id: Vec<u8>,
+        nullifier_old: Vec<u8>,
+        account_balance: Vec<u8>,
+        token_address: Vec<u8>,
+        path: Vec<u8>,
+        nullifier_new: Vec<u8>,
+        commitment: Vec<u8>,
+        mac_salt: Vec<u8>,
+    ) -> Vec<u8> {
+        self.0.prove(
+            &knowledge(
+                old_note_version,
+                id,
+                nullifier_old,
+                account_balance,
+                token_address,
+                path,
+                nullifier_new,
+                commitment,
+                mac_salt,
+            ),
+            &mut rand::thread_rng(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        &self,
+        merkle_root: Vec<u8>,
+        h_nullifier_old: Vec<u8>,
+        h_note_new: Vec<u8>,
+        token_address: Vec<u8>,
+        old_note_version: Vec<u8>,
+        commitment: Vec<u8>,
+        mac_salt: Vec<u8>,
+        mac_commitment: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> Result<(), VerificationError> {
+        let public_input = |input: MigrationInstance| {
+            let value = match input {
+                MigrationInstance::MerkleRoot => &merkle_root,
+                MigrationInstance::HashedOldNullifier => &h_nullifier_old,
+                MigrationInstance::HashedNewNote => &h_note_new,
+                MigrationInstance::TokenAddress => &token_address,
+                MigrationInstance::OldNoteVersion => &old_note_version,
+                MigrationInstance::Commitment => &commitment,
+                MigrationInstance::MacSalt => &mac_salt,
+                MigrationInstance::MacCommitment => &mac_commitment,
+            };
+            vec_to_f(value.clone())
+        };
+
+        self.0.verify(&public_input, proof).map_err(Into::into)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn migration_pub_inputs(
+    old_note_version: u8,
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    mac_salt: Vec<u8>,
+) -> MigrationPubInputsBytes {
+    knowledge(
+        old_note_version,
+        id,
+        nullifier_old,
+        account_balance,
+        token_address,
+        path,
+        nullifier_new,
+        commitment,
+        mac_salt,
+    )
+    .into()
+}
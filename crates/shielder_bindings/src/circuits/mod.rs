@@ -4,19 +4,28 @@ use core::marker::PhantomData;
 use halo2_proofs::plonk::Error;
 use rand::RngCore;
 use shielder_circuits::{
+    balance_attestation::BalanceAttestationProverKnowledge,
     circuits::{Params, ProvingKey, VerifyingKey},
+    claim::ClaimProverKnowledge,
     deposit::DepositProverKnowledge,
     generate_keys_with_min_k, generate_proof, generate_setup_params,
     marshall::{unmarshall_params, unmarshall_pk},
+    migration::MigrationProverKnowledge,
     new_account::NewAccountProverKnowledge,
+    transfer::TransferProverKnowledge,
     verify,
     withdraw::WithdrawProverKnowledge,
     Fr, ProverKnowledge, PublicInputProvider, MAX_K,
 };
+use shielder_setup::vk_fingerprint::{check_vk_fingerprint, DeployedCircuit};
 
+pub mod balance_attestation;
+pub mod claim;
 pub mod deposit;
 pub mod error;
+pub mod migration;
 pub mod new_account;
+pub mod transfer;
 pub mod withdraw;
 
 pub trait WasmCircuit {
@@ -32,14 +41,31 @@ pub struct Circuit<PK: ProverKnowledge> {
     _phantom: PhantomData<PK>,
 }
 
+/// Panics if the verifying key of `pk` isn't the one expected by the contract.
+fn check_deployed_pk<C: DeployedCircuit>(pk: &ProvingKey) {
+    check_vk_fingerprint(C::CIRCUIT, pk.get_vk()).unwrap_or_else(|err| panic!("{err}"));
+}
+
+/// Balance attestations are verified off-chain, so there is no deployed verifying key to check
+/// their proving keys against.
+fn accept_off_chain_pk(_: &ProvingKey) {}
+
 macro_rules! impl_decode_bytes {
     ($circuit_type:ty, $circuit_name:literal) => {
+        impl_decode_bytes!(
+            $circuit_type,
+            $circuit_name,
+            check_deployed_pk::<<$circuit_type as ProverKnowledge>::Circuit>
+        );
+    };
+    ($circuit_type:ty, $circuit_name:literal, $check_pk:expr) => {
         impl WasmCircuit for Circuit<$circuit_type> {
             fn decode_from_bytes(params_buf: &[u8], pk_buf: &[u8]) -> (Params, ProvingKey, u32) {
                 let params = unmarshall_params(params_buf).expect("Failed to unmarshall params");
 
-                let (k, pk) = unmarshall_pk::<<$circuit_type as ProverKnowledge>::Circuit>(pk_buf)
-                    .expect("Failed to unmarshall pk");
+                type C = <$circuit_type as ProverKnowledge>::Circuit;
+                let (k, pk) = unmarshall_pk::<C>(pk_buf).expect("Failed to unmarshall pk");
+                $check_pk(&pk);
 
                 (params, pk, k)
             }
@@ -50,6 +76,14 @@ macro_rules! impl_decode_bytes {
 impl_decode_bytes!(DepositProverKnowledge<Fr>, "deposit");
 impl_decode_bytes!(NewAccountProverKnowledge<Fr>, "new_account");
 impl_decode_bytes!(WithdrawProverKnowledge<Fr>, "withdraw");
+impl_decode_bytes!(TransferProverKnowledge<Fr>, "transfer");
+impl_decode_bytes!(ClaimProverKnowledge<Fr>, "claim");
+impl_decode_bytes!(MigrationProverKnowledge<Fr>, "migration");
+impl_decode_bytes!(
+    BalanceAttestationProverKnowledge<Fr>,
+    "balance_attestation",
+    accept_off_chain_pk
+);
 
 impl<PK: ProverKnowledge> Circuit<PK>
 where
@@ -127,15 +161,24 @@ where
 pub type DepositCircuit = Circuit<DepositProverKnowledge<Fr>>;
 pub type NewAccountCircuit = Circuit<NewAccountProverKnowledge<Fr>>;
 pub type WithdrawCircuit = Circuit<WithdrawProverKnowledge<Fr>>;
+pub type TransferCircuit = Circuit<TransferProverKnowledge<Fr>>;
+pub type ClaimCircuit = Circuit<ClaimProverKnowledge<Fr>>;
+pub type MigrationCircuit = Circuit<MigrationProverKnowledge<Fr>>;
+pub type BalanceAttestationCircuit = Circuit<BalanceAttestationProverKnowledge<Fr>>;
 
 #[cfg(test)]
 mod tests {
     use shielder_circuits::{
-        deposit::DepositProverKnowledge, new_account::NewAccountProverKnowledge,
+        balance_attestation::BalanceAttestationProverKnowledge, claim::ClaimProverKnowledge,
+        deposit::DepositProverKnowledge, migration::MigrationProverKnowledge,
+        new_account::NewAccountProverKnowledge, transfer::TransferProverKnowledge,
         withdraw::WithdrawProverKnowledge, Fr, ProverKnowledge,
     };
 
-    use super::{DepositCircuit, NewAccountCircuit, WithdrawCircuit};
+    use super::{
+        BalanceAttestationCircuit, ClaimCircuit, DepositCircuit, MigrationCircuit,
+        NewAccountCircuit, TransferCircuit, WithdrawCircuit,
+    };
 
     #[test]
     fn deposit_pronto() {
@@ -172,4 +215,52 @@ mod tests {
         let proof = circuit.prove(&values, &mut rng);
         circuit.verify(&values, proof).unwrap();
     }
+
+    #[test]
+    fn transfer_pronto() {
+        let mut rng = rand::thread_rng();
+        let circuit = TransferCircuit::new_pronto(
+            include_bytes!("../../artifacts/transfer/params.bin"),
+            include_bytes!("../../artifacts/transfer/pk.bin"),
+        );
+        let values = TransferProverKnowledge::<Fr>::random_correct_example(&mut rng);
+        let proof = circuit.prove(&values, &mut rng);
+        circuit.verify(&values, proof).unwrap();
+    }
+
+    #[test]
+    fn claim_pronto() {
+        let mut rng = rand::thread_rng();
+        let circuit = ClaimCircuit::new_pronto(
+            include_bytes!("../../artifacts/claim/params.bin"),
+            include_bytes!("../../artifacts/claim/pk.bin"),
+        );
+        let values = ClaimProverKnowledge::<Fr>::random_correct_example(&mut rng);
+        let proof = circuit.prove(&values, &mut rng);
+        circuit.verify(&values, proof).unwrap();
+    }
+
+    #[test]
+    fn migration_pronto() {
+        let mut rng = rand::thread_rng();
+        let circuit = MigrationCircuit::new_pronto(
+            include_bytes!("../../artifacts/migration/params.bin"),
+            include_bytes!("../../artifacts/migration/pk.bin"),
+        );
+        let values = MigrationProverKnowledge::<Fr>::random_correct_example(&mut rng);
+        let proof = circuit.prove(&values, &mut rng);
+        circuit.verify(&values, proof).unwrap();
+    }
+
+    #[test]
+    fn balance_attestation_pronto() {
+        let mut rng = rand::thread_rng();
+        let circuit = BalanceAttestationCircuit::new_pronto(
+            include_bytes!("../../artifacts/balance_attestation/params.bin"),
+            include_bytes!("../../artifacts/balance_attestation/pk.bin"),
+        );
+        let values = BalanceAttestationProverKnowledge::<Fr>::random_correct_example(&mut rng);
+        let proof = circuit.prove(&values, &mut rng);
+        circuit.verify(&values, proof).unwrap();
+    }
 }
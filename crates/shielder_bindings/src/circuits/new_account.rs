@@ -10,7 +10,7 @@ use type_conversions::field_to_bytes;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use super::error::VerificationError;
-use crate::utils::vec_to_f;
+use crate::utils::{vec_to_f, vec_to_path};
 
 #[cfg_attr(feature = "build-uniffi", derive(uniffi::Record))]
 // `getter_with_clone` is required for `Vec<u8>` struct fields
@@ -30,6 +30,7 @@ pub struct NewAccountPubInputsBytes {
     pub sym_key_encryption_2_y: Vec<u8>,
     pub mac_salt: Vec<u8>,
     pub mac_commitment: Vec<u8>,
+    pub token_list_root: Vec<u8>,
 }
 
 impl From<NewAccountProverKnowledge<Fr>> for NewAccountPubInputsBytes {
@@ -72,6 +73,9 @@ impl From<NewAccountProverKnowledge<Fr>> for NewAccountPubInputsBytes {
             mac_commitment: field_to_bytes(
                 knowledge.compute_public_input(NewAccountInstance::MacCommitment),
             ),
+            token_list_root: field_to_bytes(
+                knowledge.compute_public_input(NewAccountInstance::TokenListRoot),
+            ),
         }
     }
 }
@@ -117,6 +121,7 @@ impl NewAccountCircuit {
         mac_salt: Vec<u8>,
         anonymity_revoker_public_key_x: Vec<u8>,
         anonymity_revoker_public_key_y: Vec<u8>,
+        token_list_path: Vec<u8>,
     ) -> Vec<u8> {
         self.0.prove(
             &NewAccountProverKnowledge {
@@ -131,6 +136,7 @@ impl NewAccountCircuit {
                     x: vec_to_f(anonymity_revoker_public_key_x),
                     y: vec_to_f(anonymity_revoker_public_key_y),
                 },
+                token_list_path: vec_to_path(token_list_path),
             },
             &mut rand::thread_rng(),
         )
@@ -152,6 +158,7 @@ impl NewAccountCircuit {
         sym_key_encryption_2_y: Vec<u8>,
         mac_salt: Vec<u8>,
         mac_commitment: Vec<u8>,
+        token_list_root: Vec<u8>,
         proof: Vec<u8>,
     ) -> Result<(), VerificationError> {
         let public_input = |input: NewAccountInstance| {
@@ -169,6 +176,7 @@ impl NewAccountCircuit {
                 NewAccountInstance::EncryptedKeyCiphertext2Y => &sym_key_encryption_2_y,
                 NewAccountInstance::MacSalt => &mac_salt,
                 NewAccountInstance::MacCommitment => &mac_commitment,
+                NewAccountInstance::TokenListRoot => &token_list_root,
             };
             vec_to_f(value.clone())
         };
@@ -190,6 +198,7 @@ pub fn new_account_pub_inputs(
     mac_salt: Vec<u8>,
     anonymity_revoker_public_key_x: Vec<u8>,
     anonymity_revoker_public_key_y: Vec<u8>,
+    token_list_path: Vec<u8>,
 ) -> NewAccountPubInputsBytes {
     let knowledge = NewAccountProverKnowledge {
         id: vec_to_f(id),
@@ -203,6 +212,7 @@ pub fn new_account_pub_inputs(
             x: vec_to_f(anonymity_revoker_public_key_x),
             y: vec_to_f(anonymity_revoker_public_key_y),
         },
+        token_list_path: vec_to_path(token_list_path),
     };
 
     knowledge.into()
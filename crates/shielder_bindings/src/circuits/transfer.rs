@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+
+use shielder_circuits::{
+    field_element_to_le_bits,
+    transfer::{TransferInstance, TransferProverKnowledge},
+    Fr, GrumpkinPointAffine, PublicInputProvider,
+};
+use type_conversions::field_to_bytes;
+#[cfg(feature = "build-wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::error::VerificationError;
+use crate::utils::{vec_to_f, vec_to_path};
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Record))]
+// `getter_with_clone` is required for `Vec<u8>` struct fields
+#[cfg_attr(feature = "build-wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default)]
+pub struct TransferPubInputsBytes {
+    pub merkle_root: Vec<u8>,
+    pub h_nullifier_old: Vec<u8>,
+    pub h_note_new: Vec<u8>,
+    pub receipt: Vec<u8>,
+    pub token_address: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub encrypted_nonce_ciphertext1_x: Vec<u8>,
+    pub encrypted_nonce_ciphertext1_y: Vec<u8>,
+    pub encrypted_nonce_ciphertext2_x: Vec<u8>,
+    pub encrypted_nonce_ciphertext2_y: Vec<u8>,
+    pub encrypted_value: Vec<u8>,
+    pub anonymity_revoker_public_key_x: Vec<u8>,
+    pub anonymity_revoker_public_key_y: Vec<u8>,
+    pub revoker_ciphertext1_x: Vec<u8>,
+    pub revoker_ciphertext1_y: Vec<u8>,
+    pub revoker_ciphertext2_x: Vec<u8>,
+    pub revoker_ciphertext2_y: Vec<u8>,
+    pub mac_salt: Vec<u8>,
+    pub mac_commitment: Vec<u8>,
+}
+
+impl From<TransferProverKnowledge<Fr>> for TransferPubInputsBytes {
+    fn from(knowledge: TransferProverKnowledge<Fr>) -> Self {
+        let input = |instance| field_to_bytes(knowledge.compute_public_input(instance));
+        TransferPubInputsBytes {
+            merkle_root: input(TransferInstance::MerkleRoot),
+            h_nullifier_old: input(TransferInstance::HashedOldNullifier),
+            h_note_new: input(TransferInstance::HashedNewNote),
+            receipt: input(TransferInstance::Receipt),
+            token_address: input(TransferInstance::TokenAddress),
+            commitment: input(TransferInstance::Commitment),
+            encrypted_nonce_ciphertext1_x: input(TransferInstance::EncryptedNonceCiphertext1X),
+            encrypted_nonce_ciphertext1_y: input(TransferInstance::EncryptedNonceCiphertext1Y),
+            encrypted_nonce_ciphertext2_x: input(TransferInstance::EncryptedNonceCiphertext2X),
+            encrypted_nonce_ciphertext2_y: input(TransferInstance::EncryptedNonceCiphertext2Y),
+            encrypted_value: input(TransferInstance::EncryptedValue),
+            anonymity_revoker_public_key_x: input(TransferInstance::AnonymityRevokerPublicKeyX),
+            anonymity_revoker_public_key_y: input(TransferInstance::AnonymityRevokerPublicKeyY),
+            revoker_ciphertext1_x: input(TransferInstance::RevokerCiphertext1X),
+            revoker_ciphertext1_y: input(TransferInstance::RevokerCiphertext1Y),
+            revoker_ciphertext2_x: input(TransferInstance::RevokerCiphertext2X),
+            revoker_ciphertext2_y: input(TransferInstance::RevokerCiphertext2Y),
+            mac_salt: input(TransferInstance::MacSalt),
+            mac_commitment: input(TransferInstance::MacCommitment),
+        }
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", derive(uniffi::Object))]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct TransferCircuit(super::TransferCircuit);
+
+#[cfg(feature = "build-wasm")]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl TransferCircuit {
+    #[cfg_attr(feature = "build-wasm", wasm_bindgen(constructor))]
+    pub fn new_pronto(params_buf: &[u8], pk_buf: &[u8]) -> Self {
+        TransferCircuit(super::TransferCircuit::new_pronto(params_buf, pk_buf))
+    }
+}
+
+#[cfg(not(feature = "build-wasm"))]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+impl TransferCircuit {
+    #[cfg_attr(feature = "build-uniffi", uniffi::constructor)]
+    pub fn new_pronto() -> Self {
+        TransferCircuit(super::TransferCircuit::new_pronto(
+            include_bytes!("../../artifacts/transfer/params.bin"),
+            include_bytes!("../../artifacts/transfer/pk.bin"),
+        ))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn knowledge(
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance_old: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    value: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    recipient_tag: Vec<u8>,
+    receipt_nonce: Vec<u8>,
+    recipient_public_key_x: Vec<u8>,
+    recipient_public_key_y: Vec<u8>,
+    encryption_salt: Vec<u8>,
+    anonymity_revoker_public_key_x: Vec<u8>,
+    anonymity_revoker_public_key_y: Vec<u8>,
+    revoker_encryption_salt: Vec<u8>,
+    mac_salt: Vec<u8>,
+) -> TransferProverKnowledge<Fr> {
+    TransferProverKnowledge {
+        transfer_value: vec_to_f(value),
+        commitment: vec_to_f(commitment),
+        id: vec_to_f(id),
+        nullifier_old: vec_to_f(nullifier_old),
+        account_old_balance: vec_to_f(account_balance_old),
+        token_address: vec_to_f(token_address),
+        path: vec_to_path(path),
+        nullifier_new: vec_to_f(nullifier_new),
+        recipient_tag: vec_to_f(recipient_tag),
+        receipt_nonce: vec_to_f(receipt_nonce),
+        recipient_public_key: GrumpkinPointAffine {
+            x: vec_to_f(recipient_public_key_x),
+            y: vec_to_f(recipient_public_key_y),
+        },
+        encryption_salt: field_element_to_le_bits(vec_to_f(encryption_salt)),
+        anonymity_revoker_public_key: GrumpkinPointAffine {
+            x: vec_to_f(anonymity_revoker_public_key_x),
+            y: vec_to_f(anonymity_revoker_public_key_y),
+        },
+        revoker_encryption_salt: field_element_to_le_bits(vec_to_f(revoker_encryption_salt)),
+        mac_salt: vec_to_f(mac_salt),
+    }
+}
+
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+impl TransferCircuit {
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove(
+        &self,
+        id: Vec<u8>,
+        nullifier_old: Vec<u8>,
+        account_balance_old: Vec<u8>,
+        token_address: Vec<u8>,
+        path: Vec<u8>,
+        value: Vec<u8>,
+        nullifier_new: Vec<u8>,
+        commitment: Vec<u8>,
+        recipient_tag: Vec<u8>,
+        receipt_nonce: Vec<u8>,
+        recipient_public_key_x: Vec<u8>,
+        recipient_public_key_y: Vec<u8>,
+        encryption_salt: Vec<u8>,
+        anonymity_revoker_public_key_x: Vec<u8>,
+        anonymity_revoker_public_key_y: Vec<u8>,
+        revoker_encryption_salt: Vec<u8>,
+        mac_salt: Vec<u8>,
+    ) -> Vec<u8> {
+        self.0.prove(
+            &knowledge(
+                id,
+                nullifier_old,
+                account_balance_old,
+                token_address,
+                path,
+                value,
+                nullifier_new,
+                commitment,
+                recipient_tag,
+                receipt_nonce,
+                recipient_public_key_x,
+                recipient_public_key_y,
+                encryption_salt,
+                anonymity_revoker_public_key_x,
+                anonymity_revoker_public_key_y,
+                revoker_encryption_salt,
+                mac_salt,
+            ),
+            &mut rand::thread_rng(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        &self,
+        merkle_root: Vec<u8>,
+        h_nullifier_old: Vec<u8>,
+        h_note_new: Vec<u8>,
+        receipt: Vec<u8>,
+        token_address: Vec<u8>,
+        commitment: Vec<u8>,
+        encrypted_nonce_ciphertext1_x: Vec<u8>,
+        encrypted_nonce_ciphertext1_y: Vec<u8>,
+        encrypted_nonce_ciphertext2_x: Vec<u8>,
+        encrypted_nonce_ciphertext2_y: Vec<u8>,
+        encrypted_value: Vec<u8>,
+        anonymity_revoker_public_key_x: Vec<u8>,
+        anonymity_revoker_public_key_y: Vec<u8>,
+        revoker_ciphertext1_x: Vec<u8>,
+        revoker_ciphertext1_y: Vec<u8>,
+        revoker_ciphertext2_x: Vec<u8>,
+        revoker_ciphertext2_y: Vec<u8>,
+        mac_salt: Vec<u8>,
+        mac_commitment: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> Result<(), VerificationError> {
+        let public_input = |input: TransferInstance| {
+            let value = match input {
+                TransferInstance::MerkleRoot => &merkle_root,
+                TransferInstance::HashedOldNullifier => &h_nullifier_old,
+                TransferInstance::HashedNewNote => &h_note_new,
+                TransferInstance::Receipt => &receipt,
+                TransferInstance::TokenAddress => &token_address,
+                TransferInstance::Commitment => &commitment,
+                TransferInstance::EncryptedNonceCiphertext1X => &encrypted_nonce_ciphertext1_x,
+                TransferInstance::EncryptedNonceCiphertext1Y => &encrypted_nonce_ciphertext1_y,
+                TransferInstance::EncryptedNonceCiphertext2X => &encrypted_nonce_ciphertext2_x,
+                TransferInstance::EncryptedNonceCiphertext2Y => &encrypted_nonce_ciphertext2_y,
+                TransferInstance::EncryptedValue => &encrypted_value,
+                TransferInstance::AnonymityRevokerPublicKeyX => &anonymity_revoker_public_key_x,
+                TransferInstance::AnonymityRevokerPublicKeyY => &anonymity_revoker_public_key_y,
+                TransferInstance::RevokerCiphertext1X => &revoker_ciphertext1_x,
+                TransferInstance::RevokerCiphertext1Y => &revoker_ciphertext1_y,
+                TransferInstance::RevokerCiphertext2X => &revoker_ciphertext2_x,
+                TransferInstance::RevokerCiphertext2Y => &revoker_ciphertext2_y,
+                TransferInstance::MacSalt => &mac_salt,
+                TransferInstance::MacCommitment => &mac_commitment,
+            };
+            vec_to_f(value.clone())
+        };
+
+        self.0.verify(&public_input, proof).map_err(Into::into)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn transfer_pub_inputs(
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance_old: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    value: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    recipient_tag: Vec<u8>,
+    receipt_nonce: Vec<u8>,
+    recipient_public_key_x: Vec<u8>,
+    recipient_public_key_y: Vec<u8>,
+    encryption_salt: Vec<u8>,
+    anonymity_revoker_public_key_x: Vec<u8>,
+    anonymity_revoker_public_key_y: Vec<u8>,
+    revoker_encryption_salt: Vec<u8>,
+    mac_salt: Vec<u8>,
+) -> TransferPubInputsBytes {
+    knowledge(
+        id,
+        nullifier_old,
+        account_balance_old,
+        token_address,
+        path,
+        value,
+        nullifier_new,
+        commitment,
+        recipient_tag,
+        receipt_nonce,
+        recipient_public_key_x,
+        recipient_public_key_y,
+        encryption_salt,
+        anonymity_revoker_public_key_x,
+        anonymity_revoker_public_key_y,
+        revoker_encryption_salt,
+        mac_salt,
+    )
+    .into()
+}
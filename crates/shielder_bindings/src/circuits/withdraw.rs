@@ -1,8 +1,9 @@
 use alloc::vec::Vec;
 
 use shielder_circuits::{
+    empty_association_path,
     withdraw::{WithdrawInstance, WithdrawProverKnowledge},
-    Fr, PublicInputProvider,
+    Field, Fr, PublicInputProvider,
 };
 use type_conversions::field_to_bytes;
 #[cfg(feature = "build-wasm")]
@@ -24,6 +25,7 @@ pub struct WithdrawPubInputsBytes {
     pub commitment: Vec<u8>,
     pub mac_salt: Vec<u8>,
     pub mac_commitment: Vec<u8>,
+    pub association_set_root: Vec<u8>,
 }
 
 impl From<WithdrawProverKnowledge<Fr>> for WithdrawPubInputsBytes {
@@ -51,6 +53,9 @@ impl From<WithdrawProverKnowledge<Fr>> for WithdrawPubInputsBytes {
             mac_commitment: field_to_bytes(
                 knowledge.compute_public_input(WithdrawInstance::MacCommitment),
             ),
+            association_set_root: field_to_bytes(
+                knowledge.compute_public_input(WithdrawInstance::AssociationSetRoot),
+            ),
         }
     }
 }
@@ -96,19 +101,23 @@ impl WithdrawCircuit {
         nullifier_new: Vec<u8>,
         commitment: Vec<u8>,
         mac_salt: Vec<u8>,
+        association_set_root: Option<Vec<u8>>,
+        association_path: Option<Vec<u8>>,
     ) -> Vec<u8> {
         self.0.prove(
-            &WithdrawProverKnowledge {
-                id: vec_to_f(id),
-                nullifier_old: vec_to_f(nullifier_old),
-                account_old_balance: vec_to_f(account_balance_old),
-                token_address: vec_to_f(token_address),
-                path: vec_to_path(path),
-                withdrawal_value: vec_to_f(value),
-                nullifier_new: vec_to_f(nullifier_new),
-                commitment: vec_to_f(commitment),
-                mac_salt: vec_to_f(mac_salt),
-            },
+            &knowledge(
+                id,
+                nullifier_old,
+                account_balance_old,
+                token_address,
+                path,
+                value,
+                nullifier_new,
+                commitment,
+                mac_salt,
+                association_set_root,
+                association_path,
+            ),
             &mut rand::thread_rng(),
         )
     }
@@ -125,9 +134,13 @@ impl WithdrawCircuit {
         mac_salt: Vec<u8>,
         mac_commitment: Vec<u8>,
         proof: Vec<u8>,
+        association_set_root: Option<Vec<u8>>,
     ) -> Result<(), VerificationError> {
         let public_input = |input: WithdrawInstance| {
             let value = match input {
+                WithdrawInstance::AssociationSetRoot => {
+                    return association_set_root.clone().map_or(Fr::ZERO, vec_to_f)
+                }
                 WithdrawInstance::MerkleRoot => &merkle_root,
                 WithdrawInstance::HashedOldNullifier => &h_nullifier_old,
                 WithdrawInstance::HashedNewNote => &h_note_new,
@@ -157,9 +170,51 @@ pub fn withdraw_pub_inputs(
     nullifier_new: Vec<u8>,
     commitment: Vec<u8>,
     mac_salt: Vec<u8>,
+    association_set_root: Option<Vec<u8>>,
+    association_path: Option<Vec<u8>>,
 ) -> WithdrawPubInputsBytes {
-    let knowledge = WithdrawProverKnowledge {
-        id: vec_to_f(id),
+    knowledge(
+        id,
+        nullifier_old,
+        account_balance_old,
+        token_address,
+        path,
+        value,
+        nullifier_new,
+        commitment,
+        mac_salt,
+        association_set_root,
+        association_path,
+    )
+    .into()
+}
+
+/// If `association_set_root` is not provided, no association set is claimed.
+#[allow(clippy::too_many_arguments)]
+fn knowledge(
+    id: Vec<u8>,
+    nullifier_old: Vec<u8>,
+    account_balance_old: Vec<u8>,
+    token_address: Vec<u8>,
+    path: Vec<u8>,
+    value: Vec<u8>,
+    nullifier_new: Vec<u8>,
+    commitment: Vec<u8>,
+    mac_salt: Vec<u8>,
+    association_set_root: Option<Vec<u8>>,
+    association_path: Option<Vec<u8>>,
+) -> WithdrawProverKnowledge<Fr> {
+    let id = vec_to_f(id);
+    let (association_set_root, association_path) = match association_set_root {
+        Some(root) => (
+            vec_to_f(root),
+            vec_to_path(association_path.unwrap_or_default()),
+        ),
+        None => (Fr::ZERO, empty_association_path(id)),
+    };
+
+    WithdrawProverKnowledge {
+        id,
         nullifier_old: vec_to_f(nullifier_old),
         account_old_balance: vec_to_f(account_balance_old),
         token_address: vec_to_f(token_address),
@@ -168,7 +223,7 @@ pub fn withdraw_pub_inputs(
         nullifier_new: vec_to_f(nullifier_new),
         commitment: vec_to_f(commitment),
         mac_salt: vec_to_f(mac_salt),
-    };
-
-    knowledge.into()
+        association_set_root,
+        association_path,
+    }
 }
@@ -8,6 +8,7 @@ pub use wasm_bindgen_rayon::init_thread_pool;
 
 extern crate alloc;
 
+pub mod attestation;
 #[cfg(feature = "circuits")]
 pub mod circuits;
 pub mod conversions;
@@ -1,7 +1,10 @@
 use alloc::vec::Vec;
 
 use alloy_primitives::U256;
-use shielder_account::secrets::{self, nonced::derive_nullifier};
+use shielder_account::secrets::{
+    self,
+    nonced::{derive_nullifier, derive_recipient_seed},
+};
 use shielder_circuits::Fr;
 use type_conversions::{bytes_to_u256, field_to_bytes, hex_to_u256, u256_to_bytes, u256_to_field};
 #[cfg(feature = "build-wasm")]
@@ -39,3 +42,21 @@ pub fn derive_id(private_key_hex: &str, chain_id: u64, account_nonce: u32) -> Ve
     let on_curve_id = shielder_circuits::generate_user_id(id_seed_fr.to_bytes());
     field_to_bytes(on_curve_id)
 }
+
+/// Deterministically computes the receipt nonce of a transfer made by the account `id` with
+/// `nonce`.
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn derive_receipt_nonce(id: Vec<u8>, nonce: u32) -> Vec<u8> {
+    let id: U256 = bytes_to_u256(id).expect("Expecting a 32-byte vector");
+    let seed: Fr = u256_to_field(derive_recipient_seed(id, nonce));
+    field_to_bytes(shielder_circuits::generate_receipt_nonce(seed.to_bytes()))
+}
+
+/// Computes the receiving tag of the account `id`, to which transfers to the account are bound.
+#[cfg_attr(feature = "build-wasm", wasm_bindgen)]
+#[cfg_attr(feature = "build-uniffi", uniffi::export)]
+pub fn receiving_tag(id: Vec<u8>) -> Vec<u8> {
+    let id: Fr = u256_to_field(bytes_to_u256(id).expect("Expecting a 32-byte vector"));
+    field_to_bytes(shielder_circuits::receiving_tag(id))
+}
@@ -1,10 +1,7 @@
 use alloc::vec::Vec;
 
 use shielder_circuits::{
-    consts::{
-        merkle_constants::{ARITY, NOTE_TREE_HEIGHT},
-        POSEIDON_RATE,
-    },
+    consts::{merkle_constants::ARITY, POSEIDON_RATE},
     poseidon::off_circuit::hash,
     Fr,
 };
@@ -33,17 +30,17 @@ pub fn vec_to_f(v: Vec<u8>) -> Fr {
     bytes_to_field(v).expect("failed to convert to F")
 }
 
-pub fn vec_to_path(v: Vec<u8>) -> [[Fr; ARITY]; NOTE_TREE_HEIGHT] {
+pub fn vec_to_path<const TREE_HEIGHT: usize>(v: Vec<u8>) -> [[Fr; ARITY]; TREE_HEIGHT] {
     assert_eq!(
-        NOTE_TREE_HEIGHT * ARITY * Fr::size(),
+        TREE_HEIGHT * ARITY * Fr::size(),
         v.len(),
         "Vector length must be divisible by TREE_HEIGHT * ARITY * F::size()"
     );
 
-    let mut result = [[Fr::default(); ARITY]; NOTE_TREE_HEIGHT];
+    let mut result = [[Fr::default(); ARITY]; TREE_HEIGHT];
     let mut iter = v.chunks_exact(Fr::size());
 
-    for row in result.iter_mut().take(NOTE_TREE_HEIGHT) {
+    for row in result.iter_mut().take(TREE_HEIGHT) {
         for elem in row.iter_mut().take(ARITY) {
             if let Some(chunk) = iter.next() {
                 *elem = Fr::from_bytes(
@@ -2,10 +2,11 @@ use alloy_signer_local::PrivateKeySigner;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use shielder_account::{
     call_data::{NewAccountCallExtra, NewAccountCallType},
+    proving::{ProvingBackend, ProvingError},
     ShielderAccount, Token,
 };
 use shielder_circuits::{
-    circuits::{Params, ProvingKey},
+    consts::merkle_constants::{ARITY, TOKEN_TREE_HEIGHT},
     GrumpkinPointAffine,
 };
 use shielder_contract::{
@@ -52,17 +53,17 @@ impl Actor {
         self.shielder_user.address()
     }
 
-    pub fn prepare_new_account_call(
+    pub async fn prepare_new_account_call(
         &self,
-        params: &Params,
-        pk: &ProvingKey,
+        prover: &impl ProvingBackend,
         amount: U256,
         protocol_fee: U256,
-    ) -> newAccountNativeCall {
-        self.account
-            .prepare_call::<NewAccountCallType>(
-                params,
-                pk,
+        token_list_path: [[U256; ARITY]; TOKEN_TREE_HEIGHT],
+    ) -> Result<newAccountNativeCall, ProvingError> {
+        let call = self
+            .account
+            .prepare_call_with::<NewAccountCallType>(
+                prover,
                 Token::Native,
                 amount,
                 &NewAccountCallExtra {
@@ -72,10 +73,11 @@ impl Actor {
                     caller_address: self.shielder_user.address(),
                     protocol_fee,
                     memo: Bytes::from(vec![]),
+                    token_list_path,
                 },
             )
-            .try_into()
-            .unwrap()
+            .await?;
+        Ok(call.try_into().unwrap())
     }
 }
 
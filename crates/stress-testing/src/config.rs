@@ -1,8 +1,11 @@
 use alloy_signer_local::PrivateKeySigner;
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use shielder_contract::alloy_primitives::Address;
 
 #[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("tee_attestation").args(["tee_allowed_pcr0", "insecure_unattested_tee"])
+))]
 pub struct Config {
     #[clap(long, value_parser = parsing::parse_signer)]
     pub master_seed: PrivateKeySigner,
@@ -21,14 +24,37 @@ pub struct Config {
 
     #[clap(long, value_parser = parsing::parse_address)]
     pub relayer_address: Address,
+
+    /// Use the asynchronous relay API and measure latency until the relay transaction is
+    /// submitted.
+    #[clap(long, default_value = "false")]
+    pub async_relay: bool,
+
+    /// Generate proofs with the TEE prover server at this URL instead of locally.
+    #[clap(long, requires = "tee_attestation")]
+    pub tee_prover_url: Option<String>,
+
+    /// Allowed PCR0 value (hex-encoded measurement of the enclave image) of the TEE prover. Can be
+    /// repeated to allow several images.
+    #[clap(long = "tee-allowed-pcr0", value_parser = parsing::parse_pcr)]
+    pub tee_allowed_pcr0: Vec<parsing::Pcr>,
+
+    /// Trust any public key returned by the TEE prover, without checking its attestation.
+    #[clap(long, default_value = "false")]
+    pub insecure_unattested_tee: bool,
 }
 
 mod parsing {
     use std::str::FromStr;
 
     use alloy_signer_local::PrivateKeySigner;
-    use anyhow::{anyhow, Result};
-    use shielder_contract::alloy_primitives::Address;
+    use anyhow::{anyhow, bail, Result};
+    use shielder_account::proving::PCR_LENGTH;
+    use shielder_contract::alloy_primitives::{hex::FromHex, Address, Bytes};
+
+    /// Wrapper type for a PCR value, so that clap doesn't treat it as a list of bytes.
+    #[derive(Clone, Debug)]
+    pub struct Pcr(pub Vec<u8>);
 
     pub fn parse_address(string: &str) -> Result<Address> {
         Address::from_str(string).map_err(|e| anyhow!(e))
@@ -37,4 +63,12 @@ mod parsing {
     pub fn parse_signer(string: &str) -> Result<PrivateKeySigner> {
         PrivateKeySigner::from_str(string).map_err(|e| anyhow!(e))
     }
+
+    pub fn parse_pcr(string: &str) -> Result<Pcr> {
+        let pcr = Bytes::from_hex(string).map_err(|e| anyhow!(e))?;
+        if pcr.len() != PCR_LENGTH {
+            bail!("Invalid PCR, expected {PCR_LENGTH} bytes");
+        }
+        Ok(Pcr(pcr.to_vec()))
+    }
 }
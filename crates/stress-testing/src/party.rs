@@ -1,15 +1,13 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use alloy_provider::Provider;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use shielder_account::{
     call_data::{WithdrawCallType, WithdrawExtra},
+    proving::ProvingBackend,
     Token,
 };
-use shielder_circuits::{
-    circuits::{Params, ProvingKey},
-    withdraw::WithdrawCircuit,
-};
+use shielder_circuits::withdraw::WithdrawCircuit;
 use shielder_contract::{
     alloy_primitives::{Bytes, U256},
     merkle_path::get_current_merkle_path,
@@ -17,10 +15,16 @@ use shielder_contract::{
     providers::create_simple_provider,
     ShielderContract::withdrawNativeCall,
 };
-use shielder_relayer::{QuoteFeeQuery, QuoteFeeResponse, RelayCalldata, RelayQuery, RelayQuote};
+use shielder_relayer::{
+    QuoteFeeQuery, QuoteFeeResponse, RelayCalldata, RelayMode, RelayModeQuery, RelayQuery,
+    RelayQuote, RelayStatus, RelayStatusResponse, RelayTicket,
+};
 use shielder_setup::{protocol_fee::compute_protocol_fee_from_net, version::contract_version};
 
-use crate::{actor::Actor, config::Config, util::proving_keys, WITHDRAW_AMOUNT};
+use crate::{actor::Actor, config::Config, util::ProvingSetup, WITHDRAW_AMOUNT};
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const STATUS_POLL_ATTEMPTS: u32 = 600;
 
 pub async fn enter_pandemonium(config: &Config, actors: Vec<Actor>) -> Result<()> {
     let task_inputs = prepare_relay_queries(config, actors).await?;
@@ -28,10 +32,14 @@ pub async fn enter_pandemonium(config: &Config, actors: Vec<Actor>) -> Result<()
 
     println!("🎉 Entering pandemonium! 🎉");
     let mut handles = vec![];
+    let mode = match config.async_relay {
+        true => RelayMode::Async,
+        false => RelayMode::Sync,
+    };
     for (actor, query) in task_inputs {
         let relayer = config.relayer_url.clone();
         handles.push(tokio::spawn(async move {
-            actor_task(actor, query, relayer).await
+            actor_task(actor, query, relayer, mode).await
         }));
     }
 
@@ -52,35 +60,75 @@ pub async fn enter_pandemonium(config: &Config, actors: Vec<Actor>) -> Result<()
     Ok(())
 }
 
-async fn actor_task(actor: Actor, query: RelayQuery, relayer_rpc_url: String) -> Result<bool> {
+async fn actor_task(
+    actor: Actor,
+    query: RelayQuery,
+    relayer_rpc_url: String,
+    mode: RelayMode,
+) -> Result<bool> {
     println!("  🚀 Actor {} is starting the withdrawal...", actor.id);
 
     let start = Instant::now();
-    let status = reqwest::Client::new()
-        .post(relayer_rpc_url + "/relay")
+    let response = reqwest::Client::new()
+        .post(relayer_rpc_url.clone() + "/relay")
+        .query(&RelayModeQuery { mode })
         .json(&query)
         .send()
-        .await?
-        .status();
-    let elapsed = start.elapsed();
+        .await?;
+    let status = response.status();
 
-    if status.is_success() {
-        println!("  ✅ Actor {} succeeded! Latency: {elapsed:?}.", actor.id);
-        Ok(true)
-    } else {
+    if !status.is_success() {
         println!(
-            "  ❌ Actor {} failed: {status:?}. Latency: {elapsed:?}.",
-            actor.id
+            "  ❌ Actor {} failed: {status:?}. Latency: {:?}.",
+            actor.id,
+            start.elapsed()
         );
-        Ok(false)
+        return Ok(false);
+    }
+
+    if mode == RelayMode::Async {
+        let ticket = response.json::<RelayTicket>().await?;
+        if let Err(e) = wait_for_submission(&relayer_rpc_url, ticket).await {
+            println!(
+                "  ❌ Actor {} failed: {e}. Latency: {:?}.",
+                actor.id,
+                start.elapsed()
+            );
+            return Ok(false);
+        }
     }
+
+    println!(
+        "  ✅ Actor {} succeeded! Latency: {:?}.",
+        actor.id,
+        start.elapsed()
+    );
+    Ok(true)
+}
+
+async fn wait_for_submission(relayer_rpc_url: &str, ticket: RelayTicket) -> Result<()> {
+    for _ in 0..STATUS_POLL_ATTEMPTS {
+        let status = reqwest::get(format!("{relayer_rpc_url}/relay/{}", ticket.id))
+            .await?
+            .json::<RelayStatusResponse>()
+            .await?;
+        match status.status {
+            RelayStatus::Submitted | RelayStatus::Mined => return Ok(()),
+            RelayStatus::Failed => bail!("{:?}", status.error),
+            RelayStatus::Queued | RelayStatus::DryRun | RelayStatus::Submitting => {
+                tokio::time::sleep(STATUS_POLL_INTERVAL).await
+            }
+        }
+    }
+    bail!("Relay was not submitted in time (ticket: {})", ticket.id)
 }
 
 async fn prepare_relay_queries(
     config: &Config,
     actors: Vec<Actor>,
 ) -> Result<Vec<(Actor, RelayQuery)>> {
-    let (params, pk) = proving_keys::<WithdrawCircuit>();
+    let proving_setup = ProvingSetup::new::<WithdrawCircuit>(config);
+    let prover = proving_setup.prover();
     let mut result = Vec::new();
 
     let quote = reqwest::Client::new()
@@ -96,7 +144,7 @@ async fn prepare_relay_queries(
 
     println!("⏳ Preparing relay queries for actors...");
     for actor in actors {
-        let query = prepare_relay_query(config, &actor, &params, &pk, quote.clone()).await?;
+        let query = prepare_relay_query(config, &actor, &prover, quote.clone()).await?;
         result.push((actor, query));
     }
     Ok(result)
@@ -105,8 +153,7 @@ async fn prepare_relay_queries(
 async fn prepare_relay_query(
     config: &Config,
     actor: &Actor,
-    params: &Params,
-    pk: &ProvingKey,
+    prover: &impl ProvingBackend,
     quote: QuoteFeeResponse,
 ) -> Result<RelayQuery> {
     let (merkle_root, merkle_path) =
@@ -123,9 +170,8 @@ async fn prepare_relay_query(
 
     let calldata: withdrawNativeCall = actor
         .account
-        .prepare_call::<WithdrawCallType>(
-            params,
-            pk,
+        .prepare_call_with::<WithdrawCallType>(
+            prover,
             Token::Native,
             amount,
             &WithdrawExtra {
@@ -139,8 +185,10 @@ async fn prepare_relay_query(
                 pocket_money: U256::ZERO,
                 protocol_fee,
                 memo: Bytes::from(vec![]),
+                association_set: None,
             },
         )
+        .await?
         .try_into()
         .unwrap();
 
@@ -157,6 +205,7 @@ async fn prepare_relay_query(
             fee_amount: calldata.relayerFee,
             mac_salt: calldata.macSalt,
             mac_commitment: calldata.macCommitment,
+            association_set_root: calldata.associationSetRoot,
             pocket_money: U256::ZERO,
             memo: calldata.memo,
         },
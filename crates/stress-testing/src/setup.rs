@@ -13,11 +13,12 @@ use shielder_contract::{
     call_type::{Call, DryRun},
     events::get_event,
     providers::create_simple_provider,
+    token_list::get_token_list_path,
     ShielderContract::NewAccount,
 };
 use shielder_setup::protocol_fee::compute_protocol_fee_from_net;
 
-use crate::{actor::Actor, config::Config, util::proving_keys, INITIAL_BALANCE, SHIELDED_BALANCE};
+use crate::{actor::Actor, config::Config, util::ProvingSetup, INITIAL_BALANCE, SHIELDED_BALANCE};
 
 pub async fn setup_world(config: &Config) -> Result<Vec<Actor>> {
     let mut actors = generate_actors(config);
@@ -67,18 +68,25 @@ async fn distribute_tokens(config: &Config, actors: &[Actor]) -> Result<()> {
 }
 
 async fn shield_tokens(config: &Config, actors: &mut [Actor]) -> Result<()> {
-    let (params, pk) = proving_keys::<NewAccountCircuit>();
+    let proving_setup = ProvingSetup::new::<NewAccountCircuit>(config);
+    let prover = proving_setup.prover();
     let shielded_amount = U256::from(SHIELDED_BALANCE);
     let provider = create_simple_provider(&config.node_rpc_url).await?;
 
-    let protocol_fee = if let Some(actor) = actors.first() {
-        let protocol_fee_bps = actor
-            .shielder_user
-            .protocol_deposit_fee_bps::<DryRun>()
-            .await?;
-        compute_protocol_fee_from_net(shielded_amount, protocol_fee_bps)
-    } else {
-        U256::ZERO
+    let (protocol_fee, token_list_path) = match actors.first() {
+        Some(actor) => {
+            let protocol_fee_bps = actor
+                .shielder_user
+                .protocol_deposit_fee_bps::<DryRun>()
+                .await?;
+            let token_list_path =
+                get_token_list_path(Token::Native.address(), &actor.shielder_user).await?;
+            (
+                compute_protocol_fee_from_net(shielded_amount, protocol_fee_bps),
+                token_list_path,
+            )
+        }
+        None => return Ok(()),
     };
     let total_amount = shielded_amount + protocol_fee;
 
@@ -87,7 +95,9 @@ async fn shield_tokens(config: &Config, actors: &mut [Actor]) -> Result<()> {
         shielded_amount, protocol_fee
     );
     for actor in actors {
-        let call = actor.prepare_new_account_call(&params, &pk, total_amount, protocol_fee);
+        let call = actor
+            .prepare_new_account_call(&prover, total_amount, protocol_fee, token_list_path)
+            .await?;
 
         let (tx_hash, block_hash) = actor
             .shielder_user
@@ -1,9 +1,12 @@
 use powers_of_tau::{get_ptau_file_path, read as read_setup_parameters, Format};
+use shielder_account::proving::{AttestationPolicy, LocalProver, Pcrs, Prover, TeeProver};
 use shielder_circuits::{
     circuits::{Params, ProvingKey},
     generate_keys_with_min_k, Circuit, Fr, MAX_K,
 };
 
+use crate::config::Config;
+
 pub fn proving_keys<C: Circuit<Fr> + Default>() -> (Params, ProvingKey) {
     let params = read_setup_parameters(
         get_ptau_file_path(MAX_K, Format::PerpetualPowersOfTau),
@@ -13,3 +16,42 @@ pub fn proving_keys<C: Circuit<Fr> + Default>() -> (Params, ProvingKey) {
     let (params, _, pk, _) = generate_keys_with_min_k(C::default(), params).unwrap();
     (params, pk)
 }
+
+/// Proving backend selected by the config, together with the keys it needs.
+pub enum ProvingSetup {
+    Local { params: Params, pk: ProvingKey },
+    Tee(TeeProver),
+}
+
+impl ProvingSetup {
+    /// Generate keys of `C` for local proving, unless a TEE prover is configured.
+    pub fn new<C: Circuit<Fr> + Default>(config: &Config) -> Self {
+        match &config.tee_prover_url {
+            Some(url) if config.insecure_unattested_tee => {
+                ProvingSetup::Tee(TeeProver::new_insecure_unattested(url.as_str()))
+            }
+            Some(url) => {
+                let allowed_pcrs = config
+                    .tee_allowed_pcr0
+                    .iter()
+                    .map(|pcr0| Pcrs::from([(0, pcr0.0.clone())]))
+                    .collect();
+                ProvingSetup::Tee(TeeProver::new(
+                    url.as_str(),
+                    AttestationPolicy::aws(allowed_pcrs),
+                ))
+            }
+            None => {
+                let (params, pk) = proving_keys::<C>();
+                ProvingSetup::Local { params, pk }
+            }
+        }
+    }
+
+    pub fn prover(&self) -> Prover<'_> {
+        match self {
+            ProvingSetup::Local { params, pk } => Prover::Local(LocalProver::new(params, pk)),
+            ProvingSetup::Tee(prover) => Prover::Tee(prover.clone()),
+        }
+    }
+}
@@ -27,6 +27,13 @@ contract DeployShielderScript is Script {
 
         bool isArbitrumChain = vm.envBool("IS_ARBITRUM_CHAIN");
 
+        address[] memory tokenList = vm.envOr(
+            "TOKEN_LIST",
+            ",",
+            new address[](0)
+        );
+        uint256 tokenListRoot = vm.envOr("TOKEN_LIST_ROOT", uint256(0));
+
         vm.startBroadcast(privateKey);
 
         address shielderImplementation = address(new Shielder());
@@ -45,7 +52,9 @@ contract DeployShielderScript is Script {
                 isArbitrumChain,
                 protocolDepositFeeBps,
                 protocolWithdrawFeeBps,
-                protocolFeeReceiver
+                protocolFeeReceiver,
+                tokenList,
+                tokenListRoot
             )
         );
 
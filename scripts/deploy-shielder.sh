@@ -20,6 +20,9 @@ function show_help {
     echo "  PROTOCOL_DEPOSIT_FEE_BPS    Fee charged on the deposit amount in BPS (default: 0)"
     echo "  PROTOCOL_WITHDRAW_FEE_BPS   Fee charged on the withdraw amount in BPS. (default: 0)"
     echo "  PROTOCOL_FEE_RECEIVER       Receiver of the protocol fee (default: Public Address of the PRIVATE_KEY)"
+    echo "  TOKEN_LIST                  ERC20 tokens that can be shielded, as a CSV list (default: none)"
+    echo "  TOKEN_LIST_ROOT             Root of the token list tree, see \`shielder-cli token-list-root\`"
+    echo "                              (default: 0, no new accounts can be created until \`setTokenList\` is called)"
     echo ""
     echo "If AR_PUBLIC_KEY is provided, it will be used directly."
     echo "If AR_SEED is provided but not AR_PUBLIC_KEY, a new key pair will be generated."
@@ -42,6 +45,13 @@ IS_ARBITRUM_CHAIN=${IS_ARBITRUM_CHAIN:-false}
 PROTOCOL_DEPOSIT_FEE_BPS=${PROTOCOL_DEPOSIT_FEE_BPS:-0}
 PROTOCOL_WITHDRAW_FEE_BPS=${PROTOCOL_WITHDRAW_FEE_BPS:-0}
 PROTOCOL_FEE_RECEIVER=${PROTOCOL_FEE_RECEIVER:-$OWNER_ADDRESS}
+TOKEN_LIST_ROOT=${TOKEN_LIST_ROOT:-0}
+# An empty list cannot be parsed by the deployment script, so it is passed only if given.
+if [ -n "${TOKEN_LIST:-}" ]; then
+    export TOKEN_LIST
+else
+    unset TOKEN_LIST
+fi
 
 # Handle AR public key
 if [ -n "${AR_PUBLIC_KEY:-}" ]; then
@@ -81,4 +91,5 @@ IS_ARBITRUM_CHAIN=${IS_ARBITRUM_CHAIN} \
 PROTOCOL_DEPOSIT_FEE_BPS=${PROTOCOL_DEPOSIT_FEE_BPS} \
 PROTOCOL_WITHDRAW_FEE_BPS=${PROTOCOL_WITHDRAW_FEE_BPS} \
 PROTOCOL_FEE_RECEIVER=${PROTOCOL_FEE_RECEIVER} \
+TOKEN_LIST_ROOT=${TOKEN_LIST_ROOT} \
 forge script DeployShielderScript --broadcast --rpc-url ${NETWORK} --sender $(cast wallet address ${PRIVATE_KEY})
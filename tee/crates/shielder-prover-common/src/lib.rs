@@ -1,3 +1,3 @@
 pub mod base64_serialization;
 pub mod protocol;
-pub mod vsock;
+pub use tee_transport as transport;
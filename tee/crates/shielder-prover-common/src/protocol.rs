@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
+use alloy_primitives::{FixedBytes, B256};
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    base64_serialization,
-    vsock::{VsockClient, VsockServer},
-};
+use tee_transport::{Client, Server};
+
+use crate::base64_serialization;
 
 pub const VSOCK_PORT: u16 = 5000;
 
@@ -46,6 +48,10 @@ pub enum Request {
     /// Retrieves TEE Public Key, ie key which is used by the user to encrypt inputs to a circuit
     TeePublicKey,
 
+    /// Retrieves the contract version and the verifying key fingerprints of the circuits embedded
+    /// in the TEE server.
+    Info,
+
     /// Request for generate proof and pub inputs.
     /// It is encrypted using TEE Public Key.
     /// For `payload` schema, see [`RequestGenerateProofPayload`].
@@ -67,6 +73,13 @@ pub enum Response {
         attestation_document: Vec<u8>,
     },
 
+    /// Response to [`Request::Info`].
+    Info {
+        contract_version: FixedBytes<3>,
+        /// Fingerprints (see `shielder_setup::vk_fingerprint`) by circuit name.
+        vk_fingerprints: BTreeMap<String, B256>,
+    },
+
     /// A ZK-proof computed on the [`Request::GenerateProof`] request.
     /// It is encrypted using a public key sent in the request.
     /// For `payload` schema, see [`ResponseGenerateProofPayload`].
@@ -77,8 +90,8 @@ pub enum Response {
     },
 }
 
-pub type ProverServer = VsockServer<Request, Response>;
-pub type ProverClient = VsockClient<Request, Response>;
+pub type ProverServer = Server<Request, Response>;
+pub type ProverClient = Client<Request, Response>;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[repr(u8)]
@@ -1,4 +1,5 @@
 use clap::Parser;
+use shielder_prover_common::transport::Endpoint;
 
 #[derive(Parser, Debug, Clone)]
 pub struct CommandLineArgs {
@@ -24,6 +25,11 @@ pub struct CommandLineArgs {
     #[clap(long, default_value_t = vsock::VMADDR_CID_HOST, env = "TEE_CID")]
     pub tee_cid: u32,
 
+    /// Endpoint of the TEE server, overriding `tee_cid` and `tee_port`, e.g. `tcp://127.0.0.1:5000`
+    /// or `unix:///tmp/tee.sock` when the TEE binary runs locally instead of in an enclave
+    #[clap(long, env = "TEE_ENDPOINT")]
+    pub tee_endpoint: Option<Endpoint>,
+
     /// How many incoming requests can this server handle at once
     /// Do not raise it above 128 as this is the limit of vsock connections, at least
     /// for the rust lib used by this server
@@ -50,3 +56,13 @@ pub struct CommandLineArgs {
     #[clap(long, default_value_t = 60, env = "METRICS_BUCKET_DURATION_SECS")]
     pub(crate) metrics_bucket_duration_secs: u64,
 }
+
+impl CommandLineArgs {
+    /// Endpoint on which the TEE server is reachable.
+    pub fn tee_address(&self) -> Endpoint {
+        self.tee_endpoint.clone().unwrap_or(Endpoint::Vsock {
+            cid: self.tee_cid,
+            port: self.tee_port as u32,
+        })
+    }
+}
@@ -2,7 +2,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response as AxumResponse},
 };
-use shielder_prover_common::vsock::VsockError;
+use shielder_prover_common::transport::TransportError;
 use tokio::task::JoinError;
 use tracing::error;
 
@@ -18,7 +18,7 @@ pub enum ShielderProverServerError {
     JoinHandleError(#[from] JoinError),
 
     #[error("Proving Server error: {0}")]
-    ProvingServerError(#[from] VsockError),
+    ProvingServerError(#[from] TransportError),
 
     #[error("Failed to initialize metrics: {0}")]
     MetricsError(#[from] metrics_exporter_prometheus::BuildError),
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use shielder_prover_common::{
     base64_serialization,
     protocol::{ProverClient, Request, Response},
-    vsock::VsockError,
+    transport::TransportError,
 };
 use tracing::{info_span, Instrument as _};
 
@@ -13,11 +13,12 @@ use crate::{handlers::metrics::FutureTimingMetric::*, AppState};
 
 pub mod generate_proof;
 pub mod health;
+pub mod info;
 pub mod metrics;
 pub mod tee_public_key;
 
-async fn request(state: Arc<AppState>, request: Request) -> Result<Json<Response>, VsockError> {
-    let mut tee_client = ProverClient::new(state.options.tee_cid, state.options.tee_port as u32)
+async fn request(state: Arc<AppState>, request: Request) -> Result<Json<Response>, TransportError> {
+    let mut tee_client = ProverClient::connect(&state.options.tee_address())
         .instrument(info_span!(BuildingVsocksConnection.name()))
         .await?;
 
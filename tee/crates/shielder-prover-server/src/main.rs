@@ -67,6 +67,7 @@ async fn main() -> Result<(), Error> {
             "/public_key",
             get(server_handlers::tee_public_key::tee_public_key),
         )
+        .route("/info", get(server_handlers::info::info))
         .route(
             "/proof",
             post(server_handlers::generate_proof::generate_proof),
@@ -1,21 +1,34 @@
-mod circuits;
 mod server;
 use log::info;
-use shielder_prover_common::{protocol::VSOCK_PORT, vsock::VsockError};
+use shielder_prover_common::{
+    protocol::VSOCK_PORT,
+    transport::{Endpoint, TransportError},
+};
+use tokio_vsock::VMADDR_CID_ANY;
 
 #[tokio::main]
-async fn main() -> Result<(), VsockError> {
+async fn main() -> Result<(), TransportError> {
     tracing_subscriber::fmt::init();
 
-    let server = server::Server::new(VSOCK_PORT)?;
-    info!("Server listening on: {:?}", server.local_addr()?);
+    // Outside of an enclave, e.g. in integration tests, the endpoint to listen on can be given
+    // as the first argument (`tcp://127.0.0.1:5000`, `unix:///tmp/tee.sock`).
+    let endpoint = match std::env::args().nth(1) {
+        Some(endpoint) => endpoint.parse()?,
+        None => Endpoint::Vsock {
+            cid: VMADDR_CID_ANY,
+            port: VSOCK_PORT as u32,
+        },
+    };
+
+    let server = server::Server::new(&endpoint).await?;
+    info!("Server listening on: {}", server.local_endpoint()?);
 
     loop {
-        let (stream, _) = server.listener().accept().await?;
+        let connection = server.listener().accept().await?;
 
         let server_clone = server.clone();
         tokio::spawn(async move {
-            server_clone.handle_client(stream).await;
+            server_clone.handle_client(connection).await;
         });
     }
 }
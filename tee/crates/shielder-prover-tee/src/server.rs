@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 #[cfg(not(feature = "without_attestation"))]
 use aws_nitro_enclaves_nsm_api::{
@@ -12,39 +12,64 @@ use ecies_encryption_lib::{
 use log::{debug, info};
 use serde::Deserialize;
 use serde_json::Deserializer as JsonDeserializer;
+use shielder_prover_circuits::{
+    deposit::DepositCircuit, new_account::NewAccountCircuit, withdraw::WithdrawCircuit,
+    SerializableCircuit,
+};
 use shielder_prover_common::{
     protocol::{
         CircuitType, ProverServer, Request, RequestGenerateProofPayload, Response,
         ResponseGenerateProofPayload, REQUEST_PAYLOAD_PADDING, RESPONSE_PAYLOAD_PADDING,
     },
-    vsock::VsockError,
-};
-use tokio_vsock::{VsockAddr, VsockListener, VsockStream, VMADDR_CID_ANY};
-
-use crate::circuits::{
-    deposit::SerializableDepositCircuit, new_account::SerializableNewAccountCircuit,
-    withdraw::SerializableWithdrawCircuit, SerializableCircuit,
+    transport::{Connection, Endpoint, Listener, TransportError},
 };
+use shielder_setup::{version::contract_version, vk_fingerprint::ShielderCircuit};
 
 pub struct Server {
     private_key: Vec<u8>,
     public_key: Vec<u8>,
+    new_account_circuit: NewAccountCircuit,
+    deposit_circuit: DepositCircuit,
+    withdraw_circuit: WithdrawCircuit,
 
     #[cfg(not(feature = "without_attestation"))]
     nsm_fd: i32,
 
-    listener: VsockListener,
+    listener: Listener,
 }
 
 impl Server {
-    pub fn new(port: u16) -> Result<Arc<Self>, VsockError> {
-        let address = VsockAddr::new(VMADDR_CID_ANY, port as u32);
-        let listener = VsockListener::bind(address)?;
+    pub async fn new(endpoint: &Endpoint) -> Result<Arc<Self>, TransportError> {
+        let listener = Listener::bind(endpoint).await?;
         info!("Generating server's asymmetric keys...");
 
         let (private_key, public_key) = generate_keypair();
         info!("Server's public key: {}", to_hex(&public_key.to_bytes()));
 
+        info!("Loading proving keys...");
+        let new_account_circuit = NewAccountCircuit::new_pronto(
+            include_bytes!("../artifacts/new_account/params.bin"),
+            include_bytes!("../artifacts/new_account/pk.bin"),
+        );
+        let deposit_circuit = DepositCircuit::new_pronto(
+            include_bytes!("../artifacts/deposit/params.bin"),
+            include_bytes!("../artifacts/deposit/pk.bin"),
+        );
+        let withdraw_circuit = WithdrawCircuit::new_pronto(
+            include_bytes!("../artifacts/withdraw/params.bin"),
+            include_bytes!("../artifacts/withdraw/pk.bin"),
+        );
+        for (circuit, fingerprint) in [
+            (
+                ShielderCircuit::NewAccount,
+                new_account_circuit.vk_fingerprint(),
+            ),
+            (ShielderCircuit::Deposit, deposit_circuit.vk_fingerprint()),
+            (ShielderCircuit::Withdraw, withdraw_circuit.vk_fingerprint()),
+        ] {
+            info!("{circuit:?} verifying key fingerprint: {fingerprint}");
+        }
+
         #[cfg(not(feature = "without_attestation"))]
         let nsm_fd = Self::init_nsm_driver()?;
 
@@ -55,43 +80,47 @@ impl Server {
             listener,
             private_key: private_key.to_bytes(),
             public_key: public_key.to_bytes(),
+            new_account_circuit,
+            deposit_circuit,
+            withdraw_circuit,
 
             #[cfg(not(feature = "without_attestation"))]
             nsm_fd,
         }))
     }
 
-    pub fn local_addr(&self) -> Result<VsockAddr, VsockError> {
-        Ok(self.listener.local_addr()?)
+    pub fn local_endpoint(&self) -> Result<Endpoint, TransportError> {
+        self.listener.local_endpoint()
     }
 
-    pub fn listener(&self) -> &VsockListener {
+    pub fn listener(&self) -> &Listener {
         &self.listener
     }
 
     pub fn public_key(&self) -> Vec<u8> {
         self.public_key.clone()
     }
-    pub async fn handle_client(self: Arc<Self>, stream: VsockStream) {
-        let result = self.do_handle_client(stream).await;
+    pub async fn handle_client(self: Arc<Self>, connection: Connection) {
+        let result = self.do_handle_client(connection).await;
         debug!("Client disconnected: {result:?}");
     }
 
-    async fn do_handle_client(&self, stream: VsockStream) -> Result<(), VsockError> {
-        let mut server: ProverServer = stream.into();
+    async fn do_handle_client(&self, connection: Connection) -> Result<(), TransportError> {
+        let mut server: ProverServer = connection.into();
 
         loop {
             server
                 .handle_request(|request| match request {
                     Request::Ping => Ok(Response::Pong),
                     Request::TeePublicKey => self.public_key_response(),
+                    Request::Info => Ok(self.info_response()),
                     Request::GenerateProof { payload } => self.encrypted_proof_response(payload),
                 })
                 .await?;
         }
     }
 
-    fn public_key_response(&self) -> Result<Response, VsockError> {
+    fn public_key_response(&self) -> Result<Response, TransportError> {
         let public_key = self.public_key();
         let public_key_hex = to_hex(&public_key);
 
@@ -107,11 +136,31 @@ impl Server {
         })
     }
 
+    fn info_response(&self) -> Response {
+        Response::Info {
+            contract_version: contract_version().to_bytes(),
+            vk_fingerprints: BTreeMap::from([
+                (
+                    ShielderCircuit::NewAccount.name().to_string(),
+                    self.new_account_circuit.vk_fingerprint(),
+                ),
+                (
+                    ShielderCircuit::Deposit.name().to_string(),
+                    self.deposit_circuit.vk_fingerprint(),
+                ),
+                (
+                    ShielderCircuit::Withdraw.name().to_string(),
+                    self.withdraw_circuit.vk_fingerprint(),
+                ),
+            ]),
+        }
+    }
+
     #[cfg(not(feature = "without_attestation"))]
     fn request_attestation_from_nsm_driver(
         &self,
         tee_public_key: Vec<u8>,
-    ) -> Result<Vec<u8>, VsockError> {
+    ) -> Result<Vec<u8>, TransportError> {
         match nsm_process_request(
             self.nsm_fd,
             NsmRequest::Attestation {
@@ -121,19 +170,22 @@ impl Server {
             },
         ) {
             NsmResponse::Attestation { document } => Ok(document),
-            _ => Err(VsockError::Protocol(String::from(
+            _ => Err(TransportError::Protocol(String::from(
                 "NSM driver failed to compute attestation.",
             ))),
         }
     }
 
-    fn encrypted_proof_response(&self, request_payload: Vec<u8>) -> Result<Response, VsockError> {
+    fn encrypted_proof_response(
+        &self,
+        request_payload: Vec<u8>,
+    ) -> Result<Response, TransportError> {
         let decrypted_payload = self.decrypt_using_servers_private_key(&request_payload)?;
 
         let deserialized_payload: RequestGenerateProofPayload =
             serde_json::from_slice(&decrypted_payload)?;
 
-        let (proof, pub_inputs) = Self::compute_proof(
+        let (proof, pub_inputs) = self.compute_proof(
             &deserialized_payload.circuit_inputs,
             deserialized_payload.circuit_type,
         )?;
@@ -149,43 +201,42 @@ impl Server {
         })
     }
 
-    fn encrypt_bytes(user_public_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, VsockError> {
+    fn encrypt_bytes(user_public_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, TransportError> {
         let pub_key = PubKey::from_bytes(user_public_key)?;
         let encrypted_bytes = encrypt_padded(&bytes, &pub_key, RESPONSE_PAYLOAD_PADDING)?;
         Ok(encrypted_bytes)
     }
 
     fn compute_proof(
+        &self,
         serialized_circuit_inputs: &[u8],
         circuit_type: CircuitType,
-    ) -> Result<(Vec<u8>, Vec<u8>), VsockError> {
+    ) -> Result<(Vec<u8>, Vec<u8>), TransportError> {
         let (proof, pub_inputs) = match circuit_type {
             CircuitType::NewAccount => Self::compute_proof_for_circuit(
                 serialized_circuit_inputs,
-                SerializableNewAccountCircuit::new(),
-            )?,
-            CircuitType::Deposit => Self::compute_proof_for_circuit(
-                serialized_circuit_inputs,
-                SerializableDepositCircuit::new(),
-            )?,
-            CircuitType::Withdraw => Self::compute_proof_for_circuit(
-                serialized_circuit_inputs,
-                SerializableWithdrawCircuit::new(),
+                &self.new_account_circuit,
             )?,
+            CircuitType::Deposit => {
+                Self::compute_proof_for_circuit(serialized_circuit_inputs, &self.deposit_circuit)?
+            }
+            CircuitType::Withdraw => {
+                Self::compute_proof_for_circuit(serialized_circuit_inputs, &self.withdraw_circuit)?
+            }
         };
         Ok((proof, pub_inputs))
     }
 
     fn compute_proof_for_circuit<C>(
         serialized_circuit_inputs: &[u8],
-        circuit: C,
-    ) -> Result<(Vec<u8>, Vec<u8>), VsockError>
+        circuit: &C,
+    ) -> Result<(Vec<u8>, Vec<u8>), TransportError>
     where
         C: SerializableCircuit,
     {
         let mut json_deserializer = JsonDeserializer::from_reader(serialized_circuit_inputs);
         let circuit_pub_inputs_bytes = C::Input::deserialize(&mut json_deserializer)
-            .map_err(|error| VsockError::Protocol(error.to_string()))?;
+            .map_err(|error| TransportError::Protocol(error.to_string()))?;
         let pub_inputs_bytes = C::pub_inputs(circuit_pub_inputs_bytes.clone());
         // prove() might panic, which won't be caught here, however default behaviour of this server is to ignore panic
         // see https://docs.rs/tokio/latest/tokio/runtime/enum.UnhandledPanic.html#variant.Ignore
@@ -198,7 +249,7 @@ impl Server {
     fn decrypt_using_servers_private_key(
         &self,
         request_payload: &[u8],
-    ) -> Result<Vec<u8>, VsockError> {
+    ) -> Result<Vec<u8>, TransportError> {
         let private_key = PrivKey::from_bytes(self.private_key.as_slice())?;
         let decrypted_payload =
             decrypt_padded(request_payload, &private_key, REQUEST_PAYLOAD_PADDING)?;
@@ -206,12 +257,12 @@ impl Server {
     }
 
     #[cfg(not(feature = "without_attestation"))]
-    fn init_nsm_driver() -> Result<i32, VsockError> {
+    fn init_nsm_driver() -> Result<i32, TransportError> {
         info!("Opening file descriptor to /dev/nsm driver.");
         let nsm_fd = nsm_init();
 
         if nsm_fd < 0 {
-            return Err(VsockError::Protocol(String::from(
+            return Err(TransportError::Protocol(String::from(
                 "Failed to initialize NSM driver.",
             )));
         }
@@ -0,0 +1,153 @@
+//! Runs the TEE server binary outside of an enclave, listening on TCP, and proves through it the
+//! same way `shielder-prover-server` does.
+#![cfg(feature = "without_attestation")]
+
+use std::{
+    net::TcpListener,
+    process::{Child, Command},
+    time::Duration,
+};
+
+use ecies_encryption_lib::{
+    decrypt_padded, encrypt_padded, generate_keypair, utils::from_hex, PubKey,
+};
+use rand::rngs::OsRng;
+use shielder_circuits::{
+    marshall::{unmarshall_params, unmarshall_pk},
+    new_account::{NewAccountCircuit, NewAccountProverKnowledge},
+    verify, Fr, ProverKnowledge, PublicInputProvider,
+};
+use shielder_prover_circuits::{
+    new_account::{NewAccountProveInputsBytes, SerializableNewAccountCircuit},
+    SerializableCircuit,
+};
+use shielder_prover_common::{
+    protocol::{
+        CircuitType, ProverClient, Request, RequestGenerateProofPayload, Response,
+        ResponseGenerateProofPayload, REQUEST_PAYLOAD_PADDING, RESPONSE_PAYLOAD_PADDING,
+    },
+    transport::Endpoint,
+};
+use shielder_setup::{
+    version::contract_version,
+    vk_fingerprint::{expected_vk_fingerprint, ShielderCircuit},
+};
+
+/// Loading the proving keys takes a while, especially in debug builds.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The TEE server process, killed when dropped.
+struct TeeProcess(Child);
+
+impl Drop for TeeProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn spawn_tee() -> (TeeProcess, Endpoint) {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let endpoint: Endpoint = format!("tcp://127.0.0.1:{port}").parse().unwrap();
+    let process = Command::new(env!("CARGO_BIN_EXE_shielder-prover-tee"))
+        .arg(endpoint.to_string())
+        .spawn()
+        .expect("TEE server should start");
+    (TeeProcess(process), endpoint)
+}
+
+async fn connect(endpoint: &Endpoint) -> ProverClient {
+    let started = tokio::time::Instant::now();
+    loop {
+        if let Ok(mut client) = ProverClient::connect(endpoint).await {
+            if matches!(client.request(&Request::Ping).await, Ok(Response::Pong)) {
+                return client;
+            }
+        }
+        assert!(
+            started.elapsed() < STARTUP_TIMEOUT,
+            "TEE server didn't start"
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+#[tokio::test]
+async fn reports_fingerprints_expected_by_the_contract() {
+    let (_tee, endpoint) = spawn_tee();
+    let mut client = connect(&endpoint).await;
+
+    let Response::Info {
+        contract_version: version,
+        vk_fingerprints,
+    } = client.request(&Request::Info).await.unwrap()
+    else {
+        panic!("expected an info response");
+    };
+    assert_eq!(version, contract_version().to_bytes());
+    for circuit in [
+        ShielderCircuit::NewAccount,
+        ShielderCircuit::Deposit,
+        ShielderCircuit::Withdraw,
+    ] {
+        assert_eq!(
+            vk_fingerprints.get(circuit.name()).copied(),
+            expected_vk_fingerprint(contract_version(), circuit),
+            "{circuit:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn proves_new_account() {
+    let (_tee, endpoint) = spawn_tee();
+    let mut client = connect(&endpoint).await;
+
+    let Response::TeePublicKey { public_key, .. } =
+        client.request(&Request::TeePublicKey).await.unwrap()
+    else {
+        panic!("expected a public key response");
+    };
+    let tee_public_key = PubKey::from_bytes(&from_hex(&public_key).unwrap()).unwrap();
+
+    let knowledge = NewAccountProverKnowledge::<Fr>::random_correct_example(&mut OsRng);
+    let inputs = NewAccountProveInputsBytes::from(&knowledge);
+    let (user_private_key, user_public_key) = generate_keypair();
+    let request = serde_json::to_vec(&RequestGenerateProofPayload {
+        circuit_type: CircuitType::NewAccount,
+        user_public_key: user_public_key.to_bytes(),
+        circuit_inputs: serde_json::to_vec(&inputs).unwrap(),
+    })
+    .unwrap();
+    let payload = encrypt_padded(&request, &tee_public_key, REQUEST_PAYLOAD_PADDING).unwrap();
+
+    let Response::EncryptedProof { payload } = client
+        .request(&Request::GenerateProof { payload })
+        .await
+        .unwrap()
+    else {
+        panic!("expected an encrypted proof response");
+    };
+    let response = decrypt_padded(&payload, &user_private_key, RESPONSE_PAYLOAD_PADDING).unwrap();
+    let response: ResponseGenerateProofPayload = serde_json::from_slice(&response).unwrap();
+
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&response.pub_inputs).unwrap(),
+        serde_json::to_value(SerializableNewAccountCircuit::pub_inputs(inputs)).unwrap()
+    );
+    let params = unmarshall_params(include_bytes!("../artifacts/new_account/params.bin")).unwrap();
+    let (_, pk) =
+        unmarshall_pk::<NewAccountCircuit>(include_bytes!("../artifacts/new_account/pk.bin"))
+            .unwrap();
+    verify(
+        &params,
+        pk.get_vk(),
+        &response.proof,
+        &knowledge.serialize_public_input(),
+    )
+    .expect("proof from the TEE should verify");
+}
@@ -0,0 +1,158 @@
+use std::{fmt, net::SocketAddr, path::PathBuf, str::FromStr};
+
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+#[cfg(feature = "vsock")]
+use tokio_vsock::{VsockAddr, VsockListener, VsockStream};
+
+use crate::{Connection, TransportError};
+
+/// Address of a TEE server, written as `vsock://CID:PORT`, `tcp://HOST:PORT` or
+/// `unix:///path/to/socket`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    #[cfg(feature = "vsock")]
+    Vsock {
+        cid: u32,
+        port: u32,
+    },
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl Endpoint {
+    pub async fn connect(&self) -> Result<Connection, TransportError> {
+        Ok(match self {
+            #[cfg(feature = "vsock")]
+            Endpoint::Vsock { cid, port } => {
+                Box::new(VsockStream::connect(VsockAddr::new(*cid, *port)).await?)
+            }
+            Endpoint::Tcp(address) => Box::new(TcpStream::connect(address).await?),
+            Endpoint::Unix(path) => Box::new(UnixStream::connect(path).await?),
+        })
+    }
+}
+
+impl FromStr for Endpoint {
+    type Err = TransportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TransportError::InvalidEndpoint(s.to_string());
+        let (scheme, address) = s.split_once("://").ok_or_else(invalid)?;
+        match scheme {
+            #[cfg(feature = "vsock")]
+            "vsock" => {
+                let (cid, port) = address.split_once(':').ok_or_else(invalid)?;
+                Ok(Endpoint::Vsock {
+                    cid: cid.parse().map_err(|_| invalid())?,
+                    port: port.parse().map_err(|_| invalid())?,
+                })
+            }
+            "tcp" => Ok(Endpoint::Tcp(address.parse().map_err(|_| invalid())?)),
+            "unix" if !address.is_empty() => Ok(Endpoint::Unix(address.into())),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "vsock")]
+            Endpoint::Vsock { cid, port } => write!(f, "vsock://{cid}:{port}"),
+            Endpoint::Tcp(address) => write!(f, "tcp://{address}"),
+            Endpoint::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+/// Accepts connections on any of the supported transports.
+pub enum Listener {
+    #[cfg(feature = "vsock")]
+    Vsock(VsockListener),
+    Tcp(TcpListener),
+    Unix(UnixListener, PathBuf),
+}
+
+impl Listener {
+    pub async fn bind(endpoint: &Endpoint) -> Result<Self, TransportError> {
+        Ok(match endpoint {
+            #[cfg(feature = "vsock")]
+            Endpoint::Vsock { cid, port } => {
+                Listener::Vsock(VsockListener::bind(VsockAddr::new(*cid, *port))?)
+            }
+            Endpoint::Tcp(address) => Listener::Tcp(TcpListener::bind(address).await?),
+            Endpoint::Unix(path) => {
+                // A socket file left behind by a previous run would make `bind` fail.
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                Listener::Unix(UnixListener::bind(path)?, path.clone())
+            }
+        })
+    }
+
+    pub async fn accept(&self) -> Result<Connection, TransportError> {
+        Ok(match self {
+            #[cfg(feature = "vsock")]
+            Listener::Vsock(listener) => Box::new(listener.accept().await?.0),
+            Listener::Tcp(listener) => Box::new(listener.accept().await?.0),
+            Listener::Unix(listener, _) => Box::new(listener.accept().await?.0),
+        })
+    }
+
+    /// The endpoint clients should connect to (with the actual port, if an ephemeral one was
+    /// requested).
+    pub fn local_endpoint(&self) -> Result<Endpoint, TransportError> {
+        Ok(match self {
+            #[cfg(feature = "vsock")]
+            Listener::Vsock(listener) => {
+                let address = listener.local_addr()?;
+                Endpoint::Vsock {
+                    cid: address.cid(),
+                    port: address.port(),
+                }
+            }
+            Listener::Tcp(listener) => Endpoint::Tcp(listener.local_addr()?),
+            Listener::Unix(_, path) => Endpoint::Unix(path.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_endpoints() {
+        assert_eq!(
+            "tcp://127.0.0.1:5000".parse::<Endpoint>().unwrap(),
+            Endpoint::Tcp("127.0.0.1:5000".parse().unwrap())
+        );
+        assert_eq!(
+            "unix:///tmp/tee.sock".parse::<Endpoint>().unwrap(),
+            Endpoint::Unix("/tmp/tee.sock".into())
+        );
+        #[cfg(feature = "vsock")]
+        assert_eq!(
+            "vsock://3:5000".parse::<Endpoint>().unwrap(),
+            Endpoint::Vsock { cid: 3, port: 5000 }
+        );
+
+        for invalid in [
+            "",
+            "tcp://",
+            "udp://127.0.0.1:5000",
+            "unix://",
+            "127.0.0.1:5000",
+        ] {
+            assert!(invalid.parse::<Endpoint>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for endpoint in ["tcp://127.0.0.1:5000", "unix:///tmp/tee.sock"] {
+            assert_eq!(endpoint.parse::<Endpoint>().unwrap().to_string(), endpoint);
+        }
+    }
+}
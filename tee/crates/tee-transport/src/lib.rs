@@ -0,0 +1,189 @@
+//! Framed JSON request/response protocol used between the TEE servers and their hosts.
+//!
+//! Every message is a JSON document prefixed with its length (see [`LengthDelimitedCodec`]).
+//! The protocol is independent of the underlying stream: in production the TEE is reached over
+//! vsock, while TCP and Unix-domain sockets allow running a TEE binary locally, e.g. in
+//! integration tests.
+
+use std::marker::PhantomData;
+
+use futures::{SinkExt as _, StreamExt as _};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+mod endpoint;
+pub use endpoint::{Endpoint, Listener};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TransportError {
+    #[error("IO error: {0}")]
+    IO(#[from] std::io::Error),
+
+    #[error("Serde error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Encryption error: {0}")]
+    Encryption(#[from] ecies_encryption_lib::error::Error),
+
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    #[error("Connection closed")]
+    Closed,
+}
+
+/// A bidirectional byte stream that can carry the protocol.
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send + 'static> Stream for S {}
+
+/// A stream of any of the supported transports.
+pub type Connection = Box<dyn Stream>;
+
+pub struct Client<Req, Resp, S = Connection> {
+    channel: Channel<S>,
+    _marker: PhantomData<(Req, Resp)>,
+}
+
+impl<Req: Serialize, Resp: DeserializeOwned> Client<Req, Resp> {
+    pub async fn connect(endpoint: &Endpoint) -> Result<Self, TransportError> {
+        Ok(endpoint.connect().await?.into())
+    }
+}
+
+impl<Req: Serialize, Resp: DeserializeOwned, S: Stream> Client<Req, Resp, S> {
+    pub async fn request(&mut self, request: &Req) -> Result<Resp, TransportError> {
+        self.channel.send(request).await?;
+        self.channel.recv().await
+    }
+}
+
+impl<Req, Resp, S: Stream> From<S> for Client<Req, Resp, S> {
+    fn from(stream: S) -> Self {
+        Self {
+            channel: stream.into(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+pub struct Server<Req, Resp, S = Connection> {
+    channel: Channel<S>,
+    _marker: PhantomData<(Req, Resp)>,
+}
+
+impl<Req: DeserializeOwned, Resp: Serialize, S: Stream> Server<Req, Resp, S> {
+    pub async fn handle_request<F: FnOnce(Req) -> Result<Resp, TransportError>>(
+        &mut self,
+        handler: F,
+    ) -> Result<(), TransportError> {
+        let req = self.channel.recv().await?;
+        let res = handler(req)?;
+        self.channel.send(&res).await?;
+        Ok(())
+    }
+}
+
+impl<Req, Resp, S: Stream> From<S> for Server<Req, Resp, S> {
+    fn from(stream: S) -> Self {
+        Self {
+            channel: stream.into(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+struct Channel<S> {
+    framed: Framed<S, LengthDelimitedCodec>,
+}
+
+impl<S: Stream> From<S> for Channel<S> {
+    fn from(stream: S) -> Self {
+        Self {
+            framed: Framed::new(stream, LengthDelimitedCodec::new()),
+        }
+    }
+}
+
+impl<S: Stream> Channel<S> {
+    async fn send<T: Serialize>(&mut self, msg: &T) -> Result<(), TransportError> {
+        let msg = serde_json::to_vec(msg)?;
+        self.framed.send(bytes::Bytes::from(msg)).await?;
+        Ok(())
+    }
+
+    async fn recv<T: DeserializeOwned>(&mut self) -> Result<T, TransportError> {
+        let msg = self.framed.next().await.ok_or(TransportError::Closed)??;
+        Ok(serde_json::from_slice(&msg)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Message {
+        Ping(u32),
+        Pong(u32),
+    }
+
+    async fn serve_once(listener: Listener) {
+        let connection = listener.accept().await.unwrap();
+        let mut server: Server<Message, Message> = connection.into();
+        server
+            .handle_request(|request| match request {
+                Message::Ping(n) => Ok(Message::Pong(n)),
+                other => Err(TransportError::Protocol(format!("unexpected {other:?}"))),
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn round_trip(endpoint: &str) {
+        let listener = Listener::bind(&endpoint.parse().unwrap()).await.unwrap();
+        let endpoint = listener.local_endpoint().unwrap();
+        let server = tokio::spawn(serve_once(listener));
+
+        let mut client = Client::<Message, Message>::connect(&endpoint)
+            .await
+            .unwrap();
+        assert_eq!(
+            client.request(&Message::Ping(7)).await.unwrap(),
+            Message::Pong(7)
+        );
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn tcp_round_trip() {
+        round_trip("tcp://127.0.0.1:0").await;
+    }
+
+    #[tokio::test]
+    async fn unix_round_trip() {
+        let path = std::env::temp_dir().join(format!("tee-transport-{}.sock", std::process::id()));
+        round_trip(&format!("unix://{}", path.display())).await;
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn closed_connection_is_reported() {
+        let listener = Listener::bind(&"tcp://127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let endpoint = listener.local_endpoint().unwrap();
+        tokio::spawn(async move { drop(listener.accept().await) });
+
+        let mut client = Client::<Message, Message>::connect(&endpoint)
+            .await
+            .unwrap();
+        assert!(client.request(&Message::Ping(1)).await.is_err());
+    }
+}
@@ -12,7 +12,7 @@
       inputs.nixpkgs.follows = "nixpkgs";
     };
 
-    # below dependency is also used in tee/Cargo.toml, which is far from ideal since one needs to bump it there as well
+    # the TEE (and the circuits it embeds from ../crates) is built from this revision of the monorepo
     blanksquare-monorepo = {
       url = "git+https://github.com/Cardinal-Cryptography/blanksquare-monorepo?ref=main&rev=8d17e23ee807e2f1b40b31ffba27c1276afdb81f";
       flake = false;
@@ -47,7 +47,14 @@
           cargoExtraArgs = "-p shielder-prover-tee";
           version = "0.1.0";
 
-          src = "${blanksquare-monorepo}/tee";
+          # tee/ is a separate workspace, but it depends on crates of the main one
+          src = blanksquare-monorepo;
+          cargoToml = "${blanksquare-monorepo}/tee/Cargo.toml";
+          cargoLock = "${blanksquare-monorepo}/tee/Cargo.lock";
+          postUnpack = ''
+            cd $sourceRoot/tee
+            sourceRoot="."
+          '';
           strictDeps = true;
 
           CARGO_BUILD_TARGET = "x86_64-unknown-linux-musl";
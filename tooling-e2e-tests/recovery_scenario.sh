@@ -30,7 +30,16 @@ make_history() {
   alice deposit $(mtzero 8)
   alice withdraw $(mtzero 9) "${WITHDRAWAL_PUBLIC_KEY}"
 
-  # 2. ERC20 token
+  # 2. Second native account (derived from the same key, with index 1)
+  alice create-account "native"
+  alice new-account $(mtzero 50)
+  alice deposit $(mtzero 5)
+  alice select-account "native" 0
+
+  # 3. Withdrawal from both native accounts with a single proof
+  alice withdraw-batch "native" "${WITHDRAWAL_PUBLIC_KEY}" --account 0 --amount $(mtzero 3) --account 1 --amount $(mtzero 4)
+
+  # 4. ERC20 token
   alice new-account-erc20 $(mtzero 500) "${ERC20_CONTRACT_ADDRESS_1}" # so that we have enough balance for withdrawals
   alice deposit-erc20 $(mtzero 6) "${ERC20_CONTRACT_ADDRESS_1}"
   alice withdraw-erc20 $(mtzero 7) "${WITHDRAWAL_PUBLIC_KEY}" "${ERC20_CONTRACT_ADDRESS_1}" $(mtzero 1)
@@ -116,6 +116,28 @@ EOF
   log_progress "✅ Tokens deployed"
 }
 
+# Allow the deployed ERC20 tokens to be shielded. Requires the CLI (for computing the tree root).
+publish_token_list() {
+  local state_file="${TMPDIR:-/tmp}/.shielder-state-deployer"
+  local cli="target/release/shielder-cli --no-password --state-file ${state_file}"
+
+  rm -f "${state_file}"
+  RUST_LOG=warning ${cli} initialize "${DEPLOYER_PRIVATE_KEY}"
+  TOKEN_LIST_ROOT=$(RUST_LOG=warning ${cli} token-list-root $(echo ${TOKEN_CONTRACT_ADDRESSES} | sed "s/,/ /g"))
+  rm -f "${state_file}"
+
+  cast send \
+    --rpc-url "${NODE_RPC_URL}" \
+    --private-key "${DEPLOYER_PRIVATE_KEY}" \
+    "${SHIELDER_CONTRACT_ADDRESS}" \
+    "setTokenList(address[],uint256)" \
+    "[${TOKEN_CONTRACT_ADDRESSES}]" \
+    "${TOKEN_LIST_ROOT}" \
+    &>> output.log
+
+  log_progress "✅ Token list published"
+}
+
 mint_erc20_tokens() {
   AMOUNT=$(mtzero 100000)
 
@@ -218,6 +240,7 @@ setup() {
 
   deploy_shielder_contracts
   deploy_erc20_tokens
+  publish_token_list
   mint_erc20_tokens
 
   start_relayer
@@ -93,7 +93,8 @@ export class RNCryptoClient implements CryptoClient {
           scalarToArrayBuffer(advice.encryptionSalt),
           scalarToArrayBuffer(advice.macSalt),
           scalarToArrayBuffer(advice.anonymityRevokerPublicKeyX),
-          scalarToArrayBuffer(advice.anonymityRevokerPublicKeyY)
+          scalarToArrayBuffer(advice.anonymityRevokerPublicKeyY),
+          new Uint8Array(advice.tokenListPath).buffer
         );
         const rawPubInputs = newAccountPubInputs(
           scalarToArrayBuffer(advice.id),
@@ -104,7 +105,8 @@ export class RNCryptoClient implements CryptoClient {
           scalarToArrayBuffer(advice.encryptionSalt),
           scalarToArrayBuffer(advice.macSalt),
           scalarToArrayBuffer(advice.anonymityRevokerPublicKeyX),
-          scalarToArrayBuffer(advice.anonymityRevokerPublicKeyY)
+          scalarToArrayBuffer(advice.anonymityRevokerPublicKeyY),
+          new Uint8Array(advice.tokenListPath).buffer
         );
         return {
           proof: new Uint8Array(rawProof),
@@ -134,6 +136,7 @@ export class RNCryptoClient implements CryptoClient {
             ),
             macSalt: arrayBufferToScalar(rawPubInputs.macSalt),
             macCommitment: arrayBufferToScalar(rawPubInputs.macCommitment),
+            tokenListRoot: arrayBufferToScalar(rawPubInputs.tokenListRoot),
           },
         };
       });
@@ -155,6 +158,7 @@ export class RNCryptoClient implements CryptoClient {
             scalarToArrayBuffer(pubInputs.symKeyEncryption2Y),
             scalarToArrayBuffer(pubInputs.macSalt),
             scalarToArrayBuffer(pubInputs.macCommitment),
+            scalarToArrayBuffer(pubInputs.tokenListRoot),
             new Uint8Array(proof).buffer
           );
         } catch (e) {
@@ -238,7 +242,9 @@ export class RNCryptoClient implements CryptoClient {
           scalarToArrayBuffer(advice.value),
           scalarToArrayBuffer(advice.nullifierNew),
           scalarToArrayBuffer(advice.commitment),
-          scalarToArrayBuffer(advice.macSalt)
+          scalarToArrayBuffer(advice.macSalt),
+          undefined,
+          undefined
         );
 
         const rawPubInputs = withdrawPubInputs(
@@ -250,7 +256,9 @@ export class RNCryptoClient implements CryptoClient {
           scalarToArrayBuffer(advice.value),
           scalarToArrayBuffer(advice.nullifierNew),
           scalarToArrayBuffer(advice.commitment),
-          scalarToArrayBuffer(advice.macSalt)
+          scalarToArrayBuffer(advice.macSalt),
+          undefined,
+          undefined
         );
         return {
           proof: new Uint8Array(rawProof),
@@ -24,7 +24,8 @@ export class NewAccountTeeCircuit implements NewAccountCircuit {
       encryption_salt: values.encryptionSalt.bytes,
       mac_salt: values.macSalt.bytes,
       anonymity_revoker_public_key_x: values.anonymityRevokerPublicKeyX.bytes,
-      anonymity_revoker_public_key_y: values.anonymityRevokerPublicKeyY.bytes
+      anonymity_revoker_public_key_y: values.anonymityRevokerPublicKeyY.bytes,
+      token_list_path: values.tokenListPath
     };
 
     const witnessBytes = objectToBytes(witness);
@@ -46,6 +47,7 @@ export class NewAccountTeeCircuit implements NewAccountCircuit {
       sym_key_encryption_2_y: Uint8Array;
       mac_salt: Uint8Array;
       mac_commitment: Uint8Array;
+      token_list_root: Uint8Array;
     };
 
     const pubInputs: NewAccountPubInputs<Scalar> = {
@@ -65,7 +67,8 @@ export class NewAccountTeeCircuit implements NewAccountCircuit {
       symKeyEncryption2X: new Scalar(pubInputsNonScalar.sym_key_encryption_2_x),
       symKeyEncryption2Y: new Scalar(pubInputsNonScalar.sym_key_encryption_2_y),
       macSalt: new Scalar(pubInputsNonScalar.mac_salt),
-      macCommitment: new Scalar(pubInputsNonScalar.mac_commitment)
+      macCommitment: new Scalar(pubInputsNonScalar.mac_commitment),
+      tokenListRoot: new Scalar(pubInputsNonScalar.token_list_root)
     };
 
     return {
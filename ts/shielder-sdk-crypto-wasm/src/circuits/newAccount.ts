@@ -47,7 +47,8 @@ export class NewAccountCircuit
         values.encryptionSalt.bytes,
         values.macSalt.bytes,
         values.anonymityRevokerPublicKeyX.bytes,
-        values.anonymityRevokerPublicKeyY.bytes
+        values.anonymityRevokerPublicKeyY.bytes,
+        values.tokenListPath
       ),
       pubInputs: this.pubInputs(values)
     });
@@ -71,7 +72,8 @@ export class NewAccountCircuit
       values.encryptionSalt.bytes,
       values.macSalt.bytes,
       values.anonymityRevokerPublicKeyX.bytes,
-      values.anonymityRevokerPublicKeyY.bytes
+      values.anonymityRevokerPublicKeyY.bytes,
+      values.tokenListPath
     );
 
     return {
@@ -91,7 +93,8 @@ export class NewAccountCircuit
       symKeyEncryption2X: new Scalar(pubInputsBytes.sym_key_encryption_2_x),
       symKeyEncryption2Y: new Scalar(pubInputsBytes.sym_key_encryption_2_y),
       macSalt: new Scalar(pubInputsBytes.mac_salt),
-      macCommitment: new Scalar(pubInputsBytes.mac_commitment)
+      macCommitment: new Scalar(pubInputsBytes.mac_commitment),
+      tokenListRoot: new Scalar(pubInputsBytes.token_list_root)
     };
   }
 
@@ -119,6 +122,7 @@ export class NewAccountCircuit
           pubInputs.symKeyEncryption2Y.bytes,
           pubInputs.macSalt.bytes,
           pubInputs.macCommitment.bytes,
+          pubInputs.tokenListRoot.bytes,
           proof
         )
       );
@@ -46,7 +46,9 @@ export class WithdrawCircuit
         values.value.bytes,
         values.nullifierNew.bytes,
         values.commitment.bytes,
-        values.macSalt.bytes
+        values.macSalt.bytes,
+        undefined,
+        undefined
       ),
       pubInputs: this.pubInputs(values)
     });
@@ -68,7 +70,9 @@ export class WithdrawCircuit
       values.value.bytes,
       values.nullifierNew.bytes,
       values.commitment.bytes,
-      values.macSalt.bytes
+      values.macSalt.bytes,
+      undefined,
+      undefined
     );
 
     return {
@@ -102,7 +106,8 @@ export class WithdrawCircuit
           pubInputs.tokenAddress.bytes,
           pubInputs.macSalt.bytes,
           pubInputs.macCommitment.bytes,
-          proof
+          proof,
+          undefined
         )
       );
     } catch (e) {
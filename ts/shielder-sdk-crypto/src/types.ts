@@ -15,6 +15,7 @@ export type NewAccountPubInputs<T> = {
   symKeyEncryption2Y: T;
   macSalt: T;
   macCommitment: T;
+  tokenListRoot: T;
 };
 
 export type NewAccountAdvice<T> = {
@@ -27,6 +28,8 @@ export type NewAccountAdvice<T> = {
   anonymityRevokerPublicKeyX: T;
   anonymityRevokerPublicKeyY: T;
   macSalt: T;
+  // Merkle path of the token in the tree of allowed tokens
+  tokenListPath: Uint8Array;
 };
 
 // follows the order in shielder-circuits::circuits::deposit
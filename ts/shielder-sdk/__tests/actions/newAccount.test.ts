@@ -17,6 +17,7 @@ import { nativeToken } from "../../src/utils";
 import { OutdatedSdkError } from "../../src/errors";
 
 const ANONYMITY_REVOKER_PUBKEY = [123n, 456n];
+const TOKEN_TREE_HEIGHT = 5;
 
 describe("NewAccountAction", () => {
   let cryptoClient: MockedCryptoClient;
@@ -31,13 +32,27 @@ describe("NewAccountAction", () => {
   const mockProtocolFee = 0n;
   const mockMemo = new Uint8Array();
 
-  beforeEach(() => {
+  // Root of the tree with no tokens listed (only the native token allowed).
+  const emptyTokenListRoot = async (): Promise<bigint> => {
+    const arity = await cryptoClient.noteTreeConfig.arity();
+    let node = Scalar.fromBigint(0n);
+    for (let level = 0; level <= TOKEN_TREE_HEIGHT; level++) {
+      node = await cryptoClient.hasher.poseidonHash(
+        new Array(arity).fill(node)
+      );
+    }
+    return scalarToBigint(node);
+  };
+
+  beforeEach(async () => {
     cryptoClient = new MockedCryptoClient();
     contract = {
       getAddress: vitest.fn().mockReturnValue(mockAddress),
       anonymityRevokerPubkey: vitest
         .fn()
         .mockResolvedValue(ANONYMITY_REVOKER_PUBKEY),
+      tokenList: vitest.fn().mockResolvedValue([]),
+      tokenListRoot: vitest.fn().mockResolvedValue(await emptyTokenListRoot()),
       newAccountNativeCalldata: vitest
         .fn<
           (
@@ -93,6 +108,31 @@ describe("NewAccountAction", () => {
     });
   });
 
+  describe("tokenListPath", () => {
+    it("should compute the path of the native token", async () => {
+      const arity = await cryptoClient.noteTreeConfig.arity();
+      const path = await action.tokenListPath(
+        "0x0000000000000000000000000000000000000000"
+      );
+
+      expect(path.length).toBe(TOKEN_TREE_HEIGHT * arity * 32);
+    });
+
+    it("should throw for a token that is not listed", async () => {
+      await expect(action.tokenListPath(mockAddress)).rejects.toThrow(
+        `Token ${mockAddress} is not allowed by the contract`
+      );
+    });
+
+    it("should throw at token list root mismatch", async () => {
+      contract.tokenListRoot = vitest.fn().mockResolvedValue(1n);
+
+      await expect(
+        action.tokenListPath("0x0000000000000000000000000000000000000000")
+      ).rejects.toThrow("Token list root does not match the contract");
+    });
+  });
+
   describe("generateCalldata", () => {
     it("should generate valid calldata", async () => {
       const amount = 100n;
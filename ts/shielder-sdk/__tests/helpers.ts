@@ -156,7 +156,8 @@ class MockedNewAccountCircuit implements NewAccountCircuit {
         symKeyEncryption2X: Scalar.fromBigint(0n),
         symKeyEncryption2Y: Scalar.fromBigint(0n),
         macSalt: Scalar.fromBigint(0n),
-        macCommitment: Scalar.fromBigint(0n)
+        macCommitment: Scalar.fromBigint(0n),
+        tokenListRoot: Scalar.fromBigint(0n)
       }
     });
   }
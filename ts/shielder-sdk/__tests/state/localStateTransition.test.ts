@@ -19,7 +19,7 @@ const createNoteEvent = (
   amount,
   newNote: 123n, // Simplified for testing
   newNoteIndex,
-  contractVersion: "0x000101",
+  contractVersion: "0x000601",
   txHash: "0x123",
   block: 1n,
   tokenAddress: "0x0000000000000000000000000000000000000000",
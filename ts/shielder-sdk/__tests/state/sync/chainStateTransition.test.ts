@@ -66,7 +66,7 @@ describe("ChainStateTransition", () => {
         amount: 50n,
         newNote: 789n,
         newNoteIndex: 2n,
-        contractVersion: "0x000101", // Use the supported version from constants
+        contractVersion: "0x000601", // Use the supported version from constants
         txHash: "0x123",
         block: 1n,
         tokenAddress: nativeTokenAddress,
@@ -229,7 +229,7 @@ describe("ChainStateTransition", () => {
         amount: 50n,
         newNote: 789n,
         newNoteIndex: 2n,
-        contractVersion: "0x000101", // Use the supported version from constants
+        contractVersion: "0x000601", // Use the supported version from constants
         txHash: "0x123",
         block: 1n,
         tokenAddress: "0x123",
@@ -11,6 +11,7 @@ import { NoteAction } from "@/actions/utils";
 import { Token } from "@/types";
 import { getAddressByToken } from "@/utils";
 import { OutdatedSdkError } from "@/errors";
+import { nativeTokenAddress, tokenTreeHeight } from "@/constants";
 import { AccountState } from "@/state/types";
 import { SendShielderTransaction } from "@/client/types";
 import { Address, encodePacked, hexToBigInt, keccak256 } from "viem";
@@ -83,6 +84,8 @@ export class NewAccountAction extends NoteAction {
     const [anonymityRevokerPublicKeyX, anonymityRevokerPublicKeyY] =
       await this.contract.anonymityRevokerPubkey();
 
+    const tokenListPath = await this.tokenListPath(tokenAddress);
+
     const commitment = this.calculateCommitment(callerAddress, protocolFee);
     return {
       id: state.id,
@@ -93,10 +96,72 @@ export class NewAccountAction extends NoteAction {
       encryptionSalt: await this.randomSalt(),
       anonymityRevokerPublicKeyX: Scalar.fromBigint(anonymityRevokerPublicKeyX),
       anonymityRevokerPublicKeyY: Scalar.fromBigint(anonymityRevokerPublicKeyY),
-      macSalt: await this.randomSalt()
+      macSalt: await this.randomSalt(),
+      tokenListPath
     };
   }
 
+  /**
+   * Compute the Merkle path of `tokenAddress` in the tree of tokens allowed by the contract.
+   * Leaves are the listed token addresses followed by zeros, so the native token (zero address)
+   * is allowed as long as the list is not full.
+   * @param tokenAddress address of the token
+   * @returns flattened path, level by level (`tokenTreeHeight * arity` scalars)
+   */
+  async tokenListPath(tokenAddress: Address): Promise<Uint8Array> {
+    const arity = await this.cryptoClient.noteTreeConfig.arity();
+    const tokens = await this.contract.tokenList();
+
+    let index = tokens.findIndex(
+      (token) => token.toLowerCase() === tokenAddress.toLowerCase()
+    );
+    if (
+      index === -1 &&
+      tokenAddress === nativeTokenAddress &&
+      tokens.length < arity ** tokenTreeHeight
+    ) {
+      index = tokens.length;
+    }
+    if (index === -1) {
+      throw new Error(`Token ${tokenAddress} is not allowed by the contract`);
+    }
+
+    const hashChildren = async (
+      nodes: Scalar[],
+      first: number,
+      empty: Scalar
+    ) =>
+      await this.cryptoClient.hasher.poseidonHash(
+        Array.from({ length: arity }, (_, i) => nodes[first + i] ?? empty)
+      );
+
+    // Only the non-empty prefix of each level is kept, the rest is `empty`.
+    let nodes = tokens.map((token) => Scalar.fromAddress(token));
+    let empty = Scalar.fromBigint(0n);
+    const path: Scalar[] = [];
+    for (let level = 0; level < tokenTreeHeight; level++) {
+      const first = index - (index % arity);
+      for (let i = 0; i < arity; i++) {
+        path.push(nodes[first + i] ?? empty);
+      }
+
+      const parents: Scalar[] = [];
+      for (let i = 0; i < nodes.length; i += arity) {
+        parents.push(await hashChildren(nodes, i, empty));
+      }
+      empty = await hashChildren([], 0, empty);
+      nodes = parents;
+      index = Math.floor(index / arity);
+    }
+
+    const root = await hashChildren(nodes, 0, empty);
+    if (scalarToBigint(root) !== (await this.contract.tokenListRoot())) {
+      throw new Error("Token list root does not match the contract");
+    }
+
+    return new Uint8Array(path.flatMap((node) => [...node.bytes]));
+  }
+
   /**
    * Generate calldata for creation of a new account with an initial deposit.
    * @param state current account state
@@ -76,6 +76,8 @@ export type IContract = {
   getAddress: () => Address;
   getMerklePath: (idx: bigint) => Promise<readonly bigint[]>;
   anonymityRevokerPubkey: () => Promise<readonly [bigint, bigint]>;
+  tokenList: () => Promise<readonly Address[]>;
+  tokenListRoot: () => Promise<bigint>;
   newAccountNativeCalldata: (
     expectedContractVersion: `0x${string}`,
     from: Address,
@@ -195,6 +197,14 @@ export class Contract implements IContract {
     return key;
   };
 
+  tokenList = async (): Promise<readonly Address[]> => {
+    return await this.contract.read.tokenList();
+  };
+
+  tokenListRoot = async (): Promise<bigint> => {
+    return await this.contract.read.tokenListRoot();
+  };
+
   newAccountNativeCalldata = async (
     expectedContractVersion: `0x${string}`,
     from: Address,
@@ -424,6 +434,7 @@ export class Contract implements IContract {
       relayerFee,
       macSalt,
       macCommitment,
+      0n, // associationSetRoot: no association set is claimed
       bytesToHex(memo)
     ] as const;
     const gas = safe_gas(
@@ -485,6 +496,7 @@ export class Contract implements IContract {
       relayerFee,
       macSalt,
       macCommitment,
+      0n, // associationSetRoot: no association set is claimed
       bytesToHex(memo)
     ] as const;
     const gas = safe_gas(
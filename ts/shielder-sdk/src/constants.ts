@@ -1,4 +1,4 @@
-export const contractVersion = "0x000101";
+export const contractVersion = "0x000601";
 export const relayPath = "/relay";
 export const feePath = "/quote_fees";
 export const feeAddressPath = "/fee_address";
@@ -8,6 +8,12 @@ export const nativeTokenAddress = "0x0000000000000000000000000000000000000000";
 
 export const firstAccountIndex = 0;
 
+/**
+ * Height of the Merkle tree of tokens allowed by the contract.
+ * Must match `TOKEN_TREE_HEIGHT` of the circuits.
+ */
+export const tokenTreeHeight = 5;
+
 /**
  * Length of the referral ID padding in bytes.
  * This is used to ensure that the referral ID is padded to a fixed length for encryption.